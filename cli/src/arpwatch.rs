@@ -0,0 +1,271 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # arpwatch Database
+//!
+//! Reads and writes the station database of arpwatch (`arp.dat`), so a site can
+//! move from arpwatch to `zond discover` or `zond agent --every` and keep the
+//! pairings it collected over the years.
+//!
+//! Every line pairs an Ethernet address with an IPv4 address, tab separated:
+//!
+//! ```text
+//! 0:1b:63:a4:f:2	192.168.1.20	1767225600	printer.lan	eth0
+//! ```
+//!
+//! followed by the time the pair was last seen and, optionally, a hostname and
+//! an interface. Octets are written without leading zeros, like arpwatch does.
+//!
+//! Each discovered pair is checked against the database with the rules of
+//! arpwatch, and the same alerts are logged: `new station`, `new activity`
+//! (back after six months), `changed ethernet address`, `flip flop` (back to
+//! the previous address within a day) and `reused old ethernet address`.
+//! IPv6 addresses are left out, since arpwatch would reject the file.
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt, fs,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, anyhow};
+use pnet::util::MacAddr;
+use zond_common::{info, models::host::Host, warn};
+
+/// A pair unseen for longer than this is reported as `new activity`.
+const NEW_ACTIVITY_SECS: u64 = 180 * 24 * 60 * 60;
+/// Returning to the previous address within this is a `flip flop`.
+const FLIP_FLOP_SECS: u64 = 24 * 60 * 60;
+
+/// The stations of an arpwatch database, most recent address of each IP first.
+pub struct Database {
+    path: PathBuf,
+    stations: BTreeMap<Ipv4Addr, Vec<Entry>>,
+}
+
+struct Entry {
+    mac: MacAddr,
+    /// Last seen, in seconds since the epoch.
+    seen: u64,
+    hostname: String,
+    interface: String,
+}
+
+/// A change arpwatch would have reported.
+struct Alert {
+    title: &'static str,
+    ip: Ipv4Addr,
+    mac: MacAddr,
+    /// The address `mac` replaced, if any.
+    previous: Option<MacAddr>,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.title, self.ip, arpwatch_mac(&self.mac))?;
+        if let Some(previous) = &self.previous {
+            write!(f, " ({})", arpwatch_mac(previous))?;
+        }
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Loads the database at `path`. A missing file is an empty database,
+    /// created on the first [`save`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or holds a line that is not
+    /// an arpwatch entry, rather than dropping it on the next save.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut db: Database = Database {
+            path: path.to_path_buf(),
+            stations: BTreeMap::new(),
+        };
+        let content: String = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(db),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (ip, entry) = parse_line(line).with_context(|| {
+                format!(
+                    "line {} of {} is not an arpwatch entry",
+                    idx + 1,
+                    path.display()
+                )
+            })?;
+            db.stations.entry(ip).or_default().push(entry);
+        }
+        for entries in db.stations.values_mut() {
+            entries.sort_by_key(|entry| Reverse(entry.seen));
+        }
+        Ok(db)
+    }
+
+    /// Records the IPv4 addresses and MAC addresses of `hosts` and logs every
+    /// alert arpwatch would have raised for them.
+    pub fn record(&mut self, hosts: &[Host]) {
+        let now: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        for host in hosts {
+            let Some(mac) = host.mac else {
+                continue;
+            };
+            let hostname: Option<&str> = host
+                .hostname
+                .as_deref()
+                .map(|name| name.trim_end_matches('.'))
+                .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace));
+            for ip in &host.ips {
+                let IpAddr::V4(ip) = ip else {
+                    continue;
+                };
+                match self.observe(*ip, mac, hostname, now) {
+                    Some(alert) if alert.previous.is_some() => warn!("{alert}"),
+                    Some(alert) => info!("{alert}"),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    /// Applies the rules of arpwatch to one sighting of `ip` at `mac`.
+    fn observe(
+        &mut self,
+        ip: Ipv4Addr,
+        mac: MacAddr,
+        hostname: Option<&str>,
+        now: u64,
+    ) -> Option<Alert> {
+        let entries: &mut Vec<Entry> = self.stations.entry(ip).or_default();
+        let raise = |title: &'static str, previous: Option<MacAddr>| Alert {
+            title,
+            ip,
+            mac,
+            previous,
+        };
+
+        let alert: Option<Alert> = match entries.iter().position(|entry| entry.mac == mac) {
+            Some(0) => {
+                let idle: u64 = now.saturating_sub(entries[0].seen);
+                (idle > NEW_ACTIVITY_SECS).then(|| raise("new activity", None))
+            }
+            Some(idx) => {
+                let previous: MacAddr = entries[0].mac;
+                let idle: u64 = now.saturating_sub(entries[idx].seen);
+                let title: &'static str = if idx == 1 && idle <= FLIP_FLOP_SECS {
+                    "flip flop"
+                } else {
+                    "reused old ethernet address"
+                };
+                let entry: Entry = entries.remove(idx);
+                entries.insert(0, entry);
+                Some(raise(title, Some(previous)))
+            }
+            None if entries.is_empty() => {
+                entries.push(Entry::new(mac));
+                Some(raise("new station", None))
+            }
+            None => {
+                let previous: MacAddr = entries[0].mac;
+                entries.insert(0, Entry::new(mac));
+                Some(raise("changed ethernet address", Some(previous)))
+            }
+        };
+
+        let current: &mut Entry = &mut entries[0];
+        current.seen = now;
+        if let Some(hostname) = hostname {
+            current.hostname = hostname.to_string();
+        }
+        alert
+    }
+
+    /// Writes the database back in arpwatch's format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let mut content: String = String::new();
+        for (ip, entries) in &self.stations {
+            for entry in entries {
+                content.push_str(&format!(
+                    "{}\t{ip}\t{}",
+                    arpwatch_mac(&entry.mac),
+                    entry.seen
+                ));
+                if !entry.hostname.is_empty() || !entry.interface.is_empty() {
+                    content.push_str(&format!("\t{}", entry.hostname));
+                }
+                if !entry.interface.is_empty() {
+                    content.push_str(&format!("\t{}", entry.interface));
+                }
+                content.push('\n');
+            }
+        }
+
+        let tmp: PathBuf = self.path.with_extension("tmp");
+        fs::write(&tmp, content).with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+impl Entry {
+    fn new(mac: MacAddr) -> Self {
+        Self {
+            mac,
+            seen: 0,
+            hostname: String::new(),
+            interface: String::new(),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> anyhow::Result<(Ipv4Addr, Entry)> {
+    let mut fields = line.split('\t');
+    let mut next = |name: &str| fields.next().ok_or_else(|| anyhow!("{name} is missing"));
+
+    let mac: MacAddr = next("ethernet address")?
+        .parse()
+        .map_err(|_| anyhow!("invalid ethernet address"))?;
+    let ip: Ipv4Addr = next("ip address")?.parse().context("invalid ip address")?;
+    let seen: u64 = next("timestamp")?.parse().context("invalid timestamp")?;
+    let hostname: String = next("hostname").unwrap_or_default().to_string();
+    let interface: String = next("interface").unwrap_or_default().to_string();
+
+    Ok((
+        ip,
+        Entry {
+            mac,
+            seen,
+            hostname,
+            interface,
+        },
+    ))
+}
+
+/// Formats `mac` like arpwatch, without leading zeros (`0:1b:63:a4:f:2`).
+fn arpwatch_mac(mac: &MacAddr) -> String {
+    mac.octets()
+        .iter()
+        .map(|octet| format!("{octet:x}"))
+        .collect::<Vec<String>>()
+        .join(":")
+}
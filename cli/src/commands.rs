@@ -33,13 +33,91 @@
 //!   exclusive, the type system ensures the application cannot be in two states (e.g., "Scan"
 //!   and "Listen") simultaneously.
 
+pub mod agent;
+pub mod completions;
 pub mod discover;
+pub mod doctor;
+pub mod export;
+pub mod fixture;
+pub mod host;
+pub mod import;
 pub mod info;
 pub mod listen;
+pub mod mdns;
+pub mod protocols;
+pub mod report;
+#[cfg(debug_assertions)]
+pub mod respond;
 pub mod scan;
+pub mod schedule;
+pub mod schema;
+pub mod serve;
+pub mod stats;
+pub mod timeline;
+pub mod update_oui;
+pub mod view;
 
-use clap::{ArgAction, Parser, Subcommand};
-use zond_common::{config::ZondConfig, models::port::PortSet};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    ops::RangeInclusive,
+    path::PathBuf,
+    time::Duration,
+};
+
+use clap::{
+    ArgAction, Parser, Subcommand,
+    builder::{BoolishValueParser, PossibleValuesParser},
+};
+use clap_complete::Shell;
+use zond_common::{
+    config::{Technique, Timing, ZondConfig},
+    models::{port::PortSet, timeline::DeviceQuery, topology},
+    parse::cron::CronExpr,
+};
+use zond_core::scanner::{enrich, ipproto};
+
+use crate::{history::HostFilter, lock::LockPolicy, query::Query};
+
+// Printed below the help of the subcommands, e.g. `zond discover --help`.
+
+const DISCOVER_EXAMPLES: &str = "\
+Examples:
+  zond discover lan
+  zond d 192.168.1.0/24 10.0.0.1-50
+  zond d 10.0.0.0/24 --where 'rtt<10ms && vendor~apple'
+  zond d @last --filter role=printer";
+
+const SCAN_EXAMPLES: &str = "\
+Examples:
+  zond scan 192.168.1.10 -p 22,80,443
+  zond s 10.0.0.0/24 -p 1-1024,u:53
+  zond s @last --filter hostname~printer -p 9100";
+
+const HISTORY_EXAMPLES: &str = "\
+Examples:
+  zond history 192.168.1.20
+  zond history 2c:cf:67:f2:51:e3 --report before-migration.json";
+
+const HOST_EXAMPLES: &str = "\
+Examples:
+  zond host 192.168.1.1
+  zond host nas.local -p 22,80,443,5000-5001";
+
+const REPORT_EXAMPLES: &str = "\
+Examples:
+  zond report @last > scan.html
+  zond report office-scan.json --baseline last-week.json > changes.html
+  zond report @schedule:1 --baseline @schedule:1~1 > changes.html";
+
+const VIEW_EXAMPLES: &str = "\
+Examples:
+  zond view @last --by-vendor
+  zond view office-scan.json --where 'ports contains 22' --redact";
+
+const SCHEDULE_ADD_EXAMPLES: &str = "\
+Examples:
+  zond schedule add @daily discover lan
+  zond schedule add \"0 3 * * 1\" --keep 4 scan 10.0.0.0/24 -p 22,443";
 
 #[derive(Parser)]
 #[command(name = "zond")]
@@ -58,6 +136,14 @@ pub struct CommandLine {
     #[arg(short = 'n', long = "no-dns", global = true)]
     pub no_dns: bool,
 
+    /// Also resolve hostnames of public IPs (PTR queries reach third-party DNS)
+    #[arg(long = "resolve-public", global = true)]
+    pub resolve_public: bool,
+
+    /// Wait up to MS milliseconds for hostname lookups once scanning finished [default: 2000]
+    #[arg(long = "resolve-budget", value_name = "MS", global = true)]
+    pub resolve_budget: Option<u64>,
+
     /// Ports to target (e.g. 80, 443, 1-1024, u:53)
     #[arg(
         short = 'p',
@@ -75,6 +161,168 @@ pub struct CommandLine {
     #[arg(long = "redact", global = true)]
     pub redact: bool,
 
+    /// Replace hostnames, MACs and IPs with consistent fakes for presentations
+    #[arg(long = "demo", global = true)]
+    pub demo: bool,
+
+    /// Group hosts under their vendor with counts (-v keeps the details)
+    #[arg(long = "by-vendor", global = true)]
+    pub by_vendor: bool,
+
+    /// Show hostnames as received, without decoding punycode (xn--)
+    #[arg(long = "raw-hostnames", global = true)]
+    pub raw_hostnames: bool,
+
+    /// Hide the DNS search domain in hostnames (printer for printer.lan.example.com), by default the system's
+    #[arg(long = "short-names", value_name = "DOMAINS", global = true, num_args = 0..=1, require_equals = true, value_delimiter = ',')]
+    pub short_names: Option<Vec<String>>,
+
+    /// Source port range for privileged TCP probes (e.g. 40000-60000)
+    #[arg(long = "source-ports", global = true, value_parser = parse_port_range)]
+    pub source_ports: Option<RangeInclusive<u16>>,
+
+    /// Only show hosts matching an expression (e.g. 'rtt<10ms && vendor~apple')
+    #[arg(long = "where", value_name = "EXPR", global = true)]
+    pub where_clause: Option<Query>,
+
+    /// Discovery method: auto, arp (on-link only), icmp (echo) or syn
+    #[arg(
+        long = "technique",
+        value_name = "METHOD",
+        global = true,
+        default_value = "auto"
+    )]
+    pub technique: Technique,
+
+    /// Ports SYN discovery probes every target on (e.g. 443,80,22)
+    #[arg(
+        long = "probe-ports",
+        value_name = "PORTS",
+        global = true,
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub probe_ports: Vec<u16>,
+
+    /// Also probe with SCTP INITs (root only)
+    #[arg(long = "sctp", global = true)]
+    pub sctp: bool,
+
+    /// Start large scans without asking for confirmation
+    #[arg(short = 'y', long = "yes", global = true)]
+    pub yes: bool,
+
+    /// Refuse scans of more than N addresses
+    #[arg(
+        long = "max-targets",
+        value_name = "N",
+        global = true,
+        default_value_t = 65_536,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub max_targets: u64,
+
+    /// Lift the --max-targets limit
+    #[arg(long = "i-know-what-im-doing", global = true)]
+    pub i_know_what_im_doing: bool,
+
+    /// Run on at most N worker threads, e.g. 1 or 2 on a router [default: one per core]
+    #[arg(
+        long = "threads",
+        value_name = "N",
+        global = true,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub threads: Option<u16>,
+
+    /// Keep at most MIB MiB of unanswered probes in memory and the rest on disk [default: 64]
+    #[arg(
+        long = "memory-limit",
+        value_name = "MIB",
+        global = true,
+        value_parser = clap::value_parser!(u64).range(1..=1 << 20)
+    )]
+    pub memory_limit: Option<u64>,
+
+    /// Measure loss and latency on a sample of the targets first and pick timeouts and retries
+    #[arg(long = "calibrate", global = true)]
+    pub calibrate: bool,
+
+    /// Try silent ports of TCP connect probes again up to N times
+    #[arg(
+        long = "retries",
+        value_name = "N",
+        global = true,
+        default_value_t = 0,
+        conflicts_with = "calibrate",
+        value_parser = clap::value_parser!(u8).range(..=10)
+    )]
+    pub retries: u8,
+
+    /// Put interfaces into promiscuous mode while listening passively
+    #[arg(
+        long = "promiscuous",
+        value_name = "on|off",
+        global = true,
+        default_value = "on",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub promiscuous: bool,
+
+    /// Send ARP probes from a random locally administered MAC
+    #[arg(long = "random-mac", global = true)]
+    pub random_mac: bool,
+
+    /// Save the last frames received to a pcap file when they stop parsing
+    #[arg(long = "capture-errors", global = true)]
+    pub capture_errors: bool,
+
+    /// Exit with code 5 when a scan finds no hosts
+    #[arg(long = "fail-on-empty", global = true)]
+    pub fail_on_empty: bool,
+
+    /// Run the zond-enrich-* programs on PATH after discovery and port scans
+    #[arg(long = "plugins", global = true)]
+    pub plugins: bool,
+
+    /// Enrichment stages to skip (self, leases, ad, roles, printers, device)
+    #[arg(
+        long = "no-enrich",
+        value_name = "STAGES",
+        global = true,
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(enrich::STAGES)
+    )]
+    pub no_enrich: Vec<String>,
+
+    /// Also probe this machine's own addresses inside target ranges
+    #[arg(long = "include-self", global = true)]
+    pub include_self: bool,
+
+    /// Also probe the network and broadcast addresses of CIDR targets
+    #[arg(long = "include-broadcast", global = true)]
+    pub include_broadcast: bool,
+
+    /// Write the duration of every scan phase to FILE in chrome://tracing format
+    #[arg(long = "trace-timings", value_name = "FILE", global = true)]
+    pub trace_timings: Option<PathBuf>,
+
+    /// Hold a lock on the interfaces scanned, so other zond scans with --lock do not share them
+    #[arg(long = "lock", global = true)]
+    pub lock: bool,
+
+    /// Like --lock, but queue behind a running zond scan instead of failing
+    #[arg(long = "wait", global = true, conflicts_with = "force")]
+    pub wait: bool,
+
+    /// With --lock, scan even while another zond scan holds the lock
+    #[arg(long = "force", global = true, requires = "lock")]
+    pub force: bool,
+
+    /// Open raw sockets through zond-helper under pkexec (polkit) if they are unavailable
+    #[arg(long = "elevate", global = true)]
+    pub elevate: bool,
+
     /// Increase logging detail (-v: debug logs, -vv: full packets)
     #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
     pub verbosity: u8,
@@ -84,23 +332,392 @@ pub struct CommandLine {
 pub enum Commands {
     /// Display local network interfaces
     #[command(alias = "i")]
-    Info,
+    Info {
+        /// Connect to the local TCP services from the LAN side to see which are reachable
+        #[arg(long = "self-test")]
+        self_test: bool,
+    },
+    /// Check raw sockets, capture, DNS, OUI database, terminal and clock, with fixes
+    Doctor,
+
     /// Passive discovery via traffic monitoring
     #[command(alias = "l")]
     Listen,
 
     /// Find live hosts within a specified range
-    #[command(alias = "d")]
+    #[command(alias = "d", after_help = DISCOVER_EXAMPLES)]
     Discover {
-        #[arg(value_name = "TARGETS", num_args(1..))]
+        /// Targets to scan; `@last` selects the hosts of the previous scan
+        #[arg(value_name = "TARGETS", num_args(1..), required_unless_present = "interactive")]
         targets: Vec<String>,
+
+        /// Pick the interface, subnet and technique from menus instead
+        #[arg(long = "interactive", conflicts_with = "targets")]
+        interactive: bool,
+
+        /// Narrow `@last` down (e.g. hostname~printer, vendor=apple, role=gateway)
+        #[arg(long = "filter", value_name = "EXPR")]
+        filter: Vec<HostFilter>,
+
+        /// Listen for SECS seconds first and only probe on-link hosts not heard from
+        #[arg(long = "passive-warmup", value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        passive_warmup: Option<u64>,
+
+        /// Probe IP (e.g. the gateway) throughout the scan to tell slow hosts from congestion
+        #[arg(long = "baseline", value_name = "IP")]
+        baseline: Option<IpAddr>,
+
+        /// Tag Active Directory domain controllers (DC SRV records, Kerberos, SMB signing)
+        #[arg(long = "detect-ad")]
+        detect_ad: bool,
+
+        /// Check hosts against an arpwatch database (arp.dat) and update it
+        #[arg(long = "arpwatch", value_name = "FILE")]
+        arpwatch: Option<PathBuf>,
+
+        /// Print which interface each target is probed through before scanning
+        #[arg(long = "plan")]
+        plan: bool,
+
+        /// Also write the JSON report of the hosts shown to FILE
+        #[arg(long = "export", value_name = "FILE")]
+        export: Vec<PathBuf>,
     },
 
     /// Port scan specific targets
-    #[command(alias = "s")]
+    #[command(alias = "s", after_help = SCAN_EXAMPLES)]
     Scan {
+        /// Targets to scan; `@last` selects the hosts of the previous scan
+        #[arg(value_name = "TARGETS", num_args(1..))]
+        targets: Vec<String>,
+
+        /// Narrow `@last` down (e.g. hostname~printer, vendor=apple, role=gateway)
+        #[arg(long = "filter", value_name = "EXPR")]
+        filter: Vec<HostFilter>,
+
+        /// Also write the JSON report of the hosts shown to FILE
+        #[arg(long = "export", value_name = "FILE")]
+        export: Vec<PathBuf>,
+    },
+
+    /// Show how one device changed across the stored reports
+    #[command(after_help = HISTORY_EXAMPLES)]
+    History {
+        /// IP or MAC address of the device
+        #[arg(value_name = "DEVICE")]
+        device: DeviceQuery,
+
+        /// Also read this report file (repeatable)
+        #[arg(long = "report", value_name = "REPORT")]
+        report: Vec<String>,
+    },
+
+    /// Inspect a single host in depth: discovery, names, ports and TLS certificates
+    #[command(after_help = HOST_EXAMPLES)]
+    Host {
+        /// IP address or hostname of the target
+        #[arg(value_name = "TARGET")]
+        target: String,
+    },
+
+    /// Identify the devices answering mDNS and any reflectors repeating it
+    Mdns {
+        /// Listen for SECS seconds after the first query
+        #[arg(long = "duration", value_name = "SECS", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        duration: u64,
+    },
+
+    /// Find out which IP protocols (TCP, UDP, GRE, ESP, SCTP...) reach a host
+    Protocols {
+        /// IPv4 address of the target
+        #[arg(value_name = "TARGET")]
+        target: Ipv4Addr,
+
+        /// Protocols to probe, by name or number (e.g. gre,esp,115)
+        #[arg(long = "probe", value_name = "PROTO", value_delimiter = ',', value_parser = ipproto::parse)]
+        probe: Vec<u8>,
+    },
+
+    /// Print the JSON Schema of the machine-readable report format
+    Schema,
+
+    /// Print a tab completion script for SHELL (bash, zsh, fish, powershell, elvish)
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
+
+    /// Print the man page in roff format
+    Man,
+
+    /// Render a saved JSON report for sharing, e.g. as an HTML page
+    #[command(after_help = REPORT_EXAMPLES)]
+    Report {
+        /// Report file to render; `@last` renders the previous scan, `@schedule:ID` a scheduled one
+        #[arg(value_name = "REPORT")]
+        report: String,
+
+        /// Output format
+        #[arg(long = "format", value_name = "FORMAT", default_value = "html")]
+        format: report::ReportFormat,
+
+        /// Earlier report to list new, gone and changed hosts against
+        #[arg(long = "baseline", value_name = "REPORT")]
+        baseline: Option<String>,
+    },
+
+    /// Print a saved report the way a live scan shows its results
+    #[command(after_help = VIEW_EXAMPLES)]
+    View {
+        /// Report file to show; `@last` shows the previous scan, `@schedule:ID` a scheduled one
+        #[arg(value_name = "REPORT")]
+        report: String,
+    },
+
+    /// Expose a scan API over HTTP, also collecting reports from agents
+    Serve {
+        /// Address and port to listen on (non-loopback requires --token and --tls-cert)
+        #[arg(long = "listen", value_name = "ADDR", default_value = serve::DEFAULT_LISTEN)]
+        listen: SocketAddr,
+
+        /// Require this bearer token on every request (default: a random one per launch)
+        #[arg(long = "token", value_name = "TOKEN")]
+        token: Option<String>,
+
+        /// Serve over TLS with this PEM certificate chain
+        #[arg(long = "tls-cert", value_name = "FILE", requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key of --tls-cert
+        #[arg(long = "tls-key", value_name = "FILE", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Treat hosts that went missing and came back N times as asleep rather than down
+        #[arg(long = "sleepy-after", value_name = "N", default_value_t = topology::DEFAULT_SLEEPY_AFTER)]
+        sleepy_after: u32,
+
+        /// Drop hosts missing from N reports in a row
+        #[arg(long = "expire-after", value_name = "N", default_value_t = topology::DEFAULT_EXPIRE_AFTER)]
+        expire_after: u32,
+    },
+
+    /// Discover hosts and report them to a collector started with `zond serve`
+    Agent {
         #[arg(value_name = "TARGETS", num_args(1..))]
         targets: Vec<String>,
+
+        /// Collector to report to
+        #[arg(long = "collector", value_name = "HOST:PORT")]
+        collector: String,
+
+        /// Name to report under (defaults to the hostname)
+        #[arg(long = "name")]
+        name: Option<String>,
+
+        /// Bearer token expected by the collector
+        #[arg(long = "token", value_name = "TOKEN")]
+        token: Option<String>,
+
+        /// Report over TLS, trusting the PEM certificates in FILE
+        #[arg(long = "tls-ca", value_name = "FILE")]
+        tls_ca: Option<PathBuf>,
+
+        /// Keep scanning and report every SECS seconds
+        #[arg(long = "every", value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        every: Option<u64>,
+
+        /// Check hosts against an arpwatch database (arp.dat) and update it every round
+        #[arg(long = "arpwatch", value_name = "FILE")]
+        arpwatch: Option<PathBuf>,
+    },
+
+    /// Export a saved report to an inventory system
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+
+    /// Import host names from elsewhere, used for hosts DNS leaves unnamed
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+
+    /// Run discover or scan on a cron schedule, keeping their past reports
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleAction,
+    },
+
+    /// Preview or share anonymous statistics of the previous scan (opt-in)
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+
+    /// Anonymize a REPORT and the captures of its run into test fixtures
+    #[command(hide = true)]
+    Fixture {
+        /// Report to anonymize; `@last` takes the previous scan, `@schedule:ID` a scheduled one
+        #[arg(value_name = "REPORT")]
+        report: String,
+
+        /// Capture to anonymize along with it, instead of those saved during its run
+        #[arg(long = "pcap", value_name = "FILE")]
+        pcap: Vec<PathBuf>,
+
+        /// Directory to write the fixtures to
+        #[arg(long = "out", value_name = "DIR")]
+        out: PathBuf,
+    },
+
+    /// Answer ARP and ping for fake ADDRESSES on an interface, a test peer for scans
+    ///
+    /// It answers for any address, including those in use, so only debug
+    /// builds carry it.
+    #[cfg(debug_assertions)]
+    #[command(hide = true)]
+    Respond {
+        /// Addresses to answer for (e.g. 10.200.0.50-10.200.0.60)
+        #[arg(value_name = "ADDRESSES", num_args(1..), required = true)]
+        addresses: Vec<String>,
+
+        /// Interface to answer on
+        #[arg(long = "interface", value_name = "NAME")]
+        interface: String,
+
+        /// Hold every answer back for MS milliseconds
+        #[arg(long = "delay", value_name = "MS", default_value_t = 0)]
+        delay: u64,
+
+        /// Leave PERCENT of the requests unanswered
+        #[arg(long = "drop", value_name = "PERCENT", default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+        drop: u8,
+
+        /// Send every answer N times
+        #[arg(long = "copies", value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..))]
+        copies: u8,
+
+        /// Answer ARP only, like a host that ignores pings
+        #[arg(long = "no-icmp")]
+        no_icmp: bool,
+    },
+
+    /// Download the latest IEEE OUI registry for vendor lookups
+    UpdateOui {
+        /// Registry to download
+        #[arg(long = "url", value_name = "URL", default_value = update_oui::IEEE_OUI_CSV_URL)]
+        url: String,
+
+        /// Refuse the download unless it has this SHA-256 digest
+        #[arg(long = "sha256", value_name = "HEX")]
+        sha256: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportTarget {
+    /// Create or update NetBox IP addresses (and devices) for the hosts of REPORT
+    Netbox {
+        /// Report file to export; `@last` exports the previous scan
+        #[arg(value_name = "REPORT", default_value = history::LAST_TOKEN)]
+        report: String,
+
+        /// NetBox base URL; without it the API payloads are printed for review
+        #[arg(long = "url", value_name = "URL")]
+        url: Option<String>,
+
+        /// NetBox API token
+        #[arg(long = "token", value_name = "TOKEN", requires = "url")]
+        token: Option<String>,
+
+        /// Also export devices, at the site with this slug
+        #[arg(
+            long = "site",
+            value_name = "SLUG",
+            requires_all = ["device_role", "device_type"]
+        )]
+        site: Option<String>,
+
+        /// Role slug of exported devices
+        #[arg(long = "device-role", value_name = "SLUG", requires = "site")]
+        device_role: Option<String>,
+
+        /// Device type slug of exported devices
+        #[arg(long = "device-type", value_name = "SLUG", requires = "site")]
+        device_type: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportSource {
+    /// Name the hosts listed in an SSH known_hosts file (hashed entries are skipped)
+    KnownHosts {
+        /// File to read [default: ~/.ssh/known_hosts]
+        #[arg(value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Skip looking up the addresses of names listed without one
+        #[arg(long = "no-resolve")]
+        no_resolve: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScheduleAction {
+    /// Run COMMAND on SCHEDULE, e.g. `add "0 3 * * *" discover lan`
+    #[command(after_help = SCHEDULE_ADD_EXAMPLES)]
+    Add {
+        /// Five-field cron expression in UTC, or @hourly, @daily, @weekly...
+        #[arg(value_name = "SCHEDULE", value_parser = parse_cron)]
+        cron: String,
+
+        /// Keep the reports of the last N runs
+        #[arg(long = "keep", value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        keep: u32,
+
+        /// Discover or scan command line, without the leading `zond`
+        #[arg(
+            value_name = "COMMAND",
+            num_args(1..),
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+
+    /// List the scheduled scans, their next run and latest report
+    List,
+
+    /// Unschedule a scan and delete its reports
+    Remove {
+        #[arg(value_name = "ID")]
+        id: u32,
+    },
+
+    /// Run the scheduled scans on time until stopped
+    Run,
+}
+
+#[derive(Subcommand)]
+pub enum StatsAction {
+    /// Print the statistics `share` would send, without sending anything
+    Preview {
+        /// Privacy budget of the noise added to every count; smaller is more private
+        #[arg(long = "epsilon", default_value_t = stats::DEFAULT_EPSILON, value_parser = parse_epsilon)]
+        epsilon: f64,
+    },
+
+    /// Show the statistics and send them to ENDPOINT once confirmed
+    Share {
+        /// URL accepting the statistics as a JSON POST
+        #[arg(long = "endpoint", value_name = "URL")]
+        endpoint: String,
+
+        /// Privacy budget of the noise added to every count; smaller is more private
+        #[arg(long = "epsilon", default_value_t = stats::DEFAULT_EPSILON, value_parser = parse_epsilon)]
+        epsilon: f64,
     },
 }
 
@@ -108,6 +725,23 @@ impl CommandLine {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Returns how the command treats another running scan, or `None` if it
+    /// does not scan or no lock was asked for.
+    pub fn lock_policy(&self) -> Option<LockPolicy> {
+        match self.command {
+            Commands::Discover { .. } | Commands::Scan { .. } | Commands::Host { .. }
+                if self.lock || self.wait =>
+            {
+                Some(match (self.wait, self.force) {
+                    (true, _) => LockPolicy::Wait,
+                    (_, true) => LockPolicy::Force,
+                    _ => LockPolicy::Fail,
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<&CommandLine> for ZondConfig {
@@ -115,9 +749,76 @@ impl From<&CommandLine> for ZondConfig {
         Self {
             no_banner: cmd.no_banner,
             no_dns: cmd.no_dns,
+            resolve_public: cmd.resolve_public,
+            resolve_budget: cmd.resolve_budget.map(Duration::from_millis),
+            memory_limit: cmd.memory_limit.map(|mib| mib * 1024 * 1024),
             redact: cmd.redact,
+            demo: cmd.demo,
+            raw_hostnames: cmd.raw_hostnames,
+            search_domains: cmd.short_names.clone().unwrap_or_default(),
             quiet: cmd.quiet,
             disable_input: false,
+            fail_on_empty: cmd.fail_on_empty,
+            assume_yes: cmd.yes,
+            max_targets: (!cmd.i_know_what_im_doing).then_some(cmd.max_targets),
+            source_ports: cmd.source_ports.clone(),
+            technique: cmd.technique,
+            syn_ports: cmd.probe_ports.clone(),
+            sctp: cmd.sctp,
+            packet_trace: cmd.verbosity >= 2,
+            capture_errors: cmd.capture_errors,
+            passive_warmup: None,
+            baseline: None,
+            no_promiscuous: !cmd.promiscuous,
+            random_mac: cmd.random_mac,
+            detect_ad: false,
+            plugins: cmd.plugins,
+            disabled_enrichers: cmd.no_enrich.clone(),
+            timing: Timing {
+                retries: cmd.retries,
+                ..Timing::DEFAULT
+            },
+            calibrate: cmd.calibrate,
+            include_self: cmd.include_self,
+            include_broadcast: cmd.include_broadcast,
+            by_vendor: cmd.by_vendor,
+            group_details: cmd.verbosity >= 1,
         }
     }
 }
+
+/// Checks a cron expression, keeping it as given.
+fn parse_cron(input: &str) -> Result<String, String> {
+    input.parse::<CronExpr>()?;
+    Ok(input.trim().to_string())
+}
+
+/// Parses a positive, finite privacy budget.
+fn parse_epsilon(input: &str) -> Result<f64, String> {
+    match input.trim().parse::<f64>() {
+        Ok(epsilon) if epsilon.is_finite() && epsilon > 0.0 => Ok(epsilon),
+        _ => Err(format!("expected a positive number, got '{input}'")),
+    }
+}
+
+/// Parses a `START-END` port range, rejecting port 0 and reversed bounds.
+fn parse_port_range(input: &str) -> Result<RangeInclusive<u16>, String> {
+    let (start, end) = input
+        .split_once('-')
+        .ok_or_else(|| format!("expected START-END, got '{input}'"))?;
+
+    let start: u16 = start
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid start port '{start}'"))?;
+    let end: u16 = end
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid end port '{end}'"))?;
+
+    if start == 0 || start > end {
+        return Err(format!("invalid port range {start}-{end}"));
+    }
+
+    Ok(start..=end)
+}
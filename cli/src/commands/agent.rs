@@ -0,0 +1,190 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Agent Command Implementation
+//!
+//! Implements `zond agent`, which runs host discovery from this machine and uploads
+//! the results to a collector (`zond serve`). The collector merges reports from all
+//! of its agents into a single, deduplicated topology.
+//!
+//! With `--every`, the agent keeps scanning and reporting at that interval, so the
+//! collector's view follows changes on every segment an agent can see. With
+//! `--arpwatch`, every round is also checked against an arpwatch database,
+//! which makes the agent a drop-in replacement for arpwatch on its segments.
+//!
+//! With `--tls-ca`, reports go over TLS to a collector whose certificate is
+//! signed by one of the given authorities (see [`tls`](crate::tls)).
+
+use std::{path::PathBuf, sync::atomic::Ordering, time::Duration};
+
+use anyhow::{Context, anyhow, ensure};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_rustls::TlsConnector;
+use zond_common::{
+    config::ZondConfig,
+    error, info,
+    models::host::Host,
+    models::ip::set::IpSet,
+    parse::{self, DiscoveryOverride},
+    success,
+};
+use zond_core::scanner;
+
+use crate::{arpwatch::Database, impact, report, signals, tls};
+
+/// Upper bound for how long an upload may take before it is abandoned.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where and how an agent reports.
+pub struct AgentOptions {
+    /// `HOST:PORT` of the collector.
+    pub collector: String,
+    /// Name the collector files this agent's results under.
+    pub name: Option<String>,
+    /// Bearer token expected by the collector.
+    pub token: Option<String>,
+    /// Certificate authorities to trust when reporting over TLS.
+    pub tls_ca: Option<PathBuf>,
+    /// Re-scan and report at this interval instead of exiting after one report.
+    pub every: Option<Duration>,
+    /// arpwatch database checked and updated after every scan.
+    pub arpwatch: Option<PathBuf>,
+}
+
+/// Scans `targets` and reports the results to the collector.
+///
+/// # Errors
+///
+/// Returns an error if the targets cannot be parsed, the arpwatch database or
+/// TLS certificates cannot be read, the scan fails, or, in
+/// one-shot mode, the upload fails. In `--every` mode failed uploads are logged
+/// and retried in the next round.
+pub async fn agent(
+    targets: &[String],
+    opts: &AgentOptions,
+    cfg: &ZondConfig,
+) -> anyhow::Result<()> {
+    let groups: Vec<(DiscoveryOverride, IpSet)> =
+        parse::to_discovery_groups(targets, cfg.technique, cfg.include_broadcast)?;
+    impact::check_limit(groups.iter().map(|(_, ips)| ips.len()).sum(), cfg)?;
+    let name: String = match &opts.name {
+        Some(name) => name.clone(),
+        None => sys_info::hostname().context("could not determine agent name, pass --name")?,
+    };
+
+    let mut stations: Option<Database> =
+        opts.arpwatch.as_deref().map(Database::open).transpose()?;
+    let connector: Option<TlsConnector> = opts.tls_ca.as_deref().map(tls::connector).transpose()?;
+
+    loop {
+        let hosts: Vec<Host> = scanner::discover_groups(groups.clone(), cfg).await?;
+        if let Some(stations) = &mut stations {
+            stations.record(&hosts);
+            if let Err(e) = stations.save() {
+                error!("{e:#}");
+            }
+        }
+        let body: String = report::agent_report_to_json(&name, &hosts);
+
+        match upload(opts, connector.as_ref(), &body).await {
+            Ok(()) => success!(
+                "Reported {} hosts to {} as {name}",
+                hosts.len(),
+                opts.collector
+            ),
+            Err(e) if opts.every.is_some() => error!("Report to {} failed: {e:#}", opts.collector),
+            Err(e) => return Err(e),
+        }
+
+        let Some(every) = opts.every else {
+            return Ok(());
+        };
+        if scanner::STOP_SIGNAL.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        info!("Next report in {}s", every.as_secs());
+        tokio::select! {
+            _ = tokio::time::sleep(every) => {}
+            _ = signals::wait() => return Ok(()),
+        }
+    }
+}
+
+async fn upload(
+    opts: &AgentOptions,
+    connector: Option<&TlsConnector>,
+    body: &str,
+) -> anyhow::Result<()> {
+    tokio::time::timeout(UPLOAD_TIMEOUT, post_report(opts, connector, body))
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", UPLOAD_TIMEOUT.as_secs()))?
+}
+
+async fn post_report(
+    opts: &AgentOptions,
+    connector: Option<&TlsConnector>,
+    body: &str,
+) -> anyhow::Result<()> {
+    let stream: TcpStream = TcpStream::connect(&opts.collector)
+        .await
+        .with_context(|| format!("could not connect to {}", opts.collector))?;
+
+    let authorization: String = opts
+        .token
+        .as_ref()
+        .map(|token| format!("Authorization: Bearer {token}\r\n"))
+        .unwrap_or_default();
+    let head: String = format!(
+        "POST /reports HTTP/1.1\r\nHost: {}\r\n{authorization}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        opts.collector,
+        body.len()
+    );
+
+    let response: String = match connector {
+        Some(connector) => {
+            let stream = connector
+                .connect(tls::server_name(&opts.collector)?, stream)
+                .await
+                .with_context(|| format!("TLS handshake with {} failed", opts.collector))?;
+            exchange(stream, &head, body).await?
+        }
+        None => exchange(stream, &head, body).await?,
+    };
+
+    let status: u16 = response
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow!("collector sent a malformed response"))?;
+    let message: &str = response
+        .split_once("\r\n\r\n")
+        .map_or("", |(_, body)| body.trim());
+
+    ensure!(
+        (200..300).contains(&status),
+        "collector answered {status}: {message}"
+    );
+    Ok(())
+}
+
+/// Sends a request and reads the whole response.
+async fn exchange<S>(mut stream: S, head: &str, body: &str) -> anyhow::Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut response: Vec<u8> = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
@@ -0,0 +1,36 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Completions and Man Page Commands
+//!
+//! Implements `zond completions <SHELL>` and `zond man`. Both are generated from
+//! the clap definitions in [`CommandLine`], so every new flag or subcommand shows
+//! up in them without further work.
+//!
+//! Like `zond schema`, the output goes to stdout while all UI output goes to
+//! stderr, so `zond man > zond.1` yields a clean file.
+
+use std::io::Write;
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::commands::CommandLine;
+
+/// Prints the completion script for `shell`.
+pub fn completions(shell: Shell) -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    clap_complete::generate(shell, &mut CommandLine::command(), "zond", &mut stdout);
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Prints the man page in roff format.
+pub fn man() -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    clap_mangen::Man::new(CommandLine::command()).render(&mut stdout)?;
+    Ok(())
+}
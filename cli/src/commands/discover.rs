@@ -17,21 +17,37 @@
 //!
 //! 1.  **Parse**: Converts raw target strings (e.g., "10.0.0.0/24") into a valid [`IpCollection`].
 //! 2.  **Monitor**: Spawns a background spinner to show progress during the async scan.
-//! 3.  **Execute**: Calls [`scanner::discover`] to do the actual scanning.
+//! 3.  **Execute**: Calls [`scanner::discover_groups`] to do the actual scanning.
 //! 4.  **Render**: Sorts the resulting host list by IP and prints the summary to stdout.
+//!
+//! With `--arpwatch`, the hosts found are also checked against an arpwatch
+//! database before rendering, see [`crate::arpwatch`]. With `--plan`, the
+//! interface each target is probed through is printed before the scan starts.
 
-use std::time::Instant;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use colored::*;
-use tracing::info_span;
-
+use tracing::{Span, info_span};
+
+use crate::arpwatch::Database;
+use crate::exit::NoHostsFound;
+use crate::history::{self, HostFilter};
+use crate::impact;
+use crate::lock::{self, LockPolicy, ScanLock};
+use crate::query::Query;
+use crate::report;
 use crate::terminal::colors;
 use crate::terminal::print::Print;
 use crate::terminal::spinner::SpinnerGuard;
 
+use zond_common::models::host::{Host, Unreachable};
 use zond_common::models::ip::set::IpSet;
-use zond_common::parse;
-use zond_common::{config::ZondConfig, models::host::Host};
+use zond_common::parse::{self, DiscoveryOverride};
+use zond_common::utils::demo::Demo;
+use zond_common::{config::ZondConfig, phase, warn};
 use zond_core::scanner;
 
 /// Runs the active discovery scan on the provided targets.
@@ -39,30 +55,89 @@ use zond_core::scanner;
 /// This handles the full scan lifecycle: parsing the target strings, managing the
 /// progress spinner, and printing the sorted results to stdout.
 ///
-/// If no hosts are found, it prints a "No results" message and exits cleanly.
+/// If no hosts are found, it prints a "No results" message and exits cleanly, unless
+/// `--fail-on-empty` is set, in which case [`NoHostsFound`] is returned.
 ///
 /// # Arguments
 ///
 /// * `targets` - Raw target strings from the CLI (e.g., `["192.168.1.1", "10.0.0.0/24"]`).
+/// * `filters` - Narrow down the hosts selected by `@last`.
+/// * `query` - Only hosts matching this `--where` expression are shown.
+/// * `arpwatch` - arpwatch database to check the hosts against and update.
+/// * `plan` - Print the partition of the targets across interfaces first.
+/// * `export` - Files to write the JSON report of the hosts shown to.
+/// * `lock` - How to treat other scans on the same interfaces, `None` without `--lock`.
 /// * `cfg` - Scan configuration (timeout, ports, etc).
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// * The target strings cannot be parsed into valid IPs or CIDRs.
+/// * `@last` is used but no previous scan matches the filters.
+/// * The arpwatch database or an `--export` file cannot be read or written.
 /// * The underlying scanner encounters a fatal network error.
-pub async fn discover(targets: &[String], cfg: &ZondConfig) -> anyhow::Result<()> {
+pub async fn discover(
+    targets: &[String],
+    filters: &[HostFilter],
+    query: Option<&Query>,
+    arpwatch: Option<&Path>,
+    plan: bool,
+    export: &[PathBuf],
+    lock: Option<LockPolicy>,
+    cfg: &ZondConfig,
+) -> anyhow::Result<()> {
     Print::header("performing host discovery");
 
-    let _guard: SpinnerGuard = run_spinner();
+    let targets: Vec<String> = history::expand_targets(targets, filters)?;
+
+    let groups: Vec<(DiscoveryOverride, IpSet)> =
+        parse::to_discovery_groups(&targets, cfg.technique, cfg.include_broadcast)?;
+    impact::check_limit(groups.iter().map(|(_, ips)| ips.len()).sum(), cfg)?;
+    if plan {
+        Print::plan(&scanner::impact::plan(&groups));
+    }
+    impact::confirm(&scanner::impact::discovery(&groups, cfg), cfg)?;
+    let _lock: Option<ScanLock> = lock::hold(lock, || {
+        groups.iter().fold(IpSet::new(), |mut all, (_, ips)| {
+            all.merge(ips.clone());
+            all
+        })
+    })
+    .await?;
+    let mut stations: Option<Database> = arpwatch.map(Database::open).transpose()?;
 
-    let ips: IpSet = parse::to_ipset(targets)?;
+    let _guard: SpinnerGuard = run_spinner();
     let start_time: Instant = Instant::now();
 
-    let mut hosts: Vec<Host> = scanner::discover(ips, cfg).await?;
+    let mut hosts: Vec<Host> = scanner::discover_groups(groups, cfg).await?;
+    let mut unreachable: Vec<Unreachable> = scanner::take_unreachable();
+
+    if let Err(e) = history::save_last(&hosts, &unreachable) {
+        warn!("Could not store results for {}: {e:#}", history::LAST_TOKEN);
+    }
+    if let Some(stations) = &mut stations {
+        stations.record(&hosts);
+        stations.save()?;
+    }
+
+    if let Some(query) = query {
+        hosts.retain(|host| query.matches(host));
+    }
+
+    if cfg.demo {
+        hosts.iter_mut().for_each(|host| Demo::session().host(host));
+        unreachable
+            .iter_mut()
+            .for_each(|target| Demo::session().unreachable(target));
+    }
+    report::export(&hosts, &unreachable, export, cfg)?;
 
     if hosts.is_empty() {
         Print::no_results();
+        Print::unreachable(&unreachable);
+        if cfg.fail_on_empty {
+            return Err(NoHostsFound.into());
+        }
         return Ok(());
     }
 
@@ -70,8 +145,10 @@ pub async fn discover(targets: &[String], cfg: &ZondConfig) -> anyhow::Result<()
 
     hosts.sort_by_key(|host| *host.ips.iter().next().unwrap_or(&host.primary_ip));
 
+    let _phase: Span = phase!("render", hosts = hosts.len());
     Print::hosts(&hosts)?;
-    Print::discovery_summary(hosts.len(), start_time.elapsed());
+    Print::unreachable(&unreachable);
+    Print::discovery_summary(&hosts, start_time.elapsed());
 
     Ok(())
 }
@@ -81,6 +158,15 @@ fn run_spinner() -> SpinnerGuard {
     let _enter = span.enter();
 
     SpinnerGuard::with_status(span.clone(), || {
+        let outstanding: usize = zond_core::scanner::outstanding_lookups();
+        if outstanding > 0 {
+            return format!(
+                "Resolving hostnames ({} outstanding)...",
+                outstanding.to_string().green().bold()
+            )
+            .color(colors::TEXT_DEFAULT)
+            .italic();
+        }
         let count = zond_core::scanner::get_host_count();
         let count_str = count.to_string().green().bold();
         let label = if count == 1 { "host" } else { "hosts" };
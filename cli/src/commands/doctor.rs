@@ -0,0 +1,108 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Doctor Command Implementation
+//!
+//! Implements `zond doctor`, which checks whether this machine can run zond
+//! the way it is meant to: raw sockets, a capture channel per interface, the
+//! name server, the OUI database, the terminal and the clock. Each check
+//! passes or says how to fix it, so its output is the first thing a bug
+//! report needs. The environment checks live in [`zond_core::system::doctor`];
+//! the terminal is checked here, where it is drawn on.
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use anyhow::bail;
+use colored::control::SHOULD_COLORIZE;
+use zond_core::system::doctor::{self, Check, Status};
+
+use crate::terminal::print::{Print, TOTAL_WIDTH};
+
+/// Runs every check and prints the results.
+///
+/// # Errors
+///
+/// Returns an error if any check failed, for scripts to act on.
+pub async fn doctor() -> anyhow::Result<()> {
+    Print::header("checking the environment");
+
+    let mut checks: Vec<Check> = doctor::run().await;
+    checks.push(terminal());
+    Print::checks(&checks);
+    Print::checks_summary(&checks);
+
+    let failed: usize = checks
+        .iter()
+        .filter(|check| check.status == Status::Fail)
+        .count();
+    if failed > 0 {
+        bail!("{failed} environment check(s) failed");
+    }
+    Ok(())
+}
+
+/// Checks that the terminal is wide enough for the output and can show its
+/// colours and box drawing characters.
+fn terminal() -> Check {
+    const NAME: &str = "Terminal";
+    if !io::stdout().is_terminal() {
+        return Check::pass(NAME, "output is not a terminal, colours are off");
+    }
+
+    let (rows, cols) = console::Term::stdout().size();
+    let color: bool = SHOULD_COLORIZE.should_colorize();
+    let utf8: bool = cfg!(windows) || locale_is_utf8(|key| env::var(key).ok());
+    let detail: String = format!(
+        "{cols}x{rows}, {}, {}",
+        match color {
+            true => "colours",
+            false => "no colours",
+        },
+        match utf8 {
+            true => "UTF-8",
+            false => "no UTF-8",
+        }
+    );
+
+    if (cols as usize) < TOTAL_WIDTH {
+        return Check::warn(
+            NAME,
+            detail,
+            format!("output is {TOTAL_WIDTH} columns wide and wraps; widen the window or pass -q"),
+        );
+    }
+    if !utf8 {
+        return Check::warn(
+            NAME,
+            detail,
+            "trees and dividers are drawn with Unicode characters; use a UTF-8 locale, e.g. \
+             export LANG=C.UTF-8",
+        );
+    }
+    if !color {
+        return Check::warn(
+            NAME,
+            detail,
+            "NO_COLOR or the terminal type turns colours off; unset NO_COLOR or set \
+             CLICOLOR_FORCE=1",
+        );
+    }
+    Check::pass(NAME, detail)
+}
+
+/// Returns `true` if the locale in effect, by POSIX precedence, uses UTF-8.
+fn locale_is_utf8(var: impl Fn(&str) -> Option<String>) -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|key| var(key).filter(|value| !value.is_empty()))
+        .is_some_and(|locale| {
+            let locale: String = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
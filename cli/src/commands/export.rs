@@ -0,0 +1,278 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Export Command Implementation
+//!
+//! Implements `zond export netbox`, which maps the hosts of a saved report onto
+//! NetBox API objects:
+//!
+//! * every address of a host becomes an IPAM IP address (`/32` or `/128`) with
+//!   the hostname as DNS name and vendor, model and MAC in the description,
+//! * with `--site`, `--device-role` and `--device-type`, every host also
+//!   becomes a device, named after its hostname or primary IP.
+//!
+//! Without `--url` the payloads are printed for review, like `zond report` the
+//! document goes to stdout. With `--url` each object is looked up first and
+//! updated if it exists, so exporting the same scan twice creates nothing new.
+//! Related objects are referenced by slug, which NetBox 4 resolves itself.
+
+use std::{io::Write, net::IpAddr, time::Duration};
+
+use anyhow::{Context, bail};
+use serde_json::{Value, json};
+use zond_common::{info, models::host::Host, success, warn};
+
+use crate::commands::report;
+
+/// Upper bound for each API request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// NetBox rejects longer descriptions.
+const MAX_DESCRIPTION_LEN: usize = 200;
+
+/// Where and how hosts are exported to NetBox.
+pub struct NetboxOptions {
+    /// Base URL of the NetBox instance; `None` prints the payloads instead.
+    pub url: Option<String>,
+    /// API token sent as `Authorization: Token ...`.
+    pub token: Option<String>,
+    /// Slugs devices are created with; `None` exports IP addresses only.
+    pub devices: Option<DeviceDefaults>,
+}
+
+/// Related objects every exported device points to, by slug.
+pub struct DeviceDefaults {
+    pub site: String,
+    pub role: String,
+    pub device_type: String,
+}
+
+/// The NetBox objects describing a set of hosts.
+struct Payloads {
+    ip_addresses: Vec<Value>,
+    devices: Vec<Value>,
+}
+
+/// Exports the hosts of the report at `source` to NetBox, or prints the
+/// payloads if no URL was given.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be read, stdout is closed, or any
+/// object could not be created or updated.
+pub async fn netbox(source: &str, opts: &NetboxOptions) -> anyhow::Result<()> {
+    let hosts: Vec<Host> = report::load(source)?.hosts;
+    let payloads: Payloads = payloads(&hosts, opts.devices.as_ref());
+
+    let Some(url) = opts.url.clone() else {
+        let document: Value = json!({
+            "ip_addresses": payloads.ip_addresses,
+            "devices": payloads.devices,
+        });
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&document)?)?;
+        return Ok(());
+    };
+
+    info!(
+        "Exporting {} addresses and {} devices to {url}",
+        payloads.ip_addresses.len(),
+        payloads.devices.len()
+    );
+    let token: Option<String> = opts.token.clone();
+    let site: Option<String> = opts.devices.as_ref().map(|d| d.site.clone());
+    tokio::task::spawn_blocking(move || sync(&url, token.as_deref(), site.as_deref(), payloads))
+        .await
+        .context("export task failed")?
+}
+
+/// Builds the payloads of every host.
+fn payloads(hosts: &[Host], devices: Option<&DeviceDefaults>) -> Payloads {
+    let ip_addresses: Vec<Value> = hosts
+        .iter()
+        .flat_map(|host| host.ips.iter().map(move |ip| ip_address(host, ip)))
+        .collect();
+    let devices: Vec<Value> = match devices {
+        Some(defaults) => hosts.iter().map(|host| device(host, defaults)).collect(),
+        None => Vec::new(),
+    };
+    Payloads {
+        ip_addresses,
+        devices,
+    }
+}
+
+fn ip_address(host: &Host, ip: &IpAddr) -> Value {
+    let prefix_len: u8 = match ip {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    let mut object: Value = json!({
+        "address": format!("{ip}/{prefix_len}"),
+        "status": "active",
+        "description": description(host),
+    });
+    if let Some(name) = host.hostname.as_deref().filter(|name| is_dns_name(name)) {
+        object["dns_name"] = Value::from(name.trim_end_matches('.'));
+    }
+    object
+}
+
+fn device(host: &Host, defaults: &DeviceDefaults) -> Value {
+    json!({
+        "name": device_name(host),
+        "site": { "slug": defaults.site },
+        "role": { "slug": defaults.role },
+        "device_type": { "slug": defaults.device_type },
+        "status": "active",
+        "description": description(host),
+    })
+}
+
+fn device_name(host: &Host) -> String {
+    host.hostname
+        .clone()
+        .unwrap_or_else(|| host.primary_ip.to_string())
+}
+
+/// Vendor, model and MAC address, as far as they are known.
+fn description(host: &Host) -> String {
+    let mac: Option<String> = host.mac.map(|mac| format!("MAC {mac}"));
+    let parts: Vec<&str> = [
+        host.vendor.as_deref(),
+        host.model.as_deref(),
+        mac.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let mut description: String = parts.join(", ");
+    if description.len() > MAX_DESCRIPTION_LEN {
+        let mut cut: usize = MAX_DESCRIPTION_LEN;
+        while !description.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        description.truncate(cut);
+    }
+    description
+}
+
+/// NetBox only accepts letters, digits, `.`, `-`, `_` and `*` in DNS names.
+fn is_dns_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*'))
+}
+
+/// Creates or updates every object and logs the outcome.
+fn sync(
+    url: &str,
+    token: Option<&str>,
+    site: Option<&str>,
+    payloads: Payloads,
+) -> anyhow::Result<()> {
+    let api: Api = Api::new(url, token);
+    let mut created: usize = 0;
+    let mut updated: usize = 0;
+    let mut failed: usize = 0;
+
+    let addresses = payloads.ip_addresses.iter().map(|object| {
+        let filter: Vec<(&str, &str)> =
+            vec![("address", object["address"].as_str().unwrap_or_default())];
+        ("ipam/ip-addresses", filter, object)
+    });
+    let devices = payloads.devices.iter().map(|object| {
+        let mut filter: Vec<(&str, &str)> =
+            vec![("name", object["name"].as_str().unwrap_or_default())];
+        filter.extend(site.map(|site| ("site", site)));
+        ("dcim/devices", filter, object)
+    });
+
+    for (endpoint, filter, object) in addresses.chain(devices) {
+        match api.upsert(endpoint, &filter, object) {
+            Ok(true) => created += 1,
+            Ok(false) => updated += 1,
+            Err(e) => {
+                warn!("Could not export {}: {e:#}", filter[0].1);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} object(s) failed, {created} created, {updated} updated");
+    }
+    success!("Exported to NetBox: {created} created, {updated} updated");
+    Ok(())
+}
+
+/// A minimal client of the NetBox REST API.
+struct Api {
+    agent: ureq::Agent,
+    base: String,
+    authorization: Option<String>,
+}
+
+impl Api {
+    fn new(url: &str, token: Option<&str>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(concat!("zond/", env!("CARGO_PKG_VERSION")))
+                .build(),
+            base: format!("{}/api", url.trim_end_matches('/')),
+            authorization: token.map(|token| format!("Token {token}")),
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let request: ureq::Request = self
+            .agent
+            .request(method, &format!("{}/{path}", self.base))
+            .set("Accept", "application/json")
+            .set("Content-Type", "application/json");
+        match &self.authorization {
+            Some(authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    /// Updates the first object of `endpoint` matching `filter` with `object`,
+    /// or creates it. Returns whether it was created.
+    fn upsert(
+        &self,
+        endpoint: &str,
+        filter: &[(&str, &str)],
+        object: &Value,
+    ) -> anyhow::Result<bool> {
+        let lookup: ureq::Request = filter.iter().fold(
+            self.request("GET", &format!("{endpoint}/")),
+            |request, (key, value)| request.query(key, value),
+        );
+        let existing: Value = serde_json::from_str(
+            &lookup
+                .call()
+                .with_context(|| format!("lookup in {endpoint} failed"))?
+                .into_string()?,
+        )?;
+        let body: String = object.to_string();
+
+        match existing["results"][0]["id"].as_u64() {
+            Some(id) => {
+                self.request("PATCH", &format!("{endpoint}/{id}/"))
+                    .send_string(&body)
+                    .with_context(|| format!("update of {endpoint}/{id} failed"))?;
+                Ok(false)
+            }
+            None => {
+                self.request("POST", &format!("{endpoint}/"))
+                    .send_string(&body)
+                    .with_context(|| format!("creation in {endpoint} failed"))?;
+                Ok(true)
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Fixture Command Implementation
+//!
+//! Implements the hidden `zond fixture`, which turns a report and the pcap
+//! files of its run into anonymized fixtures ([`fixture`]) for regression
+//! tests of parsing and enrichment. A device that trips zond up can then be
+//! reproduced from a bug report without the reporter's addresses and names.
+//!
+//! Without `--pcap`, the captures the scanners saved during the run of the
+//! report are used, found by its scan ID.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use zond_common::{success, utils::fixture::Anonymizer, warn};
+use zond_core::scanner::capture;
+
+use crate::{
+    commands::report,
+    report::{Report, report_to_json},
+    terminal::print::Print,
+};
+
+/// Name of the anonymized report in the output directory.
+const REPORT_FILE: &str = "report.json";
+
+/// Writes anonymized copies of the report at `source` and of `pcaps`, or the
+/// captures of its run, to `out`.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read or written, or a capture is not
+/// a classic pcap file.
+pub fn fixture(source: &str, pcaps: &[PathBuf], out: &Path) -> anyhow::Result<()> {
+    Print::header("anonymizing a fixture");
+
+    let mut report: Report = report::load(source)?;
+    let pcaps: Vec<PathBuf> = match (pcaps.is_empty(), &report.scan) {
+        (false, _) => pcaps.to_vec(),
+        (true, Some(run)) => capture::captures_of(&run.id),
+        (true, None) => Vec::new(),
+    };
+    if pcaps.is_empty() {
+        warn!("No capture to go with {source}, writing the report alone");
+    }
+    let mut captures: Vec<(PathBuf, Vec<u8>)> = Vec::with_capacity(pcaps.len());
+    for path in pcaps {
+        let bytes: Vec<u8> =
+            fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        captures.push((path, bytes));
+    }
+
+    // Learn everything first, so an address only seen in a capture is also
+    // replaced where the report mentions it, and the other way around.
+    let mut anonymizer: Anonymizer = Anonymizer::new(rand::random());
+    for host in &report.hosts {
+        anonymizer.learn_host(host);
+    }
+    for target in &report.unreachable {
+        anonymizer.learn_unreachable(target);
+    }
+    if let Some(run) = &report.scan {
+        anonymizer.learn_scan(run);
+    }
+    if let Some(agent) = &report.agent {
+        anonymizer.learn_hostname(agent);
+    }
+    for (path, bytes) in &captures {
+        anonymizer
+            .learn_pcap(bytes)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+    }
+
+    fs::create_dir_all(out).with_context(|| format!("failed to create {}", out.display()))?;
+    let mut frames: usize = 0;
+    for (path, bytes) in &mut captures {
+        frames += anonymizer.pcap(bytes)?;
+        let name: &str = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("capture.pcap");
+        let target: PathBuf = out.join(name);
+        fs::write(&target, &bytes[..])
+            .with_context(|| format!("failed to write {}", target.display()))?;
+    }
+
+    for host in &mut report.hosts {
+        anonymizer.host(host);
+    }
+    for target in &mut report.unreachable {
+        anonymizer.unreachable(target);
+    }
+    if let Some(run) = &mut report.scan {
+        anonymizer.scan(run);
+    }
+    report.agent = report.agent.as_deref().map(|agent| anonymizer.text(agent));
+    let target: PathBuf = out.join(REPORT_FILE);
+    fs::write(&target, report_to_json(&report))
+        .with_context(|| format!("failed to write {}", target.display()))?;
+
+    success!(
+        "Wrote {} host(s) and {frames} frame(s) from {} capture(s) to {}",
+        report.hosts.len(),
+        captures.len(),
+        out.display()
+    );
+    Ok(())
+}
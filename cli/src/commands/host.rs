@@ -0,0 +1,160 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Host Command Implementation
+//!
+//! Implements `zond host`, an in-depth look at exactly one target.
+//!
+//! A hostname is resolved through the system resolver first and then kept as
+//! the TLS server name. [`scanner::inspect`] runs discovery, the port scan and
+//! the TLS probe in one go, and the result is printed as a single host tree
+//! followed by what only this command shows.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Instant,
+};
+
+use anyhow::Context;
+use colored::*;
+use tracing::info_span;
+
+use crate::exit::NoHostsFound;
+use crate::history;
+use crate::lock::{self, LockPolicy, ScanLock};
+use crate::terminal::colors;
+use crate::terminal::print::Print;
+use crate::terminal::spinner::SpinnerGuard;
+
+use zond_common::{
+    config::ZondConfig,
+    models::{ip::set::IpSet, port::PortSet},
+    utils::demo::Demo,
+    warn,
+};
+use zond_core::error::ZondError;
+use zond_core::scanner::{self, Dossier, tls::Certificate};
+
+/// Inspects `target`, an IP address or hostname, and prints its dossier.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * `target` is neither an address nor a resolvable hostname.
+/// * Discovery or the port scan fails.
+/// * Nothing answered and `--fail-on-empty` is set, as [`NoHostsFound`].
+pub async fn host(
+    target: &str,
+    ports: PortSet,
+    lock: Option<LockPolicy>,
+    cfg: &ZondConfig,
+) -> anyhow::Result<()> {
+    Print::header("inspecting host");
+
+    let (ip, name) = resolve(target).await?;
+    let _lock: Option<ScanLock> = lock::hold(lock, || {
+        let mut ips: IpSet = IpSet::new();
+        ips.insert(ip);
+        ips
+    })
+    .await?;
+
+    let shown: IpAddr = if cfg.demo { Demo::session().ip(ip) } else { ip };
+    let _guard: SpinnerGuard = run_spinner(shown);
+    let start_time: Instant = Instant::now();
+
+    let mut dossier: Dossier = scanner::inspect(ip, name.as_deref(), ports, cfg).await?;
+
+    if !dossier.responded() {
+        Print::no_results();
+        if cfg.fail_on_empty {
+            return Err(NoHostsFound.into());
+        }
+        return Ok(());
+    }
+
+    if dossier.host.hostname.is_none() {
+        dossier.host.hostname = name;
+    }
+
+    if let Err(e) = history::save_last(std::slice::from_ref(&dossier.host), &[]) {
+        warn!("Could not store results for {}: {e:#}", history::LAST_TOKEN);
+    }
+
+    if cfg.demo {
+        Demo::session().host(&mut dossier.host);
+        dossier
+            .certificates
+            .iter_mut()
+            .for_each(|(_, cert)| fake_certificate(cert));
+    }
+
+    Print::header("Host Dossier");
+    Print::dossier(&dossier)?;
+    Print::inspection_summary(dossier.host.primary_ip, start_time.elapsed());
+
+    Ok(())
+}
+
+/// Returns the address to inspect, and the name it was looked up by if
+/// `target` is not an address already.
+///
+/// IPv4 results are preferred, since discovery of them works on more links.
+async fn resolve(target: &str) -> anyhow::Result<(IpAddr, Option<String>)> {
+    if let Ok(ip) = target.parse::<IpAddr>() {
+        return Ok((ip, None));
+    }
+
+    let resolved: Vec<IpAddr> = tokio::net::lookup_host((target, 0))
+        .await
+        .with_context(|| format!("could not resolve '{target}'"))?
+        .map(|addr: SocketAddr| addr.ip())
+        .collect();
+    let ip: IpAddr = resolved
+        .iter()
+        .find(|ip| ip.is_ipv4())
+        .or(resolved.first())
+        .copied()
+        .ok_or_else(|| ZondError::InvalidTarget(format!("'{target}' has no addresses")))?;
+    if resolved.len() > 1 {
+        warn!(
+            "'{target}' resolves to {} addresses, inspecting only one",
+            resolved.len()
+        );
+    }
+    Ok((ip, Some(target.to_string())))
+}
+
+/// Replaces the names of a certificate with the same fakes the host gets.
+///
+/// Issuers are kept since they usually name a CA or vendor, except on
+/// self-signed certificates where the issuer is the device itself.
+fn fake_certificate(cert: &mut Certificate) {
+    let demo: &Demo = Demo::session();
+    let fake = |name: &str| -> String {
+        match name.parse::<IpAddr>() {
+            Ok(ip) => demo.ip(ip).to_string(),
+            Err(_) => demo.hostname(name),
+        }
+    };
+
+    cert.subject = cert.subject.as_deref().map(fake);
+    cert.alt_names = cert.alt_names.iter().map(|name| fake(name)).collect();
+    if cert.self_signed {
+        cert.issuer = cert.subject.clone();
+    }
+}
+
+fn run_spinner(ip: IpAddr) -> SpinnerGuard {
+    let span = info_span!("host", indicatif.pb_show = true);
+    let _enter = span.enter();
+
+    SpinnerGuard::with_status(span.clone(), move || {
+        format!("Inspecting {}...", ip.to_string().green().bold())
+            .color(colors::TEXT_DEFAULT)
+            .italic()
+    })
+}
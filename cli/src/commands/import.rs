@@ -0,0 +1,80 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Import Command Implementation
+//!
+//! Implements `zond import known-hosts`, which names the hosts the user
+//! already logs into over SSH. The names go to the name store in the data
+//! directory, and scans use them for hosts that DNS leaves unnamed.
+
+use std::{
+    env, fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use zond_common::{info, success, warn};
+use zond_core::scanner::names::{self, KnownHosts};
+
+use crate::terminal::print::Print;
+
+/// Reads `file`, or `~/.ssh/known_hosts`, looks up the names listed without
+/// an address unless `resolve` is off, and stores every name found.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or the name store cannot be
+/// written.
+pub async fn known_hosts(file: Option<&Path>, resolve: bool) -> anyhow::Result<()> {
+    Print::header("importing known hosts");
+
+    let path: PathBuf = match file {
+        Some(path) => path.to_path_buf(),
+        None => default_known_hosts()?,
+    };
+    let content: String =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let known: KnownHosts = names::parse_known_hosts(&content);
+
+    if known.hashed > 0 {
+        warn!(
+            "Skipped {} hashed entr(ies), set HashKnownHosts no in ssh_config to keep names readable",
+            known.hashed
+        );
+    }
+
+    let mut found: Vec<(IpAddr, String)> = known.named;
+    if resolve && !known.unresolved.is_empty() {
+        info!(
+            "Looking up {} name(s) listed without an address",
+            known.unresolved.len()
+        );
+        found.extend(names::resolve(known.unresolved).await);
+    }
+
+    if found.is_empty() {
+        warn!("No named host found in {}", path.display());
+        return Ok(());
+    }
+
+    let imported: usize = found.len();
+    let (store, total): (PathBuf, usize) = names::import(found)?;
+    success!(
+        "Imported {imported} name(s) from {}, {} now names {total} address(es)",
+        path.display(),
+        store.display()
+    );
+    Ok(())
+}
+
+fn default_known_hosts() -> anyhow::Result<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".ssh").join("known_hosts"))
+        .ok_or_else(|| anyhow!("no home directory, pass the known_hosts file to read"))
+}
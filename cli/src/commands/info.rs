@@ -10,7 +10,8 @@
 //! local system, including network interfaces, firewall status, and running services.
 //!
 //! It serves as a diagnostic tool for users to quickly assess their local network configuration
-//! and potential security exposure.
+//! and potential security exposure. With `--self-test`, the exposure of each TCP service is
+//! measured by connecting to it from the LAN side instead of predicted from the firewall rules.
 
 use anyhow;
 use colored::*;
@@ -25,14 +26,18 @@ use crate::{
 };
 use zond_common::{
     config::ZondConfig,
-    models::localhost::{FirewallStatus, IpServiceGroup, Service},
+    models::localhost::{
+        Exposure, FirewallRules, FirewallStatus, IpServiceGroup, SelfTest, Service, Transport,
+    },
+    warn,
 };
 
 /// Prints system and network information to the terminal.
 ///
 /// This function gathers data from the system (hostname, OS, etc.) and network interfaces.
-/// If run as root, it also performs a deeper analysis of running services and firewall status.
-pub fn info(_cfg: &ZondConfig) -> anyhow::Result<()> {
+/// If run as root, it also performs a deeper analysis of running services and firewall status,
+/// and with `self_test` checks which services the LAN can connect to.
+pub fn info(self_test: bool, _cfg: &ZondConfig) -> anyhow::Result<()> {
     print::Print::header("about the tool");
     zprint!(
         "{}",
@@ -52,8 +57,21 @@ pub fn info(_cfg: &ZondConfig) -> anyhow::Result<()> {
     printer.print_system()?;
 
     if is_root() {
-        printer.print_firewall_status(system_info.firewall)?;
-        printer.print_local_services(&system_info.services)?;
+        let probes: Option<SelfTest> = if self_test {
+            zond_core::info::self_test(&system_info.services)
+                .inspect_err(|e| warn!("Self-test failed: {e:#}"))
+                .ok()
+        } else {
+            None
+        };
+        let exposure: Exposures = Exposures {
+            rules: system_info.firewall_rules.as_ref(),
+            probes: probes.as_ref(),
+        };
+        printer.print_firewall_status(system_info.firewall, &exposure)?;
+        printer.print_local_services(&system_info.services, &exposure)?;
+    } else if self_test {
+        warn!("The self-test needs root to list local services, skipping it");
     }
 
     let interfaces = zond_common::net::interface::get_prioritized_interfaces(5)?;
@@ -62,6 +80,22 @@ pub fn info(_cfg: &ZondConfig) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// What is known about the reachability of local services.
+struct Exposures<'a> {
+    /// Firewall rules the exposure can be predicted from.
+    rules: Option<&'a FirewallRules>,
+    /// Connections made to the services, which beat any prediction.
+    probes: Option<&'a SelfTest>,
+}
+
+impl Exposures<'_> {
+    fn of(&self, service: &Service, protocol: Transport) -> Option<Exposure> {
+        self.probes
+            .and_then(|probes| probes.exposure(service))
+            .or_else(|| self.rules.map(|rules| rules.exposure(service, protocol)))
+    }
+}
+
 /// Helper struct to manage printing context and alignment.
 struct InfoPrinter {
     /// Width of the key column for alignment.
@@ -116,8 +150,13 @@ impl InfoPrinter {
         Ok(())
     }
 
-    /// Prints the firewall status.
-    fn print_firewall_status(&self, status: FirewallStatus) -> anyhow::Result<()> {
+    /// Prints the firewall status and, where they could be read, a summary of
+    /// the inbound rules and the self-test.
+    fn print_firewall_status(
+        &self,
+        status: FirewallStatus,
+        exposure: &Exposures,
+    ) -> anyhow::Result<()> {
         print::Print::header("firewall status");
         let status_str = match status {
             FirewallStatus::Active => "active".green().bold(),
@@ -127,6 +166,22 @@ impl InfoPrinter {
 
         self.aligned_line("Status", status_str);
 
+        if let Some(rules) = exposure.rules {
+            let default: &str = match rules.default_inbound {
+                Exposure::Reachable => "allow",
+                Exposure::Blocked => "block",
+                _ => "block (signed software allowed)",
+            };
+            self.aligned_line("Inbound", default);
+            self.aligned_line("Allowed", format!("{} rules", rules.allow.len()));
+        }
+        if let Some(probes) = exposure.probes {
+            self.aligned_line(
+                "Self-test",
+                format!("{} from {}", probes.target, probes.source),
+            );
+        }
+
         if status == FirewallStatus::NotDetected {
             zprint!();
             zprint!(
@@ -139,8 +194,13 @@ impl InfoPrinter {
         Ok(())
     }
 
-    /// Prints the list of local services grouped by IP and protocol.
-    fn print_local_services(&self, groups: &[IpServiceGroup]) -> anyhow::Result<()> {
+    /// Prints the list of local services grouped by IP and protocol, marked
+    /// with their exposure if it was measured or the firewall rules are known.
+    fn print_local_services(
+        &self,
+        groups: &[IpServiceGroup],
+        exposure: &Exposures,
+    ) -> anyhow::Result<()> {
         print::Print::header("local services");
 
         for (idx, group) in groups.iter().enumerate() {
@@ -148,7 +208,7 @@ impl InfoPrinter {
                 continue;
             }
 
-            self.print_service_group(group);
+            self.print_service_group(group, exposure);
 
             if idx + 1 != groups.len() {
                 zprint!();
@@ -157,7 +217,7 @@ impl InfoPrinter {
         Ok(())
     }
 
-    fn print_service_group(&self, group: &IpServiceGroup) {
+    fn print_service_group(&self, group: &IpServiceGroup, exposure: &Exposures) {
         let ip_color = if group.ip_addr.is_ipv4() {
             colors::IPV4_ADDR
         } else {
@@ -175,21 +235,32 @@ impl InfoPrinter {
         if has_tcp {
             let branch = if has_udp { "├─" } else { "└─" };
             let vertical = if has_udp { "│" } else { " " };
-            self.print_service_category(&group.tcp_services, "TCP", branch, vertical);
+            self.print_service_category(
+                &group.tcp_services,
+                Transport::Tcp,
+                exposure,
+                branch,
+                vertical,
+            );
         }
 
         if has_udp {
-            self.print_service_category(&group.udp_services, "UDP", "└─", " ");
+            self.print_service_category(&group.udp_services, Transport::Udp, exposure, "└─", " ");
         }
     }
 
     fn print_service_category(
         &self,
         services: &[Service],
-        label: &str,
+        protocol: Transport,
+        exposure: &Exposures,
         branch_char: &str,
         vertical_char: &str,
     ) {
+        let label: &str = match protocol {
+            Transport::Udp => "UDP",
+            _ => "TCP",
+        };
         zprint!(
             " {} {}",
             branch_char.color(colors::SEPARATOR),
@@ -197,13 +268,20 @@ impl InfoPrinter {
         );
 
         for (i, service) in services.iter().enumerate() {
-            self.print_service_line(service, i, services.len(), vertical_char);
+            self.print_service_line(
+                service,
+                exposure.of(service, protocol),
+                i,
+                services.len(),
+                vertical_char,
+            );
         }
     }
 
     fn print_service_line(
         &self,
         service: &Service,
+        exposure: Option<Exposure>,
         idx: usize,
         total: usize,
         vertical_branch: &str,
@@ -216,14 +294,23 @@ impl InfoPrinter {
         let dots = ".".repeat(dashes_count).color(colors::SEPARATOR);
 
         let ports = self.format_ports(&service.local_ports);
+        let tag = match exposure {
+            Some(Exposure::Reachable) => " [reachable]".red(),
+            Some(Exposure::Partial) => " [partly reachable]".yellow(),
+            Some(Exposure::Blocked) => " [blocked]".green(),
+            Some(Exposure::LocalOnly) => " [local only]".green(),
+            Some(Exposure::Unknown) => " [unknown]".yellow(),
+            None => "".normal(),
+        };
 
         zprint!(
-            " {}   {branch} {}{}{} {}",
+            " {}   {branch} {}{}{} {}{}",
             vertical_branch.color(colors::SEPARATOR),
             service.name.color(colors::SECONDARY),
             dots,
             ":".color(colors::SEPARATOR),
-            ports.color(colors::TEXT_DEFAULT)
+            ports.color(colors::TEXT_DEFAULT),
+            tag
         );
     }
 
@@ -0,0 +1,83 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # mDNS Command Implementation
+//!
+//! Implements `zond mdns`, a diagnostic for `.local` discovery problems such as
+//! AirPlay or Chromecast devices that show up on one side of a network but not
+//! the other. It lists who answers mDNS on the LAN and flags reflectors that
+//! repeat answers from other segments.
+
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use colored::*;
+use tracing::info_span;
+
+use crate::exit::NoHostsFound;
+use crate::terminal::colors;
+use crate::terminal::print::Print;
+use crate::terminal::spinner::SpinnerGuard;
+
+use zond_common::{config::ZondConfig, utils::demo::Demo};
+use zond_core::scanner::mdns::{self, MdnsSurvey};
+
+/// Queries the LAN for mDNS responders, listens for `duration` and prints them.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * There is no LAN interface, or its capture cannot be opened.
+/// * Nothing answered and `--fail-on-empty` is set, as [`NoHostsFound`].
+pub async fn mdns(duration: Duration, cfg: &ZondConfig) -> anyhow::Result<()> {
+    Print::header("identifying mdns responders");
+
+    let start_time: Instant = Instant::now();
+    let mut survey: MdnsSurvey = {
+        let _guard: SpinnerGuard = run_spinner();
+        mdns::survey(duration).await?
+    };
+
+    if survey.responders.is_empty() {
+        Print::no_results();
+        if cfg.fail_on_empty {
+            return Err(NoHostsFound.into());
+        }
+        return Ok(());
+    }
+
+    if cfg.demo {
+        let demo: &Demo = Demo::session();
+        for responder in &mut survey.responders {
+            if let IpAddr::V4(ip) = demo.ip(IpAddr::V4(responder.ip)) {
+                responder.ip = ip;
+            }
+            responder.mac = demo.mac(responder.mac);
+            responder.hostname = responder
+                .hostname
+                .as_deref()
+                .map(|name| demo.hostname(name));
+            responder.announced = responder.announced.iter().map(|ip| demo.ip(*ip)).collect();
+        }
+    }
+
+    Print::mdns_survey(&survey);
+    Print::mdns_summary(&survey, start_time.elapsed());
+    Ok(())
+}
+
+fn run_spinner() -> SpinnerGuard {
+    let span = info_span!("mdns", indicatif.pb_show = true);
+    let _enter = span.enter();
+
+    SpinnerGuard::with_status(span.clone(), || {
+        "Asking the link who answers .local..."
+            .color(colors::TEXT_DEFAULT)
+            .italic()
+    })
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Protocols Command Implementation
+//!
+//! Implements `zond protocols`, an IP protocol scan in the spirit of nmap's
+//! `-sO`. It answers questions a port scan cannot, such as whether a VPN
+//! endpoint lets ESP or GRE through, or whether a firewall drops SCTP.
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    time::Instant,
+};
+
+use colored::*;
+use tracing::info_span;
+
+use crate::terminal::colors;
+use crate::terminal::print::Print;
+use crate::terminal::spinner::SpinnerGuard;
+
+use zond_common::{config::ZondConfig, utils::demo::Demo};
+use zond_core::scanner::ipproto::{self, ProtocolSurvey};
+
+/// Probes `target` with each protocol in `probe`, or the defaults if empty,
+/// and prints which ones get through.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * zond lacks raw socket access.
+/// * No interface reaches `target`, or its captures cannot be opened.
+pub async fn protocols(target: Ipv4Addr, probe: &[u8], cfg: &ZondConfig) -> anyhow::Result<()> {
+    Print::header("probing ip protocols");
+
+    let protocols: &[u8] = match probe.is_empty() {
+        true => &ipproto::DEFAULT_PROTOCOLS,
+        false => probe,
+    };
+
+    let start_time: Instant = Instant::now();
+    let mut survey: ProtocolSurvey = {
+        let _guard: SpinnerGuard = run_spinner(protocols.len());
+        ipproto::survey(target, protocols).await?
+    };
+
+    if cfg.demo {
+        let demo: &Demo = Demo::session();
+        if let IpAddr::V4(ip) = demo.ip(IpAddr::V4(survey.target)) {
+            survey.target = ip;
+        }
+        for result in &mut survey.results {
+            if let Some(IpAddr::V4(ip)) = result.reporter.map(|ip| demo.ip(IpAddr::V4(ip))) {
+                result.reporter = Some(ip);
+            }
+        }
+    }
+
+    Print::protocol_survey(&survey);
+    Print::protocol_summary(&survey, start_time.elapsed());
+    Ok(())
+}
+
+fn run_spinner(count: usize) -> SpinnerGuard {
+    let span = info_span!("protocols", indicatif.pb_show = true);
+    let _enter = span.enter();
+
+    SpinnerGuard::with_status(span.clone(), move || {
+        format!("Probing {count} IP protocol(s)...")
+            .color(colors::TEXT_DEFAULT)
+            .italic()
+    })
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Report Command Implementation
+//!
+//! Implements `zond report`, which turns a saved JSON report (`@last`, a file
+//! fetched from `GET /scans/{id}/results` or `GET /topology`) into a document
+//! for people who do not use the CLI. Reports and baselines may also be nmap
+//! XML, which is imported through [`nmap::to_hosts`].
+//!
+//! Like `zond schema`, the document goes to stdout while all UI output goes to
+//! stderr, so `zond report @last > report.html` yields a clean file.
+
+use std::{fmt, fs, io::Write, path::PathBuf, str::FromStr};
+
+use anyhow::Context;
+use zond_common::{
+    config::ZondConfig,
+    models::host::{Host, Unreachable},
+    parse::nmap,
+    utils::demo::Demo,
+};
+
+use crate::{
+    history,
+    report::{
+        self,
+        html::{self, HtmlOptions},
+    },
+    schedule,
+};
+
+/// The document formats `zond report` can produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A self-contained HTML page.
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_ascii_lowercase().as_str() {
+            "html" => Ok(Self::Html),
+            other => Err(format!("unknown report format '{other}' (expected html)")),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Html => f.write_str("html"),
+        }
+    }
+}
+
+/// Renders the report at `source` in `format`, listing changes against `baseline`
+/// if one is given.
+///
+/// # Errors
+///
+/// Returns an error if either report cannot be read or parsed, or stdout is closed.
+pub fn report(
+    source: &str,
+    format: ReportFormat,
+    baseline: Option<&str>,
+    cfg: &ZondConfig,
+) -> anyhow::Result<()> {
+    let report: report::Report = load(source)?;
+    let mut hosts: Vec<Host> = report.hosts;
+    let mut unreachable: Vec<Unreachable> = report.unreachable;
+    let mut baseline: Option<Vec<Host>> = baseline
+        .map(|baseline| load(baseline).map(|report| report.hosts))
+        .transpose()?;
+    if cfg.demo {
+        // One mapping for both, so unchanged hosts still match their baseline.
+        let demo: &Demo = Demo::session();
+        hosts
+            .iter_mut()
+            .chain(baseline.iter_mut().flatten())
+            .for_each(|host| demo.host(host));
+        unreachable
+            .iter_mut()
+            .for_each(|target| demo.unreachable(target));
+    }
+
+    let document: String = match format {
+        ReportFormat::Html => html::render(
+            &hosts,
+            &HtmlOptions {
+                source,
+                baseline: baseline.as_deref(),
+                scan: report.scan.as_ref(),
+                unreachable: &unreachable,
+                redact: cfg.redact,
+                raw_hostnames: cfg.raw_hostnames,
+                search_domains: &cfg.search_domains,
+            },
+        ),
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(document.as_bytes())?;
+    Ok(())
+}
+
+/// Reads a report file, the report of the previous scan for `@last`, or one
+/// of a scheduled scan for `@schedule:ID[~N]`.
+pub fn load(source: &str) -> anyhow::Result<report::Report> {
+    if source == history::LAST_TOKEN {
+        return history::load_last_report();
+    }
+    let path: PathBuf = match source.strip_prefix(schedule::SCHEDULE_TOKEN) {
+        Some(token) => schedule::resolve(token)?,
+        None => PathBuf::from(source),
+    };
+    let source: String = path.display().to_string();
+
+    let content: String =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {source}"))?;
+    if content.trim_start().starts_with('<') {
+        let hosts: Vec<Host> =
+            nmap::to_hosts(&content).with_context(|| format!("failed to parse {source}"))?;
+        return Ok(report::Report {
+            agent: None,
+            scan: None,
+            hosts,
+            unreachable: Vec::new(),
+        });
+    }
+    report::from_json(&content).with_context(|| format!("failed to parse {source}"))
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Respond Command Implementation
+//!
+//! Implements the hidden `zond respond`, a development aid that makes fake
+//! hosts answer ARP and ping on one interface. Run it on one end of a veth
+//! pair, e.g. `ip netns exec NS zond respond --interface IF ADDRESSES`, and
+//! scan from the other end to try retries, RTT measurement and deduplication
+//! against a peer that is slow, lossy or chatty on demand.
+//!
+//! It answers for whatever addresses it is given, the gateway's included, which
+//! makes it an ARP spoofer on a real network. Release builds leave it out.
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use pnet::datalink::NetworkInterface;
+use zond_common::{info, models::ip::set::IpSet, net::interface, parse::ip, success};
+use zond_core::scanner::responder::{Responder, ResponderStats};
+
+use crate::terminal::print::Print;
+
+/// How the fake hosts misbehave.
+pub struct RespondOptions {
+    pub delay: Duration,
+    /// Share of requests left unanswered, 0.0 to 1.0.
+    pub drop_rate: f64,
+    /// How often every answer is sent.
+    pub copies: usize,
+    /// Whether pings are answered besides ARP.
+    pub icmp: bool,
+}
+
+/// Answers for `addresses` on `interface` until stopped with Ctrl-C.
+///
+/// # Errors
+///
+/// Returns an error if the addresses do not parse, the interface does not
+/// exist, or its capture cannot be opened.
+pub async fn respond(
+    addresses: &[String],
+    interface: &str,
+    opts: RespondOptions,
+) -> anyhow::Result<()> {
+    Print::header("answering for fake hosts");
+
+    let addresses: IpSet = ip::to_set(addresses, true)?;
+    let intf: NetworkInterface = interface::find_by_name(interface)
+        .ok_or_else(|| anyhow!("no interface named {interface}"))?;
+
+    let responder: Responder = Responder::new(addresses.clone())
+        .delay(opts.delay)
+        .drop_rate(opts.drop_rate)
+        .copies(opts.copies);
+    let responder: Responder = match opts.icmp {
+        true => responder,
+        false => responder.without_icmp(),
+    };
+
+    info!(
+        "Answering for {} address(es) on {}, press Ctrl-C to stop",
+        addresses.len(),
+        intf.name
+    );
+    let stats: ResponderStats = responder.run(&intf).await?;
+    success!(
+        "Answered {} ARP request(s) and {} ping(s), dropped {}",
+        stats.arp,
+        stats.icmp,
+        stats.dropped
+    );
+    Ok(())
+}
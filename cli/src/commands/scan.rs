@@ -4,33 +4,84 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use std::time::Instant;
+use std::{path::PathBuf, time::Instant};
 
 use colored::*;
-use tracing::info_span;
+use tracing::{Span, info_span};
 
+use crate::exit::NoHostsFound;
+use crate::history::{self, HostFilter};
+use crate::impact;
+use crate::lock::{self, LockPolicy, ScanLock};
+use crate::query::Query;
+use crate::report;
 use crate::terminal::colors;
 use crate::terminal::print::Print;
 use crate::terminal::spinner::SpinnerGuard;
 
-use zond_common::{config::ZondConfig, models::port::PortSet, parse};
+use zond_common::{
+    config::ZondConfig,
+    models::{ip::set::IpSet, port::PortSet},
+    parse, phase,
+    utils::demo::Demo,
+    warn,
+};
+use zond_core::error::ZondError;
 
 pub async fn scan(
     targets: &[String],
+    filters: &[HostFilter],
+    query: Option<&Query>,
     global_ports: PortSet,
+    export: &[PathBuf],
+    lock: Option<LockPolicy>,
     cfg: &ZondConfig,
 ) -> anyhow::Result<()> {
     Print::header("starting scanner");
 
-    let _guard: SpinnerGuard = run_spinner();
+    let targets: Vec<String> = history::expand_targets(targets, filters)?;
+
+    if let Some(target) = targets.iter().find(|t| t.contains('@')) {
+        return Err(ZondError::InvalidTarget(format!(
+            "'{target}': @technique annotations only apply to discover"
+        ))
+        .into());
+    }
 
-    let target_map = parse::to_target_map(targets, global_ports)?;
+    let target_map = parse::to_target_map(&targets, global_ports)
+        .map_err(|e| ZondError::InvalidTarget(e.to_string()))?;
+    impact::check_limit(target_map.total_ips() as u64, cfg)?;
+    impact::confirm(&zond_core::scanner::impact::port_scan(&target_map), cfg)?;
+    let _lock: Option<ScanLock> = lock::hold(lock, || {
+        target_map.units.iter().fold(IpSet::new(), |mut all, unit| {
+            all.merge(unit.ips.clone());
+            all
+        })
+    })
+    .await?;
+
+    let _guard: SpinnerGuard = run_spinner();
     let start_time = Instant::now();
 
     let mut hosts = zond_core::scanner::scan(target_map, cfg).await?;
 
+    if let Err(e) = history::save_last(&hosts, &[]) {
+        warn!("Could not store results for {}: {e:#}", history::LAST_TOKEN);
+    }
+    if let Some(query) = query {
+        hosts.retain(|host| query.matches(host));
+    }
+
+    if cfg.demo {
+        hosts.iter_mut().for_each(|host| Demo::session().host(host));
+    }
+    report::export(&hosts, &[], export, cfg)?;
+
     if hosts.is_empty() {
         Print::no_results();
+        if cfg.fail_on_empty {
+            return Err(NoHostsFound.into());
+        }
         return Ok(());
     }
 
@@ -38,8 +89,9 @@ pub async fn scan(
 
     hosts.sort_by_key(|host| *host.ips.iter().next().unwrap_or(&host.primary_ip));
 
+    let _phase: Span = phase!("render", hosts = hosts.len());
     Print::hosts(&hosts)?;
-    Print::discovery_summary(hosts.len(), start_time.elapsed());
+    Print::discovery_summary(&hosts, start_time.elapsed());
 
     Ok(())
 }
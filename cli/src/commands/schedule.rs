@@ -0,0 +1,280 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Schedule Command Implementation
+//!
+//! Implements `zond schedule`, which runs discover and scan command lines on
+//! cron schedules, e.g. `zond schedule add "0 3 * * *" discover lan`.
+//! `add`, `list` and `remove` manage the jobs ([`schedule`]); `run` is the
+//! daemon that starts them, meant to be kept alive by systemd or a similar
+//! supervisor.
+//!
+//! The daemon starts every run as a child process of its own binary, with
+//! `--yes` and `--wait` so it neither asks for confirmation nor collides with
+//! another scan, and `--export` pointing into the reports of the job. The
+//! jobs are re-read every minute, so changes apply without a restart.
+
+use std::{
+    collections::HashMap,
+    env, iter,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::Parser;
+use tokio::process::{Child, Command};
+use zond_common::{
+    error, info,
+    parse::cron::{CronExpr, UtcMinute},
+    success, warn,
+};
+
+use crate::{
+    commands::{CommandLine, Commands},
+    schedule::{self, Job, SCHEDULE_TOKEN},
+    signals,
+    terminal::print::Print,
+    zprint,
+};
+
+/// How often the daemon re-reads the jobs while waiting for the next run.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Flags every scheduled run gets, ahead of its own arguments.
+const RUN_FLAGS: [&str; 3] = ["--no-banner", "--yes", "--wait"];
+
+/// Schedules the scan `args`, a `discover` or `scan` command line without the
+/// leading `zond`, to run on `cron` and keep its newest `keep` reports.
+///
+/// # Errors
+///
+/// Returns an error if `args` is not a discover or scan command line, the
+/// schedule never fires, or the jobs cannot be stored.
+pub fn add(cron: &str, args: &[String], keep: usize) -> anyhow::Result<()> {
+    Print::header("scheduling scan");
+
+    let parsed: CommandLine =
+        CommandLine::try_parse_from(iter::once("zond").chain(args.iter().map(String::as_str)))
+            .map_err(|e| {
+                let reason: String = e.to_string();
+                anyhow!(
+                    "'zond {}' is not a valid command: {}",
+                    args.join(" "),
+                    reason
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .trim_start_matches("error: ")
+                )
+            })?;
+    match parsed.command {
+        Commands::Discover {
+            interactive: false, ..
+        }
+        | Commands::Scan { .. } => {}
+        _ => bail!("only discover and scan can be scheduled"),
+    }
+
+    let next: u64 = cron
+        .parse::<CronExpr>()
+        .map_err(|e| anyhow!("invalid schedule '{cron}': {e}"))?
+        .next_after(unix_now())
+        .ok_or_else(|| anyhow!("'{cron}' never fires"))?;
+
+    let job: Job = schedule::add(cron, args, keep)?;
+    success!(
+        "Scheduled scan #{} to run 'zond {}' on '{}', first at {}",
+        job.id,
+        job.args.join(" "),
+        job.cron,
+        UtcMinute::from_unix(next)
+    );
+    info!(
+        "It runs while `zond schedule run` does; read its reports as {SCHEDULE_TOKEN}{}",
+        job.id
+    );
+    Ok(())
+}
+
+/// Prints the scheduled scans with their next run and newest report.
+///
+/// # Errors
+///
+/// Returns an error if the jobs cannot be read.
+pub fn list() -> anyhow::Result<()> {
+    Print::header("scheduled scans");
+
+    let jobs: Vec<Job> = schedule::load()?;
+    if jobs.is_empty() {
+        info!("Nothing scheduled, add a scan with `zond schedule add`");
+        return Ok(());
+    }
+
+    let now: u64 = unix_now();
+    let last: usize = jobs.len() - 1;
+    for (idx, job) in jobs.iter().enumerate() {
+        let next: Option<UtcMinute> = job
+            .cron
+            .parse::<CronExpr>()
+            .ok()
+            .and_then(|cron| cron.next_after(now))
+            .map(UtcMinute::from_unix);
+        let reports: Vec<PathBuf> = schedule::reports(job.id)?;
+        Print::scheduled_job(job, next, &reports);
+        if idx != last {
+            zprint!();
+        }
+    }
+    Ok(())
+}
+
+/// Unschedules the scan `id` and deletes its reports.
+///
+/// # Errors
+///
+/// Returns an error if no such scan is scheduled or the jobs cannot be stored.
+pub fn remove(id: u32) -> anyhow::Result<()> {
+    let job: Job = schedule::remove(id)?;
+    success!(
+        "Removed scan #{} ('zond {}') and its reports",
+        job.id,
+        job.args.join(" ")
+    );
+    Ok(())
+}
+
+/// Starts the scheduled scans on time until SIGINT or SIGTERM.
+///
+/// Runs are sequential; a run still going when the next one is due delays
+/// it. A schedule that fired while the daemon was down is not caught up on.
+///
+/// # Errors
+///
+/// Returns an error if the zond binary cannot be located or the jobs cannot
+/// be read.
+pub async fn run() -> anyhow::Result<()> {
+    Print::header("running scheduled scans");
+    let exe: PathBuf = env::current_exe().context("could not locate the zond binary")?;
+
+    // Next run of every job, by ID.
+    let mut due: HashMap<u32, u64> = HashMap::new();
+    loop {
+        let jobs: Vec<Job> = schedule::load()?;
+        due.retain(|id, _| jobs.iter().any(|job| job.id == *id));
+
+        for job in &jobs {
+            let cron: CronExpr = match job.cron.parse() {
+                Ok(cron) => cron,
+                Err(e) => {
+                    warn!(
+                        "Skipping scan #{}, invalid schedule '{}': {e}",
+                        job.id, job.cron
+                    );
+                    continue;
+                }
+            };
+            let next: u64 = *due.entry(job.id).or_insert_with(|| next_run(job, &cron));
+            if next > unix_now() {
+                continue;
+            }
+
+            run_job(&exe, job).await;
+            if signals::received() {
+                return Ok(());
+            }
+            due.insert(job.id, next_run(job, &cron));
+        }
+
+        let until_next: u64 = due
+            .values()
+            .min()
+            .map_or(u64::MAX, |next| next.saturating_sub(unix_now()));
+        let wait: Duration = Duration::from_secs(until_next.max(1)).min(RELOAD_INTERVAL);
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = signals::wait() => return Ok(()),
+        }
+    }
+}
+
+/// Runs `job` once and deletes the reports it no longer keeps.
+async fn run_job(exe: &Path, job: &Job) {
+    let report: PathBuf = match schedule::report_path(job.id, unix_now()) {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Cannot run scan #{}: {e:#}", job.id);
+            return;
+        }
+    };
+
+    info!("Running scan #{}: zond {}", job.id, job.args.join(" "));
+    let spawned: std::io::Result<Child> = Command::new(exe)
+        .args(RUN_FLAGS)
+        .args(&job.args)
+        .arg("--export")
+        .arg(&report)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child: Child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            error!("Cannot start scan #{}: {e}", job.id);
+            return;
+        }
+    };
+
+    let status: Option<std::io::Result<ExitStatus>> = tokio::select! {
+        status = child.wait() => Some(status),
+        _ = signals::wait() => None,
+    };
+    let Some(status) = status else {
+        let _ = child.start_kill();
+        warn!("Stopped scan #{} before it finished", job.id);
+        return;
+    };
+    match status {
+        Ok(status) if status.success() => {
+            success!("Scan #{} finished, report in {}", job.id, report.display())
+        }
+        Ok(status) => warn!("Scan #{} exited with {status}", job.id),
+        Err(e) => error!("Lost track of scan #{}: {e}", job.id),
+    }
+
+    match schedule::prune(job.id, job.keep) {
+        Ok(0) => {}
+        Ok(pruned) => info!(
+            verbosity = 1,
+            "Deleted {pruned} old report(s) of scan #{}", job.id
+        ),
+        Err(e) => warn!("Could not delete old reports of scan #{}: {e:#}", job.id),
+    }
+}
+
+/// Logs and returns when `job` runs next, `u64::MAX` if never.
+fn next_run(job: &Job, cron: &CronExpr) -> u64 {
+    let Some(next) = cron.next_after(unix_now()) else {
+        warn!(
+            "Scan #{} will not run again, '{}' never fires",
+            job.id, job.cron
+        );
+        return u64::MAX;
+    };
+    info!(
+        "Scan #{} runs next at {}",
+        job.id,
+        UtcMinute::from_unix(next)
+    );
+    next
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
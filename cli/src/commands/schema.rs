@@ -0,0 +1,24 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Schema Command Implementation
+//!
+//! Implements `zond schema`, which writes the JSON Schema of the machine-readable
+//! report format to stdout so downstream tools can validate zond's output.
+//!
+//! The schema goes to stdout while all UI output goes to stderr, so
+//! `zond schema > scan-report.json` yields a clean file.
+
+use std::io::Write;
+
+use zond_common::schema;
+
+/// Prints the bundled scan report schema.
+pub fn schema() -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", schema::SCAN_REPORT_SCHEMA.trim_end())?;
+    Ok(())
+}
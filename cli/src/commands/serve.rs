@@ -0,0 +1,658 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Serve Command Implementation
+//!
+//! Implements `zond serve`, a small HTTP/1.1 API on a loopback address that lets
+//! other programs drive scans without parsing terminal output.
+//!
+//! ## Endpoints
+//!
+//! | Method   | Path                  | Description                                      |
+//! |----------|-----------------------|--------------------------------------------------|
+//! | `POST`   | `/scans`              | Starts a discovery scan; body holds the targets  |
+//! | `GET`    | `/scans/{id}`         | Status and progress of a scan                    |
+//! | `GET`    | `/scans/{id}/results` | Report of a completed scan (see `zond schema`)   |
+//! | `DELETE` | `/scans/{id}`         | Cancels a running scan                           |
+//! | `POST`   | `/reports`            | Accepts a report from a `zond agent`             |
+//! | `GET`    | `/topology`           | Agent reports merged into one deduplicated view  |
+//!
+//! The scanner core keeps its progress counters and stop signal in global state, so
+//! only one scan runs at a time; starting a second one answers `409 Conflict`.
+//!
+//! Every request must carry a token as `Authorization: Bearer <token>`, so
+//! neither other local users nor web pages the user opens (through DNS
+//! rebinding) can start scans. Without `--token`, a random token is generated
+//! for each launch and written to `serve-<port>.token` in the runtime directory,
+//! readable by the user only, and the listener refuses non-loopback addresses.
+//! Those also require TLS (`--tls-cert`, `--tls-key`, see [`tls`]), so tokens
+//! and reports never cross the network in cleartext. Tokens are compared in
+//! constant time, and checked as soon as the request head is in, before any
+//! body is read.
+//!
+//! At most `MAX_CONNECTIONS` connections are served at once, further ones
+//! wait in the listen backlog. A TLS handshake must finish within
+//! `HANDSHAKE_TIMEOUT` and a request must arrive within `REQUEST_TIMEOUT`,
+//! so clients that stall cannot hold a connection open.
+//!
+//! Once the listener and the token file are open, the process is confined
+//! with [`sandbox::confine_process`]: it parses requests and traffic from the
+//! network for as long as it runs, and needs neither to run programs nor to
+//! write files from then on. `--plugins` leaves it unconfined, as enrichment
+//! plugins are programs.
+//!
+//! Hosts an agent stops reporting stay in the topology as `down`, unless they
+//! went missing and came back often enough before (`--sleepy-after`), which
+//! marks them as sleepy and their absence as `asleep`. Hosts missing from
+//! `--expire-after` reports in a row are dropped, so the topology of a
+//! long-running collector does not grow without bound.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, anyhow, bail, ensure};
+use subtle::ConstantTimeEq;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+use tokio_rustls::TlsAcceptor;
+use zond_common::{
+    config::ZondConfig,
+    debug, info,
+    models::{
+        host::Host,
+        ip::set::IpSet,
+        topology::{PresenceState, Topology},
+    },
+    parse::{self, DiscoveryOverride},
+    utils::paths,
+    warn,
+};
+use zond_core::{network::sandbox, scanner};
+
+use crate::impact;
+use crate::report::{self, Report};
+use crate::signals;
+use crate::terminal::sanitize;
+use crate::tls;
+
+/// Address used when `--listen` is not given.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7878";
+
+/// Upper bound for a request head.
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Upper bound for a request body. Agent reports of large networks dominate this.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Connections served at the same time.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Time a client has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time a client has to send its whole request, body included.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, PartialEq)]
+enum JobState {
+    Running,
+    Finished,
+    Cancelled,
+    Failed(String),
+}
+
+impl JobState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Finished => "finished",
+            Self::Cancelled => "cancelled",
+            Self::Failed(_) => "failed",
+        }
+    }
+}
+
+struct ScanJob {
+    state: JobState,
+    targets: u64,
+    host_baseline: usize,
+    started: Instant,
+    elapsed: Option<Duration>,
+    cancel_requested: bool,
+    hosts: Vec<Host>,
+}
+
+struct Server {
+    cfg: ZondConfig,
+    token: String,
+    jobs: Mutex<HashMap<u64, ScanJob>>,
+    next_id: AtomicU64,
+    topology: Mutex<Topology>,
+}
+
+/// A request line and the headers the API reads, with the first bytes of the
+/// body that arrived along with them.
+struct Head {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    content_length: usize,
+    body_start: Vec<u8>,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, msg: &str) -> Self {
+        Self::json(status, format!("{{\"error\":{}}}", report::string(msg)))
+    }
+}
+
+/// How `zond serve` listens and keeps its topology.
+pub struct ServeOptions {
+    /// Address and port to listen on.
+    pub listen: SocketAddr,
+    /// Bearer token required on every request, generated if not set.
+    pub token: Option<String>,
+    /// PEM certificate chain and private key to serve over TLS.
+    pub tls: Option<(PathBuf, PathBuf)>,
+    /// Returns after which a host counts as sleepy.
+    pub sleepy_after: u32,
+    /// Reports in a row after which a missing host is dropped.
+    pub expire_after: u32,
+}
+
+/// Serves the scan API until the process is interrupted.
+///
+/// # Errors
+///
+/// Returns an error if the address cannot be bound, or is not a loopback
+/// address while no token or TLS certificate is set, or the TLS files or the
+/// generated token cannot be read or saved.
+pub async fn serve(opts: ServeOptions, cfg: &ZondConfig) -> anyhow::Result<()> {
+    let ServeOptions {
+        listen,
+        token,
+        tls: tls_files,
+        sleepy_after,
+        expire_after,
+    } = opts;
+    ensure!(
+        listen.ip().is_loopback() || (token.is_some() && tls_files.is_some()),
+        "refusing to serve on non-loopback address {listen} without --token and --tls-cert"
+    );
+    let acceptor: Option<TlsAcceptor> = tls_files
+        .as_ref()
+        .map(|(cert, key)| tls::acceptor(cert, key))
+        .transpose()?;
+
+    let listener: TcpListener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to bind {listen}"))?;
+
+    let (token, token_file): (String, Option<PathBuf>) = match token {
+        Some(token) => (token, None),
+        None => {
+            let token: String = generate_token();
+            let path: PathBuf = paths::runtime_dir().join(format!("serve-{}.token", listen.port()));
+            write_token(&path, &token)?;
+            (token, Some(path))
+        }
+    };
+    match cfg.plugins {
+        true => warn!("Serving without the process sandbox, --plugins runs external programs"),
+        false => sandbox::confine_process(),
+    }
+
+    let server: Arc<Server> = Arc::new(Server {
+        cfg: ZondConfig {
+            disable_input: true,
+            ..cfg.clone()
+        },
+        token,
+        jobs: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        topology: Mutex::new(
+            Topology::new()
+                .with_sleepy_after(sleepy_after)
+                .with_expire_after(expire_after),
+        ),
+    });
+
+    let scheme: &str = match acceptor {
+        Some(_) => "https",
+        None => "http",
+    };
+    match &token_file {
+        Some(path) => info!(
+            "Serving scan API on {scheme}://{listen}, token in {}",
+            path.display()
+        ),
+        None => info!("Serving scan API on {scheme}://{listen}"),
+    }
+
+    let result: anyhow::Result<()> = accept(&listener, acceptor, &server).await;
+    if let Some(path) = token_file {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+/// Answers connections, over TLS if there is an `acceptor`, until the process
+/// is interrupted.
+async fn accept(
+    listener: &TcpListener,
+    acceptor: Option<TlsAcceptor>,
+    server: &Arc<Server>,
+) -> anyhow::Result<()> {
+    let connections: Arc<Semaphore> = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        let permit: OwnedSemaphorePermit = tokio::select! {
+            permit = Arc::clone(&connections).acquire_owned() => permit?,
+            _ = signals::wait() => return Ok(()),
+        };
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = signals::wait() => return Ok(()),
+        };
+        let server: Arc<Server> = Arc::clone(server);
+        let acceptor: Option<TlsAcceptor> = acceptor.clone();
+        tokio::spawn(async move {
+            let handled: anyhow::Result<()> = match acceptor {
+                Some(acceptor) => {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => handle_connection(stream, &server).await,
+                        Ok(Err(e)) => Err(anyhow::Error::new(e).context("TLS handshake failed")),
+                        Err(_) => Err(anyhow!("TLS handshake timed out")),
+                    }
+                }
+                None => handle_connection(stream, &server).await,
+            };
+            if let Err(e) = handled {
+                debug!("Request from {peer} failed: {e:#}");
+            }
+            drop(permit);
+        });
+    }
+}
+
+async fn handle_connection<S>(mut stream: S, server: &Arc<Server>) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let response: Response =
+        match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream, server)).await {
+            Ok(Ok(request)) => route(server, &request.method, &request.path, &request.body),
+            Ok(Err(response)) => response,
+            Err(_) => Response::error(408, "request timed out"),
+        };
+
+    let head: String = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn is_authorized(server: &Server, authorization: Option<&str>) -> bool {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| bool::from(given.trim().as_bytes().ct_eq(server.token.as_bytes())))
+}
+
+/// A token of 128 random bits, hex encoded.
+fn generate_token() -> String {
+    rand::random::<[u8; 16]>()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Saves `token` to `path`, readable by the user only.
+fn write_token(path: &Path, token: &str) -> anyhow::Result<()> {
+    let mut file: File = paths::open_private(path)?;
+    file.set_len(0)?;
+    file.write_all(token.as_bytes())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Reads a single request, or the response that refuses it. The token is
+/// checked before the body is read, so clients without it cannot make the
+/// server buffer one.
+async fn read_request<S>(stream: &mut S, server: &Server) -> Result<Request, Response>
+where
+    S: AsyncRead + Unpin,
+{
+    let head: Head = read_head(stream)
+        .await
+        .map_err(|e| Response::error(400, &e.to_string()))?;
+    if !is_authorized(server, head.authorization.as_deref()) {
+        return Err(Response::error(401, "missing or invalid token"));
+    }
+    let body: String = read_body(stream, head.body_start, head.content_length)
+        .await
+        .map_err(|e| Response::error(400, &e.to_string()))?;
+    Ok(Request {
+        method: head.method,
+        path: head.path,
+        body,
+    })
+}
+
+/// Reads the request line and headers.
+async fn read_head<S>(stream: &mut S) -> anyhow::Result<Head>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut buf: Vec<u8> = Vec::with_capacity(1024);
+    let mut chunk: [u8; 1024] = [0; 1024];
+
+    let head_end: usize = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        ensure!(buf.len() < MAX_HEAD_BYTES, "request head too large");
+        let n: usize = stream.read(&mut chunk).await?;
+        ensure!(n > 0, "connection closed before request was complete");
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head: String = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        bail!("malformed request line");
+    };
+
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let header = |wanted: &str| {
+        headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+            .map(|(_, value)| *value)
+    };
+
+    let content_length: usize = header("content-length")
+        .map(str::parse)
+        .transpose()
+        .context("invalid Content-Length")?
+        .unwrap_or(0);
+    ensure!(content_length <= MAX_BODY_BYTES, "request body too large");
+
+    Ok(Head {
+        method: method.to_string(),
+        path: path.to_string(),
+        authorization: header("authorization").map(str::to_string),
+        content_length,
+        body_start: buf.split_off(head_end),
+    })
+}
+
+/// Reads the rest of a body of `content_length` bytes that starts with `buf`.
+async fn read_body<S>(
+    stream: &mut S,
+    mut buf: Vec<u8>,
+    content_length: usize,
+) -> anyhow::Result<String>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut chunk: [u8; 1024] = [0; 1024];
+    while buf.len() < content_length {
+        let n: usize = stream.read(&mut chunk).await?;
+        ensure!(n > 0, "connection closed before body was complete");
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    buf.truncate(content_length);
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn route(server: &Arc<Server>, method: &str, path: &str, body: &str) -> Response {
+    let segments: Vec<&str> = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_matches('/')
+        .split('/')
+        .collect();
+
+    let id: Option<u64> = segments.get(1).and_then(|id| id.parse().ok());
+
+    match (method, segments.as_slice()) {
+        ("POST", ["scans"]) => start_scan(server, body),
+        ("GET", ["scans", _]) => with_job(server, id, |id, job| {
+            Response::json(200, status_json(id, job))
+        }),
+        ("GET", ["scans", _, "results"]) => with_job(server, id, |_, job| match job.state {
+            JobState::Running => Response::error(409, "scan is still running"),
+            _ => Response::json(200, report::to_json(&job.hosts, &[])),
+        }),
+        ("DELETE", ["scans", _]) => cancel_scan(server, id),
+        ("POST", ["reports"]) => ingest_report(server, body),
+        ("GET", ["topology"]) => {
+            let topology = server.topology.lock().unwrap();
+            Response::json(200, report::topology_to_json(&topology))
+        }
+        (_, ["scans" | "reports" | "topology", ..]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn with_job(
+    server: &Server,
+    id: Option<u64>,
+    f: impl FnOnce(u64, &ScanJob) -> Response,
+) -> Response {
+    let jobs = server.jobs.lock().unwrap();
+    match id.and_then(|id| jobs.get(&id).map(|job| (id, job))) {
+        Some((id, job)) => f(id, job),
+        None => Response::error(404, "unknown scan id"),
+    }
+}
+
+fn start_scan(server: &Arc<Server>, body: &str) -> Response {
+    let targets: Vec<String> = body
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if targets.is_empty() {
+        return Response::error(400, "request body must list at least one target");
+    }
+
+    let groups: Vec<(DiscoveryOverride, IpSet)> = match parse::to_discovery_groups(
+        &targets,
+        server.cfg.technique,
+        server.cfg.include_broadcast,
+    ) {
+        Ok(groups) => groups,
+        Err(e) => return Response::error(400, &e.to_string()),
+    };
+    let target_count: u64 = groups.iter().map(|(_, ips)| ips.len()).sum();
+    if let Err(e) = impact::check_limit(target_count, &server.cfg) {
+        return Response::error(400, &e.to_string());
+    }
+
+    let id: u64 = {
+        let mut jobs = server.jobs.lock().unwrap();
+        if jobs.values().any(|job| job.state == JobState::Running) {
+            return Response::error(409, "another scan is already running");
+        }
+
+        let id: u64 = server.next_id.fetch_add(1, Ordering::Relaxed);
+        jobs.insert(
+            id,
+            ScanJob {
+                state: JobState::Running,
+                targets: target_count,
+                host_baseline: scanner::get_host_count(),
+                started: Instant::now(),
+                elapsed: None,
+                cancel_requested: false,
+                hosts: Vec::new(),
+            },
+        );
+        id
+    };
+
+    info!("Scan {id} started ({target_count} targets)");
+
+    let server_ref: Arc<Server> = Arc::clone(server);
+    tokio::spawn(async move {
+        let result = scanner::discover_groups(groups, &server_ref.cfg).await;
+
+        let mut jobs = server_ref.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(&id) else {
+            return;
+        };
+        job.elapsed = Some(job.started.elapsed());
+        match result {
+            Ok(hosts) => {
+                job.state = if job.cancel_requested {
+                    JobState::Cancelled
+                } else {
+                    JobState::Finished
+                };
+                job.hosts = hosts;
+            }
+            Err(e) => job.state = JobState::Failed(e.to_string()),
+        }
+        info!("Scan {id} {}", job.state.as_str());
+    });
+
+    Response::json(
+        202,
+        format!("{{\"id\":{id},\"status\":{}}}", report::string("running")),
+    )
+}
+
+fn ingest_report(server: &Server, body: &str) -> Response {
+    let report: Report = match report::from_json(body) {
+        Ok(report) => report,
+        Err(e) => return Response::error(400, &format!("{e:#}")),
+    };
+    let Some(agent) = report.agent else {
+        return Response::error(400, "report does not name its agent");
+    };
+
+    let hosts_len: usize = report.hosts.len();
+    let mut topology = server.topology.lock().unwrap();
+    topology.ingest(&agent, report.hosts);
+    info!(
+        "Agent {} reported {hosts_len} hosts, {} known in total",
+        sanitize::escape(&agent),
+        topology.hosts().len()
+    );
+    for (idx, host) in topology.hosts().iter().enumerate() {
+        if topology
+            .presence(idx)
+            .is_none_or(|presence| presence.absent_for != 1)
+        {
+            continue;
+        }
+        match topology.state(idx) {
+            Some(PresenceState::Down) => warn!(
+                "{} is missing from the report of {}",
+                host.primary_ip,
+                sanitize::escape(&agent)
+            ),
+            _ => debug!("{} is asleep again", host.primary_ip),
+        }
+    }
+
+    Response::json(
+        202,
+        format!(
+            "{{\"agent\":{},\"hosts\":{hosts_len},\"topology_hosts\":{}}}",
+            report::string(&agent),
+            topology.hosts().len()
+        ),
+    )
+}
+
+fn cancel_scan(server: &Server, id: Option<u64>) -> Response {
+    let mut jobs = server.jobs.lock().unwrap();
+    let Some((id, job)) = id.and_then(|id| jobs.get_mut(&id).map(|job| (id, job))) else {
+        return Response::error(404, "unknown scan id");
+    };
+
+    if job.state != JobState::Running {
+        return Response::error(409, "scan is not running");
+    }
+
+    job.cancel_requested = true;
+    scanner::STOP_SIGNAL.store(true, Ordering::Relaxed);
+    Response::json(202, status_json(id, job))
+}
+
+fn status_json(id: u64, job: &ScanJob) -> String {
+    let hosts_found: usize = match job.state {
+        JobState::Running => scanner::get_host_count().saturating_sub(job.host_baseline),
+        _ => job.hosts.len(),
+    };
+    let elapsed: Duration = job.elapsed.unwrap_or_else(|| job.started.elapsed());
+    let error: String = match &job.state {
+        JobState::Failed(e) => report::string(e),
+        _ => "null".to_string(),
+    };
+
+    format!(
+        "{{\"id\":{id},\"status\":{},\"cancel_requested\":{},\"targets\":{},\"hosts_found\":{hosts_found},\"elapsed_s\":{:.3},\"error\":{error}}}",
+        report::string(job.state.as_str()),
+        job.cancel_requested,
+        job.targets,
+        elapsed.as_secs_f64(),
+    )
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
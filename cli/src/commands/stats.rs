@@ -0,0 +1,173 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Stats Command Implementation
+//!
+//! Implements `zond stats preview` and `zond stats share`, the only way zond
+//! ever sends statistics anywhere. Nothing is collected in the background:
+//! sharing is a deliberate command, it shows the exact payload first and asks
+//! before sending it.
+//!
+//! The payload is built from the previous scan (`@last`) and never contains
+//! addresses, hostnames or MAC addresses, only aggregates:
+//!
+//! * the number of hosts,
+//! * hosts per vendor, as coarse buckets, with vendors of fewer than five
+//!   hosts folded into `other`.
+//!
+//! Every count gets Laplace noise calibrated to the privacy budget `--epsilon`
+//! (one host changes each count by at most one), so the payload reveals next to
+//! nothing about whether a given device was on the network. Round-trip times
+//! are not shared, since one slow host can move any of their percentiles. The
+//! noise is drawn anew for every run, which is why `share` shows its own
+//! payload rather than repeating an earlier preview.
+
+use std::{collections::BTreeMap, io::Write, time::Duration};
+
+use anyhow::{Context, bail};
+use console::Term;
+use serde::Serialize;
+use zond_common::{config::ZondConfig, info, models::host::Host, success};
+
+use crate::history;
+
+/// Privacy budget used unless `--epsilon` says otherwise.
+pub const DEFAULT_EPSILON: f64 = 1.0;
+/// Version of the payload layout.
+const FORMAT_VERSION: u32 = 2;
+/// Vendors with fewer hosts than this are counted under `other`, so a rare
+/// vendor name is never released, whatever the noise.
+const MIN_VENDOR_HOSTS: usize = 5;
+/// Upper bound for the upload.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The anonymized statistics of one scan.
+#[derive(Debug, Serialize)]
+struct SharedStats {
+    format: u32,
+    zond_version: &'static str,
+    /// Privacy budget the noise was drawn for.
+    epsilon: f64,
+    hosts: u64,
+    /// Vendor name to a bucket such as `5-9` or `50+`.
+    vendors: BTreeMap<String, &'static str>,
+}
+
+/// Prints the statistics `zond stats share` would send for the previous scan.
+///
+/// # Errors
+///
+/// Returns an error if there is no previous scan or stdout is closed.
+pub fn preview(epsilon: f64) -> anyhow::Result<()> {
+    let stats: SharedStats = collect(&history::load_last()?, epsilon);
+    info!("Statistics of the previous scan, with fresh noise; nothing is sent");
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", render(&stats))?;
+    Ok(())
+}
+
+/// Shows the statistics of the previous scan and sends them to `endpoint`
+/// once confirmed.
+///
+/// # Errors
+///
+/// Returns an error if there is no previous scan, the user declines, there is
+/// no terminal to ask on and `--yes` was not given, or the upload fails.
+pub async fn share(endpoint: &str, epsilon: f64, cfg: &ZondConfig) -> anyhow::Result<()> {
+    let body: String = render(&collect(&history::load_last()?, epsilon));
+    info!("The following statistics will be sent to {endpoint}:");
+    let term: Term = Term::stderr();
+    term.write_line(&body)?;
+
+    if !cfg.assume_yes {
+        if !term.is_term() {
+            bail!("refusing to share statistics unattended, pass --yes to confirm");
+        }
+        term.write_str("Send them? [y/N] ")?;
+        let answer: String = term.read_line()?;
+        if !matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+            bail!("nothing was sent");
+        }
+    }
+
+    let url: String = endpoint.to_string();
+    tokio::task::spawn_blocking(move || upload(&url, &body))
+        .await
+        .context("upload task failed")??;
+    success!("Shared anonymous statistics with {endpoint}, thank you");
+    Ok(())
+}
+
+fn upload(url: &str, body: &str) -> anyhow::Result<()> {
+    let agent: ureq::Agent = ureq::AgentBuilder::new()
+        .timeout(UPLOAD_TIMEOUT)
+        .user_agent(concat!("zond/", env!("CARGO_PKG_VERSION")))
+        .build();
+    agent
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+        .with_context(|| format!("failed to send statistics to {url}"))?;
+    Ok(())
+}
+
+/// Aggregates `hosts` and adds noise for a privacy budget of `epsilon`.
+///
+/// The budget is split evenly between the host count and the vendor counts.
+/// Every host is counted under exactly one vendor or `other`, so the vendor
+/// counts share their half.
+fn collect(hosts: &[Host], epsilon: f64) -> SharedStats {
+    let scale: f64 = 2.0 / epsilon;
+
+    let mut per_vendor: BTreeMap<&str, usize> = BTreeMap::new();
+    for host in hosts {
+        let vendor: &str = host.vendor.as_deref().unwrap_or("unknown");
+        *per_vendor.entry(vendor).or_default() += 1;
+    }
+    let mut vendors: BTreeMap<String, &'static str> = BTreeMap::new();
+    let mut other: usize = 0;
+    for (vendor, count) in per_vendor {
+        if count < MIN_VENDOR_HOSTS {
+            other += count;
+            continue;
+        }
+        // The bucket never drops below the threshold the count passed.
+        let noisy: f64 = (count as f64 + laplace(scale)).max(MIN_VENDOR_HOSTS as f64);
+        vendors.insert(vendor.to_string(), bucket(noisy));
+    }
+    let other: f64 = other as f64 + laplace(scale);
+    if other >= 1.0 {
+        vendors.insert("other".to_string(), bucket(other));
+    }
+
+    SharedStats {
+        format: FORMAT_VERSION,
+        zond_version: env!("CARGO_PKG_VERSION"),
+        epsilon,
+        hosts: (hosts.len() as f64 + laplace(scale)).round().max(0.0) as u64,
+        vendors,
+    }
+}
+
+/// Draws from a Laplace distribution centered on zero.
+fn laplace(scale: f64) -> f64 {
+    let u: f64 = rand::random::<f64>() - 0.5;
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln()
+}
+
+fn bucket(count: f64) -> &'static str {
+    match count {
+        ..5.0 => "1-4",
+        ..10.0 => "5-9",
+        ..20.0 => "10-19",
+        ..50.0 => "20-49",
+        _ => "50+",
+    }
+}
+
+fn render(stats: &SharedStats) -> String {
+    serde_json::to_string_pretty(stats).expect("statistics only contain serializable values")
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # History Command Implementation
+//!
+//! Implements `zond history DEVICE`, the timeline of one device across the
+//! stored reports: those kept by scheduled scans ([`schedule`]), the previous
+//! scan (`@last`) and any report passed with `--report`. Where `zond report
+//! --baseline` compares two scans, this follows one device through all of
+//! them: when it was first seen, how its addresses, hostname and open ports
+//! changed, and when it went missing.
+//!
+//! Reports are put in order by the start time encoded in their scan ID, or
+//! by file modification time for reports without one, such as nmap XML.
+
+use std::{
+    collections::HashSet,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use zond_common::{
+    debug,
+    models::{
+        scan,
+        timeline::{DeviceQuery, Snapshot, Timeline},
+    },
+    warn,
+};
+
+use crate::{commands::report, history, report::Report, schedule, terminal::print::Print};
+
+/// Prints the timeline of `device` across the stored reports and `extra`.
+///
+/// # Errors
+///
+/// Returns an error if no report can be read at all.
+pub fn history(device: DeviceQuery, extra: &[String]) -> anyhow::Result<()> {
+    Print::header(&format!("history of {device}"));
+
+    let mut sources: Vec<String> = schedule::all_reports()?
+        .into_iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    sources.push(history::LAST_TOKEN.to_string());
+    sources.extend(extra.iter().cloned());
+
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+    // A scheduled run is also `@last` until the next scan.
+    let mut runs: HashSet<String> = HashSet::new();
+    for source in &sources {
+        let report: Report = match report::load(source) {
+            Ok(report) => report,
+            Err(e) if source == history::LAST_TOKEN => {
+                debug!(verbosity = 1, "No {source} to add: {e:#}");
+                continue;
+            }
+            Err(e) => {
+                warn!("Skipping {source}: {e:#}");
+                continue;
+            }
+        };
+        if let Some(run) = &report.scan
+            && !runs.insert(run.id.clone())
+        {
+            continue;
+        }
+        let Some(at) = taken_at(source, &report) else {
+            warn!("Skipping {source}: no scan ID or file time to order it by");
+            continue;
+        };
+        snapshots.push(Snapshot {
+            at,
+            hosts: report.hosts,
+        });
+    }
+
+    if snapshots.is_empty() {
+        bail!("no stored reports, keep some with `zond schedule` or pass --report");
+    }
+    let timeline: Timeline = Timeline::build(device, &snapshots);
+    if timeline.sightings == 0 {
+        warn!(
+            "{device} is in none of the {} report(s) looked at",
+            timeline.reports
+        );
+        return Ok(());
+    }
+    Print::timeline(&timeline);
+    Ok(())
+}
+
+/// When the report was taken, in seconds since the Unix epoch.
+fn taken_at(source: &str, report: &Report) -> Option<u64> {
+    if let Some(millis) = report
+        .scan
+        .as_ref()
+        .and_then(|run| scan::started_millis(&run.id))
+    {
+        return Some(millis / 1000);
+    }
+    let modified: SystemTime = fs::metadata(source).and_then(|meta| meta.modified()).ok()?;
+    modified
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|since| since.as_secs())
+}
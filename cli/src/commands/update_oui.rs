@@ -0,0 +1,81 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Update-OUI Command Implementation
+//!
+//! Implements `zond update-oui`, which downloads the current IEEE OUI registry and
+//! installs it into the data directory. Vendor lookups prefer the installed copy over
+//! the database baked into the binary for as long as it matches its stored checksum.
+//!
+//! The IEEE does not publish checksums for the registry, so `--sha256` lets users
+//! pin the digest of a copy they verified out of band (e.g. from a mirror).
+
+use std::{io::Read, path::PathBuf, time::Duration};
+
+use anyhow::{Context, bail};
+use zond_common::{info, success, utils::mac};
+
+use crate::terminal::print::Print;
+
+/// Where the IEEE publishes the MA-L (OUI) registry.
+pub const IEEE_OUI_CSV_URL: &str = "https://standards-oui.ieee.org/oui/oui.csv";
+
+/// Upper bound for the whole download.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The registry is a few MiB; anything far beyond that is not what we asked for.
+const MAX_REGISTRY_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Downloads the OUI registry from `url`, verifies it and installs it.
+///
+/// # Errors
+///
+/// Returns an error if the download fails, exceeds [`MAX_REGISTRY_BYTES`], does not
+/// match `sha256`, or is not a valid registry.
+pub async fn update_oui(url: &str, sha256: Option<&str>) -> anyhow::Result<()> {
+    Print::header("updating oui database");
+    info!("Downloading OUI registry from {url}");
+
+    let owned_url: String = url.to_string();
+    let data: Vec<u8> = tokio::task::spawn_blocking(move || download(&owned_url))
+        .await
+        .context("download task failed")??;
+
+    let path: PathBuf = mac::install_oui_csv(&data, sha256)?;
+    success!(
+        "Installed OUI registry ({} KiB, sha256 {}) at {}",
+        data.len() / 1024,
+        mac::sha256_hex(&data),
+        path.display()
+    );
+    Ok(())
+}
+
+fn download(url: &str) -> anyhow::Result<Vec<u8>> {
+    let agent: ureq::Agent = ureq::AgentBuilder::new()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(concat!("zond/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let response: ureq::Response = agent
+        .get(url)
+        .call()
+        .with_context(|| format!("failed to download {url}"))?;
+
+    let mut data: Vec<u8> = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_REGISTRY_BYTES + 1)
+        .read_to_end(&mut data)
+        .with_context(|| format!("failed to read {url}"))?;
+
+    if data.len() as u64 > MAX_REGISTRY_BYTES {
+        bail!(
+            "registry is larger than {} MiB",
+            MAX_REGISTRY_BYTES / 1024 / 1024
+        );
+    }
+    Ok(data)
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # View Command Implementation
+//!
+//! Implements `zond view REPORT`, which prints a saved report the way the
+//! scan that produced it printed its results, so they can be reviewed on
+//! another machine than the one that ran it. Nothing is probed.
+//!
+//! Reports are read like for `zond report` ([`report::load`]): `@last`, a
+//! scheduled run, an exported file or nmap XML. The output flags of a live
+//! scan apply as usual, such as `--redact`, `--demo`, `--by-vendor`,
+//! `--short-names` and `--where`.
+
+use zond_common::{config::ZondConfig, models::host::Host, utils::demo::Demo};
+
+use crate::{commands::report, query::Query, report::Report, terminal::print::Print};
+
+/// Prints the hosts of the report at `source` that match `query`.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be read or parsed.
+pub fn view(source: &str, query: Option<&Query>, cfg: &ZondConfig) -> anyhow::Result<()> {
+    Print::header("viewing saved report");
+
+    let mut report: Report = report::load(source)?;
+    if cfg.demo {
+        let demo: &Demo = Demo::session();
+        report.hosts.iter_mut().for_each(|host| demo.host(host));
+        report
+            .unreachable
+            .iter_mut()
+            .for_each(|target| demo.unreachable(target));
+        report.agent = report.agent.as_deref().map(|agent| demo.hostname(agent));
+        if let Some(run) = &mut report.scan {
+            run.user = None;
+            run.hostname = run.hostname.as_deref().map(|name| demo.hostname(name));
+            run.interfaces.clear();
+        }
+    }
+    Print::report_origin(source, report.scan.as_ref(), report.agent.as_deref());
+
+    let mut hosts: Vec<Host> = report.hosts;
+    if let Some(query) = query {
+        hosts.retain(|host| query.matches(host));
+    }
+    if hosts.is_empty() {
+        Print::no_results();
+        Print::unreachable(&report.unreachable);
+        return Ok(());
+    }
+
+    Print::header("Network Discovery");
+    hosts.sort_by_key(|host| *host.ips.iter().next().unwrap_or(&host.primary_ip));
+    Print::hosts(&hosts)?;
+    Print::unreachable(&report.unreachable);
+    Print::report_summary(&hosts);
+    Ok(())
+}
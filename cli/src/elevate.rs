@@ -0,0 +1,102 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Privilege Elevation
+//!
+//! Implements `--elevate`: when the process cannot open raw sockets, zond
+//! starts the privileged helper `zond-helper`, installed next to the zond
+//! binary, through polkit's `pkexec`. The helper opens raw and packet sockets
+//! on request and passes them back over a unix socket, see
+//! [`privilege::use_helper`]. zond itself keeps running as the user, so what
+//! parses network traffic, reads targets and writes reports never runs as
+//! root, and its files and history stay the user's.
+//!
+//! A helper installed setuid root is started directly, without polkit.
+//!
+//! With the policy from `packaging/linux/org.hollowpointer.zond.policy`
+//! installed, polkit asks for the administrator password once and keeps the
+//! authorization for a few minutes, so repeated scans in one session do not
+//! prompt again.
+
+use zond_common::utils::privilege;
+
+/// The flag that requests elevation.
+#[cfg(not(target_os = "linux"))]
+const FLAG: &str = "--elevate";
+
+/// Starts the privileged helper unless raw sockets are already available.
+///
+/// # Errors
+///
+/// Returns an error if the helper cannot be found or started, or exits
+/// without answering, e.g. because the user was not authorized.
+#[cfg(target_os = "linux")]
+pub async fn elevate() -> anyhow::Result<()> {
+    use std::{
+        env,
+        os::{fd::OwnedFd, unix::fs::MetadataExt, unix::net::UnixStream},
+        path::PathBuf,
+        process::{ExitStatus, Stdio},
+    };
+
+    use anyhow::{Context, anyhow};
+    use tokio::process::{Child, Command};
+    use zond_common::debug;
+
+    if privilege::has_raw_socket_access() {
+        return Ok(());
+    }
+
+    let helper: PathBuf = env::current_exe()
+        .context("failed to locate the zond binary")?
+        .with_file_name("zond-helper");
+    let setuid_root: bool = std::fs::metadata(&helper)
+        .with_context(|| format!("the privileged helper {} is missing", helper.display()))
+        .map(|meta| meta.uid() == 0 && meta.mode() & 0o4000 != 0)?;
+
+    let (ours, theirs): (UnixStream, UnixStream) =
+        UnixStream::pair().context("failed to create a socket for the privileged helper")?;
+    let mut command: Command = match setuid_root {
+        true => Command::new(&helper),
+        false => {
+            let mut pkexec: Command = Command::new("pkexec");
+            pkexec.arg(&helper);
+            pkexec
+        }
+    };
+    debug!("Starting {} for raw sockets", helper.display());
+    let mut child: Child = command
+        .stdin(Stdio::from(OwnedFd::from(theirs)))
+        .spawn()
+        .context("failed to run pkexec (is polkit installed?)")?;
+    // Only the helper may hold the other end, so its exit is seen as such.
+    drop(command);
+
+    // Waits for the password prompt, if there is one.
+    let started: std::io::Result<()> =
+        tokio::task::spawn_blocking(move || privilege::use_helper(ours)).await?;
+    if let Err(e) = started {
+        let status: ExitStatus = child.wait().await?;
+        // pkexec exits with 126 when the dialog was dismissed and 127 when
+        // the user is not authorized.
+        return Err(match status.code() {
+            Some(126 | 127) => anyhow!("not authorized to open raw sockets"),
+            _ => anyhow!("the privileged helper exited ({status}): {e}"),
+        });
+    }
+    Ok(())
+}
+
+/// Elevation relies on polkit, so other platforms keep running unprivileged.
+#[cfg(not(target_os = "linux"))]
+pub async fn elevate() -> anyhow::Result<()> {
+    use zond_common::warn;
+
+    if !privilege::has_raw_socket_access() {
+        warn!("{FLAG} is only supported on Linux, continuing without elevation");
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Process Exit Codes
+//!
+//! Maps the outcome of a command onto a stable process exit code so that scripts
+//! wrapping zond can branch on *why* a run failed instead of parsing its output.
+//!
+//! | Code | Meaning                                        |
+//! |------|------------------------------------------------|
+//! | `0`  | Success                                        |
+//! | `1`  | Unclassified failure                           |
+//! | `2`  | Invalid targets                                |
+//! | `3`  | Permission denied                              |
+//! | `4`  | No viable network interface                    |
+//! | `5`  | No hosts found (only with `--fail-on-empty`)   |
+//! | `6`  | Interrupted by SIGINT or SIGTERM               |
+
+use std::fmt;
+use std::process::ExitCode;
+
+use zond_common::parse::IpParseError;
+use zond_core::error::ZondError;
+
+/// The categories a finished run can be reported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZondExit {
+    Success = 0,
+    Failure = 1,
+    InvalidTargets = 2,
+    PermissionDenied = 3,
+    NoInterface = 4,
+    NoHostsFound = 5,
+    Interrupted = 6,
+}
+
+impl ZondExit {
+    /// Picks the exit code that best describes `err`.
+    ///
+    /// Errors that carry no recognizable category fall back to [`ZondExit::Failure`].
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        if err.is::<NoHostsFound>() {
+            return ZondExit::NoHostsFound;
+        }
+
+        if let Some(zond_err) = err.downcast_ref::<ZondError>() {
+            return match zond_err {
+                ZondError::PermissionDenied(_) => ZondExit::PermissionDenied,
+                ZondError::InterfaceNotFound(_) => ZondExit::NoInterface,
+                ZondError::InvalidTarget(_) => ZondExit::InvalidTargets,
+                ZondError::ChannelError(_) | ZondError::Timeout(_) => ZondExit::Failure,
+            };
+        }
+
+        if let Some(parse_err) = err.downcast_ref::<IpParseError>() {
+            return match parse_err {
+                IpParseError::LanError(_) => ZondExit::NoInterface,
+                _ => ZondExit::InvalidTargets,
+            };
+        }
+
+        ZondExit::Failure
+    }
+}
+
+impl From<ZondExit> for ExitCode {
+    fn from(exit: ZondExit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
+
+/// Returned by scanning commands when `--fail-on-empty` is set and nothing responded.
+#[derive(Debug)]
+pub struct NoHostsFound;
+
+impl fmt::Display for NoHostsFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no hosts found")
+    }
+}
+
+impl std::error::Error for NoHostsFound {}
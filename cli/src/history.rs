@@ -0,0 +1,182 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Scan History
+//!
+//! Keeps the report of the most recent scan in the data directory
+//! ([`paths::data_dir`]) so follow-up runs can target its hosts with `@last`
+//! instead of re-typing their addresses.
+//!
+//! `@last` can be narrowed with `--filter` expressions of the form `FIELD~TEXT`
+//! (case-insensitive substring) or `FIELD=TEXT` (case-insensitive equality),
+//! where `FIELD` is `hostname`, `vendor` or `role`. All filters must match.
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use anyhow::{Context, anyhow};
+use zond_common::{
+    models::host::{Host, Unreachable},
+    utils::paths,
+};
+use zond_core::error::ZondError;
+
+use crate::report;
+
+/// Target token that expands to the hosts of the previous scan.
+pub const LAST_TOKEN: &str = "@last";
+
+const LAST_REPORT_FILE: &str = "last.json";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterField {
+    Hostname,
+    Vendor,
+    Role,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterOp {
+    Contains,
+    Equals,
+}
+
+/// A single `--filter` expression.
+#[derive(Debug, Clone)]
+pub struct HostFilter {
+    field: FilterField,
+    op: FilterOp,
+    value: String,
+}
+
+impl FromStr for HostFilter {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let split: usize = input
+            .find(['~', '='])
+            .ok_or_else(|| format!("expected FIELD~TEXT or FIELD=TEXT, got '{input}'"))?;
+        let (field, rest) = input.split_at(split);
+
+        let field: FilterField = match field.trim().to_ascii_lowercase().as_str() {
+            "hostname" => FilterField::Hostname,
+            "vendor" => FilterField::Vendor,
+            "role" => FilterField::Role,
+            other => {
+                return Err(format!(
+                    "unknown filter field '{other}' (hostname, vendor, role)"
+                ));
+            }
+        };
+        let op: FilterOp = if rest.starts_with('~') {
+            FilterOp::Contains
+        } else {
+            FilterOp::Equals
+        };
+
+        Ok(Self {
+            field,
+            op,
+            value: rest[1..].trim().to_lowercase(),
+        })
+    }
+}
+
+impl HostFilter {
+    /// Returns `true` if the host satisfies this filter.
+    pub fn matches(&self, host: &Host) -> bool {
+        let candidates: Vec<String> = match self.field {
+            FilterField::Hostname => host.hostname.iter().cloned().collect(),
+            FilterField::Vendor => host.vendor.iter().cloned().collect(),
+            FilterField::Role => host
+                .network_roles
+                .iter()
+                .map(|role| role.as_str().to_string())
+                .collect(),
+        };
+
+        candidates.iter().any(|candidate| {
+            let candidate: String = candidate.to_lowercase();
+            match self.op {
+                FilterOp::Contains => candidate.contains(&self.value),
+                FilterOp::Equals => candidate == self.value,
+            }
+        })
+    }
+}
+
+/// Stores the hosts of a finished scan, and the targets reported unreachable
+/// during it, as the new `@last`.
+pub fn save_last(hosts: &[Host], unreachable: &[Unreachable]) -> anyhow::Result<()> {
+    let path: PathBuf = last_report_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let tmp: PathBuf = path.with_extension("json.tmp");
+    fs::write(&tmp, report::to_json(hosts, unreachable))
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Loads the hosts stored by [`save_last`].
+pub fn load_last() -> anyhow::Result<Vec<Host>> {
+    Ok(load_last_report()?.hosts)
+}
+
+/// Loads the report stored by [`save_last`], including the run that wrote it.
+pub fn load_last_report() -> anyhow::Result<report::Report> {
+    let path: PathBuf = last_report_path()?;
+    let content: String = fs::read_to_string(&path)
+        .with_context(|| format!("no previous scan found at {}", path.display()))?;
+    report::from_json(&content).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Replaces every `@last` in `targets` with the addresses of the previous scan's
+/// hosts that pass all `filters`.
+///
+/// # Errors
+///
+/// Returns [`ZondError::InvalidTarget`] if filters are given without `@last`, the
+/// previous scan cannot be loaded, or no stored host matches the filters.
+pub fn expand_targets(targets: &[String], filters: &[HostFilter]) -> anyhow::Result<Vec<String>> {
+    if !targets.iter().any(|t| t == LAST_TOKEN) {
+        if !filters.is_empty() {
+            return Err(ZondError::InvalidTarget(format!(
+                "--filter requires the {LAST_TOKEN} target"
+            ))
+            .into());
+        }
+        return Ok(targets.to_vec());
+    }
+
+    let selected: Vec<String> = load_last()
+        .map_err(|e| ZondError::InvalidTarget(format!("{LAST_TOKEN}: {e:#}")))?
+        .into_iter()
+        .filter(|host| filters.iter().all(|filter| filter.matches(host)))
+        .map(|host| host.primary_ip.to_string())
+        .collect();
+
+    if selected.is_empty() {
+        return Err(ZondError::InvalidTarget(
+            "no host of the previous scan matches the given filters".to_string(),
+        )
+        .into());
+    }
+
+    Ok(targets
+        .iter()
+        .filter(|t| *t != LAST_TOKEN)
+        .cloned()
+        .chain(selected)
+        .collect())
+}
+
+fn last_report_path() -> anyhow::Result<PathBuf> {
+    paths::data_dir()
+        .map(|dir| dir.join(LAST_REPORT_FILE))
+        .ok_or_else(|| anyhow!("no data directory, set ZOND_DATA_DIR"))
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Large Scan Confirmation
+//!
+//! A mistyped prefix turns a /24 into a /8. Before `discover` or `scan` probes
+//! more than [`CONFIRM_THRESHOLD`] addresses, the estimated [`Impact`] is
+//! logged and the user has to confirm it on the terminal. `--yes` confirms up
+//! front for scripts; without a terminal and without `--yes`, the scan is
+//! refused.
+//!
+//! Beyond `--max-targets` (65,536 by default) no confirmation helps: the scan
+//! is refused before its impact is even estimated, unless
+//! `--i-know-what-im-doing` lifts the limit.
+
+use std::time::Duration;
+
+use anyhow::bail;
+use console::Term;
+use zond_common::{config::ZondConfig, info, warn};
+use zond_core::{error::ZondError, scanner::impact::Impact};
+
+/// Scans of more addresses than this need confirmation.
+pub const CONFIRM_THRESHOLD: u64 = 10_000;
+
+/// Refuses a scan of `addresses` if it exceeds [`ZondConfig::max_targets`].
+///
+/// # Errors
+///
+/// Returns [`ZondError::InvalidTarget`] if there are too many addresses.
+pub fn check_limit(addresses: u64, cfg: &ZondConfig) -> Result<(), ZondError> {
+    match cfg.max_targets {
+        Some(limit) if addresses > limit => Err(ZondError::InvalidTarget(format!(
+            "{addresses} addresses exceed the limit of {limit}; check the targets for a \
+             mistyped prefix, raise --max-targets or pass --i-know-what-im-doing"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Logs `impact` and asks for confirmation if the scan is large.
+///
+/// # Errors
+///
+/// Returns an error if the scan needs confirmation and the user declines, or
+/// there is no terminal to ask on and `--yes` was not given.
+pub fn confirm(impact: &Impact, cfg: &ZondConfig) -> anyhow::Result<()> {
+    if impact.addresses <= CONFIRM_THRESHOLD {
+        return Ok(());
+    }
+
+    warn!("Large scan: {} addresses", impact.addresses);
+    info!("Probes: about {}", impact.packets);
+    info!("Estimated duration: {}", approx(impact.duration));
+    info!("Interfaces: {}", interfaces(impact));
+    if cfg.assume_yes {
+        return Ok(());
+    }
+
+    let term: Term = Term::stderr();
+    if !term.is_term() {
+        bail!(
+            "refusing to scan {} addresses unattended, pass --yes to confirm",
+            impact.addresses
+        );
+    }
+    term.write_str("Proceed? [y/N] ")?;
+    let answer: String = term.read_line()?;
+    if !matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+        bail!("scan cancelled");
+    }
+    Ok(())
+}
+
+fn interfaces(impact: &Impact) -> String {
+    let mut names: Vec<&str> = impact.interfaces.iter().map(String::as_str).collect();
+    if impact.connect {
+        names.push("TCP connect (OS routing)");
+    }
+    match names.is_empty() {
+        true => "none".to_string(),
+        false => names.join(", "),
+    }
+}
+
+/// Renders `duration` at the precision an estimate deserves.
+fn approx(duration: Duration) -> String {
+    let secs: u64 = duration.as_secs();
+    match secs {
+        0..60 => format!("~{}s", secs.max(1)),
+        60..3600 => format!("~{}m {}s", secs / 60, secs % 60),
+        _ => format!("~{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Scan Lock
+//!
+//! Two scans sharing a NIC slow each other down, skew each other's round-trip
+//! times and can trip rate limits on the network. With `--lock`, `discover`,
+//! `scan` and `host` hold an exclusive lock (`flock` on Unix) per interface
+//! they probe through, on `scan-<interface>.lock` in the runtime directory
+//! ([`paths::runtime_dir`]), and write their PID into it so a second run can
+//! name the scan in its way. Scans on different interfaces do not wait for
+//! each other.
+//!
+//! A second run fails by default. `--wait` queues it behind the running scan,
+//! `--force` runs it anyway. The lock goes away with the process, so a crashed
+//! scan never leaves a stale lock behind.
+//!
+//! Root keeps its locks in `/run/zond`. Other users may fall back to the
+//! shared temporary directory, so the lock directory must belong to the user
+//! and be closed to everyone else (mode 0700), and lock files are never
+//! opened through a symbolic link (see [`paths::open_private`]).
+
+use std::{
+    collections::BTreeSet,
+    fs::{File, TryLockError},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Context, bail};
+use zond_common::{info, models::ip::set::IpSet, net::interface, utils::paths, warn};
+
+use crate::signals;
+
+/// How often a waiting run checks whether the lock was released.
+const WAIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What to do when another scan holds the lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockPolicy {
+    /// Fail, naming the PID of the running scan.
+    Fail,
+    /// Queue behind the running scan.
+    Wait,
+    /// Run alongside it.
+    Force,
+}
+
+/// Held by a scanning command until it finishes.
+pub struct ScanLock {
+    _files: Vec<File>,
+}
+
+impl ScanLock {
+    /// Takes the lock of every interface in `interfaces` as `policy` says.
+    ///
+    /// An interface is scanned without its lock under `--force` while another
+    /// scan holds it, or if the runtime directory is not usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another scan holds a lock and `policy` is
+    /// [`LockPolicy::Fail`], or a signal arrives while waiting for it.
+    pub async fn acquire(
+        policy: LockPolicy,
+        interfaces: &BTreeSet<String>,
+    ) -> anyhow::Result<Self> {
+        let dir: PathBuf = paths::runtime_dir();
+        let mut files: Vec<File> = Vec::new();
+
+        // Always in the same order, so two waiting scans cannot deadlock.
+        for intf in interfaces {
+            let path: PathBuf = dir.join(file_name(intf));
+            let mut file: File = match paths::open_private(&path) {
+                Ok(file) => file,
+                Err(e) => {
+                    warn!("Running without the scan lock of {intf}: {e:#}");
+                    continue;
+                }
+            };
+
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    let owner: String = owner(&mut file);
+                    match policy {
+                        LockPolicy::Fail => bail!(
+                            "another zond scan is running on {intf} ({owner}); pass --wait to \
+                             queue behind it or --force to run anyway"
+                        ),
+                        LockPolicy::Force => {
+                            warn!("Running alongside another zond scan on {intf} ({owner})");
+                            continue;
+                        }
+                        LockPolicy::Wait => {
+                            info!("Waiting for the zond scan of {owner} on {intf} to finish...");
+                            wait_for(&file).await?;
+                        }
+                    }
+                }
+                Err(TryLockError::Error(e)) => {
+                    warn!("Running without the scan lock of {intf}: {e}");
+                    continue;
+                }
+            }
+
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            writeln!(file, "{}", std::process::id())?;
+            files.push(file);
+        }
+        Ok(Self { _files: files })
+    }
+}
+
+/// Takes the locks of the interfaces the targets `ips` are probed through,
+/// if there is a `policy`, i.e. `--lock` or `--wait` was given.
+///
+/// # Errors
+///
+/// See [`ScanLock::acquire`].
+pub async fn hold(
+    policy: Option<LockPolicy>,
+    ips: impl FnOnce() -> IpSet,
+) -> anyhow::Result<Option<ScanLock>> {
+    match policy {
+        Some(policy) => Ok(Some(ScanLock::acquire(policy, &interfaces(ips())).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Names the interfaces `ips` are probed through.
+fn interfaces(ips: IpSet) -> BTreeSet<String> {
+    interface::map_ips_to_interfaces(ips)
+        .0
+        .into_keys()
+        .map(|intf| intf.name)
+        .collect()
+}
+
+/// The lock file of `intf`, with any character a file name cannot hold
+/// replaced.
+fn file_name(intf: &str) -> String {
+    let intf: String = intf
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
+    format!("scan-{intf}.lock")
+}
+
+/// Names the process holding the lock by the PID it wrote.
+fn owner(file: &mut File) -> String {
+    let mut content: String = String::new();
+    file.seek(SeekFrom::Start(0))
+        .and_then(|_| file.read_to_string(&mut content))
+        .ok()
+        .and_then(|_| content.trim().parse::<u32>().ok())
+        .map_or_else(|| "PID unknown".to_string(), |pid| format!("PID {pid}"))
+}
+
+/// Polls the lock until it is free, giving up when a signal arrives.
+async fn wait_for(file: &File) -> anyhow::Result<()> {
+    loop {
+        if signals::received() {
+            bail!("interrupted while waiting for the scan lock");
+        }
+        tokio::time::sleep(WAIT_INTERVAL).await;
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(e)) => return Err(e).context("failed to take the scan lock"),
+        }
+    }
+}
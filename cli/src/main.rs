@@ -14,54 +14,291 @@
 //!
 //! ## Responsibilities
 //!
-//! 1.  **Runtime Initialization**: The `#[tokio::main]` attribute initializes the asynchronous
-//!     runtime, setting up the thread pool and I/O drivers required for non-blocking operations.
+//! 1.  **Runtime Initialization**: Builds the asynchronous runtime ([`runtime`]), setting up the
+//!     thread pools and I/O drivers required for non-blocking operations, sized by `--threads`.
 //! 2.  **Global State Setup**: Initializes the `tracing` subscriber for logging and configures
 //!     terminal output modes (verbosity, quiet mode, banners).
 //! 3.  **ZondConfiguration Mapping**: Converts raw command-line arguments (parsed via `clap`) into
 //!     the internal `ZondConfig` struct used by the core libraries.
-//! 4.  **Command Dispatch**: Routes execution to the appropriate module in `commands/`.
+//! 4.  **Command Dispatch**: Routes execution to the appropriate module in `commands/`, holding
+//!     the scan lock ([`lock`]) while scanning commands run.
 //! 5.  **Error Boundary**: Acts as the top-level error handler. Any errors propagated up from
 //!     subcommands are caught here, logged to the error stream, and converted into a
-//!     non-zero `ExitCode`.
+//!     categorized `ExitCode` (see [`exit`]).
 
+mod arpwatch;
 mod commands;
+mod elevate;
+mod exit;
+mod history;
+mod impact;
+mod lock;
+mod query;
+mod report;
+mod runtime;
+mod schedule;
+mod signals;
 mod terminal;
+mod timings;
+mod tls;
+mod wizard;
 
-use std::process::ExitCode;
+use std::{process::ExitCode, time::Duration};
 
-use zond_common::{config::ZondConfig, error};
+use zond_common::{config::ZondConfig, error, info, models::scan::ScanInfo, warn};
 
 use crate::{
-    commands::{CommandLine, Commands, discover, info, listen, scan},
-    terminal::{print::Print, spinner},
+    commands::{
+        CommandLine, Commands, ExportTarget, ImportSource, ScheduleAction, StatsAction, agent,
+        completions, discover, doctor, export, fixture, host, import, info, listen, mdns,
+        protocols, scan, schedule, schema, serve, stats, timeline, update_oui, view,
+    },
+    exit::ZondExit,
+    query::Query,
+    terminal::{guard::TerminalGuard, print::Print, spinner},
+    timings::TimingRecorder,
+    wizard::DiscoverPlan,
 };
 
-#[tokio::main]
-async fn main() -> ExitCode {
+fn main() -> ExitCode {
+    let _terminal: TerminalGuard = TerminalGuard::install();
     let commands = CommandLine::parse_args();
-    spinner::init_logging(commands.verbosity);
-    let cfg = ZondConfig::from(&commands);
+    let timings: Option<TimingRecorder> =
+        spinner::init_logging(commands.verbosity, commands.trace_timings.as_deref());
+
+    match runtime::build(commands.threads) {
+        Ok(runtime) => runtime.block_on(zond(&commands, timings)),
+        Err(e) => {
+            error!("Could not start the worker threads: {e}");
+            ZondExit::Failure.into()
+        }
+    }
+}
+
+/// Runs the whole program on the runtime and returns its exit code.
+async fn zond(commands: &CommandLine, timings: Option<TimingRecorder>) -> ExitCode {
+    if commands.elevate {
+        if let Err(e) = elevate::elevate().await {
+            error!("Elevation failed: {e:#}");
+            return ZondExit::PermissionDenied.into();
+        }
+    }
+
+    let mut cfg: ZondConfig = ZondConfig::from(commands);
+    if commands.short_names.as_ref().is_some_and(Vec::is_empty) {
+        match zond_core::system::get_search_domains() {
+            Ok(domains) => cfg.search_domains = domains,
+            Err(e) => warn!("No DNS search domains to shorten hostnames with: {e}"),
+        }
+    }
     let _ = Print::init(&cfg);
+    let scan: &ScanInfo = report::stamp(&cfg);
+    info!(verbosity = 1, "Scan ID {}", scan.id);
 
     Print::banner();
+    signals::install();
 
-    let result = match &commands.command {
-        Commands::Info => info::info(&cfg),
-        Commands::Listen => listen::listen(&cfg),
-        Commands::Discover { targets } => discover::discover(targets, &cfg).await,
-        Commands::Scan { targets } => scan::scan(targets, commands.ports.clone(), &cfg).await,
-    };
+    let result: anyhow::Result<()> = run(commands, &cfg).await;
 
-    let exit_code = match result {
-        Ok(_) => ExitCode::SUCCESS,
+    let mut exit_code: ZondExit = match result {
+        Ok(_) => ZondExit::Success,
         Err(e) => {
-            error!("Critical failure: {e}");
-            ExitCode::FAILURE
+            let exit: ZondExit = ZondExit::from_error(&e);
+            if exit != ZondExit::NoHostsFound {
+                error!("Critical failure: {e}");
+            }
+            exit
         }
     };
 
+    if signals::received() {
+        exit_code = ZondExit::Interrupted;
+    }
+
+    if let Some(timings) = timings {
+        if commands.verbosity >= 1 {
+            Print::phase_timings(&timings.summary(), timings.elapsed());
+        }
+        if let Err(e) = timings.save() {
+            warn!("Could not save phase timings: {e:#}");
+        }
+    }
+
     Print::end_of_program();
 
-    exit_code
+    exit_code.into()
+}
+
+/// Runs the subcommand selected on the command line.
+async fn run(commands: &CommandLine, cfg: &ZondConfig) -> anyhow::Result<()> {
+    match &commands.command {
+        Commands::Info { self_test } => info::info(*self_test, cfg),
+        Commands::Doctor => doctor::doctor().await,
+        Commands::Listen => listen::listen(cfg),
+        Commands::Discover {
+            targets,
+            interactive,
+            filter,
+            passive_warmup,
+            baseline,
+            detect_ad,
+            arpwatch,
+            plan: show_plan,
+            export,
+        } => {
+            let mut cfg: ZondConfig = ZondConfig {
+                passive_warmup: passive_warmup.map(Duration::from_secs),
+                baseline: *baseline,
+                detect_ad: *detect_ad,
+                ..cfg.clone()
+            };
+            let mut targets: Vec<String> = targets.clone();
+            if *interactive {
+                let plan: DiscoverPlan = wizard::discover_plan(cfg.technique)?;
+                let args: Vec<String> = std::env::args().collect();
+                info!("Equivalent command: {}", plan.command_line(&args));
+                cfg.technique = plan.technique;
+                targets = vec![plan.target];
+            }
+            let query: Option<&Query> = commands.where_clause.as_ref();
+            discover::discover(
+                &targets,
+                filter,
+                query,
+                arpwatch.as_deref(),
+                *show_plan,
+                export,
+                commands.lock_policy(),
+                &cfg,
+            )
+            .await
+        }
+        Commands::Scan {
+            targets,
+            filter,
+            export,
+        } => {
+            let query: Option<&Query> = commands.where_clause.as_ref();
+            scan::scan(
+                targets,
+                filter,
+                query,
+                commands.ports.clone(),
+                export,
+                commands.lock_policy(),
+                cfg,
+            )
+            .await
+        }
+        Commands::History { device, report } => timeline::history(*device, report),
+        Commands::Fixture { report, pcap, out } => fixture::fixture(report, pcap, out),
+        Commands::Host { target } => {
+            host::host(target, commands.ports.clone(), commands.lock_policy(), cfg).await
+        }
+        Commands::Mdns { duration } => mdns::mdns(Duration::from_secs(*duration), cfg).await,
+        Commands::Protocols { target, probe } => protocols::protocols(*target, probe, cfg).await,
+        Commands::Schema => schema::schema(),
+        Commands::Completions { shell } => completions::completions(*shell),
+        Commands::Man => completions::man(),
+        Commands::Report {
+            report,
+            format,
+            baseline,
+        } => commands::report::report(report, *format, baseline.as_deref(), cfg),
+        Commands::View { report } => view::view(report, commands.where_clause.as_ref(), cfg),
+        Commands::Serve {
+            listen,
+            token,
+            tls_cert,
+            tls_key,
+            sleepy_after,
+            expire_after,
+        } => {
+            let opts: serve::ServeOptions = serve::ServeOptions {
+                listen: *listen,
+                token: token.clone(),
+                tls: tls_cert.clone().zip(tls_key.clone()),
+                sleepy_after: *sleepy_after,
+                expire_after: *expire_after,
+            };
+            serve::serve(opts, cfg).await
+        }
+        Commands::Agent {
+            targets,
+            collector,
+            name,
+            token,
+            tls_ca,
+            every,
+            arpwatch,
+        } => {
+            let opts: agent::AgentOptions = agent::AgentOptions {
+                collector: collector.clone(),
+                name: name.clone(),
+                token: token.clone(),
+                tls_ca: tls_ca.clone(),
+                every: every.map(Duration::from_secs),
+                arpwatch: arpwatch.clone(),
+            };
+            agent::agent(targets, &opts, cfg).await
+        }
+        Commands::Export { target } => match target {
+            ExportTarget::Netbox {
+                report,
+                url,
+                token,
+                site,
+                device_role,
+                device_type,
+            } => {
+                let opts: export::NetboxOptions = export::NetboxOptions {
+                    url: url.clone(),
+                    token: token.clone(),
+                    devices: site.clone().map(|site| export::DeviceDefaults {
+                        site,
+                        role: device_role.clone().unwrap_or_default(),
+                        device_type: device_type.clone().unwrap_or_default(),
+                    }),
+                };
+                export::netbox(report, &opts).await
+            }
+        },
+        Commands::Import { source } => match source {
+            ImportSource::KnownHosts { file, no_resolve } => {
+                import::known_hosts(file.as_deref(), !*no_resolve).await
+            }
+        },
+        Commands::Schedule { action } => match action {
+            ScheduleAction::Add {
+                cron,
+                keep,
+                command,
+            } => schedule::add(cron, command, *keep as usize),
+            ScheduleAction::List => schedule::list(),
+            ScheduleAction::Remove { id } => schedule::remove(*id),
+            ScheduleAction::Run => schedule::run().await,
+        },
+        Commands::Stats { action } => match action {
+            StatsAction::Preview { epsilon } => stats::preview(*epsilon),
+            StatsAction::Share { endpoint, epsilon } => stats::share(endpoint, *epsilon, cfg).await,
+        },
+        #[cfg(debug_assertions)]
+        Commands::Respond {
+            addresses,
+            interface,
+            delay,
+            drop,
+            copies,
+            no_icmp,
+        } => {
+            let opts: commands::respond::RespondOptions = commands::respond::RespondOptions {
+                delay: Duration::from_millis(*delay),
+                drop_rate: f64::from(*drop) / 100.0,
+                copies: usize::from(*copies),
+                icmp: !*no_icmp,
+            };
+            commands::respond::respond(addresses, interface, opts).await
+        }
+        Commands::UpdateOui { url, sha256 } => update_oui::update_oui(url, sha256.as_deref()).await,
+    }
 }
@@ -0,0 +1,569 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Result Queries
+//!
+//! A small expression language for `--where`, used to narrow scan results before
+//! they are printed.
+//!
+//! ```text
+//! rtt<10ms && vendor~"Apple"
+//! ports contains 22 || role=gateway
+//! !(hostname~printer) && hops>=2
+//! ```
+//!
+//! ## Fields
+//!
+//! | Field                             | Type     | Operators                    |
+//! |-----------------------------------|----------|------------------------------|
+//! | `ip`, `hostname`, `vendor`, `mac` | text     | `=` `!=` `~` `!~`            |
+//! | `role`                            | text set | `=` `!=` `~` `!~` `contains` |
+//! | `ports`                           | port set | `=` `!=` `contains`          |
+//! | `rtt`                             | duration | `=` `!=` `<` `<=` `>` `>=`   |
+//! | `ttl`, `hops`                     | number   | `=` `!=` `<` `<=` `>` `>=`   |
+//!
+//! Text comparisons ignore case and `~` matches substrings. `rtt` compares the
+//! fastest recorded round trip and accepts `us`, `ms` (default) and `s` units.
+//! `ports` only considers open ports. Comparisons against a value the host does not
+//! have (e.g. `hostname~nas` on a host without a hostname) are false, except for
+//! the negated operators.
+//!
+//! Conditions combine with `&&`, `||`, `!` and parentheses; `&&` binds tighter than `||`.
+//! An expression holds at most 256 conditions, nested at most 32 levels deep.
+
+use std::{str::FromStr, time::Duration};
+
+use zond_common::models::{host::Host, port::PortState};
+
+/// How deeply `!` and parentheses may nest, which bounds the recursion of the
+/// parser.
+const MAX_DEPTH: usize = 32;
+/// How many conditions one expression may combine, which bounds the depth of
+/// the expression tree.
+const MAX_CONDITIONS: usize = 256;
+
+/// A parsed `--where` expression.
+#[derive(Debug, Clone)]
+pub struct Query {
+    root: Expr,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<Token> = tokenize(input)?;
+        let mut parser: Parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+            conditions: 0,
+        };
+        let root: Expr = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(Self { root }),
+            Some(token) => Err(format!("unexpected {token} after end of expression")),
+        }
+    }
+}
+
+impl Query {
+    /// Returns `true` if the host satisfies the expression.
+    pub fn matches(&self, host: &Host) -> bool {
+        self.root.eval(host)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Condition),
+}
+
+impl Expr {
+    fn eval(&self, host: &Host) -> bool {
+        match self {
+            Self::And(lhs, rhs) => lhs.eval(host) && rhs.eval(host),
+            Self::Or(lhs, rhs) => lhs.eval(host) || rhs.eval(host),
+            Self::Not(inner) => !inner.eval(host),
+            Self::Cmp(condition) => condition.eval(host),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Like,
+    NotLike,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Op {
+    fn compare<T: PartialOrd>(self, lhs: T, rhs: T) -> bool {
+        match self {
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Like | Self::NotLike | Self::Contains => false,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Like => "~",
+            Self::NotLike => "!~",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Contains => "contains",
+        }
+    }
+
+    fn is_negated(self) -> bool {
+        matches!(self, Self::Ne | Self::NotLike)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Text(TextField, Op, String),
+    Roles(Op, String),
+    Port(Op, u16),
+    Rtt(Op, Duration),
+    Number(NumberField, Op, u16),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TextField {
+    Ip,
+    Hostname,
+    Vendor,
+    Mac,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NumberField {
+    Ttl,
+    Hops,
+}
+
+impl Condition {
+    fn parse(field: &str, op: Op, value: &str) -> Result<Self, String> {
+        let ensure_ops = |allowed: &[Op]| {
+            if allowed.contains(&op) {
+                Ok(())
+            } else {
+                Err(format!("'{}' is not supported for '{field}'", op.symbol()))
+            }
+        };
+        const TEXT_OPS: [Op; 4] = [Op::Eq, Op::Ne, Op::Like, Op::NotLike];
+        const ORDER_OPS: [Op; 6] = [Op::Eq, Op::Ne, Op::Lt, Op::Le, Op::Gt, Op::Ge];
+
+        match field.to_ascii_lowercase().as_str() {
+            name @ ("ip" | "hostname" | "vendor" | "mac") => {
+                ensure_ops(&TEXT_OPS)?;
+                let field: TextField = match name {
+                    "ip" => TextField::Ip,
+                    "hostname" => TextField::Hostname,
+                    "vendor" => TextField::Vendor,
+                    _ => TextField::Mac,
+                };
+                Ok(Self::Text(field, op, value.to_lowercase()))
+            }
+            "role" => {
+                ensure_ops(&[Op::Eq, Op::Ne, Op::Like, Op::NotLike, Op::Contains])?;
+                Ok(Self::Roles(op, value.to_lowercase()))
+            }
+            "ports" => {
+                ensure_ops(&[Op::Eq, Op::Ne, Op::Contains])?;
+                let port: u16 = value
+                    .parse()
+                    .map_err(|_| format!("'{value}' is not a port number"))?;
+                Ok(Self::Port(op, port))
+            }
+            "rtt" => {
+                ensure_ops(&ORDER_OPS)?;
+                Ok(Self::Rtt(op, parse_duration(value)?))
+            }
+            name @ ("ttl" | "hops") => {
+                ensure_ops(&ORDER_OPS)?;
+                let number: u16 = value
+                    .parse()
+                    .map_err(|_| format!("'{value}' is not a number"))?;
+                let field: NumberField = match name {
+                    "ttl" => NumberField::Ttl,
+                    _ => NumberField::Hops,
+                };
+                Ok(Self::Number(field, op, number))
+            }
+            other => Err(format!(
+                "unknown field '{other}' (ip, hostname, vendor, mac, role, ports, rtt, ttl, hops)"
+            )),
+        }
+    }
+
+    fn eval(&self, host: &Host) -> bool {
+        match self {
+            Self::Text(field, op, value) => {
+                let candidates: Vec<String> = match field {
+                    TextField::Ip => host.ips.iter().map(|ip| ip.to_string()).collect(),
+                    TextField::Hostname => host.hostname.iter().cloned().collect(),
+                    TextField::Vendor => host.vendor.iter().cloned().collect(),
+                    TextField::Mac => host.mac.iter().map(|mac| mac.to_string()).collect(),
+                };
+                match_text(&candidates, *op, value)
+            }
+            Self::Roles(op, value) => {
+                let roles: Vec<String> = host
+                    .network_roles
+                    .iter()
+                    .map(|role| role.as_str().to_string())
+                    .collect();
+                let op: Op = if *op == Op::Contains { Op::Eq } else { *op };
+                match_text(&roles, op, value)
+            }
+            Self::Port(op, number) => {
+                let open: bool = host
+                    .ports()
+                    .iter()
+                    .any(|port| port.number == *number && port.state == PortState::Open);
+                if *op == Op::Ne { !open } else { open }
+            }
+            Self::Rtt(op, rtt) => host.min_rtt().is_some_and(|min| op.compare(min, *rtt)),
+            Self::Number(field, op, number) => {
+                let actual: Option<u8> = match field {
+                    NumberField::Ttl => host.ttl,
+                    NumberField::Hops => host.hop_distance(),
+                };
+                actual.is_some_and(|actual| op.compare(actual as u16, *number))
+            }
+        }
+    }
+}
+
+/// Matches any of `candidates` for positive operators and none of them for
+/// negated ones.
+fn match_text(candidates: &[String], op: Op, value: &str) -> bool {
+    let hit = |candidate: &String| {
+        let candidate: String = candidate.to_lowercase();
+        match op {
+            Op::Like | Op::NotLike => candidate.contains(value),
+            _ => candidate == value,
+        }
+    };
+
+    if op.is_negated() {
+        !candidates.iter().any(hit)
+    } else {
+        candidates.iter().any(hit)
+    }
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split: usize = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .ok()
+        .filter(|n: &f64| n.is_finite() && *n >= 0.0)
+        .ok_or_else(|| format!("'{value}' is not a duration"))?;
+
+    let secs: f64 = match unit {
+        "us" | "µs" => number / 1_000_000.0,
+        "" | "ms" => number / 1_000.0,
+        "s" => number,
+        other => return Err(format!("unknown duration unit '{other}' (us, ms, s)")),
+    };
+    Ok(Duration::from_secs_f64(secs))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    Word(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LParen => write!(f, "'('"),
+            Self::RParen => write!(f, "')'"),
+            Self::And => write!(f, "'&&'"),
+            Self::Or => write!(f, "'||'"),
+            Self::Not => write!(f, "'!'"),
+            Self::Op(op) => write!(f, "'{}'", op.symbol()),
+            Self::Word(word) => write!(f, "'{word}'"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        let next: Option<char> = chars.peek().map(|(_, c)| *c);
+        let (token, skip): (Token, bool) = match (c, next) {
+            (c, _) if c.is_whitespace() => continue,
+            ('(', _) => (Token::LParen, false),
+            (')', _) => (Token::RParen, false),
+            ('&', Some('&')) => (Token::And, true),
+            ('|', Some('|')) => (Token::Or, true),
+            ('!', Some('=')) => (Token::Op(Op::Ne), true),
+            ('!', Some('~')) => (Token::Op(Op::NotLike), true),
+            ('!', _) => (Token::Not, false),
+            ('=', Some('=')) => (Token::Op(Op::Eq), true),
+            ('=', _) => (Token::Op(Op::Eq), false),
+            ('~', _) => (Token::Op(Op::Like), false),
+            ('<', Some('=')) => (Token::Op(Op::Le), true),
+            ('<', _) => (Token::Op(Op::Lt), false),
+            ('>', Some('=')) => (Token::Op(Op::Ge), true),
+            ('>', _) => (Token::Op(Op::Gt), false),
+            ('"', _) => {
+                let mut word: String = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => word.push(escaped),
+                            None => return Err("unterminated string".to_string()),
+                        },
+                        Some((_, c)) => word.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                (Token::Word(word), false)
+            }
+            _ if is_word_char(c) => {
+                let mut end: usize = idx + c.len_utf8();
+                while let Some(&(next_idx, next)) = chars.peek() {
+                    if !is_word_char(next) {
+                        break;
+                    }
+                    end = next_idx + next.len_utf8();
+                    chars.next();
+                }
+                let word: &str = &input[idx..end];
+                if word.eq_ignore_ascii_case("contains") {
+                    (Token::Op(Op::Contains), false)
+                } else {
+                    (Token::Word(word.to_string()), false)
+                }
+            }
+            _ => return Err(format!("unexpected '{c}' at position {idx}")),
+        };
+
+        if skip {
+            chars.next();
+        }
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"()&|!=~<>\"".contains(c)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+    conditions: usize,
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr: Expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr: Expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.nested(Self::unary)?)));
+        }
+
+        if self.eat(&Token::LParen) {
+            let expr: Expr = self.nested(Self::or)?;
+            if !self.eat(&Token::RParen) {
+                return Err("missing ')'".to_string());
+            }
+            return Ok(expr);
+        }
+
+        self.conditions += 1;
+        if self.conditions > MAX_CONDITIONS {
+            return Err(format!("more than {MAX_CONDITIONS} conditions"));
+        }
+        let field: String = self.word("field name")?;
+        let op: Op = match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => *op,
+            Some(token) => return Err(format!("expected operator after '{field}', got {token}")),
+            None => return Err(format!("expected operator after '{field}'")),
+        };
+        self.pos += 1;
+        let value: String = self.word("value")?;
+
+        Ok(Expr::Cmp(Condition::parse(&field, op, &value)?))
+    }
+
+    /// Runs `parse` one nesting level deeper.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Expr, String>) -> Result<Expr, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("nested deeper than {MAX_DEPTH} levels"));
+        }
+        self.depth += 1;
+        let expr: Result<Expr, String> = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn word(&mut self, what: &str) -> Result<String, String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                Ok(word.clone())
+            }
+            Some(token) => Err(format!("expected {what}, got {token}")),
+            None => Err(format!("expected {what}, got end of expression")),
+        }
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.tokens.get(self.pos) == Some(expected) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zond_common::models::port::{Port, Protocol};
+
+    fn host() -> Host {
+        let mut host: Host =
+            Host::new("192.168.1.20".parse().unwrap()).with_rtt(Duration::from_millis(4));
+        host.hostname = Some("NAS.local".to_string());
+        host.vendor = Some("Synology".to_string());
+        host.ttl = Some(64);
+        host.add_port(Port::new(22, Protocol::Tcp, PortState::Open));
+        host.add_port(Port::new(80, Protocol::Tcp, PortState::Closed));
+        host
+    }
+
+    fn matches(query: &str) -> bool {
+        query.parse::<Query>().unwrap().matches(&host())
+    }
+
+    #[test]
+    fn compares_fields() {
+        assert!(matches("hostname~nas"));
+        assert!(matches("vendor=\"synology\""));
+        assert!(matches("ip!=10.0.0.1"));
+        assert!(matches("rtt<5ms && rtt>=4000us"));
+        assert!(matches("ttl=64"));
+        assert!(matches("ports contains 22"));
+        assert!(!matches("ports contains 80"));
+        // Missing values only satisfy negated operators.
+        assert!(!matches("mac~00"));
+        assert!(matches("mac!~00"));
+        assert!(!matches("hops>0"));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(matches("ttl=1 && ttl=2 || ttl=64"));
+        assert!(matches("ttl=64 || ttl=1 && ttl=2"));
+        assert!(!matches("(ttl=64 || ttl=1) && ttl=2"));
+        assert!(!matches("!ttl=64 || ttl=1"));
+        assert!(matches("!(ttl=1 && ttl=64)"));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        let error = |query: &str| query.parse::<Query>().unwrap_err();
+
+        assert!(error("speed>1").starts_with("unknown field 'speed'"));
+        assert_eq!(error("hostname<nas"), "'<' is not supported for 'hostname'");
+        assert_eq!(error("ports=http"), "'http' is not a port number");
+        assert!(error("rtt<5m").starts_with("unknown duration unit 'm'"));
+        assert_eq!(error("(ttl=64"), "missing ')'");
+        assert_eq!(error("ttl=64)"), "unexpected ')' after end of expression");
+        assert_eq!(error("ttl 64"), "expected operator after 'ttl', got '64'");
+        assert_eq!(error("ttl="), "expected value, got end of expression");
+        assert_eq!(error("vendor=\"Apple"), "unterminated string");
+        assert_eq!(error("ttl=64 & hops=1"), "unexpected '&' at position 7");
+    }
+
+    #[test]
+    fn bounds_nesting_and_size() {
+        let nested = |depth: usize| {
+            format!("{}ttl=64{}", "(".repeat(depth), ")".repeat(depth)).parse::<Query>()
+        };
+        assert!(nested(MAX_DEPTH).unwrap().matches(&host()));
+        assert_eq!(
+            nested(MAX_DEPTH + 1).unwrap_err(),
+            format!("nested deeper than {MAX_DEPTH} levels")
+        );
+        assert!(
+            format!("{}ttl=64", "!".repeat(100_000))
+                .parse::<Query>()
+                .is_err()
+        );
+
+        let chain = |conditions: usize| vec!["ttl=64"; conditions].join(" && ").parse::<Query>();
+        assert!(chain(MAX_CONDITIONS).unwrap().matches(&host()));
+        assert_eq!(
+            chain(MAX_CONDITIONS + 1).unwrap_err(),
+            format!("more than {MAX_CONDITIONS} conditions")
+        );
+    }
+}
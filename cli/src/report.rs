@@ -0,0 +1,461 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Machine-Readable Reports
+//!
+//! Renders scan results as JSON documents conforming to the schema published by
+//! `zond schema` ([`zond_common::schema`]). Every document carries the
+//! `schema_version` it was written against.
+//!
+//! Reports of a run carry its [`ScanInfo`] under `scan`, so they can be matched
+//! with the logs, pcap files and `@last` of the same run.
+//!
+//! Reports can also be read back with [`from_json`], e.g. when agents upload their
+//! results to a collector, and rendered for humans with [`html`].
+
+pub mod html;
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, ensure};
+use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zond_common::config::ZondConfig;
+use zond_common::models::host::{Host, Unreachable};
+use zond_common::models::scan::{self, ScanInfo};
+use zond_common::models::topology::{Presence, PresenceState, Topology};
+use zond_common::schema::SCHEMA_VERSION;
+use zond_common::utils::{demo::Demo, redact};
+
+use crate::terminal::format;
+
+/// Stands in for the value of every `--token` in the recorded command line.
+const TOKEN_PLACEHOLDER: &str = "<token>";
+
+/// A report read back from JSON.
+pub struct Report {
+    /// Name of the agent that produced the report, if it came from one.
+    pub agent: Option<String>,
+    /// The run that produced the report, if it was stamped.
+    pub scan: Option<ScanInfo>,
+    pub hosts: Vec<Host>,
+    /// Targets a router reported unreachable instead of answering.
+    pub unreachable: Vec<Unreachable>,
+}
+
+/// The top-level report object. Optional fields are left out when unset.
+#[derive(Serialize)]
+struct Document<'a, H: Serialize> {
+    schema_version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agents: Option<&'a BTreeSet<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scan: Option<&'a ScanInfo>,
+    hosts: H,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    unreachable: &'a [Unreachable],
+}
+
+/// A host of a merged topology, tagged with the agents that saw it and how
+/// reliably they did.
+#[derive(Serialize)]
+struct TopologyHost<'a> {
+    #[serde(flatten)]
+    host: &'a Host,
+    seen_by: Vec<&'a str>,
+    state: PresenceState,
+    sleepy: bool,
+    presence: Presence,
+}
+
+/// A report as received, before its version is checked.
+#[derive(Deserialize)]
+struct IncomingReport {
+    #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
+    scan: Option<ScanInfo>,
+    #[serde(default)]
+    hosts: Vec<Host>,
+    #[serde(default)]
+    unreachable: Vec<Unreachable>,
+}
+
+/// Stamps this process with a new scan ID and the invocation it serves.
+///
+/// The value of `--token` is never recorded, see [`hide_tokens`]. Under
+/// `--redact` and `--demo` the user, hostname and interfaces are left out, as
+/// they identify the scanning machine, and the command line is masked or faked
+/// like the host data.
+pub fn stamp(cfg: &ZondConfig) -> &'static ScanInfo {
+    let argv: Vec<String> = hide_tokens(std::env::args());
+    let mut info: ScanInfo = ScanInfo {
+        id: scan::new_id(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        argv: match cfg.redact || cfg.demo {
+            true => hide_argv(&argv, cfg.demo),
+            false => argv,
+        },
+        user: None,
+        hostname: None,
+        interfaces: Vec::new(),
+    };
+
+    if !cfg.redact && !cfg.demo {
+        info.user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok();
+        info.hostname = sys_info::hostname().ok();
+        info.interfaces = pnet::datalink::interfaces()
+            .into_iter()
+            .filter(|iface| iface.is_up() && !iface.is_loopback())
+            .flat_map(|iface| {
+                let name: String = iface.name;
+                iface
+                    .ips
+                    .into_iter()
+                    .map(move |net| format!("{name} {net}"))
+            })
+            .collect();
+    }
+
+    ScanInfo::install(info)
+}
+
+/// The command line `argv` with the value of every `--token`, given as
+/// `--token VALUE` or `--token=VALUE`, replaced by [`TOKEN_PLACEHOLDER`].
+///
+/// Reports, timing files and the results served to others all carry the
+/// command line, and the token grants access to a collector.
+fn hide_tokens(argv: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut hidden: Vec<String> = Vec::new();
+    let mut value_follows: bool = false;
+    let mut positional: bool = false;
+    for arg in argv {
+        if value_follows {
+            value_follows = false;
+            hidden.push(TOKEN_PLACEHOLDER.to_string());
+            continue;
+        }
+        match arg.as_str() {
+            _ if positional => {}
+            "--" => positional = true,
+            "--token" => value_follows = true,
+            _ if arg.starts_with("--token=") => {
+                hidden.push(format!("--token={TOKEN_PLACEHOLDER}"));
+                continue;
+            }
+            _ => {}
+        }
+        hidden.push(arg);
+    }
+    hidden
+}
+
+/// The command line `argv` without the path of the binary, which may name
+/// the user, and with its addresses and hostnames hidden by [`hide_arg`].
+fn hide_argv(argv: &[String], demo: bool) -> Vec<String> {
+    let program: Option<String> = argv.first().map(|program| {
+        Path::new(program).file_name().map_or_else(
+            || program.clone(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    });
+    program
+        .into_iter()
+        .chain(argv.iter().skip(1).map(|arg| hide_arg(arg, demo)))
+        .collect()
+}
+
+/// Hides the addresses and hostnames in one argument, such as a target list
+/// or the value of `--flag=value`.
+///
+/// With `demo` they are replaced with the fakes the hosts get. Otherwise they
+/// are masked as `--redact` masks host data: MAC and IPv6 addresses keep their
+/// vendor and prefix, hostnames their first and last characters, and IPv4
+/// addresses are kept.
+fn hide_arg(arg: &str, demo: bool) -> String {
+    let mut out: String = String::with_capacity(arg.len());
+    for piece in arg.split_inclusive([',', '=', '/']) {
+        let (token, separator): (&str, &str) = match piece.strip_suffix([',', '=', '/']) {
+            Some(token) => (token, &piece[token.len()..]),
+            None => (piece, ""),
+        };
+        out.push_str(&hide_token(token, demo));
+        out.push_str(separator);
+    }
+    out
+}
+
+fn hide_token(token: &str, demo: bool) -> String {
+    if let Ok(ip) = token.parse::<IpAddr>() {
+        return match (demo, ip) {
+            (true, _) => Demo::session().ip(ip).to_string(),
+            (false, IpAddr::V4(v4)) => v4.to_string(),
+            (false, IpAddr::V6(v6)) => format::ipv6_to_string(&v6, true),
+        };
+    }
+    if token.len() == 17
+        && let Ok(mac) = token.parse::<MacAddr>()
+    {
+        return match demo {
+            true => Demo::session().mac(mac).to_string(),
+            false => redact::mac_addr(&mac),
+        };
+    }
+    // A range such as 10.0.0.1-10.0.0.9 or 10.0.0.1-9.
+    if let Some((start, end)) = token.split_once('-')
+        && start.parse::<IpAddr>().is_ok()
+    {
+        return format!("{}-{}", hide_token(start, demo), hide_token(end, demo));
+    }
+    if is_hostname(token) {
+        return match demo {
+            true => Demo::session().hostname(token),
+            false => redact::hostname(token),
+        };
+    }
+    token.to_string()
+}
+
+/// A dotted name with at least one letter, e.g. `printer.lan`.
+fn is_hostname(token: &str) -> bool {
+    token.contains('.')
+        && !token.starts_with(['-', '.'])
+        && token.chars().any(|c| c.is_ascii_alphabetic())
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+}
+
+/// Renders a full scan report (`{"schema_version": .., "hosts": [..]}`), stamped
+/// with the [`ScanInfo`] of this run. Targets reported `unreachable` are listed
+/// apart from the hosts.
+pub fn to_json(hosts: &[Host], unreachable: &[Unreachable]) -> String {
+    render(&Document {
+        schema_version: SCHEMA_VERSION,
+        agent: None,
+        agents: None,
+        scan: ScanInfo::session(),
+        hosts,
+        unreachable,
+    })
+}
+
+/// Writes the report of `hosts` and `unreachable`, as [`to_json`] renders it,
+/// to every file given with `--export`.
+///
+/// `hosts` are expected to be filtered and faked already, as shown. Under
+/// `--redact` their hostnames are masked as well; addresses stay as they are,
+/// since a report must hold valid ones.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be written.
+pub fn export(
+    hosts: &[Host],
+    unreachable: &[Unreachable],
+    paths: &[PathBuf],
+    cfg: &ZondConfig,
+) -> anyhow::Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let json: String = match cfg.redact {
+        true => to_json(
+            &hosts.iter().map(redact_names).collect::<Vec<Host>>(),
+            unreachable,
+        ),
+        false => to_json(hosts, unreachable),
+    };
+    for path in paths {
+        fs::write(path, &json).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// A copy of `host` with its hostname and mDNS name masked.
+fn redact_names(host: &Host) -> Host {
+    let mut host: Host = host.clone();
+    for name in [&mut host.hostname, &mut host.hints.mdns_name]
+        .into_iter()
+        .flatten()
+    {
+        *name = redact::hostname(name);
+    }
+    host
+}
+
+/// Renders a report read with [`from_json`] back to JSON, keeping its own
+/// agent and run.
+pub fn report_to_json(report: &Report) -> String {
+    render(&Document {
+        schema_version: SCHEMA_VERSION,
+        agent: report.agent.as_deref(),
+        agents: None,
+        scan: report.scan.as_ref(),
+        hosts: &report.hosts,
+        unreachable: &report.unreachable,
+    })
+}
+
+/// Renders a scan report tagged with the name of the agent that produced it.
+pub fn agent_report_to_json(agent: &str, hosts: &[Host]) -> String {
+    render(&Document {
+        schema_version: SCHEMA_VERSION,
+        agent: Some(agent),
+        agents: None,
+        scan: ScanInfo::session(),
+        hosts,
+        unreachable: &[],
+    })
+}
+
+/// Renders a merged topology. Each host lists the agents that saw it in `seen_by`,
+/// whether it is `up`, `asleep` or `down` in `state`, and its [`Presence`]
+/// counters. The document lists every reporting agent in `agents`.
+pub fn topology_to_json(topology: &Topology) -> String {
+    let hosts: Vec<TopologyHost<'_>> = topology
+        .hosts()
+        .iter()
+        .enumerate()
+        .map(|(idx, host)| TopologyHost {
+            host,
+            seen_by: topology
+                .seen_by(idx)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+            state: topology.state(idx).unwrap_or(PresenceState::Up),
+            sleepy: topology.is_sleepy(idx),
+            presence: topology.presence(idx).copied().unwrap_or_default(),
+        })
+        .collect();
+
+    render(&Document {
+        schema_version: SCHEMA_VERSION,
+        agent: None,
+        agents: Some(topology.agents()),
+        scan: None,
+        hosts,
+        unreachable: &[],
+    })
+}
+
+/// Parses a report produced by [`to_json`] or [`agent_report_to_json`].
+///
+/// # Errors
+///
+/// Returns an error if the document is not valid JSON, was written against a
+/// different schema version or contains malformed host records.
+pub fn from_json(input: &str) -> anyhow::Result<Report> {
+    let root: Value = serde_json::from_str(input).context("invalid JSON")?;
+
+    let version: &str = root
+        .get("schema_version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("report has no schema_version"))?;
+    ensure!(
+        version == SCHEMA_VERSION,
+        "unsupported schema version {version} (expected {SCHEMA_VERSION})"
+    );
+
+    let report: IncomingReport = serde_json::from_value(root).context("malformed host records")?;
+    let hosts: Vec<Host> = report
+        .hosts
+        .into_iter()
+        .map(|mut host| {
+            host.ips.insert(host.primary_ip);
+            host
+        })
+        .collect();
+
+    Ok(Report {
+        agent: report.agent,
+        scan: report.scan,
+        hosts,
+        unreachable: report.unreachable,
+    })
+}
+
+fn render<T: Serialize>(document: &T) -> String {
+    serde_json::to_string(document).expect("reports only contain serializable values")
+}
+
+/// Quotes and escapes a string as a JSON string literal.
+pub fn string(value: &str) -> String {
+    Value::from(value).to_string()
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zond_common::models::host::UnreachableReason;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn tokens_never_reach_the_recorded_command_line() {
+        assert_eq!(
+            hide_tokens(args(
+                "zond agent lan --token s3cret --collector https://c:8443"
+            )),
+            args("zond agent lan --token <token> --collector https://c:8443")
+        );
+        assert_eq!(
+            hide_tokens(args("zond serve --token=s3cret -q")),
+            args("zond serve --token=<token> -q")
+        );
+        assert_eq!(
+            hide_tokens(args("zond export netbox --url https://nb --token")),
+            args("zond export netbox --url https://nb --token")
+        );
+        assert_eq!(
+            hide_tokens(args("zond d -- --token x")),
+            args("zond d -- --token x")
+        );
+        assert_eq!(hide_tokens(args("zond d lan")), args("zond d lan"));
+    }
+
+    #[test]
+    fn unreachable_targets_are_kept_apart_from_hosts() {
+        let host: Host = Host::new("10.0.0.1".parse().unwrap());
+        let filtered: Unreachable = Unreachable {
+            target: "10.0.0.2".parse().unwrap(),
+            reporter: "192.0.2.1".parse().unwrap(),
+            reason: UnreachableReason::AdminProhibited,
+        };
+
+        let json: String = to_json(std::slice::from_ref(&host), &[filtered]);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["hosts"].as_array().unwrap().len(), 1);
+        assert_eq!(value["unreachable"][0]["reason"], "admin_prohibited");
+
+        let report: Report = from_json(&json).unwrap();
+        assert_eq!(report.unreachable, vec![filtered]);
+        assert!(!to_json(&[host], &[]).contains("unreachable"));
+    }
+}
@@ -0,0 +1,568 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # HTML Reports
+//!
+//! Renders a scan report as a single self-contained HTML page for readers who do
+//! not live in a terminal: summary figures, vendor and latency charts, changes
+//! against a baseline report, insights and the full host table.
+//!
+//! Styles are inlined and charts are plain CSS bars, so the page needs no scripts
+//! or network access and can be mailed around as is. Every value that came off
+//! the wire is escaped, and `--redact` / `--raw-hostnames` apply like they do in
+//! the terminal.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    net::IpAddr,
+    time::Duration,
+};
+
+use pnet::util::MacAddr;
+use zond_common::{
+    models::{
+        fingerprint::{DNS_RECURSIVE_RESOLVER, MQTT_ANONYMOUS},
+        host::{Host, NetworkRole, Unreachable},
+        port::{PortState, Protocol},
+        scan::ScanInfo,
+    },
+    utils::{domain, idn, ip, redact},
+};
+
+use crate::terminal::{histogram, sanitize};
+
+/// Vendors beyond this many are folded into "Other" in the vendor chart.
+const MAX_VENDOR_BARS: usize = 10;
+
+/// Minimum RTT above which a host is called out as slow to answer.
+const SLOW_RTT: Duration = Duration::from_millis(500);
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;padding:0 1rem;color:#1d2330;background:#f7f8fa}\
+h1{margin-bottom:.2rem}h2{margin-top:2.5rem;border-bottom:2px solid #d8dde6;padding-bottom:.3rem}\
+.meta{color:#667085;margin-top:0}\
+.cards{display:flex;flex-wrap:wrap;gap:1rem}\
+.card{background:#fff;border:1px solid #d8dde6;border-radius:.5rem;padding:.8rem 1.2rem;min-width:9rem}\
+.card b{display:block;font-size:1.6rem}\
+.charts{display:grid;grid-template-columns:repeat(auto-fit,minmax(20rem,1fr));gap:2rem}\
+.chart div{display:grid;grid-template-columns:9rem 1fr 3rem;gap:.5rem;align-items:center;margin:.25rem 0}\
+.bar{background:#e4e8ef;border-radius:.25rem;height:.9rem}\
+.bar span{display:block;height:100%;background:#3d6fd9;border-radius:.25rem}\
+table{border-collapse:collapse;width:100%;background:#fff;font-size:.9rem}\
+th,td{border:1px solid #d8dde6;padding:.35rem .6rem;text-align:left;vertical-align:top}\
+th{background:#eef1f6}\
+.added{color:#1a7f37}.removed{color:#b42318}.muted{color:#667085}";
+
+/// How a report page is rendered.
+pub struct HtmlOptions<'a> {
+    /// Where the report came from, shown below the title.
+    pub source: &'a str,
+    /// Hosts of an earlier report to list changes against.
+    pub baseline: Option<&'a [Host]>,
+    /// The run that produced the report, whose ID is shown below the title.
+    pub scan: Option<&'a ScanInfo>,
+    /// Targets a router reported unreachable, listed after the hosts.
+    pub unreachable: &'a [Unreachable],
+    /// Mask MAC addresses and hostnames like `--redact` does in the terminal.
+    pub redact: bool,
+    /// Show hostnames as received instead of decoding punycode.
+    pub raw_hostnames: bool,
+    /// Show hostnames in these DNS domains without them, like `--short-names`.
+    pub search_domains: &'a [String],
+}
+
+/// Renders `hosts` as a complete HTML document.
+pub fn render(hosts: &[Host], opts: &HtmlOptions) -> String {
+    let mut page: String = String::new();
+    let run: String = opts
+        .scan
+        .map(|scan| {
+            format!(
+                " &middot; scan {} (zond {})",
+                escape(&scan.id),
+                escape(&scan.version)
+            )
+        })
+        .unwrap_or_default();
+    let _ = write!(
+        page,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Zond scan report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Zond scan report</h1>\n<p class=\"meta\">{} &middot; {} hosts{}</p>\n",
+        escape(opts.source),
+        hosts.len(),
+        run
+    );
+
+    summary(&mut page, hosts);
+    charts(&mut page, hosts);
+    if let Some(baseline) = opts.baseline {
+        changes(&mut page, baseline, hosts, opts);
+    }
+    insights(&mut page, hosts);
+    host_table(&mut page, hosts, opts);
+    unreachable_table(&mut page, opts);
+
+    page.push_str("</body>\n</html>\n");
+    page
+}
+
+fn summary(page: &mut String, hosts: &[Host]) {
+    let open_ports: usize = hosts.iter().map(|host| open_ports(host).len()).sum();
+    let vendors: usize = hosts
+        .iter()
+        .filter_map(|host| host.vendor.as_deref())
+        .collect::<BTreeSet<&str>>()
+        .len();
+
+    let mut rtts: Vec<Duration> = hosts.iter().filter_map(Host::min_rtt).collect();
+    rtts.sort_unstable();
+    let median: String = rtts
+        .get(rtts.len() / 2)
+        .map_or_else(|| "n/a".to_string(), |rtt| format_ms(*rtt));
+
+    page.push_str("<div class=\"cards\">\n");
+    for (label, value) in [
+        ("Hosts", hosts.len().to_string()),
+        ("Open ports", open_ports.to_string()),
+        ("Vendors", vendors.to_string()),
+        ("Median RTT", median),
+    ] {
+        let _ = writeln!(page, "<div class=\"card\"><b>{value}</b>{label}</div>");
+    }
+    page.push_str("</div>\n");
+}
+
+fn charts(page: &mut String, hosts: &[Host]) {
+    let mut vendors: BTreeMap<&str, usize> = BTreeMap::new();
+    for host in hosts.iter().filter(|host| host.mac.is_some()) {
+        *vendors
+            .entry(host.vendor.as_deref().unwrap_or("Unknown"))
+            .or_default() += 1;
+    }
+    let mut vendors: Vec<(String, usize)> = vendors
+        .into_iter()
+        .map(|(vendor, count)| (vendor.to_string(), count))
+        .collect();
+    vendors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    if vendors.len() > MAX_VENDOR_BARS {
+        let other: usize = vendors
+            .drain(MAX_VENDOR_BARS - 1..)
+            .map(|(_, count)| count)
+            .sum();
+        vendors.push(("Other".to_string(), other));
+    }
+
+    let latency: Vec<(String, usize)> = histogram::BUCKETS
+        .iter()
+        .zip(histogram::bucket_counts(hosts))
+        .map(|((label, _), count)| (label.to_string(), count))
+        .collect();
+
+    page.push_str("<h2>Overview</h2>\n<div class=\"charts\">\n");
+    bar_chart(page, "Vendors", &vendors);
+    bar_chart(page, "Latency (fastest reply)", &latency);
+    page.push_str("</div>\n");
+}
+
+fn bar_chart(page: &mut String, title: &str, rows: &[(String, usize)]) {
+    let _ = writeln!(page, "<section class=\"chart\">\n<h3>{title}</h3>");
+
+    let max: usize = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    if max == 0 {
+        page.push_str("<p class=\"muted\">No data</p>\n</section>\n");
+        return;
+    }
+
+    for (label, count) in rows {
+        let width: f64 = *count as f64 * 100.0 / max as f64;
+        let _ = writeln!(
+            page,
+            "<div><span>{}</span><span class=\"bar\"><span style=\"width:{width:.1}%\"></span></span><span>{count}</span></div>",
+            escape(label)
+        );
+    }
+    page.push_str("</section>\n");
+}
+
+fn changes(page: &mut String, baseline: &[Host], hosts: &[Host], opts: &HtmlOptions) {
+    let added: Vec<&Host> = hosts
+        .iter()
+        .filter(|host| counterpart(baseline, host).is_none())
+        .collect();
+    let removed: Vec<&Host> = baseline
+        .iter()
+        .filter(|old| counterpart(hosts, old).is_none())
+        .collect();
+    let changed: Vec<(&Host, Vec<String>)> = hosts
+        .iter()
+        .filter_map(|host| {
+            let old: &Host = counterpart(baseline, host)?;
+            let diff: Vec<String> = describe_changes(old, host, opts);
+            (!diff.is_empty()).then_some((host, diff))
+        })
+        .collect();
+
+    page.push_str("<h2>Changes since baseline</h2>\n");
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        page.push_str("<p class=\"muted\">No changes.</p>\n");
+        return;
+    }
+
+    page.push_str("<ul>\n");
+    for host in added {
+        let _ = writeln!(
+            page,
+            "<li class=\"added\">New: {}</li>",
+            escape(&host_label(host, opts))
+        );
+    }
+    for host in removed {
+        let _ = writeln!(
+            page,
+            "<li class=\"removed\">Gone: {}</li>",
+            escape(&host_label(host, opts))
+        );
+    }
+    for (host, diff) in changed {
+        let _ = writeln!(
+            page,
+            "<li>Changed: {} &mdash; {}</li>",
+            escape(&host_label(host, opts)),
+            escape(&diff.join(", "))
+        );
+    }
+    page.push_str("</ul>\n");
+}
+
+/// Finds the record of `host` among `others`. A device that rotated its
+/// private MAC address is recognized by its mDNS identity, so it shows up as
+/// changed rather than as one device gone and another new.
+fn counterpart<'a>(others: &'a [Host], host: &Host) -> Option<&'a Host> {
+    others
+        .iter()
+        .find(|other| other.is_same_device(host))
+        .or_else(|| {
+            others
+                .iter()
+                .find(|other| other.is_same_randomized_device(host))
+        })
+}
+
+/// Lists what differs between two records of the same device.
+fn describe_changes(old: &Host, new: &Host, opts: &HtmlOptions) -> Vec<String> {
+    let mut diff: Vec<String> = Vec::new();
+
+    if let (Some(old_mac), Some(new_mac)) = (old.mac, new.mac)
+        && old_mac != new_mac
+    {
+        let mac = |mac: &MacAddr| {
+            if opts.redact {
+                redact::mac_addr(mac)
+            } else {
+                mac.to_string()
+            }
+        };
+        diff.push(format!("private MAC {} → {}", mac(&old_mac), mac(&new_mac)));
+    }
+
+    if old.primary_ip != new.primary_ip {
+        diff.push(format!("address {} → {}", old.primary_ip, new.primary_ip));
+    }
+    if old.hostname != new.hostname {
+        let name = |host: &Host| {
+            host.hostname
+                .as_deref()
+                .map_or_else(|| "none".to_string(), |name| hostname(name, opts))
+        };
+        diff.push(format!("hostname {} → {}", name(old), name(new)));
+    }
+
+    let old_ports: BTreeSet<(u16, &str)> = open_ports(old);
+    let new_ports: BTreeSet<(u16, &str)> = open_ports(new);
+    diff.extend(
+        new_ports
+            .difference(&old_ports)
+            .map(|(port, proto)| format!("opened {port}/{proto}")),
+    );
+    diff.extend(
+        old_ports
+            .difference(&new_ports)
+            .map(|(port, proto)| format!("closed {port}/{proto}")),
+    );
+
+    diff
+}
+
+fn insights(page: &mut String, hosts: &[Host]) {
+    let mut notes: Vec<String> = Vec::new();
+
+    for (role, name) in [
+        (NetworkRole::Gateway, "Gateway"),
+        (NetworkRole::DNS, "DNS server"),
+        (NetworkRole::DHCP, "DHCP server"),
+        (NetworkRole::Nat, "Likely NAT device"),
+        (NetworkRole::DomainController, "Domain controller"),
+        (NetworkRole::Iot, "IoT/OT device"),
+        (NetworkRole::Printer, "Printer"),
+        (NetworkRole::Camera, "Camera"),
+        (NetworkRole::Scanner, "This machine"),
+        (NetworkRole::ProxyArp, "Proxy ARP / ARP sponge"),
+    ] {
+        let ips: Vec<String> = hosts
+            .iter()
+            .filter(|host| host.network_roles.contains(&role))
+            .map(|host| host.primary_ip.to_string())
+            .collect();
+        if !ips.is_empty() {
+            notes.push(format!("{name}: {}", ips.join(", ")));
+        }
+    }
+
+    for (service, name) in [
+        (
+            MQTT_ANONYMOUS,
+            "MQTT broker(s) accepting clients without credentials",
+        ),
+        (
+            DNS_RECURSIVE_RESOLVER,
+            "DNS server(s) resolving external names for LAN clients; check that they do not \
+             answer from the Internet",
+        ),
+    ] {
+        let ips: Vec<String> = hosts
+            .iter()
+            .filter(|host| {
+                host.ports()
+                    .iter()
+                    .any(|port| port.service_info.as_deref() == Some(service))
+            })
+            .map(|host| host.primary_ip.to_string())
+            .collect();
+        if !ips.is_empty() {
+            notes.push(format!("{name}: {}", ips.join(", ")));
+        }
+    }
+
+    let slow: usize = hosts
+        .iter()
+        .filter(|host| host.min_rtt().is_some_and(|rtt| rtt >= SLOW_RTT))
+        .count();
+    if slow > 0 {
+        notes.push(format!(
+            "{slow} host(s) took over {}ms to answer, typical for mobile or power-saving IoT devices",
+            SLOW_RTT.as_millis()
+        ));
+    }
+
+    let randomized: usize = hosts
+        .iter()
+        .filter(|host| host.mac.is_some_and(|mac| mac.0 & 0x02 != 0))
+        .count();
+    if randomized > 0 {
+        notes.push(format!(
+            "{randomized} host(s) use a locally administered MAC address, likely randomized for privacy"
+        ));
+    }
+
+    let unnamed: usize = hosts.iter().filter(|host| host.hostname.is_none()).count();
+    if unnamed > 0 {
+        notes.push(format!(
+            "{unnamed} of {} hosts have no hostname",
+            hosts.len()
+        ));
+    }
+
+    page.push_str("<h2>Insights</h2>\n");
+    if notes.is_empty() {
+        page.push_str("<p class=\"muted\">Nothing stands out.</p>\n");
+        return;
+    }
+    page.push_str("<ul>\n");
+    for note in notes {
+        let _ = writeln!(page, "<li>{}</li>", escape(&note));
+    }
+    page.push_str("</ul>\n");
+}
+
+fn host_table(page: &mut String, hosts: &[Host], opts: &HtmlOptions) {
+    page.push_str(
+        "<h2>Hosts</h2>\n<table>\n<tr><th>IP</th><th>Hostname</th><th>MAC</th><th>Vendor</th>\
+         <th>Roles</th><th>RTT</th><th>Distance</th><th>Open ports</th></tr>\n",
+    );
+
+    let mut sorted: Vec<&Host> = hosts.iter().collect();
+    sorted.sort_by_key(|host| host.primary_ip);
+
+    for host in sorted {
+        let other_ips: Vec<String> = host
+            .ips
+            .iter()
+            .filter(|ip| **ip != host.primary_ip)
+            .map(|ip| ip_label(*ip, opts))
+            .collect();
+        let mut ip_cell: String = escape(&host.primary_ip.to_string());
+        if !other_ips.is_empty() {
+            let _ = write!(
+                ip_cell,
+                "<br><span class=\"muted\">{}</span>",
+                escape(&other_ips.join(", "))
+            );
+        }
+
+        let name: String = host
+            .hostname
+            .as_deref()
+            .map(|name| hostname(name, opts))
+            .unwrap_or_default();
+        let mac: String = match host.mac {
+            Some(mac) if opts.redact => redact::mac_addr(&mac),
+            Some(mac) => mac.to_string(),
+            None => String::new(),
+        };
+        let roles: Vec<&str> = [
+            (NetworkRole::Gateway, "gateway"),
+            (NetworkRole::DNS, "dns"),
+            (NetworkRole::DHCP, "dhcp"),
+            (NetworkRole::Nat, "nat"),
+            (NetworkRole::DomainController, "dc"),
+            (NetworkRole::Iot, "iot"),
+            (NetworkRole::Printer, "printer"),
+            (NetworkRole::Camera, "camera"),
+            (NetworkRole::Scanner, "self"),
+            (NetworkRole::ProxyArp, "proxy-arp"),
+        ]
+        .into_iter()
+        .filter(|(role, _)| host.network_roles.contains(role))
+        .map(|(_, name)| name)
+        .collect();
+        let rtt: String = rtt_label(host);
+        let distance: String = host
+            .hop_distance()
+            .map(|hops| format!("~{hops}"))
+            .unwrap_or_default();
+        let ports: Vec<String> = open_ports(host)
+            .into_iter()
+            .map(|(port, proto)| format!("{port}/{proto}"))
+            .collect();
+
+        let _ = writeln!(
+            page,
+            "<tr><td>{ip_cell}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&name),
+            escape(&mac),
+            escape(host.vendor.as_deref().unwrap_or_default()),
+            roles.join(", "),
+            escape(&rtt),
+            distance,
+            ports.join(", ")
+        );
+    }
+    page.push_str("</table>\n");
+}
+
+fn unreachable_table(page: &mut String, opts: &HtmlOptions) {
+    if opts.unreachable.is_empty() {
+        return;
+    }
+    page.push_str(
+        "<h2>Unreachable targets</h2>\n<p class=\"muted\">A router answered for these targets \
+         instead of them; they are not counted as hosts.</p>\n<table>\n\
+         <tr><th>Target</th><th>Reason</th><th>Reported by</th></tr>\n",
+    );
+    let mut sorted: Vec<&Unreachable> = opts.unreachable.iter().collect();
+    sorted.sort_by_key(|target| target.target);
+    for target in sorted {
+        let _ = writeln!(
+            page,
+            "<tr><td>{}</td><td>unreachable ({})</td><td>{}</td></tr>",
+            escape(&ip_label(target.target, opts)),
+            target.reason.label(),
+            escape(&ip_label(target.reporter, opts))
+        );
+    }
+    page.push_str("</table>\n");
+}
+
+/// Returns the open ports of `host` as `(number, protocol)` pairs.
+fn open_ports(host: &Host) -> BTreeSet<(u16, &'static str)> {
+    host.ports()
+        .iter()
+        .filter(|port| port.state == PortState::Open)
+        .map(|port| {
+            let proto: &str = match port.protocol {
+                Protocol::Tcp => "tcp",
+                Protocol::Udp => "udp",
+                Protocol::Sctp => "sctp",
+            };
+            (port.number, proto)
+        })
+        .collect()
+}
+
+fn host_label(host: &Host, opts: &HtmlOptions) -> String {
+    match &host.hostname {
+        Some(name) => format!("{} ({})", host.primary_ip, hostname(name, opts)),
+        None => host.primary_ip.to_string(),
+    }
+}
+
+fn hostname(name: &str, opts: &HtmlOptions) -> String {
+    let name: &str = domain::strip_search_domain(name, opts.search_domains);
+    let name: String = if opts.raw_hostnames {
+        name.to_string()
+    } else {
+        idn::to_unicode(name)
+    };
+    if opts.redact {
+        redact::hostname(&name)
+    } else {
+        name
+    }
+}
+
+fn ip_label(ip: IpAddr, opts: &HtmlOptions) -> String {
+    match ip {
+        IpAddr::V6(ipv6) if opts.redact => match ip::get_ipv6_type(&ipv6) {
+            ip::Ipv6AddressType::GlobalUnicast => redact::global_unicast(&ipv6),
+            ip::Ipv6AddressType::UniqueLocal => redact::unique_local(&ipv6),
+            ip::Ipv6AddressType::LinkLocal => redact::link_local(&ipv6),
+            _ => ipv6.to_string(),
+        },
+        ip => ip.to_string(),
+    }
+}
+
+fn rtt_label(host: &Host) -> String {
+    match (host.min_rtt(), host.max_rtt()) {
+        (Some(min), Some(max)) if min == max => format_ms(min),
+        (Some(min), Some(max)) => format!("{} - {}", format_ms(min), format_ms(max)),
+        _ => String::new(),
+    }
+}
+
+fn format_ms(rtt: Duration) -> String {
+    format!("{:.1}ms", rtt.as_secs_f64() * 1000.0)
+}
+
+/// Escapes `input` for HTML text and attribute values, after neutralizing control
+/// characters like the terminal output does.
+fn escape(input: &str) -> String {
+    let mut escaped: String = String::with_capacity(input.len());
+    for c in sanitize::escape(input).chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
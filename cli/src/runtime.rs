@@ -0,0 +1,41 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Thread Pools
+//!
+//! Builds the tokio runtime the commands run on. Zond keeps two pools of
+//! worker threads: tokio's for probing and I/O, and rayon's for splitting
+//! large target ranges across interfaces. Both default to one thread per
+//! core, so on a small device such as a router they compete for the same
+//! few cores. `--threads N` caps each pool at N threads.
+//!
+//! Threads that block on a socket, like the packet capture loops and
+//! hostname lookups, are not counted: they sleep while waiting and must not
+//! queue behind each other.
+
+use std::io;
+
+use tokio::runtime::{Builder, Runtime};
+
+/// Builds the runtime, with at most `threads` workers in it and in the
+/// global rayon pool, or one per core if `None`.
+///
+/// # Errors
+///
+/// Returns an error if the operating system refuses to start the threads.
+pub fn build(threads: Option<u16>) -> io::Result<Runtime> {
+    let mut builder: Builder = Builder::new_multi_thread();
+    builder.enable_all();
+    if let Some(threads) = threads {
+        let threads: usize = usize::from(threads);
+        builder.worker_threads(threads);
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(io::Error::other)?;
+    }
+    builder.build()
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Scheduled Scans
+//!
+//! Keeps the scans added with `zond schedule add` in `schedule.json` in the data
+//! directory ([`paths::data_dir`]), and the JSON reports of their runs under
+//! `schedule/ID/`, one file per run named after the UTC second it started.
+//! `zond schedule run` is the daemon that starts them on time and deletes all
+//! but the newest reports a job keeps.
+//!
+//! The reports read like any other: `@schedule:ID` is the newest report of a
+//! job and `@schedule:ID~N` the one N runs before it, so
+//! `zond report @schedule:1 --baseline @schedule:1~1` shows what changed since
+//! the previous run.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use zond_common::{parse::cron::UtcMinute, utils::paths};
+
+/// Prefix of the report tokens of scheduled scans, `@schedule:ID[~N]`.
+pub const SCHEDULE_TOKEN: &str = "@schedule:";
+
+const JOBS_FILE: &str = "schedule.json";
+const REPORTS_DIR: &str = "schedule";
+
+/// A scan run on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u32,
+    /// Cron expression of the schedule, in UTC.
+    pub cron: String,
+    /// Command line of the scan, without the leading `zond`.
+    pub args: Vec<String>,
+    /// How many reports to keep; older ones are deleted.
+    pub keep: usize,
+}
+
+/// Loads the scheduled scans, none if nothing was scheduled yet.
+pub fn load() -> anyhow::Result<Vec<Job>> {
+    let path: PathBuf = data_path(JOBS_FILE)?;
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Schedules the scan `args` and returns it with its new ID.
+pub fn add(cron: &str, args: &[String], keep: usize) -> anyhow::Result<Job> {
+    let mut jobs: Vec<Job> = load()?;
+    let job: Job = Job {
+        id: jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1,
+        cron: cron.to_string(),
+        args: args.to_vec(),
+        keep,
+    };
+    jobs.push(job.clone());
+    save(&jobs)?;
+    Ok(job)
+}
+
+/// Unschedules the scan `id` and deletes its reports.
+pub fn remove(id: u32) -> anyhow::Result<Job> {
+    let mut jobs: Vec<Job> = load()?;
+    let idx: usize = jobs
+        .iter()
+        .position(|job| job.id == id)
+        .ok_or_else(|| anyhow!("no scheduled scan #{id}"))?;
+    let job: Job = jobs.remove(idx);
+    save(&jobs)?;
+
+    let dir: PathBuf = reports_dir(id)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("failed to delete {}", dir.display()))?;
+    }
+    Ok(job)
+}
+
+/// Lists the reports of the scan `id`, oldest first.
+pub fn reports(id: u32) -> anyhow::Result<Vec<PathBuf>> {
+    let dir: PathBuf = reports_dir(id)?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // The names are UTC timestamps, so they sort by age.
+    reports.sort();
+    Ok(reports)
+}
+
+/// Lists the reports of every scheduled scan.
+pub fn all_reports() -> anyhow::Result<Vec<PathBuf>> {
+    let mut all: Vec<PathBuf> = Vec::new();
+    for job in load()? {
+        all.extend(reports(job.id)?);
+    }
+    Ok(all)
+}
+
+/// Where the run of the scan `id` starting at `unix_secs` writes its report.
+pub fn report_path(id: u32, unix_secs: u64) -> anyhow::Result<PathBuf> {
+    let dir: PathBuf = reports_dir(id)?;
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let at: UtcMinute = UtcMinute::from_unix(unix_secs);
+    Ok(dir.join(format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z.json",
+        at.year,
+        at.month,
+        at.day,
+        at.hour,
+        at.minute,
+        unix_secs % 60
+    )))
+}
+
+/// Deletes all but the newest `keep` reports of the scan `id` and returns how
+/// many were deleted.
+pub fn prune(id: u32, keep: usize) -> anyhow::Result<usize> {
+    let reports: Vec<PathBuf> = reports(id)?;
+    let stale: usize = reports.len().saturating_sub(keep);
+    for path in &reports[..stale] {
+        fs::remove_file(path).with_context(|| format!("failed to delete {}", path.display()))?;
+    }
+    Ok(stale)
+}
+
+/// Finds the report `token` names, given without the [`SCHEDULE_TOKEN`]
+/// prefix: `ID` for the newest report of a scan, `ID~N` for the one N runs
+/// before it.
+pub fn resolve(token: &str) -> anyhow::Result<PathBuf> {
+    let (id, back): (&str, &str) = token.split_once('~').unwrap_or((token, "0"));
+    let (Ok(id), Ok(back)) = (id.parse::<u32>(), back.parse::<usize>()) else {
+        bail!("expected {SCHEDULE_TOKEN}ID or {SCHEDULE_TOKEN}ID~N, got '{SCHEDULE_TOKEN}{token}'");
+    };
+
+    let reports: Vec<PathBuf> = reports(id)?;
+    match reports.len().checked_sub(back + 1) {
+        Some(idx) => Ok(reports[idx].clone()),
+        None if reports.is_empty() => bail!("scheduled scan #{id} has no report yet"),
+        None => bail!(
+            "scheduled scan #{id} keeps only {} report(s)",
+            reports.len()
+        ),
+    }
+}
+
+fn save(jobs: &[Job]) -> anyhow::Result<()> {
+    let path: PathBuf = data_path(JOBS_FILE)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let tmp: PathBuf = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(jobs)?)
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+fn reports_dir(id: u32) -> anyhow::Result<PathBuf> {
+    Ok(data_path(REPORTS_DIR)?.join(id.to_string()))
+}
+
+fn data_path(name: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+    paths::data_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| anyhow!("no data directory, set ZOND_DATA_DIR"))
+}
@@ -0,0 +1,86 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Signal Handling
+//!
+//! Turns SIGINT (Ctrl-C) and SIGTERM into the same graceful stop as pressing `q`:
+//! the running scan ends early, its partial results are printed and stored, and the
+//! process exits with [`ZondExit::Interrupted`](crate::exit::ZondExit::Interrupted).
+//!
+//! This matters whenever the raw-mode key listener is not active, e.g. with
+//! `--disable-input`, under `zond serve` and `zond agent`, or when a supervisor
+//! sends SIGTERM. A second signal exits immediately.
+
+use std::{
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use zond_common::{utils::input, warn};
+use zond_core::scanner;
+
+use crate::exit::ZondExit;
+
+/// How often [`wait`] checks whether a signal arrived.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static RECEIVED: AtomicBool = AtomicBool::new(false);
+
+/// Installs the SIGINT/SIGTERM handlers. Must be called from within the runtime.
+pub fn install() {
+    tokio::spawn(async {
+        let first: &str = next_signal().await;
+        RECEIVED.store(true, Ordering::Relaxed);
+        scanner::request_shutdown();
+        warn!("Received {first}, stopping (send again to quit immediately)");
+
+        let second: &str = next_signal().await;
+        input::restore_terminal();
+        warn!("Received {second} again, quitting");
+        process::exit(ZondExit::Interrupted as i32);
+    });
+}
+
+/// Returns `true` once SIGINT or SIGTERM was received.
+pub fn received() -> bool {
+    RECEIVED.load(Ordering::Relaxed)
+}
+
+/// Completes once SIGINT or SIGTERM was received.
+pub async fn wait() {
+    while !received() {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(unix)]
+async fn next_signal() -> &'static str {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let Ok(mut term) = signal(SignalKind::terminate()) else {
+        ctrl_c().await;
+        return "SIGINT";
+    };
+
+    tokio::select! {
+        _ = ctrl_c() => "SIGINT",
+        _ = term.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn next_signal() -> &'static str {
+    ctrl_c().await;
+    "Ctrl-C"
+}
+
+/// Waits for Ctrl-C, or forever if the handler could not be registered.
+async fn ctrl_c() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
@@ -7,9 +7,16 @@
 pub mod banner;
 pub mod colors;
 pub mod format;
+pub mod guard;
+pub mod histogram;
 pub mod host;
 pub mod insights;
 pub mod logging;
 pub mod network_fmt;
+pub mod phases;
+pub mod plan;
 pub mod print;
+pub mod sanitize;
+pub mod select;
 pub mod spinner;
+pub mod timeline;
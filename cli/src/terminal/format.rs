@@ -4,12 +4,15 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use crate::terminal::colors;
+use crate::terminal::{colors, sanitize};
 use colored::*;
 use pnet::util::MacAddr;
+use std::collections::BTreeSet;
 use std::net::{IpAddr, Ipv6Addr};
-use zond_common::models::host::Host;
-use zond_common::utils::{ip, redact};
+use zond_common::models::host::{DataSource, Host, LatencyCause, NetworkRole};
+use zond_common::models::reachability::{Family, Reachability};
+use zond_common::utils::{domain, idn, ip, redact};
+use zond_core::scanner::tls::Certificate;
 
 // Logic moved from network/ip.rs
 pub fn ipv6_to_type_str(ipv6_addr: &Ipv6Addr) -> &'static str {
@@ -36,23 +39,47 @@ pub fn ip_to_detail(host: &Host, redact: bool) -> Vec<(String, ColoredString)> {
             }
             IpAddr::V6(ipv6_addr) => {
                 let ipv6_type: &str = ipv6_to_type_str(ipv6_addr);
-                let ipv6_addr: ColoredString = if redact {
-                    let ip_str: String = match ip::get_ipv6_type(ipv6_addr) {
-                        ip::Ipv6AddressType::GlobalUnicast => redact::global_unicast(ipv6_addr),
-                        ip::Ipv6AddressType::UniqueLocal => redact::unique_local(ipv6_addr),
-                        ip::Ipv6AddressType::LinkLocal => redact::link_local(ipv6_addr),
-                        _ => ipv6_addr.to_string(),
-                    };
-                    ip_str.color(colors::IPV6_ADDR)
-                } else {
-                    ipv6_addr.to_string().color(colors::IPV6_ADDR)
-                };
+                let ipv6_addr: ColoredString =
+                    ipv6_to_string(ipv6_addr, redact).color(colors::IPV6_ADDR);
                 (String::from(ipv6_type), ipv6_addr)
             }
         })
         .collect()
 }
 
+/// Builds the line listing the addresses an mDNS responder announced.
+pub fn announced_to_detail(
+    ips: &BTreeSet<IpAddr>,
+    redact: bool,
+) -> Option<(String, ColoredString)> {
+    if ips.is_empty() {
+        return None;
+    }
+    let ips: Vec<String> = ips
+        .iter()
+        .map(|ip| match ip {
+            IpAddr::V4(ipv4_addr) => ipv4_addr.to_string().color(colors::IPV4_ADDR).to_string(),
+            IpAddr::V6(ipv6_addr) => ipv6_to_string(ipv6_addr, redact)
+                .color(colors::IPV6_ADDR)
+                .to_string(),
+        })
+        .collect();
+    Some(("Announced".to_string(), ips.join(", ").normal()))
+}
+
+/// Formats an IPv6 address, masking its host part under `redact`.
+pub fn ipv6_to_string(ipv6_addr: &Ipv6Addr, redact: bool) -> String {
+    if !redact {
+        return ipv6_addr.to_string();
+    }
+    match ip::get_ipv6_type(ipv6_addr) {
+        ip::Ipv6AddressType::GlobalUnicast => redact::global_unicast(ipv6_addr),
+        ip::Ipv6AddressType::UniqueLocal => redact::unique_local(ipv6_addr),
+        ip::Ipv6AddressType::LinkLocal => redact::link_local(ipv6_addr),
+        _ => ipv6_addr.to_string(),
+    }
+}
+
 fn is_global_unicast(ip_addr: &IpAddr) -> bool {
     match ip_addr {
         IpAddr::V6(ipv6_addr) => {
@@ -63,27 +90,69 @@ fn is_global_unicast(ip_addr: &IpAddr) -> bool {
     }
 }
 
+/// Builds the hostname line of a host tree.
+///
+/// Punycode labels are decoded for display unless `raw` is set, in which case the
+/// name is shown exactly as received. Control characters are escaped either way so
+/// a hostile responder cannot smuggle terminal escape sequences into the output.
+/// Names in one of `search_domains` are shown without it.
 pub fn hostname_to_detail(
     hostname_opt: &Option<String>,
     redact: bool,
+    raw: bool,
+    search_domains: &[String],
 ) -> Option<(String, ColoredString)> {
     let mut result: Option<(String, ColoredString)> = None;
 
     if let Some(hostname) = hostname_opt {
+        let hostname: &str = domain::strip_search_domain(hostname, search_domains);
+        let hostname: String = if raw {
+            hostname.to_string()
+        } else {
+            idn::to_unicode(hostname)
+        };
         let hostname_str: String = if redact {
-            redact::hostname(hostname)
+            redact::hostname(&hostname)
         } else {
-            hostname.to_string()
+            hostname
         };
         result = Some((
             String::from("Hostname"),
-            hostname_str.color(colors::HOSTNAME),
+            sanitize::escape(&hostname_str).color(colors::HOSTNAME),
         ))
     }
 
     result
 }
 
+/// Builds the one-line summary of a host used in collapsed vendor groups:
+/// its primary IP, then its hostname and model where known.
+pub fn host_to_line(
+    host: &Host,
+    redact: bool,
+    raw_hostnames: bool,
+    search_domains: &[String],
+) -> String {
+    let mut parts: Vec<String> = vec![
+        host.primary_ip
+            .to_string()
+            .color(colors::PRIMARY)
+            .to_string(),
+    ];
+    if host.network_roles.contains(&NetworkRole::Scanner) {
+        parts[0].push_str(&" (self)".color(colors::SECONDARY).to_string());
+    }
+    if let Some((_, hostname)) =
+        hostname_to_detail(&host.hostname, redact, raw_hostnames, search_domains)
+    {
+        parts.push(hostname.to_string());
+    }
+    if let Some((_, model)) = model_to_detail(host) {
+        parts.push(model.to_string());
+    }
+    parts.join(&"  ·  ".color(colors::SEPARATOR).to_string())
+}
+
 pub fn mac_to_detail(mac_opt: &Option<MacAddr>, redact: bool) -> Option<(String, ColoredString)> {
     let mut result: Option<(String, ColoredString)> = None;
 
@@ -103,7 +172,197 @@ pub fn vendor_to_detail(vendor_opt: &Option<String>) -> Option<(String, ColoredS
     vendor_opt.as_ref().map(|vendor| {
         (
             "Vendor".to_string(),
-            vendor.to_string().color(colors::MAC_ADDR),
+            sanitize::escape(vendor).color(colors::MAC_ADDR),
         )
     })
 }
+
+/// Builds the model line, with the location the device reports if it has one.
+pub fn model_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    let model: &str = host.model.as_deref()?;
+    let text: String = match &host.location {
+        Some(location) => format!("{model} ({location})"),
+        None => model.to_string(),
+    };
+    Some((
+        "Model".to_string(),
+        sanitize::escape(&text).color(colors::MAC_ADDR),
+    ))
+}
+
+/// Builds the device type line, with the platform and the friendly name the
+/// device announces where known. The name is masked like a hostname when
+/// redacting.
+pub fn device_to_detail(host: &Host, redact: bool) -> Option<(String, ColoredString)> {
+    let mut text: String = host.device_type?.to_string();
+    if let Some(platform) = &host.platform {
+        text.push_str(&format!(", {platform}"));
+    }
+    match &host.hints.mdns_name {
+        Some(name) if redact => text.push_str(&format!(" ({})", redact::hostname(name))),
+        Some(name) => text.push_str(&format!(" ({name})")),
+        None => {}
+    }
+    Some((
+        "Type".to_string(),
+        sanitize::escape(&text).color(colors::TEXT_DEFAULT),
+    ))
+}
+
+/// Builds the roles line for hosts tagged with any network role.
+pub fn roles_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    if host.network_roles.is_empty() {
+        return None;
+    }
+    let mut roles: Vec<&str> = host.network_roles.iter().map(NetworkRole::as_str).collect();
+    roles.sort_unstable();
+    Some((
+        "Roles".to_string(),
+        roles.join(", ").color(colors::TEXT_DEFAULT),
+    ))
+}
+
+/// Builds the line explaining high round trips, set when discovery was
+/// calibrated against a baseline host.
+pub fn latency_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    let text: &str = match host.latency? {
+        LatencyCause::Target => "high, slow target",
+        LatencyCause::Congestion => "high, network congested during the scan",
+    };
+    Some(("Latency".to_string(), text.color(colors::TEXT_DEFAULT)))
+}
+
+/// Builds the line counting the addresses a proxy ARP responder or ARP sponge
+/// answered for.
+pub fn proxied_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    if host.proxied_ips.is_empty() {
+        return None;
+    }
+    Some((
+        "Proxy ARP".to_string(),
+        format!("answers for {} other addresses", host.proxied_ips.len())
+            .color(colors::TEXT_DEFAULT),
+    ))
+}
+
+/// Builds the line naming where else information about a host came from,
+/// such as a local DHCP lease file.
+pub fn sources_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    if host.sources.is_empty() {
+        return None;
+    }
+    let mut sources: Vec<&str> = host.sources.iter().map(DataSource::as_str).collect();
+    sources.sort_unstable();
+    Some((
+        "Sources".to_string(),
+        sources.join(", ").color(colors::TEXT_DEFAULT),
+    ))
+}
+
+/// Builds one line per annotation of an external enricher, labelled with its
+/// key. Values are escaped, and masked like hostnames when `redact` is set,
+/// since they often name people or asset tags.
+pub fn annotations_to_details(host: &Host, redact: bool) -> Vec<(String, ColoredString)> {
+    host.annotations
+        .iter()
+        .map(|(key, value)| {
+            let value: String = match redact {
+                true => redact::hostname(value),
+                false => value.clone(),
+            };
+            (
+                sanitize::escape(key),
+                sanitize::escape(&value).color(colors::TEXT_DEFAULT),
+            )
+        })
+        .collect()
+}
+
+/// Builds the lines describing a TLS certificate.
+///
+/// Names come straight from the device, so they are escaped, and masked like
+/// hostnames when `redact` is set.
+pub fn certificate_to_details(cert: &Certificate, redact: bool) -> Vec<(String, ColoredString)> {
+    let name = |name: &str| -> String {
+        let shown: String = if redact && name.parse::<IpAddr>().is_err() {
+            redact::hostname(name)
+        } else {
+            name.to_string()
+        };
+        sanitize::escape(&shown)
+    };
+
+    let mut details: Vec<(String, ColoredString)> = Vec::new();
+    if let Some(subject) = &cert.subject {
+        details.push(("Subject".to_string(), name(subject).color(colors::HOSTNAME)));
+    }
+    let issuer: String = match (&cert.issuer, cert.self_signed) {
+        (_, true) => "self-signed".to_string(),
+        (Some(issuer), false) => sanitize::escape(issuer),
+        (None, false) => "unknown".to_string(),
+    };
+    details.push(("Issuer".to_string(), issuer.color(colors::TEXT_DEFAULT)));
+    if !cert.alt_names.is_empty() {
+        let names: Vec<String> = cert.alt_names.iter().map(|alt| name(alt)).collect();
+        details.push((
+            "Names".to_string(),
+            names.join(", ").color(colors::HOSTNAME),
+        ));
+    }
+    if let Some(not_after) = &cert.not_after {
+        details.push((
+            "Expires".to_string(),
+            sanitize::escape(not_after).color(colors::TEXT_DEFAULT),
+        ));
+    }
+    details
+}
+
+/// Builds the line showing which discovery probes the host answered per
+/// address family, e.g. `IPv4 ARP ✓ ICMP ✗ · IPv6 NDP ✓`. A host that only
+/// answers in one of two probed families is flagged.
+pub fn reachability_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    let reachability: &Reachability = &host.reachability;
+    if reachability.is_empty() {
+        return None;
+    }
+    let mut parts: Vec<String> = [Family::Ipv4, Family::Ipv6]
+        .into_iter()
+        .filter(|family| !reachability.family(*family).is_empty())
+        .map(|family| {
+            let probes: Vec<String> = reachability
+                .family(family)
+                .iter()
+                .map(|(method, answered)| {
+                    let mark: ColoredString = match answered {
+                        true => "✓".green(),
+                        false => "✗".red(),
+                    };
+                    format!("{} {mark}", method.label().color(colors::TEXT_DEFAULT))
+                })
+                .collect();
+            format!(
+                "{} {}",
+                family.label().color(colors::SECONDARY),
+                probes.join(" ")
+            )
+        })
+        .collect();
+    if let Some(family) = reachability.only_over() {
+        parts.push(format!("{} only", family.label()).yellow().to_string());
+    }
+    let separator: String = " · ".color(colors::SEPARATOR).to_string();
+    Some(("Reachable".to_string(), parts.join(&separator).normal()))
+}
+
+/// Builds the hop distance line for hosts whose response TTL is known.
+pub fn distance_to_detail(host: &Host) -> Option<(String, ColoredString)> {
+    let ttl: u8 = host.ttl?;
+    let hops: u8 = host.hop_distance()?;
+    let label: &str = if hops == 1 { "hop" } else { "hops" };
+
+    Some((
+        "Distance".to_string(),
+        format!("~{hops} {label} (TTL {ttl})").color(colors::TEXT_DEFAULT),
+    ))
+}
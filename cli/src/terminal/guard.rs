@@ -0,0 +1,38 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Terminal Guard
+//!
+//! The key listener puts the terminal into raw mode on a background thread. If the
+//! process panics meanwhile, the user's shell would be left in raw mode with a
+//! hidden cursor. [`TerminalGuard`] makes sure the terminal is restored both on
+//! every regular exit from `main` and before a panic message is printed.
+
+use std::panic::{self, PanicHookInfo};
+
+use zond_common::utils::input;
+
+/// Restores the terminal when dropped, and on panic.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    /// Chains a panic hook that restores the terminal before the default hook
+    /// prints the panic message, and returns the guard for the regular exit path.
+    pub fn install() -> Self {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info: &PanicHookInfo<'_>| {
+            input::restore_terminal();
+            default_hook(info);
+        }));
+        Self
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        input::restore_terminal();
+    }
+}
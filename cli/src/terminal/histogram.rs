@@ -0,0 +1,86 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! RTT histogram for the discovery summary.
+//!
+//! Buckets every host's fastest round trip into a handful of latency classes and
+//! draws them as a horizontal bar chart. A tall `<1ms` bar means mostly on-link
+//! devices, while weight in the upper buckets points at routed or congested paths.
+
+use std::time::Duration;
+
+use colored::*;
+use zond_common::models::host::Host;
+
+use crate::terminal::colors;
+
+/// Width of the longest bar in terminal cells.
+const BAR_WIDTH: usize = 40;
+
+/// Partial blocks for the fractional end of a bar, in eighths of a cell.
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Bucket labels paired with their exclusive upper bound.
+pub const BUCKETS: [(&str, Option<Duration>); 5] = [
+    ("<1ms", Some(Duration::from_millis(1))),
+    ("1-5ms", Some(Duration::from_millis(5))),
+    ("5-20ms", Some(Duration::from_millis(20))),
+    ("20-100ms", Some(Duration::from_millis(100))),
+    (">100ms", None),
+];
+
+/// Counts the hosts per latency bucket, based on each host's minimum RTT.
+///
+/// Hosts without any RTT measurement are left out.
+pub fn bucket_counts(hosts: &[Host]) -> [usize; BUCKETS.len()] {
+    let mut counts: [usize; BUCKETS.len()] = [0; BUCKETS.len()];
+
+    for rtt in hosts.iter().filter_map(Host::min_rtt) {
+        let idx: usize = BUCKETS
+            .iter()
+            .position(|(_, upper)| upper.is_none_or(|upper| rtt < upper))
+            .unwrap_or(BUCKETS.len() - 1);
+        counts[idx] += 1;
+    }
+
+    counts
+}
+
+/// Renders the histogram as one line per bucket.
+///
+/// Returns an empty list if no host carries an RTT.
+pub fn render(hosts: &[Host]) -> Vec<String> {
+    let counts: [usize; BUCKETS.len()] = bucket_counts(hosts);
+    let max: usize = counts.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return Vec::new();
+    }
+
+    BUCKETS
+        .iter()
+        .zip(counts)
+        .map(|((label, _), count)| {
+            format!(
+                " {} {} {}",
+                format!("{label:<8}").color(colors::TEXT_DEFAULT),
+                bar(count, max).color(colors::ACCENT),
+                count.to_string().color(colors::SECONDARY)
+            )
+        })
+        .collect()
+}
+
+/// Draws a bar of `count / max * BAR_WIDTH` cells, padded to `BAR_WIDTH`.
+fn bar(count: usize, max: usize) -> String {
+    let eighths: usize = count * BAR_WIDTH * 8 / max;
+    let mut bar: String = "█".repeat(eighths / 8);
+
+    if eighths % 8 != 0 {
+        bar.push(EIGHTHS[eighths % 8]);
+    }
+
+    format!("{bar:<BAR_WIDTH$}")
+}
@@ -8,13 +8,14 @@ use std::{net::IpAddr, time::Duration};
 
 use colored::*;
 use unicode_width::UnicodeWidthStr;
-use zond_common::models::host::Host;
+use zond_common::models::host::{Host, NetworkRole};
 use zond_common::models::port::{Port, PortState, Protocol};
 
 use crate::{
     terminal::{
         colors, format,
         print::{self, Print, TOTAL_WIDTH},
+        sanitize,
     },
     zprint,
 };
@@ -50,10 +51,42 @@ impl PrintableHost for Host {
             details.push(vendor_detail);
         }
 
-        if let Some(hostname_detail) = format::hostname_to_detail(&self.hostname, p.redact) {
+        if let Some(model_detail) = format::model_to_detail(self) {
+            details.push(model_detail);
+        }
+
+        if let Some(device_detail) = format::device_to_detail(self, p.redact) {
+            details.push(device_detail);
+        }
+
+        if let Some(hostname_detail) =
+            format::hostname_to_detail(&self.hostname, p.redact, p.raw_hostnames, &p.search_domains)
+        {
             details.push(hostname_detail);
         }
 
+        if let Some(proxied_detail) = format::proxied_to_detail(self) {
+            details.push(proxied_detail);
+        }
+
+        if let Some(sources_detail) = format::sources_to_detail(self) {
+            details.push(sources_detail);
+        }
+
+        details.extend(format::annotations_to_details(self, p.redact));
+
+        if let Some(distance_detail) = format::distance_to_detail(self) {
+            details.push(distance_detail);
+        }
+
+        if let Some(latency_detail) = format::latency_to_detail(self) {
+            details.push(latency_detail);
+        }
+
+        if let Some(reachability_detail) = format::reachability_to_detail(self) {
+            details.push(reachability_detail);
+        }
+
         print::as_tree(details);
 
         if !self.ports().is_empty() {
@@ -80,16 +113,22 @@ fn print_host_head(idx: usize, primary_ip: &IpAddr, host: &Host) {
     let local_pad: usize = block_width.saturating_sub(rtt_width);
     let right_part: String = format!("{}{}", " ".repeat(local_pad), rtt_string);
 
-    let left_part: String = format!("[{}] {}", idx, primary_ip);
+    let self_label: &str = if host.network_roles.contains(&NetworkRole::Scanner) {
+        " (self)"
+    } else {
+        ""
+    };
+    let left_part: String = format!("[{}] {}{}", idx, primary_ip, self_label);
     let used_width: usize = left_part.width() + block_width;
 
     let padding_len: usize = TOTAL_WIDTH.saturating_sub(used_width + 1);
     let padding: String = " ".repeat(padding_len);
 
     zprint!(
-        "{} {}{}{}",
+        "{} {}{}{}{}",
         format!("[{}]", idx.to_string().color(colors::ACCENT)).color(colors::SEPARATOR),
         primary_ip.to_string().color(colors::PRIMARY),
+        self_label.color(colors::SECONDARY),
         padding,
         right_part.color(colors::SECONDARY)
     );
@@ -172,6 +211,7 @@ fn print_services(ports: &[Port]) {
         let proto_str = match p.protocol {
             Protocol::Tcp => "tcp",
             Protocol::Udp => "udp",
+            Protocol::Sctp => "sctp",
         };
         let port_spec = format!("{}/{}", p.number, proto_str);
         let port_spec_padded = format!("{:width$}", port_spec, width = 9);
@@ -185,7 +225,10 @@ fn print_services(ports: &[Port]) {
         };
 
         let state_fmt = format!("[ {} ]", state_str.color(state_color));
-        let svc_name = p.service_info.as_deref().unwrap_or("???");
+        let svc_name: String = p
+            .service_info
+            .as_deref()
+            .map_or_else(|| String::from("???"), sanitize::escape);
 
         zprint!(
             "      {} {} {}  {}",
@@ -4,12 +4,28 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+//! Spinner insights.
+//!
+//! Most of the time the spinner shows a shuffled mix of tips and trivia, but
+//! when something about the current run is worth pointing out (missing root,
+//! piped output, lost probes, a silent scan) a contextual hint takes the next
+//! free slot.
+
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::time::Duration;
+
 use rand::seq::SliceRandom;
 use rand::{Rng, rng};
+use zond_common::utils::privilege;
+
+use crate::terminal::print::Print;
 
 /// Internal scanner-specific operational guidance.
 const SCANNER_TIPS: &[&str] = &[
     "Press 'q' to stop and print results",
+    "Press 'p' to pause sending and 'p' again to resume",
+    "Press 's' for a status line, 'v' to change verbosity",
     "Running with root enables faster raw socket scanning",
     "Ranges (e.g. 5-11ms) show min/max RTT latency",
     "Timings with ~ are averages of consistent RTT results",
@@ -34,6 +50,130 @@ const DEV_HUMOR: &[&str] = &[
     "Hardware is the part you kick when the software fails",
 ];
 
+/// Runtime facts about the current run that decide which hints apply.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsightContext {
+    /// Whether the process can open raw sockets.
+    pub privileged: bool,
+    /// Whether stdout is redirected into a file or another program.
+    pub piped: bool,
+    /// Whether `--redact` is active.
+    pub redact: bool,
+    /// Hosts identified so far.
+    pub hosts_found: usize,
+    /// Probes sent so far.
+    pub probes_sent: usize,
+    /// TCP connect probes that went unanswered, retries included.
+    pub probes_timed_out: usize,
+    /// Time since the spinner started.
+    pub elapsed: Duration,
+}
+
+impl InsightContext {
+    /// Captures the current state of the run.
+    pub fn capture(elapsed: Duration) -> Self {
+        let (probes_sent, probes_timed_out) = zond_core::scanner::probe_counts();
+        Self {
+            privileged: privilege::has_raw_socket_access(),
+            piped: !std::io::stdout().is_terminal(),
+            redact: Print::get().redact,
+            hosts_found: zond_core::scanner::get_host_count(),
+            probes_sent,
+            probes_timed_out,
+            elapsed,
+        }
+    }
+}
+
+/// A hint that is only worth showing when its condition holds for the current run.
+struct ContextualHint {
+    text: &'static str,
+    applies: fn(&InsightContext) -> bool,
+}
+
+/// Probes sent before lost ones say anything about the network.
+const MIN_PROBES_FOR_LOSS: usize = 100;
+
+const LOSS_HINT: &str = "Many probes time out, '--retries 2' gives silent ports another try";
+
+/// Returns `true` once at least half of the probes timed out while some hosts
+/// did answer, so the network is reachable but drops packets.
+fn is_lossy(ctx: &InsightContext) -> bool {
+    ctx.hosts_found > 0
+        && ctx.probes_sent >= MIN_PROBES_FOR_LOSS
+        && ctx.probes_timed_out * 2 >= ctx.probes_sent
+}
+
+/// Contextual hints, in order of priority. Each one is shown at most once per run.
+const CONTEXTUAL_HINTS: &[ContextualHint] = &[
+    ContextualHint {
+        text: "Unprivileged mode: run with sudo or --elevate for ARP, MACs and vendors",
+        applies: |ctx| !ctx.privileged,
+    },
+    ContextualHint {
+        text: "Output is piped, consider '--redact' before sharing it",
+        applies: |ctx| ctx.piped && !ctx.redact,
+    },
+    ContextualHint {
+        text: LOSS_HINT,
+        applies: is_lossy,
+    },
+    ContextualHint {
+        text: "Nothing yet? Double-check the targets or add '-v' for details",
+        applies: |ctx| ctx.hosts_found == 0 && ctx.elapsed >= Duration::from_secs(10),
+    },
+    ContextualHint {
+        text: "Slow scan? '-n' skips hostname lookups entirely",
+        applies: |ctx| ctx.elapsed >= Duration::from_secs(30),
+    },
+];
+
+/// Chooses what the spinner shows in each tip slot.
+pub struct Insights {
+    general: Vec<&'static str>,
+    shown: HashSet<&'static str>,
+    current: Option<(usize, &'static str)>,
+}
+
+impl Default for Insights {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Insights {
+    pub fn new() -> Self {
+        Self {
+            general: get_shuffled_insights(),
+            shown: HashSet::new(),
+            current: None,
+        }
+    }
+
+    /// Returns the message for the given tip slot.
+    ///
+    /// A slot keeps its message until the spinner moves on to the next one.
+    /// When a new slot starts, the first applicable contextual hint that has
+    /// not been shown yet wins over the general rotation.
+    pub fn pick(&mut self, slot: usize, ctx: &InsightContext) -> &'static str {
+        if let Some((current_slot, text)) = self.current
+            && current_slot == slot
+        {
+            return text;
+        }
+
+        let text: &'static str = CONTEXTUAL_HINTS
+            .iter()
+            .find(|hint| !self.shown.contains(hint.text) && (hint.applies)(ctx))
+            .map(|hint| hint.text)
+            .unwrap_or_else(|| self.general[slot % self.general.len()]);
+
+        self.shown.insert(text);
+        self.current = Some((slot, text));
+        text
+    }
+}
+
 /// Generates a randomized list of UI messages.
 ///
 /// Every slot in the resulting list has a 50% probability of being an
@@ -68,3 +208,66 @@ pub fn get_shuffled_insights() -> Vec<&'static str> {
     output.extend(flavor);
     output
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lossy() -> InsightContext {
+        InsightContext {
+            privileged: true,
+            hosts_found: 3,
+            probes_sent: 400,
+            probes_timed_out: 250,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn suggests_retries_when_probes_time_out() {
+        let mut insights: Insights = Insights::new();
+        assert_eq!(insights.pick(0, &lossy()), LOSS_HINT);
+        // Shown once per run.
+        assert_ne!(insights.pick(1, &lossy()), LOSS_HINT);
+    }
+
+    #[test]
+    fn needs_answers_and_enough_probes_to_call_it_loss() {
+        let silent: InsightContext = InsightContext {
+            hosts_found: 0,
+            ..lossy()
+        };
+        let early: InsightContext = InsightContext {
+            probes_sent: MIN_PROBES_FOR_LOSS - 1,
+            probes_timed_out: MIN_PROBES_FOR_LOSS - 1,
+            ..lossy()
+        };
+        let healthy: InsightContext = InsightContext {
+            probes_timed_out: 100,
+            ..lossy()
+        };
+        for ctx in [silent, early, healthy] {
+            assert_ne!(Insights::new().pick(0, &ctx), LOSS_HINT);
+        }
+    }
+
+    #[test]
+    fn privileges_come_first() {
+        let ctx: InsightContext = InsightContext {
+            privileged: false,
+            ..lossy()
+        };
+        let mut insights: Insights = Insights::new();
+        assert!(insights.pick(0, &ctx).starts_with("Unprivileged"));
+        assert_eq!(insights.pick(1, &ctx), LOSS_HINT);
+    }
+}
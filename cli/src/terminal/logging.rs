@@ -10,10 +10,11 @@ use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::fmt::FormatEvent;
 use tracing_subscriber::fmt::format::{self, Writer};
 use tracing_subscriber::registry::LookupSpan;
+use zond_common::logging;
 
-pub struct ZondFormatter {
-    pub max_verbosity: u8,
-}
+/// Formats events, dropping those above [`logging::verbosity`], which the `v`
+/// key can change while a scan runs.
+pub struct ZondFormatter;
 
 impl<S, N> FormatEvent<S, N> for ZondFormatter
 where
@@ -38,7 +39,7 @@ where
         event.record(&mut meta_visitor);
 
         let event_verbosity = meta_visitor.verbosity.unwrap_or(0);
-        if event_verbosity > self.max_verbosity {
+        if event_verbosity > logging::verbosity() {
             return Ok(());
         }
 
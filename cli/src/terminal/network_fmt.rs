@@ -8,6 +8,7 @@ use crate::terminal::{colors, print};
 use colored::*;
 use pnet::datalink::NetworkInterface;
 use pnet::ipnetwork::IpNetwork;
+use zond_common::net::interface::os::{self, Duplex, LinkDetails};
 use zond_common::utils::ip::{self, Ipv6AddressType};
 
 pub fn print_interface(interface: &NetworkInterface, idx: usize) {
@@ -19,9 +20,46 @@ pub fn print_interface(interface: &NetworkInterface, idx: usize) {
             mac_addr.to_string().color(colors::MAC_ADDR),
         ));
     }
+    print_map.extend(to_print_map_link(&os::link_details(interface)));
     print::as_tree(print_map);
 }
 
+/// Builds the link speed, MTU and driver lines, skipping what the platform
+/// does not report.
+fn to_print_map_link(link: &LinkDetails) -> Vec<(String, ColoredString)> {
+    let mut print_map: Vec<(String, ColoredString)> = Vec::new();
+
+    let speed: Option<String> = link.speed_mbps.map(|mbps| match mbps {
+        0..1000 => format!("{mbps} Mb/s"),
+        _ => format!("{} Gb/s", mbps as f64 / 1000.0),
+    });
+    let duplex: Option<&str> = link.duplex.map(|duplex| match duplex {
+        Duplex::Full => "full duplex",
+        Duplex::Half => "half duplex",
+    });
+    let parts: Vec<String> = speed
+        .into_iter()
+        .chain(duplex.map(str::to_string))
+        .collect();
+    if !parts.is_empty() {
+        print_map.push((
+            "Link".to_string(),
+            parts.join(", ").color(colors::TEXT_DEFAULT),
+        ));
+    }
+
+    if let Some(mtu) = link.mtu {
+        print_map.push((
+            "MTU".to_string(),
+            mtu.to_string().color(colors::TEXT_DEFAULT),
+        ));
+    }
+    if let Some(driver) = &link.driver {
+        print_map.push(("Driver".to_string(), driver.color(colors::SECONDARY)));
+    }
+    print_map
+}
+
 fn to_print_map_net(ip_net: &[IpNetwork]) -> Vec<(String, ColoredString)> {
     ip_net
         .iter()
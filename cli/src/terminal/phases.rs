@@ -0,0 +1,69 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Phase timing table printed at `-v`.
+//!
+//! One row per phase of the run, in the order they started: how often it
+//! ran, how long it took and what it counted, so a slow scan shows where its
+//! time went without recording a full trace.
+
+use std::time::Duration;
+
+use colored::*;
+
+use crate::terminal::colors;
+use crate::timings::PhaseSummary;
+
+const NAME_WIDTH: usize = 20;
+const RUNS_WIDTH: usize = 5;
+const TIME_WIDTH: usize = 10;
+
+/// Renders `phases` as a table, closed by the run time of the whole process.
+pub fn render(phases: &[PhaseSummary], total: Duration) -> Vec<String> {
+    let mut lines: Vec<String> = vec![
+        row("Phase", "Runs", "Time", "Counts")
+            .color(colors::PRIMARY)
+            .to_string(),
+    ];
+
+    for phase in phases {
+        let counts: String = phase
+            .counts
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        lines.push(
+            row(
+                phase.name,
+                &phase.runs.to_string(),
+                &duration(phase.wall),
+                &counts,
+            )
+            .color(colors::TEXT_DEFAULT)
+            .to_string(),
+        );
+    }
+    if phases.is_empty() {
+        lines.push(" no phase finished".color(colors::SEPARATOR).to_string());
+    }
+
+    lines.push(row("total", "", &duration(total), "").bold().to_string());
+    lines
+}
+
+fn row(name: &str, runs: &str, time: &str, counts: &str) -> String {
+    format!(" {name:<NAME_WIDTH$} {runs:>RUNS_WIDTH$} {time:>TIME_WIDTH$}  {counts}")
+        .trim_end()
+        .to_string()
+}
+
+fn duration(time: Duration) -> String {
+    match time.as_millis() {
+        0..1000 => format!("{:.1} ms", time.as_secs_f64() * 1000.0),
+        _ => format!("{:.2} s", time.as_secs_f64()),
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Target partition table for `discover --plan`.
+//!
+//! One row per interface, counting the targets on its own subnets and those
+//! behind a gateway it leads to, so multi-homed hosts can check that probes
+//! leave through the intended NIC before any is sent.
+
+use colored::*;
+use pnet::ipnetwork::IpNetwork;
+use zond_core::scanner::impact::{InterfacePlan, Plan};
+
+use crate::terminal::colors;
+
+const NAME_WIDTH: usize = 12;
+const ADDRESS_WIDTH: usize = 24;
+const COUNT_WIDTH: usize = 10;
+
+/// Renders `plan` as a table, followed by notes on targets no interface takes.
+pub fn render(plan: &Plan) -> Vec<String> {
+    let mut lines: Vec<String> = vec![
+        row("Interface", "Address", "Local", "Routed")
+            .color(colors::PRIMARY)
+            .to_string(),
+    ];
+
+    for (name, intf) in &plan.interfaces {
+        lines.push(
+            row(
+                name,
+                &address(intf),
+                &intf.local.to_string(),
+                &intf.routed.to_string(),
+            )
+            .color(colors::TEXT_DEFAULT)
+            .to_string(),
+        );
+    }
+    if plan.interfaces.is_empty() {
+        lines.push(
+            " no interface reaches any target"
+                .color(colors::SEPARATOR)
+                .to_string(),
+        );
+    }
+
+    if plan.unmapped > 0 {
+        lines.push(String::new());
+        lines.push(format!(
+            " {} {}",
+            plan.unmapped.to_string().yellow().bold(),
+            "unmapped targets, probed with TCP connects routed by the OS"
+                .color(colors::TEXT_DEFAULT)
+        ));
+    }
+    if plan.connect_only {
+        lines.push(String::new());
+        lines.push(format!(
+            " {}",
+            "No raw sockets: every target gets TCP connects, routed by the OS".yellow()
+        ));
+    }
+    lines
+}
+
+fn row(name: &str, address: &str, local: &str, routed: &str) -> String {
+    format!(
+        " {name:<NAME_WIDTH$} {address:<ADDRESS_WIDTH$} {local:>COUNT_WIDTH$} {routed:>COUNT_WIDTH$}"
+    )
+}
+
+/// The first IPv4 network of the interface, else its first network, noting
+/// how many more there are.
+fn address(intf: &InterfacePlan) -> String {
+    let first: Option<&IpNetwork> = intf
+        .networks
+        .iter()
+        .find(|net| net.is_ipv4())
+        .or(intf.networks.first());
+    match (first, intf.networks.len()) {
+        (None, _) => "-".to_string(),
+        (Some(net), 1) => net.to_string(),
+        (Some(net), count) => format!("{net} (+{})", count - 1),
+    }
+}
@@ -4,13 +4,36 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use std::{sync::OnceLock, time::Duration};
+use std::{net::IpAddr, path::PathBuf, sync::OnceLock, time::Duration};
 
 use anyhow::bail;
 use colored::*;
-use zond_common::{config::ZondConfig, models::host::Host, success};
-
-use crate::terminal::{banner, colors, host::PrintableHost};
+use zond_common::{
+    config::ZondConfig,
+    models::{
+        host::{Host, Unreachable},
+        scan::{self, ScanInfo},
+        timeline::Timeline,
+    },
+    parse::cron::UtcMinute,
+    success,
+    utils::redact,
+};
+use zond_core::{
+    scanner::{
+        Dossier,
+        impact::Plan,
+        ipproto::{ProtocolState, ProtocolSurvey},
+        mdns::MdnsSurvey,
+    },
+    system::doctor::{Check, Status},
+};
+
+use crate::schedule::Job;
+use crate::terminal::{
+    banner, colors, format, histogram, host::PrintableHost, phases, plan, sanitize, timeline,
+};
+use crate::timings::PhaseSummary;
 
 /// Central logging macro for terminal output.
 ///
@@ -45,6 +68,10 @@ pub struct Print {
     pub(crate) no_banner: bool,
     pub(crate) q_level: u8,
     pub(crate) redact: bool,
+    pub(crate) raw_hostnames: bool,
+    pub(crate) search_domains: Vec<String>,
+    pub(crate) by_vendor: bool,
+    pub(crate) group_details: bool,
 }
 
 impl Print {
@@ -54,6 +81,10 @@ impl Print {
             no_banner: cfg.no_banner,
             q_level: cfg.quiet,
             redact: cfg.redact,
+            raw_hostnames: cfg.raw_hostnames,
+            search_domains: cfg.search_domains.clone(),
+            by_vendor: cfg.by_vendor,
+            group_details: cfg.group_details,
         }
     }
 
@@ -106,12 +137,16 @@ impl Print {
         zprint!("{}", output);
     }
 
-    /// Iterates over discovered hosts and triggers their visual representation.
+    /// Iterates over discovered hosts and triggers their visual representation,
+    /// grouped by vendor under `--by-vendor`.
     ///
     /// # Errors
     /// Returns an error if an unsupported quiet level is requested.
     pub fn hosts(hosts: &[Host]) -> anyhow::Result<()> {
         let p = Self::get();
+        if p.by_vendor {
+            return Self::vendor_groups(hosts);
+        }
         for (idx, host) in hosts.iter().enumerate() {
             match p.q_level {
                 2 => bail!("-qq is currently unimplemented"),
@@ -124,9 +159,187 @@ impl Print {
         Ok(())
     }
 
+    /// Prints one group per vendor, largest first, headed by `Vendor (count)`.
+    ///
+    /// Members are listed one line each, or as full host trees with `-v`.
+    fn vendor_groups(hosts: &[Host]) -> anyhow::Result<()> {
+        let p = Self::get();
+        if p.q_level == 2 {
+            bail!("-qq is currently unimplemented");
+        }
+
+        let groups: Vec<(&str, Vec<&Host>)> = group_by_vendor(hosts);
+        let mut idx: usize = 0;
+        for (group_idx, (vendor, members)) in groups.iter().enumerate() {
+            zprint!(
+                "{} {}",
+                sanitize::escape(vendor).color(colors::PRIMARY).bold(),
+                format!("({})", members.len()).color(colors::ACCENT)
+            );
+
+            for (member_idx, host) in members.iter().enumerate() {
+                if p.group_details {
+                    host.print(idx);
+                    if member_idx + 1 != members.len() {
+                        zprint!();
+                    }
+                } else {
+                    let last: bool = member_idx + 1 == members.len();
+                    let branch: ColoredString = if !last { "├─" } else { "└─" }.bright_black();
+                    zprint!(
+                        " {} {}",
+                        branch,
+                        format::host_to_line(host, p.redact, p.raw_hostnames, &p.search_domains)
+                    );
+                }
+                idx += 1;
+            }
+
+            if group_idx + 1 != groups.len() {
+                zprint!();
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the single-host view of `zond host`: the usual host tree, then
+    /// the roles, which stages the host answered and its TLS certificates.
+    ///
+    /// # Errors
+    /// Returns an error if an unsupported quiet level is requested.
+    pub fn dossier(dossier: &Dossier) -> anyhow::Result<()> {
+        let p = Self::get();
+        Self::hosts(std::slice::from_ref(&dossier.host))?;
+
+        let found_by: &str = match (dossier.discovered, dossier.host.ports().is_empty()) {
+            (true, true) => "discovery",
+            (true, false) => "discovery and port scan",
+            (false, _) => "port scan only",
+        };
+        let mut details: Vec<Detail> =
+            vec![("Found by".to_string(), found_by.color(colors::TEXT_DEFAULT))];
+        if let Some(roles) = format::roles_to_detail(&dossier.host) {
+            details.push(roles);
+        }
+        zprint!();
+        as_tree(details);
+
+        if dossier.certificates.is_empty() {
+            return Ok(());
+        }
+        Self::header("tls certificates");
+        for (idx, (port, cert)) in dossier.certificates.iter().enumerate() {
+            tree_head(*port as usize, "tcp");
+            as_tree(format::certificate_to_details(cert, p.redact));
+            if idx + 1 != dossier.certificates.len() {
+                zprint!();
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the completion line of a single-host lookup.
+    pub fn inspection_summary(ip: IpAddr, total_time: Duration) {
+        let p = Self::get();
+        let target: ColoredString = ip.to_string().bold().green();
+        let total_time: ColoredString = format!("{:.2}s", total_time.as_secs_f64()).bold().yellow();
+        let output: &ColoredString =
+            &format!("Inspection Complete: {target} profiled in {total_time}")
+                .color(colors::TEXT_DEFAULT);
+
+        match p.q_level {
+            0 => {
+                divider();
+                centerln(output);
+            }
+            _ => {
+                zprint!();
+                success!("{output}")
+            }
+        }
+    }
+
+    /// Prints how the targets of a discovery are split across interfaces.
+    pub fn plan(partition: &Plan) {
+        Self::header("scan plan");
+        plan::render(partition)
+            .iter()
+            .for_each(|line| zprint!("{line}"));
+    }
+
+    /// Prints where the time of the run went, one row per phase.
+    pub fn phase_timings(summary: &[PhaseSummary], total: Duration) {
+        Self::header("phase timings");
+        phases::render(summary, total)
+            .iter()
+            .for_each(|line| zprint!("{line}"));
+    }
+
+    /// Prints where a saved report came from: the file, and the run, zond
+    /// version and machine that produced it if the report was stamped.
+    pub fn report_origin(source: &str, run: Option<&ScanInfo>, agent: Option<&str>) {
+        let p = Self::get();
+        let name = |name: &str| match p.redact {
+            true => redact::hostname(name),
+            false => sanitize::escape(name),
+        };
+        let mut details: Vec<Detail> = vec![(
+            "Source".to_string(),
+            sanitize::escape(source).color(colors::TEXT_DEFAULT),
+        )];
+        if let Some(run) = run {
+            details.push(("Scan".to_string(), run.id.color(colors::TEXT_DEFAULT)));
+            if let Some(millis) = scan::started_millis(&run.id) {
+                let taken: UtcMinute = UtcMinute::from_unix(millis / 1_000);
+                details.push((
+                    "Taken".to_string(),
+                    taken.to_string().color(colors::TEXT_DEFAULT),
+                ));
+            }
+            details.push((
+                "Version".to_string(),
+                format!("zond {}", run.version).color(colors::TEXT_DEFAULT),
+            ));
+            if let Some(hostname) = run.hostname.as_deref() {
+                details.push((
+                    "Machine".to_string(),
+                    name(hostname).color(colors::HOSTNAME),
+                ));
+            }
+        }
+        if let Some(agent) = agent {
+            details.push(("Agent".to_string(), name(agent).color(colors::HOSTNAME)));
+        }
+        as_tree(details);
+    }
+
+    /// Prints the closing line of `zond view`, with the RTT histogram of the
+    /// hosts shown in the default output mode.
+    pub fn report_summary(hosts: &[Host]) {
+        let p = Self::get();
+        let listed: ColoredString = format!("{} hosts", hosts.len()).bold().green();
+        let output: &ColoredString =
+            &format!("Report Loaded: {listed} listed").color(colors::TEXT_DEFAULT);
+
+        match p.q_level {
+            0 => {
+                divider();
+                centerln(output);
+                Self::latency_histogram(hosts);
+            }
+            _ => {
+                zprint!();
+                success!("{output}")
+            }
+        }
+    }
+
     /// Prints the completion summary for the network discovery phase.
-    pub fn discovery_summary(hosts_len: usize, total_time: Duration) {
+    ///
+    /// In the default output mode this includes an RTT histogram of all hosts.
+    pub fn discovery_summary(hosts: &[Host], total_time: Duration) {
         let p = Self::get();
+        let hosts_len: usize = hosts.len();
         let active_hosts: ColoredString = format!("{hosts_len} active hosts").bold().green();
         let total_time: ColoredString = format!("{:.2}s", total_time.as_secs_f64()).bold().yellow();
         let output: &ColoredString =
@@ -137,6 +350,7 @@ impl Print {
             0 => {
                 divider();
                 centerln(output);
+                Self::latency_histogram(hosts);
             }
             _ => {
                 zprint!();
@@ -145,6 +359,284 @@ impl Print {
         }
     }
 
+    /// Prints one tree per mDNS responder, reflectors marked as such.
+    pub fn mdns_survey(survey: &MdnsSurvey) {
+        let p = Self::get();
+        for (idx, responder) in survey.responders.iter().enumerate() {
+            tree_head(idx, &responder.ip.to_string());
+
+            let mut details: Vec<Detail> = Vec::new();
+            details.extend(format::mac_to_detail(&Some(responder.mac), p.redact));
+            details.extend(format::hostname_to_detail(
+                &responder.hostname,
+                p.redact,
+                p.raw_hostnames,
+                &p.search_domains,
+            ));
+            details.extend(format::announced_to_detail(&responder.announced, p.redact));
+            details.push((
+                "Answers".to_string(),
+                format!("{}, {} repeated", responder.responses, responder.repeated)
+                    .color(colors::TEXT_DEFAULT),
+            ));
+            let role: ColoredString = match responder.is_reflector() {
+                true => "reflector".yellow().bold(),
+                false => "responder".color(colors::TEXT_DEFAULT),
+            };
+            details.push(("Role".to_string(), role));
+            as_tree(details);
+
+            if idx + 1 != survey.responders.len() {
+                zprint!();
+            }
+        }
+    }
+
+    /// Prints the completion line of an mDNS survey.
+    pub fn mdns_summary(survey: &MdnsSurvey, total_time: Duration) {
+        let p = Self::get();
+        let reflectors: usize = survey
+            .responders
+            .iter()
+            .filter(|responder| responder.is_reflector())
+            .count();
+        let responders: ColoredString = format!("{} responders", survey.responders.len())
+            .bold()
+            .green();
+        let reflectors: ColoredString = match reflectors {
+            0 => "no reflectors".normal(),
+            n => format!("{n} reflector(s)").bold().yellow(),
+        };
+        let total_time: ColoredString = format!("{:.2}s", total_time.as_secs_f64()).bold().yellow();
+        let output: &ColoredString = &format!(
+            "mDNS Survey Complete: {responders}, {reflectors} on {} in {total_time}",
+            survey.interface
+        )
+        .color(colors::TEXT_DEFAULT);
+
+        match p.q_level {
+            0 => {
+                divider();
+                centerln(output);
+            }
+            _ => {
+                zprint!();
+                success!("{output}")
+            }
+        }
+    }
+
+    /// Prints the verdict on each protocol of an IP protocol scan.
+    pub fn protocol_survey(survey: &ProtocolSurvey) {
+        for (idx, result) in survey.results.iter().enumerate() {
+            let name: String = match result.name() {
+                Some(name) => format!("{name} ({})", result.number),
+                None => format!("protocol {}", result.number),
+            };
+            tree_head(idx, &name);
+
+            let state: ColoredString = match result.state {
+                ProtocolState::Supported => "supported".green().bold(),
+                ProtocolState::Unsupported => "unsupported".red(),
+                ProtocolState::Filtered => "filtered".yellow().bold(),
+                ProtocolState::Silent => "no answer".color(colors::TEXT_DEFAULT),
+            };
+            let mut details: Vec<Detail> = vec![("State".to_string(), state)];
+            if let Some(reporter) = result.reporter {
+                details.push((
+                    "Reporter".to_string(),
+                    reporter.to_string().color(colors::IPV4_ADDR),
+                ));
+            }
+            as_tree(details);
+
+            if idx + 1 != survey.results.len() {
+                zprint!();
+            }
+        }
+    }
+
+    /// Prints the changes of one device across stored reports, and how often
+    /// it was in them.
+    pub fn timeline(history: &Timeline) {
+        let p = Self::get();
+        timeline::render(history, p.redact)
+            .iter()
+            .for_each(|line| zprint!("{line}"));
+
+        let (Some(first), Some(last)) = (history.first_seen, history.last_seen) else {
+            return;
+        };
+        let seen: ColoredString = format!("{} of {}", history.sightings, history.reports)
+            .bold()
+            .green();
+        let mac: String = match history.mac {
+            Some(mac) if p.redact => format!(", followed by MAC {}", redact::mac_addr(&mac)),
+            Some(mac) => format!(", followed by MAC {mac}"),
+            None => String::new(),
+        };
+        zprint!();
+        success!(
+            "{}",
+            format!(
+                "Seen in {seen} reports from {} to {}{mac}",
+                UtcMinute::from_unix(first),
+                UtcMinute::from_unix(last)
+            )
+            .color(colors::TEXT_DEFAULT)
+        );
+    }
+
+    /// Prints a scheduled scan: its schedule, next run and kept reports.
+    pub fn scheduled_job(job: &Job, next: Option<UtcMinute>, reports: &[PathBuf]) {
+        tree_head(job.id as usize, &format!("zond {}", job.args.join(" ")));
+
+        let next: ColoredString = match next {
+            Some(next) => next.to_string().color(colors::TEXT_DEFAULT),
+            None => "never".red(),
+        };
+        let mut details: Vec<Detail> = vec![
+            ("Schedule".to_string(), job.cron.color(colors::TEXT_DEFAULT)),
+            ("Next run".to_string(), next),
+            (
+                "Reports".to_string(),
+                format!("{} of {} kept", reports.len(), job.keep).color(colors::TEXT_DEFAULT),
+            ),
+        ];
+        if let Some(latest) = reports.last() {
+            details.push((
+                "Latest".to_string(),
+                latest.display().to_string().color(colors::TEXT_DEFAULT),
+            ));
+        }
+        as_tree(details);
+    }
+
+    /// Prints the completion line of an IP protocol scan.
+    pub fn protocol_summary(survey: &ProtocolSurvey, total_time: Duration) {
+        let p = Self::get();
+        let supported: usize = survey
+            .results
+            .iter()
+            .filter(|result| result.state == ProtocolState::Supported)
+            .count();
+        let supported: ColoredString = format!("{supported}/{} supported", survey.results.len())
+            .bold()
+            .green();
+        let total_time: ColoredString = format!("{:.2}s", total_time.as_secs_f64()).bold().yellow();
+        let output: &ColoredString = &format!(
+            "Protocol Scan Complete: {supported} by {} via {} in {total_time}",
+            survey.target, survey.interface
+        )
+        .color(colors::TEXT_DEFAULT);
+
+        match p.q_level {
+            0 => {
+                divider();
+                centerln(output);
+            }
+            _ => {
+                zprint!();
+                success!("{output}")
+            }
+        }
+    }
+
+    /// Prints each environment check, what it found and how to fix it.
+    pub fn checks(checks: &[Check]) {
+        for (idx, check) in checks.iter().enumerate() {
+            tree_head(idx, &check.name);
+
+            let status: ColoredString = match check.status {
+                Status::Pass => "pass".green().bold(),
+                Status::Warn => "warning".yellow().bold(),
+                Status::Fail => "fail".red().bold(),
+            };
+            let mut details: Vec<Detail> = vec![
+                ("Status".to_string(), status),
+                (
+                    "Found".to_string(),
+                    check.detail.color(colors::TEXT_DEFAULT),
+                ),
+            ];
+            if let Some(hint) = &check.hint {
+                details.push(("Fix".to_string(), hint.color(colors::TEXT_DEFAULT)));
+            }
+            as_tree(details);
+
+            if idx + 1 != checks.len() {
+                zprint!();
+            }
+        }
+    }
+
+    /// Prints the completion line of `zond doctor`.
+    pub fn checks_summary(checks: &[Check]) {
+        let p = Self::get();
+        let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+        let passed: ColoredString = format!("{} passed", count(Status::Pass)).bold().green();
+        let warned: ColoredString = match count(Status::Warn) {
+            0 => "no warnings".normal(),
+            n => format!("{n} warning(s)").bold().yellow(),
+        };
+        let failed: ColoredString = match count(Status::Fail) {
+            0 => "none failed".normal(),
+            n => format!("{n} failed").bold().red(),
+        };
+        let output: &ColoredString =
+            &format!("Environment Check Complete: {passed}, {warned}, {failed}")
+                .color(colors::TEXT_DEFAULT);
+
+        match p.q_level {
+            0 => {
+                divider();
+                centerln(output);
+            }
+            _ => {
+                zprint!();
+                success!("{output}")
+            }
+        }
+    }
+
+    /// Prints the RTT histogram of `hosts`, if any of them has round trips.
+    fn latency_histogram(hosts: &[Host]) {
+        let histogram: Vec<String> = histogram::render(hosts);
+        if !histogram.is_empty() {
+            Self::header("latency");
+            histogram.iter().for_each(|line| zprint!("{line}"));
+        }
+    }
+
+    /// Lists the targets a router reported unreachable instead of answering,
+    /// with the reason and the router. They are not counted as hosts.
+    pub fn unreachable(unreachable: &[Unreachable]) {
+        let p = Self::get();
+        if unreachable.is_empty() || p.q_level == 2 {
+            return;
+        }
+        let show = |ip: IpAddr| match ip {
+            IpAddr::V4(ipv4) => ipv4.to_string(),
+            IpAddr::V6(ipv6) => format::ipv6_to_string(&ipv6, p.redact),
+        };
+        Self::header("unreachable");
+        for (idx, target) in unreachable.iter().enumerate() {
+            let last: bool = idx + 1 == unreachable.len();
+            let branch: ColoredString = if !last { "├─" } else { "└─" }.bright_black();
+            zprint!(
+                " {} {} {}",
+                branch,
+                show(target.target).color(colors::PRIMARY),
+                format!(
+                    "unreachable ({}) via {}",
+                    target.reason.label(),
+                    show(target.reporter)
+                )
+                .yellow()
+            );
+        }
+    }
+
     /// Prints the fallback output when zero hosts are detected during a scan.
     pub fn no_results() {
         let p = Self::get();
@@ -166,6 +658,29 @@ impl Print {
     }
 }
 
+/// Groups `hosts` by vendor, largest group first and ties by name. Hosts
+/// without a vendor go last; members keep their order.
+fn group_by_vendor(hosts: &[Host]) -> Vec<(&str, Vec<&Host>)> {
+    let mut groups: Vec<(&str, Vec<&Host>)> = Vec::new();
+    let mut unknown: Vec<&Host> = Vec::new();
+    for host in hosts {
+        let Some(vendor) = host.vendor.as_deref() else {
+            unknown.push(host);
+            continue;
+        };
+        match groups.iter_mut().find(|(name, _)| *name == vendor) {
+            Some((_, members)) => members.push(host),
+            None => groups.push((vendor, vec![host])),
+        }
+    }
+
+    groups.sort_by(|(a, a_hosts), (b, b_hosts)| b_hosts.len().cmp(&a_hosts.len()).then(a.cmp(b)));
+    if !unknown.is_empty() {
+        groups.push(("Unknown vendor", unknown));
+    }
+    groups
+}
+
 /// Prints a horizontal divider line across the standard output width.
 pub fn divider() {
     zprint!("{}", format_centered("", "═", TOTAL_WIDTH));
@@ -0,0 +1,88 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Sanitization for untrusted strings before they reach the terminal.
+//!
+//! Hostnames, vendor names and service banners are all supplied by the network.
+//! Printing them verbatim would let any responder on the wire inject ANSI escape
+//! sequences (cursor movement, colour resets, title changes) or reorder text with
+//! bidirectional overrides. Everything from such a source must pass through
+//! [`escape`] before it is handed to `zprint!`.
+
+use std::fmt::Write;
+
+/// Replaces control and bidirectional-override characters with visible escapes.
+///
+/// Bytes below `0x100` become `\xNN`, every other offending code point becomes
+/// `\u{...}`. All remaining characters are passed through unchanged.
+pub fn escape(input: &str) -> String {
+    let mut escaped: String = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            c if c.is_control() && (c as u32) < 0x100 => {
+                let _ = write!(escaped, "\\x{:02x}", c as u32);
+            }
+            c if c.is_control() || is_bidi_control(c) => {
+                let _ = write!(escaped, "\\u{{{:x}}}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns `true` for the explicit embedding, override and isolate characters.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neutralizes_ansi_sequences() {
+        assert_eq!(escape("\x1b[31mred\x1b[0m"), "\\x1b[31mred\\x1b[0m");
+        // Window title change, terminated by BEL.
+        assert_eq!(escape("\x1b]0;pwned\x07"), "\\x1b]0;pwned\\x07");
+        // Single-byte CSI of the C1 range.
+        assert_eq!(escape("\u{9b}2J"), "\\x9b2J");
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        assert_eq!(escape("nas\r\nfake line"), "nas\\x0d\\x0afake line");
+        assert_eq!(escape("tab\there"), "tab\\x09here");
+        assert_eq!(escape("\0\x7f"), "\\x00\\x7f");
+    }
+
+    #[test]
+    fn escapes_bidirectional_overrides() {
+        assert_eq!(escape("abc\u{202e}gpj.exe"), "abc\\u{202e}gpj.exe");
+        assert_eq!(escape("\u{2066}x\u{2069}"), "\\u{2066}x\\u{2069}");
+    }
+
+    #[test]
+    fn keeps_printable_text() {
+        for text in [
+            "printer.local",
+            "Café 🖨",
+            "日本語",
+            "Brother MFC-L2710DW (Büro 2)",
+        ] {
+            assert_eq!(escape(text), text);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Selection Menus
+//!
+//! A small arrow-key menu on stderr for the interactive modes. Up/Down (or
+//! `k`/`j`) move, a digit jumps to an option, Enter picks, and Esc, `q` or
+//! Ctrl-C cancel. Once picked, the menu collapses into a single answer line.
+
+use anyhow::{bail, ensure};
+use colored::*;
+use console::{Key, Term};
+
+use crate::terminal::colors;
+
+/// One entry of a menu.
+pub struct Choice {
+    pub label: String,
+    /// Shown dimmed after the label.
+    pub detail: String,
+}
+
+/// Asks `question` and returns the index of the option picked, starting at
+/// `default`.
+///
+/// # Errors
+///
+/// Returns an error if stderr is not a terminal, `options` is empty, or the
+/// user cancels.
+pub fn select(question: &str, options: &[Choice], default: usize) -> anyhow::Result<usize> {
+    let term: Term = Term::stderr();
+    ensure!(term.is_term(), "interactive mode needs a terminal");
+    ensure!(
+        !options.is_empty(),
+        "nothing to choose from for '{question}'"
+    );
+
+    let mut current: usize = default.min(options.len() - 1);
+    term.hide_cursor()?;
+    term.write_line(&format!(
+        "{} {}",
+        "?".color(colors::ACCENT).bold(),
+        question.color(colors::TEXT_DEFAULT).bold()
+    ))?;
+
+    let picked: anyhow::Result<usize> = loop {
+        render(&term, options, current)?;
+        let key: Key = term.read_key()?;
+        term.clear_last_lines(options.len())?;
+
+        match key {
+            Key::ArrowUp | Key::Char('k') => {
+                current = current.checked_sub(1).unwrap_or(options.len() - 1);
+            }
+            Key::ArrowDown | Key::Char('j') | Key::Tab => {
+                current = (current + 1) % options.len();
+            }
+            Key::Char(c) if c.is_ascii_digit() => {
+                let idx: usize = c.to_digit(10).unwrap_or(0) as usize;
+                if (1..=options.len()).contains(&idx) {
+                    current = idx - 1;
+                }
+            }
+            Key::Enter => break Ok(current),
+            Key::Escape | Key::CtrlC | Key::Char('q') => break Err(anyhow::anyhow!("cancelled")),
+            _ => {}
+        }
+    };
+    term.show_cursor()?;
+    term.clear_last_lines(1)?;
+
+    let idx: usize = picked?;
+    answer(&term, question, &options[idx].label)?;
+    Ok(idx)
+}
+
+/// Prints `question` with an answer that needed no menu, e.g. because there
+/// was only one option.
+pub fn answer(term: &Term, question: &str, label: &str) -> anyhow::Result<()> {
+    if !term.is_term() {
+        bail!("interactive mode needs a terminal");
+    }
+    term.write_line(&format!(
+        "{} {} {}",
+        "✔".green().bold(),
+        question.color(colors::TEXT_DEFAULT),
+        label.color(colors::PRIMARY)
+    ))?;
+    Ok(())
+}
+
+fn render(term: &Term, options: &[Choice], current: usize) -> anyhow::Result<()> {
+    for (idx, option) in options.iter().enumerate() {
+        let line: String = format!("{}. {}", idx + 1, option.label);
+        let line: ColoredString = match idx == current {
+            true => format!("❯ {line}").color(colors::PRIMARY).bold(),
+            false => format!("  {line}").color(colors::TEXT_DEFAULT),
+        };
+        term.write_line(&format!(
+            "  {line}  {}",
+            option.detail.color(colors::SEPARATOR)
+        ))?;
+    }
+    Ok(())
+}
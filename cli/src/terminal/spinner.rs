@@ -21,21 +21,28 @@
 //! flip between content:
 //!
 //! * **0s - 2s**: Show Status (e.g., "Identified 6 hosts so far...")
-//! * **2s - 5s**: Show Random Tip (e.g., "Did you know you can use -vv?")
+//! * **2s - 5s**: Show Tip (e.g., "Did you know you can use -vv?")
 //! * **Repeat**
+//!
+//! Tips are random unless the run itself suggests a better one, see [`crate::terminal::insights`].
 
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use crate::terminal::insights;
+use crate::terminal::insights::{InsightContext, Insights};
 use colored::*;
 use indicatif::ProgressStyle;
 use tracing::Span;
 use tracing_indicatif::{IndicatifLayer, span_ext::IndicatifSpanExt};
-use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::filter_fn, layer::SubscriberExt, util::SubscriberInitExt,
+};
+use zond_common::logging::{TIMING_TARGET, set_verbosity};
 
 use crate::terminal::{colors, logging};
+use crate::timings::{self, TimingRecorder};
 
 /// Total length of one text cycle (Status + Tip).
 const CYCLE_MS: u128 = 5000;
@@ -48,7 +55,10 @@ const STATUS_MS: u128 = 2000;
 /// 1.  **Filter**: Decides what to log based on `RUST_LOG` or the `-v` flag.
 /// 2.  **Formatter**: Our custom `ZondFormatter` that makes logs look nice.
 /// 3.  **Indicatif**: Ensures logs print *above* the spinner line, not over it.
-pub fn init_logging(verbosity: u8) {
+/// 4.  **Timings**: At `-v` or with `trace_timings` set, records the phase
+///     spans of the run. The returned recorder summarizes them for the phase
+///     table and writes them to `trace_timings`, if given.
+pub fn init_logging(verbosity: u8, trace_timings: Option<&Path>) -> Option<TimingRecorder> {
     #[cfg(target_os = "windows")]
     let _ = colored::control::set_virtual_terminal(true);
 
@@ -67,20 +77,34 @@ pub fn init_logging(verbosity: u8) {
             ]),
     );
 
-    let filter_layer = EnvFilter::try_from_default_env()
+    set_verbosity(verbosity);
+    let mut filter_layer = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info,zond=debug,mio=error"));
+    let (timing_layer, recorder) = (verbosity >= 1 || trace_timings.is_some())
+        .then(|| timings::recorder(trace_timings))
+        .unzip();
+    // Phases are recorded whatever RUST_LOG says about the rest.
+    if timing_layer.is_some()
+        && let Ok(directive) = format!("{TIMING_TARGET}=info").parse()
+    {
+        filter_layer = filter_layer.add_directive(directive);
+    }
 
     let formatting_layer = tracing_subscriber::fmt::layer()
-        .event_format(logging::ZondFormatter {
-            max_verbosity: verbosity,
-        })
+        .event_format(logging::ZondFormatter)
         .with_writer(indicatif_layer.get_stderr_writer());
 
+    // Phase spans are only timed, they get no spinner of their own.
+    let indicatif_layer =
+        indicatif_layer.with_filter(filter_fn(|metadata| metadata.target() != TIMING_TARGET));
+
     tracing_subscriber::registry()
         .with(filter_layer)
         .with(formatting_layer)
         .with(indicatif_layer)
+        .with(timing_layer)
         .init();
+    recorder
 }
 
 /// The actual animation loop running in the background.
@@ -93,7 +117,7 @@ where
     let start_time = tokio::time::Instant::now();
     let mut last_text = String::new();
 
-    let active_insights = insights::get_shuffled_insights();
+    let mut insights = Insights::new();
 
     while running.load(Ordering::Relaxed) {
         interval.tick().await;
@@ -101,8 +125,8 @@ where
         let elapsed_ms = start_time.elapsed().as_millis();
         let cycle_time = elapsed_ms % CYCLE_MS;
 
-        // Rotate through the tips list based on total elapsed time.
-        let tip_index = (elapsed_ms / CYCLE_MS) as usize % active_insights.len();
+        // Each cycle gets its own tip slot.
+        let tip_slot = (elapsed_ms / CYCLE_MS) as usize;
 
         // Should we show the dynamic status or the static tip?
         let show_status = status_fn.is_some() && cycle_time < STATUS_MS;
@@ -110,7 +134,9 @@ where
         let colored_msg: ColoredString = if show_status {
             (status_fn.as_ref().unwrap())()
         } else {
-            active_insights[tip_index]
+            let ctx = InsightContext::capture(start_time.elapsed());
+            insights
+                .pick(tip_slot, &ctx)
                 .italic()
                 .color(colors::TEXT_DEFAULT)
         };
@@ -0,0 +1,127 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Device timeline printed by `zond history`.
+//!
+//! One line per change, oldest first: the time of the report it showed up
+//! in, what kind of change it was and what changed, with additions in green
+//! and removals in red.
+
+use std::net::IpAddr;
+
+use colored::*;
+use zond_common::{
+    models::{
+        port::Protocol,
+        timeline::{Change, Event, Timeline},
+    },
+    parse::cron::UtcMinute,
+    utils::redact,
+};
+
+use crate::terminal::{colors, sanitize};
+
+const KIND_WIDTH: usize = 10;
+
+/// Renders the events of `timeline`, hostnames masked under `redact`.
+pub fn render(timeline: &Timeline, redact: bool) -> Vec<String> {
+    timeline
+        .events
+        .iter()
+        .map(|event| line(event, redact))
+        .collect()
+}
+
+fn line(event: &Event, redact: bool) -> String {
+    let (kind, detail): (ColoredString, String) = match &event.change {
+        Change::FirstSeen {
+            ips,
+            hostname,
+            ports,
+        } => {
+            let mut parts: Vec<String> = vec![ips_list(ips.iter())];
+            parts.extend(hostname.as_deref().map(|name| name_of(name, redact)));
+            if !ports.is_empty() {
+                parts.push(format!("open {}", ports_list(ports)));
+            }
+            ("first seen".green().bold(), parts.join(", "))
+        }
+        Change::Ips { added, removed } => ("address".cyan(), diff(added, removed, ip_text)),
+        Change::Hostname { from, to } => {
+            let name = |name: &Option<String>| {
+                name.as_deref()
+                    .map_or("(none)".to_string(), |name| name_of(name, redact))
+            };
+            ("hostname".cyan(), format!("{} → {}", name(from), name(to)))
+        }
+        Change::Ports { opened, closed } => ("ports".cyan(), diff(opened, closed, port_text)),
+        Change::Gone => (
+            "gone".yellow().bold(),
+            "missing from this report".to_string(),
+        ),
+        Change::Back { missed } => (
+            "back".green(),
+            format!("after {missed} report(s) without it"),
+        ),
+    };
+
+    format!(
+        " {}  {}{} {}",
+        UtcMinute::from_unix(event.at)
+            .to_string()
+            .color(colors::SEPARATOR),
+        kind,
+        " ".repeat(KIND_WIDTH.saturating_sub(kind.chars().count())),
+        detail.color(colors::TEXT_DEFAULT)
+    )
+}
+
+/// `+added -removed`, each in its own color.
+fn diff<T>(added: &[T], removed: &[T], text: fn(&T) -> String) -> String {
+    added
+        .iter()
+        .map(|item| format!("+{}", text(item)).green().to_string())
+        .chain(
+            removed
+                .iter()
+                .map(|item| format!("-{}", text(item)).red().to_string()),
+        )
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn ips_list<'a>(ips: impl Iterator<Item = &'a IpAddr>) -> String {
+    ips.map(ip_text).collect::<Vec<String>>().join(" ")
+}
+
+fn ports_list(ports: &[(u16, Protocol)]) -> String {
+    ports
+        .iter()
+        .map(port_text)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn ip_text(ip: &IpAddr) -> String {
+    ip.to_string()
+}
+
+fn port_text((number, protocol): &(u16, Protocol)) -> String {
+    let protocol: &str = match protocol {
+        Protocol::Tcp => "tcp",
+        Protocol::Udp => "udp",
+        Protocol::Sctp => "sctp",
+    };
+    format!("{number}/{protocol}")
+}
+
+/// Hostnames of saved reports came from the network, so they are escaped.
+fn name_of(name: &str, redact: bool) -> String {
+    match redact {
+        true => redact::hostname(name),
+        false => sanitize::escape(name),
+    }
+}
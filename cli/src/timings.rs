@@ -0,0 +1,311 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Phase Timings
+//!
+//! Backs `--trace-timings FILE` and the phase table printed at `-v`. The
+//! crates open a span per phase with [`zond_common::phase!`]: target parsing,
+//! interface mapping, the send and receive phases of every scanner, hostname
+//! resolution, enrichment and rendering. [`TimingLayer`] notes when each of
+//! them opens and closes.
+//!
+//! [`TimingRecorder::summary`] folds the runs of each phase into one row for
+//! the table, and [`TimingRecorder::save`] writes them as "complete" events of
+//! the Chrome trace event format, which `chrome://tracing`, Perfetto and
+//! speedscope all open.
+//!
+//! The trace carries the [`ScanInfo`] of the run in its `otherData`, so it can
+//! be matched with the report of the same run.
+//!
+//! Phases run concurrently, e.g. one scanner per NIC. The trace format needs the
+//! events of one thread to nest, so overlapping phases are spread over as many
+//! rows (`tid`s) as it takes.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde_json::{Map, Value, json};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+use zond_common::{logging::TIMING_TARGET, models::scan::ScanInfo};
+
+/// A phase that finished, relative to the start of the recording.
+#[derive(Debug, Clone)]
+struct Phase {
+    name: &'static str,
+    start: Duration,
+    end: Duration,
+    args: Map<String, Value>,
+}
+
+/// Kept in the extensions of an open phase span.
+struct Started {
+    start: Duration,
+    args: Map<String, Value>,
+}
+
+/// Records the spans of [`TIMING_TARGET`] as they close.
+pub struct TimingLayer {
+    origin: Instant,
+    phases: Arc<Mutex<Vec<Phase>>>,
+}
+
+/// Holds the phases recorded by a [`TimingLayer`] until they are saved.
+pub struct TimingRecorder {
+    origin: Instant,
+    path: Option<PathBuf>,
+    phases: Arc<Mutex<Vec<Phase>>>,
+}
+
+/// Every run of one phase taken together, a row of the table of `-v`.
+#[derive(Debug, Clone)]
+pub struct PhaseSummary {
+    pub name: &'static str,
+    pub runs: usize,
+    /// How long at least one run was open. Runs in parallel, such as one
+    /// scanner per interface, count once.
+    pub wall: Duration,
+    /// The numeric fields of the runs, such as `hosts`, summed up.
+    pub counts: Vec<(String, u64)>,
+}
+
+/// Creates the layer to install and the recorder that later summarizes its
+/// phases and, given a `path`, writes them there.
+pub fn recorder(path: Option<&Path>) -> (TimingLayer, TimingRecorder) {
+    let origin: Instant = Instant::now();
+    let phases: Arc<Mutex<Vec<Phase>>> = Arc::new(Mutex::new(Vec::new()));
+    let layer: TimingLayer = TimingLayer {
+        origin,
+        phases: Arc::clone(&phases),
+    };
+    let recorder: TimingRecorder = TimingRecorder {
+        origin,
+        path: path.map(Path::to_path_buf),
+        phases,
+    };
+    (layer, recorder)
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().target() != TIMING_TARGET {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut args: ArgsVisitor = ArgsVisitor::default();
+        attrs.record(&mut args);
+        span.extensions_mut().insert(Started {
+            start: self.origin.elapsed(),
+            args: args.0,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(started) = span.extensions_mut().get_mut::<Started>() {
+            let mut args: ArgsVisitor = ArgsVisitor(std::mem::take(&mut started.args));
+            values.record(&mut args);
+            started.args = args.0;
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(started) = span.extensions_mut().remove::<Started>() else {
+            return;
+        };
+        let phase: Phase = Phase {
+            name: span.name(),
+            start: started.start,
+            end: self.origin.elapsed(),
+            args: started.args,
+        };
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.push(phase);
+        }
+    }
+}
+
+impl TimingRecorder {
+    /// Time since recording started, about the run time of the process.
+    pub fn elapsed(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    /// Folds the phases that finished so far into one row per name, in the
+    /// order they first started.
+    pub fn summary(&self) -> Vec<PhaseSummary> {
+        let mut phases: Vec<Phase> = self.finished();
+        phases.sort_by_key(|phase| phase.start);
+
+        let mut names: Vec<&'static str> = Vec::new();
+        for phase in &phases {
+            if !names.contains(&phase.name) {
+                names.push(phase.name);
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                let runs: Vec<&Phase> = phases.iter().filter(|p| p.name == name).collect();
+                PhaseSummary {
+                    name,
+                    runs: runs.len(),
+                    wall: wall_time(&runs),
+                    counts: sum_counts(&runs),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the phases that finished so far to the file given on the
+    /// command line, if one was.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let trace: Value = to_trace(&mut self.finished());
+        fs::write(path, serde_json::to_vec_pretty(&trace)?)
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+
+    fn finished(&self) -> Vec<Phase> {
+        self.phases
+            .lock()
+            .map(|phases| phases.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// The time covered by at least one of `runs`, which are sorted by start.
+fn wall_time(runs: &[&Phase]) -> Duration {
+    let mut wall: Duration = Duration::ZERO;
+    let mut covered_until: Duration = Duration::ZERO;
+    for run in runs {
+        let start: Duration = run.start.max(covered_until);
+        if run.end > start {
+            wall += run.end - start;
+            covered_until = run.end;
+        }
+    }
+    wall
+}
+
+/// Sums the numeric fields of `runs` by name, in the order they first appear.
+fn sum_counts(runs: &[&Phase]) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = Vec::new();
+    for (key, value) in runs.iter().flat_map(|run| &run.args) {
+        let Some(value) = value.as_u64() else {
+            continue;
+        };
+        match counts.iter_mut().find(|(known, _)| known == key) {
+            Some((_, sum)) => *sum += value,
+            None => counts.push((key.clone(), value)),
+        }
+    }
+    counts
+}
+
+/// Builds the trace document, ordering `phases` by start.
+fn to_trace(phases: &mut [Phase]) -> Value {
+    phases.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+    let lanes: Vec<usize> = assign_lanes(phases);
+
+    let events: Vec<Value> = phases
+        .iter()
+        .zip(lanes)
+        .map(|(phase, lane)| {
+            json!({
+                "name": phase.name,
+                "cat": "zond",
+                "ph": "X",
+                "ts": phase.start.as_micros() as u64,
+                "dur": (phase.end - phase.start).as_micros() as u64,
+                "pid": 1,
+                "tid": lane + 1,
+                "args": phase.args,
+            })
+        })
+        .collect();
+    let mut trace: Value = json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+    if let Some(scan) = ScanInfo::session() {
+        trace["otherData"] = json!({ "scan": scan });
+    }
+    trace
+}
+
+/// Puts every phase on the first row where it either follows or nests inside
+/// the phases already there. `phases` must be sorted by start, longest first.
+fn assign_lanes(phases: &[Phase]) -> Vec<usize> {
+    // The end times of the phases still open on each row, innermost last.
+    let mut rows: Vec<Vec<Duration>> = Vec::new();
+
+    phases
+        .iter()
+        .map(|phase| {
+            for (idx, open) in rows.iter_mut().enumerate() {
+                while open.last().is_some_and(|end| *end <= phase.start) {
+                    open.pop();
+                }
+                if open.last().is_none_or(|end| *end >= phase.end) {
+                    open.push(phase.end);
+                    return idx;
+                }
+            }
+            rows.push(vec![phase.end]);
+            rows.len() - 1
+        })
+        .collect()
+}
+
+/// Collects the fields of a phase span as trace event arguments.
+#[derive(Default)]
+struct ArgsVisitor(Map<String, Value>);
+
+impl Visit for ArgsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{value:?}")));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+}
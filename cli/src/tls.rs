@@ -0,0 +1,85 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # TLS Between Agents and Collector
+//!
+//! Agents send their bearer token and full host lists to the collector, so
+//! anything beyond the loopback interface goes over TLS. The collector loads
+//! its certificate chain and key from PEM files (`--tls-cert`, `--tls-key`),
+//! and agents trust the certificate authorities in `--tls-ca`, which for a
+//! self-signed collector is its certificate itself.
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context, anyhow, ensure};
+use tokio_rustls::{
+    TlsAcceptor, TlsConnector,
+    rustls::{
+        ClientConfig, RootCertStore, ServerConfig,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+    },
+};
+
+/// Builds the acceptor of a collector serving the chain in `cert` with the
+/// private key in `key`.
+///
+/// # Errors
+///
+/// Returns an error if either file cannot be read or parsed, or the key does
+/// not match the certificate.
+pub fn acceptor(cert: &Path, key: &Path) -> anyhow::Result<TlsAcceptor> {
+    let chain: Vec<CertificateDer<'static>> = certificates(cert)?;
+    let key: PrivateKeyDer<'static> = PrivateKeyDer::from_pem_file(key)
+        .with_context(|| format!("failed to read a private key from {}", key.display()))?;
+
+    let config: ServerConfig = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(chain, key)
+        .context("the TLS certificate and key do not fit together")?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Builds the connector of an agent trusting the certificates in `ca`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or holds no usable certificate.
+pub fn connector(ca: &Path) -> anyhow::Result<TlsConnector> {
+    let mut roots: RootCertStore = RootCertStore::empty();
+    for cert in certificates(ca)? {
+        roots
+            .add(cert)
+            .with_context(|| format!("unusable certificate in {}", ca.display()))?;
+    }
+
+    let config: ClientConfig = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// The name the certificate of the collector at `HOST:PORT` must carry.
+///
+/// # Errors
+///
+/// Returns an error if `collector` has no valid host part.
+pub fn server_name(collector: &str) -> anyhow::Result<ServerName<'static>> {
+    let host: &str = collector
+        .rsplit_once(':')
+        .map_or(collector, |(host, _)| host)
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    ServerName::try_from(host.to_string())
+        .map_err(|_| anyhow!("{collector} does not start with a valid host name"))
+}
+
+fn certificates(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let certs: Vec<CertificateDer<'static>> = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect())
+        .with_context(|| format!("failed to read certificates from {}", path.display()))?;
+    ensure!(!certs.is_empty(), "{} holds no certificate", path.display());
+    Ok(certs)
+}
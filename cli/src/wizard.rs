@@ -0,0 +1,173 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Interactive Target Builder
+//!
+//! Backs `zond discover --interactive`: instead of typing a target, the user
+//! picks an interface, one of its subnets and a discovery technique from menus
+//! ([`select`]). The result is an ordinary discovery, and the equivalent
+//! command line is printed so the same scan can be scripted.
+
+use console::Term;
+use pnet::{
+    datalink::NetworkInterface,
+    ipnetwork::{IpNetwork, Ipv4Network},
+};
+use zond_common::config::Technique;
+
+use crate::terminal::select::{self, Choice};
+
+/// Techniques offered, in menu order, with what they mean in practice.
+const TECHNIQUES: &[(Technique, &str)] = &[
+    (Technique::Auto, "ARP on the local link, TCP SYN beyond it"),
+    (Technique::Arp, "local link only, nothing above Layer 2"),
+    (
+        Technique::Icmp,
+        "echo requests, for networks that flag SYN sweeps",
+    ),
+    (Technique::Syn, "TCP SYN everywhere, also on the local link"),
+];
+
+/// The scan the user put together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoverPlan {
+    /// The subnet to discover, as a CIDR block.
+    pub target: String,
+    pub technique: Technique,
+}
+
+impl DiscoverPlan {
+    /// Renders the command line that runs the same scan without the menus.
+    ///
+    /// Other flags of the original invocation in `args` are kept, except
+    /// `--interactive` and any `--technique`.
+    pub fn command_line(&self, args: &[String]) -> String {
+        let mut parts: Vec<String> = vec!["zond".to_string()];
+        let mut rest = args.iter().skip(1);
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--interactive" => {}
+                "--technique" => {
+                    rest.next();
+                }
+                _ if arg.starts_with("--technique=") => {}
+                _ => parts.push(quote(arg)),
+            }
+        }
+
+        parts.push(self.target.clone());
+        if self.technique != Technique::Auto {
+            parts.push(format!("--technique {}", self.technique));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Walks the user through picking an interface, a subnet and a technique,
+/// starting at `technique`.
+///
+/// # Errors
+///
+/// Returns an error if there is no terminal, no interface with an IPv4
+/// subnet, or the user cancels.
+pub fn discover_plan(technique: Technique) -> anyhow::Result<DiscoverPlan> {
+    let interfaces: Vec<NetworkInterface> = pnet::datalink::interfaces()
+        .into_iter()
+        .filter(|iface| iface.is_up() && !iface.is_loopback() && !subnets(iface).is_empty())
+        .collect();
+    if interfaces.is_empty() {
+        anyhow::bail!("no interface with an IPv4 subnet is up");
+    }
+
+    let iface: &NetworkInterface = match interfaces.as_slice() {
+        [only] => {
+            select::answer(&Term::stderr(), "Interface", &only.name)?;
+            only
+        }
+        _ => {
+            let choices: Vec<Choice> = interfaces
+                .iter()
+                .map(|iface| Choice {
+                    label: iface.name.clone(),
+                    detail: describe(iface),
+                })
+                .collect();
+            &interfaces[select::select("Interface", &choices, 0)?]
+        }
+    };
+
+    let subnets: Vec<Ipv4Network> = subnets(iface);
+    let subnet: Ipv4Network = match subnets.as_slice() {
+        [only] => {
+            select::answer(&Term::stderr(), "Subnet", &only.to_string())?;
+            *only
+        }
+        _ => {
+            let choices: Vec<Choice> = subnets
+                .iter()
+                .map(|net| Choice {
+                    label: net.to_string(),
+                    detail: format!("{} addresses", net.size()),
+                })
+                .collect();
+            subnets[select::select("Subnet", &choices, 0)?]
+        }
+    };
+
+    let choices: Vec<Choice> = TECHNIQUES
+        .iter()
+        .map(|(technique, detail)| Choice {
+            label: technique.to_string(),
+            detail: detail.to_string(),
+        })
+        .collect();
+    let default: usize = TECHNIQUES
+        .iter()
+        .position(|(t, _)| *t == technique)
+        .unwrap_or(0);
+    let technique: Technique = TECHNIQUES[select::select("Technique", &choices, default)?].0;
+
+    Ok(DiscoverPlan {
+        target: subnet.to_string(),
+        technique,
+    })
+}
+
+/// Returns the IPv4 subnets of `iface`, normalized to their network address.
+fn subnets(iface: &NetworkInterface) -> Vec<Ipv4Network> {
+    let mut subnets: Vec<Ipv4Network> = iface
+        .ips
+        .iter()
+        .filter_map(|net| match net {
+            IpNetwork::V4(net) => Ipv4Network::new(net.network(), net.prefix()).ok(),
+            IpNetwork::V6(_) => None,
+        })
+        .collect();
+    subnets.dedup();
+    subnets
+}
+
+fn describe(iface: &NetworkInterface) -> String {
+    let mut parts: Vec<String> = iface
+        .ips
+        .iter()
+        .filter(|net| net.is_ipv4())
+        .map(|net| net.to_string())
+        .collect();
+    if let Some(mac) = iface.mac {
+        parts.push(mac.to_string());
+    }
+    parts.join(", ")
+}
+
+/// Quotes `arg` for POSIX shells if it holds anything but plain characters.
+fn quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=@,%+".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
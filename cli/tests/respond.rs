@@ -0,0 +1,62 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Discovery against fake hosts of `zond respond`, which only debug builds
+//! carry. The binary under test is the one Cargo just built for this package.
+
+#![cfg(all(target_os = "linux", debug_assertions))]
+
+#[path = "../../tests/src/utils/netns.rs"]
+mod netns;
+
+use std::net::IpAddr;
+use std::path::Path;
+
+use netns::NetnsContext;
+use zond_common::config::ZondConfig;
+use zond_common::models::ip::set::IpSet;
+use zond_core::scanner::{self, responder};
+
+#[tokio::test]
+async fn privileged_discovery_fake_hosts_dedup() {
+    let ctx: NetnsContext = match NetnsContext::new("respond") {
+        Some(c) => c,
+        None => {
+            eprintln!("Skipping responder test: Requires root privileges or 'ip' command.");
+            return;
+        }
+    };
+    // Every answer arrives twice and late; each host must still show up once.
+    let _responder = ctx.spawn_responder(
+        Path::new(env!("CARGO_BIN_EXE_zond")),
+        "10.200.0.50-10.200.0.59",
+        &["--copies", "2", "--delay", "50"],
+    );
+
+    let config: ZondConfig = ZondConfig {
+        no_banner: true,
+        no_dns: true,
+        redact: false,
+        quiet: 0,
+        disable_input: true,
+        ..Default::default()
+    };
+
+    let mut collection = IpSet::new();
+    collection.insert_range("10.200.0.50-10.200.0.59".parse().unwrap());
+
+    let hosts = scanner::discover(collection, &config)
+        .await
+        .expect("Discovery failed");
+
+    assert_eq!(hosts.len(), 10, "Each fake host should be reported once");
+    for host in &hosts {
+        let IpAddr::V4(ip) = host.primary_ip else {
+            panic!("Unexpected IPv6 host {}", host.primary_ip);
+        };
+        assert_eq!(host.mac, Some(responder::fake_mac(ip)));
+    }
+}
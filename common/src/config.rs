@@ -4,6 +4,8 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+use std::{fmt, net::IpAddr, ops::RangeInclusive, str::FromStr, time::Duration};
+
 /// Global configuration options for the scanner execution.
 ///
 /// This struct controls the runtime behavior of the application, including
@@ -28,6 +30,13 @@ pub struct ZondConfig {
     /// processing incoming DNS packets if they were initiated elsewhere.
     pub no_dns: bool,
 
+    /// Sends PTR lookups for public addresses too.
+    ///
+    /// By default only private, shared and loopback addresses are resolved,
+    /// since reverse lookups of public space go to third-party DNS servers
+    /// whose operators can see what is being scanned.
+    pub resolve_public: bool,
+
     /// Enables privacy mode for sensitive data in the output.
     ///
     /// When enabled, personally identifiable information (PII) or sensitive
@@ -41,6 +50,26 @@ pub struct ZondConfig {
     /// Use this when sharing screenshots or logs publicly.
     pub redact: bool,
 
+    /// Replaces hostnames, MAC addresses and IPs with consistent fakes.
+    ///
+    /// Unlike [`redact`](Self::redact), the output still looks like a real
+    /// network, which suits talks and streams. See [`crate::utils::demo`].
+    pub demo: bool,
+
+    /// Displays hostnames exactly as they were received.
+    ///
+    /// By default, internationalized labels in their ASCII form (`xn--...`) are
+    /// decoded to Unicode before printing. Control characters are escaped
+    /// regardless of this setting.
+    pub raw_hostnames: bool,
+
+    /// DNS domains cut off hostnames for display, so with `lan.example.com`
+    /// the host `printer.lan.example.com` shows as `printer`.
+    ///
+    /// Empty unless asked for; reports and exports always keep the full name.
+    /// See [`crate::utils::domain`].
+    pub search_domains: Vec<String>,
+
     /// Controls the visual density and formatting of the terminal output.
     ///
     /// This value is typically mapped from the `-q` or `--quiet` CLI flags.
@@ -61,4 +90,190 @@ pub struct ZondConfig {
     /// * Running as a background system service (daemon).
     /// * Non-interactive testing environments.
     pub disable_input: bool,
+
+    /// Treats a scan without any responding hosts as a failure.
+    ///
+    /// By default an empty result is a successful run. With this enabled the CLI
+    /// exits with a dedicated non-zero code instead, so wrapper scripts can tell
+    /// "nothing is up" apart from "the scan worked".
+    pub fail_on_empty: bool,
+
+    /// Starts large scans without asking for confirmation first.
+    ///
+    /// Interactive runs show the estimated impact of a large scan and wait for
+    /// a yes; unattended runs refuse it unless this is set.
+    pub assume_yes: bool,
+
+    /// Refuses scans of more addresses than this outright, confirmed or not.
+    ///
+    /// Guards against a mistyped prefix turning a /24 into a /8. `None` lifts
+    /// the limit.
+    pub max_targets: Option<u64>,
+
+    /// How long hostname lookups may still run once scanning finished.
+    ///
+    /// PTR answers arriving later are dropped. `None` uses the resolver's
+    /// built-in default.
+    pub resolve_budget: Option<Duration>,
+
+    /// How much memory the records of unanswered probes may take, in bytes.
+    ///
+    /// Routed scanners move records past this limit to disk, see
+    /// [`paths::spill_dir`](crate::utils::paths::spill_dir). `None` uses the
+    /// scanners' built-in default.
+    pub memory_limit: Option<u64>,
+
+    /// Source ports privileged TCP probes are sent from.
+    ///
+    /// Every probe picks a random port from this range. `None` uses the scanner's
+    /// built-in default.
+    pub source_ports: Option<RangeInclusive<u16>>,
+
+    /// Restricts host discovery to a single probing method.
+    ///
+    /// [`Technique::Auto`] lets the scanner pick per target; every other value is
+    /// strict, failing the scan instead of silently falling back to another method.
+    pub technique: Technique,
+
+    /// Destination ports of TCP SYN discovery probes, each target gets one SYN
+    /// per port. Empty uses the scanner's built-in default.
+    pub syn_ports: Vec<u16>,
+
+    /// Also send SCTP INITs, for hosts that only expose SCTP services.
+    ///
+    /// Discovery probes common telecom ports on top of the chosen technique; a
+    /// port scan probes the TCP port numbers over SCTP as well. Needs raw
+    /// sockets.
+    pub sctp: bool,
+
+    /// Print decoded probes and replies per host after each scanner finishes.
+    pub packet_trace: bool,
+
+    /// Save the frames a scanner received last to a pcap file when they
+    /// suddenly stop parsing. Never done under `redact` or `demo`.
+    pub capture_errors: bool,
+
+    /// Listen passively for this long before discovery and skip probing the
+    /// on-link hosts heard in the meantime. `None` probes every target.
+    pub passive_warmup: Option<Duration>,
+
+    /// Leave interfaces out of promiscuous mode while listening passively, so
+    /// only broadcasts, multicasts and frames for this machine are captured.
+    pub no_promiscuous: bool,
+
+    /// Send ARP probes from a random locally administered MAC address instead
+    /// of the interface's own, with an empty sender address.
+    pub random_mac: bool,
+
+    /// Probe this known-good host, typically the gateway, throughout discovery
+    /// to tell slow hosts from a network congested by the scan itself.
+    pub baseline: Option<IpAddr>,
+
+    /// Check discovered hosts for Active Directory markers and tag domain
+    /// controllers.
+    pub detect_ad: bool,
+
+    /// Run the external `zond-enrich-*` enrichers found on `PATH`.
+    pub plugins: bool,
+
+    /// Names of enrichment stages to leave out after discovery or a port scan,
+    /// e.g. `leases` or `printers`.
+    pub disabled_enrichers: Vec<String>,
+
+    /// Timeout and retries of TCP connect probes.
+    pub timing: Timing,
+
+    /// Probe a sample of the targets before scanning and replace
+    /// [`timing`](Self::timing) with values fitting the measured loss and latency.
+    pub calibrate: bool,
+
+    /// Probe the addresses of this machine that fall inside a target range.
+    ///
+    /// By default they are skipped, since the answers come back through the
+    /// loopback path rather than the network. An own address targeted on its
+    /// own is always probed. Either way, a host answering from one of them is
+    /// tagged with [`NetworkRole::Scanner`](crate::models::host::NetworkRole::Scanner).
+    pub include_self: bool,
+
+    /// Keep the network and broadcast addresses of CIDR targets.
+    ///
+    /// By default `10.0.0.0/24` stands for `10.0.0.1` to `10.0.0.254`, like
+    /// the `lan` keyword. Explicit ranges are always taken as given.
+    pub include_broadcast: bool,
+
+    /// Print hosts grouped under their vendor, each group headed by its count.
+    ///
+    /// Hosts are listed one line each unless [`group_details`](Self::group_details)
+    /// is set. Hosts without a known vendor form the last group.
+    pub by_vendor: bool,
+
+    /// Keep the full tree of every host inside vendor groups.
+    pub group_details: bool,
+}
+
+/// How patiently TCP connect probes wait for an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// Time a connection attempt may take before the port counts as silent.
+    pub probe_timeout: Duration,
+    /// Further attempts for a port that stayed silent.
+    pub retries: u8,
+}
+
+impl Timing {
+    /// Suits a healthy LAN or a nearby routed network.
+    pub const DEFAULT: Timing = Timing {
+        probe_timeout: Duration::from_millis(1000),
+        retries: 0,
+    };
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Probing method used for host discovery.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Technique {
+    /// ARP/NDP for on-link targets, TCP SYN for routed ones, TCP connect when
+    /// unprivileged.
+    #[default]
+    Auto,
+    /// ARP only. Never leaves the local segment and has no Layer 3 footprint, so
+    /// it can only reach on-link IPv4 targets.
+    Arp,
+    /// ICMPv4 echo requests only, for networks where TCP SYN sweeps trigger an IDS.
+    Icmp,
+    /// TCP SYN only, including on-link targets.
+    Syn,
+}
+
+impl FromStr for Technique {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "arp" => Ok(Self::Arp),
+            "icmp" => Ok(Self::Icmp),
+            "syn" => Ok(Self::Syn),
+            other => Err(format!(
+                "unknown technique '{other}' (expected auto, arp, icmp or syn)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            Self::Auto => "auto",
+            Self::Arp => "arp",
+            Self::Icmp => "icmp",
+            Self::Syn => "syn",
+        };
+        f.write_str(name)
+    }
 }
@@ -9,5 +9,6 @@ pub mod logging;
 pub mod models;
 pub mod net;
 pub mod parse;
+pub mod schema;
 pub mod sender;
 pub mod utils;
@@ -9,6 +9,34 @@
 //! do not depend on tracing directrly, making it easy to swap
 //! our way of logging more easily in the future if needed.
 
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Highest verbosity that changes the output (`-vv`).
+pub const MAX_VERBOSITY: u8 = 2;
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the verbosity events are filtered by.
+pub fn verbosity() -> u8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Sets the verbosity events are filtered by, e.g. from `-v`.
+pub fn set_verbosity(level: u8) {
+    VERBOSITY.store(level, Ordering::Relaxed);
+}
+
+/// Steps to the next verbosity, wrapping from [`MAX_VERBOSITY`] back to 0, and
+/// returns it.
+pub fn cycle_verbosity() -> u8 {
+    let next: u8 = match verbosity() {
+        level if level >= MAX_VERBOSITY => 0,
+        level => level + 1,
+    };
+    set_verbosity(next);
+    next
+}
+
 #[macro_export]
 macro_rules! info {
     ($($arg:tt)+) => {
@@ -43,3 +71,18 @@ macro_rules! warn {
         tracing::warn!(status = "warn", $($arg)+)
     };
 }
+
+/// Target of the spans opened by [`phase!`].
+pub const TIMING_TARGET: &str = "zond::timing";
+
+/// Opens a span that times one phase of a run for `--trace-timings` and the
+/// phase table of `-v`.
+///
+/// The phase ends when the returned span is dropped. It never has to be
+/// entered, so it may be held across `.await` points.
+#[macro_export]
+macro_rules! phase {
+    ($($arg:tt)+) => {
+        tracing::info_span!(target: "zond::timing", $($arg)+)
+    };
+}
@@ -4,9 +4,15 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+mod codec;
+pub mod device;
 pub mod fingerprint;
 pub mod host;
 pub mod ip;
 pub mod localhost;
 pub mod port;
+pub mod reachability;
+pub mod scan;
 pub mod target;
+pub mod timeline;
+pub mod topology;
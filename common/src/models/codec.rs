@@ -0,0 +1,79 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Serde helpers for model fields whose types have no stable representation of
+//! their own. Field names and formats follow the published report schema
+//! ([`crate::schema`]).
+
+/// `Option<MacAddr>` as a lowercase `aa:bb:cc:dd:ee:ff` string or `null`.
+pub(crate) mod mac {
+    use pnet::util::MacAddr;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(mac: &Option<MacAddr>, s: S) -> Result<S::Ok, S::Error> {
+        match mac {
+            Some(mac) => s.serialize_some(&mac.to_string()),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<MacAddr>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|mac| {
+                mac.parse()
+                    .map_err(|_| D::Error::custom(format!("invalid mac '{mac}'")))
+            })
+            .transpose()
+    }
+}
+
+/// RTT history as milliseconds with microsecond precision, oldest first.
+pub(crate) mod rtts_ms {
+    use std::{collections::VecDeque, time::Duration};
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    /// Matches the `maxItems` of `rtts_ms` in the schema.
+    const MAX_RTTS: usize = 10;
+
+    pub fn serialize<S: Serializer>(rtts: &VecDeque<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(
+            rtts.iter()
+                .map(|rtt| (rtt.as_secs_f64() * 1_000_000.0).round() / 1_000.0),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<VecDeque<Duration>, D::Error> {
+        let millis: Vec<f64> = Vec::deserialize(d)?;
+        let skip: usize = millis.len().saturating_sub(MAX_RTTS);
+
+        millis
+            .into_iter()
+            .skip(skip)
+            .map(|ms| {
+                if ms.is_finite() && ms >= 0.0 {
+                    Ok(Duration::from_secs_f64(ms / 1_000.0))
+                } else {
+                    Err(D::Error::custom(format!("invalid round-trip time {ms}")))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Ports sorted by number without duplicates, as [`Host`](super::host::Host) keeps them.
+pub(crate) mod ports {
+    use serde::{Deserialize, Deserializer};
+
+    use crate::models::port::Port;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Port>, D::Error> {
+        let mut ports: Vec<Port> = Vec::deserialize(d)?;
+        ports.sort_by_key(|port| port.number);
+        ports.dedup_by_key(|port| port.number);
+        Ok(ports)
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Device Types
+//!
+//! Guesses what kind of device a [`Host`] is. What the device says about
+//! itself wins: the model in its mDNS TXT records, then the [`dhcp`]
+//! fingerprint of its DHCP client, then the vendor class that client sends.
+//! Roles derived from open ports come last, since a phone can run a web server
+//! but rarely speaks IPP.
+
+pub mod dhcp;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::host::{Host, IdentityHints, NetworkRole};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceType {
+    Computer,
+    Phone,
+    Tablet,
+    Printer,
+    Camera,
+    /// TVs and streaming sticks such as Apple TV or Chromecast.
+    #[serde(rename = "media")]
+    MediaPlayer,
+    Speaker,
+    Router,
+    /// Embedded devices without a more specific type.
+    Iot,
+}
+
+impl DeviceType {
+    /// Returns the lowercase name used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceType::Computer => "computer",
+            DeviceType::Phone => "phone",
+            DeviceType::Tablet => "tablet",
+            DeviceType::Printer => "printer",
+            DeviceType::Camera => "camera",
+            DeviceType::MediaPlayer => "media",
+            DeviceType::Speaker => "speaker",
+            DeviceType::Router => "router",
+            DeviceType::Iot => "iot",
+        }
+    }
+}
+
+impl FromStr for DeviceType {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_ascii_lowercase().as_str() {
+            "computer" => Ok(Self::Computer),
+            "phone" => Ok(Self::Phone),
+            "tablet" => Ok(Self::Tablet),
+            "printer" => Ok(Self::Printer),
+            "camera" => Ok(Self::Camera),
+            "media" => Ok(Self::MediaPlayer),
+            "speaker" => Ok(Self::Speaker),
+            "router" => Ok(Self::Router),
+            "iot" => Ok(Self::Iot),
+            other => Err(format!("unknown device type '{other}'")),
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Model prefixes announced over mDNS, matched case-insensitively.
+const MODEL_PREFIXES: [(&str, DeviceType); 12] = [
+    ("iphone", DeviceType::Phone),
+    ("ipad", DeviceType::Tablet),
+    ("macbook", DeviceType::Computer),
+    ("imac", DeviceType::Computer),
+    ("macmini", DeviceType::Computer),
+    ("macpro", DeviceType::Computer),
+    ("mac1", DeviceType::Computer),
+    ("appletv", DeviceType::MediaPlayer),
+    ("chromecast", DeviceType::MediaPlayer),
+    ("google tv", DeviceType::MediaPlayer),
+    ("audioaccessory", DeviceType::Speaker),
+    ("sonos", DeviceType::Speaker),
+];
+
+/// DHCP vendor class prefixes, matched case-insensitively.
+const VENDOR_CLASS_PREFIXES: [(&str, DeviceType); 3] = [
+    ("msft", DeviceType::Computer),
+    ("android-dhcp", DeviceType::Phone),
+    ("udhcp", DeviceType::Iot),
+];
+
+/// Returns the most likely type of `host`, if anything hints at one.
+pub fn classify(host: &Host) -> Option<DeviceType> {
+    let hints: &IdentityHints = &host.hints;
+    hints
+        .mdns_model
+        .as_deref()
+        .and_then(|model| match_prefix(model, &MODEL_PREFIXES))
+        .or_else(|| dhcp::lookup(&hints.dhcp_parameters).map(|found| found.device_type))
+        .or_else(|| {
+            hints
+                .dhcp_vendor_class
+                .as_deref()
+                .and_then(|class| match_prefix(class, &VENDOR_CLASS_PREFIXES))
+        })
+        .or_else(|| from_roles(host))
+}
+
+/// Returns the operating system or product the DHCP fingerprint of `host`
+/// names, e.g. `Windows 10/11`.
+pub fn platform(host: &Host) -> Option<String> {
+    dhcp::lookup(&host.hints.dhcp_parameters).map(|found| found.platform.clone())
+}
+
+fn match_prefix(text: &str, prefixes: &[(&str, DeviceType)]) -> Option<DeviceType> {
+    let text: String = text.to_ascii_lowercase();
+    prefixes
+        .iter()
+        .find(|(prefix, _)| text.starts_with(prefix))
+        .map(|(_, device_type)| *device_type)
+}
+
+fn from_roles(host: &Host) -> Option<DeviceType> {
+    let roles: &HashSet<NetworkRole> = &host.network_roles;
+    [
+        (NetworkRole::Printer, DeviceType::Printer),
+        (NetworkRole::Camera, DeviceType::Camera),
+        (NetworkRole::Gateway, DeviceType::Router),
+        (NetworkRole::Iot, DeviceType::Iot),
+    ]
+    .into_iter()
+    .find(|(role, _)| roles.contains(role))
+    .map(|(_, device_type)| device_type)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+
+    #[test]
+    fn self_reported_hints_win_over_roles() {
+        let mut host: Host = Host::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7)));
+        assert_eq!(classify(&host), None);
+
+        host.network_roles.insert(NetworkRole::Iot);
+        assert_eq!(classify(&host), Some(DeviceType::Iot));
+
+        host.hints.dhcp_vendor_class = Some("android-dhcp-14".to_string());
+        assert_eq!(classify(&host), Some(DeviceType::Phone));
+
+        // A Windows parameter request list outweighs the vendor class.
+        host.hints.dhcp_parameters = vec![1, 3, 6, 15, 31, 33, 43, 44, 46, 47, 119, 121, 249, 252];
+        assert_eq!(classify(&host), Some(DeviceType::Computer));
+        assert_eq!(platform(&host).as_deref(), Some("Windows 10/11"));
+
+        host.hints.mdns_model = Some("iPad13,4".to_string());
+        assert_eq!(classify(&host), Some(DeviceType::Tablet));
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # DHCP Fingerprints
+//!
+//! Every DHCP client asks for its own set of options in its own order, so the
+//! parameter request list (option 55) names the client's operating system
+//! without sending a single probe. The database in
+//! `assets/dhcp/fingerprints.txt` maps known lists to a [`DeviceType`] and a
+//! platform name. Lists are compared as a whole, since the order is what tells
+//! related systems apart.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::DeviceType;
+
+const DATABASE: &str = include_str!("../../../assets/dhcp/fingerprints.txt");
+
+/// A database entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub device_type: DeviceType,
+    /// Operating system or product, e.g. `Windows 10/11` or `Android`.
+    pub platform: String,
+}
+
+static FINGERPRINTS: OnceLock<HashMap<Vec<u8>, Fingerprint>> = OnceLock::new();
+
+/// Looks up the client sending `parameters` as its parameter request list.
+pub fn lookup(parameters: &[u8]) -> Option<&'static Fingerprint> {
+    if parameters.is_empty() {
+        return None;
+    }
+    FINGERPRINTS
+        .get_or_init(|| parse(DATABASE).unwrap_or_default())
+        .get(parameters)
+}
+
+/// Parses the `list | type | platform` lines of the database, skipping
+/// comments and blank lines.
+fn parse(database: &str) -> Result<HashMap<Vec<u8>, Fingerprint>, String> {
+    let mut fingerprints: HashMap<Vec<u8>, Fingerprint> = HashMap::new();
+    for (idx, line) in database.lines().enumerate() {
+        let line: &str = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [list, device_type, platform] = fields[..] else {
+            return Err(format!("line {}: expected 3 fields", idx + 1));
+        };
+        let parameters: Vec<u8> = list
+            .split(',')
+            .map(|option| option.trim().parse::<u8>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("line {}: {e}", idx + 1))?;
+        let fingerprint: Fingerprint = Fingerprint {
+            device_type: DeviceType::from_str(device_type)
+                .map_err(|e| format!("line {}: {e}", idx + 1))?,
+            platform: platform.to_string(),
+        };
+        if fingerprints.insert(parameters, fingerprint).is_some() {
+            return Err(format!("line {}: duplicate fingerprint", idx + 1));
+        }
+    }
+    Ok(fingerprints)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn database_parses_and_matches_whole_lists() {
+        assert!(parse(DATABASE).is_ok_and(|db| !db.is_empty()));
+
+        let windows: &[u8] = &[1, 3, 6, 15, 31, 33, 43, 44, 46, 47, 119, 121, 249, 252];
+        let found: &Fingerprint = lookup(windows).unwrap();
+        assert_eq!(found.device_type, DeviceType::Computer);
+        assert_eq!(found.platform, "Windows 10/11");
+
+        assert_eq!(lookup(&windows[..windows.len() - 1]), None);
+        assert_eq!(lookup(&[]), None);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse("1,3,6 | phone").is_err());
+        assert!(parse("1,3,256 | phone | x").is_err());
+        assert!(parse("1,3 | toaster | x").is_err());
+        assert!(parse("1,3 | phone | a\n1,3 | phone | b").is_err());
+    }
+}
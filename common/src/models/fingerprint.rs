@@ -6,6 +6,11 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Service description of an MQTT broker that accepts clients without credentials.
+pub const MQTT_ANONYMOUS: &str = "mqtt (anonymous access)";
+/// Service description of a DNS server that resolves external names for LAN clients.
+pub const DNS_RECURSIVE_RESOLVER: &str = "dns (recursive resolver)";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceSignature {
     pub name: String,
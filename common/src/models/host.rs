@@ -13,49 +13,261 @@
 //! * **Identity**: A host is primarily identified by its IP address for the duration of a scan.
 //! * **Enrichment**: The model is mutable and strictly additive; scans populate optional fields (hostname, vendor) as data becomes available.
 
-use crate::{models::port::Port, utils::mac};
+use crate::{
+    models::{codec, device::DeviceType, port::Port, reachability::Reachability},
+    utils::mac,
+};
 use pnet::datalink::MacAddr;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeSet, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
     net::IpAddr,
     time::Duration,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NetworkRole {
     Gateway,
     DHCP,
     DNS,
+    /// Different machines answer behind this address.
+    Nat,
+    /// Active Directory domain controller.
+    #[serde(rename = "dc")]
+    DomainController,
+    /// Speaks an IoT or OT protocol such as MQTT, Modbus or CoAP.
+    Iot,
+    /// Answers IPP or PJL printer queries.
+    Printer,
+    /// Streams video over RTSP, typically an IP camera or video recorder.
+    Camera,
+    /// The machine running the scan.
+    #[serde(rename = "self")]
+    Scanner,
+    /// Answers ARP or neighbor solicitations for addresses that are not its
+    /// own, such as a router doing proxy ARP or an ARP sponge.
+    #[serde(rename = "proxy-arp")]
+    ProxyArp,
+}
+
+impl NetworkRole {
+    /// Returns the lowercase name used in reports, filters and queries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NetworkRole::Gateway => "gateway",
+            NetworkRole::DHCP => "dhcp",
+            NetworkRole::DNS => "dns",
+            NetworkRole::Nat => "nat",
+            NetworkRole::DomainController => "dc",
+            NetworkRole::Iot => "iot",
+            NetworkRole::Printer => "printer",
+            NetworkRole::Camera => "camera",
+            NetworkRole::Scanner => "self",
+            NetworkRole::ProxyArp => "proxy-arp",
+        }
+    }
+}
+
+/// Where information about a host came from, besides the probes of the scan.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataSource {
+    /// A DHCP server lease file on the scanning machine.
+    DhcpLease,
+    /// An external `zond-enrich-*` program.
+    Plugin,
+    /// A name imported from SSH `known_hosts` with `zond import known-hosts`.
+    KnownHosts,
+}
+
+impl DataSource {
+    /// Returns the label used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataSource::DhcpLease => "dhcp-lease",
+            DataSource::Plugin => "plugin",
+            DataSource::KnownHosts => "known-hosts",
+        }
+    }
+}
+
+/// Why a host answered slowly, judged against a baseline host probed
+/// throughout the scan.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyCause {
+    /// The host is slow even compared with the baseline under load.
+    Target,
+    /// The baseline slowed down just as much, so the scan or other traffic
+    /// congested the network.
+    Congestion,
+}
+
+impl LatencyCause {
+    /// Returns the label used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LatencyCause::Target => "target",
+            LatencyCause::Congestion => "congestion",
+        }
+    }
+}
+
+/// What a device said about itself while announcing or configuring itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityHints {
+    /// Model from an mDNS TXT record (`model=`, `md=`, `am=` or `ty=`).
+    #[serde(default)]
+    pub mdns_model: Option<String>,
+
+    /// Friendly name from an mDNS TXT record (`fn=`).
+    #[serde(default)]
+    pub mdns_name: Option<String>,
+
+    /// DHCP vendor class identifier (option 60), e.g. `MSFT 5.0`.
+    #[serde(default)]
+    pub dhcp_vendor_class: Option<String>,
+
+    /// DHCP parameter request list (option 55), in the order the client asked.
+    #[serde(default)]
+    pub dhcp_parameters: Vec<u8>,
+}
+
+impl IdentityHints {
+    /// Fills in what `self` is missing from `other`.
+    pub fn merge(&mut self, other: IdentityHints) {
+        if self.mdns_model.is_none() {
+            self.mdns_model = other.mdns_model;
+        }
+        if self.mdns_name.is_none() {
+            self.mdns_name = other.mdns_name;
+        }
+        if self.dhcp_vendor_class.is_none() {
+            self.dhcp_vendor_class = other.dhcp_vendor_class;
+        }
+        if self.dhcp_parameters.is_empty() {
+            self.dhcp_parameters = other.dhcp_parameters;
+        }
+    }
+}
+
+/// Why a router refused to deliver a probe, as reported by ICMP.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnreachableReason {
+    Network,
+    Host,
+    Port,
+    /// Explicitly blocked by a filtering device (ICMP codes 9, 10 and 13).
+    AdminProhibited,
+}
+
+impl UnreachableReason {
+    /// Short description for reports, e.g. `filtered`.
+    pub fn label(self) -> &'static str {
+        match self {
+            UnreachableReason::AdminProhibited => "filtered",
+            UnreachableReason::Network => "network unreachable",
+            UnreachableReason::Host => "host unreachable",
+            UnreachableReason::Port => "port unreachable",
+        }
+    }
+}
+
+/// A target a router reported unreachable by ICMP instead of the target
+/// answering. Such targets are not hosts; reports list them on their own.
+///
+/// Serializes to the `unreachable_target` object of the report schema.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Unreachable {
+    pub target: IpAddr,
+    /// The router that sent the ICMP error.
+    pub reporter: IpAddr,
+    pub reason: UnreachableReason,
 }
 
 /// Represents a discovered network host.
 ///
 /// A host is defined by what we know about it.
-#[derive(Debug, Clone)]
+///
+/// Serializes to the `host` object of the report schema ([`crate::schema`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Host {
     /// The primary way to identify the host (on this run).
     /// Note: A host might have multiple IPs, but we usually discover it via one.
     pub primary_ip: IpAddr,
 
     /// The resolved hostname (if any).
+    #[serde(default)]
     pub hostname: Option<String>,
 
     /// All known IP addresses for this host.
     pub ips: BTreeSet<IpAddr>,
 
     /// Open ports found on the host.
+    #[serde(deserialize_with = "codec::ports::deserialize")]
     ports: Vec<Port>,
 
     /// The MAC address (only available if the host is on the same LAN).
+    #[serde(default, with = "codec::mac")]
     pub mac: Option<MacAddr>,
 
     /// The device vendor/manufacturer (derived from MAC).
+    #[serde(default)]
     pub vendor: Option<String>,
 
+    /// Make and model as reported by the device itself, e.g. by a printer.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Physical location as configured on the device.
+    #[serde(default)]
+    pub location: Option<String>,
+
     /// Inferred network roles (e.g., is it a Gateway?).
     pub network_roles: HashSet<NetworkRole>,
 
+    /// Identity hints observed passively or in mDNS announcements.
+    #[serde(default)]
+    pub hints: IdentityHints,
+
+    /// What kind of device this most likely is, see [`crate::models::device`].
+    #[serde(default)]
+    pub device_type: Option<DeviceType>,
+
+    /// Operating system or product named by a fingerprint, e.g. `Android`.
+    #[serde(default)]
+    pub platform: Option<String>,
+
+    /// Addresses the host answered for on behalf of others, see
+    /// [`NetworkRole::ProxyArp`].
+    #[serde(default)]
+    pub proxied_ips: BTreeSet<IpAddr>,
+
+    /// Sources other than the scan's own probes that contributed to this host.
+    #[serde(default)]
+    pub sources: HashSet<DataSource>,
+
+    /// Site-specific key/value pairs from external enrichers, e.g. an asset tag.
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+
+    /// TTL (or hop limit) of the most recent response, if the scanner could see it.
+    #[serde(default)]
+    pub ttl: Option<u8>,
+
+    /// Why the round-trip times are high, if they are and a baseline host was
+    /// probed to tell.
+    #[serde(default)]
+    pub latency: Option<LatencyCause>,
+
+    /// Which discovery probes reached the host, per address family.
+    #[serde(default)]
+    pub reachability: Reachability,
+
     /// The last 10 round-trip time measurements.
+    #[serde(rename = "rtts_ms", with = "codec::rtts_ms")]
     rtt_history: VecDeque<Duration>,
 }
 
@@ -72,7 +284,18 @@ impl Host {
             ports: Vec::new(),
             mac: None,
             vendor: None,
+            model: None,
+            location: None,
             network_roles: HashSet::new(),
+            hints: IdentityHints::default(),
+            device_type: None,
+            platform: None,
+            proxied_ips: BTreeSet::new(),
+            sources: HashSet::new(),
+            annotations: BTreeMap::new(),
+            ttl: None,
+            latency: None,
+            reachability: Reachability::default(),
             rtt_history: VecDeque::with_capacity(10),
         }
     }
@@ -85,21 +308,142 @@ impl Host {
         &self.ports
     }
 
+    /// Mutable access to the service information of the ports, leaving the
+    /// ports themselves and their order untouched.
+    pub fn service_infos_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        self.ports
+            .iter_mut()
+            .filter_map(|port| port.service_info.as_mut())
+    }
+
     /// Ingests a port finding, either adding it to the host or updating
     /// an existing record if the port is already known.
     ///
     /// Maintains a strictly sorted internal vector for rapid `O(log N)`
-    /// lookups and immediate output-readiness.
+    /// lookups and immediate output-readiness. The same number under another
+    /// protocol is a port of its own.
     pub fn add_port(&mut self, new_port: Port) {
-        match self
-            .ports
-            .binary_search_by_key(&new_port.number, |p| p.number)
-        {
+        match self.ports.binary_search_by(|p| {
+            (p.number, p.protocol as u8).cmp(&(new_port.number, new_port.protocol as u8))
+        }) {
             Ok(idx) => self.ports[idx].merge(new_port),
             Err(idx) => self.ports.insert(idx, new_port),
         }
     }
 
+    /// Returns `true` if both records describe the same device.
+    ///
+    /// Hosts with known MAC addresses are matched by MAC alone, as routed
+    /// vantage points may see different addresses of one machine. Otherwise a
+    /// shared IP address is taken as proof of identity.
+    pub fn is_same_device(&self, other: &Host) -> bool {
+        match (self.mac, other.mac) {
+            (Some(a), Some(b)) => a == b,
+            _ => !self.ips.is_disjoint(&other.ips),
+        }
+    }
+
+    /// Returns `true` if `other` is this device after a change of its private
+    /// MAC address.
+    ///
+    /// Phones and laptops pick a random, locally administered MAC address per
+    /// network and rotate it, so [`is_same_device`](Self::is_same_device) sees
+    /// a new device each time. The name they announce over mDNS stays the same,
+    /// and so does the model where both records have one.
+    pub fn is_same_randomized_device(&self, other: &Host) -> bool {
+        let (Some(ours), Some(theirs)) = (self.mac, other.mac) else {
+            return false;
+        };
+        if ours == theirs || !ours.is_local() || !theirs.is_local() {
+            return false;
+        }
+        let (Some((our_name, our_model)), Some((their_name, their_model))) =
+            (self.mdns_identity(), other.mdns_identity())
+        else {
+            return false;
+        };
+        our_name.eq_ignore_ascii_case(their_name)
+            && (our_model.is_none() || their_model.is_none() || our_model == their_model)
+    }
+
+    /// Returns the name the host announces over mDNS, preferring its `.local`
+    /// hostname over its friendly name, together with its announced model.
+    fn mdns_identity(&self) -> Option<(&str, Option<&str>)> {
+        let name: &str = self
+            .hostname
+            .as_deref()
+            .filter(|name| name.ends_with(".local"))
+            .or(self.hints.mdns_name.as_deref())?;
+        Some((name, self.hints.mdns_model.as_deref()))
+    }
+
+    /// Merges another observation of the same device into this record.
+    ///
+    /// Identity fields are filled in where missing and collections are unioned.
+    /// Path-dependent measurements (RTT, TTL) are taken from the observation
+    /// closest to the host.
+    pub fn merge(&mut self, other: Host) {
+        self.ips.extend(other.ips);
+        if self.primary_ip.is_ipv6() && other.primary_ip.is_ipv4() {
+            self.primary_ip = other.primary_ip;
+        }
+        if self.mac.is_none() {
+            self.mac = other.mac;
+        }
+        if self.hostname.is_none() {
+            self.hostname = other.hostname;
+        }
+        if self.vendor.is_none() {
+            self.vendor = other.vendor;
+        }
+        if self.model.is_none() {
+            self.model = other.model;
+        }
+        if self.location.is_none() {
+            self.location = other.location;
+        }
+        self.network_roles.extend(other.network_roles);
+        self.proxied_ips.extend(other.proxied_ips);
+        self.hints.merge(other.hints);
+        if self.device_type.is_none() {
+            self.device_type = other.device_type;
+        }
+        if self.platform.is_none() {
+            self.platform = other.platform;
+        }
+        self.sources.extend(other.sources);
+        for (key, value) in other.annotations {
+            self.annotations.entry(key).or_insert(value);
+        }
+        other.ports.into_iter().for_each(|port| self.add_port(port));
+        self.reachability.merge(other.reachability);
+
+        let other_is_closer: bool = match (other.min_rtt(), self.min_rtt()) {
+            (Some(theirs), Some(ours)) => theirs < ours,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        if other_is_closer {
+            self.rtt_history = other.rtt_history;
+            self.latency = other.latency;
+        }
+        self.ttl = self.ttl.max(other.ttl);
+    }
+
+    /// Marks the host as answering for addresses that are not its own.
+    ///
+    /// Every address except the primary one and IPv6 link-local addresses,
+    /// which are never proxied, moves to [`proxied_ips`](Self::proxied_ips).
+    pub fn mark_proxy(&mut self) {
+        let primary_ip: IpAddr = self.primary_ip;
+        let (own, proxied): (BTreeSet<IpAddr>, BTreeSet<IpAddr>) = std::mem::take(&mut self.ips)
+            .into_iter()
+            .partition(|ip| *ip == primary_ip || is_link_local_v6(ip));
+        self.ips = own;
+        self.proxied_ips.extend(proxied);
+        self.network_roles.insert(NetworkRole::ProxyArp);
+    }
+
     pub fn with_mac(mut self, mac: MacAddr) -> Self {
         self.mac = Some(mac);
         self.vendor = mac::get_vendor(mac);
@@ -127,6 +471,11 @@ impl Host {
         }
     }
 
+    /// Returns the recorded RTTs, oldest first.
+    pub fn rtts(&self) -> &VecDeque<Duration> {
+        &self.rtt_history
+    }
+
     /// Returns the quickest RTT from the last 10 RTT's
     pub fn min_rtt(&self) -> Option<Duration> {
         self.rtt_history.iter().min().copied()
@@ -137,6 +486,15 @@ impl Host {
         self.rtt_history.iter().max().copied()
     }
 
+    /// Estimates how many routers lie between us and the host.
+    ///
+    /// Operating systems start with one of a few well-known TTLs (64, 128, 255, and
+    /// rarely 32), so the nearest of those at or above the observed value is taken as
+    /// the initial TTL and the difference as the hop count.
+    pub fn hop_distance(&self) -> Option<u8> {
+        self.ttl.map(estimate_hops)
+    }
+
     /// Calculates the average RTT using the RTT history
     pub fn average_rtt(&self) -> Option<Duration> {
         if self.rtt_history.is_empty() {
@@ -147,6 +505,21 @@ impl Host {
     }
 }
 
+fn is_link_local_v6(ip: &IpAddr) -> bool {
+    matches!(ip, IpAddr::V6(v6) if v6.is_unicast_link_local())
+}
+
+fn estimate_hops(observed_ttl: u8) -> u8 {
+    const INITIAL_TTLS: [u8; 4] = [32, 64, 128, 255];
+
+    let initial: u8 = INITIAL_TTLS
+        .into_iter()
+        .find(|&initial| initial >= observed_ttl)
+        .unwrap_or(u8::MAX);
+
+    initial - observed_ttl
+}
+
 // ╔════════════════════════════════════════════╗
 // ║ ████████╗███████╗███████╗████████╗███████╗ ║
 // ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
@@ -163,10 +536,69 @@ mod tests {
         time::Duration,
     };
 
-    use super::Host;
+    use pnet::datalink::MacAddr;
+
+    use super::{Host, NetworkRole};
+    use crate::models::port::{Port, PortState, Protocol};
 
     static IP_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 100));
 
+    #[test]
+    fn serde_uses_schema_field_names() {
+        let mut host: Host = Host::new(IP_ADDR).with_rtt(Duration::from_micros(1_500));
+        host.mac = Some(MacAddr::new(0xaa, 0xbb, 0xcc, 0x00, 0x11, 0x22));
+        host.network_roles.insert(NetworkRole::Gateway);
+        host.add_port(Port::new(22, Protocol::Tcp, PortState::Open));
+
+        let json: String = serde_json::to_string(&host).unwrap();
+        assert!(json.contains(r#""mac":"aa:bb:cc:00:11:22""#));
+        assert!(json.contains(r#""network_roles":["gateway"]"#));
+        assert!(json.contains(r#""rtts_ms":[1.5]"#));
+        assert!(json.contains(r#""protocol":"tcp","state":"open""#));
+
+        let back: Host = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.mac, host.mac);
+        assert_eq!(back.rtts(), host.rtts());
+        assert_eq!(back.ports(), host.ports());
+    }
+
+    #[test]
+    fn mark_proxy_keeps_only_own_addresses() {
+        let mut host: Host = Host::new(IP_ADDR);
+        let link_local: IpAddr = "fe80::1".parse().unwrap();
+        host.ips.insert(link_local);
+        (101..120).for_each(|last| {
+            host.ips
+                .insert(IpAddr::V4(Ipv4Addr::new(192, 168, 0, last)));
+        });
+
+        host.mark_proxy();
+        assert_eq!(host.ips.len(), 2);
+        assert!(host.ips.contains(&IP_ADDR) && host.ips.contains(&link_local));
+        assert_eq!(host.proxied_ips.len(), 19);
+        assert!(host.network_roles.contains(&NetworkRole::ProxyArp));
+    }
+
+    #[test]
+    fn private_mac_rotation_keeps_mdns_identity() {
+        let phone = |mac: MacAddr, name: &str| -> Host {
+            let mut host: Host = Host::new(IP_ADDR).with_mac(mac);
+            host.hostname = Some(name.to_string());
+            host.hints.mdns_model = Some("iPhone15,2".to_string());
+            host
+        };
+        let before: Host = phone(MacAddr::new(0x3a, 1, 2, 3, 4, 5), "Alices-iPhone.local");
+        let after: Host = phone(MacAddr::new(0x7e, 9, 8, 7, 6, 5), "alices-iphone.local");
+        assert!(before.is_same_randomized_device(&after));
+
+        let other: Host = phone(MacAddr::new(0x7e, 9, 8, 7, 6, 5), "Bobs-iPhone.local");
+        assert!(!before.is_same_randomized_device(&other));
+
+        // Burned-in addresses do not rotate, so a change means another device.
+        let burned_in: Host = phone(MacAddr::new(0x00, 9, 8, 7, 6, 5), "Alices-iPhone.local");
+        assert!(!before.is_same_randomized_device(&burned_in));
+    }
+
     #[test]
     fn rtt_history_caps_at_ten() {
         let mut host: Host = Host::new(IP_ADDR);
@@ -231,6 +663,41 @@ mod tests {
         assert_eq!(host.average_rtt(), Some(Duration::from_millis(6)));
     }
 
+    #[test]
+    fn merge_combines_vantage_points() {
+        let other_ip: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 100));
+        let mut near: Host = Host::new(IP_ADDR).with_rtt(Duration::from_millis(2));
+        near.ttl = Some(64);
+        let mut far: Host = Host::new(IP_ADDR).with_rtt(Duration::from_millis(30));
+        far.ips.insert(other_ip);
+        far.hostname = Some("nas.lan".to_string());
+        far.ttl = Some(61);
+
+        assert!(near.is_same_device(&far));
+        near.merge(far);
+
+        assert_eq!(near.ips.len(), 2);
+        assert_eq!(near.hostname.as_deref(), Some("nas.lan"));
+        assert_eq!(near.min_rtt(), Some(Duration::from_millis(2)));
+        assert_eq!(near.ttl, Some(64));
+        assert!(!near.is_same_device(&Host::new(IpAddr::V4(Ipv4Addr::LOCALHOST))));
+    }
+
+    #[test]
+    fn hop_distance_uses_nearest_initial_ttl() {
+        let mut host: Host = Host::new(IP_ADDR);
+        assert_eq!(host.hop_distance(), None);
+
+        host.ttl = Some(64);
+        assert_eq!(host.hop_distance(), Some(0));
+        host.ttl = Some(57);
+        assert_eq!(host.hop_distance(), Some(7));
+        host.ttl = Some(118);
+        assert_eq!(host.hop_distance(), Some(10));
+        host.ttl = Some(240);
+        assert_eq!(host.hop_distance(), Some(15));
+    }
+
     #[test]
     fn average_rtt_returns_none() {
         let host: Host = Host::new(IP_ADDR);
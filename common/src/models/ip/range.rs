@@ -9,6 +9,7 @@
 //! This module provides the [`Ipv4Range`] struct, which represents a contiguous
 //! block of IPv4 addresses, and utilities for generating ranges from CIDR notation.
 
+use serde::{Deserialize, Serialize};
 use std::{
     net::{IpAddr, Ipv4Addr},
     str::FromStr,
@@ -45,8 +46,10 @@ pub enum IpError {
 
 /// A contiguous range of IPv4 addresses defined by a start and end point.
 ///
-/// Both boundaries are inclusive.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Both boundaries are inclusive. Deserializing rejects ranges whose start lies
+/// after their end, like [`Ipv4Range::new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawIpv4Range")]
 pub struct Ipv4Range {
     /// The inclusive starting address of the range.
     pub start_addr: Ipv4Addr,
@@ -54,6 +57,21 @@ pub struct Ipv4Range {
     pub end_addr: Ipv4Addr,
 }
 
+/// The unchecked wire form of an [`Ipv4Range`].
+#[derive(Deserialize)]
+struct RawIpv4Range {
+    start_addr: Ipv4Addr,
+    end_addr: Ipv4Addr,
+}
+
+impl TryFrom<RawIpv4Range> for Ipv4Range {
+    type Error = IpError;
+
+    fn try_from(raw: RawIpv4Range) -> Result<Self, Self::Error> {
+        Self::new(raw.start_addr, raw.end_addr)
+    }
+}
+
 impl Ipv4Range {
     /// Creates a new `Ipv4Range`.
     ///
@@ -8,9 +8,24 @@
 //!
 //! This module provides [`IpSet`], which ensures that all stored addresses
 //! are unique and contiguous blocks are merged upon insertion.
+//!
+//! Iterating a set yields its addresses lazily, range by range, so even a `/8`
+//! never has to be expanded into memory.
+//!
+//! A set also remembers which addresses were added on their own, as a range of
+//! one address, since merging may fold them into a wider range.
 
 use super::range::Ipv4Range;
-use std::{net::IpAddr, str::FromStr};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    iter::Copied,
+    net::{IpAddr, Ipv4Addr},
+    ops::RangeInclusive,
+    slice,
+    str::FromStr,
+    vec,
+};
 
 /// Errors that can occur when processing an `IpSet`.
 #[derive(Debug, thiserror::Error)]
@@ -21,9 +36,39 @@ pub enum IpSetError {
 }
 
 /// A collection of IPv4 addresses stored as non-overlapping ranges.
-#[derive(Debug, Clone, Default)]
+///
+/// Deserialized ranges are merged as if inserted one by one, so overlapping input
+/// still yields a normalized set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "RawIpSet")]
 pub struct IpSet {
     ranges: Vec<Ipv4Range>,
+    /// Addresses that were added on their own, see [`IpSet::is_listed`].
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    listed: BTreeSet<Ipv4Addr>,
+}
+
+/// The unnormalized wire form of an [`IpSet`].
+#[derive(Deserialize)]
+struct RawIpSet {
+    ranges: Vec<Ipv4Range>,
+    #[serde(default)]
+    listed: BTreeSet<Ipv4Addr>,
+}
+
+impl From<RawIpSet> for IpSet {
+    fn from(raw: RawIpSet) -> Self {
+        let mut set: IpSet = IpSet::new();
+        raw.ranges
+            .into_iter()
+            .for_each(|range| set.add_range(range));
+        set.listed = raw
+            .listed
+            .into_iter()
+            .filter(|ip| set.contains(&IpAddr::V4(*ip)))
+            .collect();
+        set
+    }
 }
 
 impl IpSet {
@@ -40,7 +85,18 @@ impl IpSet {
     }
 
     /// Adds a range of addresses to the set, merging any overlaps.
+    ///
+    /// The address of a range of one counts as listed, see [`IpSet::is_listed`].
     pub fn insert_range(&mut self, new_range: Ipv4Range) {
+        if new_range.start_addr == new_range.end_addr {
+            self.listed.insert(new_range.start_addr);
+        }
+        self.add_range(new_range);
+    }
+
+    /// Adds a range of addresses to the set, merging any overlaps, without
+    /// listing any of them.
+    fn add_range(&mut self, new_range: Ipv4Range) {
         self.ranges.push(new_range);
 
         if self.ranges.len() < 2 {
@@ -70,9 +126,76 @@ impl IpSet {
         self.ranges = merged;
     }
 
+    /// Adds every address of `other` to the set.
+    pub fn merge(&mut self, other: IpSet) {
+        self.listed.extend(other.listed);
+        other
+            .ranges
+            .into_iter()
+            .for_each(|range| self.add_range(range));
+    }
+
+    /// Removes an IP address from the set, splitting the range it falls into.
+    pub fn remove(&mut self, ip: &IpAddr) {
+        let (IpAddr::V4(v4), Some(idx)) = (ip, self.position(ip)) else {
+            return;
+        };
+        self.listed.remove(v4);
+        let range: Ipv4Range = self.ranges.remove(idx);
+        let target: u32 = u32::from(*v4);
+
+        let mut pieces: Vec<Ipv4Range> = Vec::with_capacity(2);
+        if target > u32::from(range.start_addr) {
+            pieces.push(Ipv4Range {
+                start_addr: range.start_addr,
+                end_addr: Ipv4Addr::from(target - 1),
+            });
+        }
+        if target < u32::from(range.end_addr) {
+            pieces.push(Ipv4Range {
+                start_addr: Ipv4Addr::from(target + 1),
+                end_addr: range.end_addr,
+            });
+        }
+        self.ranges.splice(idx..idx, pieces);
+    }
+
     /// Checks if the set contains the given IP address.
     pub fn contains(&self, ip: &IpAddr) -> bool {
-        let IpAddr::V4(v4) = ip else { return false };
+        self.position(ip).is_some()
+    }
+
+    /// Returns `true` if `ip` was added on its own rather than as part of a
+    /// wider range, e.g. `10.0.0.5` as opposed to `10.0.0.0/24`, even if it
+    /// was merged with its neighbors since.
+    pub fn is_listed(&self, ip: &IpAddr) -> bool {
+        match ip {
+            IpAddr::V4(v4) => self.listed.contains(v4),
+            IpAddr::V6(_) => false,
+        }
+    }
+
+    /// Returns `true` if the two sets share at least one address.
+    pub fn overlaps(&self, other: &IpSet) -> bool {
+        let (mut ours, mut theirs) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(a), Some(b)) = (ours.peek(), theirs.peek()) {
+            if a.end_addr < b.start_addr {
+                ours.next();
+            } else if b.end_addr < a.start_addr {
+                theirs.next();
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the index of the range holding `ip`.
+    fn position(&self, ip: &IpAddr) -> Option<usize> {
+        let IpAddr::V4(v4) = ip else { return None };
         let target = u32::from(*v4);
 
         self.ranges
@@ -88,7 +211,7 @@ impl IpSet {
                     std::cmp::Ordering::Equal
                 }
             })
-            .is_ok()
+            .ok()
     }
 
     /// Returns the total count of unique IP addresses in the set.
@@ -106,23 +229,78 @@ impl IpSet {
         &self.ranges
     }
 
-    /// Returns an iterator over every individual IP address in the set.
-    pub fn iter(&self) -> impl Iterator<Item = IpAddr> + '_ {
-        self.ranges.iter().flat_map(|range| range.to_iter())
+    /// Returns a lazy iterator over every individual IP address in the set.
+    pub fn iter(&self) -> Iter<'_> {
+        Addresses::new(self.ranges.iter().copied(), self.len())
+    }
+}
+
+/// Lazily yields the addresses of a sequence of ranges, in order.
+///
+/// Only the range being walked is held as a pair of integers; `size_hint` is
+/// exact whenever the remaining count fits into `usize`.
+#[derive(Debug, Clone)]
+pub struct Addresses<R> {
+    ranges: R,
+    current: Option<RangeInclusive<u32>>,
+    remaining: u64,
+}
+
+/// Borrowing iterator returned by [`IpSet::iter`].
+pub type Iter<'a> = Addresses<Copied<slice::Iter<'a, Ipv4Range>>>;
+
+/// Owning iterator returned by [`IpSet::into_iter`].
+pub type IntoIter = Addresses<vec::IntoIter<Ipv4Range>>;
+
+impl<R: Iterator<Item = Ipv4Range>> Addresses<R> {
+    fn new(ranges: R, remaining: u64) -> Self {
+        Self {
+            ranges,
+            current: None,
+            remaining,
+        }
+    }
+}
+
+impl<R: Iterator<Item = Ipv4Range>> Iterator for Addresses<R> {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ip) = self.current.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(IpAddr::V4(Ipv4Addr::from(ip)));
+            }
+            let range: Ipv4Range = self.ranges.next()?;
+            self.current = Some(u32::from(range.start_addr)..=u32::from(range.end_addr));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
     }
 }
 
 impl IntoIterator for IpSet {
     type Item = IpAddr;
-    type IntoIter = std::vec::IntoIter<IpAddr>;
+    type IntoIter = IntoIter;
 
-    /// Consumes the `IpSet` and returns an iterator over its individual IP addresses.
+    /// Consumes the `IpSet` and returns a lazy iterator over its individual IP addresses.
     fn into_iter(self) -> Self::IntoIter {
-        let mut all_ips = Vec::with_capacity(self.len() as usize);
-        for range in self.ranges {
-            all_ips.extend(range.to_iter());
-        }
-        all_ips.into_iter()
+        let remaining: u64 = self.len();
+        Addresses::new(self.ranges.into_iter(), remaining)
+    }
+}
+
+impl<'a> IntoIterator for &'a IpSet {
+    type Item = IpAddr;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
@@ -131,9 +309,7 @@ impl FromIterator<IpSet> for IpSet {
     fn from_iter<I: IntoIterator<Item = IpSet>>(iter: I) -> Self {
         let mut master = IpSet::new();
         for set in iter {
-            for range in set.ranges {
-                master.insert_range(range);
-            }
+            master.merge(set);
         }
         master
     }
@@ -163,6 +339,11 @@ impl From<Vec<Ipv4Range>> for IpSet {
         if ranges.is_empty() {
             return Self::default();
         }
+        let listed: BTreeSet<Ipv4Addr> = ranges
+            .iter()
+            .filter(|r| r.start_addr == r.end_addr)
+            .map(|r| r.start_addr)
+            .collect();
 
         ranges.sort_by_key(|r| r.start_addr);
         let mut merged: Vec<Ipv4Range> = Vec::with_capacity(ranges.len());
@@ -183,7 +364,10 @@ impl From<Vec<Ipv4Range>> for IpSet {
             }
         }
         merged.push(current);
-        Self { ranges: merged }
+        Self {
+            ranges: merged,
+            listed,
+        }
     }
 }
 
@@ -234,6 +418,20 @@ mod tests {
     use super::*;
     use std::net::Ipv4Addr;
 
+    #[test]
+    fn deserialize_merges_overlapping_ranges() {
+        let json: &str = r#"{"ranges":[
+            {"start_addr":"10.0.0.5","end_addr":"10.0.0.9"},
+            {"start_addr":"10.0.0.1","end_addr":"10.0.0.6"}
+        ]}"#;
+        let set: IpSet = serde_json::from_str(json).unwrap();
+        assert_eq!(set.ranges().len(), 1);
+        assert_eq!(set.len(), 9);
+
+        let reversed: &str = r#"{"ranges":[{"start_addr":"10.0.0.9","end_addr":"10.0.0.1"}]}"#;
+        assert!(serde_json::from_str::<IpSet>(reversed).is_err());
+    }
+
     #[test]
     fn insert_single_ips() {
         let mut set = IpSet::new();
@@ -245,6 +443,28 @@ mod tests {
         assert_eq!(set.len(), 2);
     }
 
+    #[test]
+    fn single_addresses_stay_listed_after_merging() {
+        let own: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5));
+        let neighbor: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 6));
+        let mut set: IpSet = IpSet::from(own);
+        set.merge(IpSet::from(neighbor));
+        set.insert_range(
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 10)).unwrap(),
+        );
+
+        assert_eq!(set.ranges.len(), 1);
+        assert!(set.is_listed(&own) && set.is_listed(&neighbor));
+        assert!(!set.is_listed(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7))));
+
+        let json: String = serde_json::to_string(&set).unwrap();
+        let mut set: IpSet = serde_json::from_str(&json).unwrap();
+        assert!(set.is_listed(&own));
+
+        set.remove(&own);
+        assert!(!set.is_listed(&own));
+    }
+
     #[test]
     fn insert_range() {
         let mut set = IpSet::new();
@@ -303,6 +523,44 @@ mod tests {
         assert_eq!(set.len(), 11);
     }
 
+    #[test]
+    fn remove_splits_ranges() {
+        let mut set = IpSet::new();
+        set.insert_range(
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 10)).unwrap(),
+        );
+        set.insert(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 20)));
+
+        set.remove(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)));
+        set.remove(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        set.remove(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 20)));
+        set.remove(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 99)));
+
+        assert_eq!(set.len(), 8);
+        assert_eq!(set.ranges.len(), 2);
+        assert!(!set.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5))));
+        assert!(set.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 6))));
+        assert_eq!(set.ranges[0].start_addr, Ipv4Addr::new(10, 0, 0, 2));
+    }
+
+    #[test]
+    fn overlaps_needs_a_shared_address() {
+        let set: IpSet = IpSet::from(vec![
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 9)).unwrap(),
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 1, 1), Ipv4Addr::new(10, 0, 1, 9)).unwrap(),
+        ]);
+        let gap: IpSet = Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 10), Ipv4Addr::new(10, 0, 1, 0))
+            .unwrap()
+            .into();
+        assert!(!set.overlaps(&gap));
+        assert!(!gap.overlaps(&set));
+
+        let mut touching: IpSet = gap.clone();
+        touching.insert(IpAddr::V4(Ipv4Addr::new(10, 0, 1, 9)));
+        assert!(set.overlaps(&touching));
+        assert!(!set.overlaps(&IpSet::new()));
+    }
+
     #[test]
     fn from_vec_ranges() {
         let r1 = Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 5)).unwrap();
@@ -348,6 +606,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn iteration_is_lazy_and_sized() {
+        let mut set: IpSet = IpSet::new();
+        set.insert_range(
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 255, 255, 255)).unwrap(),
+        );
+        set.insert(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)));
+
+        let mut iter = set.iter();
+        assert_eq!(iter.size_hint(), (16_777_217, Some(16_777_217)));
+        assert_eq!(iter.nth(256), Some(IpAddr::V4(Ipv4Addr::new(10, 0, 1, 0))));
+        assert_eq!(iter.size_hint(), (16_776_960, Some(16_776_960)));
+    }
+
     #[test]
     fn from_iterator() {
         let set1 = {
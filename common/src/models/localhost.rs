@@ -11,10 +11,12 @@
 //!
 //! This includes:
 //! * Active network services (ports opened by local processes).
-//! * Firewall status.
+//! * Firewall status, and the inbound rules deciding which of those services
+//!   the LAN can reach.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
+use std::ops::RangeInclusive;
 
 /// Represents a group of services running on a specific local IP address.
 #[derive(Debug, Clone)]
@@ -57,3 +59,229 @@ pub enum FirewallStatus {
     Inactive,
     NotDetected,
 }
+
+/// The protocols a firewall rule applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Any,
+    Tcp,
+    Udp,
+}
+
+/// Inbound traffic a firewall lets through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowRule {
+    pub name: String,
+    /// Executable the rule is limited to, `None` for any.
+    pub program: Option<String>,
+    pub protocol: Transport,
+    /// Local ports the rule opens, empty for every port.
+    pub ports: Vec<RangeInclusive<u16>>,
+}
+
+impl AllowRule {
+    /// Returns `true` if the rule lets `port` of `protocol` through to the
+    /// process `process`.
+    pub fn allows(&self, process: &str, protocol: Transport, port: u16) -> bool {
+        let protocol_matches: bool = self.protocol == Transport::Any || self.protocol == protocol;
+        let port_matches: bool =
+            self.ports.is_empty() || self.ports.iter().any(|range| range.contains(&port));
+        let program_matches: bool = self
+            .program
+            .as_deref()
+            .is_none_or(|program| is_same_program(program, process));
+        protocol_matches && port_matches && program_matches
+    }
+}
+
+/// Compares a rule's program path with a process name, ignoring the
+/// directory, case and `.exe` or `.app` extensions.
+fn is_same_program(program: &str, process: &str) -> bool {
+    let file_name: &str = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let stem = |name: &str| -> String {
+        let lower: String = name.to_ascii_lowercase();
+        lower
+            .strip_suffix(".exe")
+            .or_else(|| lower.strip_suffix(".app"))
+            .unwrap_or(&lower)
+            .to_string()
+    };
+    stem(file_name) == stem(process)
+}
+
+/// Whether the LAN can reach a local service through the firewall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exposure {
+    Reachable,
+    /// Some of the service's ports are let through, others are not.
+    Partial,
+    Blocked,
+    /// Bound to a loopback address, so unreachable whatever the firewall says.
+    LocalOnly,
+    /// The firewall's decision depends on something zond cannot see, such as
+    /// the code signature of the program.
+    Unknown,
+}
+
+/// The inbound rules of the native firewall.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirewallRules {
+    /// What happens to traffic no allow rule matches.
+    pub default_inbound: Exposure,
+    pub allow: Vec<AllowRule>,
+}
+
+impl FirewallRules {
+    /// Decides whether the LAN can reach `service`, listening on `protocol`.
+    pub fn exposure(&self, service: &Service, protocol: Transport) -> Exposure {
+        if service.local_addr.is_loopback() {
+            return Exposure::LocalOnly;
+        }
+        let mut reachable: usize = 0;
+        let mut unknown: usize = 0;
+        for port in &service.local_ports {
+            if self
+                .allow
+                .iter()
+                .any(|rule| rule.allows(&service.name, protocol, *port))
+            {
+                reachable += 1;
+                continue;
+            }
+            match self.default_inbound {
+                Exposure::Reachable => reachable += 1,
+                Exposure::Unknown => unknown += 1,
+                _ => {}
+            }
+        }
+
+        let ports: usize = service.local_ports.len();
+        match (reachable, unknown) {
+            (_, unknown) if unknown > 0 && reachable + unknown == ports => Exposure::Unknown,
+            (reachable, _) if reachable == ports => Exposure::Reachable,
+            (0, 0) => Exposure::Blocked,
+            _ => Exposure::Partial,
+        }
+    }
+}
+
+/// Which local TCP ports answered connections from the LAN side of the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTest {
+    /// Where the connections came from, e.g. `network namespace via eth0`.
+    pub source: String,
+    /// LAN address of the host that wildcard listeners were probed on.
+    pub target: IpAddr,
+    /// Whether each probed `(listening address, port)` accepted the connection.
+    pub results: HashMap<(IpAddr, u16), bool>,
+}
+
+impl SelfTest {
+    /// Sums up the probes of `service`, `None` if none of its ports was probed.
+    pub fn exposure(&self, service: &Service) -> Option<Exposure> {
+        if service.local_addr.is_loopback() {
+            return Some(Exposure::LocalOnly);
+        }
+        let answers: Vec<bool> = service
+            .local_ports
+            .iter()
+            .filter_map(|port| self.results.get(&(service.local_addr, *port)).copied())
+            .collect();
+        if answers.is_empty() {
+            return None;
+        }
+        Some(match answers.iter().filter(|open| **open).count() {
+            0 => Exposure::Blocked,
+            open if open == answers.len() => Exposure::Reachable,
+            _ => Exposure::Partial,
+        })
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn service(name: &str, ip: Ipv4Addr, ports: &[u16]) -> Service {
+        Service::new(
+            name.to_string(),
+            IpAddr::V4(ip),
+            ports.iter().copied().collect(),
+        )
+    }
+
+    #[test]
+    fn allow_rules_decide_exposure_of_services() {
+        let rules: FirewallRules = FirewallRules {
+            default_inbound: Exposure::Blocked,
+            allow: vec![
+                AllowRule {
+                    name: "Web".to_string(),
+                    program: None,
+                    protocol: Transport::Tcp,
+                    ports: vec![80..=80, 8000..=8010],
+                },
+                AllowRule {
+                    name: "SSH".to_string(),
+                    program: Some(r"C:\Windows\System32\OpenSSH\sshd.exe".to_string()),
+                    protocol: Transport::Any,
+                    ports: Vec::new(),
+                },
+            ],
+        };
+        let lan: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 2);
+
+        let web: Service = service("nginx.exe", lan, &[80, 8005]);
+        assert_eq!(rules.exposure(&web, Transport::Tcp), Exposure::Reachable);
+        assert_eq!(rules.exposure(&web, Transport::Udp), Exposure::Blocked);
+
+        let mixed: Service = service("nginx.exe", lan, &[80, 443]);
+        assert_eq!(rules.exposure(&mixed, Transport::Tcp), Exposure::Partial);
+
+        let sshd: Service = service("SSHD.EXE", lan, &[22]);
+        assert_eq!(rules.exposure(&sshd, Transport::Tcp), Exposure::Reachable);
+
+        let local: Service = service("postgres", Ipv4Addr::LOCALHOST, &[5432]);
+        assert_eq!(rules.exposure(&local, Transport::Tcp), Exposure::LocalOnly);
+
+        let signed: FirewallRules = FirewallRules {
+            default_inbound: Exposure::Unknown,
+            allow: Vec::new(),
+        };
+        assert_eq!(signed.exposure(&sshd, Transport::Tcp), Exposure::Unknown);
+    }
+
+    #[test]
+    fn self_test_results_sum_up_per_service() {
+        let lan: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 2);
+        let self_test: SelfTest = SelfTest {
+            source: "host".to_string(),
+            target: IpAddr::V4(lan),
+            results: HashMap::from([
+                ((IpAddr::V4(lan), 22), true),
+                ((IpAddr::V4(lan), 80), true),
+                ((IpAddr::V4(lan), 443), false),
+            ]),
+        };
+
+        let ssh: Service = service("sshd", lan, &[22]);
+        assert_eq!(self_test.exposure(&ssh), Some(Exposure::Reachable));
+        let web: Service = service("nginx", lan, &[80, 443]);
+        assert_eq!(self_test.exposure(&web), Some(Exposure::Partial));
+        let tls: Service = service("nginx", lan, &[443]);
+        assert_eq!(self_test.exposure(&tls), Some(Exposure::Blocked));
+        let unprobed: Service = service("dnsmasq", lan, &[53]);
+        assert_eq!(self_test.exposure(&unprobed), None);
+    }
+}
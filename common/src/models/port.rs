@@ -12,6 +12,7 @@
 //! "Rich" model. It encapsulates not just the port number and protocol, but also
 //! state information (Open/Closed) and service metadata gathered during fingerprinting.
 
+use serde::{Deserialize, Serialize};
 use std::{num::ParseIntError, ops::RangeInclusive, str::FromStr};
 use thiserror::Error;
 
@@ -33,14 +34,18 @@ pub enum PortSetParseError {
     MalformedSpec(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Protocol {
     Tcp,
     Udp,
+    /// Found by SCTP INIT probes, see `--sctp`.
+    Sctp,
 }
 
 #[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PortState {
     /// Actively accepting connections.
     Open,
@@ -59,12 +64,12 @@ pub enum PortState {
 ///
 /// A `Port` is the primary unit of data returned after a scan has
 /// moved past the initial "ping" or "syn-check" phase.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Port {
     /// The 16-bit port number (e.g., 80, 443).
     pub number: u16,
 
-    /// The transport layer protocol used (TCP or UDP).
+    /// The transport layer protocol used (TCP, UDP or SCTP).
     pub protocol: Protocol,
 
     /// The current state of the port as determined by a scanner.
@@ -72,6 +77,7 @@ pub struct Port {
 
     /// Optional service information (e.g., "http", "ssh").
     /// This is typically populated during service version detection.
+    #[serde(default)]
     pub service_info: Option<String>,
 }
 
@@ -0,0 +1,229 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Which discovery probes a host answered, per address family.
+//!
+//! Every scanner records the probes a host answered, and the probes it sent
+//! to the host without an answer. Side by side, the two families show
+//! dual-stack setups that only half work: a printer that answers NDP but
+//! ignores ARP, or a server whose IPv6 firewall drops pings it lets through
+//! over IPv4.
+
+use std::{collections::BTreeMap, net::IpAddr};
+
+use serde::{Deserialize, Serialize};
+
+/// An address family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Family {
+    Ipv4,
+    Ipv6,
+}
+
+/// A kind of discovery probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// ARP request on the local segment.
+    Arp,
+    /// IPv6 neighbor discovery on the local segment, answered by any frame
+    /// from the host.
+    Ndp,
+    /// ICMP echo request.
+    Icmp,
+    /// TCP SYN or connect attempt.
+    Tcp,
+    /// SCTP INIT.
+    Sctp,
+}
+
+/// Discovery probes sent to a host, by family, and whether it answered them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reachability {
+    #[serde(default)]
+    pub ipv4: BTreeMap<Method, bool>,
+    #[serde(default)]
+    pub ipv6: BTreeMap<Method, bool>,
+}
+
+impl Family {
+    pub fn of(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(_) => Family::Ipv4,
+            IpAddr::V6(_) => Family::Ipv6,
+        }
+    }
+
+    /// Returns the name shown to users.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Family::Ipv4 => "IPv4",
+            Family::Ipv6 => "IPv6",
+        }
+    }
+
+    fn other(&self) -> Self {
+        match self {
+            Family::Ipv4 => Family::Ipv6,
+            Family::Ipv6 => Family::Ipv4,
+        }
+    }
+}
+
+impl Method {
+    /// Returns the name shown to users.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Method::Arp => "ARP",
+            Method::Ndp => "NDP",
+            Method::Icmp => "ICMP",
+            Method::Tcp => "TCP",
+            Method::Sctp => "SCTP",
+        }
+    }
+}
+
+impl Reachability {
+    /// Records that `method` reached the host at `ip`.
+    pub fn answered(&mut self, ip: IpAddr, method: Method) {
+        self.family_mut(Family::of(ip)).insert(method, true);
+    }
+
+    /// Records that `method` was sent to the host at `ip`, without changing
+    /// the outcome if it already answered.
+    pub fn probed(&mut self, ip: IpAddr, method: Method) {
+        self.probed_family(Family::of(ip), method);
+    }
+
+    /// Records that `method` went out in `family` to a whole segment the host
+    /// is on, without knowing its address.
+    pub fn probed_family(&mut self, family: Family, method: Method) {
+        self.family_mut(family).entry(method).or_insert(false);
+    }
+
+    /// Adds the probes of another observation; an answer to either counts.
+    pub fn merge(&mut self, other: Reachability) {
+        for (family, probes) in [(Family::Ipv4, other.ipv4), (Family::Ipv6, other.ipv6)] {
+            let ours: &mut BTreeMap<Method, bool> = self.family_mut(family);
+            for (method, answered) in probes {
+                *ours.entry(method).or_insert(false) |= answered;
+            }
+        }
+    }
+
+    /// The probes sent in `family`, in a fixed order.
+    pub fn family(&self, family: Family) -> &BTreeMap<Method, bool> {
+        match family {
+            Family::Ipv4 => &self.ipv4,
+            Family::Ipv6 => &self.ipv6,
+        }
+    }
+
+    /// Returns `true` if no probe was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    /// Returns `true` if any probe of `family` was answered.
+    pub fn reachable(&self, family: Family) -> bool {
+        self.family(family).values().any(|answered| *answered)
+    }
+
+    /// Returns the only family the host answered in, if it was probed in
+    /// both and the other one stayed silent.
+    pub fn only_over(&self) -> Option<Family> {
+        [Family::Ipv4, Family::Ipv6].into_iter().find(|family| {
+            let other: Family = family.other();
+            self.reachable(*family) && !self.family(other).is_empty() && !self.reachable(other)
+        })
+    }
+
+    fn family_mut(&mut self, family: Family) -> &mut BTreeMap<Method, bool> {
+        match family {
+            Family::Ipv4 => &mut self.ipv4,
+            Family::Ipv6 => &mut self.ipv6,
+        }
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V4: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 5));
+    const V6: IpAddr = IpAddr::V6(std::net::Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 5));
+
+    #[test]
+    fn answers_win_over_silence_when_merging() {
+        let mut local: Reachability = Reachability::default();
+        local.answered(V4, Method::Arp);
+        local.probed(V4, Method::Tcp);
+        local.probed_family(Family::Ipv6, Method::Ndp);
+        // A later silent probe does not undo an answer.
+        local.probed(V4, Method::Arp);
+
+        let mut routed: Reachability = Reachability::default();
+        routed.answered(V4, Method::Tcp);
+        routed.probed(V4, Method::Icmp);
+
+        local.merge(routed);
+        assert_eq!(
+            local.ipv4.into_iter().collect::<Vec<(Method, bool)>>(),
+            vec![
+                (Method::Arp, true),
+                (Method::Icmp, false),
+                (Method::Tcp, true)
+            ]
+        );
+        assert_eq!(local.ipv6.get(&Method::Ndp), Some(&false));
+    }
+
+    #[test]
+    fn tells_a_host_that_only_answers_one_family() {
+        let mut printer: Reachability = Reachability::default();
+        printer.probed(V4, Method::Arp);
+        printer.answered(V6, Method::Ndp);
+        assert_eq!(printer.only_over(), Some(Family::Ipv6));
+
+        printer.answered(V4, Method::Icmp);
+        assert_eq!(printer.only_over(), None);
+
+        // Never probed over IPv6 says nothing about it.
+        let mut legacy: Reachability = Reachability::default();
+        legacy.answered(V4, Method::Arp);
+        assert_eq!(legacy.only_over(), None);
+        assert!(!legacy.is_empty());
+        assert!(Reachability::default().is_empty());
+    }
+
+    #[test]
+    fn serializes_probes_by_family() {
+        let mut reachability: Reachability = Reachability::default();
+        reachability.answered(V4, Method::Arp);
+        reachability.probed(V6, Method::Icmp);
+
+        let json: String = serde_json::to_string(&reachability).unwrap();
+        assert_eq!(json, r#"{"ipv4":{"arp":true},"ipv6":{"icmp":false}}"#);
+        assert_eq!(
+            serde_json::from_str::<Reachability>(&json).unwrap(),
+            reachability
+        );
+        assert!(
+            serde_json::from_str::<Reachability>("{}")
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
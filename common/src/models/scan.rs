@@ -0,0 +1,134 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Scan Metadata Model
+//!
+//! Identifies one run of zond across everything it leaves behind. The ID is a
+//! [ULID](https://github.com/ulid/spec): 26 characters that sort by the time
+//! the run started, so reports, logs, pcap files and `@last` of the same run
+//! can be matched up, and listing them by name lists them by age.
+//!
+//! The CLI stamps the process once at startup with [`ScanInfo::install`];
+//! every writer then picks the stamp up through [`ScanInfo::session`].
+
+use std::{
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Crockford's base32 alphabet, which leaves out I, L, O and U.
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const ULID_LEN: usize = 26;
+
+static SESSION: OnceLock<ScanInfo> = OnceLock::new();
+
+/// Who ran zond, how and where.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanInfo {
+    /// ULID of the run.
+    pub id: String,
+    /// Version of zond that ran.
+    pub version: String,
+    /// The command line, program name first.
+    pub argv: Vec<String>,
+    /// Login name of the user running zond.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Hostname of the scanning machine.
+    #[serde(default)]
+    pub hostname: Option<String>,
+    /// Network interfaces that were up, as `NAME ADDRESS/PREFIX`.
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+}
+
+impl ScanInfo {
+    /// Makes `info` the stamp of this process, unless one was installed before.
+    ///
+    /// Returns the stamp in effect.
+    pub fn install(info: ScanInfo) -> &'static ScanInfo {
+        SESSION.get_or_init(|| info)
+    }
+
+    /// Returns the stamp of this process, if one was installed.
+    pub fn session() -> Option<&'static ScanInfo> {
+        SESSION.get()
+    }
+}
+
+/// Generates a new ULID from the current time and 80 random bits.
+pub fn new_id() -> String {
+    let millis: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    encode_ulid(millis, rand::random())
+}
+
+/// Reads the time a run started, in milliseconds since the Unix epoch, back
+/// from its ID. Returns `None` if `id` is not a ULID.
+pub fn started_millis(id: &str) -> Option<u64> {
+    if id.len() != ULID_LEN {
+        return None;
+    }
+    id.bytes().take(10).try_fold(0u64, |millis, c| {
+        let digit: usize = ULID_ALPHABET
+            .iter()
+            .position(|&known| known == c.to_ascii_uppercase())?;
+        Some(millis << 5 | digit as u64)
+    })
+}
+
+/// Encodes a 48-bit millisecond timestamp and the low 80 bits of `random`.
+fn encode_ulid(millis: u64, random: u128) -> String {
+    let mut value: u128 =
+        (u128::from(millis) & 0xFFFF_FFFF_FFFF) << 80 | (random & ((1 << 80) - 1));
+    let mut id: [u8; ULID_LEN] = [0; ULID_LEN];
+    for slot in id.iter_mut().rev() {
+        *slot = ULID_ALPHABET[(value & 0x1F) as usize];
+        value >>= 5;
+    }
+    id.iter().map(|&c| c as char).collect()
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ulids_encode_time_first() {
+        assert_eq!(encode_ulid(0, 0), "00000000000000000000000000");
+        // Example from the ULID specification's timestamp section.
+        assert_eq!(&encode_ulid(1_469_918_176_385, 0)[..10], "01ARYZ6S41");
+        assert_eq!(
+            encode_ulid(u64::MAX, u128::MAX),
+            "7ZZZZZZZZZZZZZZZZZZZZZZZZZ"
+        );
+
+        let earlier: String = encode_ulid(1_800_000_000_000, u128::MAX);
+        let later: String = encode_ulid(1_800_000_000_001, 0);
+        assert!(earlier < later, "IDs sort by time");
+        assert_eq!(new_id().len(), ULID_LEN);
+
+        assert_eq!(started_millis(&later), Some(1_800_000_000_001));
+        assert_eq!(
+            started_millis("01ARYZ6S41TSV4RRFFQ69G5FAV"),
+            Some(1_469_918_176_385)
+        );
+        assert_eq!(started_millis("not a ulid"), None);
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! The history of one device across stored reports, for `zond history`.
+//!
+//! [`Timeline::build`] walks the reports in the order they were taken and
+//! picks the device out of each one, then turns the differences between
+//! consecutive sightings into [`Event`]s: when it was first seen, changed its
+//! addresses, hostname or open ports, and when it went missing and came back.
+//!
+//! A device asked for by IP address is followed by its MAC address once a
+//! report shows one, so it stays the same device after it moves to another
+//! address, and another machine taking over the address is not mistaken for
+//! it.
+
+use std::{collections::BTreeSet, fmt, net::IpAddr, str::FromStr};
+
+use pnet::datalink::MacAddr;
+
+use crate::models::{
+    host::Host,
+    port::{PortState, Protocol},
+};
+
+/// The device a timeline follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceQuery {
+    Ip(IpAddr),
+    Mac(MacAddr),
+}
+
+/// The hosts of one stored report.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// When the report was taken, in seconds since the Unix epoch.
+    pub at: u64,
+    pub hosts: Vec<Host>,
+}
+
+/// Something that changed about the device between two reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The first report listing the device, with what it showed.
+    FirstSeen {
+        ips: BTreeSet<IpAddr>,
+        hostname: Option<String>,
+        ports: Vec<(u16, Protocol)>,
+    },
+    Ips {
+        added: Vec<IpAddr>,
+        removed: Vec<IpAddr>,
+    },
+    Hostname {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Ports {
+        opened: Vec<(u16, Protocol)>,
+        closed: Vec<(u16, Protocol)>,
+    },
+    /// The first report after a sighting that left the device out.
+    Gone,
+    /// Listed again after `missed` reports without it.
+    Back { missed: usize },
+}
+
+/// A [`Change`] and the time of the report it showed up in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub at: u64,
+    pub change: Change,
+}
+
+/// The events of one device, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timeline {
+    pub events: Vec<Event>,
+    /// Reports looked at.
+    pub reports: usize,
+    /// Reports listing the device.
+    pub sightings: usize,
+    /// Time of the first report listing the device.
+    pub first_seen: Option<u64>,
+    /// Time of the last report listing the device.
+    pub last_seen: Option<u64>,
+    /// The MAC address the device was followed by, if a report showed one.
+    pub mac: Option<MacAddr>,
+}
+
+/// What one report showed of the device.
+struct Sighting {
+    ips: BTreeSet<IpAddr>,
+    hostname: Option<String>,
+    ports: Vec<(u16, Protocol)>,
+}
+
+impl FromStr for DeviceQuery {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input: &str = input.trim();
+        if let Ok(ip) = input.parse::<IpAddr>() {
+            return Ok(Self::Ip(ip));
+        }
+        input
+            .replace('-', ":")
+            .parse::<MacAddr>()
+            .map(Self::Mac)
+            .map_err(|_| format!("expected an IP or MAC address, got '{input}'"))
+    }
+}
+
+impl fmt::Display for DeviceQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ip(ip) => write!(f, "{ip}"),
+            Self::Mac(mac) => write!(f, "{mac}"),
+        }
+    }
+}
+
+impl Timeline {
+    /// Follows the device `query` names through `snapshots`, which are put
+    /// in time order first.
+    pub fn build(query: DeviceQuery, snapshots: &[Snapshot]) -> Self {
+        let mut ordered: Vec<&Snapshot> = snapshots.iter().collect();
+        ordered.sort_by_key(|snapshot| snapshot.at);
+
+        let mut timeline: Timeline = Timeline {
+            reports: ordered.len(),
+            mac: match query {
+                DeviceQuery::Mac(mac) => Some(mac),
+                DeviceQuery::Ip(_) => None,
+            },
+            ..Self::default()
+        };
+        let mut last: Option<Sighting> = None;
+        let mut missed: usize = 0;
+
+        for snapshot in ordered {
+            let Some(host) = timeline.find(query, &snapshot.hosts) else {
+                if last.is_some() && missed == 0 {
+                    timeline.push(snapshot.at, Change::Gone);
+                }
+                missed += 1;
+                continue;
+            };
+            timeline.sightings += 1;
+            timeline.first_seen = timeline.first_seen.or(Some(snapshot.at));
+            timeline.last_seen = Some(snapshot.at);
+            timeline.mac = timeline.mac.or(host.mac);
+
+            let mut current: Sighting = Sighting::of(host);
+            match &last {
+                None => timeline.push(
+                    snapshot.at,
+                    Change::FirstSeen {
+                        ips: current.ips.clone(),
+                        hostname: current.hostname.clone(),
+                        ports: current.ports.clone(),
+                    },
+                ),
+                Some(previous) => {
+                    if missed > 0 {
+                        timeline.push(snapshot.at, Change::Back { missed });
+                    }
+                    timeline.compare(snapshot.at, previous, &current);
+                    // A report without a name says nothing new about it.
+                    if current.hostname.is_none() {
+                        current.hostname = previous.hostname.clone();
+                    }
+                }
+            }
+            // Reports before the first sighting are not gaps.
+            missed = 0;
+            last = Some(current);
+        }
+        timeline
+    }
+
+    /// The device in `hosts`: by MAC address once known, else by IP address.
+    fn find<'a>(&self, query: DeviceQuery, hosts: &'a [Host]) -> Option<&'a Host> {
+        match (self.mac, query) {
+            (Some(mac), DeviceQuery::Ip(ip)) => {
+                hosts.iter().find(|host| host.mac == Some(mac)).or_else(|| {
+                    hosts
+                        .iter()
+                        .find(|host| host.mac.is_none() && host.ips.contains(&ip))
+                })
+            }
+            (Some(mac), DeviceQuery::Mac(_)) => hosts.iter().find(|host| host.mac == Some(mac)),
+            (None, DeviceQuery::Ip(ip)) => hosts.iter().find(|host| host.ips.contains(&ip)),
+            (None, DeviceQuery::Mac(_)) => None,
+        }
+    }
+
+    fn compare(&mut self, at: u64, previous: &Sighting, current: &Sighting) {
+        let added: Vec<IpAddr> = current.ips.difference(&previous.ips).copied().collect();
+        let removed: Vec<IpAddr> = previous.ips.difference(&current.ips).copied().collect();
+        if !added.is_empty() || !removed.is_empty() {
+            self.push(at, Change::Ips { added, removed });
+        }
+
+        if current.hostname.is_some() && current.hostname != previous.hostname {
+            self.push(
+                at,
+                Change::Hostname {
+                    from: previous.hostname.clone(),
+                    to: current.hostname.clone(),
+                },
+            );
+        }
+
+        let opened: Vec<(u16, Protocol)> = current
+            .ports
+            .iter()
+            .filter(|port| !previous.ports.contains(port))
+            .copied()
+            .collect();
+        let closed: Vec<(u16, Protocol)> = previous
+            .ports
+            .iter()
+            .filter(|port| !current.ports.contains(port))
+            .copied()
+            .collect();
+        if !opened.is_empty() || !closed.is_empty() {
+            self.push(at, Change::Ports { opened, closed });
+        }
+    }
+
+    fn push(&mut self, at: u64, change: Change) {
+        self.events.push(Event { at, change });
+    }
+}
+
+impl Sighting {
+    fn of(host: &Host) -> Self {
+        Self {
+            ips: host.ips.clone(),
+            hostname: host.hostname.clone(),
+            ports: host
+                .ports()
+                .iter()
+                .filter(|port| port.state == PortState::Open)
+                .map(|port| (port.number, port.protocol))
+                .collect(),
+        }
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::port::Port;
+
+    const MAC: MacAddr = MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+
+    fn host(ip: &str, mac: Option<MacAddr>, hostname: Option<&str>, ports: &[u16]) -> Host {
+        let mut host: Host = Host::new(ip.parse().unwrap());
+        host.mac = mac;
+        host.hostname = hostname.map(str::to_string);
+        for port in ports {
+            host.add_port(Port::new(*port, Protocol::Tcp, PortState::Open));
+        }
+        host
+    }
+
+    fn snapshot(at: u64, hosts: Vec<Host>) -> Snapshot {
+        Snapshot { at, hosts }
+    }
+
+    #[test]
+    fn follows_a_device_through_its_changes() {
+        let snapshots: Vec<Snapshot> = vec![
+            // Out of order on purpose.
+            snapshot(300, vec![]),
+            snapshot(
+                100,
+                vec![host("10.0.0.5", Some(MAC), Some("nas"), &[22, 80])],
+            ),
+            snapshot(200, vec![host("10.0.0.5", Some(MAC), None, &[22, 80])]),
+            snapshot(400, vec![]),
+            snapshot(
+                500,
+                vec![
+                    // Another machine took the address over.
+                    host(
+                        "10.0.0.5",
+                        Some(MacAddr(0x02, 0, 0, 0, 0, 0x02)),
+                        Some("laptop"),
+                        &[],
+                    ),
+                    host("10.0.0.9", Some(MAC), Some("nas.lan"), &[22, 443]),
+                ],
+            ),
+        ];
+        let timeline: Timeline = Timeline::build("10.0.0.5".parse().unwrap(), &snapshots);
+        let changes: Vec<(u64, Change)> = timeline
+            .events
+            .iter()
+            .map(|event| (event.at, event.change.clone()))
+            .collect();
+
+        let tcp = |port: u16| (port, Protocol::Tcp);
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    100,
+                    Change::FirstSeen {
+                        ips: ["10.0.0.5".parse().unwrap()].into(),
+                        hostname: Some("nas".to_string()),
+                        ports: vec![tcp(22), tcp(80)],
+                    }
+                ),
+                (300, Change::Gone),
+                (500, Change::Back { missed: 2 }),
+                (
+                    500,
+                    Change::Ips {
+                        added: vec!["10.0.0.9".parse().unwrap()],
+                        removed: vec!["10.0.0.5".parse().unwrap()],
+                    }
+                ),
+                (
+                    500,
+                    Change::Hostname {
+                        from: Some("nas".to_string()),
+                        to: Some("nas.lan".to_string()),
+                    }
+                ),
+                (
+                    500,
+                    Change::Ports {
+                        opened: vec![tcp(443)],
+                        closed: vec![tcp(80)],
+                    }
+                ),
+            ]
+        );
+        assert_eq!((timeline.reports, timeline.sightings), (5, 3));
+        assert_eq!(timeline.mac, Some(MAC));
+        assert_eq!(
+            (timeline.first_seen, timeline.last_seen),
+            (Some(100), Some(500))
+        );
+    }
+
+    #[test]
+    fn parses_ip_and_mac_queries() {
+        assert_eq!(
+            "10.0.0.5".parse::<DeviceQuery>(),
+            Ok(DeviceQuery::Ip("10.0.0.5".parse().unwrap()))
+        );
+        assert_eq!(
+            "02-00-00-00-00-01".parse::<DeviceQuery>(),
+            Ok(DeviceQuery::Mac(MAC))
+        );
+        assert!("nas.lan".parse::<DeviceQuery>().is_err());
+
+        let never: Timeline = Timeline::build(DeviceQuery::Mac(MAC), &[snapshot(1, vec![])]);
+        assert!(never.events.is_empty());
+        assert_eq!((never.sightings, never.first_seen), (0, None));
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A merged view of hosts reported from several vantage points.
+//!
+//! Agents scanning different segments (or the same one from different places)
+//! report overlapping host lists. [`Topology`] deduplicates them and
+//! remembers which agents saw each host. Hosts are matched across agents by
+//! MAC address only: private address ranges repeat between sites, so a shared
+//! IP address identifies a device only within the reports of one agent.
+//!
+//! Agents running with `--every` report in rounds, so the topology also keeps
+//! a [`Presence`] per host: how often the agents that know it listed it or
+//! left it out. Phones, laptops and battery-powered sensors drop off the
+//! network while they sleep and come back later. A host that did so often
+//! enough counts as sleepy, and its next absence reads
+//! [`PresenceState::Asleep`] instead of [`PresenceState::Down`]. Hosts
+//! missing from too many reports in a row are dropped altogether, so the
+//! topology does not grow for as long as the collector runs.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::models::host::Host;
+
+/// How often a host has to come back after going missing before it counts as
+/// sleepy, unless [`Topology::with_sleepy_after`] says otherwise.
+pub const DEFAULT_SLEEPY_AFTER: u32 = 2;
+
+/// How many reports in a row a host may be missing from before it is dropped,
+/// unless [`Topology::with_expire_after`] says otherwise.
+pub const DEFAULT_EXPIRE_AFTER: u32 = 1_000;
+
+/// How a host showed up in the reports of the agents that know it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Presence {
+    /// Reports that listed the host.
+    pub seen: u32,
+    /// Reports that left it out.
+    pub missed: u32,
+    /// Times it came back after going missing.
+    pub returns: u32,
+    /// Reports in a row it has been missing from, `0` while it is up.
+    pub absent_for: u32,
+}
+
+impl Presence {
+    fn see(&mut self) {
+        if self.absent_for > 0 {
+            self.returns += 1;
+        }
+        self.absent_for = 0;
+        self.seen += 1;
+    }
+
+    fn miss(&mut self) {
+        self.missed += 1;
+        self.absent_for += 1;
+    }
+
+    /// Returns `true` once the host came back at least `sleepy_after` times.
+    pub fn is_sleepy(&self, sleepy_after: u32) -> bool {
+        self.returns >= sleepy_after.max(1)
+    }
+
+    pub fn state(&self, sleepy_after: u32) -> PresenceState {
+        match (self.absent_for, self.is_sleepy(sleepy_after)) {
+            (0, _) => PresenceState::Up,
+            (_, true) => PresenceState::Asleep,
+            (_, false) => PresenceState::Down,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresenceState {
+    /// Listed in the latest report.
+    Up,
+    /// Missing, but a sleepy host that is expected to return.
+    Asleep,
+    /// Missing without a history of coming back.
+    Down,
+}
+
+impl PresenceState {
+    /// Returns the label used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresenceState::Up => "up",
+            PresenceState::Asleep => "asleep",
+            PresenceState::Down => "down",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Topology {
+    hosts: Vec<Host>,
+    seen_by: Vec<BTreeSet<String>>,
+    presence: Vec<Presence>,
+    agents: BTreeSet<String>,
+    sleepy_after: u32,
+    expire_after: u32,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self {
+            hosts: Vec::new(),
+            seen_by: Vec::new(),
+            presence: Vec::new(),
+            agents: BTreeSet::new(),
+            sleepy_after: DEFAULT_SLEEPY_AFTER,
+            expire_after: DEFAULT_EXPIRE_AFTER,
+        }
+    }
+}
+
+impl Topology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how often a host has to come back after going missing before its
+    /// absence no longer counts as down. Lower values silence alerts sooner.
+    pub fn with_sleepy_after(mut self, returns: u32) -> Self {
+        self.sleepy_after = returns;
+        self
+    }
+
+    /// Sets after how many reports in a row without a host it is dropped.
+    pub fn with_expire_after(mut self, reports: u32) -> Self {
+        self.expire_after = reports;
+        self
+    }
+
+    /// Merges the hosts reported by `agent` into the topology.
+    ///
+    /// Known hosts that `agent` reported before but left out this time are
+    /// counted as missed, and dropped once they were missed too often.
+    pub fn ingest(&mut self, agent: &str, hosts: Vec<Host>) {
+        self.agents.insert(agent.to_string());
+
+        let mut reported: BTreeSet<usize> = BTreeSet::new();
+        for host in hosts {
+            match self.position(agent, &host) {
+                Some(idx) => {
+                    self.hosts[idx].merge(host);
+                    self.seen_by[idx].insert(agent.to_string());
+                    reported.insert(idx);
+                }
+                None => {
+                    self.hosts.push(host);
+                    self.seen_by.push(BTreeSet::from([agent.to_string()]));
+                    self.presence.push(Presence::default());
+                    reported.insert(self.hosts.len() - 1);
+                }
+            }
+        }
+
+        for (idx, presence) in self.presence.iter_mut().enumerate() {
+            if reported.contains(&idx) {
+                presence.see();
+            } else if self.seen_by[idx].contains(agent) {
+                presence.miss();
+            }
+        }
+        self.expire();
+    }
+
+    /// Finds the known host that `host`, as reported by `agent`, describes.
+    fn position(&self, agent: &str, host: &Host) -> Option<usize> {
+        self.hosts
+            .iter()
+            .zip(&self.seen_by)
+            .position(|(known, seen_by)| match (known.mac, host.mac) {
+                (Some(a), Some(b)) => a == b,
+                _ => seen_by.contains(agent) && !known.ips.is_disjoint(&host.ips),
+            })
+    }
+
+    /// Drops the hosts missing from the last `expire_after` reports.
+    fn expire(&mut self) {
+        let limit: u32 = self.expire_after.max(1);
+        let keep: Vec<bool> = self
+            .presence
+            .iter()
+            .map(|presence| presence.absent_for < limit)
+            .collect();
+        if keep.iter().all(|&keep| keep) {
+            return;
+        }
+
+        let mut flags = keep.iter();
+        self.hosts.retain(|_| *flags.next().unwrap());
+        let mut flags = keep.iter();
+        self.seen_by.retain(|_| *flags.next().unwrap());
+        let mut flags = keep.iter();
+        self.presence.retain(|_| *flags.next().unwrap());
+    }
+
+    /// All known hosts, in order of first appearance.
+    pub fn hosts(&self) -> &[Host] {
+        &self.hosts
+    }
+
+    /// Names of the agents that reported the host at `idx`.
+    pub fn seen_by(&self, idx: usize) -> Option<&BTreeSet<String>> {
+        self.seen_by.get(idx)
+    }
+
+    /// How the host at `idx` showed up across the reports so far.
+    pub fn presence(&self, idx: usize) -> Option<&Presence> {
+        self.presence.get(idx)
+    }
+
+    /// Whether the host at `idx` is up, asleep or down.
+    pub fn state(&self, idx: usize) -> Option<PresenceState> {
+        self.presence
+            .get(idx)
+            .map(|presence| presence.state(self.sleepy_after))
+    }
+
+    /// Returns `true` if the host at `idx` is known to drop off and return.
+    pub fn is_sleepy(&self, idx: usize) -> bool {
+        self.presence
+            .get(idx)
+            .is_some_and(|presence| presence.is_sleepy(self.sleepy_after))
+    }
+
+    /// Names of all agents that have reported so far.
+    pub fn agents(&self) -> &BTreeSet<String> {
+        &self.agents
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use pnet::util::MacAddr;
+
+    use super::*;
+
+    fn host(last_octet: u8) -> Host {
+        Host::new(IpAddr::V4(Ipv4Addr::new(192, 168, 1, last_octet)))
+    }
+
+    #[test]
+    fn deduplicates_hosts_across_agents() {
+        let mut topology: Topology = Topology::new();
+        topology.ingest("office", vec![host(1), host(2)]);
+        topology.ingest("lab", vec![host(2), host(3)]);
+
+        assert_eq!(topology.hosts().len(), 3);
+        assert_eq!(topology.agents().len(), 2);
+
+        let seen: Vec<&str> = topology
+            .seen_by(1)
+            .unwrap()
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(seen, ["lab", "office"]);
+    }
+
+    #[test]
+    fn repeated_reports_do_not_duplicate() {
+        let mut topology: Topology = Topology::new();
+        topology.ingest("office", vec![host(1)]);
+        topology.ingest("office", vec![host(1)]);
+
+        assert_eq!(topology.hosts().len(), 1);
+        assert_eq!(topology.seen_by(0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn hosts_that_keep_returning_fall_asleep_instead_of_down() {
+        let mut topology: Topology = Topology::new().with_sleepy_after(2);
+        topology.ingest("office", vec![host(1), host(2)]);
+        topology.ingest("lab", vec![host(3)]);
+        assert_eq!(topology.state(0), Some(PresenceState::Up));
+
+        // Only reports of agents that know a host count against it.
+        topology.ingest("office", vec![host(1)]);
+        assert_eq!(topology.state(1), Some(PresenceState::Down));
+        assert_eq!(topology.state(2), Some(PresenceState::Up));
+
+        topology.ingest("office", vec![host(1), host(2)]);
+        topology.ingest("office", vec![host(1)]);
+        assert_eq!(topology.state(1), Some(PresenceState::Down));
+
+        topology.ingest("office", vec![host(1), host(2)]);
+        topology.ingest("office", vec![host(1)]);
+        assert!(topology.is_sleepy(1));
+        assert_eq!(topology.state(1), Some(PresenceState::Asleep));
+
+        let presence: &Presence = topology.presence(1).unwrap();
+        assert_eq!(
+            (presence.seen, presence.missed, presence.returns),
+            (3, 3, 2)
+        );
+    }
+
+    #[test]
+    fn addresses_alone_only_match_within_one_agent() {
+        let mut topology: Topology = Topology::new();
+        topology.ingest("site-a", vec![host(1)]);
+        topology.ingest("site-b", vec![host(1)]);
+        assert_eq!(topology.hosts().len(), 2);
+
+        let mac: MacAddr = MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        topology.ingest("site-a", vec![host(2).with_mac(mac)]);
+        topology.ingest("site-b", vec![host(9).with_mac(mac)]);
+        assert_eq!(topology.hosts().len(), 3);
+        assert_eq!(topology.seen_by(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn hosts_missing_for_too_long_are_dropped() {
+        let mut topology: Topology = Topology::new().with_expire_after(2);
+        topology.ingest("office", vec![host(1), host(2)]);
+        topology.ingest("office", vec![host(1)]);
+        assert_eq!(topology.hosts().len(), 2);
+
+        topology.ingest("office", vec![host(1)]);
+        assert_eq!(topology.hosts().len(), 1);
+        assert_eq!(topology.seen_by(0).unwrap().len(), 1);
+        assert_eq!(topology.presence(0).unwrap().seen, 3);
+    }
+}
@@ -14,10 +14,13 @@
 pub mod ext;
 pub mod lan;
 pub mod os;
+pub mod route_table;
 pub mod routing;
 pub mod utils;
 
 pub use ext::NetworkInterfaceExtension;
-pub use lan::{ViabilityError, get_lan_network};
+pub use lan::{ViabilityError, get_lan_interface, get_lan_network};
 pub use routing::map_ips_to_interfaces;
-pub use utils::{get_prioritized_interfaces, is_layer_2_capable, is_on_link};
+pub use utils::{
+    find_by_name, get_prioritized_interfaces, is_layer_2_capable, is_on_link, own_addresses,
+};
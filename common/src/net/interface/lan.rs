@@ -34,10 +34,28 @@ pub fn get_lan_network() -> anyhow::Result<Option<Ipv4Network>> {
     get_lan_network_with(interfaces)
 }
 
+/// Identifies the interface [`get_lan_network`] would pick.
+///
+/// # Errors
+///
+/// Returns an error if no interface is viable for LAN discovery.
+pub fn get_lan_interface() -> anyhow::Result<NetworkInterface> {
+    select_lan_interface(pnet::datalink::interfaces())
+}
+
 /// Core LAN selection logic, decoupled from OS interface dependencies for testing.
 pub(crate) fn get_lan_network_with(
     interfaces: Vec<NetworkInterface>,
 ) -> anyhow::Result<Option<Ipv4Network>> {
+    let interface: NetworkInterface = select_lan_interface(interfaces)?;
+    let private_v4_net: Option<Ipv4Network> = interface.ips.iter().find_map(|net| match net {
+        IpNetwork::V4(v4) if v4.ip().is_private() => Some(*v4),
+        _ => None,
+    });
+    Ok(private_v4_net)
+}
+
+fn select_lan_interface(interfaces: Vec<NetworkInterface>) -> anyhow::Result<NetworkInterface> {
     let interfaces_str: &str = match interfaces.len() {
         1 => "interface",
         _ => "interfaces",
@@ -70,11 +88,7 @@ pub(crate) fn get_lan_network_with(
         } else {
             anyhow::bail!("No interfaces available for LAN discovery");
         };
-    let private_v4_net: Option<Ipv4Network> = interface.ips.iter().find_map(|net| match net {
-        IpNetwork::V4(v4) if v4.ip().is_private() => Some(*v4),
-        _ => None,
-    });
-    Ok(private_v4_net)
+    Ok(interface)
 }
 
 fn is_viable_lan_interface(
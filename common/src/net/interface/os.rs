@@ -7,26 +7,49 @@
 //! Hardware detection heuristics specialized for target Operating Systems.
 //!
 //! Bridges platform-specific techniques (sysfs, CLI tools, Windows API)
-//! to classify network adapters seamlessly.
+//! to classify network adapters seamlessly and describe their link.
 
 use pnet::datalink::NetworkInterface;
 
 #[cfg(target_os = "linux")]
 #[doc(inline)]
-pub use linux_impl::{is_physical, is_wireless};
+pub use linux_impl::{is_physical, is_wireless, link_details};
 #[cfg(target_os = "macos")]
 #[doc(inline)]
-pub use macos_impl::{is_physical, is_wireless};
+pub use macos_impl::{is_physical, is_wireless, link_details};
 #[cfg(target_os = "windows")]
 #[doc(inline)]
-pub use windows_impl::{is_physical, is_wireless};
+pub use windows_impl::{is_physical, is_wireless, link_details};
+
+/// Link properties that matter when interpreting scan performance.
+///
+/// Every field is optional: links that are down, virtual adapters and some
+/// platforms simply do not report them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkDetails {
+    /// Negotiated speed in Mbit/s.
+    pub speed_mbps: Option<u32>,
+    pub duplex: Option<Duplex>,
+    /// Kernel driver on Linux, adapter description on Windows.
+    pub driver: Option<String>,
+    pub mtu: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    Full,
+    Half,
+}
 
 /// Determines if the interface corresponds to a physical adapter (not virtual).
 #[cfg(target_os = "linux")]
 pub mod linux_impl {
     use super::*;
+    use std::fs;
     use std::path::Path;
 
+    const SYSFS_NET: &str = "/sys/class/net";
+
     pub fn is_physical(interface: &NetworkInterface) -> bool {
         Path::new(&format!("/sys/class/net/{}/device", interface.name)).exists()
     }
@@ -34,6 +57,38 @@ pub mod linux_impl {
     pub fn is_wireless(interface: &NetworkInterface) -> bool {
         Path::new(&format!("sys/class/net/{}/wireless", interface.name)).exists()
     }
+
+    /// Reads speed, duplex, driver and MTU from sysfs.
+    pub fn link_details(interface: &NetworkInterface) -> LinkDetails {
+        read_link_details(&Path::new(SYSFS_NET).join(&interface.name))
+    }
+
+    /// Reads the link attributes from the sysfs directory of one interface.
+    ///
+    /// Reading `speed` fails while the link is down, and virtual interfaces
+    /// report `-1` and `unknown`, which all end up as `None`.
+    pub(super) fn read_link_details(dir: &Path) -> LinkDetails {
+        let read = |name: &str| -> Option<String> {
+            fs::read_to_string(dir.join(name))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+
+        LinkDetails {
+            speed_mbps: read("speed")
+                .and_then(|speed| speed.parse::<u32>().ok())
+                .filter(|speed| *speed > 0 && *speed != u32::MAX),
+            duplex: match read("duplex").as_deref() {
+                Some("full") => Some(Duplex::Full),
+                Some("half") => Some(Duplex::Half),
+                _ => None,
+            },
+            driver: fs::read_link(dir.join("device/driver"))
+                .ok()
+                .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned())),
+            mtu: read("mtu").and_then(|mtu| mtu.parse().ok()),
+        }
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -102,13 +157,60 @@ pub mod macos_impl {
             .wireless_devices
             .contains(&interface.name)
     }
+
+    /// Reads MTU, speed and duplex from `ifconfig`. macOS does not expose
+    /// the driver of an interface.
+    pub fn link_details(interface: &NetworkInterface) -> LinkDetails {
+        Command::new("ifconfig")
+            .arg(&interface.name)
+            .output()
+            .map(|out| parse_ifconfig(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or_default()
+    }
+
+    /// Parses the `mtu 1500` of the first line and a media line such as
+    /// `media: autoselect (1000baseT <full-duplex>)`.
+    fn parse_ifconfig(output: &str) -> LinkDetails {
+        let mtu: Option<u32> = output
+            .split_whitespace()
+            .skip_while(|word| *word != "mtu")
+            .nth(1)
+            .and_then(|mtu| mtu.parse().ok());
+
+        let media: String = output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("media:"))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let speed_mbps: Option<u32> = media
+            .split(|c: char| c == '(' || c.is_whitespace())
+            .find_map(|word| word.split_once("base"))
+            .and_then(|(speed, _)| match speed.strip_suffix('g') {
+                Some(gbps) => gbps.parse::<u32>().ok().map(|gbps| gbps * 1000),
+                None => speed.parse().ok(),
+            });
+        let duplex: Option<Duplex> = if media.contains("full-duplex") {
+            Some(Duplex::Full)
+        } else if media.contains("half-duplex") {
+            Some(Duplex::Half)
+        } else {
+            None
+        };
+
+        LinkDetails {
+            speed_mbps,
+            duplex,
+            driver: None,
+            mtu,
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
 pub mod windows_impl {
     use super::*;
     use std::cell::RefCell;
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use std::time::{Duration, Instant};
 
     use windows_sys::Win32::NetworkManagement::IpHelper::{
@@ -123,6 +225,7 @@ pub mod windows_impl {
     struct WindowsInterfaceInfo {
         physical_devices: HashSet<String>,
         wireless_devices: HashSet<String>,
+        links: HashMap<String, LinkDetails>,
     }
 
     // Thread-local cache prevents the N+1 FFI performance trap when iterating,
@@ -134,6 +237,7 @@ pub mod windows_impl {
     fn fetch_windows_interface_info() -> WindowsInterfaceInfo {
         let mut physical = HashSet::new();
         let mut wireless = HashSet::new();
+        let mut links = HashMap::new();
 
         unsafe {
             let mut table_ptr: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
@@ -169,6 +273,27 @@ pub mod windows_impl {
                         guid.data4[7]
                     );
 
+                    // u64::MAX marks an unknown speed.
+                    let speed_mbps: Option<u32> = Some(row.TransmitLinkSpeed)
+                        .filter(|bps| *bps != 0 && *bps != u64::MAX)
+                        .map(|bps| (bps / 1_000_000) as u32);
+                    let description_len: usize = row
+                        .Description
+                        .iter()
+                        .position(|c| *c == 0)
+                        .unwrap_or(row.Description.len());
+                    let description: String =
+                        String::from_utf16_lossy(&row.Description[..description_len]);
+                    links.insert(
+                        guid_str.clone(),
+                        LinkDetails {
+                            speed_mbps,
+                            duplex: None,
+                            driver: (!description.is_empty()).then_some(description),
+                            mtu: Some(row.Mtu),
+                        },
+                    );
+
                     // Must check !is_software first to prevent spoofed connector bits
                     if !is_software && (is_hardware_bit || has_connector_bit) {
                         physical.insert(guid_str.clone());
@@ -185,6 +310,7 @@ pub mod windows_impl {
         WindowsInterfaceInfo {
             physical_devices: physical,
             wireless_devices: wireless,
+            links,
         }
     }
 
@@ -230,4 +356,63 @@ pub mod windows_impl {
             info.wireless_devices.contains(name)
         })
     }
+
+    /// Reports MTU, transmit speed and adapter description from the interface
+    /// table. Windows does not report the duplex mode there.
+    pub fn link_details(interface: &NetworkInterface) -> LinkDetails {
+        with_cache(|info| {
+            let name = interface
+                .name
+                .strip_prefix(r"\Device\NPF_")
+                .unwrap_or(&interface.name);
+            info.links.get(name).cloned().unwrap_or_default()
+        })
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::{fs, os::unix::fs::symlink, path::PathBuf};
+
+    #[test]
+    fn reads_link_details_from_sysfs() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("zond-sysfs-{}", std::process::id()));
+        let driver: PathBuf = dir.join("drivers/e1000e");
+        let interface: PathBuf = dir.join("eth0");
+        fs::create_dir_all(&driver).unwrap();
+        fs::create_dir_all(interface.join("device")).unwrap();
+        fs::write(interface.join("speed"), "1000\n").unwrap();
+        fs::write(interface.join("duplex"), "full\n").unwrap();
+        fs::write(interface.join("mtu"), "9000\n").unwrap();
+        symlink(&driver, interface.join("device/driver")).unwrap();
+
+        let details: LinkDetails = linux_impl::read_link_details(&interface);
+
+        // A link that is down reports no speed and an unknown duplex.
+        fs::write(interface.join("speed"), "-1\n").unwrap();
+        fs::write(interface.join("duplex"), "unknown\n").unwrap();
+        let down: LinkDetails = linux_impl::read_link_details(&interface);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            details,
+            LinkDetails {
+                speed_mbps: Some(1000),
+                duplex: Some(Duplex::Full),
+                driver: Some("e1000e".to_string()),
+                mtu: Some(9000),
+            }
+        );
+        assert_eq!((down.speed_mbps, down.duplex), (None, None));
+    }
 }
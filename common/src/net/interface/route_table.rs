@@ -0,0 +1,290 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Route Table
+//!
+//! Reads the host's IPv4 routing table so targets can be assigned to interfaces
+//! by longest-prefix match, instead of asking the kernel about every single
+//! address with a UDP connect.
+//!
+//! Linux reads the kernel's main table from `/proc/net/route`, macOS parses
+//! `netstat -rn -f inet`. On other platforms [`RouteTable::load`] returns `None`
+//! and callers fall back to per-address lookups.
+
+use std::net::Ipv4Addr;
+
+/// `RTF_UP` from `<linux/route.h>`: the route is usable.
+const RTF_UP: u16 = 0x0001;
+/// `RTF_GATEWAY` from `<linux/route.h>`: the destination is behind a gateway.
+const RTF_GATEWAY: u16 = 0x0002;
+
+/// A single IPv4 route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The destination network address.
+    pub network: Ipv4Addr,
+    /// The destination prefix length (`0` for the default route).
+    pub prefix: u8,
+    /// The next hop, or `None` for directly connected destinations.
+    pub gateway: Option<Ipv4Addr>,
+    /// The name of the outgoing interface.
+    pub interface: String,
+    /// The route metric; lower wins between routes of equal prefix length.
+    pub metric: u32,
+}
+
+impl Route {
+    /// Returns the first address covered by the route as an integer.
+    pub fn first(&self) -> u32 {
+        u32::from(self.network) & self.mask()
+    }
+
+    /// Returns the last address covered by the route as an integer.
+    pub fn last(&self) -> u32 {
+        self.first() | !self.mask()
+    }
+
+    /// Checks if `ip` lies within the route's destination network.
+    pub fn contains(&self, ip: Ipv4Addr) -> bool {
+        u32::from(ip) & self.mask() == self.first()
+    }
+
+    fn mask(&self) -> u32 {
+        u32::MAX
+            .checked_shl(32 - u32::from(self.prefix))
+            .unwrap_or(0)
+    }
+}
+
+/// The IPv4 routes of the host, ordered for longest-prefix matching.
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<Route>,
+}
+
+impl RouteTable {
+    /// Creates a table from `routes`, most specific (then cheapest) first.
+    pub fn new(mut routes: Vec<Route>) -> Self {
+        routes.sort_by(|a, b| b.prefix.cmp(&a.prefix).then(a.metric.cmp(&b.metric)));
+        Self { routes }
+    }
+
+    /// Reads the routing table of the running system.
+    ///
+    /// Returns `None` if the platform is unsupported, the table cannot be read,
+    /// or it holds no usable routes.
+    pub fn load() -> Option<Self> {
+        let table: Self = load_platform()?;
+        (!table.is_empty()).then_some(table)
+    }
+
+    /// Parses the contents of Linux's `/proc/net/route`.
+    ///
+    /// Routes that are not up or have a non-contiguous mask are skipped.
+    pub fn from_proc_net_route(content: &str) -> Self {
+        let routes: Vec<Route> = content
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 8 {
+                    return None;
+                }
+
+                let network: Ipv4Addr = parse_proc_addr(fields[1])?;
+                let gateway: Ipv4Addr = parse_proc_addr(fields[2])?;
+                let flags: u16 = u16::from_str_radix(fields[3], 16).ok()?;
+                let metric: u32 = fields[6].parse().ok()?;
+                let mask: u32 = u32::from(parse_proc_addr(fields[7])?);
+
+                if flags & RTF_UP == 0 || mask.leading_ones() + mask.trailing_zeros() != 32 {
+                    return None;
+                }
+
+                Some(Route {
+                    network,
+                    prefix: mask.leading_ones() as u8,
+                    gateway: (flags & RTF_GATEWAY != 0).then_some(gateway),
+                    interface: fields[0].to_string(),
+                    metric,
+                })
+            })
+            .collect();
+
+        Self::new(routes)
+    }
+
+    /// Parses the output of `netstat -rn -f inet` as printed by macOS and the BSDs.
+    ///
+    /// Destinations may be abbreviated (`10`, `192.168.1`) or carry an explicit
+    /// prefix (`10.0/16`); host routes are recognized by the `H` flag.
+    pub fn from_netstat(content: &str) -> Self {
+        let routes: Vec<Route> = content
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 4 {
+                    return None;
+                }
+                let (destination, gateway, flags, interface) =
+                    (fields[0], fields[1], fields[2], fields[3]);
+
+                let (network, prefix) = parse_netstat_destination(destination, flags)?;
+                let gateway: Option<Ipv4Addr> = if flags.contains('G') {
+                    gateway.parse().ok()
+                } else {
+                    None
+                };
+
+                Some(Route {
+                    network,
+                    prefix,
+                    gateway,
+                    interface: interface.to_string(),
+                    metric: 0,
+                })
+            })
+            .collect();
+
+        Self::new(routes)
+    }
+
+    /// Returns the routes, most specific first.
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Returns `true` if the table holds no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Returns the route the kernel would pick for `ip`, if any.
+    pub fn lookup(&self, ip: Ipv4Addr) -> Option<&Route> {
+        self.routes.iter().find(|route| route.contains(ip))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn load_platform() -> Option<RouteTable> {
+    let content: String = std::fs::read_to_string("/proc/net/route").ok()?;
+    Some(RouteTable::from_proc_net_route(&content))
+}
+
+#[cfg(target_os = "macos")]
+fn load_platform() -> Option<RouteTable> {
+    let output = std::process::Command::new("netstat")
+        .args(["-rn", "-f", "inet"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(RouteTable::from_netstat(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn load_platform() -> Option<RouteTable> {
+    None
+}
+
+/// Decodes an address field of `/proc/net/route`, which the kernel prints as the
+/// raw network-order word in host byte order.
+fn parse_proc_addr(field: &str) -> Option<Ipv4Addr> {
+    let raw: u32 = u32::from_str_radix(field, 16).ok()?;
+    Some(Ipv4Addr::from(raw.to_ne_bytes()))
+}
+
+fn parse_netstat_destination(destination: &str, flags: &str) -> Option<(Ipv4Addr, u8)> {
+    if destination == "default" {
+        return Some((Ipv4Addr::UNSPECIFIED, 0));
+    }
+
+    let (addr, prefix) = match destination.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+        None => (destination, None),
+    };
+
+    let mut octets: [u8; 4] = [0; 4];
+    let mut count: usize = 0;
+    for part in addr.split('.') {
+        *octets.get_mut(count)? = part.parse().ok()?;
+        count += 1;
+    }
+
+    let prefix: u8 = match prefix {
+        Some(prefix) if prefix <= 32 => prefix,
+        Some(_) => return None,
+        None if flags.contains('H') => 32,
+        None => (count * 8) as u8,
+    };
+    Some((Ipv4Addr::from(octets), prefix))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_proc_net_route() {
+        let content: &str = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+tun0\t0000000A\t00000000\t0001\t0\t0\t0\t000000FF\t0\t0\t0
+down0\t0000A8C0\t00000000\t0000\t0\t0\t0\t0000FFFF\t0\t0\t0
+";
+        let table: RouteTable = RouteTable::from_proc_net_route(content);
+        assert_eq!(table.routes().len(), 3);
+
+        let default: &Route = table.lookup(Ipv4Addr::new(8, 8, 8, 8)).unwrap();
+        assert_eq!(default.interface, "eth0");
+        assert_eq!(default.gateway, Some(Ipv4Addr::new(192, 168, 1, 1)));
+
+        let vpn: &Route = table.lookup(Ipv4Addr::new(10, 20, 0, 1)).unwrap();
+        assert_eq!((vpn.interface.as_str(), vpn.prefix), ("tun0", 8));
+        assert_eq!(vpn.gateway, None);
+    }
+
+    #[test]
+    fn parses_netstat_and_prefers_longest_prefix() {
+        let content: &str = "\
+Routing tables
+
+Internet:
+Destination        Gateway            Flags               Netif Expire
+default            192.168.1.1        UGScg                 en0
+10.8/16            10.8.0.1           UGSc                utun3
+127                127.0.0.1          UCS                   lo0
+192.168.1          link#6             UCS                   en0      !
+192.168.1.1        a4:91:b1:0:0:1     UHLWIir               en0   1186
+";
+        let table: RouteTable = RouteTable::from_netstat(content);
+        assert_eq!(table.routes().len(), 5);
+
+        let vpn: &Route = table.lookup(Ipv4Addr::new(10, 8, 3, 4)).unwrap();
+        assert_eq!(vpn.interface, "utun3");
+        assert_eq!((vpn.first(), vpn.last()), (0x0A08_0000, 0x0A08_FFFF));
+
+        let host: &Route = table.lookup(Ipv4Addr::new(192, 168, 1, 1)).unwrap();
+        assert_eq!(host.prefix, 32);
+
+        let fallback: &Route = table.lookup(Ipv4Addr::new(1, 1, 1, 1)).unwrap();
+        assert_eq!(fallback.gateway, Some(Ipv4Addr::new(192, 168, 1, 1)));
+    }
+}
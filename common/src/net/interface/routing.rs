@@ -5,30 +5,39 @@
 // https://mozilla.org/MPL/2.0/.
 
 use pnet::datalink::{self, NetworkInterface};
+use pnet::ipnetwork::IpNetwork;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, UdpSocket};
 
-use crate::models::ip::set::IpSet;
+use super::route_table::RouteTable;
+use crate::models::ip::{range::Ipv4Range, set::IpSet};
+use crate::phase;
 
 /// Maps target IPs to the interface used to reach them, split by Local vs Routed.
 /// Returns: Map<Interface, (Local_Targets, Routed_Targets)> and a set of Unmapped Targets.
 ///
-/// Under the hood, this evaluates `pnet::datalink::interfaces()`.
+/// Under the hood, this evaluates `pnet::datalink::interfaces()` and the system's
+/// route table. Without a readable route table, routed targets are resolved one
+/// UDP connect at a time.
 pub fn map_ips_to_interfaces(
     collection: IpSet,
 ) -> (HashMap<NetworkInterface, (IpSet, IpSet)>, IpSet) {
+    let _phase = phase!("interface mapping", targets = collection.len());
     let interfaces: Vec<NetworkInterface> = datalink::interfaces()
         .into_iter()
         .filter(|i| i.is_up() && !i.is_loopback() && !i.ips.is_empty())
         .collect();
 
-    map_ips_to_interfaces_with(collection, interfaces)
+    let routes: Option<RouteTable> = RouteTable::load();
+
+    map_ips_to_interfaces_with(collection, interfaces, routes.as_ref())
 }
 
 pub(crate) fn map_ips_to_interfaces_with(
     collection: IpSet,
     interfaces: Vec<NetworkInterface>,
+    routes: Option<&RouteTable>,
 ) -> (HashMap<NetworkInterface, (IpSet, IpSet)>, IpSet) {
     let ip_to_idx: HashMap<IpAddr, usize> = interfaces
         .iter()
@@ -37,8 +46,7 @@ pub(crate) fn map_ips_to_interfaces_with(
         .collect();
 
     let mut result_map: HashMap<usize, (IpSet, IpSet)> = HashMap::new();
-    let mut unmapped_ips = IpSet::new();
-    let mut singles_to_route = Vec::new();
+    let mut unowned_ranges: Vec<Ipv4Range> = Vec::new();
 
     // 1. Handle Ranges
     for range in collection.ranges() {
@@ -60,13 +68,27 @@ pub(crate) fn map_ips_to_interfaces_with(
         if let Some(idx) = owner_idx {
             result_map.entry(idx).or_default().0.insert_range(*range);
         } else {
-            for ip in range.to_iter() {
-                singles_to_route.push(ip);
-            }
+            unowned_ranges.push(*range);
+        }
+    }
+
+    // 2. With a route table, resolve the remaining ranges piece by piece.
+    if let Some(routes) = routes {
+        let mut unmapped_ips: IpSet = IpSet::new();
+        for range in unowned_ranges {
+            map_range_with_routes(
+                range,
+                &interfaces,
+                routes,
+                &mut result_map,
+                &mut unmapped_ips,
+            );
         }
+        return (into_interface_map(result_map, &interfaces), unmapped_ips);
     }
 
     type ThreadSockets = (Option<UdpSocket>, Option<UdpSocket>);
+    type Mapping = (HashMap<usize, (IpSet, IpSet)>, IpSet);
 
     enum RouteType {
         Local,
@@ -74,11 +96,16 @@ pub(crate) fn map_ips_to_interfaces_with(
         Unmapped,
     }
 
-    let processed_singles: Vec<(Option<usize>, RouteType, IpAddr)> = singles_to_route
+    // 3. Otherwise route the remaining addresses one by one. They are streamed straight out
+    // of their ranges and folded into per-thread sets, so a huge routed range is
+    // never expanded into a list of addresses.
+    let (routed_map, unmapped_ips): Mapping = unowned_ranges
         .par_iter()
+        .flat_map(|range| (u32::from(range.start_addr)..=u32::from(range.end_addr)).into_par_iter())
         .map_init(
             || -> ThreadSockets { (None, None) },
-            |sockets, &target_ip| {
+            |sockets, target| {
+                let target_ip: IpAddr = IpAddr::V4(Ipv4Addr::from(target));
                 if let Some(idx) = find_local_index(&interfaces, target_ip) {
                     return (Some(idx), RouteType::Local, target_ip);
                 }
@@ -92,32 +119,104 @@ pub(crate) fn map_ips_to_interfaces_with(
                 (None, RouteType::Unmapped, target_ip)
             },
         )
+        .fold(
+            Mapping::default,
+            |(mut map, mut unmapped), (idx_opt, route_type, ip)| {
+                match (route_type, idx_opt) {
+                    (RouteType::Local, Some(idx)) => map.entry(idx).or_default().0.insert(ip),
+                    (RouteType::Routed, Some(idx)) => map.entry(idx).or_default().1.insert(ip),
+                    (RouteType::Unmapped, _) => unmapped.insert(ip),
+                    _ => {}
+                }
+                (map, unmapped)
+            },
+        )
+        .reduce(
+            Mapping::default,
+            |(mut map, mut unmapped), (other_map, other_unmapped)| {
+                merge_mapping(&mut map, other_map);
+                unmapped.merge(other_unmapped);
+                (map, unmapped)
+            },
+        );
+
+    merge_mapping(&mut result_map, routed_map);
+
+    (into_interface_map(result_map, &interfaces), unmapped_ips)
+}
+
+/// Splits `range` wherever a route or local subnet begins or ends and assigns each
+/// piece by longest-prefix match, so a /16 costs one lookup per route rather than
+/// one socket call per address.
+fn map_range_with_routes(
+    range: Ipv4Range,
+    interfaces: &[NetworkInterface],
+    routes: &RouteTable,
+    map: &mut HashMap<usize, (IpSet, IpSet)>,
+    unmapped: &mut IpSet,
+) {
+    let start: u32 = u32::from(range.start_addr);
+    let end: u32 = u32::from(range.end_addr);
+
+    let local_nets = interfaces
+        .iter()
+        .flat_map(|iface| iface.ips.iter())
+        .filter_map(|ip_net| match ip_net {
+            IpNetwork::V4(net) => Some((u32::from(net.network()), u32::from(net.broadcast()))),
+            IpNetwork::V6(_) => None,
+        });
+    let route_nets = routes
+        .routes()
+        .iter()
+        .map(|route| (route.first(), route.last()));
+
+    let mut cuts: Vec<u32> = local_nets
+        .chain(route_nets)
+        .flat_map(|(first, last)| [Some(first), last.checked_add(1)])
+        .flatten()
+        .filter(|cut| *cut > start && *cut <= end)
         .collect();
+    cuts.push(start);
+    cuts.sort_unstable();
+    cuts.dedup();
 
-    for (idx_opt, route_type, ip) in processed_singles {
-        match route_type {
-            RouteType::Local => {
-                if let Some(idx) = idx_opt {
-                    result_map.entry(idx).or_default().0.insert(ip);
-                }
-            }
-            RouteType::Routed => {
-                if let Some(idx) = idx_opt {
-                    result_map.entry(idx).or_default().1.insert(ip);
-                }
-            }
-            RouteType::Unmapped => {
-                unmapped_ips.insert(ip);
-            }
+    for (i, &piece_start) in cuts.iter().enumerate() {
+        let piece_end: u32 = cuts.get(i + 1).map_or(end, |next| next - 1);
+        let first: Ipv4Addr = Ipv4Addr::from(piece_start);
+        let Ok(piece) = Ipv4Range::new(first, Ipv4Addr::from(piece_end)) else {
+            continue;
+        };
+
+        if let Some(idx) = find_local_index(interfaces, IpAddr::V4(first)) {
+            map.entry(idx).or_default().0.insert_range(piece);
+        } else if let Some(idx) = routes.lookup(first).and_then(|route| {
+            interfaces
+                .iter()
+                .position(|iface| iface.name == route.interface)
+        }) {
+            map.entry(idx).or_default().1.insert_range(piece);
+        } else {
+            unmapped.insert_range(piece);
         }
     }
+}
 
-    let mapped_interfaces = result_map
-        .into_iter()
+fn into_interface_map(
+    map: HashMap<usize, (IpSet, IpSet)>,
+    interfaces: &[NetworkInterface],
+) -> HashMap<NetworkInterface, (IpSet, IpSet)> {
+    map.into_iter()
         .map(|(idx, (local_ips, routed_ips))| (interfaces[idx].clone(), (local_ips, routed_ips)))
-        .collect();
+        .collect()
+}
 
-    (mapped_interfaces, unmapped_ips)
+/// Adds the local and routed targets of `other` to those of `map`.
+fn merge_mapping(map: &mut HashMap<usize, (IpSet, IpSet)>, other: HashMap<usize, (IpSet, IpSet)>) {
+    for (idx, (local, routed)) in other {
+        let entry: &mut (IpSet, IpSet) = map.entry(idx).or_default();
+        entry.0.merge(local);
+        entry.1.merge(routed);
+    }
 }
 
 fn find_local_index(interfaces: &[NetworkInterface], target: IpAddr) -> Option<usize> {
@@ -168,16 +267,21 @@ fn resolve_route_source_ip(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pnet::ipnetwork::{IpNetwork, Ipv4Network};
+    use crate::net::interface::route_table::Route;
+    use pnet::ipnetwork::Ipv4Network;
 
     fn mock_interface(ip: IpAddr, prefix: u8) -> NetworkInterface {
+        mock_named_interface("test0", ip, prefix)
+    }
+
+    fn mock_named_interface(name: &str, ip: IpAddr, prefix: u8) -> NetworkInterface {
         let net = match ip {
             IpAddr::V4(v4) => IpNetwork::V4(Ipv4Network::new(v4, prefix).unwrap()),
             IpAddr::V6(_v6) => unimplemented!(),
         };
 
         NetworkInterface {
-            name: "test0".to_string(),
+            name: name.to_string(),
             description: "".to_string(),
             index: 0,
             mac: None,
@@ -211,4 +315,42 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn maps_routed_ranges_by_longest_prefix() {
+        let interfaces: Vec<NetworkInterface> = vec![
+            mock_named_interface("eth0", IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)), 24),
+            mock_named_interface("tun0", IpAddr::V4(Ipv4Addr::new(10, 8, 0, 2)), 24),
+        ];
+        let route = |network: Ipv4Addr, prefix: u8, interface: &str| Route {
+            network,
+            prefix,
+            gateway: None,
+            interface: interface.to_string(),
+            metric: 0,
+        };
+        let routes: RouteTable = RouteTable::new(vec![
+            route(Ipv4Addr::UNSPECIFIED, 0, "eth0"),
+            route(Ipv4Addr::new(10, 0, 0, 0), 8, "tun0"),
+            route(Ipv4Addr::new(10, 9, 0, 0), 16, "gone0"),
+        ]);
+
+        // 10.0.0.0 - 11.0.0.255: tun0 local /24, tun0 routed, unmapped /16, eth0 default
+        let mut targets: IpSet = IpSet::new();
+        targets.insert_range(
+            Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(11, 0, 0, 255)).unwrap(),
+        );
+
+        let (map, unmapped) =
+            map_ips_to_interfaces_with(targets, interfaces.clone(), Some(&routes));
+
+        let (eth_local, eth_routed) = &map[&interfaces[0]];
+        let (tun_local, tun_routed) = &map[&interfaces[1]];
+        assert!(eth_local.is_empty());
+        assert_eq!(eth_routed.len(), 256);
+        assert_eq!(tun_local.len(), 256);
+        assert_eq!(tun_routed.len(), (1 << 24) - 256 - (1 << 16));
+        assert_eq!(unmapped.len(), 1 << 16);
+        assert!(unmapped.contains(&IpAddr::V4(Ipv4Addr::new(10, 9, 1, 1))));
+    }
 }
@@ -4,6 +4,8 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+use std::{collections::HashSet, net::IpAddr};
+
 use crate::models::ip::set::IpSet;
 use pnet::datalink::NetworkInterface;
 
@@ -19,6 +21,25 @@ pub fn get_prioritized_interfaces(limit: usize) -> anyhow::Result<Vec<NetworkInt
     Ok(get_prioritized_interfaces_with(limit, interfaces))
 }
 
+/// Looks up the current state of an interface by name.
+///
+/// Returns `None` if the interface no longer exists, e.g. after a USB adapter was
+/// unplugged or a VPN tunnel was torn down.
+pub fn find_by_name(name: &str) -> Option<NetworkInterface> {
+    pnet::datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == name)
+}
+
+/// Collects the addresses of every interface of this machine, whether up or
+/// not, loopback included.
+pub fn own_addresses() -> HashSet<IpAddr> {
+    pnet::datalink::interfaces()
+        .iter()
+        .flat_map(|intf| intf.ips.iter().map(|net| net.ip()))
+        .collect()
+}
+
 /// Core prioritization logic, decoupled from OS interface dependencies for testing.
 pub(crate) fn get_prioritized_interfaces_with(
     limit: usize,
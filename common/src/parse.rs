@@ -12,40 +12,149 @@
 //!
 //! Currently supported:
 //! * **IP Resolution**: Translating strings and keywords into [`IpSet`] models.
-
+//! * **Discovery Annotations**: Per-target settings such as `10.0.0.0/24@syn:8443`.
+//! * **Target Hints**: Suggestions for malformed targets, such as a CIDR block for `10.0.0.*`.
+//! * **Never-Scan List**: Refusing targets inside ranges an operator listed as off limits.
+//! * **Nmap Import**: Reading `nmap -oX` reports into [`Host`](crate::models::host::Host) records.
+//! * **Cron Expressions**: The schedules of `zond schedule`.
+
+pub mod cron;
+pub mod denylist;
+pub mod hint;
 pub mod ip;
+pub mod nmap;
 
 pub use ip::{IS_LAN_SCAN, IpParseError, to_set as to_ipset};
 
+use crate::config::Technique;
 use crate::models::ip::set::IpSet;
 use crate::models::port::PortSet;
 use crate::models::target::{TargetMap, TargetSet};
+use crate::phase;
+use denylist::Denylist;
 
 /// Parses a list of target strings (e.g. `["1.1.1.1:80,443", "8.8.8.8"]`) into a `TargetMap`.
 /// Combines per-target specified ports, or falls back to `global_ports`.
+/// Targets touching the never-scan list ([`Denylist`]) fail the whole map.
 pub fn to_target_map(
     targets: &[String],
     global_ports: PortSet,
 ) -> Result<TargetMap, anyhow::Error> {
+    let _phase = phase!("target parsing", targets = targets.len());
+    let denylist: Denylist = Denylist::system()?;
+    let mut denied: Vec<String> = Vec::new();
     let mut map = TargetMap::new();
 
     for target in targets {
         if let Some((ip_str, port_str)) = target.split_once(':') {
-            let ip_set = IpSet::try_from(ip_str)
-                .map_err(|e| anyhow::anyhow!("Invalid IP in '{}': {}", ip_str, e))?;
+            let ip_set = IpSet::try_from(ip_str).map_err(|e| {
+                anyhow::anyhow!("Invalid IP in '{}': {}{}", ip_str, e, hint::suffix(ip_str))
+            })?;
             let port_set = PortSet::try_from(port_str)
                 .map_err(|e| anyhow::anyhow!("Invalid Port in '{}': {}", port_str, e))?;
+            denylist.check(ip_str, &ip_set, &mut denied);
             map.add_unit(TargetSet::new(ip_set, port_set));
         } else {
-            let ip_set = IpSet::try_from(target.as_str())
-                .map_err(|e| anyhow::anyhow!("Invalid IP '{}': {}", target, e))?;
+            let ip_set = IpSet::try_from(target.as_str()).map_err(|e| {
+                anyhow::anyhow!("Invalid IP '{}': {}{}", target, e, hint::suffix(target))
+            })?;
+            denylist.check(target, &ip_set, &mut denied);
             map.add_unit(TargetSet::new(ip_set, global_ports.clone()));
         }
     }
 
+    if !denied.is_empty() {
+        return Err(IpParseError::Denied(denied).into());
+    }
     Ok(map)
 }
 
+/// Discovery settings attached to a target as `@technique[:port]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DiscoveryOverride {
+    pub technique: Technique,
+    /// Destination port of TCP SYN probes; only allowed with `syn`.
+    pub syn_port: Option<u16>,
+}
+
+/// Splits discovery targets into groups sharing the same settings.
+///
+/// Every comma-separated part of a target may end in `@technique` or
+/// `@syn:PORT` (e.g. `["192.168.1.0/24@arp", "10.0.0.5@syn:8443"]`). Parts without
+/// an annotation use `default_technique`. Groups keep the order in which their
+/// settings first appear. CIDR blocks lose their network and broadcast
+/// addresses unless `include_broadcast` is set.
+///
+/// # Errors
+///
+/// Returns an [`IpParseError`] if an address or annotation is malformed, a port is
+/// given for a technique other than `syn`, or no address was given at all.
+pub fn to_discovery_groups<S: AsRef<str>>(
+    inputs: &[S],
+    default_technique: Technique,
+    include_broadcast: bool,
+) -> Result<Vec<(DiscoveryOverride, IpSet)>, IpParseError> {
+    let _phase = phase!("target parsing", targets = inputs.len());
+    let default: DiscoveryOverride = DiscoveryOverride {
+        technique: default_technique,
+        syn_port: None,
+    };
+    let mut groups: Vec<(DiscoveryOverride, Vec<String>)> = Vec::new();
+
+    for part in inputs
+        .iter()
+        .flat_map(|input| input.as_ref().split(','))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let (target, settings) = match part.split_once('@') {
+            Some((target, annotation)) => (target.trim(), parse_annotation(annotation)?),
+            None => (part, default),
+        };
+
+        match groups.iter_mut().find(|(s, _)| *s == settings) {
+            Some((_, targets)) => targets.push(target.to_string()),
+            None => groups.push((settings, vec![target.to_string()])),
+        }
+    }
+
+    if groups.is_empty() {
+        return Err(IpParseError::EmptySet);
+    }
+
+    groups
+        .into_iter()
+        .map(|(settings, targets)| Ok((settings, to_ipset(&targets, include_broadcast)?)))
+        .collect()
+}
+
+fn parse_annotation(annotation: &str) -> Result<DiscoveryOverride, IpParseError> {
+    let invalid =
+        |reason: &str| IpParseError::InvalidAnnotation(annotation.to_string(), reason.to_string());
+
+    let (technique, port) = match annotation.split_once(':') {
+        Some((technique, port)) => (technique, Some(port)),
+        None => (annotation, None),
+    };
+    let technique: Technique = technique.trim().parse().map_err(|e: String| invalid(&e))?;
+
+    let syn_port: Option<u16> = match port {
+        None => None,
+        Some(_) if technique != Technique::Syn => {
+            return Err(invalid("a port can only be given for syn"));
+        }
+        Some(port) => match port.trim().parse::<u16>() {
+            Ok(port) if port > 0 => Some(port),
+            _ => return Err(invalid("port must be between 1 and 65535")),
+        },
+    };
+
+    Ok(DiscoveryOverride {
+        technique,
+        syn_port,
+    })
+}
+
 // ╔════════════════════════════════════════════╗
 // ║ ████████╗███████╗███████╗████████╗███████╗ ║
 // ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
@@ -64,7 +173,7 @@ mod tests {
     fn test_facade_ip_resolution() {
         let inputs = vec!["127.0.0.1", "10.0.0.1-5"];
 
-        let set = to_ipset(&inputs).expect("Facade should resolve IP targets");
+        let set = to_ipset(&inputs, false).expect("Facade should resolve IP targets");
 
         assert_eq!(set.len(), 6);
         assert!(set.contains(&"127.0.0.1".parse::<IpAddr>().unwrap()));
@@ -74,16 +183,43 @@ mod tests {
     #[test]
     fn test_facade_empty_input() {
         let inputs: Vec<&str> = vec![];
-        let result = to_ipset(&inputs);
+        let result = to_ipset(&inputs, false);
 
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), IpParseError::EmptySet);
     }
 
+    #[test]
+    fn discovery_groups_follow_annotations() {
+        let inputs = vec![
+            "192.168.1.0/30@arp, 10.0.0.5@syn:8443",
+            "10.0.0.9",
+            "172.16.0.1@arp",
+        ];
+        let groups: Vec<(DiscoveryOverride, IpSet)> =
+            to_discovery_groups(&inputs, Technique::Auto, false).unwrap();
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].0.technique, Technique::Arp);
+        assert_eq!(groups[0].1.len(), 3);
+        assert_eq!(groups[1].0.syn_port, Some(8443));
+        assert_eq!(groups[2].0.technique, Technique::Auto);
+    }
+
+    #[test]
+    fn discovery_groups_reject_bad_annotations() {
+        for input in ["10.0.0.1@ping", "10.0.0.1@arp:80", "10.0.0.1@syn:0"] {
+            assert!(matches!(
+                to_discovery_groups(&[input], Technique::Auto, false),
+                Err(IpParseError::InvalidAnnotation(..))
+            ));
+        }
+    }
+
     #[test]
     fn test_facade_comma_splitting() {
         let inputs = vec!["1.1.1.1, 2.2.2.2"];
-        let set = to_ipset(&inputs).unwrap();
+        let set = to_ipset(&inputs, false).unwrap();
 
         assert_eq!(set.len(), 2);
     }
@@ -0,0 +1,271 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Cron Expressions
+//!
+//! Parses the five-field schedules of crontab(5), `MINUTE HOUR DAY MONTH
+//! WEEKDAY`, used by `zond schedule`. A field is `*`, a number, a range
+//! (`1-5`), either of those with a step (`*/15`, `0-30/10`), or a
+//! comma-separated list of them. Weekdays run from 0 (Sunday) to 7 (Sunday
+//! again). `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` stand for
+//! their usual expansions.
+//!
+//! As in cron, a day matches if either its day of the month or its weekday
+//! does when both fields are restricted. All times are UTC.
+
+use std::{fmt, str::FromStr};
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+/// How far [`CronExpr::next_after`] looks ahead, enough for any leap day.
+const SEARCH_DAYS: u64 = 8 * 366;
+/// Days from 0000-03-01 to 1970-01-01 in the proleptic Gregorian calendar.
+const EPOCH_SHIFT: i64 = 719_468;
+
+/// A parsed five-field cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of the month was given as anything but `*`.
+    days_restricted: bool,
+    /// Whether the weekday was given as anything but `*`.
+    weekdays_restricted: bool,
+}
+
+/// A minute of UTC wall clock time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UtcMinute {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let expanded: &str = match input.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (MINUTE HOUR DAY MONTH WEEKDAY), got '{input}'"
+            ));
+        };
+
+        let weekdays: u64 = parse_field(weekday, 0, 7)?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            // 7 is another name for Sunday.
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7F,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+}
+
+impl CronExpr {
+    /// Returns the first time the schedule fires strictly after `unix_secs`,
+    /// in seconds since the Unix epoch, or `None` if it never fires (e.g. on
+    /// February 30th).
+    pub fn next_after(&self, unix_secs: u64) -> Option<u64> {
+        let mut minute: u64 = unix_secs / 60 + 1;
+        let limit: u64 = minute + SEARCH_DAYS * MINUTES_PER_DAY;
+
+        while minute < limit {
+            let day: u64 = minute / MINUTES_PER_DAY;
+            if !self.matches_day(day) {
+                minute = (day + 1) * MINUTES_PER_DAY;
+                continue;
+            }
+
+            let hour: u64 = minute % MINUTES_PER_DAY / 60;
+            if has(self.hours, hour)
+                && let Some(next) = (minute % 60..60).find(|m| has(self.minutes, *m))
+            {
+                return Some((day * MINUTES_PER_DAY + hour * 60 + next) * 60);
+            }
+            minute = day * MINUTES_PER_DAY + (hour + 1) * 60;
+        }
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: u64) -> bool {
+        let date: UtcMinute = UtcMinute::from_unix(days_since_epoch * MINUTES_PER_DAY * 60);
+        if !has(self.months, u64::from(date.month)) {
+            return false;
+        }
+        // 1970-01-01 was a Thursday.
+        let weekday: u64 = (days_since_epoch + 4) % 7;
+        let by_day: bool = has(self.days, u64::from(date.day));
+        let by_weekday: bool = has(self.weekdays, weekday);
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => by_day || by_weekday,
+            _ => by_day && by_weekday,
+        }
+    }
+}
+
+impl UtcMinute {
+    /// Converts seconds since the Unix epoch to the UTC minute they fall in.
+    pub fn from_unix(unix_secs: u64) -> Self {
+        let days: i64 = (unix_secs / 86_400) as i64 + EPOCH_SHIFT;
+        let secs_of_day: u64 = unix_secs % 86_400;
+
+        // Civil date from day count, after Howard Hinnant's `civil_from_days`.
+        let era: i64 = days.div_euclid(146_097);
+        let day_of_era: i64 = days.rem_euclid(146_097);
+        let year_of_era: i64 =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year: i64 =
+            day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month: i64 = (5 * day_of_year + 2) / 153;
+        let day: i64 = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month: i64 = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month: month as u32,
+            day: day as u32,
+            hour: (secs_of_day / 3600) as u32,
+            minute: (secs_of_day % 3600 / 60) as u32,
+        }
+    }
+}
+
+impl fmt::Display for UtcMinute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+/// Parses one field into a bit set of the values it selects.
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut bits: u64 = 0;
+    for part in field.split(',') {
+        let (range, step): (&str, u64) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u64>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step in '{part}'")),
+            },
+            None => (part, 1),
+        };
+
+        let (start, end): (u64, u64) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start, min, max)?, value(end, min, max)?),
+            // `5/10` runs from 5 to the end of the range, as in Vixie cron.
+            None if step > 1 => (value(range, min, max)?, max),
+            None => {
+                let value: u64 = value(range, min, max)?;
+                (value, value)
+            }
+        };
+        if start > end {
+            return Err(format!("reversed range in '{part}'"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn value(input: &str, min: u64, max: u64) -> Result<u64, String> {
+    match input.parse::<u64>() {
+        Ok(value) if (min..=max).contains(&value) => Ok(value),
+        _ => Err(format!("'{input}' is not a number from {min} to {max}")),
+    }
+}
+
+fn has(bits: u64, value: u64) -> bool {
+    bits & (1 << value) != 0
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-10-16 12:34:56 UTC, a Friday.
+    const NOW: u64 = 1_792_154_096;
+
+    fn next(expr: &str, after: u64) -> String {
+        let cron: CronExpr = expr.parse().unwrap();
+        UtcMinute::from_unix(cron.next_after(after).unwrap()).to_string()
+    }
+
+    #[test]
+    fn finds_the_next_firing() {
+        assert_eq!(
+            UtcMinute::from_unix(NOW).to_string(),
+            "2026-10-16 12:34 UTC"
+        );
+        assert_eq!(next("0 3 * * *", NOW), "2026-10-17 03:00 UTC");
+        assert_eq!(next("*/15 * * * *", NOW), "2026-10-16 12:45 UTC");
+        assert_eq!(next("30 9-17/4 * * 1-5", NOW), "2026-10-16 13:30 UTC");
+        assert_eq!(next("@weekly", NOW), "2026-10-18 00:00 UTC");
+        assert_eq!(next("0 0 * * 7", NOW), "2026-10-18 00:00 UTC");
+        assert_eq!(next("0 0 29 2 *", NOW), "2028-02-29 00:00 UTC");
+        // Day of the month or weekday, whichever comes first.
+        assert_eq!(next("0 0 1 * 1", NOW), "2026-10-19 00:00 UTC");
+
+        // Strictly after: a schedule never fires twice in the same minute.
+        let fired: u64 = "0 3 * * *"
+            .parse::<CronExpr>()
+            .unwrap()
+            .next_after(NOW)
+            .unwrap();
+        assert_eq!(next("0 3 * * *", fired), "2026-10-18 03:00 UTC");
+    }
+
+    #[test]
+    fn rejects_malformed_and_impossible_schedules() {
+        for bad in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+        ] {
+            assert!(bad.parse::<CronExpr>().is_err(), "{bad}");
+        }
+        let never: CronExpr = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(never.next_after(NOW), None);
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Never-Scan List
+//!
+//! A safety net for operators running zond next to production or OT networks.
+//! Ranges listed in the system-wide [`DENYLIST_PATH`] (or the file named by
+//! `$ZOND_NEVER_SCAN`) are never probed: target parsing rejects every target that
+//! touches one of them, and every entry point of the scanner checks its targets
+//! again with [`enforce`], which also covers addresses that never went through
+//! target parsing, such as `--baseline`. Only an administrator who can edit the
+//! file can lift it. `$ZOND_NEVER_SCAN` replaces the file for unprivileged runs
+//! only; a run with raw socket access (root, file capabilities or `--elevate`)
+//! reads the named file in addition, so the environment can only add ranges.
+//!
+//! The file holds one IPv4 or IPv6 address, range or CIDR block per line, in
+//! the same notation as targets. CIDR blocks always include their network and
+//! broadcast addresses. `#` starts a comment; a comment after an entry is
+//! quoted in the error, so it can say why the range is off limits:
+//!
+//! ```text
+//! # Plant floor, change requests only
+//! 10.20.0.0/16    # PLCs
+//! 192.168.50.10   # billing database
+//! fd00:20::/48    # PLCs, IPv6
+//! ```
+//!
+//! A missing file means an empty list. A file that exists but cannot be read or
+//! parsed stops every scan, rather than letting it run unguarded.
+
+use std::{
+    env, fs,
+    io::ErrorKind,
+    net::{IpAddr, Ipv6Addr},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use super::ip::{IpParseError, parse_and_insert};
+use crate::models::ip::set::IpSet;
+use crate::utils::privilege;
+
+/// Where the never-scan list is read from unless `$ZOND_NEVER_SCAN` is set.
+pub const DENYLIST_PATH: &str = "/etc/zond/never-scan.conf";
+const DENYLIST_VAR: &str = "ZOND_NEVER_SCAN";
+
+/// One line of the never-scan list.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The entry as written.
+    spec: String,
+    /// The comment after it, if any.
+    note: Option<String>,
+    /// The IPv4 addresses listed, empty for an IPv6 entry.
+    set: IpSet,
+    /// The IPv6 addresses listed, as numbers.
+    v6: Option<RangeInclusive<u128>>,
+}
+
+/// The ranges that must never be probed.
+#[derive(Debug, Clone, Default)]
+pub struct Denylist {
+    entries: Vec<Entry>,
+}
+
+impl Denylist {
+    /// Reads the system-wide list, and the one named by `$ZOND_NEVER_SCAN`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IpParseError::Denylist`] if a file exists but cannot be read or
+    /// holds a malformed entry.
+    pub fn system() -> Result<Self, IpParseError> {
+        let system: &Path = Path::new(DENYLIST_PATH);
+        let Some(path) = env::var_os(DENYLIST_VAR)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+        else {
+            return Self::read(system);
+        };
+        match privilege::has_raw_socket_access() {
+            // Whoever controls the environment of a privileged run must not
+            // lift what the administrator listed.
+            true => {
+                let mut list: Self = Self::read(system)?;
+                list.entries.extend(Self::read(&path)?.entries);
+                Ok(list)
+            }
+            false => Self::read(&path),
+        }
+    }
+
+    /// Reads the list at `path`; a missing file is an empty list.
+    fn read(path: &Path) -> Result<Self, IpParseError> {
+        let failed = |reason: String| IpParseError::Denylist(path.display().to_string(), reason);
+
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(failed),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(failed(e.to_string())),
+        }
+    }
+
+    /// Parses the content of a never-scan list.
+    ///
+    /// # Errors
+    ///
+    /// Returns the line and reason of the first entry that is not an IPv4 or
+    /// IPv6 address, range or CIDR block.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let (spec, note) = match line.split_once('#') {
+                Some((spec, note)) => (spec.trim(), Some(note.trim())),
+                None => (line.trim(), None),
+            };
+            if spec.is_empty() {
+                continue;
+            }
+
+            let invalid = |reason: String| format!("line {}: {reason}", idx + 1);
+            if spec.eq_ignore_ascii_case("lan") {
+                return Err(invalid(
+                    "'lan' cannot be listed, name its range".to_string(),
+                ));
+            }
+            let mut set: IpSet = IpSet::new();
+            let v6: Option<RangeInclusive<u128>> = match spec.contains(':') {
+                true => Some(parse_v6(spec).map_err(invalid)?),
+                false => {
+                    parse_and_insert(spec, &mut set, true).map_err(|e| invalid(e.to_string()))?;
+                    if set.is_empty() {
+                        return Err(invalid(format!("'{spec}' is not an IP target")));
+                    }
+                    None
+                }
+            };
+
+            entries.push(Entry {
+                spec: spec.to_string(),
+                note: note.filter(|note| !note.is_empty()).map(str::to_string),
+                set,
+                v6,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Returns `true` if nothing is listed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Describes the entries that `target` touches, or returns `None` if it is
+    /// clear to scan.
+    pub fn blocking(&self, target: &IpSet) -> Option<String> {
+        self.hits(|entry| entry.set.overlaps(target))
+    }
+
+    /// Describes the entries that hold `ip`, which may be IPv6 unlike an
+    /// [`IpSet`], or returns `None` if it is clear to scan.
+    pub fn blocking_ip(&self, ip: IpAddr) -> Option<String> {
+        match ip {
+            IpAddr::V4(_) => self.hits(|entry| entry.set.contains(&ip)),
+            IpAddr::V6(v6) => self.blocking_v6(&(u128::from(v6)..=u128::from(v6))),
+        }
+    }
+
+    /// Describes the IPv6 entries that overlap `range`.
+    fn blocking_v6(&self, range: &RangeInclusive<u128>) -> Option<String> {
+        self.hits(|entry| {
+            entry.v6.as_ref().is_some_and(|listed| {
+                listed.start() <= range.end() && range.start() <= listed.end()
+            })
+        })
+    }
+
+    /// Describes the entries `matches` accepts, with their comments.
+    fn hits(&self, matches: impl Fn(&Entry) -> bool) -> Option<String> {
+        let hits: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|&entry| matches(entry))
+            .map(|entry| match &entry.note {
+                Some(note) => format!("{} '{note}'", entry.spec),
+                None => entry.spec.clone(),
+            })
+            .collect();
+        (!hits.is_empty()).then(|| hits.join(", "))
+    }
+
+    /// Records `target` in `denied` if it touches a listed range. An IPv6
+    /// `spec`, which `target` cannot hold, is checked as written.
+    pub fn check(&self, spec: &str, target: &IpSet, denied: &mut Vec<String>) {
+        let hits: Option<String> = match spec.contains(':') {
+            true => parse_v6(spec)
+                .ok()
+                .and_then(|range| self.blocking_v6(&range)),
+            false => self.blocking(target),
+        };
+        if let Some(hits) = hits {
+            denied.push(format!("{spec} (listed: {hits})"));
+        }
+    }
+}
+
+/// Parses an IPv6 address, range (`START-END`) or CIDR block into the
+/// addresses it covers, as numbers.
+fn parse_v6(spec: &str) -> Result<RangeInclusive<u128>, String> {
+    let invalid = || format!("'{spec}' is not an IPv6 address, range or CIDR block");
+    let addr = |text: &str| {
+        text.parse::<Ipv6Addr>()
+            .map(u128::from)
+            .map_err(|_| invalid())
+    };
+
+    if let Some((ip, prefix)) = spec.split_once('/') {
+        let prefix: u32 = prefix
+            .parse()
+            .ok()
+            .filter(|prefix| *prefix <= 128)
+            .ok_or_else(invalid)?;
+        let host_bits: u128 = u128::MAX.checked_shr(prefix).unwrap_or(0);
+        let start: u128 = addr(ip)? & !host_bits;
+        return Ok(start..=start | host_bits);
+    }
+    if let Some((start, end)) = spec.split_once('-') {
+        let (start, end): (u128, u128) = (addr(start)?, addr(end)?);
+        if start > end {
+            return Err(format!("'{spec}' ends before it starts"));
+        }
+        return Ok(start..=end);
+    }
+    let ip: u128 = addr(spec)?;
+    Ok(ip..=ip)
+}
+
+/// Checks `targets`, named `spec` in the error, against the system-wide list.
+///
+/// # Errors
+///
+/// Returns [`IpParseError::Denied`] if `targets` touch a listed range, or the
+/// error of [`Denylist::system`].
+pub fn enforce(spec: &str, targets: &IpSet) -> Result<(), IpParseError> {
+    let mut denied: Vec<String> = Vec::new();
+    Denylist::system()?.check(spec, targets, &mut denied);
+    match denied.is_empty() {
+        true => Ok(()),
+        false => Err(IpParseError::Denied(denied)),
+    }
+}
+
+/// Checks the single address `ip`, IPv4 or IPv6, named `spec` in the error,
+/// against the system-wide list.
+///
+/// # Errors
+///
+/// As [`enforce`].
+pub fn enforce_ip(spec: &str, ip: IpAddr) -> Result<(), IpParseError> {
+    match Denylist::system()?.blocking_ip(ip) {
+        Some(hits) => Err(IpParseError::Denied(vec![format!(
+            "{spec} (listed: {hits})"
+        )])),
+        None => Ok(()),
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(spec: &str) -> IpSet {
+        let mut set: IpSet = IpSet::new();
+        parse_and_insert(spec, &mut set, false).unwrap();
+        set
+    }
+
+    #[test]
+    fn entries_block_overlapping_targets() {
+        let list: Denylist = Denylist::parse(
+            "# production\n\n10.20.0.0/16  # PLCs\n192.168.50.10\n172.16.0.1-20 #\n",
+        )
+        .unwrap();
+        assert!(!list.is_empty());
+
+        assert_eq!(
+            list.blocking(&target("10.20.4.0/24")).as_deref(),
+            Some("10.20.0.0/16 'PLCs'")
+        );
+        assert_eq!(
+            list.blocking(&target("192.168.50.0/24")).as_deref(),
+            Some("192.168.50.10")
+        );
+        assert_eq!(
+            list.blocking(&target("172.16.0.20-30")).as_deref(),
+            Some("172.16.0.1-20")
+        );
+        assert_eq!(list.blocking(&target("10.21.0.0/16")), None);
+
+        let mut denied: Vec<String> = Vec::new();
+        list.check("10.0.0.0/8", &target("10.0.0.0/8"), &mut denied);
+        list.check("10.21.0.1", &target("10.21.0.1"), &mut denied);
+        assert_eq!(denied, ["10.0.0.0/8 (listed: 10.20.0.0/16 'PLCs')"]);
+    }
+
+    #[test]
+    fn listed_blocks_keep_their_edges() {
+        // Listed CIDR blocks keep their network address, target blocks drop
+        // their broadcast address.
+        let list: Denylist = Denylist::parse("10.0.1.0/24").unwrap();
+        assert!(list.blocking(&target("10.0.1.0")).is_some());
+        let list: Denylist = Denylist::parse("10.0.0.255").unwrap();
+        assert!(list.blocking(&target("10.0.0.0/24")).is_none());
+    }
+
+    #[test]
+    fn malformed_entries_name_their_line() {
+        assert!(Denylist::parse("").unwrap().is_empty());
+        let err: String = Denylist::parse("10.0.0.1\n10.0.0.300\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(Denylist::parse("lan").is_err());
+        assert!(Denylist::parse("fe80::/129").is_err());
+        assert!(Denylist::parse("fe80::9-fe80::1").is_err());
+        assert!(Denylist::parse("fe80::g").is_err());
+    }
+
+    #[test]
+    fn ipv6_entries_block_ipv6_targets() {
+        let list: Denylist =
+            Denylist::parse("fd00:20::/48  # PLCs\n2001:db8::5-2001:db8::9\nfe80::1\n").unwrap();
+
+        let ip = |text: &str| text.parse::<IpAddr>().unwrap();
+        assert_eq!(
+            list.blocking_ip(ip("fd00:20:0:7::1")).as_deref(),
+            Some("fd00:20::/48 'PLCs'")
+        );
+        assert!(list.blocking_ip(ip("fd00:21::1")).is_none());
+        assert!(list.blocking_ip(ip("2001:db8::7")).is_some());
+        assert!(list.blocking_ip(ip("2001:db8::a")).is_none());
+        assert!(list.blocking_ip(ip("fe80::1")).is_some());
+        assert!(list.blocking_ip(ip("10.0.0.1")).is_none());
+
+        let mut denied: Vec<String> = Vec::new();
+        list.check("2001:db8::/120", &IpSet::new(), &mut denied);
+        list.check("2001:db9::1", &IpSet::new(), &mut denied);
+        assert_eq!(denied, ["2001:db8::/120 (listed: 2001:db8::5-2001:db8::9)"]);
+
+        // IPv4 entries keep working next to IPv6 ones.
+        let list: Denylist = Denylist::parse("10.0.0.0/8\n::/0").unwrap();
+        assert!(list.blocking(&target("10.1.2.3")).is_some());
+        assert!(list.blocking_ip(ip("10.1.2.3")).is_some());
+        assert!(list.blocking_ip(ip("::1")).is_some());
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Target Hints
+//!
+//! Suggestions for targets the parser could not read. Most malformed targets
+//! are near misses of a supported format: a wildcard instead of a CIDR block,
+//! a netmask instead of a prefix length, a dropped octet. [`target`] names
+//! what to type instead, and [`IpParseError::Malformed`](super::IpParseError)
+//! carries it in its message.
+//!
+//! Some targets arrive mangled by the shell before zond sees them: an
+//! unquoted `*` is replaced by the file names it matches, and shells without
+//! brace expansion pass `{1..5}` on as it is.
+
+use std::{net::Ipv4Addr, path::Path};
+
+/// Characters users write for "any value" in an octet.
+const WILDCARDS: [&str; 4] = ["*", "x", "X", "?"];
+
+/// Returns what the malformed target `input` was most likely meant to be,
+/// phrased as advice, or `None` if nothing fits.
+///
+/// # Examples
+/// ```
+/// use zond_common::parse::hint;
+///
+/// assert_eq!(
+///     hint::target("192.168.1.*").as_deref(),
+///     Some("did you mean 192.168.1.0/24?")
+/// );
+/// assert_eq!(hint::target("10.0.0.1"), None);
+/// ```
+pub fn target(input: &str) -> Option<String> {
+    let input: &str = input.trim();
+    if input.is_empty() || input.parse::<Ipv4Addr>().is_ok() {
+        return None;
+    }
+    if input.contains(':') {
+        return ipv6(input);
+    }
+    if let Some(hint) = shell_leftover(input) {
+        return Some(hint);
+    }
+    if let Some(cidr) = cidr(input)
+        .or_else(|| wildcard(input))
+        .or_else(|| range(input))
+    {
+        return Some(format!("did you mean {cidr}?"));
+    }
+    if let Some(octet) = input
+        .split(['.', '-', '/'])
+        .filter_map(|part| part.parse::<u32>().ok())
+        .find(|octet| *octet > 255)
+    {
+        return Some(format!(
+            "{octet} is not an octet, each part of an IPv4 address is 0-255"
+        ));
+    }
+    if let Some(keyword) = ["lan", "@last"]
+        .into_iter()
+        .find(|keyword| edit_distance(&input.to_ascii_lowercase(), keyword) == 1)
+    {
+        return Some(format!("did you mean {keyword}?"));
+    }
+    if Path::new(input).exists() {
+        return Some(format!(
+            "'{input}' is a file here: the shell expands an unquoted `*` to matching file \
+             names, so quote the target or write it as a CIDR block"
+        ));
+    }
+    if input.chars().any(|c| c.is_ascii_alphabetic())
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    {
+        return Some(format!(
+            "targets are addresses, not names; `zond host {input}` looks a host up by name"
+        ));
+    }
+    None
+}
+
+/// [`target`] as a clause to append to an error message, or nothing.
+pub fn suffix(input: &str) -> String {
+    target(input)
+        .map(|hint| format!("; {hint}"))
+        .unwrap_or_default()
+}
+
+fn ipv6(input: &str) -> Option<String> {
+    if let Some((addr, _zone)) = input.split_once('%') {
+        return Some(format!("drop the zone index: {addr}"));
+    }
+    (input.contains('/') || input.contains('-'))
+        .then(|| "IPv6 targets are single addresses, ranges and CIDR blocks are IPv4 only".into())
+}
+
+/// Brace and bracket expressions a shell passed on unexpanded, e.g.
+/// `10.0.0.{1..5}` or `10.0.0.[1-5]`.
+fn shell_leftover(input: &str) -> Option<String> {
+    let (open, close, separator) = match (input.find('{'), input.find('[')) {
+        (Some(at), _) => (at, '}', ".."),
+        (None, Some(at)) => (at, ']', "-"),
+        (None, None) => return None,
+    };
+    let prefix: &str = &input[..open];
+    let inner: &str = input[open + 1..].strip_suffix(close)?;
+    let (start, end) = inner.split_once(separator)?;
+    let suggestion: String = format!("{prefix}{start}-{end}");
+    range_ok(&suggestion)
+        .then(|| format!("the shell did not expand '{input}', write the range as {suggestion}"))
+}
+
+/// A CIDR block with a netmask or missing octets, e.g. `10.0.0.0/255.0.0.0`
+/// or `192.168.1/24`.
+fn cidr(input: &str) -> Option<String> {
+    let (addr, prefix) = input.split_once('/')?;
+    let prefix: u32 = match prefix.parse::<Ipv4Addr>() {
+        Ok(mask) => {
+            let mask: u32 = u32::from(mask);
+            // Only contiguous masks have a prefix length.
+            (mask.leading_ones() + mask.trailing_zeros() == 32).then_some(mask.leading_ones())?
+        }
+        Err(_) => prefix.parse::<u32>().ok().filter(|len| *len <= 32)?,
+    };
+    let octets: Vec<u8> = addr
+        .trim_end_matches('.')
+        .split('.')
+        .map(|octet| octet.parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+    if octets.len() > 4 {
+        return None;
+    }
+    let padded: String = pad(&octets);
+    (format!("{padded}/{prefix}") != input).then(|| format!("{padded}/{prefix}"))
+}
+
+/// Trailing wildcard octets, e.g. `10.0.*.*` for `10.0.0.0/16`.
+fn wildcard(input: &str) -> Option<String> {
+    let parts: Vec<&str> = input.split('.').collect();
+    let fixed: usize = parts.iter().position(|part| WILDCARDS.contains(part))?;
+    if fixed == 0 || parts.len() > 4 || !parts[fixed..].iter().all(|part| WILDCARDS.contains(part))
+    {
+        return None;
+    }
+    let octets: Vec<u8> = parts[..fixed]
+        .iter()
+        .map(|octet| octet.parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(format!("{}/{}", pad(&octets), fixed * 8))
+}
+
+/// A range written with `..`, a typographic dash or a trailing dot.
+fn range(input: &str) -> Option<String> {
+    let fixed: String = input
+        .replace("..", "-")
+        .replace(['–', '—'], "-")
+        .trim_end_matches('.')
+        .to_string();
+    (fixed != input && (fixed.parse::<Ipv4Addr>().is_ok() || range_ok(&fixed))).then_some(fixed)
+}
+
+/// Returns `true` if `range` is a valid `START-END` or `START-SUFFIX` range.
+fn range_ok(range: &str) -> bool {
+    let Some((start, end)) = range.split_once('-') else {
+        return false;
+    };
+    let Ok(start) = start.parse::<Ipv4Addr>() else {
+        return false;
+    };
+    let end: Ipv4Addr = match end.parse::<Ipv4Addr>() {
+        Ok(end) => end,
+        Err(_) => {
+            let parts: Option<Vec<u8>> = end.split('.').map(|p| p.parse::<u8>().ok()).collect();
+            match parts {
+                Some(parts) if (1..=4).contains(&parts.len()) => {
+                    let mut octets: [u8; 4] = start.octets();
+                    octets[4 - parts.len()..].copy_from_slice(&parts);
+                    Ipv4Addr::from(octets)
+                }
+                _ => return false,
+            }
+        }
+    };
+    start <= end
+}
+
+/// `octets` followed by as many zeros as make an address.
+fn pad(octets: &[u8]) -> String {
+    let mut all: [u8; 4] = [0; 4];
+    all[..octets.len()].copy_from_slice(octets);
+    Ipv4Addr::from(all).to_string()
+}
+
+/// Edit distance between two short ASCII strings, counting a swap of two
+/// neighbouring characters as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (&[u8], &[u8]) = (a.as_bytes(), b.as_bytes());
+    let mut d: Vec<Vec<usize>> = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost: usize = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint(input: &str) -> String {
+        target(input).unwrap_or_default()
+    }
+
+    #[test]
+    fn suggests_the_format_a_near_miss_meant() {
+        assert_eq!(hint("10.0.*.*"), "did you mean 10.0.0.0/16?");
+        assert_eq!(hint("10.0.0.x"), "did you mean 10.0.0.0/24?");
+        assert_eq!(hint("10.0.0.0/255.255.255.0"), "did you mean 10.0.0.0/24?");
+        assert_eq!(hint("192.168.1/24"), "did you mean 192.168.1.0/24?");
+        assert_eq!(hint("10.0.0.1..50"), "did you mean 10.0.0.1-50?");
+        assert_eq!(hint("10.0.0.1–10.0.0.9"), "did you mean 10.0.0.1-10.0.0.9?");
+        assert_eq!(hint("10.0.0.1."), "did you mean 10.0.0.1?");
+        assert_eq!(hint("lna"), "did you mean lan?");
+        assert_eq!(hint("last"), "did you mean @last?");
+        assert!(hint("10.0.0.256").starts_with("256 is not an octet"));
+
+        // Nothing sensible to offer.
+        assert_eq!(target("10.0.0.0/255.0.255.0"), None);
+        assert_eq!(target("10.*.0.1"), None);
+        assert_eq!(target("10.0.0.1"), None);
+    }
+
+    #[test]
+    fn explains_what_the_shell_did() {
+        assert_eq!(
+            hint("10.0.0.{1..5}"),
+            "the shell did not expand '10.0.0.{1..5}', write the range as 10.0.0.1-5"
+        );
+        assert!(hint("10.0.0.[1-5]").ends_with("write the range as 10.0.0.1-5"));
+        assert!(hint("Cargo.toml").contains("is a file here"));
+        assert!(hint("fe80::1%eth0").ends_with("fe80::1"));
+        assert!(hint("fd00::/64").starts_with("IPv6 targets are single addresses"));
+        assert!(hint("nas.example.com").contains("zond host nas.example.com"));
+        assert_eq!(suffix("10.0.0.1"), "");
+    }
+}
@@ -15,7 +15,9 @@
 //! The parser recognizes several distinct IPv4 formats:
 //!
 //! * **Single IP**: Standard dotted-decimal notation (e.g., `127.0.0.1`).
-//! * **CIDR Block**: Network address with a prefix length (e.g., `192.168.1.0/24`).
+//! * **CIDR Block**: Network address with a prefix length (e.g., `192.168.1.0/24`). The
+//!   network and broadcast addresses are left out unless asked for, since no host
+//!   answers on them.
 //! * **Explicit Range**: Two full IPs separated by a hyphen (e.g., `10.0.0.1-10.0.0.50`).
 //! * **Shortened Range**: An IP followed by a hyphen and a partial suffix (e.g., `10.0.0.1-50` or `192.168.1.1-2.254`).
 //! * **Keywords**: Special identifiers like `lan`, which resolve dynamically based on the host's active interface.
@@ -29,6 +31,8 @@ use std::net::{IpAddr, Ipv4Addr};
 use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
+use super::denylist::Denylist;
+use super::hint;
 use crate::models::ip::range::{IpError, Ipv4Range};
 use crate::models::ip::set::IpSet;
 use crate::net::interface;
@@ -49,7 +53,8 @@ pub enum IpParseError {
     InvalidRange(Ipv4Addr, Ipv4Addr),
 
     /// The input string does not match any known IP, Range, or CIDR format.
-    #[error("Malformed IP or range string: '{0}'")]
+    /// The message suggests what was likely meant, see [`hint`].
+    #[error("Malformed IP or range string: '{0}'{}", hint::suffix(.0))]
     Malformed(String),
 
     /// Failed to retrieve local interface information for "lan" resolution.
@@ -60,6 +65,18 @@ pub enum IpParseError {
     #[error("Network error: {0}")]
     NetworkError(String),
 
+    /// A `@technique[:port]` annotation could not be understood.
+    #[error("Invalid annotation '{0}': {1}")]
+    InvalidAnnotation(String, String),
+
+    /// The never-scan list exists but could not be read or parsed.
+    #[error("Could not load never-scan list {0}: {1}")]
+    Denylist(String, String),
+
+    /// Targets touch ranges of the never-scan list.
+    #[error("Refusing to scan targets on the never-scan list: {}", .0.join("; "))]
+    Denied(Vec<String>),
+
     /// The provided input resulted in zero valid IP addresses.
     #[error("Target input resulted in an empty set")]
     EmptySet,
@@ -68,15 +85,18 @@ pub enum IpParseError {
 /// Resolves a collection of input strings into a consolidated [`IpSet`].
 ///
 /// Handles whitespace trimming, comma-separated lists, and individual item parsing.
+/// Targets are checked against the never-scan list ([`Denylist`]).
 ///
 /// # Arguments
 ///
 /// * `inputs` - A slice of string-like objects representing scan targets.
+/// * `include_broadcast` - Keeps the network and broadcast addresses of CIDR blocks.
 ///
 /// # Errors
 ///
-/// Returns an [`IpParseError`] if any component fails to parse or if the final set
-/// is empty.
+/// Returns an [`IpParseError`] if any component fails to parse, the final set is
+/// empty, or targets touch the never-scan list ([`IpParseError::Denied`], naming
+/// all of them).
 ///
 /// # Examples
 ///
@@ -84,27 +104,30 @@ pub enum IpParseError {
 /// use zond_common::parse::ip::to_set;
 ///
 /// let targets = vec!["192.168.1.0/24", "10.0.0.1, 10.0.0.5-10"];
-/// let set = to_set(&targets).unwrap();
+/// let set = to_set(&targets, false).unwrap();
 ///
-/// // /24 (256) + single (1) + range 5-10 (6) = 263
-/// assert_eq!(set.len(), 263);
+/// // /24 without .0 and .255 (254) + single (1) + range 5-10 (6) = 261
+/// assert_eq!(set.len(), 261);
 /// ```
-pub fn to_set<S: AsRef<str>>(inputs: &[S]) -> Result<IpSet, IpParseError> {
+pub fn to_set<S: AsRef<str>>(inputs: &[S], include_broadcast: bool) -> Result<IpSet, IpParseError> {
+    let denylist: Denylist = Denylist::system()?;
     let mut set = IpSet::new();
+    let mut denied: Vec<String> = Vec::new();
+
+    for part in inputs
+        .iter()
+        .flat_map(|input| input.as_ref().split(','))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let mut part_set: IpSet = IpSet::new();
+        parse_and_insert(part, &mut part_set, include_broadcast)?;
+        denylist.check(part, &part_set, &mut denied);
+        set.merge(part_set);
+    }
 
-    for input in inputs {
-        let s = input.as_ref().trim();
-        if s.is_empty() {
-            continue;
-        }
-
-        if s.contains(',') {
-            for part in s.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
-                parse_and_insert(part, &mut set)?;
-            }
-        } else {
-            parse_and_insert(s, &mut set)?;
-        }
+    if !denied.is_empty() {
+        return Err(IpParseError::Denied(denied));
     }
 
     if set.is_empty() {
@@ -119,13 +142,17 @@ pub fn to_set<S: AsRef<str>>(inputs: &[S]) -> Result<IpSet, IpParseError> {
 }
 
 /// Identifies the format of a single target string and inserts it into the set.
-fn parse_and_insert(s: &str, set: &mut IpSet) -> Result<(), IpParseError> {
+pub(super) fn parse_and_insert(
+    s: &str,
+    set: &mut IpSet,
+    include_broadcast: bool,
+) -> Result<(), IpParseError> {
     if s.eq_ignore_ascii_case("lan") {
         return resolve_lan(set);
     }
 
     if s.contains('/') {
-        let range = parse_cidr(s)?;
+        let range = parse_cidr(s, include_broadcast)?;
         set.insert_range(range);
         return Ok(());
     }
@@ -211,7 +238,10 @@ fn parse_range(s: &str) -> Result<Ipv4Range, IpParseError> {
 }
 
 /// Parses CIDR notation strings into an [`Ipv4Range`].
-fn parse_cidr(s: &str) -> Result<Ipv4Range, IpParseError> {
+///
+/// Unless `include_broadcast` is set, the network and broadcast addresses are
+/// cut off. Blocks of /31 and /32 have neither (RFC 3021) and are kept whole.
+fn parse_cidr(s: &str, include_broadcast: bool) -> Result<Ipv4Range, IpParseError> {
     let (ip_str, prefix_str) = s
         .split_once('/')
         .ok_or_else(|| IpParseError::Malformed(s.into()))?;
@@ -222,7 +252,7 @@ fn parse_cidr(s: &str) -> Result<Ipv4Range, IpParseError> {
 
     let prefix = prefix_str
         .parse::<u8>()
-        .map_err(|_| IpParseError::InvalidPrefix(0))?;
+        .map_err(|_| IpParseError::Malformed(s.into()))?;
 
     if prefix > 32 {
         return Err(IpParseError::InvalidPrefix(prefix));
@@ -231,7 +261,12 @@ fn parse_cidr(s: &str) -> Result<Ipv4Range, IpParseError> {
     let network = pnet::ipnetwork::Ipv4Network::new(ip, prefix)
         .map_err(|e| IpParseError::NetworkError(e.to_string()))?;
 
-    Ok(Ipv4Range::new(network.network(), network.broadcast()).unwrap())
+    if include_broadcast || prefix > 30 {
+        return Ok(Ipv4Range::new(network.network(), network.broadcast()).unwrap());
+    }
+    let start: Ipv4Addr = Ipv4Addr::from(u32::from(network.network()) + 1);
+    let end: Ipv4Addr = Ipv4Addr::from(u32::from(network.broadcast()) - 1);
+    Ok(Ipv4Range::new(start, end).unwrap())
 }
 
 // ╔════════════════════════════════════════════╗
@@ -251,7 +286,7 @@ mod tests {
     #[test]
     fn to_set_basic_single() {
         let input = vec!["192.168.1.1"];
-        let set = to_set(&input).expect("Should parse single IP");
+        let set = to_set(&input, false).expect("Should parse single IP");
         assert_eq!(set.len(), 1);
         assert!(set.contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
     }
@@ -259,7 +294,7 @@ mod tests {
     #[test]
     fn to_set_comma_separated() {
         let input = vec!["10.0.0.1, 10.0.0.2, 10.0.0.5"];
-        let set = to_set(&input).expect("Should parse comma list");
+        let set = to_set(&input, false).expect("Should parse comma list");
         assert_eq!(set.len(), 3);
         assert!(set.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
     }
@@ -267,35 +302,59 @@ mod tests {
     #[test]
     fn parse_cidr_blocks() {
         let input = vec!["172.16.0.0/24"];
-        let set = to_set(&input).expect("Should parse CIDR");
+        let set = to_set(&input, true).expect("Should parse CIDR");
         assert_eq!(set.len(), 256);
     }
 
+    #[test]
+    fn cidr_blocks_skip_network_and_broadcast() {
+        let set = to_set(&["10.0.0.0/24"], false).unwrap();
+        assert_eq!(set.len(), 254);
+        assert!(!set.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0))));
+        assert!(!set.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 255))));
+
+        // Point-to-point and single-host blocks have no such addresses.
+        assert_eq!(to_set(&["10.0.0.0/31"], false).unwrap().len(), 2);
+        assert_eq!(to_set(&["10.0.0.7/32"], false).unwrap().len(), 1);
+
+        // Explicit ranges are taken as given.
+        assert_eq!(to_set(&["10.0.0.0-10.0.0.255"], false).unwrap().len(), 256);
+    }
+
     #[test]
     fn parse_short_range_suffix() {
         let input = vec!["192.168.1.250-2.10"];
-        let set = to_set(&input).unwrap();
+        let set = to_set(&input, false).unwrap();
         assert_eq!(set.len(), 17);
     }
 
     #[test]
     fn error_invalid_cidr() {
         let input = vec!["192.168.1.1/33"];
-        let result = to_set(&input);
+        let result = to_set(&input, false);
         assert_eq!(result.unwrap_err(), IpParseError::InvalidPrefix(33));
     }
 
     #[test]
     fn error_invalid_range_order() {
         let input = vec!["10.0.0.10-1"];
-        let result = to_set(&input);
+        let result = to_set(&input, false);
         assert!(matches!(result, Err(IpParseError::InvalidRange(_, _))));
     }
 
     #[test]
     fn empty_input_error() {
         let input: Vec<&str> = vec!["", " "];
-        let result = to_set(&input);
+        let result = to_set(&input, false);
         assert_eq!(result.unwrap_err(), IpParseError::EmptySet);
     }
+
+    #[test]
+    fn malformed_error_suggests_a_fix() {
+        let err: IpParseError = to_set(&["10.0.0.*"], false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Malformed IP or range string: '10.0.0.*'; did you mean 10.0.0.0/24?"
+        );
+    }
 }
@@ -0,0 +1,526 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Nmap XML Import
+//!
+//! Reads the XML written by `nmap -oX` into [`Host`] records, so existing nmap
+//! results can serve as a baseline for zond's and the two tools can be
+//! cross-checked against each other.
+//!
+//! Only hosts nmap reported `up` are imported, and of their ports only the open
+//! ones, matching what zond itself reports. Addresses, the MAC address and vendor,
+//! the first hostname, open ports with their service name and the smoothed RTT
+//! are carried over; everything else in the document is ignored.
+//!
+//! The reader understands the subset of XML nmap emits: elements, quoted
+//! attributes and character references. The prolog, the doctype, comments and
+//! text content are skipped.
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use pnet::util::MacAddr;
+use thiserror::Error;
+
+use crate::models::host::Host;
+use crate::models::port::{Port, PortState, Protocol};
+
+/// Errors that can occur while importing an nmap XML report.
+#[derive(Debug, Error)]
+pub enum NmapParseError {
+    /// The document has no `<nmaprun>` root element.
+    #[error("not an nmap XML report (no <nmaprun> element)")]
+    NotNmap,
+
+    /// The markup could not be read, e.g. an unterminated tag or attribute.
+    #[error("malformed XML at byte {0}")]
+    Malformed(usize),
+
+    /// An attribute holds a value nmap never writes there.
+    #[error("invalid {0} '{1}' in nmap report")]
+    InvalidValue(&'static str, String),
+}
+
+/// Imports the hosts of an nmap XML report.
+///
+/// # Errors
+///
+/// Returns [`NmapParseError`] if the input is not well-formed enough to read, is
+/// not an nmap report, or carries malformed addresses or port numbers.
+pub fn to_hosts(input: &str) -> Result<Vec<Host>, NmapParseError> {
+    let mut hosts: Vec<Host> = Vec::new();
+    let mut is_nmap: bool = false;
+    let mut host: Option<HostDraft> = None;
+
+    for tag in Tags::new(input) {
+        let tag: Tag<'_> = tag?;
+
+        if tag.closing {
+            match tag.name {
+                "host" => {
+                    if let Some(draft) = host.take()
+                        && let Some(finished) = draft.finish()
+                    {
+                        hosts.push(finished);
+                    }
+                }
+                "port" => {
+                    if let Some(draft) = host.as_mut() {
+                        draft.finish_port();
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if tag.name == "nmaprun" {
+            is_nmap = true;
+            continue;
+        }
+        if tag.name == "host" {
+            host = (!tag.self_closing).then(HostDraft::default);
+            continue;
+        }
+        let Some(draft) = host.as_mut() else {
+            continue;
+        };
+
+        match tag.name {
+            "status" => draft.up = tag.attr("state") == Some("up"),
+            "address" => draft.add_address(&tag)?,
+            "hostname" => {
+                if draft.hostname.is_none() {
+                    draft.hostname = tag.attr("name").map(str::to_string);
+                }
+            }
+            "port" => {
+                draft.start_port(&tag)?;
+                if tag.self_closing {
+                    draft.finish_port();
+                }
+            }
+            "state" => {
+                if let Some(port) = draft.port.as_mut() {
+                    port.open = tag.attr("state") == Some("open");
+                }
+            }
+            "service" => {
+                if let Some(port) = draft.port.as_mut() {
+                    port.service = tag.attr("name").map(str::to_string);
+                }
+            }
+            "times" => {
+                draft.rtt = tag
+                    .attr("srtt")
+                    .and_then(|srtt| srtt.parse::<u64>().ok())
+                    .filter(|srtt| *srtt > 0)
+                    .map(Duration::from_micros);
+            }
+            _ => {}
+        }
+    }
+
+    if !is_nmap {
+        return Err(NmapParseError::NotNmap);
+    }
+    Ok(hosts)
+}
+
+/// A `<host>` element being read.
+#[derive(Default)]
+struct HostDraft {
+    up: bool,
+    ips: Vec<IpAddr>,
+    mac: Option<MacAddr>,
+    vendor: Option<String>,
+    hostname: Option<String>,
+    rtt: Option<Duration>,
+    ports: Vec<Port>,
+    port: Option<PortDraft>,
+}
+
+/// A `<port>` element being read.
+struct PortDraft {
+    number: u16,
+    protocol: Protocol,
+    open: bool,
+    service: Option<String>,
+}
+
+impl HostDraft {
+    fn add_address(&mut self, tag: &Tag<'_>) -> Result<(), NmapParseError> {
+        let addr: &str = tag.attr("addr").unwrap_or_default();
+        match tag.attr("addrtype") {
+            Some("ipv4" | "ipv6") => {
+                let ip: IpAddr = addr
+                    .parse()
+                    .map_err(|_| NmapParseError::InvalidValue("address", addr.to_string()))?;
+                self.ips.push(ip);
+            }
+            Some("mac") => {
+                let mac: MacAddr = addr
+                    .parse()
+                    .map_err(|_| NmapParseError::InvalidValue("MAC address", addr.to_string()))?;
+                self.mac = Some(mac);
+                self.vendor = tag.attr("vendor").map(str::to_string);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn start_port(&mut self, tag: &Tag<'_>) -> Result<(), NmapParseError> {
+        let protocol: Protocol = match tag.attr("protocol") {
+            Some("tcp") => Protocol::Tcp,
+            Some("udp") => Protocol::Udp,
+            Some("sctp") => Protocol::Sctp,
+            // IP protocol scans have no counterpart in zond.
+            _ => {
+                self.port = None;
+                return Ok(());
+            }
+        };
+        let portid: &str = tag.attr("portid").unwrap_or_default();
+        let number: u16 = portid
+            .parse()
+            .map_err(|_| NmapParseError::InvalidValue("port", portid.to_string()))?;
+
+        self.port = Some(PortDraft {
+            number,
+            protocol,
+            open: false,
+            service: None,
+        });
+        Ok(())
+    }
+
+    fn finish_port(&mut self) {
+        let Some(draft) = self.port.take() else {
+            return;
+        };
+        if draft.open {
+            let mut port: Port = Port::new(draft.number, draft.protocol, PortState::Open);
+            port.service_info = draft.service;
+            self.ports.push(port);
+        }
+    }
+
+    /// Builds the host, or `None` if nmap did not find it up or it has no address.
+    fn finish(self) -> Option<Host> {
+        if !self.up {
+            return None;
+        }
+        let primary_ip: IpAddr = self
+            .ips
+            .iter()
+            .find(|ip| ip.is_ipv4())
+            .or_else(|| self.ips.first())
+            .copied()?;
+
+        let mut host: Host = Host::new(primary_ip);
+        host.ips.extend(self.ips);
+        host.hostname = self.hostname;
+        if let Some(mac) = self.mac {
+            host = host.with_mac(mac);
+            if host.vendor.is_none() {
+                host.vendor = self.vendor;
+            }
+        }
+        if let Some(rtt) = self.rtt {
+            host.add_rtt(rtt);
+        }
+        for port in self.ports {
+            host.add_port(port);
+        }
+        Some(host)
+    }
+}
+
+/// A start, end or empty-element tag.
+#[derive(Debug)]
+struct Tag<'a> {
+    name: &'a str,
+    attrs: Vec<(&'a str, String)>,
+    closing: bool,
+    self_closing: bool,
+}
+
+impl Tag<'_> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Iterates over the tags of a document, skipping everything in between.
+struct Tags<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tags<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// Moves past `terminator`, failing if the document ends first.
+    fn skip_past(&mut self, terminator: &str) -> Result<(), NmapParseError> {
+        let end: usize = self.input[self.pos..]
+            .find(terminator)
+            .ok_or(NmapParseError::Malformed(self.pos))?;
+        self.pos += end + terminator.len();
+        Ok(())
+    }
+
+    fn read_tag(&mut self) -> Result<Tag<'a>, NmapParseError> {
+        let start: usize = self.pos;
+        let bytes: &[u8] = self.input.as_bytes();
+        let malformed = || NmapParseError::Malformed(start);
+
+        let mut i: usize = start + 1;
+        let closing: bool = bytes.get(i) == Some(&b'/');
+        if closing {
+            i += 1;
+        }
+
+        let name_start: usize = i;
+        while i < bytes.len() && !is_delimiter(bytes[i]) {
+            i += 1;
+        }
+        let name: &'a str = &self.input[name_start..i];
+        if name.is_empty() {
+            return Err(malformed());
+        }
+
+        let mut attrs: Vec<(&'a str, String)> = Vec::new();
+        loop {
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(b'>') => {
+                    self.pos = i + 1;
+                    return Ok(Tag {
+                        name,
+                        attrs,
+                        closing,
+                        self_closing: false,
+                    });
+                }
+                Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                    self.pos = i + 2;
+                    return Ok(Tag {
+                        name,
+                        attrs,
+                        closing,
+                        self_closing: true,
+                    });
+                }
+                Some(_) => {}
+                None => return Err(malformed()),
+            }
+
+            let key_start: usize = i;
+            while i < bytes.len() && bytes[i] != b'=' && !is_delimiter(bytes[i]) {
+                i += 1;
+            }
+            let key: &'a str = &self.input[key_start..i];
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+            if key.is_empty() || bytes.get(i) != Some(&b'=') {
+                return Err(malformed());
+            }
+            i += 1;
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+
+            let quote: u8 = match bytes.get(i) {
+                Some(&q @ (b'"' | b'\'')) => q,
+                _ => return Err(malformed()),
+            };
+            let value_start: usize = i + 1;
+            let value_len: usize = bytes[value_start..]
+                .iter()
+                .position(|b| *b == quote)
+                .ok_or_else(malformed)?;
+            attrs.push((
+                key,
+                unescape(&self.input[value_start..value_start + value_len]),
+            ));
+            i = value_start + value_len + 1;
+        }
+    }
+}
+
+impl<'a> Iterator for Tags<'a> {
+    type Item = Result<Tag<'a>, NmapParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.pos += self.input[self.pos..].find('<')?;
+            let rest: &str = &self.input[self.pos..];
+
+            let skipped: Result<(), NmapParseError> = if rest.starts_with("<!--") {
+                self.skip_past("-->")
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")
+            } else {
+                let tag: Result<Tag<'a>, NmapParseError> = self.read_tag();
+                if tag.is_err() {
+                    self.pos = self.input.len();
+                }
+                return Some(tag);
+            };
+
+            if let Err(e) = skipped {
+                self.pos = self.input.len();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || byte == b'>' || byte == b'/'
+}
+
+/// Resolves the predefined entities and numeric character references.
+/// Unknown references are kept as written.
+fn unescape(value: &str) -> String {
+    let mut out: String = String::with_capacity(value.len());
+    let mut rest: &str = value;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let resolved: Option<(char, usize)> = rest.find(';').and_then(|end| {
+            let c: char = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code: u32 = match entity.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+
+        match resolved {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE nmaprun>
+<?xml-stylesheet href="file:///usr/share/nmap/nmap.xsl" type="text/xsl"?>
+<!-- Nmap 7.94 scan initiated as: nmap -oX - 192.168.1.0/24 -->
+<nmaprun scanner="nmap" args="nmap -oX - 192.168.1.0/24" version="7.94">
+<host starttime="1" endtime="2"><status state="up" reason="arp-response" reason_ttl="0"/>
+<address addr="192.168.1.1" addrtype="ipv4"/>
+<address addr="AA:BB:CC:00:11:22" addrtype="mac" vendor="Acme &amp; Sons"/>
+<hostnames><hostname name="router.lan" type="PTR"/></hostnames>
+<ports><extraports state="closed" count="997"/>
+<port protocol="tcp" portid="22"><state state="open" reason="syn-ack" reason_ttl="64"/><service name="ssh" method="table" conf="3"/></port>
+<port protocol="tcp" portid="23"><state state="filtered" reason="no-response" reason_ttl="0"/></port>
+<port protocol="udp" portid="53"><state state="open" reason="udp-response" reason_ttl="64"/><service name="domain"/></port>
+</ports>
+<times srtt="1500" rttvar="500" to="100000"/>
+</host>
+<host><status state="down" reason="no-response" reason_ttl="0"/>
+<address addr="192.168.1.2" addrtype="ipv4"/>
+</host>
+<runstats><finished time="3"/><hosts up="1" down="1" total="2"/></runstats>
+</nmaprun>
+"#;
+
+    #[test]
+    fn imports_up_hosts_with_open_ports() {
+        let hosts: Vec<Host> = to_hosts(REPORT).unwrap();
+        assert_eq!(hosts.len(), 1);
+
+        let host: &Host = &hosts[0];
+        assert_eq!(host.primary_ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(host.hostname.as_deref(), Some("router.lan"));
+        assert_eq!(
+            host.mac,
+            Some(MacAddr::new(0xaa, 0xbb, 0xcc, 0x00, 0x11, 0x22))
+        );
+        assert_eq!(host.vendor.as_deref(), Some("Acme & Sons"));
+        assert_eq!(host.min_rtt(), Some(Duration::from_micros(1500)));
+
+        let ports: Vec<(u16, Protocol, Option<&str>)> = host
+            .ports()
+            .iter()
+            .map(|p| (p.number, p.protocol, p.service_info.as_deref()))
+            .collect();
+        assert_eq!(
+            ports,
+            vec![
+                (22, Protocol::Tcp, Some("ssh")),
+                (53, Protocol::Udp, Some("domain"))
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(matches!(
+            to_hosts("<html><body/></html>"),
+            Err(NmapParseError::NotNmap)
+        ));
+        assert!(matches!(
+            to_hosts(r#"<nmaprun><host><address addr="1.2.3.4 addrtype="ipv4"/>"#),
+            Err(NmapParseError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn unescapes_character_references() {
+        assert_eq!(
+            unescape("a &amp; b &lt;&#65;&#x42;&gt; &bogus; &"),
+            "a & b <AB> &bogus; &"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Machine Output Schema
+//!
+//! The JSON Schema describing zond's machine-readable scan reports.
+//!
+//! Every JSON document zond emits carries a top-level `schema_version` field
+//! equal to [`SCHEMA_VERSION`]. Additive changes keep the version; renaming or
+//! removing a field, or changing its type, requires a new schema file and a
+//! bumped version so consumers can detect the break.
+
+/// Version of the report format described by [`SCAN_REPORT_SCHEMA`].
+pub const SCHEMA_VERSION: &str = "1";
+
+/// The JSON Schema (draft 2020-12) for scan reports.
+pub const SCAN_REPORT_SCHEMA: &str = include_str!("../assets/schema/scan-report.v1.json");
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_pins_current_version() {
+        let pinned: String = format!("\"const\": \"{SCHEMA_VERSION}\"");
+        assert!(SCAN_REPORT_SCHEMA.contains(&pinned));
+        assert!(SCAN_REPORT_SCHEMA.contains(&format!("scan-report.v{SCHEMA_VERSION}.json")));
+    }
+}
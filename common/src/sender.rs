@@ -24,11 +24,12 @@ use pnet::{
 };
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
 };
 use thiserror::Error;
 
+use crate::models::ip::{range::Ipv4Range, set::IpSet};
 use crate::net::interface::NetworkInterfaceExtension;
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
@@ -47,6 +48,23 @@ pub enum SenderError {
     MissingLinkLocal,
 }
 
+/// A misconfiguration found by [`SenderConfig::preflight`] before any packet is sent.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PreflightIssue {
+    /// ICMPv6 probes are enabled, but there is no link-local address to send them from.
+    #[error("ICMPv6 discovery requested, but the interface has no link-local IPv6 address")]
+    MissingLinkLocal,
+
+    /// ARP targets are present, but there is no IPv4 address to send from.
+    #[error("{0} ARP target(s), but the interface has no IPv4 address")]
+    MissingIpv4(u64),
+
+    /// Targets lie outside every IPv4 subnet of the interface, where ARP
+    /// requests go unanswered unless a router proxies them.
+    #[error("{0} of {1} target(s) lie outside the IPv4 subnets of the interface")]
+    OffLinkTargets(u64, u64),
+}
+
 /// Configuration for the network sender.
 ///
 /// This struct holds the configuration for sending packets, including
@@ -55,10 +73,13 @@ pub enum SenderError {
 pub struct SenderConfig {
     /// The MAC address of the local network interface.
     pub local_mac: Option<MacAddr>,
+    /// The MAC address ARP probes claim instead of [`local_mac`](Self::local_mac).
+    probe_mac: Option<MacAddr>,
     ipv4_nets: Vec<Ipv4Network>,
     ipv6_nets: Vec<Ipv6Network>,
-    targets_v4: HashSet<Ipv4Addr>,
+    targets_v4: IpSet,
     targets_v6: HashSet<Ipv6Addr>,
+    neighbors: HashMap<Ipv4Addr, MacAddr>,
     packet_types: HashSet<PacketType>,
 }
 
@@ -66,10 +87,12 @@ impl From<&NetworkInterface> for SenderConfig {
     fn from(interface: &NetworkInterface) -> Self {
         Self {
             local_mac: interface.mac,
+            probe_mac: None,
             ipv4_nets: interface.get_ipv4_nets(),
             ipv6_nets: interface.get_ipv6_nets(),
-            targets_v4: HashSet::new(),
+            targets_v4: IpSet::new(),
             targets_v6: HashSet::new(),
+            neighbors: HashMap::new(),
             packet_types: HashSet::new(),
         }
     }
@@ -85,6 +108,32 @@ impl SenderConfig {
         self.local_mac.ok_or(SenderError::LocalMacNotSet)
     }
 
+    /// Sends ARP probes from `mac` rather than the interface's own address.
+    pub fn set_probe_mac(&mut self, mac: MacAddr) {
+        self.probe_mac = Some(mac);
+    }
+
+    /// Returns the MAC address set with [`set_probe_mac`](Self::set_probe_mac), if any.
+    pub fn probe_mac(&self) -> Option<MacAddr> {
+        self.probe_mac
+    }
+
+    /// Returns the MAC address ARP probes are sent from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither a probe MAC nor the local MAC address is set.
+    pub fn get_probe_mac(&self) -> Result<MacAddr, SenderError> {
+        self.probe_mac
+            .or(self.local_mac)
+            .ok_or(SenderError::LocalMacNotSet)
+    }
+
+    /// Returns the IPv4 addresses of the interface.
+    pub fn ipv4_addrs(&self) -> impl Iterator<Item = Ipv4Addr> + '_ {
+        self.ipv4_nets.iter().map(|net| net.ip())
+    }
+
     /// Returns the first configured IPv4 network.
     ///
     /// # Errors
@@ -100,6 +149,18 @@ impl SenderConfig {
         Ok(ipv4_net)
     }
 
+    /// Returns the local address to probe `target` from: the one whose subnet
+    /// contains it, the most specific one if several subnets overlap.
+    ///
+    /// Returns `None` if `target` lies outside every configured IPv4 network.
+    pub fn ipv4_source_for(&self, target: Ipv4Addr) -> Option<Ipv4Addr> {
+        self.ipv4_nets
+            .iter()
+            .filter(|net| net.contains(target))
+            .max_by_key(|net| net.prefix())
+            .map(|net| net.ip())
+    }
+
     /// Returns the link-local IPv6 address for the interface.
     ///
     /// # Errors
@@ -115,14 +176,14 @@ impl SenderConfig {
             .ok_or(SenderError::MissingLinkLocal)
     }
 
-    /// Returns an iterator over the IPv4 target addresses.
-    pub fn iter_targets_v4(&self) -> impl Iterator<Item = &Ipv4Addr> {
-        self.targets_v4.iter()
+    /// Returns the IPv4 target addresses, kept as ranges.
+    pub fn targets_v4(&self) -> &IpSet {
+        &self.targets_v4
     }
 
     /// Returns the total number of target addresses (IPv4 + IPv6).
     pub fn len(&self) -> usize {
-        self.targets_v4.len() + self.targets_v6.len()
+        self.targets_v4.len() as usize + self.targets_v6.len()
     }
 
     /// Returns `true` if there are no target addresses.
@@ -135,9 +196,11 @@ impl SenderConfig {
     /// The address is added to either the IPv4 or IPv6 target set depending on its version.
     pub fn add_target(&mut self, target_addr: IpAddr) {
         match target_addr {
-            IpAddr::V4(ipv4_addr) => self.targets_v4.insert(ipv4_addr),
-            IpAddr::V6(ipv6_addr) => self.targets_v6.insert(ipv6_addr),
-        };
+            IpAddr::V4(_) => self.targets_v4.insert(target_addr),
+            IpAddr::V6(ipv6_addr) => {
+                self.targets_v6.insert(ipv6_addr);
+            }
+        }
     }
 
     /// Adds multiple target IP addresses to the configuration.
@@ -147,10 +210,26 @@ impl SenderConfig {
         }
     }
 
+    /// Adds a whole set of IPv4 targets without expanding it into addresses.
+    pub fn add_target_set(&mut self, targets: IpSet) {
+        self.targets_v4.merge(targets);
+    }
+
+    /// Records the MAC address `ip` is already known to use, so its probes can be
+    /// sent unicast instead of broadcast.
+    pub fn add_neighbor(&mut self, ip: Ipv4Addr, mac: MacAddr) {
+        self.neighbors.insert(ip, mac);
+    }
+
+    /// Returns the known IPv4 neighbors and their MAC addresses.
+    pub fn neighbors(&self) -> &HashMap<Ipv4Addr, MacAddr> {
+        &self.neighbors
+    }
+
     /// Checks if a target IP address is present in the configuration.
     pub fn has_addr(&self, target_addr: &IpAddr) -> bool {
         match target_addr {
-            IpAddr::V4(ipv4_addr) => self.targets_v4.contains(ipv4_addr),
+            IpAddr::V4(_) => self.targets_v4.contains(target_addr),
             IpAddr::V6(ipv6_addr) => self.targets_v6.contains(ipv6_addr),
         }
     }
@@ -163,11 +242,163 @@ impl SenderConfig {
         }
     }
 
+    /// Replaces the local identity (MAC and subnets) with the current state of `interface`.
+    ///
+    /// Targets and packet types are kept. Returns `true` if anything changed, which
+    /// means packets built from the previous identity carry stale source addresses.
+    pub fn refresh_identity(&mut self, interface: &NetworkInterface) -> bool {
+        let ipv4_nets: Vec<Ipv4Network> = interface.get_ipv4_nets();
+        let ipv6_nets: Vec<Ipv6Network> = interface.get_ipv6_nets();
+
+        if self.local_mac == interface.mac
+            && self.ipv4_nets == ipv4_nets
+            && self.ipv6_nets == ipv6_nets
+        {
+            return false;
+        }
+
+        self.local_mac = interface.mac;
+        self.ipv4_nets = ipv4_nets;
+        self.ipv6_nets = ipv6_nets;
+        true
+    }
+
+    /// Checks the assembled configuration for problems that would make probes
+    /// fail or go unanswered.
+    ///
+    /// Nothing is changed; the caller decides whether to warn, drop a packet
+    /// type or give up.
+    pub fn preflight(&self) -> Vec<PreflightIssue> {
+        let mut issues: Vec<PreflightIssue> = Vec::new();
+
+        if self.has_packet_type(PacketType::ICMPv6) && self.get_link_local().is_err() {
+            issues.push(PreflightIssue::MissingLinkLocal);
+        }
+
+        let targets: u64 = self.targets_v4.len();
+        if !self.has_packet_type(PacketType::ARP) || targets == 0 {
+            return issues;
+        }
+        if self.ipv4_nets.is_empty() {
+            issues.push(PreflightIssue::MissingIpv4(targets));
+            return issues;
+        }
+
+        let off_link: u64 = targets - self.on_link_v4_targets();
+        if off_link > 0 {
+            issues.push(PreflightIssue::OffLinkTargets(off_link, targets));
+        }
+        issues
+    }
+
+    /// Counts the IPv4 targets inside any of the local subnets.
+    fn on_link_v4_targets(&self) -> u64 {
+        let mut subnets: IpSet = IpSet::new();
+        for net in &self.ipv4_nets {
+            if let Ok(range) = Ipv4Range::new(net.network(), net.broadcast()) {
+                subnets.insert_range(range);
+            }
+        }
+
+        let mut on_link: u64 = 0;
+        for target in self.targets_v4.ranges() {
+            for subnet in subnets.ranges() {
+                let start: u32 = u32::from(target.start_addr).max(u32::from(subnet.start_addr));
+                let end: u32 = u32::from(target.end_addr).min(u32::from(subnet.end_addr));
+                if start <= end {
+                    on_link += u64::from(end - start) + 1;
+                }
+            }
+        }
+        on_link
+    }
+
+    /// Returns `true` if at least one target still lies inside a local subnet.
+    pub fn has_on_link_targets(&self) -> bool {
+        let v4_on_link: bool = self.targets_v4.ranges().iter().any(|range| {
+            self.ipv4_nets
+                .iter()
+                .any(|net| range.start_addr <= net.broadcast() && range.end_addr >= net.network())
+        });
+
+        v4_on_link
+            || self
+                .targets_v6
+                .iter()
+                .any(|addr| self.is_addr_in_subnet(IpAddr::V6(*addr)))
+    }
+
     pub fn add_packet_type(&mut self, packet_type: PacketType) {
         self.packet_types.insert(packet_type);
     }
 
+    pub fn remove_packet_type(&mut self, packet_type: PacketType) {
+        self.packet_types.remove(&packet_type);
+    }
+
     pub fn has_packet_type(&self, packet_type: PacketType) -> bool {
         self.packet_types.contains(&packet_type)
     }
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pnet::ipnetwork::IpNetwork;
+
+    fn mock_interface(ips: Vec<IpNetwork>) -> NetworkInterface {
+        NetworkInterface {
+            name: "eth0".to_string(),
+            description: String::new(),
+            index: 0,
+            mac: Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)),
+            ips,
+            flags: 0,
+        }
+    }
+
+    #[test]
+    fn preflight_accepts_on_link_targets() {
+        let net: Ipv4Network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 5), 24).unwrap();
+        let link_local: Ipv6Network = "fe80::1/64".parse().unwrap();
+        let mut config: SenderConfig =
+            SenderConfig::from(&mock_interface(vec![net.into(), link_local.into()]));
+        config.add_packet_type(PacketType::ARP);
+        config.add_packet_type(PacketType::ICMPv6);
+        config.add_target_set("192.168.1.0/24".parse().unwrap());
+
+        assert!(config.preflight().is_empty());
+    }
+
+    #[test]
+    fn preflight_reports_missing_addresses_and_off_link_targets() {
+        let net: Ipv4Network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 5), 24).unwrap();
+        let mut config: SenderConfig = SenderConfig::from(&mock_interface(vec![net.into()]));
+        config.add_packet_type(PacketType::ARP);
+        config.add_packet_type(PacketType::ICMPv6);
+        config.add_target_set("192.168.1.250-192.168.2.9".parse().unwrap());
+
+        assert_eq!(
+            config.preflight(),
+            vec![
+                PreflightIssue::MissingLinkLocal,
+                PreflightIssue::OffLinkTargets(10, 16)
+            ]
+        );
+
+        let mut config: SenderConfig = SenderConfig::from(&mock_interface(Vec::new()));
+        config.add_packet_type(PacketType::ARP);
+        config.add_target(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)));
+
+        assert_eq!(config.preflight(), vec![PreflightIssue::MissingIpv4(1)]);
+    }
+}
@@ -4,8 +4,14 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+pub mod demo;
+pub mod domain;
+pub mod fixture;
+pub mod idn;
 pub mod input;
 pub mod ip;
 pub mod mac;
+pub mod paths;
+pub mod privilege;
 pub mod redact;
 pub mod timing;
@@ -0,0 +1,288 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Fake-but-plausible output for demos and screen sharing.
+//!
+//! Where [`redact`](super::redact) masks values with `X`s, demo mode swaps
+//! them for invented ones that look real, so a recording shows a believable
+//! network instead of a wall of placeholders.
+//!
+//! Every fake value is derived from the real one with a key chosen at random
+//! once per process. The same address therefore maps to the same fake
+//! everywhere in a run, while the mapping cannot be reversed or correlated
+//! across runs. The structure that makes output readable is kept:
+//!
+//! * IPv4 addresses keep their last octet and stay private or public, with
+//!   every real `/24` moved to its own fake `/24`.
+//! * IPv6 addresses keep their scope: link-local stays `fe80::/64`, unique
+//!   local gets a fake `fd00::/8` prefix, everything else lands in `2001:db8::/32`.
+//! * MAC addresses keep the vendor OUI, so vendor names still match.
+//! * Hostnames become `adjective-noun-NN`, keeping generic suffixes such as
+//!   `.local` and replacing every other domain.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::OnceLock,
+};
+
+use pnet::util::MacAddr;
+use sha2::{Digest, Sha256};
+
+use crate::models::host::{Host, Unreachable};
+
+const ADJECTIVES: [&str; 16] = [
+    "amber", "brisk", "calm", "dusty", "eager", "fuzzy", "gentle", "hollow", "icy", "jolly",
+    "lunar", "misty", "noble", "quiet", "rusty", "sunny",
+];
+const NOUNS: [&str; 16] = [
+    "badger", "comet", "falcon", "garden", "harbor", "lantern", "maple", "otter", "pebble",
+    "quartz", "raven", "summit", "tulip", "walrus", "willow", "zephyr",
+];
+/// Suffixes that say nothing about the network and are kept as they are.
+pub(crate) const GENERIC_DOMAINS: [&str; 7] = [
+    "local",
+    "lan",
+    "home",
+    "home.arpa",
+    "internal",
+    "localdomain",
+    "fritz.box",
+];
+
+static SESSION: OnceLock<Demo> = OnceLock::new();
+
+/// Maps real values to fake ones under a fixed key.
+pub struct Demo {
+    key: [u8; 32],
+}
+
+impl Demo {
+    /// Creates a mapping with the given key. Equal keys give equal fakes.
+    pub fn with_key(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Returns the mapping of this process, keyed randomly on first use.
+    pub fn session() -> &'static Demo {
+        SESSION.get_or_init(|| Demo::with_key(rand::random()))
+    }
+
+    /// Replaces the addresses, names and location of `host` with fakes.
+    ///
+    /// The vendor is kept since the OUI it derives from is kept, and the
+    /// model is kept since it names a product rather than a device.
+    pub fn host(&self, host: &mut Host) {
+        host.primary_ip = self.ip(host.primary_ip);
+        host.ips = host.ips.iter().map(|ip| self.ip(*ip)).collect();
+        host.proxied_ips = host.proxied_ips.iter().map(|ip| self.ip(*ip)).collect();
+        host.mac = host.mac.map(|mac| self.mac(mac));
+        host.hostname = host.hostname.as_deref().map(|name| self.hostname(name));
+        host.hints.mdns_name = host
+            .hints
+            .mdns_name
+            .as_deref()
+            .map(|name| self.hostname(name));
+        host.location = host
+            .location
+            .as_deref()
+            .map(|location| self.location(location));
+    }
+
+    /// Replaces the target and the reporting router of `unreachable` with
+    /// fakes.
+    pub fn unreachable(&self, unreachable: &mut Unreachable) {
+        unreachable.target = self.ip(unreachable.target);
+        unreachable.reporter = self.ip(unreachable.reporter);
+    }
+
+    pub fn ip(&self, ip: IpAddr) -> IpAddr {
+        match ip {
+            IpAddr::V4(v4) => IpAddr::V4(self.ipv4(v4)),
+            IpAddr::V6(v6) => IpAddr::V6(self.ipv6(v6)),
+        }
+    }
+
+    fn ipv4(&self, ip: Ipv4Addr) -> Ipv4Addr {
+        if ip.is_loopback() || ip.is_unspecified() || ip.is_broadcast() {
+            return ip;
+        }
+
+        let [a, b, c, last] = ip.octets();
+        let h: [u8; 32] = self.digest("ipv4", &[a, b, c]);
+        let is_shared: bool = a == 100 && (b & 0xC0) == 64;
+        if ip.is_link_local() {
+            Ipv4Addr::new(169, 254, h[0].clamp(1, 254), last)
+        } else if ip.is_private() || is_shared {
+            Ipv4Addr::new(10, h[0], h[1], last)
+        } else {
+            // 198.18.0.0/15 is reserved for benchmarks and never routed.
+            Ipv4Addr::new(198, 18 | (h[0] & 1), h[1], last)
+        }
+    }
+
+    fn ipv6(&self, ip: Ipv6Addr) -> Ipv6Addr {
+        if ip.is_loopback() || ip.is_unspecified() {
+            return ip;
+        }
+
+        let segments: [u16; 8] = ip.segments();
+        let prefix: Vec<u8> = segments[..4].iter().flat_map(|s| s.to_be_bytes()).collect();
+        let p: [u8; 32] = self.digest("ipv6-prefix", &prefix);
+        let i: [u8; 32] = self.digest("ipv6-iid", &ip.octets());
+        let word = |bytes: &[u8; 32], idx: usize| u16::from_be_bytes([bytes[idx], bytes[idx + 1]]);
+
+        let head: [u16; 4] = if segments[0] & 0xFFC0 == 0xFE80 {
+            [0xFE80, 0, 0, 0]
+        } else if segments[0] & 0xFE00 == 0xFC00 {
+            [
+                0xFD00 | (p[0] as u16),
+                word(&p, 1),
+                word(&p, 3),
+                word(&p, 5),
+            ]
+        } else {
+            [0x2001, 0x0DB8, word(&p, 1), word(&p, 3)]
+        };
+        Ipv6Addr::new(
+            head[0],
+            head[1],
+            head[2],
+            head[3],
+            word(&i, 0),
+            word(&i, 2),
+            word(&i, 4),
+            word(&i, 6),
+        )
+    }
+
+    pub fn mac(&self, mac: MacAddr) -> MacAddr {
+        let h: [u8; 32] = self.digest("mac", &mac.octets());
+        // Locally administered addresses are random anyway; keep only that bit.
+        if mac.0 & 0x02 != 0 {
+            return MacAddr::new((h[0] | 0x02) & 0xFE, h[1], h[2], h[3], h[4], h[5]);
+        }
+        MacAddr::new(mac.0, mac.1, mac.2, h[0], h[1], h[2])
+    }
+
+    pub fn hostname(&self, name: &str) -> String {
+        let trimmed: &str = name.trim_end_matches('.');
+        let (label, domain) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+        let h: [u8; 32] = self.digest("hostname", label.to_ascii_lowercase().as_bytes());
+        let fake: String = format!(
+            "{}-{}-{}",
+            ADJECTIVES[(h[0] & 0x0F) as usize],
+            NOUNS[(h[1] & 0x0F) as usize],
+            10 + h[2] % 90
+        );
+
+        let domain: String = domain.to_ascii_lowercase();
+        if domain.is_empty() {
+            fake
+        } else if GENERIC_DOMAINS.contains(&domain.as_str()) {
+            format!("{fake}.{domain}")
+        } else {
+            let d: [u8; 32] = self.digest("domain", domain.as_bytes());
+            format!("{fake}.site{}.example", 10 + d[0] % 90)
+        }
+    }
+
+    /// Replaces `word` with a fake of the same length in bytes and the same
+    /// shape: letters stay letters, digits stay digits and the rest is kept.
+    pub fn word(&self, word: &str) -> String {
+        let h: [u8; 32] = self.digest("word", word.to_lowercase().as_bytes());
+        let mut n: usize = 0;
+        let mut next = || {
+            let byte: u8 = h[n % h.len()].wrapping_add((n / h.len()) as u8);
+            n += 1;
+            byte
+        };
+        word.chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphabetic() => char::from(b'a' + next() % 26).to_string(),
+                c if c.is_ascii_digit() => char::from(b'0' + next() % 10).to_string(),
+                c if c.is_ascii() => c.to_string(),
+                c => "x".repeat(c.len_utf8()),
+            })
+            .collect()
+    }
+
+    fn location(&self, location: &str) -> String {
+        let h: [u8; 32] = self.digest("location", location.as_bytes());
+        format!("Room {}{:02}", 1 + h[0] % 5, h[1] % 40)
+    }
+
+    fn digest(&self, domain: &str, data: &[u8]) -> [u8; 32] {
+        let mut hasher: Sha256 = Sha256::new();
+        hasher.update(self.key);
+        hasher.update(domain.as_bytes());
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_keep_their_shape() {
+        let demo: Demo = Demo::with_key([7; 32]);
+
+        let private: IpAddr = demo.ip("192.168.1.1".parse().unwrap());
+        assert_eq!(private, demo.ip("192.168.1.1".parse().unwrap()));
+        let IpAddr::V4(private) = private else {
+            panic!("IPv4 turned into IPv6")
+        };
+        assert_eq!(private.octets()[3], 1);
+        assert!(private.is_private());
+        assert_ne!(private, Ipv4Addr::new(192, 168, 1, 1));
+
+        // Hosts of one real /24 share their fake /24.
+        let IpAddr::V4(neighbor) = demo.ip("192.168.1.77".parse().unwrap()) else {
+            panic!("IPv4 turned into IPv6")
+        };
+        assert_eq!(neighbor.octets()[..3], private.octets()[..3]);
+
+        let IpAddr::V6(link_local) = demo.ip("fe80::ca52:61ff:fec7:594".parse().unwrap()) else {
+            panic!("IPv6 turned into IPv4")
+        };
+        assert_eq!(link_local.segments()[..4], [0xFE80, 0, 0, 0]);
+
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(demo.ip(loopback), loopback);
+
+        let mac: MacAddr = MacAddr::new(0x2c, 0xcf, 0x67, 0xf2, 0x51, 0xe3);
+        let fake: MacAddr = demo.mac(mac);
+        assert_eq!((fake.0, fake.1, fake.2), (0x2c, 0xcf, 0x67));
+        assert_ne!(fake, mac);
+    }
+
+    #[test]
+    fn hostnames_are_consistent_and_hide_domains() {
+        let demo: Demo = Demo::with_key([7; 32]);
+
+        let fake: String = demo.hostname("nas.fritz.box");
+        assert_eq!(fake, demo.hostname("NAS.fritz.box"));
+        assert!(fake.ends_with(".fritz.box"));
+        assert!(!fake.starts_with("nas."));
+
+        let corporate: String = demo.hostname("build01.acme-corp.com.");
+        assert!(corporate.ends_with(".example"));
+        assert!(!corporate.contains("acme"));
+
+        let other: Demo = Demo::with_key([8; 32]);
+        assert_ne!(other.hostname("build01"), demo.hostname("build01"));
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Short display names for hosts in the local DNS search domains.
+//!
+//! Reverse lookups answer with fully qualified names, and on a network with
+//! a search domain they all end in the same suffix. With `--short-names`,
+//! that suffix is cut off for display, the way users type the names anyway:
+//! `printer` instead of `printer.lan.example.com`. Reports and exports keep
+//! the full name.
+
+/// Returns `hostname` without the longest of `domains` it ends in, or
+/// `hostname` as it is if it is in none of them or would be left empty.
+///
+/// Matching ignores ASCII case and a trailing dot on either side.
+///
+/// # Examples
+/// ```
+/// use zond_common::utils::domain;
+///
+/// let search: Vec<String> = vec!["lan.example.com".to_string()];
+/// assert_eq!(domain::strip_search_domain("printer.lan.example.com.", &search), "printer");
+/// assert_eq!(domain::strip_search_domain("printer.example.com", &search), "printer.example.com");
+/// ```
+pub fn strip_search_domain<'a>(hostname: &'a str, domains: &[String]) -> &'a str {
+    let name: &str = hostname.trim_end_matches('.');
+    domains
+        .iter()
+        .map(|domain| domain.trim_matches('.'))
+        .filter(|domain| !domain.is_empty())
+        .filter_map(|domain| {
+            let host_len: usize = name.len().checked_sub(domain.len() + 1)?;
+            let suffix: &str = name.get(host_len..)?;
+            (host_len > 0 && suffix.starts_with('.') && suffix[1..].eq_ignore_ascii_case(domain))
+                .then(|| &name[..host_len])
+        })
+        .min_by_key(|short| short.len())
+        .unwrap_or(hostname)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_longest_search_domain() {
+        let search: Vec<String> = vec![
+            "example.com".to_string(),
+            "LAN.Example.com.".to_string(),
+            String::new(),
+        ];
+        let short = |name: &str| strip_search_domain(name, &search).to_string();
+
+        assert_eq!(short("printer.lan.example.com"), "printer");
+        assert_eq!(short("nas.office.example.com."), "nas.office");
+        assert_eq!(short("a.b.lan.example.com"), "a.b");
+        // Never down to nothing, and only on a label boundary.
+        assert_eq!(short("example.com"), "example.com");
+        assert_eq!(short("myexample.com"), "myexample.com");
+        assert_eq!(short("printer.local"), "printer.local");
+        assert_eq!(short("drucker-büro.lan.example.com"), "drucker-büro");
+        assert_eq!(strip_search_domain("printer.lan", &[]), "printer.lan");
+    }
+}
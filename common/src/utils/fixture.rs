@@ -0,0 +1,981 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Anonymized test fixtures from real scans, for `zond fixture`.
+//!
+//! Turns a report and the pcap files of its run into fixtures that can be
+//! shared in a bug report or checked into the test suite: every address and
+//! name is swapped for a fake, and the same fake everywhere, so the frames
+//! still belong to the hosts the report lists.
+//!
+//! [`Anonymizer`] first learns what to replace from the report and the
+//! captures, then rewrites both:
+//!
+//! * IP and MAC addresses are remapped with a [`Demo`] mapping and keep its
+//!   structure (vendor OUIs, private ranges, IPv6 scopes). An EUI-64 interface
+//!   ID also follows its MAC address, and a solicited-node multicast address
+//!   the address it solicits, so neighbor discovery still lines up.
+//! * Names are replaced with fakes of the same length and shape. Generic
+//!   suffixes such as `.local` and the top-level domain are kept.
+//!
+//! Payloads can carry anything, from cookies to the names of unlearned
+//! hosts, so only the headers the scanners parse survive: ARP, IP, the TCP,
+//! UDP and SCTP headers and first chunk header, ICMP headers with the packet
+//! header they quote, and NDP messages with their address, prefix and MTU
+//! options. Every byte after them is zeroed.
+//!
+//! Frames keep their length, so a fixture parses the way the capture did:
+//! addresses are replaced byte for byte wherever they appear in the headers.
+//! The IPv4, TCP, UDP, ICMP and ICMPv6 checksums are then adjusted by the
+//! difference, which keeps a correct checksum correct and a broken one broken
+//! by the same amount, and a correct SCTP checksum is computed anew.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ops::Range,
+};
+
+use anyhow::{bail, ensure};
+use pnet::util::MacAddr;
+
+use super::demo::{Demo, GENERIC_DOMAINS};
+use crate::models::{
+    host::{Host, Unreachable},
+    scan::ScanInfo,
+};
+
+/// Shorter names are left alone; they would match all over the payloads.
+const MIN_NAME_LEN: usize = 3;
+/// Lengths of the address patterns, longest first: IPv6, MAC, IPv4.
+const PATTERN_LENS: [usize; 3] = [16, 6, 4];
+
+const LINK_ETHERNET: u32 = 1;
+const LINK_RAW: u32 = 101;
+const LINK_LINUX_SLL: u32 = 113;
+const LINK_IPV4: u32 = 228;
+const LINK_IPV6: u32 = 229;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+
+/// Replaces the addresses and names of a scan with consistent fakes.
+pub struct Anonymizer {
+    demo: Demo,
+    /// Real addresses in network byte order, and fakes of the same length.
+    patterns: HashMap<Vec<u8>, Vec<u8>>,
+    /// Real names and addresses as text, in lowercase, and fakes of the same
+    /// length.
+    names: HashMap<String, String>,
+}
+
+/// Where the IP packet of a frame starts and which version it is.
+struct Network {
+    offset: usize,
+    ethertype: u16,
+}
+
+impl Anonymizer {
+    /// Creates an anonymizer with the given key. Equal keys give equal fakes.
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            demo: Demo::with_key(key),
+            patterns: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Learns the addresses and names of `host`.
+    pub fn learn_host(&mut self, host: &Host) {
+        for ip in host.ips.iter().chain(&host.proxied_ips) {
+            self.learn_ip(*ip);
+        }
+        self.learn_ip(host.primary_ip);
+        if let Some(mac) = host.mac {
+            self.learn_mac(mac);
+        }
+        if let Some(hostname) = &host.hostname {
+            self.learn_hostname(hostname);
+        }
+        // Free-form names are replaced as a whole, since their words, such
+        // as "Room", would also match unrelated payloads.
+        for phrase in [&host.hints.mdns_name, &host.location]
+            .into_iter()
+            .flatten()
+        {
+            self.learn_name(phrase);
+        }
+    }
+
+    /// Learns the target and the reporting router of `unreachable`.
+    pub fn learn_unreachable(&mut self, unreachable: &Unreachable) {
+        self.learn_ip(unreachable.target);
+        self.learn_ip(unreachable.reporter);
+    }
+
+    /// Learns the scanning machine of `scan`: its name, user and addresses.
+    pub fn learn_scan(&mut self, scan: &ScanInfo) {
+        if let Some(hostname) = &scan.hostname {
+            self.learn_hostname(hostname);
+        }
+        if let Some(user) = &scan.user {
+            self.learn_name(user);
+        }
+        for interface in &scan.interfaces {
+            let address: &str = interface.split_whitespace().last().unwrap_or_default();
+            let ip: &str = address.split('/').next().unwrap_or_default();
+            if let Ok(ip) = ip.parse::<IpAddr>() {
+                self.learn_ip(ip);
+            }
+        }
+    }
+
+    /// Learns the addresses in the link, ARP and IP headers of every frame in
+    /// `pcap`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pcap` is not a classic pcap file of a link type
+    /// this knows.
+    pub fn learn_pcap(&mut self, pcap: &[u8]) -> anyhow::Result<()> {
+        let (link, frames): (u32, Vec<Range<usize>>) = pcap_frames(pcap)?;
+        for range in frames {
+            self.learn_frame(link, &pcap[range]);
+        }
+        Ok(())
+    }
+
+    /// Replaces the addresses and names of `host` with their fakes.
+    pub fn host(&self, host: &mut Host) {
+        host.primary_ip = self.ip(host.primary_ip);
+        host.ips = host.ips.iter().map(|ip| self.ip(*ip)).collect();
+        host.proxied_ips = host.proxied_ips.iter().map(|ip| self.ip(*ip)).collect();
+        host.mac = host.mac.map(|mac| self.mac(mac));
+
+        for text in [
+            &mut host.hostname,
+            &mut host.hints.mdns_name,
+            &mut host.location,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *text = self.text(text);
+        }
+        for banner in host.service_infos_mut() {
+            *banner = self.text(banner);
+        }
+        for value in host.annotations.values_mut() {
+            *value = self.text(value);
+        }
+    }
+
+    /// Replaces the addresses of `unreachable` with their fakes.
+    pub fn unreachable(&self, unreachable: &mut Unreachable) {
+        unreachable.target = self.ip(unreachable.target);
+        unreachable.reporter = self.ip(unreachable.reporter);
+    }
+
+    /// Leaves out the user and hostname of the scanning machine, as
+    /// `--redact` does, and replaces the addresses and names in the command
+    /// line and interfaces of `scan`.
+    pub fn scan(&self, scan: &mut ScanInfo) {
+        scan.user = None;
+        scan.hostname = None;
+        for arg in scan.argv.iter_mut().chain(&mut scan.interfaces) {
+            *arg = self.text(arg);
+        }
+    }
+
+    /// Rewrites every frame of `pcap` in place and returns how many there were.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pcap` is not a classic pcap file of a link type
+    /// this knows.
+    pub fn pcap(&self, pcap: &mut [u8]) -> anyhow::Result<usize> {
+        let (link, frames): (u32, Vec<Range<usize>>) = pcap_frames(pcap)?;
+        for range in &frames {
+            let frame: &mut [u8] = &mut pcap[range.clone()];
+            let original: Vec<u8> = frame.to_vec();
+            self.replace_addresses(&original, frame);
+            scrub_payloads(link, frame);
+            if *frame != original[..] {
+                fix_checksums(link, &original, frame);
+            }
+        }
+        Ok(frames.len())
+    }
+
+    /// Replaces the IP addresses, MAC addresses and learned names in `text`.
+    pub fn text(&self, text: &str) -> String {
+        let mut out: String = String::with_capacity(text.len());
+        let mut rest: &str = text;
+        while let Some(start) = rest.find(is_address_char) {
+            out.push_str(&rest[..start]);
+            let run: &str = &rest[start..];
+            let end: usize = run.find(|c| !is_address_char(c)).unwrap_or(run.len());
+            // "at 10.0.0.5." ends a sentence rather than the address.
+            let token: &str = run[..end].trim_end_matches(['.', ':']);
+            let fake: Option<String> = match (token.parse::<IpAddr>(), token.parse::<MacAddr>()) {
+                (Ok(ip), _) => Some(self.ip(ip).to_string()),
+                (_, Ok(mac)) if token.len() == 17 => Some(self.mac(mac).to_string()),
+                _ => None,
+            };
+            let taken: usize = match fake {
+                Some(fake) => {
+                    out.push_str(&fake);
+                    token.len()
+                }
+                None => {
+                    out.push_str(&run[..end]);
+                    end
+                }
+            };
+            rest = &run[taken..];
+        }
+        out.push_str(rest);
+
+        let mut bytes: Vec<u8> = out.into_bytes();
+        self.replace_names(&mut bytes);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// The fake of `ip`. Loopback, unspecified, broadcast and multicast
+    /// addresses are kept.
+    pub fn ip(&self, ip: IpAddr) -> IpAddr {
+        if is_kept(ip) {
+            return ip;
+        }
+        match self.demo.ip(ip) {
+            IpAddr::V6(fake) => {
+                let IpAddr::V6(real) = ip else {
+                    unreachable!("demo mode keeps the address family");
+                };
+                IpAddr::V6(match eui64_mac(real) {
+                    Some(mac) => with_eui64(fake, self.mac(mac)),
+                    None => fake,
+                })
+            }
+            fake => fake,
+        }
+    }
+
+    /// The fake of `mac`. Broadcast, multicast and all-zero addresses are kept.
+    pub fn mac(&self, mac: MacAddr) -> MacAddr {
+        if mac.0 & 0x01 != 0 || mac == MacAddr::zero() {
+            return mac;
+        }
+        self.demo.mac(mac)
+    }
+
+    fn learn_ip(&mut self, ip: IpAddr) {
+        if is_kept(ip) {
+            return;
+        }
+        let fake: IpAddr = self.ip(ip);
+        self.learn_text(&ip.to_string(), &fake.to_string());
+        match (ip, fake) {
+            (IpAddr::V4(real), IpAddr::V4(fake)) => {
+                self.learn_pattern(&real.octets(), &fake.octets());
+            }
+            (IpAddr::V6(real), IpAddr::V6(fake)) => {
+                self.learn_pattern(&real.octets(), &fake.octets());
+                self.learn_pattern(
+                    &solicited_node(real).octets(),
+                    &solicited_node(fake).octets(),
+                );
+                let (real, fake): ([u8; 16], [u8; 16]) = (real.octets(), fake.octets());
+                self.learn_pattern(
+                    &[0x33, 0x33, 0xFF, real[13], real[14], real[15]],
+                    &[0x33, 0x33, 0xFF, fake[13], fake[14], fake[15]],
+                );
+            }
+            _ => unreachable!("demo mode keeps the address family"),
+        }
+    }
+
+    fn learn_mac(&mut self, mac: MacAddr) {
+        let fake: MacAddr = self.mac(mac);
+        self.learn_pattern(&mac.octets(), &fake.octets());
+        if mac != fake {
+            self.learn_text(&mac.to_string(), &fake.to_string());
+        }
+    }
+
+    /// Learns an address written out in payloads. A fake of another length
+    /// would shift the rest of the frame, so it is masked instead.
+    fn learn_text(&mut self, real: &str, fake: &str) {
+        let fake: String = match fake.len() == real.len() {
+            true => fake.to_string(),
+            false => "x".repeat(real.len()),
+        };
+        self.names.insert(real.to_ascii_lowercase(), fake);
+    }
+
+    fn learn_pattern(&mut self, real: &[u8], fake: &[u8]) {
+        if real != fake {
+            self.patterns.insert(real.to_vec(), fake.to_vec());
+        }
+    }
+
+    /// Learns the labels of `hostname`, such as the name of an agent, but its
+    /// generic suffix and top-level domain.
+    pub fn learn_hostname(&mut self, hostname: &str) {
+        let labels: Vec<&str> = hostname.trim_end_matches('.').split('.').collect();
+        let own: usize = labels.len() - usize::from(labels.len() > 1);
+        for label in &labels[..own] {
+            let generic: bool = GENERIC_DOMAINS.iter().any(|domain| {
+                domain
+                    .split('.')
+                    .any(|part| part.eq_ignore_ascii_case(label))
+            });
+            if !generic {
+                self.learn_name(label);
+            }
+        }
+    }
+
+    fn learn_name(&mut self, name: &str) {
+        let name: &str = name.trim();
+        if name.len() < MIN_NAME_LEN {
+            return;
+        }
+        let key: String = name.to_ascii_lowercase();
+        if !self.names.contains_key(&key) {
+            let fake: String = self.demo.word(&key);
+            self.names.insert(key, fake);
+        }
+    }
+
+    fn learn_frame(&mut self, link: u32, frame: &[u8]) {
+        let mac_at = |offset: usize| -> Option<MacAddr> {
+            let octets: &[u8] = frame.get(offset..offset + 6)?;
+            Some(MacAddr::new(
+                octets[0], octets[1], octets[2], octets[3], octets[4], octets[5],
+            ))
+        };
+        match link {
+            LINK_ETHERNET => {
+                for mac in [mac_at(0), mac_at(6)].into_iter().flatten() {
+                    self.learn_mac(mac);
+                }
+            }
+            LINK_LINUX_SLL if frame.get(4..6) == Some(&[0, 6]) => {
+                if let Some(mac) = mac_at(6) {
+                    self.learn_mac(mac);
+                }
+            }
+            _ => {}
+        }
+
+        let Some(network) = network(link, frame) else {
+            return;
+        };
+        let at: usize = network.offset;
+        let (addresses, len): ([usize; 2], usize) = match network.ethertype {
+            ETHERTYPE_IPV4 => ([at + 12, at + 16], 4),
+            ETHERTYPE_IPV6 => ([at + 8, at + 24], 16),
+            // Sender and target protocol address of an Ethernet/IPv4 ARP packet.
+            ETHERTYPE_ARP if frame.get(at + 4..at + 6) == Some(&[6, 4]) => {
+                for mac in [mac_at(at + 8), mac_at(at + 18)].into_iter().flatten() {
+                    self.learn_mac(mac);
+                }
+                ([at + 14, at + 24], 4)
+            }
+            _ => return,
+        };
+        for offset in addresses {
+            let ip: Option<IpAddr> = match frame.get(offset..offset + len) {
+                Some(&[a, b, c, d]) => Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d))),
+                Some(octets) if len == 16 => <[u8; 16]>::try_from(octets)
+                    .ok()
+                    .map(|o| IpAddr::V6(o.into())),
+                _ => None,
+            };
+            if let Some(ip) = ip {
+                self.learn_ip(ip);
+            }
+        }
+    }
+
+    /// Writes `original` into `frame` with every learned address replaced,
+    /// longest patterns first.
+    fn replace_addresses(&self, original: &[u8], frame: &mut [u8]) {
+        let mut at: usize = 0;
+        'outer: while at < original.len() {
+            for len in PATTERN_LENS {
+                let Some(window) = original.get(at..at + len) else {
+                    continue;
+                };
+                if let Some(fake) = self.patterns.get(window) {
+                    frame[at..at + len].copy_from_slice(fake);
+                    at += len;
+                    continue 'outer;
+                }
+            }
+            at += 1;
+        }
+    }
+
+    /// Replaces every learned name in `bytes` that stands on its own, in text
+    /// or as a DNS label.
+    fn replace_names(&self, bytes: &mut [u8]) {
+        for (name, fake) in &self.names {
+            let name: &[u8] = name.as_bytes();
+            let mut at: usize = 0;
+            while at + name.len() <= bytes.len() {
+                let window: &[u8] = &bytes[at..at + name.len()];
+                let before: Option<u8> = at.checked_sub(1).map(|idx| bytes[idx]);
+                let after: Option<u8> = bytes.get(at + name.len()).copied();
+                // A DNS label is preceded by its length, which may be a digit.
+                let starts: bool = before.is_none_or(|byte| {
+                    !byte.is_ascii_alphanumeric() || usize::from(byte) == name.len()
+                });
+                let ends: bool = after.is_none_or(|byte| !byte.is_ascii_alphanumeric());
+                if starts && ends && window.eq_ignore_ascii_case(name) {
+                    bytes[at..at + name.len()].copy_from_slice(fake.as_bytes());
+                    at += name.len();
+                } else {
+                    at += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Whether `ip` says nothing about the network and is kept as it is.
+fn is_kept(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_loopback() || v4.is_unspecified() || v4.is_broadcast() || v4.is_multicast()
+        }
+        IpAddr::V6(v6) => v6.is_loopback() || v6.is_unspecified() || v6.is_multicast(),
+    }
+}
+
+fn is_address_char(c: char) -> bool {
+    c.is_ascii_hexdigit() || c == '.' || c == ':'
+}
+
+/// The MAC address a modified EUI-64 interface ID was built from.
+fn eui64_mac(ip: Ipv6Addr) -> Option<MacAddr> {
+    let o: [u8; 16] = ip.octets();
+    (o[11] == 0xFF && o[12] == 0xFE)
+        .then(|| MacAddr::new(o[8] ^ 0x02, o[9], o[10], o[13], o[14], o[15]))
+}
+
+/// `ip` with its interface ID built from `mac`.
+fn with_eui64(ip: Ipv6Addr, mac: MacAddr) -> Ipv6Addr {
+    let mut o: [u8; 16] = ip.octets();
+    o[8..].copy_from_slice(&[mac.0 ^ 0x02, mac.1, mac.2, 0xFF, 0xFE, mac.3, mac.4, mac.5]);
+    Ipv6Addr::from(o)
+}
+
+/// The solicited-node multicast address of `ip`, `ff02::1:ffXX:XXXX`.
+fn solicited_node(ip: Ipv6Addr) -> Ipv6Addr {
+    let o: [u8; 16] = ip.octets();
+    Ipv6Addr::new(
+        0xFF02,
+        0,
+        0,
+        0,
+        0,
+        1,
+        0xFF00 | u16::from(o[13]),
+        u16::from_be_bytes([o[14], o[15]]),
+    )
+}
+
+/// Reads the link type of a classic pcap file and where its frames are.
+fn pcap_frames(pcap: &[u8]) -> anyhow::Result<(u32, Vec<Range<usize>>)> {
+    let Some(magic) = pcap.get(..4) else {
+        bail!("not a pcap file");
+    };
+    let big_endian: bool = match magic {
+        [0xD4, 0xC3, 0xB2, 0xA1] | [0x4D, 0x3C, 0xB2, 0xA1] => false,
+        [0xA1, 0xB2, 0xC3, 0xD4] | [0xA1, 0xB2, 0x3C, 0x4D] => true,
+        [0x0A, 0x0D, 0x0D, 0x0A] => {
+            bail!("pcapng is not supported, convert it with `editcap -F pcap`")
+        }
+        _ => bail!("not a pcap file"),
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes: [u8; 4] = pcap.get(offset..offset + 4)?.try_into().ok()?;
+        Some(match big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    };
+
+    let Some(link) = u32_at(20).map(|field| field & 0xFFFF) else {
+        bail!("pcap header is truncated");
+    };
+    ensure!(
+        matches!(
+            link,
+            LINK_ETHERNET | LINK_RAW | LINK_LINUX_SLL | LINK_IPV4 | LINK_IPV6
+        ),
+        "unsupported pcap link type {link}"
+    );
+
+    let mut frames: Vec<Range<usize>> = Vec::new();
+    let mut at: usize = 24;
+    while at < pcap.len() {
+        let Some(len) = u32_at(at + 8) else {
+            bail!("pcap record header at byte {at} is truncated");
+        };
+        let frame: Range<usize> = at + 16..at + 16 + len as usize;
+        ensure!(
+            frame.end <= pcap.len(),
+            "pcap frame at byte {at} is truncated"
+        );
+        at = frame.end;
+        frames.push(frame);
+    }
+    Ok((link, frames))
+}
+
+/// Where the IP or ARP packet of `frame` starts.
+fn network(link: u32, frame: &[u8]) -> Option<Network> {
+    let (offset, ethertype): (usize, u16) = match link {
+        LINK_ETHERNET => {
+            let mut offset: usize = 12;
+            // VLAN tags, single or stacked.
+            while matches!(u16_at(frame, offset)?, 0x8100 | 0x88A8) {
+                offset += 4;
+            }
+            (offset + 2, u16_at(frame, offset)?)
+        }
+        LINK_LINUX_SLL => (16, u16_at(frame, 14)?),
+        LINK_IPV4 => (0, ETHERTYPE_IPV4),
+        LINK_IPV6 => (0, ETHERTYPE_IPV6),
+        _ => match frame.first()? >> 4 {
+            4 => (0, ETHERTYPE_IPV4),
+            6 => (0, ETHERTYPE_IPV6),
+            _ => return None,
+        },
+    };
+    Some(Network { offset, ethertype })
+}
+
+/// Zeroes everything in `frame` after the headers the scanners parse.
+fn scrub_payloads(link: u32, frame: &mut [u8]) {
+    let Some(Network {
+        offset: at,
+        ethertype,
+    }) = network(link, frame)
+    else {
+        return;
+    };
+
+    let (protocol, segment): (u8, usize) = match ethertype {
+        ETHERTYPE_ARP => return zero_from(frame, at + 28),
+        ETHERTYPE_IPV4 => {
+            let header_len: usize = usize::from(frame.get(at).map_or(0, |byte| byte & 0x0F)) * 4;
+            // Later fragments carry no transport header.
+            if u16_at(frame, at + 6).is_none_or(|fragment| fragment & 0x1FFF != 0) {
+                return zero_from(frame, at + header_len.max(20));
+            }
+            (
+                frame.get(at + 9).copied().unwrap_or(0),
+                at + header_len.max(20),
+            )
+        }
+        ETHERTYPE_IPV6 => (frame.get(at + 6).copied().unwrap_or(0), at + 40),
+        _ => return zero_from(frame, at),
+    };
+
+    let kept: usize = match (protocol, frame.get(segment).copied()) {
+        (6, _) => {
+            let data_offset: u8 = frame.get(segment + 12).map_or(0, |byte| byte >> 4);
+            segment + (usize::from(data_offset) * 4).max(20)
+        }
+        (17, _) => segment + 8,
+        (132, _) => segment + 16,
+        // Errors quote the header of the probe and the ports after it.
+        (1, Some(3 | 4 | 5 | 11 | 12)) => {
+            let quoted: u8 = frame.get(segment + 8).map_or(0, |byte| byte & 0x0F);
+            segment + 8 + (usize::from(quoted) * 4).max(20) + 8
+        }
+        (58, Some(1..=4)) => segment + 8 + 40 + 8,
+        (58, Some(kind @ 133..=137)) => return scrub_ndp_options(frame, segment, kind),
+        (1 | 58, _) => segment + 8,
+        _ => segment,
+    };
+    zero_from(frame, kept);
+}
+
+/// Zeroes the data of NDP options other than link-layer addresses, prefixes
+/// and the MTU, such as the DNS search list of a router advertisement.
+fn scrub_ndp_options(frame: &mut [u8], segment: usize, kind: u8) {
+    let fixed_len: usize = match kind {
+        133 => 8,
+        134 => 16,
+        137 => 40,
+        _ => 24,
+    };
+    let mut at: usize = segment + fixed_len;
+    while at + 2 <= frame.len() {
+        let len: usize = usize::from(frame[at + 1]) * 8;
+        if len == 0 {
+            return zero_from(frame, at);
+        }
+        if !matches!(frame[at], 1 | 2 | 3 | 5) {
+            zero(frame, at + 2..at + len);
+        }
+        at += len;
+    }
+}
+
+fn zero(frame: &mut [u8], range: Range<usize>) {
+    let end: usize = range.end.min(frame.len());
+    if range.start < end {
+        frame[range.start..end].fill(0);
+    }
+}
+
+fn zero_from(frame: &mut [u8], start: usize) {
+    zero(frame, start..frame.len());
+}
+
+/// Adjusts the checksums of the IP packet in `frame` for what changed since
+/// `original`.
+fn fix_checksums(link: u32, original: &[u8], frame: &mut [u8]) {
+    let Some(Network {
+        offset: at,
+        ethertype,
+    }) = network(link, frame)
+    else {
+        return;
+    };
+
+    let (pseudo, protocol, segment, is_v4): (Range<usize>, u8, Range<usize>, bool) = match ethertype
+    {
+        ETHERTYPE_IPV4 => {
+            let header_len: usize = usize::from(frame.get(at).map_or(0, |byte| byte & 0x0F)) * 4;
+            let (Some(total_len), Some(fragment)) = (u16_at(frame, at + 2), u16_at(frame, at + 6))
+            else {
+                return;
+            };
+            if header_len < 20 || frame.len() < at + header_len {
+                return;
+            }
+            adjust(original, frame, at + 10, &[at..at + header_len], false);
+            // Only the first fragment carries the transport header.
+            if fragment & 0x1FFF != 0 {
+                return;
+            }
+            let end: usize = frame.len().min(at + usize::from(total_len).max(header_len));
+            (at + 12..at + 20, frame[at + 9], at + header_len..end, true)
+        }
+        ETHERTYPE_IPV6 if frame.len() >= at + 40 => {
+            let Some(payload_len) = u16_at(frame, at + 4) else {
+                return;
+            };
+            let end: usize = frame.len().min(at + 40 + usize::from(payload_len));
+            (at + 8..at + 40, frame[at + 6], at + 40..end, false)
+        }
+        _ => return,
+    };
+
+    let start: usize = segment.start;
+    match protocol {
+        6 => adjust(original, frame, start + 16, &[pseudo, segment], false),
+        17 => adjust(original, frame, start + 6, &[pseudo, segment], is_v4),
+        1 if is_v4 => adjust(original, frame, start + 2, &[segment], false),
+        58 if !is_v4 => adjust(original, frame, start + 2, &[pseudo, segment], false),
+        132 => recompute_crc32c(original, frame, segment),
+        _ => {}
+    }
+}
+
+/// Computes the SCTP checksum of `segment` in `frame` anew, if it was correct
+/// in `original`. It covers no pseudo-header, so only the scrubbed chunks
+/// change it.
+fn recompute_crc32c(original: &[u8], frame: &mut [u8], segment: Range<usize>) {
+    if segment.len() < 12 {
+        return;
+    }
+    let field: Range<usize> = segment.start + 8..segment.start + 12;
+    let checksum = |bytes: &[u8]| -> u32 {
+        let mut packet: Vec<u8> = bytes[segment.clone()].to_vec();
+        packet[8..12].fill(0);
+        crc32c(&packet)
+    };
+    let stored: [u8; 4] = original[field.clone()].try_into().unwrap_or_default();
+    if checksum(original) == u32::from_le_bytes(stored) {
+        // CRC32c goes on the wire least significant byte first.
+        frame[field].copy_from_slice(&checksum(frame).to_le_bytes());
+    }
+}
+
+/// CRC32c (Castagnoli), as used by the SCTP checksum.
+fn crc32c(bytes: &[u8]) -> u32 {
+    const POLY: u32 = 0x82F6_3B78;
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (POLY & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Adds the difference between the one's complement sums of `ranges` in
+/// `original` and `frame` to the checksum at `field`.
+///
+/// With `optional`, a zero checksum means none was computed and stays zero.
+fn adjust(
+    original: &[u8],
+    frame: &mut [u8],
+    field: usize,
+    ranges: &[Range<usize>],
+    optional: bool,
+) {
+    let Some(bytes) = original.get(field..field + 2) else {
+        return;
+    };
+    let checksum: u16 = u16::from_be_bytes([bytes[0], bytes[1]]);
+    if optional && checksum == 0 {
+        return;
+    }
+
+    let before: u16 = ones_sum(original, ranges, field);
+    let after: u16 = ones_sum(frame, ranges, field);
+    let mut adjusted: u16 = !fold(u32::from(!checksum) + u32::from(!before) + u32::from(after));
+    if optional && adjusted == 0 {
+        adjusted = 0xFFFF;
+    }
+    frame[field..field + 2].copy_from_slice(&adjusted.to_be_bytes());
+}
+
+/// The one's complement sum of the 16-bit words of `ranges`, with the
+/// checksum at `field` counted as zero.
+fn ones_sum(bytes: &[u8], ranges: &[Range<usize>], field: usize) -> u16 {
+    let mut sum: u32 = 0;
+    for range in ranges {
+        for idx in range.clone().step_by(2) {
+            if idx == field {
+                continue;
+            }
+            let high: u8 = bytes[idx];
+            let low: u8 = if idx + 1 < range.end {
+                bytes[idx + 1]
+            } else {
+                0
+            };
+            sum = u32::from(fold(sum + u32::from(u16::from_be_bytes([high, low]))));
+        }
+    }
+    fold(sum)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn fold(mut sum: u32) -> u16 {
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    sum as u16
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: MacAddr = MacAddr(0x00, 0x11, 0x32, 0xAA, 0xBB, 0xCC);
+    const SCANNER: MacAddr = MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+
+    /// An Ethernet frame carrying a UDP datagram from `src` to `dst`, with a
+    /// correct checksum unless `broken`.
+    fn udp_frame(src: Ipv4Addr, dst: Ipv4Addr, payload: &[u8], broken: bool) -> Vec<u8> {
+        let mut frame: Vec<u8> = Vec::new();
+        frame.extend_from_slice(&MAC.octets());
+        frame.extend_from_slice(&SCANNER.octets());
+        frame.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+        let total: u16 = (20 + 8 + payload.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0, 0, 0, 0, 1, 0, 0, 64, 17, 0, 0]);
+        frame[16..18].copy_from_slice(&total.to_be_bytes());
+        frame.extend_from_slice(&src.octets());
+        frame.extend_from_slice(&dst.octets());
+        let ip: u16 = !ones_sum(&frame, &[14..34], usize::MAX);
+        frame[24..26].copy_from_slice(&ip.to_be_bytes());
+
+        frame.extend_from_slice(&5353u16.to_be_bytes());
+        frame.extend_from_slice(&5353u16.to_be_bytes());
+        frame.extend_from_slice(&(total - 20).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(payload);
+        let udp: u16 = (!udp_sum(&frame)).wrapping_sub(u16::from(broken));
+        frame[40..42].copy_from_slice(&udp.to_be_bytes());
+        frame
+    }
+
+    /// The one's complement sum of a UDP datagram and its pseudo-header,
+    /// checksum included, which is `0xFFFF` when the checksum is correct.
+    fn udp_sum(frame: &[u8]) -> u16 {
+        let len: u16 = (frame.len() - 34) as u16;
+        let mut pseudo: Vec<u8> = frame[26..34].to_vec();
+        pseudo.extend_from_slice(&[0, 17]);
+        pseudo.extend_from_slice(&len.to_be_bytes());
+        let pseudo: u16 = ones_sum(&pseudo, &[0..12], usize::MAX);
+        fold(u32::from(pseudo) + u32::from(ones_sum(frame, &[34..frame.len()], usize::MAX)))
+    }
+
+    fn pcap(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut out: Vec<u8> = vec![0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0];
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&2048u32.to_le_bytes());
+        out.extend_from_slice(&LINK_ETHERNET.to_le_bytes());
+        for frame in frames {
+            out.extend_from_slice(&[0; 8]);
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend_from_slice(frame);
+        }
+        out
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn remaps_report_and_capture_consistently() {
+        let real: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 20);
+        let scanner: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 2);
+        let mut host: Host = Host::new(IpAddr::V4(real)).with_mac(MAC);
+        host.hostname = Some("nas.corp.example.com".to_string());
+
+        let payload: &[u8] = b"\x03nas\x04corp\x07example\x03com\x00 at 192.168.1.20";
+        let mut capture: Vec<u8> = pcap(&[
+            udp_frame(real, scanner, payload, false),
+            udp_frame(scanner, real, b" NAS", true),
+        ]);
+        let mut anonymizer: Anonymizer = Anonymizer::new([3; 32]);
+        anonymizer.learn_host(&host);
+        anonymizer.learn_pcap(&capture).unwrap();
+        assert_eq!(anonymizer.pcap(&mut capture).unwrap(), 2);
+        anonymizer.host(&mut host);
+
+        let IpAddr::V4(fake) = host.primary_ip else {
+            panic!("address family changed");
+        };
+        assert_ne!(fake, real);
+        assert_eq!(fake.octets()[3], 20);
+        let fake_mac: MacAddr = host.mac.unwrap();
+        assert_eq!(fake_mac.octets()[..3], MAC.octets()[..3]);
+        let hostname: String = host.hostname.clone().unwrap();
+        assert_eq!(hostname.len(), "nas.corp.example.com".len());
+        assert!(hostname.ends_with(".com") && !hostname.contains("corp"));
+
+        let first: &[u8] = &capture[24 + 16..24 + 16 + 42 + payload.len()];
+        let second: &[u8] = &capture[24 + 16 + first.len() + 16..];
+        // The frames now belong to the host the report lists.
+        assert_eq!(first[..6], fake_mac.octets());
+        assert_eq!(first[26..30], fake.octets());
+        assert_eq!(second[30..34], fake.octets());
+        for leaked in [
+            &b"nas"[..],
+            b"corp",
+            b"example",
+            b"192.168.1.20",
+            &real.octets(),
+            &MAC.octets(),
+        ] {
+            assert!(!contains(first, leaked) && !contains(second, leaked));
+        }
+        // Only the headers are left of the datagrams.
+        assert!(
+            first[42..]
+                .iter()
+                .chain(&second[42..])
+                .all(|byte| *byte == 0)
+        );
+
+        // Correct checksums stay correct, broken ones stay broken.
+        assert_eq!(ones_sum(first, &[14..34], usize::MAX), 0xFFFF);
+        assert_eq!(udp_sum(first), 0xFFFF);
+        assert_eq!(udp_sum(second), 0xFFFE);
+    }
+
+    #[test]
+    fn keeps_only_the_ndp_options_scanners_read() {
+        let mut frame: Vec<u8> = Vec::new();
+        frame.extend_from_slice(&[0x33, 0x33, 0, 0, 0, 1]);
+        frame.extend_from_slice(&MAC.octets());
+        frame.extend_from_slice(&ETHERTYPE_IPV6.to_be_bytes());
+        frame.extend_from_slice(&[0x60, 0, 0, 0, 0, 40, 58, 255]);
+        frame.extend_from_slice(
+            &"fe80::211:32ff:feaa:bbcc"
+                .parse::<Ipv6Addr>()
+                .unwrap()
+                .octets(),
+        );
+        frame.extend_from_slice(&"ff02::1".parse::<Ipv6Addr>().unwrap().octets());
+        // A router advertisement with the source link-layer address and a
+        // DNS search list.
+        frame.extend_from_slice(&[134, 0, 0, 0, 64, 0, 0x07, 0x08, 0, 0, 0, 0, 0, 0, 0, 0]);
+        frame.extend_from_slice(&[1, 1]);
+        frame.extend_from_slice(&MAC.octets());
+        frame.extend_from_slice(&[31, 2, 0, 0, 0, 0, 0x07, 0x08]);
+        frame.extend_from_slice(b"\x04corp\x00\x00\x00");
+
+        let original: Vec<u8> = frame.clone();
+        scrub_payloads(LINK_ETHERNET, &mut frame);
+        assert_eq!(frame[..78], original[..78]);
+        assert_eq!(frame[78..80], [31, 2]);
+        assert!(frame[80..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn follows_the_structure_of_addresses() {
+        let anonymizer: Anonymizer = Anonymizer::new([5; 32]);
+        let eui64: Ipv6Addr = "fe80::211:32ff:feaa:bbcc".parse().unwrap();
+        let IpAddr::V6(fake) = anonymizer.ip(IpAddr::V6(eui64)) else {
+            panic!("address family changed");
+        };
+        assert_eq!(fake.segments()[0], 0xFE80);
+        assert_eq!(eui64_mac(fake), Some(anonymizer.mac(MAC)));
+
+        for kept in ["224.0.0.251", "255.255.255.255", "ff02::fb", "127.0.0.1"] {
+            let ip: IpAddr = kept.parse().unwrap();
+            assert_eq!(anonymizer.ip(ip), ip);
+        }
+        assert_eq!(anonymizer.mac(MacAddr::broadcast()), MacAddr::broadcast());
+
+        let real: IpAddr = "10.1.2.3".parse().unwrap();
+        assert_eq!(
+            anonymizer.text("nmap -sn 10.1.2.3/24 via 00:11:32:aa:bb:cc."),
+            format!(
+                "nmap -sn {}/24 via {}.",
+                anonymizer.ip(real),
+                anonymizer.mac(MAC)
+            )
+        );
+
+        assert!(pcap_frames(&[0x0A, 0x0D, 0x0D, 0x0A, 0, 0]).is_err());
+        let mut truncated: Vec<u8> = pcap(&[vec![0; 20]]);
+        truncated.pop();
+        assert!(pcap_frames(&truncated).is_err());
+    }
+}
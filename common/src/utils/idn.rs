@@ -0,0 +1,159 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Internationalized domain name (IDN) helpers.
+//!
+//! DNS and mDNS responders may hand back labels in their ASCII-compatible
+//! encoding (`xn--...`). This module decodes them back into Unicode
+//! (RFC 3492, Punycode) so hostnames can be shown the way their owners typed them.
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+const ACE_PREFIX: &str = "xn--";
+
+/// Converts every `xn--` label of a hostname into Unicode.
+///
+/// Labels that are not valid Punycode are left untouched, so the result is
+/// never less informative than the input.
+///
+/// # Examples
+/// ```
+/// use zond_common::utils::idn;
+///
+/// assert_eq!(idn::to_unicode("xn--mnchen-3ya.local"), "münchen.local");
+/// assert_eq!(idn::to_unicode("printer.lan"), "printer.lan");
+/// ```
+pub fn to_unicode(hostname: &str) -> String {
+    hostname
+        .split('.')
+        .map(|label| {
+            label
+                .get(..ACE_PREFIX.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(ACE_PREFIX))
+                .and_then(|_| decode_punycode(&label[ACE_PREFIX.len()..]))
+                .filter(|decoded| !decoded.is_empty())
+                .unwrap_or_else(|| label.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Decodes a single Punycode label (without the `xn--` prefix).
+///
+/// Returns `None` if the input is malformed or would overflow.
+pub fn decode_punycode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(pos) => (&input[..pos], &input[pos + 1..]),
+        None => ("", input),
+    };
+
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n: u32 = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias: u32 = INITIAL_BIAS;
+    let mut digits = extended.bytes().peekable();
+
+    while digits.peek().is_some() {
+        let old_i: u32 = i;
+        let mut weight: u32 = 1;
+        let mut k: u32 = BASE;
+
+        loop {
+            let digit: u32 = match digits.next()? {
+                byte @ b'a'..=b'z' => (byte - b'a') as u32,
+                byte @ b'A'..=b'Z' => (byte - b'A') as u32,
+                byte @ b'0'..=b'9' => (byte - b'0') as u32 + 26,
+                _ => return None,
+            };
+
+            i = i.checked_add(digit.checked_mul(weight)?)?;
+
+            let threshold: u32 = if k <= bias {
+                T_MIN
+            } else if k >= bias + T_MAX {
+                T_MAX
+            } else {
+                k - bias
+            };
+
+            if digit < threshold {
+                break;
+            }
+
+            weight = weight.checked_mul(BASE - threshold)?;
+            k += BASE;
+        }
+
+        let len: u32 = output.len() as u32 + 1;
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len)?;
+        i %= len;
+
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta: u32 = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+
+    let mut k: u32 = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (((BASE - T_MIN + 1) * delta) / (delta + SKEW))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_rfc_samples() {
+        assert_eq!(decode_punycode("bcher-kva").as_deref(), Some("bücher"));
+        assert_eq!(decode_punycode("mnchen-3ya").as_deref(), Some("münchen"));
+        assert_eq!(decode_punycode("wgv71a119e").as_deref(), Some("日本語"));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(decode_punycode("abc-!!"), None);
+        assert_eq!(decode_punycode("bcher-kv"), None);
+        assert_eq!(decode_punycode("99999999999999"), None);
+        assert_eq!(decode_punycode("ü-abc"), None);
+    }
+
+    #[test]
+    fn leaves_plain_and_broken_labels_alone() {
+        assert_eq!(to_unicode("router.fritz.box"), "router.fritz.box");
+        assert_eq!(to_unicode("xn--!!.local"), "xn--!!.local");
+        assert_eq!(to_unicode("xn-ü.xn--"), "xn-ü.xn--");
+        assert_eq!(to_unicode("XN--bcher-kva.example"), "bücher.example");
+    }
+}
@@ -4,15 +4,77 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+//! # Keyboard Controls
+//!
+//! While a scan runs, a background thread reads keys in raw mode and forwards
+//! the ones that mean something as [`Control`]s:
+//!
+//! | Key | Control |
+//! |---|---|
+//! | `q`, Ctrl-C | [`Control::Interrupt`] |
+//! | `p` | [`Control::TogglePause`] |
+//! | `v` | [`Control::CycleVerbosity`] |
+//! | `s` | [`Control::Status`] |
+
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    cursor::Show,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use std::{sync::mpsc, thread};
+use std::{
+    io::{self, IsTerminal},
+    sync::mpsc,
+    thread,
+};
+
+/// Leaves raw mode and shows the cursor again.
+///
+/// Safe to call at any time, e.g. when the process exits while the input
+/// listener thread is still blocked reading keys. The cursor escape is only
+/// written if stderr is a terminal, so redirected logs stay clean.
+pub fn restore_terminal() {
+    let _ = disable_raw_mode();
+    if io::stderr().is_terminal() {
+        let _ = execute!(io::stderr(), Show);
+    }
+}
+
+/// What a key press asks the running scan to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Stop and print what was found so far.
+    Interrupt,
+    /// Pause sending probes, or resume it.
+    TogglePause,
+    /// Step to the next log verbosity.
+    CycleVerbosity,
+    /// Print an interim status line.
+    Status,
+}
+
+impl Control {
+    /// Maps a key press to its control, ignoring releases and unbound keys.
+    pub fn from_key(key: &KeyEvent) -> Option<Self> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return (key.code == KeyCode::Char('c')).then_some(Self::Interrupt);
+        }
+        match key.code {
+            KeyCode::Char('q') => Some(Self::Interrupt),
+            KeyCode::Char('p') => Some(Self::TogglePause),
+            KeyCode::Char('v') => Some(Self::CycleVerbosity),
+            KeyCode::Char('s') => Some(Self::Status),
+            _ => None,
+        }
+    }
+}
 
 pub struct InputHandle {
-    rx: mpsc::Receiver<Event>,
-    tx: Option<mpsc::Sender<Event>>,
+    rx: mpsc::Receiver<Control>,
+    tx: Option<mpsc::Sender<Control>>,
 }
 
 impl Default for InputHandle {
@@ -27,20 +89,20 @@ impl InputHandle {
         Self { rx, tx: Some(tx) }
     }
 
+    /// Starts reading keys. The reader stops after [`Control::Interrupt`].
     pub fn start(&mut self) {
         if let Some(tx) = self.tx.take() {
             thread::spawn(move || {
                 enable_raw_mode().expect("failed to enable raw mode");
                 loop {
-                    if let Ok(Event::Key(key_event)) = event::read() {
-                        let is_q = key_event.code == KeyCode::Char('q');
-                        let is_ctrl_c = key_event.code == KeyCode::Char('c')
-                            && key_event.modifiers.contains(KeyModifiers::CONTROL);
-
-                        if (is_q || is_ctrl_c) && key_event.kind == KeyEventKind::Press {
-                            let _ = tx.send(Event::Key(key_event));
-                            break;
-                        }
+                    let Ok(Event::Key(key_event)) = event::read() else {
+                        continue;
+                    };
+                    let Some(control) = Control::from_key(&key_event) else {
+                        continue;
+                    };
+                    if tx.send(control).is_err() || control == Control::Interrupt {
+                        break;
                     }
                 }
                 let _ = disable_raw_mode();
@@ -48,13 +110,9 @@ impl InputHandle {
         }
     }
 
-    pub fn should_interrupt(&self) -> bool {
-        match self.rx.try_recv() {
-            Ok(Event::Key(event)) => {
-                event.code == KeyCode::Char('q') || event.code == KeyCode::Char('c')
-            }
-            _ => false,
-        }
+    /// Returns the next pending control without blocking.
+    pub fn next_control(&self) -> Option<Control> {
+        self.rx.try_recv().ok()
     }
 }
 
@@ -63,3 +121,55 @@ impl Drop for InputHandle {
         let _ = disable_raw_mode();
     }
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn keys_map_to_controls() {
+        let none: KeyModifiers = KeyModifiers::NONE;
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('q'), none)),
+            Some(Control::Interrupt)
+        );
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Control::Interrupt)
+        );
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('p'), none)),
+            Some(Control::TogglePause)
+        );
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('v'), none)),
+            Some(Control::CycleVerbosity)
+        );
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('s'), none)),
+            Some(Control::Status)
+        );
+
+        assert_eq!(Control::from_key(&key(KeyCode::Char('c'), none)), None);
+        assert_eq!(
+            Control::from_key(&key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            None
+        );
+        let mut release: KeyEvent = key(KeyCode::Char('q'), none);
+        release.kind = KeyEventKind::Release;
+        assert_eq!(Control::from_key(&release), None);
+    }
+}
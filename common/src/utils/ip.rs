@@ -38,6 +38,25 @@ pub fn is_private(ip_addr: &IpAddr) -> bool {
     }
 }
 
+/// Returns `true` for addresses outside every private, shared (CGNAT),
+/// loopback and link-local range, whose reverse zones live on third-party DNS
+/// servers.
+pub fn is_public(ip_addr: &IpAddr) -> bool {
+    match ip_addr {
+        IpAddr::V4(ipv4) => {
+            let is_shared: bool = ipv4.octets()[0] == 100 && (ipv4.octets()[1] & 0xC0) == 64;
+            !(ipv4.is_private()
+                || is_shared
+                || ipv4.is_loopback()
+                || ipv4.is_link_local()
+                || ipv4.is_unspecified()
+                || ipv4.is_broadcast()
+                || ipv4.is_multicast())
+        }
+        IpAddr::V6(ipv6) => is_global_unicast(ipv6),
+    }
+}
+
 pub fn reverse_address_to_ptr(ip_addr: &IpAddr) -> String {
     match ip_addr {
         IpAddr::V4(ipv4_addr) => {
@@ -65,7 +84,58 @@ pub fn reverse_address_to_ptr(ip_addr: &IpAddr) -> String {
     }
 }
 
+/// Returns the reverse zone an address most likely lives in.
+///
+/// IPv4 addresses map to their `/24` zone (`c.b.a.in-addr.arpa`) and IPv6
+/// addresses to their `/64` zone, which is where networks usually delegate
+/// reverse lookups.
+pub fn reverse_zone(ip_addr: &IpAddr) -> String {
+    let ptr: String = reverse_address_to_ptr(ip_addr);
+    let host_labels: usize = match ip_addr {
+        IpAddr::V4(_) => 1,
+        IpAddr::V6(_) => 16,
+    };
+
+    ptr.splitn(host_labels + 1, '.')
+        .last()
+        .unwrap_or_default()
+        .to_string()
+}
+
 pub fn get_gateway_addr(_ip_addr: &IpAddr) -> IpAddr {
     // Simplified stub as per original implementation
     std::net::IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 0, 1))
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_public_from_internal_addresses() {
+        for internal in [
+            "10.0.0.1",
+            "192.168.1.5",
+            "100.64.0.1",
+            "127.0.0.1",
+            "169.254.3.4",
+        ] {
+            assert!(!is_public(&internal.parse().unwrap()), "{internal}");
+        }
+        for internal in ["fe80::1", "fd12:3456::1", "::1"] {
+            assert!(!is_public(&internal.parse().unwrap()), "{internal}");
+        }
+        for public in ["8.8.8.8", "100.128.0.1", "2a00:1450::1"] {
+            assert!(is_public(&public.parse().unwrap()), "{public}");
+        }
+    }
+}
@@ -8,18 +8,154 @@
 //!
 //! This also includes things like **Organizationally unique identifier (OUI)** database
 //! initialization and handling, thus being able to link a vendor (e.g Cisco) to a MAC address.
+//!
+//! The database baked into the binary ages quickly, so a newer IEEE registry can be
+//! installed into the data directory with [`install_oui_csv`]. It is stored next to
+//! its SHA-256 checksum and only used while the two still match.
 
 use mac_oui::Oui;
 use pnet::util::MacAddr;
-use std::sync::OnceLock;
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use thiserror::Error;
+
+use crate::utils::paths;
+use crate::{debug, warn};
+
+/// The OUI database, and the installed registry it was loaded from.
+static OUI_DB: OnceLock<(Oui, Option<PathBuf>)> = OnceLock::new();
+
+/// File name of the installed IEEE OUI registry inside the data directory.
+pub const OUI_CSV_FILE: &str = "oui.csv";
+
+/// Header row every IEEE MA-L registry export starts with.
+const CSV_HEADER: &[u8] = b"Registry,Assignment,Organization Name";
+
+#[derive(Debug, Error)]
+pub enum OuiUpdateError {
+    /// None of the data directory candidates is set.
+    #[error("no data directory, set ZOND_DATA_DIR")]
+    NoDataDir,
+
+    /// The download does not hash to the checksum the user expected.
+    #[error("checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
 
-static OUI_DB: OnceLock<Oui> = OnceLock::new();
+    /// The download is not a registry the OUI database can be built from.
+    #[error("not an IEEE OUI registry: {0}")]
+    InvalidRegistry(String),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
 
 /// Retrieves or initializes the **Organizationally unique identifier** database.
 ///
-/// Used for linking a vendor to a MAC address (LAN)
+/// Used for linking a vendor to a MAC address (LAN). Prefers a registry installed
+/// with [`install_oui_csv`] and falls back to the built-in one.
 fn get_oui_db() -> &'static Oui {
-    OUI_DB.get_or_init(|| Oui::default().expect("failed to load OUI database"))
+    &oui_db().0
+}
+
+fn oui_db() -> &'static (Oui, Option<PathBuf>) {
+    OUI_DB.get_or_init(|| match load_installed_db() {
+        Some((db, path)) => (db, Some(path)),
+        None => (Oui::default().expect("failed to load OUI database"), None),
+    })
+}
+
+/// Returns the installed registry vendor lookups use, or `None` while they
+/// use the built-in database.
+pub fn installed_oui_db() -> Option<&'static Path> {
+    oui_db().1.as_deref()
+}
+
+/// Loads the installed registry, if there is one and it matches its checksum.
+fn load_installed_db() -> Option<(Oui, PathBuf)> {
+    let path: PathBuf = paths::data_dir()?.join(OUI_CSV_FILE);
+    let data: Vec<u8> = fs::read(&path).ok()?;
+
+    let expected: Option<String> = fs::read_to_string(checksum_path(&path)).ok();
+    if expected.as_deref().map(str::trim) != Some(sha256_hex(&data).as_str()) {
+        warn!(
+            "{} does not match its checksum, using the built-in OUI database",
+            path.display()
+        );
+        return None;
+    }
+
+    match Oui::from_csv_file(&path) {
+        Ok(db) => {
+            debug!("Loaded OUI database from {}", path.display());
+            Some((db, path))
+        }
+        Err(e) => {
+            warn!(
+                "Could not load {}, using the built-in OUI database: {e:?}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+/// Verifies a downloaded IEEE OUI registry (CSV) and installs it into the data
+/// directory, where vendor lookups pick it up from the next run on.
+///
+/// If `expected_sha256` is given, the data must hash to it (hex, any case).
+///
+/// # Errors
+///
+/// Returns an [`OuiUpdateError`] if the checksum does not match, the data is not a
+/// loadable registry, or it cannot be written.
+pub fn install_oui_csv(
+    data: &[u8],
+    expected_sha256: Option<&str>,
+) -> Result<PathBuf, OuiUpdateError> {
+    let actual: String = sha256_hex(data);
+    if let Some(expected) = expected_sha256
+        && !expected.trim().eq_ignore_ascii_case(&actual)
+    {
+        return Err(OuiUpdateError::ChecksumMismatch {
+            expected: expected.trim().to_lowercase(),
+            actual,
+        });
+    }
+
+    let body: &[u8] = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    if !body.starts_with(CSV_HEADER) {
+        return Err(OuiUpdateError::InvalidRegistry(
+            "missing CSV header".to_string(),
+        ));
+    }
+
+    let dir: PathBuf = paths::data_dir().ok_or(OuiUpdateError::NoDataDir)?;
+    fs::create_dir_all(&dir)?;
+    let path: PathBuf = dir.join(OUI_CSV_FILE);
+
+    let tmp: PathBuf = path.with_extension("csv.tmp");
+    fs::write(&tmp, data)?;
+    if let Err(e) = Oui::from_csv_file(&tmp) {
+        let _ = fs::remove_file(&tmp);
+        return Err(OuiUpdateError::InvalidRegistry(format!("{e:?}")));
+    }
+
+    fs::write(checksum_path(&path), format!("{actual}\n"))?;
+    fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+/// Lowercase hex SHA-256 digest of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn checksum_path(csv: &Path) -> PathBuf {
+    csv.with_extension("csv.sha256")
 }
 
 /// Identify the vendor of a MAC address.
@@ -32,6 +168,19 @@ pub fn get_vendor(mac: MacAddr) -> Option<String> {
     }
 }
 
+/// Returns a random unicast MAC address from the locally administered range,
+/// which no vendor can be assigned.
+pub fn random_locally_administered() -> MacAddr {
+    let mut octets: [u8; 6] = rand::random();
+    octets[0] = (octets[0] | 0x02) & !0x01;
+    MacAddr::from(octets)
+}
+
+/// Returns `true` if `mac` has the locally administered bit set.
+pub fn is_locally_administered(mac: MacAddr) -> bool {
+    mac.0 & 0x02 != 0
+}
+
 // ╔════════════════════════════════════════════╗
 // ║ ████████╗███████╗███████╗████████╗███████╗ ║
 // ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
@@ -76,6 +225,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sha256_matches_known_digest() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn install_rejects_unverified_registry() {
+        let csv: &[u8] = b"Registry,Assignment,Organization Name,Organization Address\n";
+
+        let mismatch = install_oui_csv(csv, Some("00"));
+        assert!(matches!(
+            mismatch,
+            Err(OuiUpdateError::ChecksumMismatch { .. })
+        ));
+
+        let html = install_oui_csv(b"<html>Access denied</html>", None);
+        assert!(matches!(html, Err(OuiUpdateError::InvalidRegistry(_))));
+    }
+
+    #[test]
+    fn random_macs_are_local_unicast() {
+        for _ in 0..64 {
+            let mac: MacAddr = random_locally_administered();
+            assert!(is_locally_administered(mac), "{mac}");
+            assert_eq!(mac.0 & 0x01, 0, "{mac} is multicast");
+        }
+        assert!(!is_locally_administered(MacAddr::new(
+            0x00, 0x00, 0x0C, 0x01, 0x02, 0x03
+        )));
+    }
+
     #[test]
     fn unknown_vendor_lookup() {
         // This is a locally administered address (no vendors linked to it)
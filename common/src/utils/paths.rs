@@ -0,0 +1,257 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Locations of the files zond keeps between runs and while it runs, and how
+//! files are opened where other users could get at them.
+
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use is_root::is_root;
+
+/// Returns the directory for persistent data such as previous scan results.
+///
+/// Resolution order:
+/// 1. `$ZOND_DATA_DIR`
+/// 2. `$XDG_DATA_HOME/zond`
+/// 3. `$HOME/.local/share/zond`
+/// 4. `%LOCALAPPDATA%\zond`
+///
+/// The directory is not created; returns `None` if no candidate is set.
+pub fn data_dir() -> Option<PathBuf> {
+    resolve_data_dir(|key| env::var_os(key).map(PathBuf::from))
+}
+
+fn resolve_data_dir(var: impl Fn(&str) -> Option<PathBuf>) -> Option<PathBuf> {
+    let non_empty = |key: &str| var(key).filter(|path| !path.as_os_str().is_empty());
+
+    non_empty("ZOND_DATA_DIR")
+        .or_else(|| non_empty("XDG_DATA_HOME").map(|dir| dir.join("zond")))
+        .or_else(|| non_empty("HOME").map(|home| home.join(".local/share/zond")))
+        .or_else(|| non_empty("LOCALAPPDATA").map(|dir| dir.join("zond")))
+}
+
+/// Returns the directory for files that only matter while zond runs, such as
+/// the scan lock.
+///
+/// Resolution order:
+/// 1. `$ZOND_RUNTIME_DIR`
+/// 2. `/run/zond` for root on Unix, as `sudo` drops `$XDG_RUNTIME_DIR`
+/// 3. `$XDG_RUNTIME_DIR/zond`
+/// 4. `zond` in the temporary directory of the system
+///
+/// The directory is not created. The temporary directory is shared with the
+/// other users, so files go in through [`open_private`] or [`create_private`],
+/// which check that the directory is their own.
+pub fn runtime_dir() -> PathBuf {
+    resolve_runtime_dir(
+        |key| env::var_os(key).map(PathBuf::from),
+        cfg!(unix) && is_root(),
+    )
+}
+
+fn resolve_runtime_dir(var: impl Fn(&str) -> Option<PathBuf>, root: bool) -> PathBuf {
+    let non_empty = |key: &str| var(key).filter(|path| !path.as_os_str().is_empty());
+
+    non_empty("ZOND_RUNTIME_DIR")
+        .or_else(|| root.then(|| PathBuf::from("/run/zond")))
+        .or_else(|| non_empty("XDG_RUNTIME_DIR").map(|dir| dir.join("zond")))
+        .unwrap_or_else(|| env::temp_dir().join("zond"))
+}
+
+/// Returns the directory for scan state that outgrew its memory limit.
+///
+/// Resolution order:
+/// 1. `$ZOND_SPILL_DIR`
+/// 2. `spill` in the [data directory](data_dir)
+/// 3. `zond-spill` in the temporary directory of the system
+///
+/// The runtime directory is no candidate, as it usually lives in memory. The
+/// directory is not created, and shared with other users in the last case, so
+/// files go in through [`create_private`].
+pub fn spill_dir() -> PathBuf {
+    resolve_spill_dir(|key| env::var_os(key).map(PathBuf::from))
+}
+
+fn resolve_spill_dir(var: impl Fn(&str) -> Option<PathBuf>) -> PathBuf {
+    var("ZOND_SPILL_DIR")
+        .filter(|path| !path.as_os_str().is_empty())
+        .or_else(|| resolve_data_dir(&var).map(|dir| dir.join("spill")))
+        .unwrap_or_else(|| env::temp_dir().join("zond-spill"))
+}
+
+/// Opens or creates `path` for reading and writing, readable by this user
+/// only. See [`ensure_private_dir`] for its directory.
+///
+/// # Errors
+///
+/// Returns an error if the directory is not private or the file cannot be
+/// opened, e.g. because it is a symbolic link.
+pub fn open_private(path: &Path) -> anyhow::Result<File> {
+    open_in_private_dir(path, OpenOptions::new().create(true).truncate(false))
+}
+
+/// Creates `path`, which must not exist yet, for reading and writing,
+/// readable by this user only. See [`ensure_private_dir`] for its directory.
+///
+/// # Errors
+///
+/// Returns an error if the directory is not private or the file exists.
+pub fn create_private(path: &Path) -> anyhow::Result<File> {
+    open_in_private_dir(path, OpenOptions::new().create_new(true))
+}
+
+fn open_in_private_dir(path: &Path, options: &mut OpenOptions) -> anyhow::Result<File> {
+    let dir: &Path = path.parent().context("the file has no directory")?;
+    ensure_private_dir(dir)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600).custom_flags(libc::O_NOFOLLOW);
+    }
+    options
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))
+}
+
+/// Creates `dir` with mode 0700 and refuses it if it belongs to someone else
+/// or is open to others.
+///
+/// Another user could have created the directory first, to read what goes in
+/// or swap a file for a link to one of ours.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created or is not private.
+#[cfg(unix)]
+pub fn ensure_private_dir(dir: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let meta: fs::Metadata = fs::symlink_metadata(dir)
+        .with_context(|| format!("failed to inspect {}", dir.display()))?;
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let euid: u32 = unsafe { libc::geteuid() };
+    anyhow::ensure!(
+        meta.is_dir() && meta.uid() == euid && meta.mode() & 0o077 == 0,
+        "{} is not a directory of this user with mode 0700",
+        dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn ensure_private_dir(dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_dir_prefers_explicit_override() {
+        let vars = |key: &str| match key {
+            "ZOND_DATA_DIR" => Some(PathBuf::from("/srv/zond")),
+            "XDG_DATA_HOME" => Some(PathBuf::from("")),
+            "HOME" => Some(PathBuf::from("/home/op")),
+            _ => None,
+        };
+        assert_eq!(resolve_data_dir(vars), Some(PathBuf::from("/srv/zond")));
+
+        let vars = |key: &str| match key {
+            "XDG_DATA_HOME" => Some(PathBuf::from("")),
+            "HOME" => Some(PathBuf::from("/home/op")),
+            _ => None,
+        };
+        assert_eq!(
+            resolve_data_dir(vars),
+            Some(PathBuf::from("/home/op/.local/share/zond"))
+        );
+    }
+
+    #[test]
+    fn runtime_dir_falls_back_to_temp() {
+        let vars = |key: &str| match key {
+            "XDG_RUNTIME_DIR" => Some(PathBuf::from("/run/user/1000")),
+            _ => None,
+        };
+        assert_eq!(
+            resolve_runtime_dir(vars, false),
+            PathBuf::from("/run/user/1000/zond")
+        );
+        assert_eq!(
+            resolve_runtime_dir(|_| None, false),
+            env::temp_dir().join("zond")
+        );
+        assert_eq!(
+            resolve_runtime_dir(|_| None, true),
+            PathBuf::from("/run/zond")
+        );
+    }
+
+    #[test]
+    fn spill_dir_stays_off_the_runtime_dir() {
+        let vars = |key: &str| match key {
+            "XDG_RUNTIME_DIR" => Some(PathBuf::from("/run/user/1000")),
+            "HOME" => Some(PathBuf::from("/home/op")),
+            _ => None,
+        };
+        assert_eq!(
+            resolve_spill_dir(vars),
+            PathBuf::from("/home/op/.local/share/zond/spill")
+        );
+        assert_eq!(
+            resolve_spill_dir(|key| (key == "ZOND_SPILL_DIR").then(|| PathBuf::from("/mnt/usb"))),
+            PathBuf::from("/mnt/usb")
+        );
+        assert_eq!(
+            resolve_spill_dir(|_| None),
+            env::temp_dir().join("zond-spill")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_refuse_shared_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir: PathBuf =
+            env::temp_dir().join(format!("zond-private-test-{}", std::process::id()));
+        let path: PathBuf = dir.join("state");
+        let file: File = create_private(&path).unwrap();
+        assert_eq!(file.metadata().unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(create_private(&path).is_err());
+        assert!(open_private(&path).is_ok());
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(open_private(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_dir(&dir).unwrap();
+    }
+}
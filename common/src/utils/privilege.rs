@@ -0,0 +1,130 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Detection of the privileges raw packet I/O needs.
+//!
+//! Root always qualifies. On Linux a binary granted `CAP_NET_RAW` as a file
+//! capability (`setcap cap_net_raw,cap_net_admin+ep`) may open raw and packet
+//! sockets as an ordinary user, so the effective capability set is checked too.
+//!
+//! With `--elevate`, the privileged helper `zond-helper` opens the sockets and
+//! hands them over, see [`use_helper`], while zond keeps running as the user.
+
+#[cfg(target_os = "linux")]
+use std::{
+    io::{self, Write},
+    os::{fd::OwnedFd, unix::net::UnixStream},
+    sync::{Mutex, MutexGuard, OnceLock, PoisonError},
+};
+
+use is_root::is_root;
+
+/// The sockets zond can ask the privileged helper for.
+pub use zond_helper::Request as SocketRequest;
+
+/// `CAP_NET_RAW` from `<linux/capability.h>`.
+#[cfg(any(target_os = "linux", test))]
+const CAP_NET_RAW: u32 = 13;
+
+/// Returns `true` if the process may open raw and packet sockets.
+pub fn has_raw_socket_access() -> bool {
+    is_root() || has_net_raw_capability() || uses_helper()
+}
+
+/// Returns `true` if raw sockets come from the privileged helper, see
+/// [`use_helper`].
+#[cfg(target_os = "linux")]
+pub fn uses_helper() -> bool {
+    HELPER.get().is_some()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn uses_helper() -> bool {
+    false
+}
+
+/// Takes `stream`, connected to a privileged helper that was just started,
+/// as the source of raw sockets from now on. Blocks until the helper runs,
+/// which may take a password prompt.
+///
+/// # Errors
+///
+/// Returns an error if the helper exits without answering, e.g. because the
+/// user was not authorized.
+#[cfg(target_os = "linux")]
+pub fn use_helper(stream: UnixStream) -> io::Result<()> {
+    let mut writer: &UnixStream = &stream;
+    writer.write_all(&SocketRequest::Hello.encode())?;
+    zond_helper::recv_reply(&stream)?;
+    let _ = HELPER.set(Mutex::new(stream));
+    Ok(())
+}
+
+/// Asks the privileged helper for the socket `request` describes. Returns
+/// `None` if there is no helper, and the socket should be opened directly.
+///
+/// # Errors
+///
+/// Returns the error the helper met opening the socket, or the error of
+/// talking to it.
+#[cfg(target_os = "linux")]
+pub fn helper_socket(request: SocketRequest) -> io::Result<Option<OwnedFd>> {
+    let Some(helper) = HELPER.get() else {
+        return Ok(None);
+    };
+    let stream: MutexGuard<'_, UnixStream> = helper.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut writer: &UnixStream = &stream;
+    writer.write_all(&request.encode())?;
+    match zond_helper::recv_reply(&stream)? {
+        Some(socket) => Ok(Some(socket)),
+        None => Err(io::Error::other("the privileged helper sent no socket")),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn has_net_raw_capability() -> bool {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| effective_capabilities(&status))
+        .is_some_and(|caps| caps & (1 << CAP_NET_RAW) != 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn has_net_raw_capability() -> bool {
+    false
+}
+
+/// Reads the `CapEff` mask from the contents of `/proc/<pid>/status`.
+#[cfg(any(target_os = "linux", test))]
+fn effective_capabilities(status: &str) -> Option<u64> {
+    let mask: &str = status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))?;
+    u64::from_str_radix(mask.trim(), 16).ok()
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_effective_capabilities() {
+        let status: &str = "Name:\tzond\nCapInh:\t0000000000000000\nCapPrm:\t0000000000003000\nCapEff:\t0000000000003000\n";
+        let caps: u64 = effective_capabilities(status).unwrap();
+        assert_ne!(caps & (1 << CAP_NET_RAW), 0);
+
+        assert_eq!(effective_capabilities("Name:\tzond\n"), None);
+    }
+}
@@ -38,6 +38,12 @@ impl ScanTimer {
         self.last_seen = Instant::now();
     }
 
+    /// Moves both deadlines back by `by`, e.g. the time a scan was paused.
+    pub fn extend(&mut self, by: Duration) {
+        self.hard_deadline += by;
+        self.min_runtime += by;
+    }
+
     /// Calculates how long to wait for the next event.
     /// Returns a fallback (e.g., 100ms) if the calculation is negative.
     pub fn next_wait(&self) -> Duration {
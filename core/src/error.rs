@@ -0,0 +1,93 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Core Error Types
+//!
+//! The public entry points of `zond_core` report failures through [`ZondError`]
+//! so that callers can react to the category of a failure (missing privileges,
+//! a vanished interface, ...) without matching on message strings.
+//!
+//! Internal helpers are free to keep using `anyhow`; errors are only mapped into
+//! a [`ZondError`] at the boundary of the public API.
+
+use std::io;
+
+use thiserror::Error;
+
+/// Errors returned by the public scanning and capture functions of `zond_core`.
+#[derive(Debug, Error)]
+pub enum ZondError {
+    /// The operating system refused access to a raw socket or capture device.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    /// The requested network interface does not exist or has no usable address.
+    #[error("Network interface not found: {0}")]
+    InterfaceNotFound(String),
+
+    /// A packet channel could not be opened or stopped working unexpectedly.
+    #[error("Channel error: {0}")]
+    ChannelError(String),
+
+    /// An operation did not complete within its allotted time.
+    #[error("Operation timed out: {0}")]
+    Timeout(String),
+
+    /// The scan targets were empty or could not be turned into probes.
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+}
+
+impl ZondError {
+    /// Classifies an I/O error raised while opening a channel on `context`.
+    pub(crate) fn from_io(err: io::Error, context: &str) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => {
+                ZondError::PermissionDenied(format!("{context}: {err}"))
+            }
+            io::ErrorKind::NotFound | io::ErrorKind::AddrNotAvailable => {
+                ZondError::InterfaceNotFound(format!("{context}: {err}"))
+            }
+            io::ErrorKind::TimedOut => ZondError::Timeout(format!("{context}: {err}")),
+            _ => ZondError::ChannelError(format!("{context}: {err}")),
+        }
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_errors_map_to_categories() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            ZondError::from_io(denied, "eth0"),
+            ZondError::PermissionDenied(_)
+        ));
+
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert!(matches!(
+            ZondError::from_io(missing, "eth9"),
+            ZondError::InterfaceNotFound(_)
+        ));
+
+        let other = io::Error::other("boom");
+        assert!(matches!(
+            ZondError::from_io(other, "eth0"),
+            ZondError::ChannelError(_)
+        ));
+    }
+}
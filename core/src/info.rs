@@ -12,23 +12,33 @@
 //! configuration, useful for debugging or self-awareness context.
 
 use pnet::datalink::NetworkInterface;
-use zond_common::models::localhost::{FirewallStatus, IpServiceGroup};
+use zond_common::models::localhost::{FirewallRules, FirewallStatus, IpServiceGroup, SelfTest};
 
 /// Retrieves a comprehensive snapshot of the local system's network state.
 pub fn get_system_info() -> anyhow::Result<SystemInfo> {
     let services = crate::system::get_local_services()?;
     let firewall = crate::system::get_firewall_status()?;
+    // Missing rules only cost the reachability column, not the whole report.
+    let firewall_rules = crate::system::get_firewall_rules().unwrap_or(None);
     let interfaces = crate::system::get_network_interfaces()?;
 
     Ok(SystemInfo {
         services,
         firewall,
+        firewall_rules,
         interfaces,
     })
 }
 
+/// Checks which of the local TCP `services` the LAN can actually connect to.
+pub fn self_test(services: &[IpServiceGroup]) -> anyhow::Result<SelfTest> {
+    crate::system::self_test(services)
+}
+
 pub struct SystemInfo {
     pub services: Vec<IpServiceGroup>,
     pub firewall: FirewallStatus,
+    /// Inbound rules of the native firewall, where they can be read.
+    pub firewall_rules: Option<FirewallRules>,
     pub interfaces: Vec<NetworkInterface>,
 }
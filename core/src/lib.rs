@@ -4,6 +4,7 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
+pub mod error;
 pub mod info;
 pub mod network;
 pub mod scanner;
@@ -5,60 +5,235 @@
 // https://mozilla.org/MPL/2.0/.
 
 // use crate::adapters::outbound::terminal::print;
-use anyhow::{self, Context};
 use pnet::datalink;
 use pnet::datalink::{Channel, Config, DataLinkReceiver, DataLinkSender, NetworkInterface};
-use std::thread;
-use std::time::Duration;
+use std::io;
+#[cfg(target_os = "linux")]
+use std::os::fd::IntoRawFd;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+#[cfg(target_os = "linux")]
+use zond_common::utils::privilege::{self, SocketRequest};
+
+use crate::error::ZondError;
+use crate::network::sandbox;
+use crate::network::supervisor::{self, CaptureFailure, RecvErrors};
+use crate::network::timestamp::ReceiveClock;
 
 const READ_TIMEOUT_MS: u64 = 50;
 
 pub struct EthernetHandle {
     pub tx: Box<dyn DataLinkSender>,
-    pub rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    /// Captured frames and when they were received. Closes if the capture
+    /// stops, see [`EthernetHandle::recover`].
+    pub rx: mpsc::UnboundedReceiver<(Vec<u8>, Instant)>,
+    intf: NetworkInterface,
+    promiscuous: bool,
+    failure: CaptureFailure,
+    reopened: bool,
+}
+
+impl EthernetHandle {
+    /// Reopens the capture after `rx` closed, once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZondError::ChannelError`] with the reason the capture
+    /// stopped if it cannot be reopened or was reopened before.
+    pub fn recover(&mut self) -> Result<(), ZondError> {
+        let fresh: EthernetHandle =
+            supervisor::reopen(&self.intf.name, &self.failure, self.reopened, || {
+                start_capture_with(&self.intf, self.promiscuous)
+            })?;
+        *self = EthernetHandle {
+            reopened: true,
+            ..fresh
+        };
+        Ok(())
+    }
 }
 
-pub fn start_capture(intf: &NetworkInterface) -> anyhow::Result<EthernetHandle> {
+pub fn start_capture(intf: &NetworkInterface) -> Result<EthernetHandle, ZondError> {
+    start_capture_with(intf, true)
+}
+
+/// Like [`start_capture`], but only puts the interface into promiscuous mode
+/// if `promiscuous` is set. Otherwise only broadcasts, multicasts and frames
+/// addressed to this machine arrive.
+pub fn start_capture_with(
+    intf: &NetworkInterface,
+    promiscuous: bool,
+) -> Result<EthernetHandle, ZondError> {
     let cfg = Config {
         read_timeout: Some(Duration::from_millis(READ_TIMEOUT_MS)),
+        promiscuous,
         ..Default::default()
     };
     let (tx, rx_socket) = open_eth_channel(intf, datalink::channel, cfg)?;
     let (queue_tx, queue_rx) = mpsc::unbounded_channel();
-    spawn_eth_listener(queue_tx, rx_socket);
-    Ok(EthernetHandle { tx, rx: queue_rx })
+    let failure: CaptureFailure = CaptureFailure::default();
+    spawn_eth_listener(&intf.name, queue_tx, rx_socket, failure.clone())
+        .map_err(|e| ZondError::from_io(e, &intf.name))?;
+    Ok(EthernetHandle {
+        tx,
+        rx: queue_rx,
+        intf: intf.clone(),
+        promiscuous,
+        failure,
+        reopened: false,
+    })
 }
 
+/// Opens a data link channel on `intf` through `channel_opener`, on a packet
+/// socket from the privileged helper if zond runs with one.
 pub fn open_eth_channel<F>(
     intf: &NetworkInterface,
     channel_opener: F,
     cfg: Config,
-) -> anyhow::Result<(Box<dyn DataLinkSender>, Box<dyn DataLinkReceiver>)>
+) -> Result<(Box<dyn DataLinkSender>, Box<dyn DataLinkReceiver>), ZondError>
 where
     F: FnOnce(&NetworkInterface, Config) -> std::io::Result<datalink::Channel>,
 {
-    let ch: Channel =
-        channel_opener(intf, cfg).with_context(|| format!("opening on {}", intf.name))?;
+    #[cfg(target_os = "linux")]
+    let cfg: Config = match privilege::helper_socket(SocketRequest::Packet)
+        .map_err(|e| ZondError::from_io(e, &intf.name))?
+    {
+        // The channel owns the socket from here on.
+        Some(socket) => Config {
+            socket_fd: Some(socket.into_raw_fd()),
+            ..cfg
+        },
+        None => cfg,
+    };
+    let ch: Channel = channel_opener(intf, cfg).map_err(|e| ZondError::from_io(e, &intf.name))?;
 
     match ch {
         Channel::Ethernet(tx, rx) => Ok((tx, rx)),
-        _ => anyhow::bail!("non-ethernet channel for {}", intf.name),
+        _ => Err(ZondError::ChannelError(format!(
+            "non-ethernet channel for {}",
+            intf.name
+        ))),
+    }
+}
+
+/// How a failed `send_to` on a data link channel should be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendFailure {
+    /// The kernel is temporarily out of buffers; the frame may be retried.
+    Transient,
+    /// The interface vanished or went down; further sends are pointless.
+    LinkDown,
+    /// Any other error. The frame is dropped but the scan can go on.
+    Other,
+}
+
+/// Sorts a send error into one of the [`SendFailure`] categories.
+pub fn classify_send_error(err: &io::Error) -> SendFailure {
+    match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted | io::ErrorKind::OutOfMemory => {
+            return SendFailure::Transient;
+        }
+        io::ErrorKind::NetworkDown | io::ErrorKind::NotFound => return SendFailure::LinkDown,
+        _ => {}
+    }
+
+    err.raw_os_error()
+        .map_or(SendFailure::Other, classify_os_error)
+}
+
+/// Sorts the error codes the standard library does not map to an
+/// [`io::ErrorKind`].
+#[cfg(unix)]
+fn classify_os_error(code: i32) -> SendFailure {
+    match code {
+        libc::ENOBUFS => SendFailure::Transient,
+        // The device behind the socket disappeared.
+        libc::ENXIO | libc::ENODEV => SendFailure::LinkDown,
+        _ => SendFailure::Other,
     }
 }
 
+#[cfg(not(unix))]
+fn classify_os_error(_code: i32) -> SendFailure {
+    SendFailure::Other
+}
+
+/// Forwards every frame of `eth_rx` to `eth_tx`, stamped on arrival, on a
+/// thread supervised as described in [`supervisor`].
+///
+/// pnet keeps the data link socket to itself, so frames are stamped by the
+/// capture thread rather than the kernel, see
+/// [`timestamp`](crate::network::timestamp).
 pub fn spawn_eth_listener(
-    eth_tx: mpsc::UnboundedSender<Vec<u8>>,
+    intf_name: &str,
+    eth_tx: mpsc::UnboundedSender<(Vec<u8>, Instant)>,
     eth_rx: Box<dyn DataLinkReceiver>,
-) {
-    thread::spawn(move || {
-        let mut eth_iter = eth_rx;
-        loop {
-            if let Ok(frame) = eth_iter.next()
-                && eth_tx.send(frame.to_vec()).is_err()
-            {
-                break;
+    failure: CaptureFailure,
+) -> io::Result<()> {
+    supervisor::spawn(
+        format!("capture-{intf_name}"),
+        eth_tx,
+        failure,
+        move |eth_tx| {
+            sandbox::confine_capture_thread();
+            let clock: ReceiveClock = ReceiveClock::userspace();
+            let mut eth_iter = eth_rx;
+            let mut errors: RecvErrors = RecvErrors::default();
+            loop {
+                match eth_iter.next() {
+                    Ok(frame) => {
+                        errors.reset();
+                        if eth_tx.send((frame.to_vec(), clock.stamp())).is_err() {
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => errors.check(e)?,
+                }
             }
-        }
-    });
+        },
+    )
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_send_errors_by_kind() {
+        let classify = |kind: io::ErrorKind| classify_send_error(&kind.into());
+        assert_eq!(classify(io::ErrorKind::WouldBlock), SendFailure::Transient);
+        assert_eq!(classify(io::ErrorKind::Interrupted), SendFailure::Transient);
+        assert_eq!(classify(io::ErrorKind::NetworkDown), SendFailure::LinkDown);
+        assert_eq!(classify(io::ErrorKind::NotFound), SendFailure::LinkDown);
+        assert_eq!(
+            classify(io::ErrorKind::PermissionDenied),
+            SendFailure::Other
+        );
+        assert_eq!(
+            classify_send_error(&io::Error::other("send not supported by channel")),
+            SendFailure::Other
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sorts_send_errors_by_code() {
+        let classify = |code: i32| classify_send_error(&io::Error::from_raw_os_error(code));
+        assert_eq!(classify(libc::ENOBUFS), SendFailure::Transient);
+        assert_eq!(classify(libc::EAGAIN), SendFailure::Transient);
+        assert_eq!(classify(libc::ENXIO), SendFailure::LinkDown);
+        assert_eq!(classify(libc::ENODEV), SendFailure::LinkDown);
+        assert_eq!(classify(libc::ENETDOWN), SendFailure::LinkDown);
+        assert_eq!(classify(libc::EPERM), SendFailure::Other);
+        assert_eq!(classify(libc::EMSGSIZE), SendFailure::Other);
+    }
 }
@@ -5,5 +5,8 @@
 // https://mozilla.org/MPL/2.0/.
 
 pub mod channel;
+pub mod sandbox;
+pub mod supervisor;
+pub mod timestamp;
 pub mod transport;
 pub mod utils;
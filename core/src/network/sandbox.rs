@@ -0,0 +1,201 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Confinement of the packet capture threads and of long-running commands.
+//!
+//! Capture threads parse whatever arrives on the wire, so they are the part of
+//! zond most exposed to hostile input. Once a thread owns its already opened
+//! socket it needs nothing but `recv`, the receive timestamps of the socket and
+//! the in-process queue, so on Linux it drops every other ability with a
+//! Landlock ruleset:
+//!
+//! * no filesystem access at all, which also rules out executing programs,
+//! * no binding or connecting TCP sockets.
+//!
+//! Landlock restricts only the calling thread and the threads it spawns, so the
+//! rest of the process (history, reports) keeps working normally. Kernels
+//! without Landlock (before 5.13) run the threads unconfined; the outcome is
+//! logged once per run at debug level.
+//!
+//! Commands that run for a long time and answer the network, like
+//! `zond serve`, confine the whole process with [`confine_process`] once their
+//! sockets and output files are open. A seccomp filter synchronised to every
+//! thread forbids executing programs and opening files for writing; reading
+//! files and writing to descriptors that are already open keep working.
+
+use std::sync::Once;
+
+use zond_common::debug;
+
+static REPORTED: Once = Once::new();
+
+/// Restricts the calling thread as far as the kernel supports.
+///
+/// Must only be called on threads that never touch the filesystem or open
+/// sockets again. Failures are not fatal: the thread keeps running unconfined.
+#[cfg(target_os = "linux")]
+pub fn confine_capture_thread() {
+    use landlock::{
+        ABI, Access, AccessFs, AccessNet, RestrictionStatus, Ruleset, RulesetAttr, RulesetError,
+        RulesetStatus,
+    };
+
+    // Newer rights are dropped silently on kernels that do not know them.
+    let abi: ABI = ABI::V4;
+    let status: Result<RestrictionStatus, RulesetError> = Ruleset::default()
+        .handle_access(AccessFs::from_all(abi))
+        .and_then(|ruleset| ruleset.handle_access(AccessNet::from_all(abi)))
+        .and_then(|ruleset| ruleset.create())
+        .and_then(|ruleset| ruleset.restrict_self());
+
+    REPORTED.call_once(|| match status {
+        Ok(status) => match status.ruleset {
+            RulesetStatus::FullyEnforced => debug!("Capture threads confined by Landlock"),
+            RulesetStatus::PartiallyEnforced => {
+                debug!("Capture threads partially confined by Landlock (older kernel ABI)")
+            }
+            RulesetStatus::NotEnforced => {
+                debug!("Landlock unavailable, capture threads run unconfined")
+            }
+        },
+        Err(e) => debug!("Failed to confine capture threads: {e}"),
+    });
+}
+
+/// Landlock is Linux-only; capture threads run unconfined elsewhere.
+#[cfg(not(target_os = "linux"))]
+pub fn confine_capture_thread() {
+    REPORTED.call_once(|| debug!("Capture thread sandboxing is only available on Linux"));
+}
+
+/// Forbids every thread of the process, and all it starts later, to execute
+/// programs or open files for writing.
+///
+/// Must only be called once every file the process writes is open. Failures
+/// are not fatal: the process keeps running unconfined.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn confine_process() {
+    match install_process_filter() {
+        Ok(()) => debug!("Process confined by seccomp, no programs or writable files"),
+        Err(e) => debug!("Failed to confine the process: {e}"),
+    }
+}
+
+/// Confinement of the whole process needs a seccomp filter for the
+/// architecture; the process runs unconfined elsewhere.
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub fn confine_process() {
+    debug!("Process sandboxing is only available on Linux on x86_64 and aarch64");
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+fn install_process_filter() -> std::io::Result<()> {
+    use libc::{
+        BPF_ABS, BPF_JEQ, BPF_JMP, BPF_JSET, BPF_K, BPF_LD, BPF_RET, BPF_W, SECCOMP_RET_ALLOW,
+        SECCOMP_RET_ERRNO, sock_filter, sock_fprog,
+    };
+    use std::io;
+
+    // Only x86_64 still has the old `creat` and `open`, flags in argument 1.
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "x86_64")]
+    const LEGACY_DENIED: &[libc::c_long] = &[libc::SYS_creat];
+    #[cfg(target_arch = "x86_64")]
+    const LEGACY_OPENING: &[(libc::c_long, u32)] = &[(libc::SYS_open, 1)];
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+    #[cfg(target_arch = "aarch64")]
+    const LEGACY_DENIED: &[libc::c_long] = &[];
+    #[cfg(target_arch = "aarch64")]
+    const LEGACY_OPENING: &[(libc::c_long, u32)] = &[];
+    // Offsets into `struct seccomp_data`, whose arguments are 64 bits wide;
+    // both architectures are little-endian, so the flags are the low half.
+    const NR: u32 = 0;
+    const ARCH: u32 = 4;
+    const ARGS: u32 = 16;
+
+    let stmt = |code: u32, k: u32| sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    let jump = |code: u32, k: u32, jt: u8, jf: u8| sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    };
+    let deny: u32 = SECCOMP_RET_ERRNO | libc::EACCES as u32;
+    let write_flags: u32 = (libc::O_WRONLY | libc::O_RDWR | libc::O_CREAT | libc::O_TRUNC) as u32;
+
+    let denied = [
+        libc::SYS_execve,
+        libc::SYS_execveat,
+        libc::SYS_openat2,
+        libc::SYS_truncate,
+    ]
+    .into_iter()
+    .chain(LEGACY_DENIED.iter().copied());
+    // System calls opening files, with the index of their flags argument.
+    let opening = [(libc::SYS_openat, 2)]
+        .into_iter()
+        .chain(LEGACY_OPENING.iter().copied());
+
+    // Calls of another architecture's ABI could slip past the numbers below.
+    let mut filter: Vec<sock_filter> = vec![
+        stmt(BPF_LD | BPF_W | BPF_ABS, ARCH),
+        jump(BPF_JMP | BPF_JEQ | BPF_K, AUDIT_ARCH, 1, 0),
+        stmt(BPF_RET | BPF_K, deny),
+        stmt(BPF_LD | BPF_W | BPF_ABS, NR),
+    ];
+    for nr in denied {
+        filter.push(jump(BPF_JMP | BPF_JEQ | BPF_K, nr as u32, 0, 1));
+        filter.push(stmt(BPF_RET | BPF_K, deny));
+    }
+    for (nr, flags) in opening {
+        filter.push(jump(BPF_JMP | BPF_JEQ | BPF_K, nr as u32, 0, 4));
+        filter.push(stmt(BPF_LD | BPF_W | BPF_ABS, ARGS + 8 * flags));
+        filter.push(jump(BPF_JMP | BPF_JSET | BPF_K, write_flags, 0, 1));
+        filter.push(stmt(BPF_RET | BPF_K, deny));
+        filter.push(stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW));
+    }
+    filter.push(stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW));
+
+    let program: sock_fprog = sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_mut_ptr(),
+    };
+    // SAFETY: both calls only read their arguments, and `program` points to
+    // `filter`, which outlives them; the kernel copies the filter.
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        match libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER as libc::c_ulong,
+            libc::SECCOMP_FILTER_FLAG_TSYNC,
+            &program as *const sock_fprog,
+        ) {
+            0 => Ok(()),
+            -1 => Err(io::Error::last_os_error()),
+            tid => Err(io::Error::other(format!(
+                "thread {tid} could not take the filter"
+            ))),
+        }
+    }
+}
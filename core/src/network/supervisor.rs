@@ -0,0 +1,220 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Supervision of the packet capture threads.
+//!
+//! A capture thread forwards what its socket receives into a queue until the
+//! scanner drops the other end. It can also stop on its own: when it panics on
+//! a packet, or when its socket breaks, e.g. because the interface went away.
+//! Either way the thread records why before it closes the queue, so the
+//! scanner waiting on it wakes up at once, with the reason at hand, instead of
+//! waiting out its deadline for packets that never come.
+//!
+//! Scanners then reopen the capture once, see
+//! [`EthernetHandle::recover`](super::channel::EthernetHandle::recover), and
+//! abort with a [`ZondError::ChannelError`] if that fails or the capture
+//! stops again.
+
+use std::{
+    any::Any,
+    io,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, OnceLock},
+    thread,
+};
+
+use zond_common::warn;
+
+use crate::error::ZondError;
+use crate::network::channel::{self, SendFailure};
+
+/// Receive errors in a row after which a socket counts as broken.
+const MAX_ERROR_STREAK: u32 = 100;
+
+/// Why a capture thread stopped, once it has.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureFailure(Arc<OnceLock<String>>);
+
+impl CaptureFailure {
+    pub fn reason(&self) -> Option<&str> {
+        self.0.get().map(String::as_str)
+    }
+
+    fn set(&self, reason: String) {
+        let _ = self.0.set(reason);
+    }
+}
+
+/// Runs the capture loop `body` on a thread called `name`, feeding `queue`.
+///
+/// `body` returns `Ok` once the queue is dropped by the scanner, and an error
+/// if its socket broke. That error or a panic is recorded in `failure` before
+/// `queue` is dropped.
+pub(crate) fn spawn<T, F>(
+    name: String,
+    queue: T,
+    failure: CaptureFailure,
+    body: F,
+) -> io::Result<()>
+where
+    T: Send + 'static,
+    F: FnOnce(&T) -> io::Result<()> + Send + 'static,
+{
+    thread::Builder::new().name(name).spawn(move || {
+        match panic::catch_unwind(AssertUnwindSafe(|| body(&queue))) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => failure.set(format!("receive failed: {e}")),
+            Err(payload) => failure.set(format!(
+                "capture thread panicked: {}",
+                panic_message(payload.as_ref())
+            )),
+        }
+        drop(queue);
+    })?;
+    Ok(())
+}
+
+/// Opens a capture again in place of one that stopped for `failure`, unless
+/// it was `reopened` already. `what` names the capture in messages.
+pub(crate) fn reopen<H>(
+    what: &str,
+    failure: &CaptureFailure,
+    reopened: bool,
+    open: impl FnOnce() -> Result<H, ZondError>,
+) -> Result<H, ZondError> {
+    let reason: &str = failure.reason().unwrap_or("capture thread stopped");
+    if reopened {
+        return Err(ZondError::ChannelError(format!(
+            "capture on {what} stopped again after it was reopened: {reason}"
+        )));
+    }
+
+    warn!("Capture on {what} stopped ({reason}), reopening it");
+    open().map_err(|e| {
+        ZondError::ChannelError(format!(
+            "capture on {what} stopped ({reason}) and could not be reopened: {e}"
+        ))
+    })
+}
+
+/// Tells the receive errors a capture loop rides out from those that end it.
+#[derive(Debug, Default)]
+pub(crate) struct RecvErrors {
+    streak: u32,
+}
+
+impl RecvErrors {
+    /// Call after every packet received.
+    pub(crate) fn reset(&mut self) {
+        self.streak = 0;
+    }
+
+    /// Passes over timeouts and passing errors, and returns `err` if the
+    /// socket is gone or has failed too often in a row.
+    pub(crate) fn check(&mut self, err: io::Error) -> io::Result<()> {
+        if matches!(
+            err.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+        ) {
+            return Ok(());
+        }
+        // A link that is down for sending is down for receiving too.
+        if is_closed(&err) || channel::classify_send_error(&err) == SendFailure::LinkDown {
+            return Err(err);
+        }
+
+        self.streak += 1;
+        match self.streak >= MAX_ERROR_STREAK {
+            true => Err(err),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Returns `true` for `EBADF`, reported once the socket itself is gone.
+#[cfg(unix)]
+fn is_closed(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::EBADF)
+}
+
+#[cfg(not(unix))]
+fn is_closed(_err: &io::Error) -> bool {
+    false
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn failures_are_recorded_before_the_queue_closes() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<u8>();
+        let failure: CaptureFailure = CaptureFailure::default();
+        spawn("capture-test".into(), tx, failure.clone(), |queue| {
+            queue.send(1).unwrap();
+            panic!("malformed packet");
+        })
+        .unwrap();
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(rx.recv().await, None);
+        assert_eq!(
+            failure.reason(),
+            Some("capture thread panicked: malformed packet")
+        );
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<u8>();
+        let failure: CaptureFailure = CaptureFailure::default();
+        spawn("capture-test".into(), tx, failure.clone(), |_| {
+            Err(io::Error::from(io::ErrorKind::NetworkDown))
+        })
+        .unwrap();
+        assert_eq!(rx.recv().await, None);
+        assert!(failure.reason().unwrap().starts_with("receive failed"));
+
+        let reopened: Result<(), ZondError> = reopen("eth0", &failure, true, || Ok(()));
+        assert!(matches!(reopened, Err(ZondError::ChannelError(e)) if e.contains("again")));
+    }
+
+    #[test]
+    fn only_lasting_receive_errors_end_a_capture() {
+        let mut errors: RecvErrors = RecvErrors::default();
+        assert!(errors.check(io::ErrorKind::TimedOut.into()).is_ok());
+        #[cfg(unix)]
+        assert!(
+            errors
+                .check(io::Error::from_raw_os_error(libc::EBADF))
+                .is_err()
+        );
+        assert!(errors.check(io::ErrorKind::NetworkDown.into()).is_err());
+
+        for _ in 1..MAX_ERROR_STREAK {
+            assert!(errors.check(io::ErrorKind::InvalidData.into()).is_ok());
+        }
+        errors.reset();
+        for _ in 1..MAX_ERROR_STREAK {
+            assert!(errors.check(io::ErrorKind::InvalidData.into()).is_ok());
+        }
+        assert!(errors.check(io::ErrorKind::InvalidData.into()).is_err());
+    }
+}
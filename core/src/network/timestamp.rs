@@ -0,0 +1,135 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Receive timestamps of captured packets.
+//!
+//! Round trips used to be measured when a scanner got around to a reply, so
+//! they included the queue between capture thread and scanner, and whatever
+//! the scanner was busy with at the time. Captured packets now carry the
+//! moment they arrived, and scanners take the send time as soon as the kernel
+//! accepted a probe, so RTTs of different scanners can be compared.
+//!
+//! On Linux, transport sockets are dated by the kernel when a packet came off
+//! the wire, read back with `SIOCGSTAMPNS` after each `recv`. The socket option
+//! `SO_TIMESTAMPNS` is not used: it only hands stamps out as `recvmsg` control
+//! messages, which pnet never asks for. Elsewhere, and for data link captures,
+//! whose socket pnet keeps to itself, the capture thread stamps a packet as
+//! soon as `recv` returned it.
+
+use std::time::{Duration, Instant, SystemTime};
+
+/// Kernel stamps further in the past are taken for a stepped clock and
+/// replaced by the time of capture.
+const MAX_KERNEL_AGE: Duration = Duration::from_secs(10);
+
+/// Dates the packets received on one socket.
+pub struct ReceiveClock {
+    #[cfg(target_os = "linux")]
+    fd: Option<libc::c_int>,
+}
+
+impl ReceiveClock {
+    /// Stamps packets when the capture thread gets them.
+    pub fn userspace() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            fd: None,
+        }
+    }
+
+    /// Asks the kernel to stamp the packets of `fd`, falling back to
+    /// [`userspace`](Self::userspace) stamps if it refuses.
+    ///
+    /// `fd` must stay open for as long as the clock is used.
+    #[cfg(target_os = "linux")]
+    pub fn kernel(fd: libc::c_int) -> Self {
+        // The first request switches stamping on; nothing was stamped yet.
+        let enabled: bool = match read_stamp(fd) {
+            Ok(_) => true,
+            Err(e) => e.raw_os_error() == Some(libc::ENOENT),
+        };
+        Self {
+            fd: enabled.then_some(fd),
+        }
+    }
+
+    /// Returns when the packet last read from the socket arrived.
+    pub fn stamp(&self) -> Instant {
+        let now: Instant = Instant::now();
+        self.kernel_stamp()
+            .and_then(|stamp| to_instant(stamp, SystemTime::now(), now))
+            .unwrap_or(now)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn kernel_stamp(&self) -> Option<SystemTime> {
+        read_stamp(self.fd?).ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn kernel_stamp(&self) -> Option<SystemTime> {
+        None
+    }
+}
+
+/// Reads when the packet last received on `fd` arrived.
+#[cfg(target_os = "linux")]
+fn read_stamp(fd: libc::c_int) -> std::io::Result<SystemTime> {
+    /// Not exported by `libc`; the timespec variant of `SIOCGSTAMP`.
+    const SIOCGSTAMPNS: u64 = 0x8907;
+
+    let mut ts: libc::timespec = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: SIOCGSTAMPNS writes a single timespec into `ts`.
+    let status: libc::c_int =
+        unsafe { libc::ioctl(fd, SIOCGSTAMPNS as _, &mut ts as *mut libc::timespec) };
+    if status != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let since_epoch: Duration = Duration::new(ts.tv_sec.max(0) as u64, ts.tv_nsec as u32);
+    Ok(SystemTime::UNIX_EPOCH + since_epoch)
+}
+
+/// Maps the wall clock `stamp` onto the monotonic clock, given that `now_wall`
+/// and `now` were read together.
+fn to_instant(stamp: SystemTime, now_wall: SystemTime, now: Instant) -> Option<Instant> {
+    let age: Duration = now_wall.duration_since(stamp).ok()?;
+    if age > MAX_KERNEL_AGE {
+        return None;
+    }
+    now.checked_sub(age)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernel_stamps_map_onto_the_monotonic_clock() {
+        let now_wall: SystemTime = SystemTime::now();
+        let now: Instant = Instant::now();
+        let stamp: SystemTime = now_wall - Duration::from_micros(250);
+
+        let received: Instant = to_instant(stamp, now_wall, now).unwrap();
+        assert_eq!(now - received, Duration::from_micros(250));
+
+        let future: SystemTime = now_wall + Duration::from_millis(1);
+        assert!(to_instant(future, now_wall, now).is_none());
+        let stale: SystemTime = now_wall - MAX_KERNEL_AGE - Duration::from_secs(1);
+        assert!(to_instant(stale, now_wall, now).is_none());
+    }
+}
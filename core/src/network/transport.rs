@@ -5,72 +5,323 @@
 // https://mozilla.org/MPL/2.0/.
 
 use pnet::{
-    packet::{Packet, ip::IpNextHeaderProtocols},
-    transport::{
-        self, TransportChannelType, TransportProtocol, TransportReceiver, TransportSender,
+    packet::{
+        Packet,
+        ip::{IpNextHeaderProtocol, IpNextHeaderProtocols},
     },
+    transport::{self, TransportChannelType, TransportProtocol, TransportReceiver},
 };
+#[cfg(target_os = "linux")]
+use pnet_sys::{FileDesc, SockAddrStorage, SockLen};
+use std::io;
+#[cfg(target_os = "linux")]
+use std::mem;
 use std::net::IpAddr;
+#[cfg(target_os = "linux")]
+use std::net::SocketAddr;
+#[cfg(target_os = "linux")]
+use std::os::fd::{IntoRawFd, OwnedFd};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::mpsc;
+#[cfg(target_os = "linux")]
+use zond_common::utils::privilege::{self, SocketRequest};
+
+use crate::error::ZondError;
+use crate::network::supervisor::{self, CaptureFailure, RecvErrors};
+use crate::network::timestamp::ReceiveClock;
 
 const TRANSPORT_BUFFER_SIZE: usize = 4096;
 const CHANNEL_TYPE_UDP: TransportChannelType =
     TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Udp));
 const CHANNEL_TYPE_TCP: TransportChannelType =
     TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Tcp));
+const CHANNEL_TYPE_TCP_L3: TransportChannelType =
+    TransportChannelType::Layer3(IpNextHeaderProtocols::Tcp);
+const CHANNEL_TYPE_ICMP: TransportChannelType =
+    TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Icmp));
+const CHANNEL_TYPE_ICMP_L3: TransportChannelType =
+    TransportChannelType::Layer3(IpNextHeaderProtocols::Icmp);
+const CHANNEL_TYPE_SCTP: TransportChannelType =
+    TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Sctp));
+const CHANNEL_TYPE_SCTP_L3: TransportChannelType =
+    TransportChannelType::Layer3(IpNextHeaderProtocols::Sctp);
 
 #[derive(Debug, Clone, Copy)]
 pub enum TransportType {
     TcpLayer4,
     UdpLayer4,
+    IcmpLayer4,
+    /// Delivers whole IPv4 packets carrying TCP, so header fields like the TTL are
+    /// visible. Receive-only: sending would require building IP headers by hand.
+    TcpLayer3,
+    /// Like [`TransportType::TcpLayer3`], for IPv4 packets carrying ICMP.
+    IcmpLayer3,
+    /// Send-only: pnet has no iterator for SCTP packets, so replies are
+    /// captured with [`TransportType::SctpLayer3`].
+    SctpLayer4,
+    /// Like [`TransportType::TcpLayer3`], for IPv4 packets carrying SCTP.
+    SctpLayer3,
+    /// Like [`TransportType::TcpLayer3`], for IPv4 packets carrying the given
+    /// protocol. Sending takes a whole IPv4 packet, of any protocol.
+    IpLayer3(u8),
+}
+
+/// Sends on a raw IPv4 socket, opened by this process or handed over by the
+/// privileged helper of `--elevate`.
+pub struct TransportSender(Sender);
+
+enum Sender {
+    Pnet(transport::TransportSender),
+    /// A raw IPv4 socket from the helper, which only exists on Linux, where
+    /// packets are sent as they are.
+    #[cfg(target_os = "linux")]
+    Helper(Arc<FileDesc>),
+}
+
+impl TransportSender {
+    /// Sends `packet` to `destination`.
+    pub fn send_to<T: Packet>(&mut self, packet: T, destination: IpAddr) -> io::Result<usize> {
+        match &mut self.0 {
+            Sender::Pnet(tx) => tx.send_to(packet, destination),
+            #[cfg(target_os = "linux")]
+            Sender::Helper(socket) => {
+                // SAFETY: all zeroes is a valid `sockaddr_storage`, filled in
+                // below.
+                let mut storage: SockAddrStorage = unsafe { mem::zeroed() };
+                let len: SockLen =
+                    pnet_sys::addr_to_sockaddr(SocketAddr::new(destination, 0), &mut storage);
+                pnet_sys::send_to(
+                    socket.fd,
+                    packet.packet(),
+                    (&storage as *const SockAddrStorage).cast(),
+                    len,
+                )
+            }
+        }
+    }
+}
+
+/// Bytes handed to a transport sender as they are, for protocols pnet has no
+/// packet type for.
+pub struct RawPacket<'a>(pub &'a [u8]);
+
+impl Packet for RawPacket<'_> {
+    fn packet(&self) -> &[u8] {
+        self.0
+    }
+
+    fn payload(&self) -> &[u8] {
+        &[]
+    }
 }
 
 pub struct TransportHandle {
-    pub tx: std::sync::Arc<std::sync::Mutex<TransportSender>>,
-    pub rx: mpsc::UnboundedReceiver<(Vec<u8>, IpAddr)>,
+    pub tx: Arc<Mutex<TransportSender>>,
+    /// Captured packets, their source and when they were received. Closes if
+    /// the capture stops, see [`TransportHandle::recover`].
+    pub rx: mpsc::UnboundedReceiver<(Vec<u8>, IpAddr, Instant)>,
+    transport_type: TransportType,
+    failure: CaptureFailure,
+    reopened: bool,
+}
+
+impl TransportHandle {
+    /// Reopens the capture after `rx` closed, once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZondError::ChannelError`] with the reason the capture
+    /// stopped if it cannot be reopened or was reopened before.
+    pub fn recover(&mut self) -> Result<(), ZondError> {
+        let what: String = format!("the {:?} socket", self.transport_type);
+        let fresh: TransportHandle =
+            supervisor::reopen(&what, &self.failure, self.reopened, || {
+                start_packet_capture(self.transport_type)
+            })?;
+        *self = TransportHandle {
+            reopened: true,
+            ..fresh
+        };
+        Ok(())
+    }
 }
 
 macro_rules! spawn_listener {
-    ($tx:expr, $rx:expr, $iter_func:path) => {
-        std::thread::spawn(move || {
+    ($name:expr, $tx:expr, $rx:expr, $failure:expr, $iter_func:path) => {
+        supervisor::spawn($name, $tx, $failure, move |queue| {
+            $crate::network::sandbox::confine_capture_thread();
+            // `$rx` owns the socket and lives as long as this thread.
+            #[cfg(target_os = "linux")]
+            let clock = ReceiveClock::kernel($rx.socket.fd);
+            #[cfg(not(target_os = "linux"))]
+            let clock = ReceiveClock::userspace();
             let mut iterator = $iter_func(&mut $rx);
+            let mut errors: RecvErrors = RecvErrors::default();
             loop {
-                if let Ok((packet, source_ip)) = iterator.next() {
-                    if $tx.send((packet.packet().to_vec(), source_ip)).is_err() {
-                        break;
+                match iterator.next() {
+                    Ok((packet, source_ip)) => {
+                        errors.reset();
+                        let received: Instant = clock.stamp();
+                        if queue
+                            .send((packet.packet().to_vec(), source_ip, received))
+                            .is_err()
+                        {
+                            return Ok(());
+                        }
                     }
+                    Err(e) => errors.check(e)?,
                 }
             }
         })
     };
 }
 
-pub fn start_packet_capture(transport_type: TransportType) -> anyhow::Result<TransportHandle> {
+pub fn start_packet_capture(transport_type: TransportType) -> Result<TransportHandle, ZondError> {
     let (tx, mut rx_socket) = open_channel(transport_type)?;
     let (queue_tx, queue_rx) = mpsc::unbounded_channel();
+    let failure: CaptureFailure = CaptureFailure::default();
+    let name: String = format!("capture-{transport_type:?}");
+    let listener: CaptureFailure = failure.clone();
 
-    match transport_type {
+    let spawned: io::Result<()> = match transport_type {
         TransportType::TcpLayer4 => {
-            spawn_listener!(queue_tx, rx_socket, pnet::transport::tcp_packet_iter)
+            spawn_listener!(
+                name,
+                queue_tx,
+                rx_socket,
+                listener,
+                pnet::transport::tcp_packet_iter
+            )
         }
         TransportType::UdpLayer4 => {
-            spawn_listener!(queue_tx, rx_socket, pnet::transport::udp_packet_iter)
+            spawn_listener!(
+                name,
+                queue_tx,
+                rx_socket,
+                listener,
+                pnet::transport::udp_packet_iter
+            )
+        }
+        TransportType::IcmpLayer4 => {
+            spawn_listener!(
+                name,
+                queue_tx,
+                rx_socket,
+                listener,
+                pnet::transport::icmp_packet_iter
+            )
+        }
+        TransportType::TcpLayer3
+        | TransportType::IcmpLayer3
+        | TransportType::SctpLayer3
+        | TransportType::IpLayer3(_) => {
+            spawn_listener!(
+                name,
+                queue_tx,
+                rx_socket,
+                listener,
+                pnet::transport::ipv4_packet_iter
+            )
+        }
+        TransportType::SctpLayer4 => {
+            return Err(ZondError::ChannelError(
+                "SctpLayer4 transport cannot capture, use SctpLayer3".to_string(),
+            ));
         }
     };
+    spawned.map_err(|e| ZondError::from_io(e, "capture thread"))?;
 
     Ok(TransportHandle {
-        tx: std::sync::Arc::new(std::sync::Mutex::new(tx)),
+        tx: Arc::new(Mutex::new(tx)),
         rx: queue_rx,
+        transport_type,
+        failure,
+        reopened: false,
     })
 }
 
+/// Opens a channel for sending only, without spawning a capture thread.
+pub fn open_sender(
+    transport_type: TransportType,
+) -> Result<Arc<Mutex<TransportSender>>, ZondError> {
+    let (tx, _) = open_channel(transport_type)?;
+    Ok(Arc::new(Mutex::new(tx)))
+}
+
 fn open_channel(
     transport_type: TransportType,
-) -> anyhow::Result<(TransportSender, TransportReceiver)> {
+) -> Result<(TransportSender, TransportReceiver), ZondError> {
     let channel_type: TransportChannelType = match transport_type {
         TransportType::TcpLayer4 => CHANNEL_TYPE_TCP,
         TransportType::UdpLayer4 => CHANNEL_TYPE_UDP,
+        TransportType::IcmpLayer4 => CHANNEL_TYPE_ICMP,
+        TransportType::TcpLayer3 => CHANNEL_TYPE_TCP_L3,
+        TransportType::IcmpLayer3 => CHANNEL_TYPE_ICMP_L3,
+        TransportType::SctpLayer4 => CHANNEL_TYPE_SCTP,
+        TransportType::SctpLayer3 => CHANNEL_TYPE_SCTP_L3,
+        TransportType::IpLayer3(protocol) => {
+            TransportChannelType::Layer3(IpNextHeaderProtocol::new(protocol))
+        }
+    };
+    let what = || format!("{transport_type:?} transport");
+
+    if let Some(channel) =
+        open_helper_channel(channel_type).map_err(|e| ZondError::from_io(e, &what()))?
+    {
+        return Ok(channel);
+    }
+    let (tx, rx) = transport::transport_channel(TRANSPORT_BUFFER_SIZE, channel_type)
+        .map_err(|e| ZondError::from_io(e, &what()))?;
+    Ok((TransportSender(Sender::Pnet(tx)), rx))
+}
+
+/// Opens the channel of `channel_type` on a socket from the privileged
+/// helper, if zond runs with one.
+#[cfg(target_os = "linux")]
+fn open_helper_channel(
+    channel_type: TransportChannelType,
+) -> io::Result<Option<(TransportSender, TransportReceiver)>> {
+    let Some(socket) = helper_socket(channel_type)? else {
+        return Ok(None);
+    };
+    let socket: Arc<FileDesc> = Arc::new(FileDesc {
+        fd: socket.into_raw_fd(),
+    });
+    let rx: TransportReceiver = TransportReceiver {
+        socket: Arc::clone(&socket),
+        buffer: vec![0; TRANSPORT_BUFFER_SIZE],
+        channel_type,
+    };
+    Ok(Some((TransportSender(Sender::Helper(socket)), rx)))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_helper_channel(
+    _channel_type: TransportChannelType,
+) -> io::Result<Option<(TransportSender, TransportReceiver)>> {
+    Ok(None)
+}
+
+/// Asks the privileged helper for the socket of `channel_type`, if zond runs
+/// with one.
+#[cfg(target_os = "linux")]
+fn helper_socket(channel_type: TransportChannelType) -> io::Result<Option<OwnedFd>> {
+    if !privilege::uses_helper() {
+        return Ok(None);
+    }
+    let request: SocketRequest = match channel_type {
+        TransportChannelType::Layer4(TransportProtocol::Ipv4(protocol)) => SocketRequest::RawIpv4 {
+            protocol: protocol.0,
+            header_included: false,
+        },
+        TransportChannelType::Layer3(protocol) => SocketRequest::RawIpv4 {
+            protocol: protocol.0,
+            header_included: true,
+        },
+        TransportChannelType::Layer4(TransportProtocol::Ipv6(_)) => {
+            return Err(io::ErrorKind::Unsupported.into());
+        }
     };
-    let (tx, rx) = transport::transport_channel(TRANSPORT_BUFFER_SIZE, channel_type)?;
-    Ok((tx, rx))
+    privilege::helper_socket(request)
 }
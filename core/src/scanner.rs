@@ -14,37 +14,169 @@
 //! spawning concurrent explorers, and piping results through a background
 //! [`HostnameResolver`].use std::net::IpAddr;
 
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
+use std::ops::RangeInclusive;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use is_root::is_root;
-use zond_common::config::ZondConfig;
-use zond_common::models::host::Host;
+use pnet::datalink::NetworkInterface;
+use zond_common::config::{Technique, Timing, ZondConfig};
+use zond_common::logging;
+use zond_common::models::host::{Host, NetworkRole, Unreachable};
 use zond_common::models::ip::set::IpSet;
+use zond_common::models::port::{PortSet, PortState, Protocol};
+use zond_common::models::reachability::Method;
 use zond_common::models::target::TargetMap;
 use zond_common::net::interface;
-use zond_common::utils::input::InputHandle;
-use zond_common::{error, info, success, warn};
+use zond_common::parse::{self, DiscoveryOverride, denylist};
+use zond_common::utils::input::{Control, InputHandle};
+use zond_common::utils::privilege;
+use zond_common::{debug, error, info, phase, success, warn};
 
+mod ad;
+mod addresses;
+mod arp_cache;
+mod baseline;
+mod calibrate;
+pub mod capture;
 mod connect;
+mod deadline;
 pub mod dispatcher;
+pub mod enrich;
+pub mod impact;
+mod ipid;
+pub mod ipproto;
+mod leases;
 mod local;
+pub mod mdns;
+pub mod names;
+mod passive;
+mod plugins;
+mod printer;
 mod resolver;
+pub mod responder;
 mod routed;
+mod spill;
+pub mod tls;
+mod trace;
 
+use arp_cache::ArpCache;
 use local::LocalScanner;
-use routed::RoutedScanner;
+use routed::{Probe, RoutedScanner};
+use tokio::sync::Semaphore;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::task::JoinHandle;
+use tracing::{Span, field};
 
+use crate::error::ZondError;
 use crate::scanner::resolver::HostnameResolver;
 
+/// Upper bound on physical NICs being scanned at the same time.
+///
+/// Every scanner owns a capture thread, so a machine with many interfaces would
+/// otherwise end up with dozens of threads competing for the same cores.
+const MAX_PARALLEL_NICS: usize = 4;
+/// Ports [`scan`] probes at the same time.
+const PORT_SCAN_CONCURRENCY: usize = 50;
+
 pub static FOUND_HOST_COUNT: AtomicUsize = AtomicUsize::new(0);
 pub static STOP_SIGNAL: AtomicBool = AtomicBool::new(false);
+/// Set once the process was asked to terminate. Unlike [`STOP_SIGNAL`], a new scan
+/// does not reset it, so a shutdown requested between scans is not lost.
+pub static SHUTDOWN_SIGNAL: AtomicBool = AtomicBool::new(false);
+/// Set while the user paused sending with `p`. Replies are still collected.
+pub static PAUSE_SIGNAL: AtomicBool = AtomicBool::new(false);
+static PROBES_SENT: AtomicUsize = AtomicUsize::new(0);
+static PROBES_TIMED_OUT: AtomicUsize = AtomicUsize::new(0);
+static SCAN_STARTED: Mutex<Option<Instant>> = Mutex::new(None);
 static INPUT_LISTENER_SPAWNED: AtomicBool = AtomicBool::new(false);
+/// Targets routers reported unreachable during the last [`discover_groups`].
+static UNREACHABLE: Mutex<Vec<Unreachable>> = Mutex::new(Vec::new());
+/// How often a paused sender checks whether it may go on.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Stops the running scan and keeps any later scan from starting, the same way
+/// pressing `q` stops the current one.
+pub fn request_shutdown() {
+    SHUTDOWN_SIGNAL.store(true, Ordering::Relaxed);
+    STOP_SIGNAL.store(true, Ordering::Relaxed);
+}
+
+/// Clears a stop request left over from a previous scan, unless a shutdown is pending,
+/// and restarts the clock and probe count of the status line.
+fn reset_stop_signal() {
+    STOP_SIGNAL.store(SHUTDOWN_SIGNAL.load(Ordering::Relaxed), Ordering::Relaxed);
+    PROBES_SENT.store(0, Ordering::Relaxed);
+    PROBES_TIMED_OUT.store(0, Ordering::Relaxed);
+    *SCAN_STARTED.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+}
+
+/// Returns `true` while sending is paused and the scan was not stopped.
+pub fn is_paused() -> bool {
+    PAUSE_SIGNAL.load(Ordering::Relaxed) && !STOP_SIGNAL.load(Ordering::Relaxed)
+}
+
+/// Pauses sending if it runs, resumes it otherwise, and returns whether it is
+/// paused now.
+pub fn toggle_pause() -> bool {
+    !PAUSE_SIGNAL.fetch_xor(true, Ordering::Relaxed)
+}
+
+/// Blocks the calling thread while sending is paused.
+fn hold_while_paused() {
+    while is_paused() {
+        std::thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+}
+
+/// Waits while sending is paused.
+async fn wait_while_paused() {
+    while is_paused() {
+        tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+    }
+}
+
+/// Counts one probe sent, for the status line.
+fn record_probe() {
+    PROBES_SENT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts one TCP connect probe that stayed silent through all its retries.
+fn record_timeout() {
+    PROBES_TIMED_OUT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns how many probes the running scan sent, and how many of its TCP
+/// connect probes went unanswered even after [`Timing::retries`].
+pub fn probe_counts() -> (usize, usize) {
+    (
+        PROBES_SENT.load(Ordering::Relaxed),
+        PROBES_TIMED_OUT.load(Ordering::Relaxed),
+    )
+}
+
+/// Summarizes the running scan for the `s` key.
+pub fn status_line() -> String {
+    let hosts: usize = get_host_count();
+    let probes: usize = PROBES_SENT.load(Ordering::Relaxed);
+    let started: Option<Instant> = *SCAN_STARTED.lock().unwrap_or_else(PoisonError::into_inner);
+    let elapsed: Duration = started.map(|at| at.elapsed()).unwrap_or_default();
+
+    let mut line: String = format!(
+        "{hosts} {} found, {probes} {} sent, {:.1}s elapsed",
+        if hosts == 1 { "host" } else { "hosts" },
+        if probes == 1 { "probe" } else { "probes" },
+        elapsed.as_secs_f64()
+    );
+    if is_paused() {
+        line.push_str(", sending paused");
+    }
+    line
+}
 
 pub fn increment_host_count() {
     FOUND_HOST_COUNT.fetch_add(1, Ordering::Relaxed);
@@ -54,23 +186,138 @@ pub fn get_host_count() -> usize {
     FOUND_HOST_COUNT.load(Ordering::Relaxed)
 }
 
+/// Takes the targets that routers reported unreachable during the last
+/// [`discover_groups`], instead of the targets answering. They are not among
+/// the hosts it returned.
+pub fn take_unreachable() -> Vec<Unreachable> {
+    std::mem::take(&mut *UNREACHABLE.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Keeps `targets` for [`take_unreachable`].
+fn record_unreachable(targets: impl IntoIterator<Item = Unreachable>) {
+    UNREACHABLE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .extend(targets);
+}
+
+/// Returns how many hostname lookups are still awaited after scanning
+/// finished, or 0 while scanning or once resolution is over.
+pub fn outstanding_lookups() -> usize {
+    resolver::OUTSTANDING.load(Ordering::Relaxed)
+}
+
+/// Refuses `targets`, named `spec` in the error, if they touch the never-scan
+/// list. Every entry point of the scanner calls this, since not every probe
+/// starts from a target that went through target parsing.
+pub(crate) fn enforce_denylist(spec: &str, targets: &IpSet) -> Result<(), ZondError> {
+    denylist::enforce(spec, targets).map_err(|e| ZondError::InvalidTarget(e.to_string()))
+}
+
+/// Refuses the single address `ip` like [`enforce_denylist`]. Unlike an
+/// [`IpSet`], it may be IPv6.
+pub(crate) fn enforce_denylist_ip(spec: &str, ip: IpAddr) -> Result<(), ZondError> {
+    denylist::enforce_ip(spec, ip).map_err(|e| ZondError::InvalidTarget(e.to_string()))
+}
+
 #[async_trait]
 trait NetworkExplorer {
     async fn discover_hosts(&mut self) -> anyhow::Result<Vec<Host>>;
 }
 
-pub async fn scan(target_map: TargetMap, cfg: &ZondConfig) -> anyhow::Result<Vec<Host>> {
-    STOP_SIGNAL.store(false, Ordering::Relaxed);
+pub async fn scan(target_map: TargetMap, cfg: &ZondConfig) -> Result<Vec<Host>, ZondError> {
+    reset_stop_signal();
+    for unit in &target_map.units {
+        enforce_denylist("port scan targets", &unit.ips)?;
+    }
     let use_raw_sockets = preflight_check(cfg);
 
     if use_raw_sockets {
         // Future: Remove this fallback once SYN scanner is ready
         warn!("Privileged port scanning (SYN) not yet implemented; using TCP connect fallback");
+    } else if cfg.sctp {
+        return Err(ZondError::PermissionDenied(
+            "--sctp needs raw sockets, run as root".to_string(),
+        ));
     }
 
+    // SCTP ports share the numbers given for TCP.
+    let sctp_units: Vec<(IpSet, Vec<u16>)> = match cfg.sctp {
+        true => target_map
+            .units
+            .iter()
+            .map(|unit| {
+                let ports: Vec<u16> = unit
+                    .ports
+                    .iter()
+                    .filter(|(_, protocol)| *protocol == Protocol::Tcp)
+                    .map(|(port, _)| port)
+                    .collect();
+                (unit.ips.clone(), ports)
+            })
+            .filter(|(_, ports)| !ports.is_empty())
+            .collect(),
+        false => Vec::new(),
+    };
+
+    let cfg: &ZondConfig = &calibrated(cfg, target_map.units.iter().map(|unit| &unit.ips)).await;
     let dispatcher = dispatcher::Dispatcher::new(target_map);
     let rx = dispatcher.run_shuffled();
-    connect::scan(rx, 50).await
+    let scan_phase: Span = phase!("port scan");
+    let mut hosts: Vec<Host> = connect::scan(rx, PORT_SCAN_CONCURRENCY, cfg.timing).await?;
+    drop(scan_phase);
+
+    for (ips, ports) in sctp_units {
+        if STOP_SIGNAL.load(Ordering::Relaxed) {
+            break;
+        }
+        for host in sctp_sweep(ips, ports, cfg).await? {
+            match hosts.iter_mut().find(|known| known.is_same_device(&host)) {
+                Some(known) => known.merge(host),
+                None => hosts.push(host),
+            }
+        }
+    }
+
+    enrich::Pipeline::port_scan(cfg).run(&mut hosts).await;
+    Ok(hosts)
+}
+
+/// Sends SCTP INITs to `ports` of every target and returns the hosts that
+/// answered, with the state of each port that did.
+///
+/// Targets no interface reaches are skipped, as SCTP has no unprivileged
+/// fallback.
+async fn sctp_sweep(ips: IpSet, ports: Vec<u16>, cfg: &ZondConfig) -> Result<Vec<Host>, ZondError> {
+    let (interface_map, unmapped_ips) = interface::map_ips_to_interfaces(ips);
+    if !unmapped_ips.is_empty() {
+        warn!(
+            "{} target(s) are not reachable through any interface and get no SCTP probes",
+            unmapped_ips.len()
+        );
+    }
+    let source_ports: RangeInclusive<u16> = cfg
+        .source_ports
+        .clone()
+        .unwrap_or(routed::DEFAULT_SOURCE_PORTS);
+
+    let mut hosts: Vec<Host> = Vec::new();
+    for (intf, (local_ips, mut ips)) in interface_map {
+        local_ips
+            .ranges()
+            .iter()
+            .for_each(|range| ips.insert_range(*range));
+        let _phase: Span = phase!("sctp sweep", interface = %intf.name);
+        let probe: Probe = Probe::SctpInit(ports.clone());
+        let found: Vec<Host> = RoutedScanner::new(intf, ips, source_ports.clone(), probe, None)
+            .map_err(into_zond_error)?
+            .exhaustive()
+            .discover_hosts()
+            .await
+            .map_err(into_zond_error)?;
+        hosts.extend(found);
+    }
+    Ok(hosts)
 }
 
 /// The primary entry point for network discovery.
@@ -83,76 +330,675 @@ pub async fn scan(target_map: TargetMap, cfg: &ZondConfig) -> anyhow::Result<Vec
 /// ### Integration Notes
 /// - **State**: Updates [`FOUND_HOST_COUNT`] and reacts to [`STOP_SIGNAL`].
 /// - **Concurrency**: Spawns multiple Tokio tasks; ensure the caller is within a multi-threaded runtime.
-pub async fn discover(targets: IpSet, cfg: &ZondConfig) -> anyhow::Result<Vec<Host>> {
-    STOP_SIGNAL.store(false, Ordering::Relaxed);
+///
+/// ### Errors
+/// - Returns the error of the first failed scanner if **every** scanner task failed,
+///   e.g. [`ZondError::PermissionDenied`] when raw sockets could not be opened.
+pub async fn discover(targets: IpSet, cfg: &ZondConfig) -> Result<Vec<Host>, ZondError> {
+    let mut hosts: Vec<Host> = run_discovery(targets, cfg, &ArpCache::new()).await?;
+    label_self(&mut hosts);
+    Ok(hosts)
+}
+
+/// Runs [`discover`], reusing and extending the MAC addresses of `arp_cache`.
+async fn run_discovery(
+    mut targets: IpSet,
+    cfg: &ZondConfig,
+    arp_cache: &ArpCache,
+) -> Result<Vec<Host>, ZondError> {
+    reset_stop_signal();
+    enforce_denylist("discovery targets", &targets)?;
+    if !cfg.include_self {
+        exclude_self(&mut targets);
+    }
+    trace::ENABLED.store(cfg.packet_trace, Ordering::Relaxed);
+    capture::ENABLED.store(
+        cfg.capture_errors && !cfg.redact && !cfg.demo,
+        Ordering::Relaxed,
+    );
     let use_raw_sockets = preflight_check(cfg);
     if !use_raw_sockets {
-        return connect::discover(targets).await;
+        if cfg.technique != Technique::Auto {
+            return Err(ZondError::PermissionDenied(format!(
+                "--technique {} needs raw sockets, run as root or use --technique auto",
+                cfg.technique
+            )));
+        }
+        if cfg.sctp {
+            return Err(ZondError::PermissionDenied(
+                "--sctp needs raw sockets, run as root".to_string(),
+            ));
+        }
+        let _phase: Span = phase!("fallback scanner");
+        return connect::discover(targets, cfg.timing).await;
     }
+    if cfg.sctp && cfg.technique == Technique::Arp {
+        return Err(ZondError::InvalidTarget(
+            "--technique arp stays on the local segment, which rules out --sctp".to_string(),
+        ));
+    }
+
+    let (interface_map, unmapped_ips) = interface::map_ips_to_interfaces(targets);
+    check_technique(cfg.technique, &interface_map, &unmapped_ips)?;
 
     let (dns_tx, resolver_task) = if !cfg.no_dns {
         let (tx, rx) = mpsc::unbounded_channel();
-        let task = spawn_resolver(rx).await;
+        let task = spawn_resolver(rx, cfg).await;
         (Some(tx), Some(task))
     } else {
         info!("DNS resolution skipped by user flag");
         (None, None)
     };
 
-    let scanner_handles = spawn_explorers(targets, dns_tx).await;
+    let source_ports: RangeInclusive<u16> = cfg
+        .source_ports
+        .clone()
+        .unwrap_or(routed::DEFAULT_SOURCE_PORTS);
+    let syn_ports: Vec<u16> = if cfg.syn_ports.is_empty() {
+        vec![routed::DEFAULT_SYN_PORT]
+    } else {
+        cfg.syn_ports.clone()
+    };
+    let (scanner_handles, plan) = spawn_explorers(
+        interface_map,
+        unmapped_ips,
+        dns_tx,
+        source_ports,
+        &syn_ports,
+        arp_cache,
+        cfg,
+    )
+    .await;
+    let scanner_count: usize = scanner_handles.len();
 
     let mut hosts = Vec::new();
+    let mut failures: Vec<ZondError> = Vec::new();
     for handle in scanner_handles {
         match handle.await {
             Ok(Ok(res)) => hosts.extend(res),
-            Ok(Err(e)) => error!("Scanner task failed: {e}"),
-            Err(e) => error!("Task panicked: {e}"),
+            Ok(Err(e)) => {
+                error!("Scanner task failed: {e}");
+                failures.push(into_zond_error(e));
+            }
+            Err(e) => {
+                error!("Task panicked: {e}");
+                failures.push(ZondError::ChannelError(e.to_string()));
+            }
         }
     }
 
+    if scanner_count > 0 && failures.len() == scanner_count {
+        return Err(failures.swap_remove(0));
+    }
+
+    mark_silent(&mut hosts, &plan);
+
     if let Some(task) = resolver_task
         && let Ok(Some(mut resolver)) = task.await
     {
+        let _phase: Span = phase!("hostname merge", hosts = hosts.len());
         resolver.resolve_hosts(&mut hosts);
     }
 
     Ok(hosts)
 }
 
-async fn spawn_explorers(
-    targets: IpSet,
-    dns_tx: Option<mpsc::UnboundedSender<IpAddr>>,
-) -> Vec<JoinHandle<anyhow::Result<Vec<Host>>>> {
-    let mut handles = Vec::new();
+/// Runs [`discover`] once per group of targets that share discovery settings.
+///
+/// Each group overrides the technique of `cfg`, and a `@syn:PORT` annotation
+/// replaces its SYN probe ports. Hosts found by more than one group are merged
+/// into a single entry. A stop request ends the run after the current group. MAC
+/// addresses resolved by one group let later groups probe those hosts with
+/// unicast ARP.
+///
+/// With [`ZondConfig::passive_warmup`] set, the run starts by listening for
+/// hosts that announce themselves and only probes the addresses not heard from.
+/// With [`ZondConfig::calibrate`] set, a sample of the remaining targets is
+/// measured first to pick the [`Timing`] of the connect probes.
+/// With [`ZondConfig::baseline`] set, that host is probed throughout and the
+/// round trips of slow hosts are judged against it. Hosts found finally pass
+/// through the discovery [`enrich::Pipeline`].
+///
+/// Targets a router reported unreachable are not hosts, see
+/// [`take_unreachable`].
+///
+/// ### Errors
+/// - Returns the first error of any group's [`discover`].
+pub async fn discover_groups(
+    mut groups: Vec<(DiscoveryOverride, IpSet)>,
+    cfg: &ZondConfig,
+) -> Result<Vec<Host>, ZondError> {
+    for (_, ips) in &groups {
+        enforce_denylist("discovery targets", ips)?;
+    }
+    UNREACHABLE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    if let Some(ip) = cfg.baseline {
+        enforce_denylist_ip(&format!("--baseline {ip}"), ip)?;
+    }
+    let arp_cache: ArpCache = ArpCache::new();
+    let mut hosts: Vec<Host> = match cfg.passive_warmup {
+        Some(warmup) => passive_warmup(&mut groups, warmup, cfg, &arp_cache).await,
+        None => Vec::new(),
+    };
+    if STOP_SIGNAL.load(Ordering::Relaxed) {
+        return Ok(hosts);
+    }
+    let cfg: &ZondConfig = &calibrated(cfg, groups.iter().map(|(_, ips)| ips)).await;
 
-    let (interface_map, unmapped_ips) = interface::map_ips_to_interfaces(targets);
+    let monitor: Option<baseline::Monitor> = match cfg.baseline {
+        Some(ip) => {
+            let _phase: Span = phase!("baseline");
+            baseline::Monitor::start(ip).await
+        }
+        None => None,
+    };
+
+    for (idx, (settings, ips)) in groups.into_iter().enumerate() {
+        if idx > 0 && STOP_SIGNAL.load(Ordering::Relaxed) {
+            break;
+        }
+        if ips.is_empty() {
+            continue;
+        }
+
+        let group_cfg: ZondConfig = ZondConfig {
+            technique: settings.technique,
+            syn_ports: match settings.syn_port {
+                Some(port) => vec![port],
+                None => cfg.syn_ports.clone(),
+            },
+            ..cfg.clone()
+        };
+        for host in run_discovery(ips, &group_cfg, &arp_cache).await? {
+            match hosts.iter_mut().find(|known| known.is_same_device(&host)) {
+                Some(known) => known.merge(host),
+                None => hosts.push(host),
+            }
+        }
+    }
+
+    if let Some(monitor) = monitor {
+        monitor.finish().await.annotate(&mut hosts);
+    }
+
+    enrich::Pipeline::discovery(cfg).run(&mut hosts).await;
+    // Another group may have reached what one was told is unreachable.
+    UNREACHABLE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|unreachable| {
+            !hosts
+                .iter()
+                .any(|host| host.ips.contains(&unreachable.target))
+        });
+    Ok(hosts)
+}
+
+/// Everything [`inspect`] found out about a single target.
+#[derive(Debug)]
+pub struct Dossier {
+    /// The target with the results of every stage merged in.
+    pub host: Host,
+    /// The target answered discovery probes, rather than only showing up in
+    /// the port scan.
+    pub discovered: bool,
+    /// Certificates served on the open TCP ports, by port.
+    pub certificates: Vec<(u16, tls::Certificate)>,
+}
+
+impl Dossier {
+    /// Returns `true` if anything at all answered.
+    pub fn responded(&self) -> bool {
+        self.discovered || !self.host.ports().is_empty() || !self.certificates.is_empty()
+    }
+}
+
+/// Runs the whole enrichment pipeline against one address.
+///
+/// Discovery (ARP or ping, PTR and mDNS names, vendor) is followed by a port
+/// scan of `ports` with the usual service and printer identification, and
+/// finally every open TCP port is asked for a TLS certificate. A target that
+/// ignores discovery probes is still port scanned. `server_name` is sent as
+/// TLS SNI; without it the resolved hostname is used.
+///
+/// ### Errors
+/// - [`ZondError::InvalidTarget`] if `ip` is on the never-scan list.
+/// - Returns the first error of discovery or the port scan.
+pub async fn inspect(
+    ip: IpAddr,
+    server_name: Option<&str>,
+    ports: PortSet,
+    cfg: &ZondConfig,
+) -> Result<Dossier, ZondError> {
+    let target: [String; 1] = [ip.to_string()];
+    enforce_denylist_ip(&target[0], ip)?;
+    let groups: Vec<(DiscoveryOverride, IpSet)> =
+        parse::to_discovery_groups(&target, cfg.technique, cfg.include_broadcast)
+            .map_err(|e| ZondError::InvalidTarget(e.to_string()))?;
+    let mut found: Vec<Host> = discover_groups(groups, cfg).await?;
+    let discovered: bool = !found.is_empty();
+    if STOP_SIGNAL.load(Ordering::Relaxed) {
+        return Ok(Dossier {
+            host: merge_all(ip, found),
+            discovered,
+            certificates: Vec::new(),
+        });
+    }
+
+    let target_map: TargetMap = parse::to_target_map(&target, ports)
+        .map_err(|e| ZondError::InvalidTarget(e.to_string()))?;
+    found.extend(scan(target_map, cfg).await?);
+    let host: Host = merge_all(ip, found);
+
+    let open_tcp: Vec<u16> = host
+        .ports()
+        .iter()
+        .filter(|port| port.protocol == Protocol::Tcp && port.state == PortState::Open)
+        .map(|port| port.number)
+        .collect();
+    let server_name: Option<&str> = server_name.or(host.hostname.as_deref());
+    let _phase: Span = phase!("tls", ports = open_tcp.len());
+    let certificates: Vec<(u16, tls::Certificate)> =
+        tls::read_certificates(ip, &open_tcp, server_name).await;
+
+    Ok(Dossier {
+        host,
+        discovered,
+        certificates,
+    })
+}
+
+/// Returns `cfg`, with the [`Timing`] measured on a sample of `targets` if
+/// [`ZondConfig::calibrate`] asks for it.
+async fn calibrated<'a>(
+    cfg: &ZondConfig,
+    targets: impl IntoIterator<Item = &'a IpSet> + Clone,
+) -> ZondConfig {
+    if !cfg.calibrate {
+        return cfg.clone();
+    }
+    let _phase: Span = phase!("calibration");
+    let timing: Timing = calibrate::timing_for(calibrate::sample(targets))
+        .await
+        .unwrap_or(cfg.timing);
+    ZondConfig {
+        timing,
+        calibrate: false,
+        ..cfg.clone()
+    }
+}
+
+/// Folds every record found for `ip` into one host.
+fn merge_all(ip: IpAddr, hosts: Vec<Host>) -> Host {
+    let mut hosts = hosts.into_iter();
+    let mut merged: Host = hosts.next().unwrap_or_else(|| Host::new(ip));
+    hosts.for_each(|host| merged.merge(host));
+    merged
+}
+
+/// Listens for `warmup` and removes every address heard from `groups`.
+///
+/// Returns the hosts heard, with hostnames resolved unless DNS is disabled, and
+/// records their MAC addresses in `arp_cache`. Without raw sockets nothing can
+/// be captured, so the warmup is skipped.
+async fn passive_warmup(
+    groups: &mut [(DiscoveryOverride, IpSet)],
+    warmup: Duration,
+    cfg: &ZondConfig,
+    arp_cache: &ArpCache,
+) -> Vec<Host> {
+    reset_stop_signal();
+    if !preflight_check(cfg) {
+        warn!("Passive warmup needs raw sockets, probing every target");
+        return Vec::new();
+    }
+
+    let targets: IpSet = groups.iter().map(|(_, ips)| ips.clone()).collect();
+    info!(
+        "Listening passively for {}s before probing",
+        warmup.as_secs()
+    );
+    let harvest_phase: Span = phase!("passive warmup");
+    let mut hosts: Vec<Host> = passive::harvest(&targets, warmup, !cfg.no_promiscuous).await;
+    drop(harvest_phase);
+
+    for host in &hosts {
+        for ip in &host.ips {
+            if let (IpAddr::V4(v4), Some(mac)) = (ip, host.mac) {
+                arp_cache.insert(*v4, mac);
+            }
+            groups.iter_mut().for_each(|(_, ips)| ips.remove(ip));
+        }
+    }
+    info!(
+        "Passive warmup heard {} host(s), probing the remaining {} address(es)",
+        hosts.len(),
+        groups.iter().map(|(_, ips)| ips.len()).sum::<u64>()
+    );
+
+    if !cfg.no_dns && !hosts.is_empty() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = spawn_resolver(rx, cfg).await;
+        hosts
+            .iter()
+            .flat_map(|host| host.ips.iter())
+            .for_each(|ip| _ = tx.send(*ip));
+        drop(tx);
+        if let Ok(Some(mut resolver)) = task.await {
+            resolver.resolve_hosts(&mut hosts);
+        }
+    }
+
+    hosts
+}
+
+/// Makes sure a strict technique can reach every target before any probe is sent.
+fn check_technique(
+    technique: Technique,
+    interface_map: &HashMap<NetworkInterface, (IpSet, IpSet)>,
+    unmapped_ips: &IpSet,
+) -> Result<(), ZondError> {
+    if technique == Technique::Auto {
+        return Ok(());
+    }
+
+    if let Some(ip) = unmapped_ips.iter().next() {
+        return Err(ZondError::InvalidTarget(format!(
+            "{ip} is not reachable through any interface, which --technique {technique} requires"
+        )));
+    }
+
+    if technique == Technique::Arp
+        && let Some((intf, ip)) = interface_map
+            .iter()
+            .find_map(|(intf, (_, routed))| routed.iter().next().map(|ip| (intf, ip)))
+    {
+        return Err(ZondError::InvalidTarget(format!(
+            "ARP only reaches on-link targets, but {ip} is routed via {}",
+            intf.name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Drops the addresses of this machine that `targets` holds as part of a range.
+///
+/// Probing them only tests the loopback path. An own address targeted on its
+/// own is kept, since asking for exactly that address is deliberate, see
+/// [`IpSet::is_listed`].
+fn exclude_self(targets: &mut IpSet) {
+    let inside_ranges: Vec<IpAddr> = interface::own_addresses()
+        .into_iter()
+        .filter(|ip| targets.contains(ip) && !targets.is_listed(ip))
+        .collect();
+    if inside_ranges.is_empty() {
+        return;
+    }
+
+    debug!(
+        verbosity = 1,
+        "Skipping {} address(es) of this machine inside the target ranges",
+        inside_ranges.len()
+    );
+    inside_ranges.iter().for_each(|ip| targets.remove(ip));
+}
+
+/// Tags the hosts answering from an address of this machine as [`NetworkRole::Scanner`].
+fn label_self(hosts: &mut [Host]) {
+    let own: HashSet<IpAddr> = interface::own_addresses();
+    hosts
+        .iter_mut()
+        .filter(|host| {
+            host.ips
+                .iter()
+                .any(|ip| ip.is_loopback() || own.contains(ip))
+        })
+        .for_each(|host| {
+            host.network_roles.insert(NetworkRole::Scanner);
+        });
+}
+
+/// Recovers the [`ZondError`] behind a scanner task failure, if there is one.
+fn into_zond_error(err: anyhow::Error) -> ZondError {
+    err.downcast::<ZondError>()
+        .unwrap_or_else(|e| ZondError::ChannelError(e.to_string()))
+}
+
+/// Records on every host the probes sent to one of its addresses, so the
+/// ones it left unanswered show up next to those it answered. The local
+/// sweeps cover whole segments and are recorded by the scanner itself.
+fn mark_silent(hosts: &mut [Host], plan: &[(Method, IpSet)]) {
+    for host in hosts {
+        for (method, targets) in plan {
+            for ip in host.ips.iter().filter(|ip| targets.contains(ip)) {
+                host.reachability.probed(*ip, *method);
+            }
+        }
+    }
+}
+
+/// A scanner bound to one interface, waiting for its NIC to become free.
+enum Explorer {
+    /// ARP sweep of on-link targets, with an NDP probe unless restricted to ARP,
+    /// from a random MAC address if the flag is set.
+    Local(NetworkInterface, IpSet, Technique, ArpCache, bool),
+    /// TCP SYN, ICMP echo or SCTP INIT sweep, from the given source ports.
+    Routed(NetworkInterface, IpSet, RangeInclusive<u16>, Probe),
+}
+
+impl Explorer {
+    /// The probe a routed scanner sends and the addresses it sends it to.
+    fn routed_probe(&self) -> Option<(Method, IpSet)> {
+        match self {
+            Explorer::Local(..) => None,
+            Explorer::Routed(_, ips, _, probe) => Some((probe.method(), ips.clone())),
+        }
+    }
+
+    /// Runs the scanner, a routed one keeping up to `memory_limit` bytes of
+    /// unanswered probes in memory.
+    async fn run(
+        self,
+        dns_tx: Option<mpsc::UnboundedSender<IpAddr>>,
+        memory_limit: u64,
+    ) -> anyhow::Result<Vec<Host>> {
+        match self {
+            Explorer::Local(intf, ips, technique, arp_cache, random_mac) => {
+                info!(verbosity = 1, "Spawning LOCAL scanner for {}", intf.name);
+                let phase: Span =
+                    phase!("local scanner", interface = %intf.name, hosts = field::Empty);
+                let hosts: Vec<Host> = LocalScanner::new(intf, ips, technique, arp_cache, dns_tx)?
+                    .random_mac(random_mac)
+                    .discover_hosts()
+                    .await?;
+                phase.record("hosts", hosts.len());
+                Ok(hosts)
+            }
+            Explorer::Routed(intf, ips, source_ports, probe) => {
+                info!(
+                    verbosity = 1,
+                    "Spawning ROUTED scanner ({probe:?}) for {}", intf.name
+                );
+                let phase: Span =
+                    phase!("routed scanner", interface = %intf.name, hosts = field::Empty);
+                let hosts: Vec<Host> = RoutedScanner::new(intf, ips, source_ports, probe, dns_tx)?
+                    .memory_limit(memory_limit)
+                    .discover_hosts()
+                    .await?;
+                phase.record("hosts", hosts.len());
+                Ok(hosts)
+            }
+        }
+    }
+}
+
+/// Groups the per-interface scanners by the physical NIC behind them.
+///
+/// Aliases and VLANs share the MAC address of their parent device, so keying by
+/// MAC keeps them in the same queue. Interfaces without a usable MAC (tunnels,
+/// point-to-point links) are treated as their own NIC.
+///
+/// Strict techniques other than ARP probe on-link targets the same way as routed
+/// ones, so both sets go to a single routed scanner. With `sctp` set, every
+/// target of an interface also gets SCTP INITs from a scanner of its own.
+fn group_by_nic(
+    interface_map: HashMap<NetworkInterface, (IpSet, IpSet)>,
+    source_ports: &RangeInclusive<u16>,
+    technique: Technique,
+    syn_ports: &[u16],
+    sctp: bool,
+    random_mac: bool,
+    arp_cache: &ArpCache,
+) -> HashMap<String, Vec<Explorer>> {
+    let mut nics: HashMap<String, Vec<Explorer>> = HashMap::new();
 
     for (intf, (local_ips, routed_ips)) in interface_map {
-        // Local Scanner (ARP/ICMP)
-        if !local_ips.is_empty() {
-            info!(verbosity = 1, "Spawning LOCAL scanner for {}", intf.name);
-            let tx = dns_tx.clone();
-            let intf_c = intf.clone();
+        let nic: String = match intf.mac {
+            Some(mac) if !mac.is_zero() => mac.to_string(),
+            _ => intf.name.clone(),
+        };
+        let queue: &mut Vec<Explorer> = nics.entry(nic).or_default();
 
-            let handle = tokio::spawn(async move {
-                let mut scanner = LocalScanner::new(intf_c, local_ips, tx)?;
-                scanner.discover_hosts().await
-            });
-            handles.push(handle);
+        if sctp {
+            let mut all_ips: IpSet = routed_ips.clone();
+            local_ips
+                .ranges()
+                .iter()
+                .for_each(|range| all_ips.insert_range(*range));
+            queue.push(Explorer::Routed(
+                intf.clone(),
+                all_ips,
+                source_ports.clone(),
+                Probe::SctpInit(routed::DEFAULT_SCTP_PORTS.to_vec()),
+            ));
         }
 
-        // Routed Scanner (TCP Syn Scan)
+        let (local_ips, routed_ips, probe) = match technique {
+            Technique::Auto | Technique::Arp => {
+                (local_ips, routed_ips, Probe::Syn(syn_ports.to_vec()))
+            }
+            Technique::Syn | Technique::Icmp => {
+                let mut all_ips: IpSet = routed_ips;
+                local_ips
+                    .ranges()
+                    .iter()
+                    .for_each(|range| all_ips.insert_range(*range));
+                let probe: Probe = match technique {
+                    Technique::Icmp => Probe::IcmpEcho,
+                    _ => Probe::Syn(syn_ports.to_vec()),
+                };
+                (IpSet::new(), all_ips, probe)
+            }
+        };
+
+        if !local_ips.is_empty() {
+            queue.push(Explorer::Local(
+                intf.clone(),
+                local_ips,
+                technique,
+                arp_cache.clone(),
+                random_mac,
+            ));
+        }
         if !routed_ips.is_empty() {
-            info!(verbosity = 1, "Spawning ROUTED scanner for {}", intf.name);
-            let tx = dns_tx.clone();
-            let intf_c = intf.clone();
+            queue.push(Explorer::Routed(
+                intf,
+                routed_ips,
+                source_ports.clone(),
+                probe,
+            ));
+        }
+    }
 
-            let handle = tokio::spawn(async move {
-                let mut scanner = RoutedScanner::new(intf_c, routed_ips, tx)?;
-                scanner.discover_hosts().await
-            });
-            handles.push(handle);
+    nics.retain(|_, queue| !queue.is_empty());
+    nics
+}
+
+/// Runs the scanners of a single NIC one after another.
+///
+/// Only fails if every scanner failed, so one broken capture does not throw
+/// away hosts another scanner on the same NIC already found.
+async fn run_serialized(
+    explorers: Vec<Explorer>,
+    dns_tx: Option<mpsc::UnboundedSender<IpAddr>>,
+    memory_limit: u64,
+) -> anyhow::Result<Vec<Host>> {
+    let mut hosts: Vec<Host> = Vec::new();
+    let mut errors: Vec<anyhow::Error> = Vec::new();
+    let mut any_succeeded: bool = false;
+
+    for explorer in explorers {
+        if STOP_SIGNAL.load(Ordering::Relaxed) {
+            break;
         }
+        match explorer.run(dns_tx.clone(), memory_limit).await {
+            Ok(found) => {
+                hosts.extend(found);
+                any_succeeded = true;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if !any_succeeded && !errors.is_empty() {
+        return Err(errors.swap_remove(0));
+    }
+
+    for e in errors {
+        error!("Scanner task failed: {e}");
+    }
+    Ok(hosts)
+}
+
+async fn spawn_explorers(
+    interface_map: HashMap<NetworkInterface, (IpSet, IpSet)>,
+    unmapped_ips: IpSet,
+    dns_tx: Option<mpsc::UnboundedSender<IpAddr>>,
+    source_ports: RangeInclusive<u16>,
+    syn_ports: &[u16],
+    arp_cache: &ArpCache,
+    cfg: &ZondConfig,
+) -> (
+    Vec<JoinHandle<anyhow::Result<Vec<Host>>>>,
+    Vec<(Method, IpSet)>,
+) {
+    let mut handles = Vec::new();
+    let mut plan: Vec<(Method, IpSet)> = Vec::new();
+
+    let permits: Arc<Semaphore> = Arc::new(Semaphore::new(MAX_PARALLEL_NICS));
+
+    let nics: HashMap<String, Vec<Explorer>> = group_by_nic(
+        interface_map,
+        &source_ports,
+        cfg.technique,
+        syn_ports,
+        cfg.sctp,
+        cfg.random_mac,
+        arp_cache,
+    );
+    // NICs scan in parallel, so the memory limit is shared between them.
+    let memory_limit: u64 = cfg.memory_limit.unwrap_or(spill::DEFAULT_MEMORY_LIMIT)
+        / nics.len().clamp(1, MAX_PARALLEL_NICS) as u64;
+
+    for (nic, explorers) in nics {
+        info!(
+            verbosity = 1,
+            "Queueing {} scanner(s) on NIC {nic}",
+            explorers.len()
+        );
+        plan.extend(explorers.iter().filter_map(Explorer::routed_probe));
+        let tx = dns_tx.clone();
+        let permits = Arc::clone(&permits);
+
+        let handle = tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await?;
+            run_serialized(explorers, tx, memory_limit).await
+        });
+        handles.push(handle);
     }
 
     // Fallback Scanner (Unprivileged TCP Handshake) for unmapped IPs (e.g. localhost)
@@ -161,19 +1007,32 @@ async fn spawn_explorers(
             verbosity = 1,
             "Spawning FALLBACK scanner for unmapped targets"
         );
-        let handle = tokio::spawn(async move { connect::discover(unmapped_ips).await });
+        plan.push((Method::Tcp, unmapped_ips.clone()));
+        let timing: Timing = cfg.timing;
+        let handle = tokio::spawn(async move {
+            let _phase: Span = phase!("fallback scanner");
+            connect::discover(unmapped_ips, timing)
+                .await
+                .map_err(anyhow::Error::from)
+        });
         handles.push(handle);
     }
 
-    handles
+    (handles, plan)
 }
 
-async fn spawn_resolver(dns_rx: UnboundedReceiver<IpAddr>) -> JoinHandle<Option<HostnameResolver>> {
+async fn spawn_resolver(
+    dns_rx: UnboundedReceiver<IpAddr>,
+    cfg: &ZondConfig,
+) -> JoinHandle<Option<HostnameResolver>> {
+    let resolve_public: bool = cfg.resolve_public;
+    let budget: Duration = cfg.resolve_budget.unwrap_or(resolver::DEFAULT_BUDGET);
     tokio::spawn(async move {
-        match HostnameResolver::new(dns_rx) {
+        match HostnameResolver::new(dns_rx, resolve_public) {
             Ok(resolver) => {
                 success!("Successfully initialized hostname resolver");
-                Some(resolver.run().await)
+                let _phase: Span = phase!("resolver");
+                Some(resolver.run(budget).await)
             }
             Err(e) => {
                 error!("Resolver failed to start: {e}");
@@ -192,11 +1051,11 @@ fn preflight_check(cfg: &ZondConfig) -> bool {
         spawn_user_input_listener();
     }
 
-    if !is_root() {
+    if !privilege::has_raw_socket_access() {
         warn!("Root privileges missing, defaulting to unprivileged TCP scan");
         return false;
     }
-    success!("Root privileges detected, raw socket scan enabled");
+    success!("Raw socket access detected, raw socket scan enabled");
     true
 }
 
@@ -209,11 +1068,21 @@ fn spawn_user_input_listener() {
         let mut input_handle = InputHandle::new();
         input_handle.start();
         loop {
-            if input_handle.should_interrupt() {
-                STOP_SIGNAL.store(true, Ordering::Relaxed);
-                break;
+            match input_handle.next_control() {
+                Some(Control::Interrupt) => {
+                    STOP_SIGNAL.store(true, Ordering::Relaxed);
+                    break;
+                }
+                Some(Control::TogglePause) => match toggle_pause() {
+                    true => info!("Sending paused, press 'p' to resume"),
+                    false => info!("Sending resumed"),
+                },
+                Some(Control::CycleVerbosity) => {
+                    info!("Verbosity set to {}", logging::cycle_verbosity());
+                }
+                Some(Control::Status) => info!("Status: {}", status_line()),
+                None => std::thread::sleep(Duration::from_millis(50)),
             }
-            std::thread::sleep(Duration::from_millis(50));
         }
     });
 }
@@ -0,0 +1,224 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Active Directory domain controller detection.
+//!
+//! Runs after discovery and tags hosts with [`NetworkRole::DomainController`]
+//! based on two independent markers:
+//!
+//! * **DNS**: the `_ldap._tcp.dc._msdcs.<domain>` SRV records every AD domain
+//!   publishes for its controllers. Domains are taken from the system resolver
+//!   configuration and from the hostnames found during discovery.
+//! * **Services**: Kerberos listening on 88/tcp together with an SMB server that
+//!   requires signing. Domain controllers enforce signing by default, while
+//!   member servers and workstations only offer it.
+//!
+//! Both checks only read: an SRV lookup, a TCP connect and an unauthenticated
+//! SMB2 `NEGOTIATE`.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
+
+use hickory_resolver::{
+    TokioResolver, name_server::TokioConnectionProvider, system_conf::read_system_conf,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::Semaphore,
+    task::JoinSet,
+    time::timeout,
+};
+use zond_common::{
+    debug, info,
+    models::host::{Host, NetworkRole},
+};
+use zond_protocols::smb;
+
+const KERBEROS_PORT: u16 = 88;
+const SMB_PORT: u16 = 445;
+
+/// Time allowed for one TCP connect or one SMB exchange.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Time allowed for the SRV lookup of one domain, including its targets.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+/// Upper bound on hosts probed at the same time.
+const MAX_CONCURRENT_PROBES: usize = 64;
+
+/// Tags the domain controllers among `hosts`.
+pub async fn tag_domain_controllers(hosts: &mut [Host]) {
+    let mut controllers: HashSet<IpAddr> = lookup_srv_controllers(hosts).await;
+    if !super::STOP_SIGNAL.load(Ordering::Relaxed) {
+        controllers.extend(probe_controllers(hosts).await);
+    }
+
+    let mut tagged: usize = 0;
+    for host in hosts.iter_mut() {
+        if host.ips.iter().any(|ip| controllers.contains(ip)) {
+            host.network_roles.insert(NetworkRole::DomainController);
+            tagged += 1;
+        }
+    }
+    info!("Active Directory check found {tagged} domain controller(s)");
+}
+
+/// Returns the addresses named by the DC SRV records of every known domain.
+async fn lookup_srv_controllers(hosts: &[Host]) -> HashSet<IpAddr> {
+    let mut addrs: HashSet<IpAddr> = HashSet::new();
+    let (config, opts) = match read_system_conf() {
+        Ok(conf) => conf,
+        Err(e) => {
+            debug!("Skipping DC SRV lookups, no resolver configuration: {e}");
+            return addrs;
+        }
+    };
+
+    let configured: Vec<String> = config
+        .domain()
+        .into_iter()
+        .chain(config.search())
+        .map(|name| name.to_string())
+        .collect();
+    let hostnames = hosts.iter().filter_map(|host| host.hostname.as_deref());
+    let domains: BTreeSet<String> = candidate_domains(&configured, hostnames);
+
+    let resolver: TokioResolver =
+        TokioResolver::builder_with_config(config, TokioConnectionProvider::default())
+            .with_options(opts)
+            .build();
+
+    for domain in domains {
+        match timeout(LOOKUP_TIMEOUT, lookup_domain(&resolver, &domain)).await {
+            Ok(found) => addrs.extend(found),
+            Err(_) => debug!(verbosity = 1, "DC SRV lookup for {domain} timed out"),
+        }
+    }
+    addrs
+}
+
+/// Resolves the domain controllers `domain` advertises over DNS.
+async fn lookup_domain(resolver: &TokioResolver, domain: &str) -> Vec<IpAddr> {
+    let query: String = format!("_ldap._tcp.dc._msdcs.{domain}.");
+    let Ok(records) = resolver.srv_lookup(query.as_str()).await else {
+        return Vec::new();
+    };
+    debug!(verbosity = 1, "{domain} advertises domain controllers");
+
+    let mut addrs: Vec<IpAddr> = records.ip_iter().collect();
+    for record in records.iter() {
+        if let Ok(ips) = resolver.lookup_ip(record.target().clone()).await {
+            addrs.extend(ips.iter());
+        }
+    }
+    addrs
+}
+
+/// Returns the DNS domains worth an SRV lookup, lowercased and without the
+/// trailing dot.
+///
+/// Hostnames contribute everything after their first label. Single-label
+/// suffixes such as `local` or `lan` are never AD domains and are skipped.
+fn candidate_domains<'a>(
+    configured: &[String],
+    hostnames: impl Iterator<Item = &'a str>,
+) -> BTreeSet<String> {
+    let from_hostnames = hostnames.filter_map(|name| name.split_once('.').map(|(_, rest)| rest));
+    configured
+        .iter()
+        .map(String::as_str)
+        .chain(from_hostnames)
+        .map(|domain| domain.trim_end_matches('.').to_ascii_lowercase())
+        .filter(|domain| domain.contains('.'))
+        .collect()
+}
+
+/// Returns the hosts that run Kerberos and require SMB signing.
+async fn probe_controllers(hosts: &[Host]) -> Vec<IpAddr> {
+    let permits: Arc<Semaphore> = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+    let mut probes: JoinSet<Option<IpAddr>> = JoinSet::new();
+
+    for ip in hosts.iter().map(|host| host.primary_ip) {
+        let permits: Arc<Semaphore> = Arc::clone(&permits);
+        probes.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            if super::STOP_SIGNAL.load(Ordering::Relaxed) {
+                return None;
+            }
+            (is_open(ip, KERBEROS_PORT).await && requires_smb_signing(ip).await).then_some(ip)
+        });
+    }
+
+    let mut controllers: Vec<IpAddr> = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(Some(ip)) = result {
+            controllers.push(ip);
+        }
+    }
+    controllers
+}
+
+async fn is_open(ip: IpAddr, port: u16) -> bool {
+    let addr: SocketAddr = SocketAddr::new(ip, port);
+    matches!(
+        timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Negotiates SMB2 with `ip` and returns whether it demands signed messages.
+async fn requires_smb_signing(ip: IpAddr) -> bool {
+    let exchange = async {
+        let mut stream: TcpStream = TcpStream::connect(SocketAddr::new(ip, SMB_PORT)).await?;
+        stream.write_all(&smb::create_negotiate_request()).await?;
+        let mut response: [u8; smb::NEGOTIATE_RESPONSE_PREFIX_LEN] =
+            [0; smb::NEGOTIATE_RESPONSE_PREFIX_LEN];
+        stream.read_exact(&mut response).await?;
+        anyhow::Ok(smb::is_signing_required(&response)?)
+    };
+
+    match timeout(PROBE_TIMEOUT, exchange).await {
+        Ok(Ok(required)) => required,
+        Ok(Err(e)) => {
+            debug!(verbosity = 2, "SMB negotiation with {ip} failed: {e}");
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_candidate_domains() {
+        let configured: Vec<String> = vec!["Corp.Example.com.".to_string()];
+        let hostnames = [
+            "dc01.corp.example.com",
+            "printer.local",
+            "nas",
+            "ws7.lab.corp.example.com.",
+        ];
+
+        let domains: Vec<String> = candidate_domains(&configured, hostnames.into_iter())
+            .into_iter()
+            .collect();
+        assert_eq!(domains, vec!["corp.example.com", "lab.corp.example.com"]);
+    }
+}
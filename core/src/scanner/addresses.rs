@@ -0,0 +1,302 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Secondary address enumeration after discovery.
+//!
+//! Discovery finds a device under the address it answered on, but laptops,
+//! phones and servers usually hold several: an IPv4 lease, a link-local and
+//! one or more global IPv6 addresses, a second NIC or a VPN adapter. Each
+//! discovered host is asked for the rest:
+//!
+//! * **mDNS**: a legacy unicast query for the `A` and `AAAA` records of its
+//!   `.local` name, answered with every address the host announces.
+//! * **NetBIOS**: a node status request names the workstation, and a name query
+//!   for that name lists every IPv4 address it is registered on.
+//! * **NDP**: the IPv6 neighbor cache of this machine maps addresses to MACs,
+//!   so entries carrying the MAC of a known host belong to it.
+//!
+//! Only unicast UDP to the host itself is sent; the neighbor cache is read
+//! from the OS.
+
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
+
+use pnet::util::MacAddr;
+use tokio::{net::UdpSocket, sync::Semaphore, task::JoinSet, time::timeout};
+use zond_common::{debug, info, models::host::Host};
+use zond_protocols::{mdns, netbios};
+
+/// Time allowed for one query and its answer.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(800);
+/// Upper bound on hosts queried at the same time.
+const MAX_CONCURRENT_QUERIES: usize = 64;
+/// Longest answer read; NetBIOS node status lists fit several times over.
+const MAX_ANSWER_LEN: usize = 2048;
+
+/// Adds the other addresses of every host in `hosts` to [`Host::ips`].
+pub async fn enumerate(hosts: &mut [Host]) {
+    let permits: Arc<Semaphore> = Arc::new(Semaphore::new(MAX_CONCURRENT_QUERIES));
+    let mut queries: JoinSet<(usize, Vec<IpAddr>)> = JoinSet::new();
+    for (idx, host) in hosts.iter().enumerate() {
+        let mdns_name: Option<String> = host
+            .hostname
+            .clone()
+            .filter(|name| name.trim_end_matches('.').ends_with(".local"));
+        let ipv4: Option<Ipv4Addr> = host.ips.iter().find_map(|ip| match ip {
+            IpAddr::V4(ipv4) => Some(*ipv4),
+            IpAddr::V6(_) => None,
+        });
+        let primary_ip: IpAddr = host.primary_ip;
+        let permits: Arc<Semaphore> = Arc::clone(&permits);
+
+        queries.spawn(async move {
+            let mut found: Vec<IpAddr> = Vec::new();
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return (idx, found);
+            };
+            if super::STOP_SIGNAL.load(Ordering::Relaxed) {
+                return (idx, found);
+            }
+            if let Some(name) = mdns_name {
+                found.extend(query_mdns(primary_ip, &name).await);
+            }
+            if let Some(ipv4) = ipv4 {
+                found.extend(query_netbios(ipv4).await.into_iter().map(IpAddr::V4));
+            }
+            (idx, found)
+        });
+    }
+
+    let mut added: usize = 0;
+    while let Some(result) = queries.join_next().await {
+        if let Ok((idx, found)) = result {
+            added += add_addresses(&mut hosts[idx], found, "mDNS/NetBIOS");
+        }
+    }
+
+    let neighbors: Vec<(Ipv6Addr, MacAddr)> = neighbor_table();
+    for host in hosts.iter_mut() {
+        let Some(mac) = host.mac else {
+            continue;
+        };
+        let found: Vec<IpAddr> = neighbors
+            .iter()
+            .filter(|(_, neighbor_mac)| *neighbor_mac == mac)
+            .map(|(ip, _)| IpAddr::V6(*ip))
+            .collect();
+        added += add_addresses(host, found, "the neighbor cache");
+    }
+
+    if added > 0 {
+        info!("Found {added} more address(es) of known hosts");
+    }
+}
+
+/// Inserts the usable addresses of `found` into `host` and returns how many
+/// were new.
+fn add_addresses(host: &mut Host, found: Vec<IpAddr>, source: &str) -> usize {
+    let new: BTreeSet<IpAddr> = found
+        .into_iter()
+        .filter(|ip| !ip.is_unspecified() && !ip.is_loopback() && !ip.is_multicast())
+        .filter(|ip| !host.ips.contains(ip))
+        .collect();
+    for ip in &new {
+        debug!(
+            verbosity = 1,
+            "{} also holds {ip}, found through {source}", host.primary_ip
+        );
+    }
+    let count: usize = new.len();
+    host.ips.extend(new);
+    count
+}
+
+/// Asks `ip` for the addresses of its mDNS name `hostname`.
+async fn query_mdns(ip: IpAddr, hostname: &str) -> Vec<IpAddr> {
+    let id: u16 = rand::random();
+    let query: Vec<u8> = mdns::create_address_query(hostname, id);
+    let Some(answer) = exchange(SocketAddr::new(ip, mdns::MDNS_PORT), &query).await else {
+        return Vec::new();
+    };
+    if answer[..2] != id.to_be_bytes() || !mdns::is_response(&answer) {
+        return Vec::new();
+    }
+    match mdns::extract_resource(&answer) {
+        Ok(record) => record.ips.into_iter().collect(),
+        Err(e) => {
+            debug!(verbosity = 2, "mDNS address answer of {ip} rejected: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Asks `ip` for its workstation name and then for every address that name
+/// is registered on.
+async fn query_netbios(ip: Ipv4Addr) -> Vec<Ipv4Addr> {
+    let addr: SocketAddr = SocketAddr::new(IpAddr::V4(ip), netbios::NBNS_PORT);
+    let Some(status) = exchange(addr, &netbios::create_node_status_query(rand::random())).await
+    else {
+        return Vec::new();
+    };
+    let name: String = match netbios::get_node_status(&status) {
+        Ok(entries) => match netbios::workstation_name(&entries) {
+            Some(name) => name.to_string(),
+            None => return Vec::new(),
+        },
+        Err(e) => {
+            debug!(verbosity = 2, "NetBIOS node status of {ip} rejected: {e}");
+            return Vec::new();
+        }
+    };
+
+    let query: Vec<u8> = netbios::create_name_query(rand::random(), &name, 0x00);
+    let Some(answer) = exchange(addr, &query).await else {
+        return Vec::new();
+    };
+    match netbios::get_addresses(&answer) {
+        Ok(addresses) => addresses,
+        Err(e) => {
+            debug!(verbosity = 2, "NetBIOS name answer of {ip} rejected: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Sends `query` to `addr` from an ephemeral port and waits for one answer.
+async fn exchange(addr: SocketAddr, query: &[u8]) -> Option<Vec<u8>> {
+    let bind: SocketAddr = match addr {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+    };
+    let session = async {
+        let socket: UdpSocket = UdpSocket::bind(bind).await?;
+        socket.connect(addr).await?;
+        socket.send(query).await?;
+        let mut buf: Vec<u8> = vec![0; MAX_ANSWER_LEN];
+        let len: usize = socket.recv(&mut buf).await?;
+        buf.truncate(len);
+        std::io::Result::Ok(buf)
+    };
+    match timeout(QUERY_TIMEOUT, session).await {
+        Ok(Ok(answer)) if answer.len() >= 2 => Some(answer),
+        _ => None,
+    }
+}
+
+/// Reads the IPv6 neighbor cache of this machine.
+#[cfg(target_os = "linux")]
+fn neighbor_table() -> Vec<(Ipv6Addr, MacAddr)> {
+    let Ok(output) = std::process::Command::new("ip")
+        .args(["-6", "neigh", "show"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    parse_ip_neigh(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "macos")]
+fn neighbor_table() -> Vec<(Ipv6Addr, MacAddr)> {
+    let Ok(output) = std::process::Command::new("ndp").arg("-an").output() else {
+        return Vec::new();
+    };
+    parse_ndp(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn neighbor_table() -> Vec<(Ipv6Addr, MacAddr)> {
+    Vec::new()
+}
+
+/// Parses `ip -6 neigh show`, e.g.
+/// `fe80::1 dev eth0 lladdr aa:bb:cc:dd:ee:ff router REACHABLE`. Entries
+/// without a link-layer address, such as `FAILED` ones, are skipped.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ip_neigh(output: &str) -> Vec<(Ipv6Addr, MacAddr)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let ip: Ipv6Addr = fields.next()?.parse().ok()?;
+            let mac: MacAddr = fields
+                .skip_while(|field| *field != "lladdr")
+                .nth(1)?
+                .parse()
+                .ok()?;
+            Some((ip, mac))
+        })
+        .collect()
+}
+
+/// Parses `ndp -an`, e.g. `fe80::1%en0  a:b:c:d:e:f  en0  23h59m58s S R`.
+/// The header line and incomplete entries fail to parse and are skipped.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ndp(output: &str) -> Vec<(Ipv6Addr, MacAddr)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let ip: &str = fields.next()?;
+            let ip: Ipv6Addr = ip.split('%').next()?.parse().ok()?;
+            let mac: MacAddr = fields.next()?.parse().ok()?;
+            Some((ip, mac))
+        })
+        .collect()
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_neighbor_caches() {
+        let mac: MacAddr = MacAddr::new(0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f);
+        let linux: &str = "fe80::1 dev eth0 lladdr 0a:0b:0c:0d:0e:0f router REACHABLE\n\
+                           2001:db8::5 dev eth0 lladdr 0a:0b:0c:0d:0e:0f STALE\n\
+                           2001:db8::9 dev eth0 FAILED\n";
+        assert_eq!(
+            parse_ip_neigh(linux),
+            [
+                ("fe80::1".parse().unwrap(), mac),
+                ("2001:db8::5".parse().unwrap(), mac)
+            ]
+        );
+
+        let macos: &str = "Neighbor                Linklayer Address  Netif Expire    S Flags\n\
+                           fe80::1%en0             a:b:c:d:e:f        en0   23h59m58s S R\n\
+                           2001:db8::7             (incomplete)       en0   expired   N\n";
+        assert_eq!(parse_ndp(macos), [("fe80::1".parse().unwrap(), mac)]);
+    }
+
+    #[test]
+    fn adds_only_new_usable_addresses() {
+        let mut host: Host = Host::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)));
+        let found: Vec<IpAddr> = vec![
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 56, 1)),
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ];
+        assert_eq!(add_addresses(&mut host, found, "test"), 1);
+        assert!(
+            host.ips
+                .contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 56, 1)))
+        );
+    }
+}
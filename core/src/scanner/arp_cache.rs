@@ -0,0 +1,78 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A discovery-scoped cache of resolved IPv4 → MAC pairs.
+//!
+//! Every ARP reply a [`LocalScanner`](super::local::LocalScanner) sees is recorded
+//! here. Later stages of the same discovery run (further target groups, probes
+//! rebuilt after an address change) send their ARP requests straight to the
+//! cached MAC address instead of broadcasting them to the whole segment again.
+//!
+//! Only discovery builds its own ARP frames. The port scan and the enrichment
+//! stages talk through kernel sockets, so their address resolution is left to
+//! the neighbor table of the operating system and this cache plays no part.
+//!
+//! The cache lives only as long as one [`discover_groups`](super::discover_groups)
+//! run, so stale entries from earlier scans never misdirect a probe.
+
+use std::{
+    collections::HashMap,
+    net::Ipv4Addr,
+    sync::{Arc, Mutex},
+};
+
+use pnet::util::MacAddr;
+
+/// Shared handle to the resolved neighbors of the current run.
+#[derive(Debug, Clone, Default)]
+pub struct ArpCache {
+    entries: Arc<Mutex<HashMap<Ipv4Addr, MacAddr>>>,
+}
+
+impl ArpCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `ip` answered from `mac`, replacing any older entry.
+    pub fn insert(&self, ip: Ipv4Addr, mac: MacAddr) {
+        self.entries.lock().unwrap().insert(ip, mac);
+    }
+
+    /// Returns a copy of all entries.
+    pub fn snapshot(&self) -> HashMap<Ipv4Addr, MacAddr> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_entries() {
+        let cache: ArpCache = ArpCache::new();
+        let stage: ArpCache = cache.clone();
+        let ip: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+
+        stage.insert(ip, MacAddr::new(0, 1, 2, 3, 4, 5));
+        stage.insert(ip, MacAddr::new(0, 1, 2, 3, 4, 6));
+
+        let entries: HashMap<Ipv4Addr, MacAddr> = cache.snapshot();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[&ip], MacAddr::new(0, 1, 2, 3, 4, 6));
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Round-trip calibration against a known-good host.
+//!
+//! A burst of probes can slow down the very network it measures, so a host
+//! answering in 40 ms during a sweep is not necessarily slow. A [`Monitor`]
+//! times TCP connects to a baseline host, typically the gateway, before
+//! discovery starts and keeps doing so while it runs. Comparing a host's round
+//! trips with both medians tells a slow target from a congested network, see
+//! [`LatencyCause`].
+//!
+//! A refused connection is as good a sample as an accepted one, so the
+//! baseline only has to answer on one of [`PROBE_PORTS`].
+
+use std::{
+    io::ErrorKind,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use tokio::{net::TcpStream, sync::oneshot, task::JoinHandle, time::timeout};
+use zond_common::{
+    debug, info,
+    models::host::{Host, LatencyCause},
+    warn,
+};
+
+/// Ports tried in order until the baseline answers on one.
+const PROBE_PORTS: [u16; 3] = [443, 80, 53];
+/// Time allowed for one connect; slower samples count as lost.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Pause between two samples.
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+/// Samples taken before discovery starts.
+const IDLE_SAMPLES: usize = 3;
+/// A host is slow once its average round trip exceeds the reference this many
+/// times over...
+const SLOW_FACTOR: u32 = 3;
+/// ...and by more than this, which keeps sub-millisecond jitter on a LAN from
+/// counting.
+const MIN_EXCESS: Duration = Duration::from_millis(2);
+
+/// Probes a baseline host in the background.
+pub struct Monitor {
+    ip: IpAddr,
+    idle: Duration,
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<(Vec<Duration>, usize)>,
+}
+
+impl Monitor {
+    /// Measures the idle round trip to `ip` and keeps probing it until
+    /// [`finish`](Self::finish) is called.
+    ///
+    /// Returns `None`, with a warning, if `ip` answers on none of
+    /// [`PROBE_PORTS`].
+    pub async fn start(ip: IpAddr) -> Option<Self> {
+        let mut port_and_rtt: Option<(u16, Duration)> = None;
+        for port in PROBE_PORTS {
+            if let Some(rtt) = probe(ip, port).await {
+                port_and_rtt = Some((port, rtt));
+                break;
+            }
+        }
+        let Some((port, first)) = port_and_rtt else {
+            warn!(
+                "Baseline {ip} answers on none of ports {PROBE_PORTS:?}; latency calibration skipped"
+            );
+            return None;
+        };
+
+        let mut samples: Vec<Duration> = vec![first];
+        for _ in 1..IDLE_SAMPLES {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+            samples.extend(probe(ip, port).await);
+        }
+        let idle: Duration = median(&mut samples)?;
+        debug!(
+            verbosity = 1,
+            "Baseline {ip}:{port} answers in {} when idle",
+            millis(idle)
+        );
+
+        let (stop, mut stopped) = oneshot::channel::<()>();
+        let task: JoinHandle<(Vec<Duration>, usize)> = tokio::spawn(async move {
+            let mut samples: Vec<Duration> = Vec::new();
+            let mut lost: usize = 0;
+            loop {
+                tokio::select! {
+                    _ = &mut stopped => break,
+                    _ = tokio::time::sleep(PROBE_INTERVAL) => match probe(ip, port).await {
+                        Some(rtt) => samples.push(rtt),
+                        None => lost += 1,
+                    },
+                }
+            }
+            (samples, lost)
+        });
+
+        Some(Self {
+            ip,
+            idle,
+            stop,
+            task,
+        })
+    }
+
+    /// Stops probing and returns what was measured.
+    pub async fn finish(self) -> Calibration {
+        let _ = self.stop.send(());
+        let (mut samples, lost): (Vec<Duration>, usize) = self.task.await.unwrap_or_default();
+        Calibration {
+            ip: self.ip,
+            idle: self.idle,
+            loaded: median(&mut samples).unwrap_or(self.idle),
+            lost,
+        }
+    }
+}
+
+/// Round trips to the baseline before and during discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    pub ip: IpAddr,
+    /// Median before the first probe of the scan.
+    pub idle: Duration,
+    /// Median while the scan ran, or [`idle`](Self::idle) if no sample came
+    /// back in time.
+    pub loaded: Duration,
+    /// Samples taken during the scan that timed out.
+    pub lost: usize,
+}
+
+impl Calibration {
+    /// Reports the drift of the baseline and sets [`Host::latency`] on every
+    /// slow host of `hosts`.
+    pub fn annotate(&self, hosts: &mut [Host]) {
+        if is_slow(self.loaded, self.idle) || self.lost > 0 {
+            warn!(
+                "Round trips to baseline {} went from {} to {} during the scan ({} probes lost); \
+                 high latencies were at least partly caused by congestion",
+                self.ip,
+                millis(self.idle),
+                millis(self.loaded),
+                self.lost
+            );
+        } else {
+            info!(
+                "Baseline {} stayed at {} during the scan",
+                self.ip,
+                millis(self.loaded)
+            );
+        }
+
+        for host in hosts.iter_mut().filter(|host| host.primary_ip != self.ip) {
+            host.latency = host.average_rtt().and_then(|rtt| self.judge(rtt));
+        }
+    }
+
+    /// Returns why a host averaging `rtt` is slow, or `None` if it is not.
+    fn judge(&self, rtt: Duration) -> Option<LatencyCause> {
+        if !is_slow(rtt, self.idle) {
+            return None;
+        }
+        if is_slow(rtt, self.loaded) {
+            Some(LatencyCause::Target)
+        } else {
+            Some(LatencyCause::Congestion)
+        }
+    }
+}
+
+fn is_slow(rtt: Duration, reference: Duration) -> bool {
+    rtt > reference * SLOW_FACTOR && rtt.saturating_sub(reference) > MIN_EXCESS
+}
+
+/// Times a TCP connect to `ip`. A refusal answers just as fast as a handshake.
+async fn probe(ip: IpAddr, port: u16) -> Option<Duration> {
+    let started: Instant = Instant::now();
+    match timeout(PROBE_TIMEOUT, TcpStream::connect(SocketAddr::new(ip, port))).await {
+        Ok(Ok(_)) => Some(started.elapsed()),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Some(started.elapsed()),
+        _ => None,
+    }
+}
+
+fn median(samples: &mut [Duration]) -> Option<Duration> {
+    samples.sort_unstable();
+    samples.get(samples.len() / 2).copied()
+}
+
+fn millis(rtt: Duration) -> String {
+    format!("{:.1}ms", rtt.as_secs_f64() * 1000.0)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn calibration(idle_ms: u64, loaded_ms: u64) -> Calibration {
+        Calibration {
+            ip: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
+            idle: Duration::from_millis(idle_ms),
+            loaded: Duration::from_millis(loaded_ms),
+            lost: 0,
+        }
+    }
+
+    #[test]
+    fn tells_slow_targets_from_congestion() {
+        let ms = Duration::from_millis;
+
+        let quiet: Calibration = calibration(2, 2);
+        assert_eq!(quiet.judge(ms(3)), None);
+        assert_eq!(quiet.judge(ms(40)), Some(LatencyCause::Target));
+
+        let congested: Calibration = calibration(2, 30);
+        assert_eq!(congested.judge(ms(40)), Some(LatencyCause::Congestion));
+        assert_eq!(congested.judge(ms(200)), Some(LatencyCause::Target));
+
+        // Sub-millisecond LANs need a real excess, not just a large factor.
+        assert_eq!(calibration(0, 0).judge(ms(1)), None);
+    }
+
+    #[test]
+    fn median_of_unsorted_samples() {
+        let ms = Duration::from_millis;
+        assert_eq!(median(&mut [ms(9), ms(1), ms(4)]), Some(ms(4)));
+        assert_eq!(median(&mut []), None);
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Dry measurement of loss and latency before a scan.
+//!
+//! A timeout that suits a LAN drops every host behind a satellite link, and a
+//! lossy Wi-Fi misses hosts unless silent ports are tried again. Instead of
+//! leaving the guess to the user, a few addresses spread over the targets are
+//! probed in short rounds first. The hosts that answer at all show how many
+//! later probes get lost and how long answers take, which picks the [`Timing`]
+//! of the scan.
+//!
+//! Only answering hosts count, and only from their second round on: an address
+//! that never answers is more likely unused than lossy.
+
+use std::{
+    io::ErrorKind,
+    net::{IpAddr, SocketAddr},
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use tokio::{net::TcpStream, task::JoinSet, time::timeout};
+use zond_common::{config::Timing, debug, info, models::ip::set::IpSet, warn};
+
+/// Addresses probed at most.
+const SAMPLE_SIZE: usize = 16;
+/// Probes per answering address, including the one that found it.
+const ROUNDS: usize = 4;
+/// Ports tried in order until an address answers on one.
+const PROBE_PORTS: [u16; 4] = [443, 80, 22, 445];
+/// Generous on purpose: the measurement must not miss slow answers itself.
+const MEASURE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Pause between two rounds to the same address.
+const ROUND_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Bounds of the picked probe timeout.
+const MIN_TIMEOUT: Duration = Duration::from_millis(250);
+const MAX_TIMEOUT: Duration = Duration::from_secs(5);
+/// The timeout leaves this many times the slowest typical answer.
+const TIMEOUT_FACTOR: u32 = 4;
+/// Highest loss rate, in percent, each number of retries is picked for.
+const RETRY_STEPS: [(u8, u32); 3] = [(0, 1), (1, 10), (2, 30)];
+const MAX_RETRIES: u8 = 3;
+
+/// What a dry run against the sample observed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    /// Sampled addresses that answered at least once.
+    pub responsive: usize,
+    /// Round trips of every answered probe.
+    pub rtts: Vec<Duration>,
+    /// Follow-up probes to answering addresses that got no answer.
+    pub lost: usize,
+    /// Follow-up probes sent to answering addresses.
+    pub sent: usize,
+}
+
+impl Measurement {
+    /// Share of follow-up probes lost, in percent.
+    pub fn loss_percent(&self) -> u32 {
+        if self.sent == 0 {
+            return 0;
+        }
+        (self.lost * 100).div_ceil(self.sent) as u32
+    }
+
+    /// The round trip 95% of the answers beat.
+    pub fn p95(&self) -> Option<Duration> {
+        let mut rtts: Vec<Duration> = self.rtts.clone();
+        rtts.sort_unstable();
+        let idx: usize = (rtts.len() * 95 / 100).min(rtts.len().checked_sub(1)?);
+        rtts.get(idx).copied()
+    }
+
+    /// Picks a timeout well above the slow answers and enough retries to
+    /// outlast the loss.
+    pub fn timing(&self) -> Option<Timing> {
+        let probe_timeout: Duration =
+            (self.p95()? * TIMEOUT_FACTOR).clamp(MIN_TIMEOUT, MAX_TIMEOUT);
+        let loss: u32 = self.loss_percent();
+        let retries: u8 = RETRY_STEPS
+            .iter()
+            .find(|(_, max_loss)| loss <= *max_loss)
+            .map_or(MAX_RETRIES, |(retries, _)| *retries);
+        Some(Timing {
+            probe_timeout,
+            retries,
+        })
+    }
+}
+
+/// Picks up to [`SAMPLE_SIZE`] addresses spread evenly over `sets`.
+pub fn sample<'a>(sets: impl IntoIterator<Item = &'a IpSet> + Clone) -> Vec<IpAddr> {
+    let total: u64 = sets.clone().into_iter().map(IpSet::len).sum();
+    let step: usize = usize::try_from(total / SAMPLE_SIZE as u64)
+        .unwrap_or(usize::MAX)
+        .max(1);
+    sets.into_iter()
+        .flat_map(IpSet::iter)
+        .step_by(step)
+        .take(SAMPLE_SIZE)
+        .collect()
+}
+
+/// Measures `sample` and returns the [`Timing`] fitting it, or `None` with a
+/// warning if no address answered.
+pub async fn timing_for(sample: Vec<IpAddr>) -> Option<Timing> {
+    let sampled: usize = sample.len();
+    let measurement: Measurement = measure(sample).await;
+    let Some(timing) = measurement.timing() else {
+        warn!(
+            "None of the {sampled} sampled addresses answered; keeping the default timeout of {}ms",
+            Timing::DEFAULT.probe_timeout.as_millis()
+        );
+        return None;
+    };
+
+    info!(
+        "Calibrated on {} of {sampled} sampled hosts: {}% loss, p95 {}ms, using a {}ms timeout \
+         with {} retries",
+        measurement.responsive,
+        measurement.loss_percent(),
+        measurement.p95().unwrap_or_default().as_millis(),
+        timing.probe_timeout.as_millis(),
+        timing.retries
+    );
+    Some(timing)
+}
+
+/// Probes every address of `sample` for [`ROUNDS`] rounds at once.
+async fn measure(sample: Vec<IpAddr>) -> Measurement {
+    let mut probes: JoinSet<Option<(Vec<Duration>, usize)>> = JoinSet::new();
+    for ip in sample {
+        probes.spawn(measure_host(ip));
+    }
+
+    let mut measurement: Measurement = Measurement {
+        responsive: 0,
+        rtts: Vec::new(),
+        lost: 0,
+        sent: 0,
+    };
+    while let Some(result) = probes.join_next().await {
+        let Ok(Some((rtts, lost))) = result else {
+            continue;
+        };
+        measurement.responsive += 1;
+        measurement.sent += ROUNDS - 1;
+        measurement.lost += lost;
+        measurement.rtts.extend(rtts);
+    }
+    measurement
+}
+
+/// Finds a port `ip` answers on and probes it again for the remaining rounds.
+///
+/// Returns the round trips and the number of follow-ups lost, or `None` if
+/// `ip` answered on no port.
+async fn measure_host(ip: IpAddr) -> Option<(Vec<Duration>, usize)> {
+    let mut found: Option<(u16, Duration)> = None;
+    for port in PROBE_PORTS {
+        if let Some(rtt) = probe(SocketAddr::new(ip, port)).await {
+            found = Some((port, rtt));
+            break;
+        }
+    }
+    let (port, first) = found?;
+    debug!(verbosity = 2, "Calibrating against {ip}:{port}");
+
+    let mut rtts: Vec<Duration> = vec![first];
+    let mut lost: usize = 0;
+    for _ in 1..ROUNDS {
+        if super::STOP_SIGNAL.load(Ordering::Relaxed) {
+            break;
+        }
+        tokio::time::sleep(ROUND_INTERVAL).await;
+        match probe(SocketAddr::new(ip, port)).await {
+            Some(rtt) => rtts.push(rtt),
+            None => lost += 1,
+        }
+    }
+    Some((rtts, lost))
+}
+
+/// Times a TCP connect. A refusal is an answer just like a handshake.
+async fn probe(addr: SocketAddr) -> Option<Duration> {
+    let started: Instant = Instant::now();
+    match timeout(MEASURE_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => Some(started.elapsed()),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Some(started.elapsed()),
+        _ => None,
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(rtts_ms: &[u64], lost: usize, sent: usize) -> Measurement {
+        Measurement {
+            responsive: 1,
+            rtts: rtts_ms.iter().copied().map(Duration::from_millis).collect(),
+            lost,
+            sent,
+        }
+    }
+
+    #[test]
+    fn picks_timing_from_loss_and_latency() {
+        let lan: Timing = measurement(&[1, 2, 1, 3], 0, 30).timing().unwrap();
+        assert_eq!(lan.probe_timeout, MIN_TIMEOUT);
+        assert_eq!(lan.retries, 0);
+
+        let satellite: Timing = measurement(&[600, 650, 700], 3, 30).timing().unwrap();
+        assert_eq!(satellite.probe_timeout, Duration::from_millis(2800));
+        assert_eq!(satellite.retries, 1);
+
+        let awful: Timing = measurement(&[2_000], 20, 30).timing().unwrap();
+        assert_eq!(awful.probe_timeout, MAX_TIMEOUT);
+        assert_eq!(awful.retries, MAX_RETRIES);
+
+        assert_eq!(measurement(&[], 0, 0).timing(), None);
+    }
+
+    #[test]
+    fn samples_spread_over_every_set() {
+        let mut first: IpSet = IpSet::new();
+        let mut second: IpSet = IpSet::new();
+        (0..=255).for_each(|last| first.insert(IpAddr::from([10, 0, 0, last])));
+        (0..=255).for_each(|last| second.insert(IpAddr::from([10, 0, 9, last])));
+
+        let picked: Vec<IpAddr> = sample([&first, &second]);
+        assert_eq!(picked.len(), SAMPLE_SIZE);
+        assert_eq!(picked[0], IpAddr::from([10, 0, 0, 0]));
+        assert_eq!(picked[1], IpAddr::from([10, 0, 0, 32]));
+        assert!(picked.iter().any(|ip| second.contains(ip)));
+
+        let mut tiny: IpSet = IpSet::new();
+        tiny.insert(IpAddr::from([10, 0, 0, 1]));
+        assert_eq!(sample([&tiny]).len(), 1);
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Post-mortem captures of the frames a scanner received last.
+//!
+//! With [`ZondConfig::capture_errors`](zond_common::config::ZondConfig::capture_errors),
+//! every raw socket scanner keeps its most recent frames in a [`FrameRing`].
+//! When frames suddenly stop parsing, or parsing one panics, the ring is written
+//! to a pcap file under [`paths::data_dir`], so a bug report about a device
+//! sending malformed packets can carry the packets themselves.
+//!
+//! The rings see all traffic of the interface, so frames are cut after their
+//! headers, and nothing is kept under `--redact` or `--demo`. Only the first
+//! spike of a scanner is written, and nothing touches the disk while frames
+//! parse fine.
+
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use zond_common::{models::scan::ScanInfo, utils::paths, warn};
+
+/// Whether scanners keep their frames, set per discovery run.
+pub(crate) static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Frames kept per scanner.
+const RING_CAPACITY: usize = 256;
+/// Longer frames are cut here, after the Ethernet, IPv6 and longest TCP
+/// header, so the headers that fail to parse are kept and payloads are not.
+const SNAP_LEN: usize = 128;
+/// This many parse errors within [`SPIKE_WINDOW`] count as a spike.
+const SPIKE_THRESHOLD: usize = 16;
+const SPIKE_WINDOW: Duration = Duration::from_secs(1);
+/// Subdirectory of the data directory the pcap files go to.
+const CAPTURE_DIR: &str = "captures";
+
+/// How the frames of a ring begin, recorded as the pcap link type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// Complete Ethernet frames.
+    Ethernet,
+    /// Bare IPv4 or IPv6 packets.
+    RawIp,
+}
+
+impl LinkType {
+    fn code(self) -> u32 {
+        match self {
+            LinkType::Ethernet => 1,
+            LinkType::RawIp => 101,
+        }
+    }
+}
+
+/// Lists the pcap files written during the run `run`, a scan ID.
+pub fn captures_of(run: &str) -> Vec<PathBuf> {
+    let Some(dir) = paths::data_dir().map(|dir| dir.join(CAPTURE_DIR)) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let suffix: String = format!("-{run}.pcap");
+    let mut captures: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(&suffix))
+        })
+        .collect();
+    captures.sort();
+    captures
+}
+
+/// The last [`RING_CAPACITY`] frames of one scanner.
+pub struct FrameRing {
+    scanner: String,
+    link_type: LinkType,
+    frames: VecDeque<(SystemTime, Vec<u8>)>,
+    parse_errors: VecDeque<Instant>,
+    dumped: bool,
+}
+
+impl FrameRing {
+    /// Starts an empty ring for the scanner named `scanner` (e.g. `"local-eth0"`),
+    /// which also names the pcap file.
+    pub fn new(scanner: impl Into<String>, link_type: LinkType) -> Self {
+        Self {
+            scanner: scanner.into(),
+            link_type,
+            frames: VecDeque::with_capacity(RING_CAPACITY),
+            parse_errors: VecDeque::new(),
+            dumped: false,
+        }
+    }
+
+    /// Keeps a copy of `frame`, dropping the oldest one once the ring is full.
+    pub fn record(&mut self, frame: &[u8]) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        if self.frames.len() == RING_CAPACITY {
+            self.frames.pop_front();
+        }
+        let len: usize = frame.len().min(SNAP_LEN);
+        self.frames
+            .push_back((SystemTime::now(), frame[..len].to_vec()));
+    }
+
+    /// Notes that a received frame could not be parsed, writing the ring out
+    /// when this completes a spike.
+    pub fn parse_error(&mut self) {
+        if self.is_spike(Instant::now()) {
+            self.dump(&format!(
+                "{SPIKE_THRESHOLD} frames failed to parse within {}s",
+                SPIKE_WINDOW.as_secs()
+            ));
+        }
+    }
+
+    /// Writes the ring to a new pcap file and logs where it went, once per ring.
+    pub fn dump(&mut self, reason: &str) {
+        if self.dumped || self.frames.is_empty() {
+            return;
+        }
+        self.dumped = true;
+
+        match self.write() {
+            Ok(path) => warn!(
+                "{reason} on {}, saved the last {} frames to {} for a bug report",
+                self.scanner,
+                self.frames.len(),
+                path.display()
+            ),
+            Err(e) => warn!(
+                "{reason} on {}, but the frames could not be saved: {e}",
+                self.scanner
+            ),
+        }
+    }
+
+    /// Returns `true` for the parse error that first completes a spike.
+    fn is_spike(&mut self, now: Instant) -> bool {
+        while self
+            .parse_errors
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > SPIKE_WINDOW)
+        {
+            self.parse_errors.pop_front();
+        }
+        self.parse_errors.push_back(now);
+        !self.dumped && self.parse_errors.len() == SPIKE_THRESHOLD
+    }
+
+    fn write(&self) -> anyhow::Result<PathBuf> {
+        let dir: PathBuf = paths::data_dir()
+            .ok_or_else(|| anyhow::anyhow!("no data directory"))?
+            .join(CAPTURE_DIR);
+        fs::create_dir_all(&dir)?;
+
+        let label: String = self
+            .scanner
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        // Named after the run when it was stamped, so the dump can be matched
+        // with its report and logs.
+        let run: String = match ScanInfo::session() {
+            Some(scan) => scan.id.clone(),
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                .to_string(),
+        };
+        let path: PathBuf = dir.join(format!("{label}-{run}.pcap"));
+        fs::write(&path, to_pcap(self.link_type, &self.frames))?;
+        Ok(path)
+    }
+}
+
+/// Encodes `frames` as a classic little-endian pcap file.
+fn to_pcap(link_type: LinkType, frames: &VecDeque<(SystemTime, Vec<u8>)>) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    out.extend_from_slice(&0xA1B2_C3D4u32.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes());
+    out.extend_from_slice(&0i32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&(SNAP_LEN as u32).to_le_bytes());
+    out.extend_from_slice(&link_type.code().to_le_bytes());
+
+    for (at, frame) in frames {
+        let since_epoch: Duration = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        out.extend_from_slice(&(since_epoch.as_secs() as u32).to_le_bytes());
+        out.extend_from_slice(&since_epoch.subsec_micros().to_le_bytes());
+        out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        out.extend_from_slice(frame);
+    }
+    out
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_keeps_latest_frames_as_pcap() {
+        ENABLED.store(true, Ordering::Relaxed);
+        let mut ring: FrameRing = FrameRing::new("routed-eth0", LinkType::RawIp);
+        ring.record(&[0; 1500]);
+        assert_eq!(ring.frames[0].1.len(), SNAP_LEN);
+
+        for n in 0..=RING_CAPACITY {
+            ring.record(&[n as u8; 20]);
+        }
+        assert_eq!(ring.frames.len(), RING_CAPACITY);
+        assert_eq!(ring.frames[0].1[0], 1);
+
+        let pcap: Vec<u8> = to_pcap(ring.link_type, &ring.frames);
+        assert_eq!(pcap[..4], [0xD4, 0xC3, 0xB2, 0xA1]);
+        assert_eq!(pcap[20..24], 101u32.to_le_bytes());
+        assert_eq!(pcap.len(), 24 + RING_CAPACITY * (16 + 20));
+        assert_eq!(pcap[24 + 8..24 + 12], 20u32.to_le_bytes());
+    }
+
+    #[test]
+    fn only_dense_parse_errors_are_a_spike() {
+        let mut ring: FrameRing = FrameRing::new("local-eth0", LinkType::Ethernet);
+        let start: Instant = Instant::now();
+
+        // Spread out errors never pile up.
+        for n in 0..SPIKE_THRESHOLD as u64 * 2 {
+            assert!(!ring.is_spike(start + Duration::from_millis(n * 500)));
+        }
+
+        let later: Instant = start + Duration::from_secs(60);
+        let spikes: usize = (0..SPIKE_THRESHOLD * 2)
+            .filter(|_| ring.is_spike(later))
+            .count();
+        assert_eq!(spikes, 1);
+    }
+}
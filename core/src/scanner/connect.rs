@@ -7,6 +7,7 @@
 use std::collections::HashMap;
 
 use std::collections::HashSet;
+use std::io;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
@@ -14,18 +15,27 @@ use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio::task::JoinSet;
+use tokio::time::error::Elapsed;
 use tokio::time::timeout;
+use zond_common::config::Timing;
 use zond_common::models::host::Host;
 use zond_common::models::ip::set::IpSet;
 use zond_common::models::port::{Port, PortSet, PortState, Protocol};
+use zond_common::models::reachability::Method;
 use zond_common::models::target::{Target, TargetMap, TargetSet};
 
 use super::STOP_SIGNAL;
 use super::dispatcher::Dispatcher;
+use crate::error::ZondError;
 use crate::scanner::increment_host_count;
 
 /// Most common ports across Linux, Windows, and Networking gear.
-const DISCOVERY_PORTS: &[u16] = &[22, 80, 443, 445, 3389];
+pub(super) const DISCOVERY_PORTS: &[u16] = &[22, 80, 443, 445, 3389];
+/// Connections [`discover`] keeps in flight at once.
+pub(super) const DISCOVERY_CONCURRENCY: usize = 2048;
+/// How long a connection attempt may take before the port counts as silent,
+/// unless calibration picked another [`Timing`].
+pub(super) const PROBE_TIMEOUT: Duration = Timing::DEFAULT.probe_timeout;
 
 /// Performs a high-concurrency, unprivileged port scan.
 ///
@@ -36,7 +46,8 @@ const DISCOVERY_PORTS: &[u16] = &[22, 80, 443, 445, 3389];
 pub async fn scan(
     mut rx: mpsc::Receiver<Target>,
     concurrency_limit: usize,
-) -> anyhow::Result<Vec<Host>> {
+    timing: Timing,
+) -> Result<Vec<Host>, ZondError> {
     let mut set = JoinSet::new();
     let mut results_map: HashMap<IpAddr, Host> = HashMap::new();
 
@@ -52,7 +63,7 @@ pub async fn scan(
             }
         }
 
-        set.spawn(async move { port_prober(target).await });
+        set.spawn(async move { port_prober(target, timing).await });
     }
 
     while let Some(Ok(Ok(Some((ip, port))))) = set.join_next().await {
@@ -67,16 +78,25 @@ pub async fn scan(
 ///
 /// Currently supports standard full TCP connect handshakes.
 /// Returns An `Ok(Some((IpAddr, Port)))` if a non-closed port is discovered.
-async fn port_prober(target: Target) -> anyhow::Result<Option<(IpAddr, Port)>> {
+async fn port_prober(target: Target, timing: Timing) -> anyhow::Result<Option<(IpAddr, Port)>> {
     if target.protocol == Protocol::Udp {
-        // UDP isn't natively handled by standard TCP streams, gracefully skip or assume closed for now.
-        return Ok(None);
+        // Only UDP services with a dedicated probe can be told apart from silence.
+        let found: Option<String> = match target.port {
+            zond_plugins::dns::DNS_PORT => {
+                zond_plugins::dns::probe_udp(target.ip, target.port).await
+            }
+            _ => zond_plugins::iot::probe_udp(target.ip, target.port).await,
+        };
+        return Ok(found.map(|info| {
+            let port: Port = Port::new(target.port, Protocol::Udp, PortState::Open);
+            (target.ip, port.with_banner(&info))
+        }));
     }
 
     let socket_addr = SocketAddr::new(target.ip, target.port);
-    let probe_timeout = Duration::from_millis(1000);
 
-    match timeout(probe_timeout, TcpStream::connect(socket_addr)).await {
+    let (_, outcome) = connect(socket_addr, timing).await;
+    match outcome {
         Ok(Ok(stream)) => {
             let mut port = Port::new(target.port, Protocol::Tcp, PortState::Open);
             port.service_info = zond_plugins::lookup_service_name(target.port, Protocol::Tcp);
@@ -119,11 +139,10 @@ async fn port_prober(target: Target) -> anyhow::Result<Option<(IpAddr, Port)>> {
 ///   has already been confirmed alive by a parallel task.
 /// - **Randomized**: Target distribution is handled by a shuffling [`Dispatcher`]
 ///   to minimize local network congestion.
-/// - **Fidelity Range**: Uses an adjustable 1000ms timeout window to capture
-///   hosts on high-latency or geographically distant links.
-pub async fn discover(ips: IpSet) -> anyhow::Result<Vec<Host>> {
-    const CONCURRENCY_LIMIT: usize = 2048;
-
+/// - **Fidelity Range**: Waits for the [`Timing`] timeout, 1000ms unless
+///   calibrated, to capture hosts on high-latency or geographically distant
+///   links, and retries silent ports as often as it allows.
+pub async fn discover(ips: IpSet, timing: Timing) -> Result<Vec<Host>, ZondError> {
     // 1. Prepare Target Map for all IP x Common Port combinations
     let mut target_map = TargetMap::new();
     let port_set = PortSet::try_from(
@@ -133,7 +152,8 @@ pub async fn discover(ips: IpSet) -> anyhow::Result<Vec<Host>> {
             .collect::<Vec<_>>()
             .join(",")
             .as_str(),
-    )?;
+    )
+    .map_err(|e| ZondError::InvalidTarget(e.to_string()))?;
     target_map.add_unit(TargetSet::new(ips, port_set));
 
     // 2. Setup Dispatcher and Shared State
@@ -149,14 +169,14 @@ pub async fn discover(ips: IpSet) -> anyhow::Result<Vec<Host>> {
             break;
         }
 
-        while set.len() >= CONCURRENCY_LIMIT {
+        while set.len() >= DISCOVERY_CONCURRENCY {
             if let Some(Ok(Ok(Some(host)))) = set.join_next().await {
                 hosts.push(host);
             }
         }
 
         let inner_found = Arc::clone(&found_hosts);
-        set.spawn(async move { prober(target, inner_found).await });
+        set.spawn(async move { prober(target, inner_found, timing).await });
     }
 
     // 4. Final Collection
@@ -176,6 +196,7 @@ pub async fn discover(ips: IpSet) -> anyhow::Result<Vec<Host>> {
 async fn prober(
     target: Target,
     found_set: Arc<Mutex<HashSet<IpAddr>>>,
+    timing: Timing,
 ) -> anyhow::Result<Option<Host>> {
     // 1. Early exit if already discovered
     {
@@ -186,16 +207,16 @@ async fn prober(
     }
 
     let socket_addr: SocketAddr = SocketAddr::new(target.ip, target.port);
-    let probe_timeout: Duration = Duration::from_millis(1000);
 
-    let start: Instant = Instant::now();
-    match timeout(probe_timeout, TcpStream::connect(socket_addr)).await {
+    let (start, outcome) = connect(socket_addr, timing).await;
+    match outcome {
         Ok(Ok(_)) => {
             // 2. Successful handshake -> Host is alive
             let mut set = found_set.lock().unwrap();
             if set.insert(target.ip) {
                 increment_host_count();
-                let host: Host = Host::new(target.ip).with_rtt(start.elapsed());
+                let mut host: Host = Host::new(target.ip).with_rtt(start.elapsed());
+                host.reachability.answered(target.ip, Method::Tcp);
                 Ok(Some(host))
             } else {
                 Ok(None)
@@ -211,7 +232,8 @@ async fn prober(
                     let mut set = found_set.lock().unwrap();
                     if set.insert(target.ip) {
                         increment_host_count();
-                        let host: Host = Host::new(target.ip).with_rtt(start.elapsed());
+                        let mut host: Host = Host::new(target.ip).with_rtt(start.elapsed());
+                        host.reachability.answered(target.ip, Method::Tcp);
                         Ok(Some(host))
                     } else {
                         Ok(None)
@@ -226,3 +248,26 @@ async fn prober(
         Err(_elapsed) => Ok(None),
     }
 }
+
+/// Connects to `addr`, trying again up to [`Timing::retries`] times while the
+/// attempts time out. Returns when the last attempt started and how it ended.
+async fn connect(
+    addr: SocketAddr,
+    timing: Timing,
+) -> (Instant, Result<io::Result<TcpStream>, Elapsed>) {
+    let mut attempt: u8 = 0;
+    loop {
+        let start: Instant = Instant::now();
+        match timeout(timing.probe_timeout, TcpStream::connect(addr)).await {
+            Err(_) if attempt < timing.retries && !STOP_SIGNAL.load(Ordering::Relaxed) => {
+                attempt += 1;
+            }
+            outcome => {
+                if outcome.is_err() {
+                    super::record_timeout();
+                }
+                return (start, outcome);
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Receive windows that follow the round trips observed so far.
+//!
+//! A fixed wait after the last probe is too long for a switched LAN, where
+//! every answer is back within milliseconds, and too short for a congested
+//! Wi-Fi or mesh network. Scanners feed every round trip they time into an
+//! [`AdaptiveWindow`], which asks them to keep listening for [`WINDOW_FACTOR`]
+//! times the 95th percentile after the last probe went out, within bounds each
+//! scanner picks. Until [`MIN_SAMPLES`] replies were timed, the scanner's fixed
+//! window applies.
+//!
+//! Round trips are counted in buckets a quarter octave wide, so the percentile
+//! costs the same however many hosts answer and errs by at most a fifth, on the
+//! long side.
+
+use std::time::Duration;
+
+/// The window leaves this many times the slowest typical answer.
+const WINDOW_FACTOR: u32 = 4;
+/// Replies needed before the percentile is trusted.
+const MIN_SAMPLES: u64 = 5;
+const BUCKETS_PER_OCTAVE: f64 = 4.0;
+/// Enough octaves of microseconds to hold round trips of up to 16 s.
+const BUCKETS: usize = 4 * 24;
+
+/// Round trips timed so far and the receive window they call for.
+pub(super) struct AdaptiveWindow {
+    counts: [u64; BUCKETS],
+    total: u64,
+    min: Duration,
+    max: Duration,
+}
+
+impl AdaptiveWindow {
+    /// Creates a window that never ends sooner than `min` or later than `max`
+    /// after the last probe.
+    pub(super) fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            counts: [0; BUCKETS],
+            total: 0,
+            min,
+            max,
+        }
+    }
+
+    pub(super) fn record(&mut self, rtt: Duration) {
+        self.counts[bucket(rtt)] += 1;
+        self.total += 1;
+    }
+
+    /// The round trip 95% of the replies beat, rounded up to its bucket.
+    pub(super) fn p95(&self) -> Option<Duration> {
+        if self.total < MIN_SAMPLES {
+            return None;
+        }
+        let rank: u64 = (self.total * 95).div_ceil(100);
+        let mut seen: u64 = 0;
+        self.counts.iter().enumerate().find_map(|(idx, count)| {
+            seen += count;
+            (seen >= rank).then(|| upper_bound(idx))
+        })
+    }
+
+    /// How long to listen after the last probe, `None` until enough replies
+    /// were timed.
+    pub(super) fn window(&self) -> Option<Duration> {
+        Some((self.p95()? * WINDOW_FACTOR).clamp(self.min, self.max))
+    }
+}
+
+fn bucket(rtt: Duration) -> usize {
+    let micros: f64 = rtt.as_micros().max(1) as f64;
+    ((micros.log2() * BUCKETS_PER_OCTAVE) as usize).min(BUCKETS - 1)
+}
+
+fn upper_bound(idx: usize) -> Duration {
+    let micros: f64 = ((idx + 1) as f64 / BUCKETS_PER_OCTAVE).exp2();
+    Duration::from_secs_f64(micros / 1e6)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_follows_the_95th_percentile_within_bounds() {
+        let mut window: AdaptiveWindow =
+            AdaptiveWindow::new(Duration::from_millis(500), Duration::from_secs(5));
+        for _ in 0..4 {
+            window.record(Duration::from_millis(200));
+        }
+        assert_eq!(window.window(), None);
+
+        for _ in 0..95 {
+            window.record(Duration::from_millis(200));
+        }
+        window.record(Duration::from_secs(3));
+        let p95: Duration = window.p95().unwrap();
+        assert!(p95 >= Duration::from_millis(200) && p95 < Duration::from_millis(240));
+        assert_eq!(window.window(), Some(p95 * 4));
+
+        let mut lan: AdaptiveWindow =
+            AdaptiveWindow::new(Duration::from_millis(500), Duration::from_secs(5));
+        (0..10).for_each(|_| lan.record(Duration::from_micros(800)));
+        assert_eq!(lan.window(), Some(Duration::from_millis(500)));
+
+        let mut mesh: AdaptiveWindow =
+            AdaptiveWindow::new(Duration::from_millis(500), Duration::from_secs(5));
+        (0..10).for_each(|_| mesh.record(Duration::from_secs(2)));
+        assert_eq!(mesh.window(), Some(Duration::from_secs(5)));
+    }
+}
@@ -59,9 +59,11 @@ impl Dispatcher {
                     if batch.len() >= self.batch_size {
                         batch.shuffle(&mut rand::rng());
                         for t in batch.drain(..) {
+                            super::wait_while_paused().await;
                             if tx.send(t).await.is_err() || STOP_SIGNAL.load(Ordering::Relaxed) {
                                 return;
                             }
+                            super::record_probe();
                         }
                     }
                 }
@@ -71,9 +73,11 @@ impl Dispatcher {
             if !batch.is_empty() {
                 batch.shuffle(&mut rand::rng());
                 for t in batch {
+                    super::wait_while_paused().await;
                     if tx.send(t).await.is_err() || STOP_SIGNAL.load(Ordering::Relaxed) {
                         return;
                     }
+                    super::record_probe();
                 }
             }
         });
@@ -0,0 +1,486 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Enrichment Pipeline
+//!
+//! Once discovery or a port scan is done, the hosts found pass through an
+//! ordered [`Pipeline`] of [`EnrichmentStage`]s, each adding what it can find
+//! out about them. Every stage runs under its own timeout and is timed, and
+//! any of them can be turned off by name through
+//! [`ZondConfig::disabled_enrichers`].
+//!
+//! Hostnames, mDNS names and vendors are not stages: lookups for them start
+//! the moment a host answers, while the scan is still running.
+//!
+//! | Stage       | Runs after | Adds                                              |
+//! |-------------|------------|---------------------------------------------------|
+//! | `self`      | discovery  | [`NetworkRole::Scanner`] for this machine         |
+//! | `leases`    | discovery  | names and MACs from local DHCP lease files        |
+//! | `names`     | discovery  | names imported from SSH `known_hosts`             |
+//! | `addresses` | discovery  | further IPs per host over mDNS, NetBIOS and NDP   |
+//! | `ad`        | discovery  | domain controllers, only with `--detect-ad`       |
+//! | `roles`     | port scan  | IoT, camera and DNS roles from open ports         |
+//! | `printers`  | port scan  | printer model and location over IPP or JetDirect  |
+//! | `plugins`   | both       | `zond-enrich-*` programs, only with `--plugins`   |
+//! | `device`    | both       | device type and platform from hints and roles     |
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tracing::Span;
+use zond_common::config::ZondConfig;
+use zond_common::models::device;
+use zond_common::models::host::{Host, NetworkRole};
+use zond_common::{debug, phase, warn};
+
+use super::{STOP_SIGNAL, ad, addresses, leases, names, plugins, printer};
+
+/// Names of the built-in stages, in the order they run.
+pub const STAGES: [&str; 9] = [
+    "self",
+    "leases",
+    "names",
+    "addresses",
+    "ad",
+    "roles",
+    "printers",
+    "plugins",
+    "device",
+];
+
+/// One step of a [`Pipeline`].
+#[async_trait]
+pub trait EnrichmentStage: Send + Sync {
+    /// Name used in logs and in [`ZondConfig::disabled_enrichers`].
+    fn name(&self) -> &'static str;
+
+    /// Upper bound on the run time of [`enrich`](Self::enrich). Hosts keep
+    /// whatever the stage added before it ran out.
+    fn timeout(&self) -> Duration;
+
+    /// Whether the stage sends packets. Those are skipped once the user
+    /// stopped the scan, while purely local stages still run.
+    fn probes(&self) -> bool;
+
+    async fn enrich(&self, hosts: &mut [Host]);
+}
+
+/// How one stage of a [`Pipeline::run`] went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageMetrics {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Completed,
+    TimedOut,
+    /// Not run, because the scan was stopped.
+    Skipped,
+}
+
+/// An ordered list of enrichment stages.
+pub struct Pipeline {
+    stages: Vec<Box<dyn EnrichmentStage>>,
+    disabled: Vec<String>,
+}
+
+impl Pipeline {
+    /// Starts an empty pipeline that leaves out the stages disabled in `cfg`.
+    pub fn new(cfg: &ZondConfig) -> Self {
+        Self {
+            stages: Vec::new(),
+            disabled: cfg.disabled_enrichers.clone(),
+        }
+    }
+
+    /// The stages run after discovery.
+    pub fn discovery(cfg: &ZondConfig) -> Self {
+        let pipeline: Self = Self::new(cfg)
+            .with(SelfLabel)
+            .with(Leases)
+            .with(ImportedNames)
+            .with(SecondaryAddresses);
+        let pipeline: Self = match cfg.detect_ad {
+            true => pipeline.with(DomainControllers),
+            false => pipeline,
+        };
+        pipeline.with_plugins(cfg).with(DeviceTypes)
+    }
+
+    /// The stages run after a port scan.
+    pub fn port_scan(cfg: &ZondConfig) -> Self {
+        Self::new(cfg)
+            .with(Roles)
+            .with(Printers)
+            .with_plugins(cfg)
+            .with(DeviceTypes)
+    }
+
+    /// Appends the external enrichers, if `cfg` opted into them. They run
+    /// programs found on `PATH`, often as root.
+    fn with_plugins(self, cfg: &ZondConfig) -> Self {
+        match cfg.plugins {
+            true => self.with(Plugins),
+            false => self,
+        }
+    }
+
+    /// Appends `stage`, unless it was disabled.
+    pub fn with(mut self, stage: impl EnrichmentStage + 'static) -> Self {
+        if !self.disabled.iter().any(|name| name == stage.name()) {
+            self.stages.push(Box::new(stage));
+        }
+        self
+    }
+
+    /// Names of the stages that will run, in order.
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Runs every stage over `hosts` in turn.
+    pub async fn run(&self, hosts: &mut [Host]) -> Vec<StageMetrics> {
+        if hosts.is_empty() {
+            return Vec::new();
+        }
+
+        let _phase: Span = phase!("enrichment", hosts = hosts.len());
+        let mut metrics: Vec<StageMetrics> = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            let name: &'static str = stage.name();
+            if stage.probes() && STOP_SIGNAL.load(Ordering::Relaxed) {
+                debug!(
+                    verbosity = 1,
+                    "Skipping enrichment stage {name}, scan stopped"
+                );
+                metrics.push(StageMetrics {
+                    name,
+                    elapsed: Duration::ZERO,
+                    outcome: Outcome::Skipped,
+                });
+                continue;
+            }
+
+            let _stage_phase: Span = phase!("enrichment stage", stage = name);
+            let started: Instant = Instant::now();
+            let outcome: Outcome =
+                match tokio::time::timeout(stage.timeout(), stage.enrich(hosts)).await {
+                    Ok(()) => Outcome::Completed,
+                    Err(_) => Outcome::TimedOut,
+                };
+            let elapsed: Duration = started.elapsed();
+            match outcome {
+                Outcome::TimedOut => warn!(
+                    "Enrichment stage {name} timed out after {}s",
+                    stage.timeout().as_secs()
+                ),
+                _ => debug!(
+                    verbosity = 1,
+                    "Enrichment stage {name} took {}ms",
+                    elapsed.as_millis()
+                ),
+            }
+            metrics.push(StageMetrics {
+                name,
+                elapsed,
+                outcome,
+            });
+        }
+        metrics
+    }
+}
+
+/// Tags the hosts answering from an address of this machine.
+struct SelfLabel;
+
+#[async_trait]
+impl EnrichmentStage for SelfLabel {
+    fn name(&self) -> &'static str {
+        "self"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        super::label_self(hosts);
+    }
+}
+
+/// Completes hosts from the lease files of a local DHCP server.
+struct Leases;
+
+#[async_trait]
+impl EnrichmentStage for Leases {
+    fn name(&self) -> &'static str {
+        "leases"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        leases::merge_into(hosts);
+    }
+}
+
+/// Names hosts DNS left unnamed from the names imported with `zond import`.
+struct ImportedNames;
+
+#[async_trait]
+impl EnrichmentStage for ImportedNames {
+    fn name(&self) -> &'static str {
+        "names"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        names::merge_into(hosts);
+    }
+}
+
+/// Asks every host for the other addresses it holds.
+struct SecondaryAddresses;
+
+#[async_trait]
+impl EnrichmentStage for SecondaryAddresses {
+    fn name(&self) -> &'static str {
+        "addresses"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn probes(&self) -> bool {
+        true
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        addresses::enumerate(hosts).await;
+    }
+}
+
+/// Tags Active Directory domain controllers.
+struct DomainControllers;
+
+#[async_trait]
+impl EnrichmentStage for DomainControllers {
+    fn name(&self) -> &'static str {
+        "ad"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn probes(&self) -> bool {
+        true
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        ad::tag_domain_controllers(hosts).await;
+    }
+}
+
+/// Derives network roles from the open ports of each host.
+struct Roles;
+
+#[async_trait]
+impl EnrichmentStage for Roles {
+    fn name(&self) -> &'static str {
+        "roles"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        for host in hosts.iter_mut() {
+            if host.ports().iter().any(zond_plugins::iot::is_iot) {
+                host.network_roles.insert(NetworkRole::Iot);
+            }
+            if host.ports().iter().any(zond_plugins::rtsp::is_camera) {
+                host.network_roles.insert(NetworkRole::Camera);
+            }
+            if host.ports().iter().any(zond_plugins::dns::is_dns) {
+                host.network_roles.insert(NetworkRole::DNS);
+            }
+        }
+    }
+}
+
+/// Identifies printers by their IPP or JetDirect answers.
+struct Printers;
+
+#[async_trait]
+impl EnrichmentStage for Printers {
+    fn name(&self) -> &'static str {
+        "printers"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn probes(&self) -> bool {
+        true
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        printer::identify_printers(hosts).await;
+    }
+}
+
+/// Hands the hosts to external enrichers, see [`plugins`].
+struct Plugins;
+
+#[async_trait]
+impl EnrichmentStage for Plugins {
+    fn name(&self) -> &'static str {
+        "plugins"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        plugins::enrich(hosts).await;
+    }
+}
+
+/// Classifies hosts with [`device::classify`] and [`device::platform`].
+struct DeviceTypes;
+
+#[async_trait]
+impl EnrichmentStage for DeviceTypes {
+    fn name(&self) -> &'static str {
+        "device"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn probes(&self) -> bool {
+        false
+    }
+
+    async fn enrich(&self, hosts: &mut [Host]) {
+        for host in hosts.iter_mut() {
+            if let Some(device_type) = device::classify(host) {
+                host.device_type = Some(device_type);
+            }
+            if let Some(platform) = device::platform(host) {
+                host.platform = Some(platform);
+            }
+        }
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+
+    struct Stall;
+
+    #[async_trait]
+    impl EnrichmentStage for Stall {
+        fn name(&self) -> &'static str {
+            "stall"
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(10)
+        }
+
+        fn probes(&self) -> bool {
+            false
+        }
+
+        async fn enrich(&self, hosts: &mut [Host]) {
+            hosts[0].hostname = Some("partial".to_string());
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    }
+
+    #[test]
+    fn disabled_stages_are_left_out() {
+        let cfg: ZondConfig = ZondConfig {
+            disabled_enrichers: vec!["leases".to_string(), "printers".to_string()],
+            detect_ad: true,
+            plugins: true,
+            ..ZondConfig::default()
+        };
+        assert_eq!(
+            Pipeline::discovery(&cfg).stage_names(),
+            ["self", "names", "addresses", "ad", "plugins", "device"]
+        );
+        assert_eq!(
+            Pipeline::port_scan(&cfg).stage_names(),
+            ["roles", "plugins", "device"]
+        );
+        assert_eq!(
+            Pipeline::discovery(&ZondConfig::default()).stage_names(),
+            ["self", "leases", "names", "addresses", "device"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stalled_stage_times_out_keeping_its_results() {
+        let mut hosts: Vec<Host> = vec![Host::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))];
+        let metrics: Vec<StageMetrics> = Pipeline::new(&ZondConfig::default())
+            .with(Stall)
+            .run(&mut hosts)
+            .await;
+
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].outcome, Outcome::TimedOut);
+        assert_eq!(hosts[0].hostname.as_deref(), Some("partial"));
+    }
+}
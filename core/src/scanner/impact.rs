@@ -0,0 +1,230 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Impact Estimates
+//!
+//! Predicts what a scan puts on the wire before it starts: how many probes,
+//! roughly how long it takes and which interfaces carry it. The estimate
+//! partitions targets the way [`discover_groups`](super::discover_groups) does
+//! and applies the pacing of each scanner, assuming nobody answers. Early exits
+//! on responding hosts only make the real scan shorter.
+//!
+//! A [`Plan`] shows the same partition target by target, for `--plan`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use pnet::datalink::NetworkInterface;
+use pnet::ipnetwork::IpNetwork;
+use zond_common::config::{Technique, ZondConfig};
+use zond_common::models::ip::set::IpSet;
+use zond_common::models::target::TargetMap;
+use zond_common::net::interface;
+use zond_common::parse::DiscoveryOverride;
+use zond_common::utils::privilege;
+
+use super::{PORT_SCAN_CONCURRENCY, connect, local, routed};
+
+/// Probes per second assumed for routed scanners. They send without pacing, so
+/// this is a conservative figure for raw socket sends rather than a limit.
+const ROUTED_SEND_RATE: u64 = 10_000;
+
+/// What a scan is expected to cost.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Impact {
+    /// Target addresses.
+    pub addresses: u64,
+    /// Probes sent, without retries and follow-ups.
+    pub packets: u64,
+    pub duration: Duration,
+    /// Names of the interfaces probes leave through.
+    pub interfaces: BTreeSet<String>,
+    /// Whether some targets are probed with TCP connects instead of raw packets.
+    pub connect: bool,
+}
+
+/// How a discovery splits its targets across interfaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Interfaces probes leave through, by name.
+    pub interfaces: BTreeMap<String, InterfacePlan>,
+    /// Targets no interface reaches, left to TCP connects and the OS routing.
+    pub unmapped: u64,
+    /// Whether raw sockets are unavailable, so every target gets TCP connects.
+    pub connect_only: bool,
+}
+
+/// The targets one interface probes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfacePlan {
+    /// Addresses of the interface.
+    pub networks: Vec<IpNetwork>,
+    /// Targets on the interface's own subnets.
+    pub local: u64,
+    /// Targets behind a gateway the interface leads to.
+    pub routed: u64,
+}
+
+/// Partitions `groups` across interfaces like [`discover_groups`](super::discover_groups).
+pub fn plan(groups: &[(DiscoveryOverride, IpSet)]) -> Plan {
+    let mut plan: Plan = Plan {
+        connect_only: !privilege::has_raw_socket_access(),
+        ..Plan::default()
+    };
+
+    for (_, ips) in groups {
+        let (interface_map, unmapped) = interface::map_ips_to_interfaces(ips.clone());
+        for (intf, (local_ips, routed_ips)) in interface_map {
+            let NetworkInterface { name, ips, .. } = intf;
+            let entry: &mut InterfacePlan =
+                plan.interfaces
+                    .entry(name)
+                    .or_insert_with(|| InterfacePlan {
+                        networks: ips,
+                        ..InterfacePlan::default()
+                    });
+            entry.local += local_ips.len();
+            entry.routed += routed_ips.len();
+        }
+        plan.unmapped += unmapped.len();
+    }
+
+    plan
+}
+
+/// Estimates a discovery of `groups` as [`discover_groups`](super::discover_groups)
+/// runs it.
+pub fn discovery(groups: &[(DiscoveryOverride, IpSet)], cfg: &ZondConfig) -> Impact {
+    let raw_sockets: bool = privilege::has_raw_socket_access();
+    let mut impact: Impact = Impact {
+        duration: cfg.passive_warmup.unwrap_or_default(),
+        ..Impact::default()
+    };
+
+    for (settings, ips) in groups {
+        impact.addresses += ips.len();
+        if !raw_sockets {
+            impact.duration += impact.connect_fallback(ips.len());
+            continue;
+        }
+
+        let probes_per_ip: u64 = match (settings.technique, settings.syn_port) {
+            (Technique::Icmp, _) | (_, Some(_)) => 1,
+            _ => cfg.syn_ports.len().max(1) as u64,
+        };
+        let (interface_map, unmapped) = interface::map_ips_to_interfaces(ips.clone());
+
+        // Interfaces and the fallback run in parallel, scanners on the same
+        // interface in turn.
+        let mut slowest: Duration = Duration::ZERO;
+        for (intf, (local_ips, routed_ips)) in interface_map {
+            let (local_count, routed_count): (u64, u64) = match settings.technique {
+                Technique::Auto | Technique::Arp => (local_ips.len(), routed_ips.len()),
+                Technique::Syn | Technique::Icmp => (0, local_ips.len() + routed_ips.len()),
+            };
+            let routed_probes: u64 = routed_count * probes_per_ip;
+
+            let mut duration: Duration = Duration::ZERO;
+            if local_count > 0 {
+                duration += local_duration(local_count);
+            }
+            if routed_probes > 0 {
+                duration += routed_duration(routed_probes);
+            }
+            // The SCTP sweep takes every target of the interface once more.
+            let sctp_probes: u64 = match cfg.sctp && settings.technique != Technique::Arp {
+                true => {
+                    (local_ips.len() + routed_ips.len()) * routed::DEFAULT_SCTP_PORTS.len() as u64
+                }
+                false => 0,
+            };
+            if sctp_probes > 0 {
+                duration += routed_duration(sctp_probes);
+            }
+            impact.packets += local_count + routed_probes + sctp_probes;
+            impact.interfaces.insert(intf.name);
+            slowest = slowest.max(duration);
+        }
+        if !unmapped.is_empty() {
+            slowest = slowest.max(impact.connect_fallback(unmapped.len()));
+        }
+        impact.duration += slowest;
+    }
+
+    impact
+}
+
+/// Estimates a port scan of `target_map`, which always uses TCP connects.
+pub fn port_scan(target_map: &TargetMap) -> Impact {
+    let packets: u64 = u64::try_from(target_map.total_targets()).unwrap_or(u64::MAX);
+    Impact {
+        addresses: target_map.total_ips() as u64,
+        packets,
+        duration: connect_duration(packets, PORT_SCAN_CONCURRENCY),
+        interfaces: BTreeSet::new(),
+        connect: true,
+    }
+}
+
+impl Impact {
+    /// Counts the probes of `addresses` handed to the TCP connect fallback and
+    /// returns how long it takes.
+    fn connect_fallback(&mut self, addresses: u64) -> Duration {
+        let attempts: u64 = addresses * connect::DISCOVERY_PORTS.len() as u64;
+        self.packets += attempts;
+        self.connect = true;
+        connect_duration(attempts, connect::DISCOVERY_CONCURRENCY)
+    }
+}
+
+/// The LAN scanner sends one frame per interval and gives up after a fixed time.
+fn local_duration(targets: u64) -> Duration {
+    let sending: Duration = local::SEND_INTERVAL_US.saturating_mul(saturate(targets));
+    (sending + local::MAX_SILENCE_MS).clamp(local::MIN_CHANNEL_TIME, local::MAX_CHANNEL_TIME)
+}
+
+fn routed_duration(probes: u64) -> Duration {
+    let sending: Duration = Duration::from_secs_f64(probes as f64 / ROUTED_SEND_RATE as f64);
+    sending + routed::receive_window(usize::try_from(probes).unwrap_or(usize::MAX))
+}
+
+/// Connect attempts run in waves of `concurrency`, each bounded by the timeout.
+fn connect_duration(attempts: u64, concurrency: usize) -> Duration {
+    let waves: u64 = attempts.div_ceil(concurrency as u64);
+    connect::PROBE_TIMEOUT.saturating_mul(saturate(waves))
+}
+
+fn saturate(count: u64) -> u32 {
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_follow_scanner_pacing() {
+        assert_eq!(local_duration(1), local::MIN_CHANNEL_TIME);
+        assert_eq!(local_duration(1_000_000), local::MAX_CHANNEL_TIME);
+
+        // A /8 with one SYN per address.
+        let sweep: Duration = routed_duration(1 << 24);
+        assert!(sweep > Duration::from_secs(1_600) && sweep < Duration::from_secs(1_700));
+
+        assert_eq!(connect_duration(0, 50), Duration::ZERO);
+        assert_eq!(connect_duration(50, 50), connect::PROBE_TIMEOUT);
+        assert_eq!(connect_duration(51, 50), connect::PROBE_TIMEOUT * 2);
+    }
+}
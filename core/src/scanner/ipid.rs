@@ -0,0 +1,249 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! IP ID sequence analysis.
+//!
+//! Many stacks (Windows, most embedded devices) fill the IPv4 identification
+//! field from one global counter. Sampling it across several replies reveals two
+//! things a single probe cannot:
+//!
+//! * **Dual-homed hosts**: two addresses whose replies, taken in arrival order,
+//!   continue one counter are one machine. Only addresses whose samples
+//!   *interleave* both ways are linked, each with at least [`MIN_SAMPLES`]
+//!   replies; an unrelated host landing inside the tiny windows between the
+//!   samples of another again and again is very unlikely.
+//! * **NAT devices**: one address answering with different TTLs and IP IDs that
+//!   do not form a single sequence has different machines behind it.
+//!
+//! No extra probes are sent for this: the samples are the replies to the
+//! sweep itself, so only sweeps over several ports sample a responder often
+//! enough.
+
+use std::{collections::HashMap, net::IpAddr, time::Instant};
+
+/// Fewest replies of an address that tell how it fills the IP ID field.
+const MIN_SAMPLES: usize = 3;
+
+/// Largest step between consecutive samples that still counts as one counter.
+/// Leaves room for other traffic the host sends between our probes.
+const MAX_STEP: u16 = 1024;
+
+/// Largest step between interleaved samples of two addresses linked as one host.
+const MAX_LINK_STEP: u16 = 64;
+
+/// The IP ID and TTL of one reply.
+#[derive(Debug, Clone, Copy)]
+pub struct IpIdSample {
+    pub at: Instant,
+    pub id: u16,
+    pub ttl: u8,
+}
+
+/// How a host fills the IP ID field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpIdPattern {
+    /// The same value every time, typically `0` alongside the DF flag.
+    Constant,
+    /// A counter that grows by small steps.
+    Incremental,
+    /// Unrelated values, as from a randomized or per-flow generator.
+    Random,
+}
+
+/// The conclusions drawn from the samples of one sweep.
+#[derive(Debug, Default)]
+pub struct IpIdReport {
+    /// Addresses that are likely NAT devices.
+    pub nat: Vec<IpAddr>,
+    /// Sets of addresses that belong to one machine, each with at least two members.
+    pub same_host: Vec<Vec<IpAddr>>,
+}
+
+/// Classifies the samples of one address, in arrival order.
+///
+/// Returns `None` with fewer than [`MIN_SAMPLES`] samples.
+pub fn classify(samples: &[IpIdSample]) -> Option<IpIdPattern> {
+    if samples.len() < MIN_SAMPLES {
+        return None;
+    }
+    if samples.windows(2).all(|pair| pair[0].id == pair[1].id) {
+        return Some(IpIdPattern::Constant);
+    }
+    if is_sequence(samples.iter().map(|s| s.id), MAX_STEP) {
+        return Some(IpIdPattern::Incremental);
+    }
+    Some(IpIdPattern::Random)
+}
+
+/// Analyzes the samples collected per address during one sweep.
+pub fn analyze(samples: &HashMap<IpAddr, Vec<IpIdSample>>) -> IpIdReport {
+    let mut report: IpIdReport = IpIdReport::default();
+
+    let mut incremental: Vec<(IpAddr, &[IpIdSample])> = Vec::new();
+    for (ip, samples) in samples {
+        match classify(samples) {
+            Some(IpIdPattern::Incremental) => incremental.push((*ip, samples)),
+            Some(IpIdPattern::Random) if has_mixed_ttls(samples) => report.nat.push(*ip),
+            _ => {}
+        }
+    }
+    incremental.sort_by_key(|(ip, _)| *ip);
+    report.nat.sort();
+
+    // Each address joins the first group it shares a counter with.
+    let mut group_of: Vec<Option<usize>> = vec![None; incremental.len()];
+    for i in 0..incremental.len() {
+        for j in (i + 1)..incremental.len() {
+            if group_of[j].is_some() || !shares_counter(incremental[i].1, incremental[j].1) {
+                continue;
+            }
+            let group: usize = *group_of[i].get_or_insert_with(|| {
+                report.same_host.push(vec![incremental[i].0]);
+                report.same_host.len() - 1
+            });
+            report.same_host[group].push(incremental[j].0);
+            group_of[j] = Some(group);
+        }
+    }
+
+    report
+}
+
+/// Returns `true` if two incremental addresses continue one counter.
+///
+/// Both must answer with one TTL throughout, a sample of each must fall
+/// between two samples of the other, and all samples taken in arrival order
+/// must grow by small steps.
+fn shares_counter(a: &[IpIdSample], b: &[IpIdSample]) -> bool {
+    let ttl: u8 = a[0].ttl;
+    if a.iter().chain(b).any(|s| s.ttl != ttl) {
+        return false;
+    }
+
+    let mut merged: Vec<(Instant, u16, bool)> = a
+        .iter()
+        .map(|s| (s.at, s.id, true))
+        .chain(b.iter().map(|s| (s.at, s.id, false)))
+        .collect();
+    merged.sort_by_key(|(at, _, _)| *at);
+
+    let interleaved = |outer: bool| {
+        merged
+            .windows(3)
+            .any(|w| w[0].2 == outer && w[2].2 == outer && w[1].2 != outer)
+    };
+    interleaved(true)
+        && interleaved(false)
+        && is_sequence(merged.iter().map(|(_, id, _)| *id), MAX_LINK_STEP)
+}
+
+/// Returns `true` if every value follows its predecessor by `1..=max_step`,
+/// allowing for the counter to wrap around.
+fn is_sequence(ids: impl Iterator<Item = u16>, max_step: u16) -> bool {
+    let ids: Vec<u16> = ids.collect();
+    ids.windows(2).all(|pair| {
+        let step: u16 = pair[1].wrapping_sub(pair[0]);
+        (1..=max_step).contains(&step)
+    })
+}
+
+fn has_mixed_ttls(samples: &[IpIdSample]) -> bool {
+    samples.iter().any(|s| s.ttl != samples[0].ttl)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::Ipv4Addr, time::Duration};
+
+    fn samples(start: Instant, points: &[(u64, u16, u8)]) -> Vec<IpIdSample> {
+        points
+            .iter()
+            .map(|&(ms, id, ttl)| IpIdSample {
+                at: start + Duration::from_millis(ms),
+                id,
+                ttl,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classifies_counters() {
+        let now: Instant = Instant::now();
+        assert_eq!(classify(&samples(now, &[(0, 7, 64), (5, 8, 64)])), None);
+        assert_eq!(
+            classify(&samples(now, &[(0, 0, 64), (5, 0, 64), (10, 0, 64)])),
+            Some(IpIdPattern::Constant)
+        );
+        assert_eq!(
+            classify(&samples(
+                now,
+                &[(0, 65_530, 128), (5, 3, 128), (10, 9, 128)]
+            )),
+            Some(IpIdPattern::Incremental)
+        );
+        assert_eq!(
+            classify(&samples(
+                now,
+                &[(0, 4_000, 64), (5, 61_000, 64), (10, 9, 64)]
+            )),
+            Some(IpIdPattern::Random)
+        );
+    }
+
+    #[test]
+    fn links_interleaved_counters_and_flags_nat() {
+        let now: Instant = Instant::now();
+        let ip = |last: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, last));
+
+        let mut map: HashMap<IpAddr, Vec<IpIdSample>> = HashMap::new();
+        map.insert(
+            ip(1),
+            samples(now, &[(0, 500, 128), (20, 510, 128), (40, 520, 128)]),
+        );
+        map.insert(
+            ip(2),
+            samples(now, &[(10, 505, 128), (30, 514, 128), (50, 530, 128)]),
+        );
+        // Incremental, but sampled after the other two and far off.
+        map.insert(
+            ip(3),
+            samples(now, &[(60, 9_000, 128), (70, 9_002, 128), (80, 9_004, 128)]),
+        );
+        map.insert(
+            ip(4),
+            samples(now, &[(0, 12_345, 64), (10, 40_000, 128), (20, 7, 64)]),
+        );
+
+        let report: IpIdReport = analyze(&map);
+        assert_eq!(report.same_host, vec![vec![ip(1), ip(2)]]);
+        assert_eq!(report.nat, vec![ip(4)]);
+    }
+
+    #[test]
+    fn links_need_interleaving_both_ways() {
+        let now: Instant = Instant::now();
+        // One reply of `b` between those of `a` is no proof of a shared counter.
+        let a: Vec<IpIdSample> = samples(now, &[(0, 100, 64), (20, 104, 64), (30, 106, 64)]);
+        let b: Vec<IpIdSample> = samples(now, &[(10, 102, 64), (40, 108, 64), (50, 110, 64)]);
+        assert!(!shares_counter(&a, &b));
+
+        let b: Vec<IpIdSample> = samples(now, &[(10, 102, 64), (25, 105, 64), (40, 108, 64)]);
+        assert!(shares_counter(&a, &b));
+
+        let b: Vec<IpIdSample> = samples(now, &[(10, 102, 64), (25, 105, 128), (40, 108, 64)]);
+        assert!(!shares_counter(&a, &b));
+    }
+}
@@ -0,0 +1,461 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! IP protocol scan: which transport protocols a host, or the firewall in
+//! front of it, lets through.
+//!
+//! Like nmap's `-sO`, [`survey`] sends the target one raw IPv4 packet per
+//! protocol number. TCP, UDP, ICMP and SCTP probes carry a valid header of
+//! their protocol; any other protocol goes out with an empty payload.
+//!
+//! * An answer in the probed protocol shows the target supports it. So does
+//!   an ICMP port unreachable from the target itself, since only a handler of
+//!   the protocol knows about ports.
+//! * An ICMP protocol unreachable from the target means its stack lacks the
+//!   protocol.
+//! * Any other unreachable message, or one sent by a router, means something
+//!   on the way filters it.
+//! * Silence proves nothing: stacks drop ESP or GRE they have no tunnel for
+//!   without a word, just like a firewall would.
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex, atomic::Ordering},
+    time::Duration,
+};
+
+use pnet::{
+    datalink::NetworkInterface,
+    packet::{Packet, ip::IpNextHeaderProtocol, ipv4::Ipv4Packet},
+};
+use tokio::{sync::mpsc, time::Instant};
+use zond_common::{debug, models::ip::set::IpSet, net::interface, utils::privilege, warn};
+use zond_protocols as protocol;
+
+use crate::error::ZondError;
+use crate::network::transport::{self, RawPacket, TransportHandle, TransportSender, TransportType};
+
+/// Protocols probed when the user did not pick any.
+pub const DEFAULT_PROTOCOLS: [u8; 5] = [TCP, UDP, GRE, ESP, SCTP];
+
+const ICMP: u8 = 1;
+const TCP: u8 = 6;
+const UDP: u8 = 17;
+const GRE: u8 = 47;
+const ESP: u8 = 50;
+const SCTP: u8 = 132;
+/// Socket protocol the probes are sent through; it can send any protocol
+/// with a header of our own but receives nothing.
+const IPPROTO_RAW: u8 = 255;
+
+/// Names accepted for and shown with protocol numbers.
+const NAMES: [(u8, &str); 14] = [
+    (ICMP, "icmp"),
+    (2, "igmp"),
+    (4, "ipip"),
+    (TCP, "tcp"),
+    (UDP, "udp"),
+    (41, "ipv6"),
+    (GRE, "gre"),
+    (ESP, "esp"),
+    (51, "ah"),
+    (89, "ospf"),
+    (103, "pim"),
+    (112, "vrrp"),
+    (SCTP, "sctp"),
+    (136, "udplite"),
+];
+
+/// Rounds of probes; the second only goes to protocols nothing answered for.
+const ATTEMPTS: usize = 2;
+/// How long answers are awaited after each round.
+const LISTEN_WINDOW: Duration = Duration::from_millis(1500);
+const PROBE_TTL: u8 = 64;
+/// Destination of TCP and SCTP probes. Open or closed, the port answers.
+const STREAM_PORT: u16 = 80;
+/// Destination of UDP probes, the first traceroute port, which is hardly ever
+/// open and so draws a port unreachable.
+const DATAGRAM_PORT: u16 = 33_434;
+
+/// What the answers, or their absence, say about one protocol.
+///
+/// Ordered by certainty, so a later answer only ever sharpens the verdict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtocolState {
+    /// Nothing came back: supported but quiet, or dropped on the way.
+    Silent,
+    /// A router, or the target itself, refused to deliver it.
+    Filtered,
+    /// The target answered with an ICMP protocol unreachable.
+    Unsupported,
+    /// The target answered in the protocol, or a handler of it refused the probe.
+    Supported,
+}
+
+/// The verdict on one protocol number.
+#[derive(Debug, Clone)]
+pub struct ProtocolResult {
+    pub number: u8,
+    pub state: ProtocolState,
+    /// Sender of the ICMP message the verdict rests on, if not the target.
+    pub reporter: Option<Ipv4Addr>,
+}
+
+impl ProtocolResult {
+    /// The usual name of the protocol, if it has one.
+    pub fn name(&self) -> Option<&'static str> {
+        name(self.number)
+    }
+}
+
+/// What [`survey`] found out about one target.
+#[derive(Debug, Clone)]
+pub struct ProtocolSurvey {
+    pub target: Ipv4Addr,
+    /// Interface the probes left through.
+    pub interface: String,
+    /// One verdict per probed protocol, in the order asked for.
+    pub results: Vec<ProtocolResult>,
+}
+
+/// Returns the usual name of protocol `number`, if it has one.
+pub fn name(number: u8) -> Option<&'static str> {
+    NAMES
+        .iter()
+        .find(|(known, _)| *known == number)
+        .map(|(_, name)| *name)
+}
+
+/// Parses a protocol given by name (`gre`) or number (`47`).
+pub fn parse(input: &str) -> Result<u8, String> {
+    let input: String = input.trim().to_ascii_lowercase();
+    if let Ok(number) = input.parse::<u8>() {
+        return Ok(number);
+    }
+    NAMES
+        .iter()
+        .find(|(_, name)| *name == input)
+        .map(|(number, _)| *number)
+        .ok_or_else(|| format!("unknown IP protocol '{input}', give a name or a number"))
+}
+
+/// Sends `target` one packet per protocol in `protocols` and sorts the
+/// protocols by the answers.
+///
+/// ### Errors
+/// - [`ZondError::PermissionDenied`] without raw socket access.
+/// - [`ZondError::InvalidTarget`] if no interface reaches `target`, or it is
+///   on the never-scan list.
+/// - The error of opening a capture or the sender.
+pub async fn survey(target: Ipv4Addr, protocols: &[u8]) -> Result<ProtocolSurvey, ZondError> {
+    super::reset_stop_signal();
+    if !privilege::has_raw_socket_access() {
+        return Err(ZondError::PermissionDenied(
+            "an IP protocol scan sends raw packets, run as root".to_string(),
+        ));
+    }
+
+    let mut targets: IpSet = IpSet::new();
+    targets.insert(IpAddr::V4(target));
+    super::enforce_denylist(&target.to_string(), &targets)?;
+    let (interface_map, _) = interface::map_ips_to_interfaces(targets);
+    let Some(intf) = interface_map.into_keys().next() else {
+        return Err(ZondError::InvalidTarget(format!(
+            "{target} is not reachable through any interface"
+        )));
+    };
+    let src_addr: Ipv4Addr = source_address(&intf, target)?;
+
+    let probes: Vec<Probe> = protocols
+        .iter()
+        .map(|number| Probe::new(*number, src_addr, target))
+        .collect::<anyhow::Result<_>>()
+        .map_err(|e| ZondError::ChannelError(e.to_string()))?;
+
+    let sender: Arc<Mutex<TransportSender>> =
+        transport::open_sender(TransportType::IpLayer3(IPPROTO_RAW))?;
+    let mut replies: mpsc::UnboundedReceiver<Vec<u8>> = capture_replies(protocols)?;
+
+    let mut results: Vec<ProtocolResult> = protocols
+        .iter()
+        .map(|number| ProtocolResult {
+            number: *number,
+            state: ProtocolState::Silent,
+            reporter: None,
+        })
+        .collect();
+
+    for _ in 0..ATTEMPTS {
+        for (probe, result) in probes.iter().zip(&results) {
+            if result.state != ProtocolState::Silent {
+                continue;
+            }
+            super::wait_while_paused().await;
+            let sent: std::io::Result<usize> = sender
+                .lock()
+                .unwrap()
+                .send_to(RawPacket(&probe.packet), IpAddr::V4(target));
+            match sent {
+                Ok(_) => super::record_probe(),
+                Err(e) => debug!(
+                    verbosity = 1,
+                    "Failed to send protocol {} probe to {target}: {e}", probe.number
+                ),
+            }
+        }
+
+        let deadline: Instant = Instant::now() + LISTEN_WINDOW;
+        while results.iter().any(|r| r.state == ProtocolState::Silent)
+            && !super::STOP_SIGNAL.load(Ordering::Relaxed)
+        {
+            let Ok(Some(packet)) = tokio::time::timeout_at(deadline, replies.recv()).await else {
+                break;
+            };
+            if let Some((number, state, reporter)) = classify(&probes, target, &packet)
+                && let Some(result) = results.iter_mut().find(|r| r.number == number)
+                && state > result.state
+            {
+                result.state = state;
+                result.reporter = reporter;
+            }
+        }
+        if super::STOP_SIGNAL.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    Ok(ProtocolSurvey {
+        target,
+        interface: intf.name,
+        results,
+    })
+}
+
+/// A probe for one protocol, with what its answers are told apart by.
+struct Probe {
+    number: u8,
+    /// The whole IPv4 packet.
+    packet: Vec<u8>,
+    /// Source port of TCP, UDP and SCTP probes, identifier of ICMP probes.
+    src_port: u16,
+}
+
+impl Probe {
+    fn new(number: u8, src_addr: Ipv4Addr, dst_addr: Ipv4Addr) -> anyhow::Result<Self> {
+        let src_port: u16 = rand::random_range(super::routed::DEFAULT_SOURCE_PORTS);
+        let payload: Vec<u8> = match number {
+            ICMP => protocol::icmp::create_echo_request_v4(src_port, rand::random())?,
+            TCP => protocol::tcp::create_packet(
+                &IpAddr::V4(src_addr),
+                &IpAddr::V4(dst_addr),
+                src_port,
+                STREAM_PORT,
+                rand::random(),
+            )?,
+            UDP => protocol::udp::create_packet(src_port, DATAGRAM_PORT, Vec::new())?,
+            SCTP => protocol::sctp::create_init_packet(
+                src_port,
+                STREAM_PORT,
+                rand::random_range(1..=u32::MAX),
+            )?,
+            _ => Vec::new(),
+        };
+        let packet: Vec<u8> = protocol::ip::create_ipv4_packet(
+            src_addr,
+            dst_addr,
+            IpNextHeaderProtocol::new(number),
+            PROBE_TTL,
+            &payload,
+        )?;
+        Ok(Self {
+            number,
+            packet,
+            src_port,
+        })
+    }
+}
+
+/// The address of `intf` in the subnet of `target`, else its first IPv4
+/// address.
+fn source_address(intf: &NetworkInterface, target: Ipv4Addr) -> Result<Ipv4Addr, ZondError> {
+    let v4: Vec<(Ipv4Addr, bool)> = intf
+        .ips
+        .iter()
+        .filter_map(|net| match net.ip() {
+            IpAddr::V4(ip) => Some((ip, net.contains(IpAddr::V4(target)))),
+            IpAddr::V6(_) => None,
+        })
+        .collect();
+    v4.iter()
+        .find(|(_, on_link)| *on_link)
+        .or(v4.first())
+        .map(|(ip, _)| *ip)
+        .ok_or_else(|| ZondError::InterfaceNotFound(format!("{} has no IPv4 address", intf.name)))
+}
+
+/// Captures IPv4 packets carrying ICMP or any of `protocols` and funnels them
+/// into one queue.
+fn capture_replies(protocols: &[u8]) -> Result<mpsc::UnboundedReceiver<Vec<u8>>, ZondError> {
+    let mut captured: Vec<u8> = vec![ICMP];
+    captured.extend(protocols.iter().filter(|number| **number != ICMP));
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    for number in captured {
+        let mut handle: TransportHandle =
+            transport::start_packet_capture(TransportType::IpLayer3(number))?;
+        let tx: mpsc::UnboundedSender<Vec<u8>> = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match handle.rx.recv().await {
+                    Some((packet, _, _)) => {
+                        if tx.send(packet).is_err() {
+                            break;
+                        }
+                    }
+                    None => {
+                        if let Err(e) = handle.recover() {
+                            warn!("{e}, protocol {number} answers are lost");
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+    Ok(rx)
+}
+
+/// Reads the verdict on one of `probes` from a captured IPv4 packet, along
+/// with the router that sent it, if not the target.
+fn classify(
+    probes: &[Probe],
+    target: Ipv4Addr,
+    packet: &[u8],
+) -> Option<(u8, ProtocolState, Option<Ipv4Addr>)> {
+    let ipv4: Ipv4Packet = Ipv4Packet::new(packet)?;
+    let sender: Ipv4Addr = ipv4.get_source();
+    let number: u8 = ipv4.get_next_level_protocol().0;
+    let payload: &[u8] = ipv4.payload();
+    let probe = |number: u8| probes.iter().find(|probe| probe.number == number);
+
+    if number == ICMP {
+        if let Some(quote) = protocol::icmp::parse_unreachable_quote_v4(payload) {
+            if quote.target != target {
+                return None;
+            }
+            let probe: &Probe = probe(quote.protocol)?;
+            let from_target: bool = sender == target;
+            let state: ProtocolState = match quote.code {
+                2 if from_target => ProtocolState::Unsupported,
+                3 if from_target => ProtocolState::Supported,
+                0..=3 | 9 | 10 | 13 => ProtocolState::Filtered,
+                _ => return None,
+            };
+            return Some((probe.number, state, (!from_target).then_some(sender)));
+        }
+        let (identifier, _) = protocol::icmp::parse_echo_reply_v4(payload)?;
+        let probe: &Probe = probe(ICMP)?;
+        return (sender == target && identifier == probe.src_port).then_some((
+            ICMP,
+            ProtocolState::Supported,
+            None,
+        ));
+    }
+
+    if sender != target {
+        return None;
+    }
+    let probe: &Probe = probe(number)?;
+    // TCP, UDP and SCTP all start with the two ports; the answer goes back to
+    // the port the probe came from.
+    if matches!(number, TCP | UDP | SCTP) {
+        let dst_port: u16 = u16::from_be_bytes(payload.get(2..4)?.try_into().ok()?);
+        if dst_port != probe.src_port {
+            return None;
+        }
+    }
+    Some((number, ProtocolState::Supported, None))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCANNER: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 2);
+    const TARGET: Ipv4Addr = Ipv4Addr::new(203, 0, 113, 7);
+    const ROUTER: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 1);
+
+    fn ipv4(src: Ipv4Addr, dst: Ipv4Addr, number: u8, payload: &[u8]) -> Vec<u8> {
+        protocol::ip::create_ipv4_packet(src, dst, IpNextHeaderProtocol::new(number), 64, payload)
+            .unwrap()
+    }
+
+    fn unreachable(reporter: Ipv4Addr, code: u8, probe: &Probe) -> Vec<u8> {
+        let mut icmp: Vec<u8> = vec![3, code, 0, 0, 0, 0, 0, 0];
+        icmp.extend_from_slice(&probe.packet[..probe.packet.len().min(28)]);
+        ipv4(reporter, SCANNER, ICMP, &icmp)
+    }
+
+    #[test]
+    fn answers_sort_protocols() {
+        let probes: Vec<Probe> = [TCP, UDP, GRE, ESP, SCTP]
+            .into_iter()
+            .map(|number| Probe::new(number, SCANNER, TARGET).unwrap())
+            .collect();
+        let [tcp, udp, gre, esp, sctp] = &probes[..] else {
+            unreachable!()
+        };
+
+        let mut rst: Vec<u8> = STREAM_PORT.to_be_bytes().to_vec();
+        rst.extend_from_slice(&tcp.src_port.to_be_bytes());
+        rst.resize(20, 0);
+        assert_eq!(
+            classify(&probes, TARGET, &ipv4(TARGET, SCANNER, TCP, &rst)),
+            Some((TCP, ProtocolState::Supported, None))
+        );
+        // A segment of another connection to the target proves nothing.
+        rst[2..4].copy_from_slice(&tcp.src_port.wrapping_add(1).to_be_bytes());
+        assert_eq!(
+            classify(&probes, TARGET, &ipv4(TARGET, SCANNER, TCP, &rst)),
+            None
+        );
+
+        assert_eq!(
+            classify(&probes, TARGET, &unreachable(TARGET, 3, udp)),
+            Some((UDP, ProtocolState::Supported, None))
+        );
+        assert_eq!(
+            classify(&probes, TARGET, &unreachable(TARGET, 2, gre)),
+            Some((GRE, ProtocolState::Unsupported, None))
+        );
+        assert_eq!(
+            classify(&probes, TARGET, &unreachable(ROUTER, 13, esp)),
+            Some((ESP, ProtocolState::Filtered, Some(ROUTER)))
+        );
+        assert_eq!(
+            classify(&probes, TARGET, &unreachable(ROUTER, 2, sctp)),
+            Some((SCTP, ProtocolState::Filtered, Some(ROUTER)))
+        );
+    }
+
+    #[test]
+    fn protocols_parse_by_name_or_number() {
+        assert_eq!(parse("GRE"), Ok(GRE));
+        assert_eq!(parse("50"), Ok(ESP));
+        assert!(parse("carrier-pigeon").is_err());
+        assert_eq!(name(SCTP), Some("sctp"));
+        assert_eq!(name(253), None);
+    }
+}
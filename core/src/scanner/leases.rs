@@ -0,0 +1,376 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Hostnames and MAC addresses from the lease files of a local DHCP server.
+//!
+//! When zond runs on the router or homelab box that hands out addresses, its
+//! DHCP server already knows the name and MAC address of every client. The
+//! lease files of dnsmasq, Kea and ISC dhcpd are read if present, and hosts
+//! found by discovery are completed from them and labelled with
+//! [`DataSource::DhcpLease`].
+//!
+//! Leases only fill in what discovery did not find. A lease whose MAC address
+//! differs from the one that answered is stale and ignored, and leased
+//! addresses that did not answer are not added as hosts.
+//!
+//! OpenWrt keeps its leases in `/tmp`, where any local user could plant a file
+//! of that name on other systems. A lease file is only read if it is not
+//! writable by others, and one in a directory open to everyone only if root
+//! owns it.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    net::{IpAddr, Ipv4Addr},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use pnet::util::MacAddr;
+use zond_common::{
+    debug,
+    models::host::{DataSource, Host},
+    utils::mac,
+};
+
+/// Lease files of dnsmasq, including OpenWrt's location.
+const DNSMASQ_LEASES: [&str; 3] = [
+    "/var/lib/misc/dnsmasq.leases",
+    "/var/lib/dnsmasq/dnsmasq.leases",
+    "/tmp/dhcp.leases",
+];
+/// Memfile lease files of Kea's DHCPv4 server.
+const KEA_LEASES: [&str; 2] = ["/var/lib/kea/kea-leases4.csv", "/var/lib/kea/dhcp4.leases"];
+/// Lease files of ISC dhcpd on Debian, Red Hat and the BSDs.
+const ISC_LEASES: [&str; 3] = [
+    "/var/lib/dhcp/dhcpd.leases",
+    "/var/lib/dhcpd/dhcpd.leases",
+    "/var/db/dhcpd.leases",
+];
+
+/// A DHCPv4 lease that is still valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lease {
+    ip: Ipv4Addr,
+    mac: MacAddr,
+    hostname: Option<String>,
+}
+
+/// Completes `hosts` from the lease files found on this machine.
+pub fn merge_into(hosts: &mut [Host]) {
+    let leases: HashMap<Ipv4Addr, Lease> = read_leases();
+    if leases.is_empty() {
+        return;
+    }
+
+    let mut merged: usize = 0;
+    for host in hosts.iter_mut() {
+        let lease: Option<&Lease> = host.ips.iter().find_map(|ip| match ip {
+            IpAddr::V4(v4) => leases.get(v4),
+            IpAddr::V6(_) => None,
+        });
+        let Some(lease) = lease else {
+            continue;
+        };
+        if host.mac.is_some_and(|mac| mac != lease.mac) {
+            continue;
+        }
+
+        if host.mac.is_none() {
+            host.mac = Some(lease.mac);
+            host.vendor = mac::get_vendor(lease.mac);
+        }
+        if host.hostname.is_none() {
+            host.hostname = lease.hostname.clone();
+        }
+        host.sources.insert(DataSource::DhcpLease);
+        merged += 1;
+    }
+    debug!(
+        verbosity = 1,
+        "Matched {merged} of {} local DHCP lease(s) to discovered hosts",
+        leases.len()
+    );
+}
+
+/// Reads every known lease file, later files and entries overriding earlier ones.
+fn read_leases() -> HashMap<Ipv4Addr, Lease> {
+    let now: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let sources: [(&[&str], fn(&str, u64) -> Vec<Lease>); 3] = [
+        (&DNSMASQ_LEASES, parse_dnsmasq),
+        (&KEA_LEASES, parse_kea),
+        (&ISC_LEASES, |content, _| parse_isc(content)),
+    ];
+
+    let mut leases: HashMap<Ipv4Addr, Lease> = HashMap::new();
+    for (paths, parse) in sources {
+        for path in paths {
+            if !is_guarded(Path::new(path)) {
+                debug!(
+                    verbosity = 1,
+                    "Ignoring DHCP leases in {path}, others can write it"
+                );
+                continue;
+            }
+            match fs::read_to_string(path) {
+                Ok(content) => leases.extend(
+                    parse(&content, now)
+                        .into_iter()
+                        .map(|lease| (lease.ip, lease)),
+                ),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => debug!(verbosity = 1, "Could not read DHCP leases from {path}: {e}"),
+            }
+        }
+    }
+    leases
+}
+
+/// Returns `false` if a user other than the DHCP server could have written
+/// `path`: it is writable by others, or lies in a directory writable by others
+/// without belonging to root. Missing files pass, reading them fails anyway.
+#[cfg(unix)]
+fn is_guarded(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(meta) = fs::metadata(path) else {
+        return true;
+    };
+    let shared_dir: bool = path
+        .parent()
+        .and_then(|dir| fs::metadata(dir).ok())
+        .is_none_or(|dir| dir.mode() & 0o002 != 0);
+    meta.mode() & 0o002 == 0 && (!shared_dir || meta.uid() == 0)
+}
+
+#[cfg(not(unix))]
+fn is_guarded(_path: &Path) -> bool {
+    true
+}
+
+/// Parses dnsmasq's `EXPIRY MAC IP HOSTNAME CLIENT-ID` lines.
+///
+/// An expiry of `0` never expires. DHCPv6 lines carry no MAC address and are
+/// skipped along with the `duid` line.
+fn parse_dnsmasq(content: &str, now: u64) -> Vec<Lease> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [expiry, mac, ip, hostname, ..] = fields[..] else {
+                return None;
+            };
+            let expiry: u64 = expiry.parse().ok()?;
+            if expiry != 0 && expiry <= now {
+                return None;
+            }
+            Some(Lease {
+                ip: ip.parse().ok()?,
+                mac: mac.parse().ok()?,
+                hostname: hostname_field(hostname, "*"),
+            })
+        })
+        .collect()
+}
+
+/// Parses Kea's CSV lease file, locating columns by its header.
+///
+/// The file is only ever appended to, so a later row for an address replaces
+/// an earlier one. Rows in any state other than `0` (assigned) are dropped.
+fn parse_kea(content: &str, now: u64) -> Vec<Lease> {
+    let mut lines = content.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let column = |name: &str| header.split(',').position(|field| field.trim() == name);
+    let (Some(address), Some(hwaddr), Some(expire)) =
+        (column("address"), column("hwaddr"), column("expire"))
+    else {
+        return Vec::new();
+    };
+    let (hostname, state) = (column("hostname"), column("state"));
+
+    let mut leases: HashMap<Ipv4Addr, Option<Lease>> = HashMap::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        let field = |idx: usize| fields.get(idx).map(|value| value.trim());
+        let Some(ip) = field(address).and_then(|ip| ip.parse::<Ipv4Addr>().ok()) else {
+            continue;
+        };
+
+        let active: bool = state.and_then(field).is_none_or(|state| state == "0")
+            && field(expire)
+                .and_then(|expire| expire.parse::<u64>().ok())
+                .is_some_and(|expire| expire > now);
+        let lease: Option<Lease> = field(hwaddr)
+            .and_then(|mac| mac.parse().ok())
+            .filter(|_| active)
+            .map(|mac| Lease {
+                ip,
+                mac,
+                hostname: hostname
+                    .and_then(field)
+                    .and_then(|name| hostname_field(name, "")),
+            });
+        leases.insert(ip, lease);
+    }
+    leases.into_values().flatten().collect()
+}
+
+/// Parses the `lease IP { ... }` blocks of ISC dhcpd.
+///
+/// A later block for an address replaces an earlier one. Only leases in the
+/// `active` binding state are kept, since dhcpd keeps expired and released
+/// leases around for reuse.
+fn parse_isc(content: &str) -> Vec<Lease> {
+    let mut leases: HashMap<Ipv4Addr, Option<Lease>> = HashMap::new();
+    let mut current: Option<(Ipv4Addr, bool, Option<MacAddr>, Option<String>)> = None;
+
+    for line in content.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("lease ") {
+            current = rest
+                .trim_end_matches('{')
+                .trim()
+                .parse()
+                .ok()
+                .map(|ip| (ip, false, None, None));
+            continue;
+        }
+        let Some((ip, active, mac, hostname)) = &mut current else {
+            continue;
+        };
+
+        let statement: &str = line.trim_end_matches(';');
+        if statement == "}" {
+            let lease: Option<Lease> = mac.filter(|_| *active).map(|mac| Lease {
+                ip: *ip,
+                mac,
+                hostname: hostname.take(),
+            });
+            leases.insert(*ip, lease);
+            current = None;
+        } else if let Some(state) = statement.strip_prefix("binding state ") {
+            *active = state == "active";
+        } else if let Some(value) = statement.strip_prefix("hardware ethernet ") {
+            *mac = value.parse().ok();
+        } else if let Some(value) = statement.strip_prefix("client-hostname ") {
+            *hostname = hostname_field(value.trim_matches('"'), "");
+        }
+    }
+    leases.into_values().flatten().collect()
+}
+
+/// Returns the hostname of a lease, unless it is the server's `none` marker.
+fn hostname_field(value: &str, none: &str) -> Option<String> {
+    let value: &str = value.trim().trim_end_matches('.');
+    (!value.is_empty() && value != none).then(|| value.to_string())
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_800_000_000;
+    const MAC: MacAddr = MacAddr(0x2c, 0xcf, 0x67, 0xf2, 0x51, 0xe3);
+
+    fn lease(ip: [u8; 4], hostname: Option<&str>) -> Lease {
+        Lease {
+            ip: Ipv4Addr::from(ip),
+            mac: MAC,
+            hostname: hostname.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_dnsmasq_leases() {
+        let content: &str = "\
+            1800003600 2c:cf:67:f2:51:e3 192.168.1.20 nas 01:2c:cf:67:f2:51:e3\n\
+            0 2c:cf:67:f2:51:e3 192.168.1.21 * *\n\
+            1700000000 2c:cf:67:f2:51:e3 192.168.1.22 old *\n\
+            duid 00:01:00:01:2c:5d:3a:10:2c:cf:67:f2:51:e3\n\
+            1800003600 1234567 fd00::20 nas 00:01:00:01\n";
+        assert_eq!(
+            parse_dnsmasq(content, NOW),
+            vec![
+                lease([192, 168, 1, 20], Some("nas")),
+                lease([192, 168, 1, 21], None)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_kea_leases_keeping_the_latest_row() {
+        let content: &str = "\
+            address,hwaddr,client_id,valid_lifetime,expire,subnet_id,fqdn_fwd,fqdn_rev,hostname,state,user_context\n\
+            192.168.1.20,2c:cf:67:f2:51:e3,,3600,1800003600,1,0,0,nas.lan.,0,\n\
+            192.168.1.21,2c:cf:67:f2:51:e3,,3600,1800003600,1,0,0,,0,\n\
+            192.168.1.21,2c:cf:67:f2:51:e3,,0,1800003600,1,0,0,,2,\n\
+            192.168.1.22,2c:cf:67:f2:51:e3,,3600,1700000000,1,0,0,old,0,\n";
+        assert_eq!(
+            parse_kea(content, NOW),
+            vec![lease([192, 168, 1, 20], Some("nas.lan"))]
+        );
+    }
+
+    #[test]
+    fn parses_active_isc_leases() {
+        let content: &str = r#"
+            # The format of this file is documented in the dhcpd.leases(5) manual page.
+            lease 192.168.1.20 {
+              starts 4 2026/10/15 08:00:00;
+              ends 4 2026/10/15 20:00:00;
+              binding state active;
+              next binding state free;
+              hardware ethernet 2c:cf:67:f2:51:e3;
+              client-hostname "nas";
+            }
+            lease 192.168.1.21 {
+              binding state active;
+              hardware ethernet 2c:cf:67:f2:51:e3;
+            }
+            lease 192.168.1.21 {
+              binding state free;
+              hardware ethernet 2c:cf:67:f2:51:e3;
+            }
+        "#;
+        assert_eq!(
+            parse_isc(content),
+            vec![lease([192, 168, 1, 20], Some("nas"))]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lease_files_others_can_write_are_ignored() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("zond-leases-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = dir.join("dhcp.leases");
+        fs::write(&path, "").unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(is_guarded(&path));
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o666)).unwrap();
+        assert!(!is_guarded(&path));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -15,7 +15,9 @@
 use std::{
     collections::{HashMap, HashSet},
     net::{IpAddr, Ipv6Addr},
+    panic::{self, AssertUnwindSafe},
     sync::atomic::Ordering,
+    thread,
     time::{Duration, Instant},
 };
 
@@ -26,17 +28,27 @@ use pnet::{
         Packet,
         arp::ArpPacket,
         ethernet::{EtherTypes, EthernetPacket},
+        icmpv6::{Icmpv6Packet, Icmpv6Types},
+        ip::IpNextHeaderProtocols,
+        ipv6::Ipv6Packet,
     },
     util::MacAddr,
 };
 
 use zond_common::{
-    error,
-    models::{host::Host, ip::set::IpSet},
+    config::Technique,
+    debug, error, info,
+    models::{
+        host::Host,
+        ip::set::IpSet,
+        reachability::{Family, Method},
+    },
+    net::interface,
     parse::IS_LAN_SCAN,
-    sender::{PacketType, SenderConfig},
-    success,
-    utils::timing::ScanTimer,
+    phase,
+    sender::{PacketType, PreflightIssue, SenderConfig},
+    utils::{mac, timing::ScanTimer},
+    warn,
 };
 
 use protocol::ethernet;
@@ -44,17 +56,39 @@ use tokio::{
     sync::mpsc::UnboundedSender,
     time::{Interval, Sleep},
 };
+use tracing::Span;
 use zond_protocols::{self as protocol, ip};
 
-use crate::network::channel::{self, EthernetHandle};
+use crate::error::ZondError;
+use crate::network::channel::{self, EthernetHandle, SendFailure};
 
 use super::NetworkExplorer;
+use super::arp_cache::ArpCache;
+use super::capture::{FrameRing, LinkType};
+use super::deadline::AdaptiveWindow;
+use super::trace::{self, PacketTrace};
 use async_trait::async_trait;
 
-const MAX_CHANNEL_TIME: Duration = Duration::from_millis(7_500);
-const MIN_CHANNEL_TIME: Duration = Duration::from_millis(2_500);
-const MAX_SILENCE_MS: Duration = Duration::from_millis(500);
-const SEND_INTERVAL_US: Duration = Duration::from_micros(1000);
+pub(super) const MAX_CHANNEL_TIME: Duration = Duration::from_millis(7_500);
+pub(super) const MIN_CHANNEL_TIME: Duration = Duration::from_millis(2_500);
+pub(super) const MAX_SILENCE_MS: Duration = Duration::from_millis(500);
+pub(super) const SEND_INTERVAL_US: Duration = Duration::from_micros(1000);
+/// Bounds of the wait after the last probe once replies were timed. Sleeping
+/// phones answer ARP late, so even a fast LAN gets a second.
+const MIN_ADAPTIVE_WINDOW: Duration = Duration::from_secs(1);
+const MAX_ADAPTIVE_WINDOW: Duration = Duration::from_secs(5);
+const INTERFACE_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+const SEND_RETRY_BACKOFF: Duration = Duration::from_millis(1);
+const MAX_SEND_RETRIES: u32 = 3;
+/// Replies of the same kind from the same host within this window count once.
+const DEDUP_WINDOW: Duration = Duration::from_millis(250);
+/// A MAC answering for more IPv4 addresses than this may be a proxy.
+const SPONGE_THRESHOLD: usize = 16;
+/// Share of the IPv4 targets no other host answered for that a proxy claims.
+const SPONGE_SHARE: f64 = 0.9;
+
+/// Who answered, from which address, and with what kind of frame.
+type ReplyKey = (MacAddr, IpAddr, u16);
 
 pub struct LocalScanner {
     hosts_map: HashMap<MacAddr, Host>,
@@ -63,6 +97,14 @@ pub struct LocalScanner {
     timer: ScanTimer,
     dns_tx: Option<UnboundedSender<IpAddr>>,
     rtt_map: HashMap<IpAddr, Instant>,
+    intf_name: String,
+    send_failures: usize,
+    last_replies: HashMap<ReplyKey, Instant>,
+    duplicate_replies: usize,
+    arp_cache: ArpCache,
+    trace: PacketTrace,
+    capture: FrameRing,
+    window: AdaptiveWindow,
 }
 
 #[async_trait]
@@ -72,10 +114,20 @@ impl NetworkExplorer for LocalScanner {
         let mut sending_finished = false;
 
         let mut send_interval: Interval = tokio::time::interval(SEND_INTERVAL_US);
+        let mut watch_interval: Interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + INTERFACE_WATCH_INTERVAL,
+            INTERFACE_WATCH_INTERVAL,
+        );
 
         let scan_deadline: Sleep = tokio::time::sleep(MAX_CHANNEL_TIME);
         tokio::pin!(scan_deadline);
 
+        let mut send_phase: Option<Span> = Some(phase!("send"));
+        let mut receive_phase: Option<Span> = None;
+        let mut paused_at: Option<Instant> = None;
+        let mut sent_at: Option<Instant> = None;
+        let mut capture_failure: Option<ZondError> = None;
+
         loop {
             if (!self.should_continue() && sending_finished)
                 || super::STOP_SIGNAL.load(Ordering::Relaxed)
@@ -86,54 +138,210 @@ impl NetworkExplorer for LocalScanner {
             tokio::select! {
                 pkt = self.eth_handle.rx.recv() => {
                     match pkt {
-                        Some(bytes) => _ = self.process_eth_packet(&bytes),
-                        None => break,
+                        Some((bytes, received)) => {
+                            self.handle_frame(&bytes, received);
+                            if let Some(deadline) = self.adaptive_deadline(sent_at) {
+                                scan_deadline.as_mut().reset(deadline);
+                            }
+                        }
+                        None => {
+                            if let Err(e) = self.eth_handle.recover() {
+                                capture_failure = Some(e);
+                                break;
+                            }
+                        }
                     }
                 }
 
                 _ = send_interval.tick(), if !sending_finished => {
+                    if super::is_paused() {
+                        paused_at.get_or_insert_with(Instant::now);
+                        continue;
+                    }
+                    if let Some(at) = paused_at.take() {
+                        // Time spent paused does not count against the scan.
+                        let paused: Duration = at.elapsed();
+                        self.timer.extend(paused);
+                        let deadline: tokio::time::Instant = scan_deadline.deadline() + paused;
+                        scan_deadline.as_mut().reset(deadline);
+                    }
                     match packet_iter.next() {
                         Some((packet, ip)) => {
-                            self.rtt_map.insert(ip, Instant::now());
-                            self.eth_handle.tx.send_to(&packet, None);
+                            self.trace.probe(ip, || trace::describe_ethernet(&packet));
+                            if let Some(sent) = self.send_probe(&packet).await? {
+                                self.rtt_map.insert(ip, sent);
+                            }
                         },
                         None => {
                             sending_finished = true;
+                            sent_at = Some(Instant::now());
+                            if let Some(deadline) = self.adaptive_deadline(sent_at) {
+                                scan_deadline.as_mut().reset(deadline);
+                            }
+                            send_phase = None;
+                            receive_phase = Some(phase!("receive"));
                         },
                     }
                 }
 
+                _ = watch_interval.tick() => {
+                    // Listing the interfaces blocks, so it stays off the runtime.
+                    let name: String = self.intf_name.clone();
+                    let current: Option<NetworkInterface> =
+                        tokio::task::spawn_blocking(move || interface::find_by_name(&name))
+                            .await?;
+                    if self.interface_changed(current)? && !sending_finished {
+                        // Replies to probes sent from the old addresses go astray,
+                        // so every target that has not answered is probed again.
+                        let answered: HashSet<IpAddr> = self
+                            .hosts_map
+                            .values()
+                            .flat_map(|host| host.ips.iter().copied())
+                            .collect();
+                        packet_iter = Box::new(
+                            protocol::eth_packet_iter(&self.sender_cfg)?
+                                .filter(move |(_, ip)| !answered.contains(ip)),
+                        );
+                    }
+                }
+
                 _ = &mut scan_deadline => break,
             }
         }
 
+        // Some drivers, Wi-Fi ones in particular, drop frames addressed to any
+        // MAC other than their own.
+        if let Some(probe_mac) = self.sender_cfg.probe_mac()
+            && self.hosts_map.is_empty()
+        {
+            warn!(
+                "No replies reached {probe_mac} on {}, the driver may drop frames for \
+                 other MAC addresses; try again without --random-mac",
+                self.intf_name
+            );
+        }
+
+        if self.send_failures > 0 {
+            warn!(
+                "{} probe(s) on {} could not be sent",
+                self.send_failures, self.intf_name
+            );
+        }
+
+        if self.duplicate_replies > 0 {
+            debug!(
+                verbosity = 1,
+                "Ignored {} duplicate replies on {}", self.duplicate_replies, self.intf_name
+            );
+        }
+
+        drop((send_phase, receive_phase));
+        self.trace.flush();
+        if let Some(e) = capture_failure {
+            return Err(e.into());
+        }
+        self.mark_silent();
+        self.collapse_sponges();
         Ok(self.hosts_map.drain().map(|(_, v)| v).collect())
     }
 }
 
+/// Returns `true` if the same reply was already seen within [`DEDUP_WINDOW`]
+/// before `received`, and records it as seen otherwise.
+///
+/// Chatty devices answer a single ARP request several times. Counting those
+/// retransmits again would skew the RTT history with replies to no probe and
+/// hand the same address to the resolver repeatedly. Suppressed replies leave
+/// the window where it is, so a host that keeps chattering still counts once
+/// per window.
+fn is_duplicate(
+    last_replies: &mut HashMap<ReplyKey, Instant>,
+    key: ReplyKey,
+    received: Instant,
+) -> bool {
+    match last_replies.get(&key) {
+        Some(previous) if received.saturating_duration_since(*previous) < DEDUP_WINDOW => true,
+        _ => {
+            last_replies.insert(key, received);
+            false
+        }
+    }
+}
+
+/// Returns `true` if `eth` carries an ICMPv6 Neighbor Advertisement, the only
+/// IPv6 frame that answers an NDP probe. Router advertisements, mDNS and other
+/// chatter show a host is there but not that it answered.
+fn is_neighbor_advert(eth: &EthernetPacket) -> bool {
+    Ipv6Packet::new(eth.payload())
+        .filter(|ipv6| ipv6.get_next_header() == IpNextHeaderProtocols::Icmpv6)
+        .and_then(|ipv6| Icmpv6Packet::new(ipv6.payload()).map(|icmp| icmp.get_icmpv6_type()))
+        == Some(Icmpv6Types::NeighborAdvert)
+}
+
+/// Returns `true` if a MAC that answered for `claimed` of `probed` IPv4
+/// targets, while other hosts answered for `others`, is a proxy.
+fn is_sponge(claimed: usize, others: usize, probed: usize) -> bool {
+    let unused: usize = probed.saturating_sub(others);
+    claimed > SPONGE_THRESHOLD && claimed as f64 >= SPONGE_SHARE * unused as f64
+}
+
+/// Warns about every [`PreflightIssue`] of `sender_cfg` before the first probe.
+///
+/// Packet types that cannot be sent at all are dropped, so the other one still
+/// runs instead of the whole scanner failing on its first packet.
+fn preflight(sender_cfg: &mut SenderConfig, intf_name: &str) {
+    for issue in sender_cfg.preflight() {
+        match issue {
+            PreflightIssue::MissingLinkLocal => {
+                warn!("{issue} on {intf_name}, skipping NDP discovery");
+                sender_cfg.remove_packet_type(PacketType::ICMPv6);
+            }
+            PreflightIssue::MissingIpv4(_) => {
+                warn!("{issue} on {intf_name}, skipping the ARP sweep");
+                sender_cfg.remove_packet_type(PacketType::ARP);
+            }
+            PreflightIssue::OffLinkTargets(..) => {
+                warn!("{issue} on {intf_name}, they will only answer through proxy ARP");
+            }
+        }
+    }
+}
+
 impl LocalScanner {
     pub fn new(
         intf: NetworkInterface,
         collection: IpSet,
+        technique: Technique,
+        arp_cache: ArpCache,
         dns_tx: Option<UnboundedSender<IpAddr>>,
     ) -> anyhow::Result<Self> {
         let eth_handle: EthernetHandle = channel::start_capture(&intf)?;
+        let intf_name: String = intf.name.clone();
         let timer: ScanTimer = ScanTimer::new(MAX_CHANNEL_TIME, MIN_CHANNEL_TIME, MAX_SILENCE_MS);
-        let ips_len: usize = collection.len() as usize;
 
         let mut sender_cfg: SenderConfig = SenderConfig::from(&intf);
         sender_cfg.add_packet_type(PacketType::ARP);
-        if IS_LAN_SCAN.load(Ordering::Relaxed) {
+        if IS_LAN_SCAN.load(Ordering::Relaxed) && technique != Technique::Arp {
             sender_cfg.add_packet_type(PacketType::ICMPv6);
         }
 
-        let mut target_ips: HashSet<IpAddr> = HashSet::new();
+        sender_cfg.add_target_set(collection);
 
-        for ip in collection.into_iter() {
-            target_ips.insert(ip);
+        let mut reused: usize = 0;
+        for (ip, mac) in arp_cache.snapshot() {
+            if sender_cfg.has_addr(&IpAddr::V4(ip)) {
+                sender_cfg.add_neighbor(ip, mac);
+                reused += 1;
+            }
+        }
+        if reused > 0 {
+            debug!(
+                verbosity = 1,
+                "Probing {reused} already resolved host(s) on {intf_name} with unicast ARP"
+            );
         }
 
-        sender_cfg.add_targets(target_ips);
+        preflight(&mut sender_cfg, &intf_name);
 
         Ok(Self {
             hosts_map: HashMap::new(),
@@ -141,21 +349,207 @@ impl LocalScanner {
             eth_handle,
             timer,
             dns_tx,
-            rtt_map: HashMap::with_capacity(ips_len),
+            rtt_map: HashMap::new(),
+            trace: PacketTrace::new(format!("arp/ndp on {intf_name}")),
+            capture: FrameRing::new(format!("local-{intf_name}"), LinkType::Ethernet),
+            intf_name,
+            send_failures: 0,
+            last_replies: HashMap::new(),
+            duplicate_replies: 0,
+            arp_cache,
+            window: AdaptiveWindow::new(MIN_ADAPTIVE_WINDOW, MAX_ADAPTIVE_WINDOW),
         })
     }
 
-    fn process_eth_packet(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
-        let eth_frame: EthernetPacket = ethernet::get_packet_from_u8(bytes)?;
-        if eth_frame.get_source() == self.sender_cfg.local_mac.unwrap() {
+    /// Sends ARP probes from a random locally administered MAC address instead
+    /// of the interface's own, if `enabled`.
+    pub fn random_mac(mut self, enabled: bool) -> Self {
+        if enabled {
+            let probe_mac: MacAddr = mac::random_locally_administered();
+            self.sender_cfg.set_probe_mac(probe_mac);
+            // NDP would still carry the real MAC, and often an EUI-64 address
+            // derived from it.
+            self.sender_cfg.remove_packet_type(PacketType::ICMPv6);
+            info!(
+                verbosity = 1,
+                "Sending ARP probes on {} from {probe_mac}", self.intf_name
+            );
+        }
+        self
+    }
+
+    /// Folds the addresses claimed by proxy ARP responders and ARP sponges
+    /// into one annotated host each.
+    ///
+    /// Routers doing proxy ARP and sponges that take over unused addresses
+    /// answer for every address they cover with the same MAC. Reporting each
+    /// of them would list dozens of hosts that do not exist. A host with many
+    /// aliases also answers for many addresses, but only for its own, so only
+    /// a MAC that answered for nearly every address nobody else took counts as
+    /// a proxy, see [`is_sponge`].
+    fn collapse_sponges(&mut self) {
+        let probed: usize = self.sender_cfg.targets_v4().len() as usize;
+        let claims: HashMap<MacAddr, usize> = self
+            .hosts_map
+            .iter()
+            .map(|(mac, host)| (*mac, host.ips.iter().filter(|ip| ip.is_ipv4()).count()))
+            .collect();
+        let total: usize = claims.values().sum();
+
+        for (mac, host) in self.hosts_map.iter_mut() {
+            let claimed: usize = claims[mac];
+            if !is_sponge(claimed, total - claimed, probed) {
+                if claimed > SPONGE_THRESHOLD {
+                    debug!(
+                        verbosity = 1,
+                        "{mac} answered for {claimed} addresses, but not for nearly all unused \
+                         ones, so it is kept as a host with aliases"
+                    );
+                }
+                continue;
+            }
+            warn!(
+                "{mac} answered for {claimed} addresses on {}. This is proxy ARP or an ARP sponge \
+                 claiming unused addresses, so it is reported as a single host",
+                self.intf_name
+            );
+            host.mark_proxy();
+        }
+    }
+
+    /// Compares the interface with the state the scan was started with.
+    ///
+    /// Roaming between access points or a DHCP renewal can swap the addresses of
+    /// an interface mid-scan, after which replies no longer match the sender's
+    /// identity. Small changes are adopted (returning `true` so the probes of
+    /// targets that have not answered can be rebuilt); losing the interface or
+    /// every on-link target aborts the scanner.
+    ///
+    /// `current` is the interface as last polled, `None` if it is gone. The
+    /// interface list is polled rather than subscribed to, which keeps this
+    /// portable across the platforms `pnet` supports.
+    fn interface_changed(&mut self, current: Option<NetworkInterface>) -> anyhow::Result<bool> {
+        let Some(current) = current else {
+            return Err(ZondError::InterfaceNotFound(format!(
+                "{} disappeared mid-scan",
+                self.intf_name
+            ))
+            .into());
+        };
+
+        if !current.is_up() || current.mac.is_none() {
+            return Err(ZondError::InterfaceNotFound(format!(
+                "{} went down mid-scan",
+                self.intf_name
+            ))
+            .into());
+        }
+
+        if !self.sender_cfg.refresh_identity(&current) {
+            return Ok(false);
+        }
+
+        if !self.sender_cfg.has_on_link_targets() {
+            return Err(ZondError::InterfaceNotFound(format!(
+                "{} moved to another network mid-scan, targets are no longer on-link",
+                self.intf_name
+            ))
+            .into());
+        }
+
+        warn!(
+            "Addresses of {} changed mid-scan, continuing with the new ones",
+            self.intf_name
+        );
+        Ok(true)
+    }
+
+    /// Puts a single probe on the wire and returns when the kernel took it.
+    ///
+    /// Transient errors such as `ENOBUFS` are retried with exponential backoff.
+    /// Frames that still cannot be sent are counted and dropped (`None`),
+    /// unless the interface itself went away, which aborts the scan.
+    async fn send_probe(&mut self, packet: &[u8]) -> anyhow::Result<Option<Instant>> {
+        let mut backoff: Duration = SEND_RETRY_BACKOFF;
+
+        for attempt in 0..=MAX_SEND_RETRIES {
+            let err: std::io::Error = match self.eth_handle.tx.send_to(packet, None) {
+                Some(Ok(())) => {
+                    let sent: Instant = Instant::now();
+                    super::record_probe();
+                    return Ok(Some(sent));
+                }
+                Some(Err(e)) => e,
+                None => std::io::Error::other("send not supported by channel"),
+            };
+
+            match channel::classify_send_error(&err) {
+                SendFailure::LinkDown => {
+                    return Err(ZondError::InterfaceNotFound(format!(
+                        "{} went down mid-scan ({err})",
+                        self.intf_name
+                    ))
+                    .into());
+                }
+                SendFailure::Transient if attempt < MAX_SEND_RETRIES => {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                _ => {
+                    debug!(verbosity = 2, "Dropping probe on {}: {err}", self.intf_name);
+                    break;
+                }
+            }
+        }
+
+        self.send_failures += 1;
+        Ok(None)
+    }
+
+    /// Keeps `bytes` in the capture ring and processes it, saving the ring
+    /// before passing on a panic of the parser.
+    fn handle_frame(&mut self, bytes: &[u8], received: Instant) {
+        self.capture.record(bytes);
+        let outcome: thread::Result<anyhow::Result<()>> =
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                self.process_eth_packet(bytes, received)
+            }));
+        if let Err(payload) = outcome {
+            self.capture.dump("Parsing a frame panicked");
+            panic::resume_unwind(payload);
+        }
+    }
+
+    fn process_eth_packet(&mut self, bytes: &[u8], received: Instant) -> anyhow::Result<()> {
+        let Ok(eth_frame) = ethernet::get_packet_from_u8(bytes) else {
+            self.capture.parse_error();
+            bail!("truncated Ethernet frame");
+        };
+        if eth_frame.get_source() == self.sender_cfg.local_mac.unwrap()
+            || Some(eth_frame.get_source()) == self.sender_cfg.probe_mac()
+        {
             return Ok(());
         }
-        let source_addr: IpAddr = protocol::get_ip_addr_from_eth(&eth_frame)?;
+        let source_addr: IpAddr = match protocol::get_ip_addr_from_eth(&eth_frame) {
+            Ok(addr) => addr,
+            Err(e) => {
+                // Other EtherTypes are expected, broken ARP or IP headers are not.
+                if matches!(
+                    eth_frame.get_ethertype(),
+                    EtherTypes::Arp | EtherTypes::Ipv4 | EtherTypes::Ipv6
+                ) {
+                    self.capture.parse_error();
+                }
+                return Err(e);
+            }
+        };
 
         ensure!(
             self.sender_cfg.is_addr_in_subnet(source_addr),
             "{source_addr} is not in range"
         );
+        self.trace
+            .reply(source_addr, || trace::describe_ethernet(bytes));
 
         // NOTE: This sucks as you might tell
         if source_addr.is_ipv6()
@@ -165,15 +559,33 @@ impl LocalScanner {
             return Ok(());
         }
 
-        let rtt: Option<Duration> = match self.calculate_rtt(&eth_frame) {
+        let key: ReplyKey = (
+            eth_frame.get_source(),
+            source_addr,
+            eth_frame.get_ethertype().0,
+        );
+        if is_duplicate(&mut self.last_replies, key, received) {
+            self.duplicate_replies += 1;
+            return Ok(());
+        }
+
+        let rtt: Option<Duration> = match self.calculate_rtt(&eth_frame, received) {
             Ok(r) => r,
             Err(e) => {
                 error!(verbosity = 2, "Failed to calculate RTT: {e}");
                 None
             }
         };
+        if let Some(rtt) = rtt {
+            self.window.record(rtt);
+        }
 
         let source_mac: MacAddr = eth_frame.get_source();
+        if let IpAddr::V4(ipv4) = source_addr
+            && eth_frame.get_ethertype() == EtherTypes::Arp
+        {
+            self.arp_cache.insert(ipv4, source_mac);
+        }
 
         let mut is_new_host: bool = false;
         let host: &mut Host = self.hosts_map.entry(source_mac).or_insert_with(|| {
@@ -184,13 +596,15 @@ impl LocalScanner {
         });
 
         if let Some(rtt) = rtt {
-            success!(
-                verbosity = 2,
-                "{source_addr} response in {}ms",
-                rtt.as_millis()
-            );
             host.add_rtt(rtt);
         }
+        match eth_frame.get_ethertype() {
+            EtherTypes::Arp => host.reachability.answered(source_addr, Method::Arp),
+            EtherTypes::Ipv6 if is_neighbor_advert(&eth_frame) => {
+                host.reachability.answered(source_addr, Method::Ndp)
+            }
+            _ => {}
+        }
 
         let is_new_ip: bool = host.ips.insert(source_addr);
 
@@ -205,7 +619,34 @@ impl LocalScanner {
         Ok(())
     }
 
-    fn calculate_rtt(&mut self, eth_frame: &EthernetPacket) -> anyhow::Result<Option<Duration>> {
+    /// Records the sweeps of this scanner on every host found, so the ones it
+    /// ignored show up as unanswered next to the ones it answered.
+    ///
+    /// Both sweeps cover the whole segment, so a host that only answered NDP
+    /// also ignored every ARP request, even though its IPv4 address is unknown.
+    fn mark_silent(&mut self) {
+        let mut sweeps: Vec<(Family, Method)> = Vec::new();
+        if self.sender_cfg.has_packet_type(PacketType::ARP)
+            && !self.sender_cfg.targets_v4().is_empty()
+        {
+            sweeps.push((Family::Ipv4, Method::Arp));
+        }
+        if self.sender_cfg.has_packet_type(PacketType::ICMPv6) {
+            sweeps.push((Family::Ipv6, Method::Ndp));
+        }
+        for host in self.hosts_map.values_mut() {
+            for (family, method) in &sweeps {
+                host.reachability.probed_family(*family, *method);
+            }
+        }
+    }
+
+    /// Returns the time from sending the probe a reply answers to `received`.
+    fn calculate_rtt(
+        &mut self,
+        eth_frame: &EthernetPacket,
+        received: Instant,
+    ) -> anyhow::Result<Option<Duration>> {
         match eth_frame.get_ethertype() {
             EtherTypes::Arp => {
                 let arp_packet: ArpPacket = ArpPacket::new(eth_frame.payload())
@@ -218,7 +659,7 @@ impl LocalScanner {
                     .remove(&src_addr)
                     .ok_or_else(|| anyhow!("unmapped address [ARP]"))?;
 
-                Ok(Some(start_time.elapsed()))
+                Ok(Some(received.saturating_duration_since(start_time)))
             }
 
             EtherTypes::Ipv6 => {
@@ -234,7 +675,7 @@ impl LocalScanner {
                         .get(&dst_addr)
                         .ok_or_else(|| anyhow!("unmapped link local [IPv6]"))?;
 
-                    return Ok(Some(start_time.elapsed()));
+                    return Ok(Some(received.saturating_duration_since(*start_time)));
                 }
 
                 Ok(None)
@@ -244,11 +685,88 @@ impl LocalScanner {
         }
     }
 
+    /// When listening should end once sending finished at `sent_at`, as far
+    /// as the replies timed so far tell, see [`AdaptiveWindow`].
+    fn adaptive_deadline(&self, sent_at: Option<Instant>) -> Option<tokio::time::Instant> {
+        let deadline: Instant = sent_at? + self.window.window()?;
+        Some(tokio::time::Instant::from_std(deadline))
+    }
+
     fn should_continue(&self) -> bool {
         let not_stopped: bool = !super::STOP_SIGNAL.load(Ordering::Relaxed);
-        let time_expired: bool = !self.timer.is_expired();
+        // Once replies were timed, the adaptive deadline replaces the fixed timer.
+        let time_expired: bool = self.window.window().is_some() || !self.timer.is_expired();
         let work_remains: bool = self.sender_cfg.len() > self.hosts_map.len();
 
         not_stopped && time_expired && work_remains
     }
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_macs_claiming_nearly_every_unused_address_are_sponges() {
+        // A sponge on a /24 with ten real hosts.
+        assert!(is_sponge(244, 10, 254));
+        // The host with 20 aliases of the multi-alias test, among 30 targets.
+        assert!(!is_sponge(21, 1, 30));
+        // Too few addresses to tell, however many were probed.
+        assert!(!is_sponge(SPONGE_THRESHOLD, 0, SPONGE_THRESHOLD));
+    }
+
+    #[test]
+    fn chattering_hosts_count_once_per_window() {
+        let mut last_replies: HashMap<ReplyKey, Instant> = HashMap::new();
+        let key: ReplyKey = (
+            MacAddr::new(0, 1, 2, 3, 4, 5),
+            "192.168.1.20".parse().unwrap(),
+            0x0806,
+        );
+        let start: Instant = Instant::now();
+        let step: Duration = DEDUP_WINDOW / 2;
+
+        assert!(!is_duplicate(&mut last_replies, key, start));
+        assert!(is_duplicate(&mut last_replies, key, start + step));
+        // Measured from the first reply, not from the duplicate before it.
+        assert!(!is_duplicate(&mut last_replies, key, start + 2 * step));
+        assert!(is_duplicate(&mut last_replies, key, start + 3 * step));
+
+        let other: ReplyKey = (key.0, key.1, 0x86dd);
+        assert!(!is_duplicate(&mut last_replies, other, start + 3 * step));
+    }
+
+    #[test]
+    fn only_neighbor_advertisements_count_as_ndp() {
+        // An IPv6 frame whose header names `next_header`, with `icmp_type` in
+        // the first byte after it.
+        let frame = |next_header: u8, icmp_type: u8| -> Vec<u8> {
+            let mut bytes: Vec<u8> = vec![0; 14 + 40 + 24];
+            bytes[12..14].copy_from_slice(&0x86ddu16.to_be_bytes());
+            bytes[14] = 0x60;
+            bytes[18..20].copy_from_slice(&24u16.to_be_bytes());
+            bytes[20] = next_header;
+            bytes[54] = icmp_type;
+            bytes
+        };
+        let is_advert = |bytes: &[u8]| is_neighbor_advert(&EthernetPacket::new(bytes).unwrap());
+
+        assert!(is_advert(&frame(58, 136)));
+        // Router advertisement, neighbor solicitation, UDP (e.g. mDNS) and a
+        // frame cut inside the IPv6 header.
+        assert!(!is_advert(&frame(58, 134)));
+        assert!(!is_advert(&frame(58, 135)));
+        assert!(!is_advert(&frame(17, 136)));
+        assert!(!is_advert(&frame(58, 136)[..30]));
+    }
+}
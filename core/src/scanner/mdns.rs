@@ -0,0 +1,290 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Identification of the devices answering mDNS on the local link.
+//!
+//! [`survey`] asks the link which DNS-SD services exist and records every
+//! device that answers, together with the addresses its answers announce.
+//! Multicast reflectors (Avahi's `enable-reflector`, many mesh routers) repeat
+//! answers heard on another segment from their own address, so the same answer
+//! arrives from two senders. Such repeats, and answers for addresses other than
+//! the sender's own, mark a device as a reflector.
+//!
+//! Every responder on the link answers every query, so the queries are paced
+//! the way RFC 6762 asks: a handful, at least a second apart.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+    net::{IpAddr, Ipv4Addr},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use pnet::{
+    datalink::NetworkInterface,
+    packet::{
+        Packet, ethernet::EtherTypes, ip::IpNextHeaderProtocols, ipv4::Ipv4Packet, udp::UdpPacket,
+    },
+    util::MacAddr,
+};
+use tokio::time::Instant;
+use zond_common::{
+    debug,
+    net::interface::{self, NetworkInterfaceExtension},
+};
+use zond_protocols::{
+    ethernet,
+    mdns::{self, MDNS_PORT, MdnsRecord},
+};
+
+use crate::error::ZondError;
+use crate::network::channel::{self, EthernetHandle};
+
+/// Queries sent per survey.
+const QUERY_COUNT: usize = 3;
+/// Pause between two queries, the minimum RFC 6762 allows for the same question.
+const QUERY_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the listener checks for a stop request while the link is quiet.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A device that answered the survey.
+#[derive(Debug, Clone)]
+pub struct Responder {
+    pub ip: Ipv4Addr,
+    pub mac: MacAddr,
+    /// The first hostname its answers named.
+    pub hostname: Option<String>,
+    /// Every address its answers announced.
+    pub announced: BTreeSet<IpAddr>,
+    /// Responses received from it, including its own repeats.
+    pub responses: usize,
+    /// Responses that repeated another device's answer byte for byte.
+    pub repeated: usize,
+}
+
+impl Responder {
+    /// Returns `true` if the device passes on answers of others rather than
+    /// only speaking for itself.
+    ///
+    /// Bonjour Sleep Proxies answer for sleeping devices and are reported the
+    /// same way.
+    pub fn is_reflector(&self) -> bool {
+        self.repeated > 0
+            || (!self.announced.is_empty() && !self.announced.contains(&IpAddr::V4(self.ip)))
+    }
+}
+
+/// What [`survey`] heard on one interface.
+#[derive(Debug, Clone)]
+pub struct MdnsSurvey {
+    pub interface: String,
+    /// Queries that made it onto the wire.
+    pub queries: usize,
+    /// Responders, ordered by address.
+    pub responders: Vec<Responder>,
+}
+
+/// Queries the LAN interface for DNS-SD services and listens for `duration`.
+///
+/// ### Errors
+/// - [`ZondError::InterfaceNotFound`] if there is no LAN interface with an
+///   IPv4 address to query from.
+/// - The error of opening the capture, e.g. [`ZondError::PermissionDenied`].
+pub async fn survey(duration: Duration) -> Result<MdnsSurvey, ZondError> {
+    super::reset_stop_signal();
+    let intf: NetworkInterface =
+        interface::get_lan_interface().map_err(|e| ZondError::InterfaceNotFound(e.to_string()))?;
+    let (Some(src_mac), Some(src_net)) = (intf.mac, intf.get_ipv4_range()) else {
+        return Err(ZondError::InterfaceNotFound(format!(
+            "{} has no MAC or IPv4 address to query mDNS from",
+            intf.name
+        )));
+    };
+    let query: Vec<u8> = mdns::create_services_query_frame(src_mac, src_net.ip())
+        .map_err(|e| ZondError::ChannelError(e.to_string()))?;
+    let mut handle: EthernetHandle = channel::start_capture(&intf)?;
+
+    let mut listener: Listener = Listener::new(src_mac);
+    let mut queries: usize = 0;
+    let mut next_query: Instant = Instant::now();
+    let deadline: Instant = Instant::now() + duration;
+
+    while Instant::now() < deadline && !super::STOP_SIGNAL.load(Ordering::Relaxed) {
+        if queries < QUERY_COUNT && Instant::now() >= next_query {
+            match handle.tx.send_to(&query, None) {
+                Some(Ok(())) => queries += 1,
+                Some(Err(e)) => debug!(verbosity = 1, "mDNS query on {} failed: {e}", intf.name),
+                None => debug!(verbosity = 1, "Sending is not supported on {}", intf.name),
+            }
+            next_query += QUERY_INTERVAL;
+        }
+
+        let mut wake: Instant = deadline.min(Instant::now() + STOP_POLL_INTERVAL);
+        if queries < QUERY_COUNT {
+            wake = wake.min(next_query);
+        }
+        tokio::select! {
+            pkt = handle.rx.recv() => match pkt {
+                Some((bytes, _)) => listener.record(&bytes),
+                None => handle.recover()?,
+            },
+            _ = tokio::time::sleep_until(wake) => {}
+        }
+    }
+
+    if listener.malformed > 0 {
+        debug!(
+            verbosity = 1,
+            "Ignored {} malformed mDNS response(s) on {}", listener.malformed, intf.name
+        );
+    }
+
+    let mut responders: Vec<Responder> = listener.responders.into_values().collect();
+    responders.sort_by_key(|responder| responder.ip);
+    Ok(MdnsSurvey {
+        interface: intf.name,
+        queries,
+        responders,
+    })
+}
+
+/// Collects the mDNS responses heard on one interface.
+struct Listener {
+    own_mac: MacAddr,
+    responders: HashMap<Ipv4Addr, Responder>,
+    /// The first sender of each distinct response, by a hash of its payload.
+    first_senders: HashMap<u64, Ipv4Addr>,
+    malformed: usize,
+}
+
+impl Listener {
+    fn new(own_mac: MacAddr) -> Self {
+        Self {
+            own_mac,
+            responders: HashMap::new(),
+            first_senders: HashMap::new(),
+            malformed: 0,
+        }
+    }
+
+    /// Records `bytes` if it is an mDNS response from another device.
+    fn record(&mut self, bytes: &[u8]) {
+        let Ok(frame) = ethernet::get_packet_from_u8(bytes) else {
+            return;
+        };
+        if frame.get_source() == self.own_mac || frame.get_ethertype() != EtherTypes::Ipv4 {
+            return;
+        }
+        let Some(ipv4) = Ipv4Packet::new(frame.payload()) else {
+            return;
+        };
+        if ipv4.get_next_level_protocol() != IpNextHeaderProtocols::Udp {
+            return;
+        }
+        let Some(udp) = UdpPacket::new(ipv4.payload()) else {
+            return;
+        };
+        if udp.get_source() != MDNS_PORT || !mdns::is_response(udp.payload()) {
+            return;
+        }
+
+        self.add_response(frame.get_source(), ipv4.get_source(), udp.payload());
+    }
+
+    fn add_response(&mut self, mac: MacAddr, ip: Ipv4Addr, payload: &[u8]) {
+        let record: MdnsRecord = match mdns::extract_resource(payload) {
+            Ok(record) => record,
+            Err(e) => {
+                debug!(verbosity = 2, "Malformed mDNS response from {ip}: {e}");
+                self.malformed += 1;
+                return;
+            }
+        };
+
+        let mut hasher: DefaultHasher = DefaultHasher::new();
+        payload.hash(&mut hasher);
+        let first_sender: Ipv4Addr = *self.first_senders.entry(hasher.finish()).or_insert(ip);
+
+        let responder: &mut Responder = self.responders.entry(ip).or_insert_with(|| Responder {
+            ip,
+            mac,
+            hostname: None,
+            announced: BTreeSet::new(),
+            responses: 0,
+            repeated: 0,
+        });
+        responder.responses += 1;
+        if first_sender != ip {
+            responder.repeated += 1;
+        }
+        if responder.hostname.is_none() {
+            responder.hostname = record.hostname.filter(|name| is_hostname(name));
+        }
+        responder.announced.extend(record.ips);
+    }
+}
+
+/// Tells hostnames from the service types and instances DNS-SD answers name,
+/// such as `_ipp._tcp.local` or `Office._ipp._tcp.local`.
+fn is_hostname(name: &str) -> bool {
+    !name.split('.').any(|label| label.starts_with('_'))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An answer naming the `_smb._tcp` service of `nas.local` at 192.168.1.20.
+    fn answer() -> Vec<u8> {
+        let mut packet: Vec<u8> = vec![0, 0, 0x84, 0, 0, 0, 0, 3, 0, 0, 0, 0];
+        // 12: _services._dns-sd._udp.local PTR _smb._tcp.local
+        packet.extend_from_slice(b"\x09_services\x07_dns-sd\x04_udp\x05local\x00");
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120, 0, 12]);
+        packet.extend_from_slice(b"\x04_smb\x04_tcp\xc0\x23");
+        // 20.1.168.192.in-addr.arpa PTR nas.local
+        packet.extend_from_slice(b"\x0220\x011\x03168\x03192\x07in-addr\x04arpa\x00");
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120, 0, 6]);
+        // 101: nas.local
+        packet.extend_from_slice(b"\x03nas\xc0\x23");
+        // nas.local A 192.168.1.20
+        packet.extend_from_slice(&[0xC0, 0x65, 0, 1, 0x80, 1, 0, 0, 0, 120, 0, 4]);
+        packet.extend_from_slice(&[192, 168, 1, 20]);
+        packet
+    }
+
+    #[test]
+    fn repeated_answers_mark_the_reflector() {
+        let nas: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 20);
+        let router: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+        let mut listener: Listener = Listener::new(MacAddr::new(2, 0, 0, 0, 0, 1));
+
+        // The NAS answers every query, the router repeats one of its answers.
+        listener.add_response(MacAddr::new(2, 0, 0, 0, 0, 20), nas, &answer());
+        listener.add_response(MacAddr::new(2, 0, 0, 0, 0, 20), nas, &answer());
+        listener.add_response(MacAddr::new(2, 0, 0, 0, 0, 2), router, &answer());
+        listener.add_response(MacAddr::new(2, 0, 0, 0, 0, 2), router, &[0, 0, 0x84]);
+
+        let nas: &Responder = &listener.responders[&nas];
+        assert_eq!((nas.responses, nas.repeated), (2, 0));
+        assert_eq!(nas.hostname.as_deref(), Some("nas.local"));
+        assert!(!nas.is_reflector());
+
+        let router: &Responder = &listener.responders[&router];
+        assert_eq!((router.responses, router.repeated), (1, 1));
+        assert!(router.is_reflector());
+        assert_eq!(listener.malformed, 1);
+    }
+}
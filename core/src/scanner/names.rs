@@ -0,0 +1,277 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Names of hosts the user already works with, imported from SSH
+//! `known_hosts`.
+//!
+//! Every host the user ever logged into left a line in `~/.ssh/known_hosts`,
+//! often with both the name typed and the address it resolved to.
+//! [`parse_known_hosts`] reads the names and addresses of those lines,
+//! [`resolve`] looks up the names that came without an address, and [`import`]
+//! adds the pairs to the name store: a file in the data directory with one
+//! `ADDRESS NAME` line per host, like `/etc/hosts`.
+//!
+//! After discovery, hosts DNS left without a hostname take theirs from the
+//! store and are labelled with [`DataSource::KnownHosts`].
+//!
+//! Hashed entries (`|1|...`, from `HashKnownHosts yes`) cannot be read back,
+//! nor can wildcard patterns; both are skipped.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use tokio::task::JoinSet;
+use zond_common::{
+    debug,
+    models::host::{DataSource, Host},
+    utils::paths,
+};
+
+/// File of the name store in the data directory.
+pub const NAMES_FILE: &str = "names";
+
+/// Upper bound for looking up one name of [`resolve`].
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+/// Lookups of [`resolve`] in flight at once.
+const MAX_CONCURRENT_LOOKUPS: usize = 32;
+
+/// What [`parse_known_hosts`] found in a `known_hosts` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownHosts {
+    /// Addresses listed together with a name, and that name.
+    pub named: Vec<(IpAddr, String)>,
+    /// Names listed without any address.
+    pub unresolved: Vec<String>,
+    /// Hashed lines, which name no host zond can read.
+    pub hashed: usize,
+}
+
+/// Parses the non-hashed entries of an OpenSSH `known_hosts` file.
+///
+/// A line lists comma-separated hosts, optionally as `[host]:port`, then the
+/// key. Every address on a line is named after the first name on it; lines
+/// marked `@revoked` or `@cert-authority` and wildcard patterns are skipped.
+pub fn parse_known_hosts(content: &str) -> KnownHosts {
+    let mut known: KnownHosts = KnownHosts::default();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for line in content.lines() {
+        let Some(hosts) = line.split_whitespace().next() else {
+            continue;
+        };
+        if hosts.starts_with('#') || hosts.starts_with('@') {
+            continue;
+        }
+        if hosts.starts_with('|') {
+            known.hashed += 1;
+            continue;
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        let mut ips: Vec<IpAddr> = Vec::new();
+        for pattern in hosts.split(',') {
+            if pattern.contains(['*', '?', '!']) {
+                continue;
+            }
+            let host: &str = strip_port(pattern);
+            match host.parse::<IpAddr>() {
+                Ok(ip) => ips.push(ip),
+                Err(_) if !host.is_empty() => names.push(host.to_ascii_lowercase()),
+                Err(_) => {}
+            }
+        }
+
+        let Some(name) = names.into_iter().next() else {
+            continue;
+        };
+        match ips.is_empty() {
+            true => {
+                if seen.insert(name.clone()) {
+                    known.unresolved.push(name);
+                }
+            }
+            false => {
+                known
+                    .named
+                    .extend(ips.into_iter().map(|ip| (ip, name.clone())));
+            }
+        }
+    }
+    known
+}
+
+/// Looks up the addresses of `names`, a few dozen at a time, and pairs each
+/// with its name. Names that do not resolve are left out.
+pub async fn resolve(names: Vec<String>) -> Vec<(IpAddr, String)> {
+    let mut lookups: JoinSet<Vec<(IpAddr, String)>> = JoinSet::new();
+    let mut resolved: Vec<(IpAddr, String)> = Vec::new();
+
+    for name in names {
+        if lookups.len() >= MAX_CONCURRENT_LOOKUPS
+            && let Some(Ok(found)) = lookups.join_next().await
+        {
+            resolved.extend(found);
+        }
+        lookups.spawn(async move {
+            let lookup = tokio::net::lookup_host((name.as_str(), 22));
+            match tokio::time::timeout(LOOKUP_TIMEOUT, lookup).await {
+                Ok(Ok(addrs)) => {
+                    let ips: HashSet<IpAddr> = addrs.map(|addr: SocketAddr| addr.ip()).collect();
+                    ips.into_iter().map(|ip| (ip, name.clone())).collect()
+                }
+                Ok(Err(e)) => {
+                    debug!(verbosity = 1, "Failed to resolve {name}: {e}");
+                    Vec::new()
+                }
+                Err(_) => {
+                    debug!(verbosity = 1, "Timed out resolving {name}");
+                    Vec::new()
+                }
+            }
+        });
+    }
+    while let Some(found) = lookups.join_next().await {
+        resolved.extend(found.unwrap_or_default());
+    }
+    resolved
+}
+
+/// Adds `names` to the name store, replacing the names stored for the same
+/// addresses, and returns the path of the store and how many addresses it
+/// now names.
+pub fn import(names: Vec<(IpAddr, String)>) -> anyhow::Result<(PathBuf, usize)> {
+    let path: PathBuf = store_path()?;
+    let mut store: BTreeMap<IpAddr, String> = match fs::read_to_string(&path) {
+        Ok(content) => parse_store(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    store.extend(names);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let tmp: PathBuf = path.with_extension("tmp");
+    fs::write(&tmp, render_store(&store))
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("failed to replace {}", path.display()))?;
+    Ok((path, store.len()))
+}
+
+/// Names the hosts in `hosts` that have no hostname yet from the name store.
+pub fn merge_into(hosts: &mut [Host]) {
+    let Ok(path) = store_path() else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    let store: BTreeMap<IpAddr, String> = parse_store(&content);
+
+    let mut merged: usize = 0;
+    for host in hosts.iter_mut().filter(|host| host.hostname.is_none()) {
+        let Some(name) = host.ips.iter().find_map(|ip| store.get(ip)) else {
+            continue;
+        };
+        host.hostname = Some(name.clone());
+        host.sources.insert(DataSource::KnownHosts);
+        merged += 1;
+    }
+    debug!(
+        verbosity = 1,
+        "Named {merged} host(s) from the {} imported name(s) in {}",
+        store.len(),
+        path.display()
+    );
+}
+
+fn store_path() -> anyhow::Result<PathBuf> {
+    paths::data_dir()
+        .map(|dir| dir.join(NAMES_FILE))
+        .ok_or_else(|| anyhow!("no data directory, set ZOND_DATA_DIR"))
+}
+
+fn parse_store(content: &str) -> BTreeMap<IpAddr, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let ip: IpAddr = fields.next()?.parse().ok()?;
+            let name: &str = fields.next()?;
+            Some((ip, name.to_string()))
+        })
+        .collect()
+}
+
+fn render_store(store: &BTreeMap<IpAddr, String>) -> String {
+    let mut out: String = String::from("# Host names imported by `zond import`, ADDRESS NAME\n");
+    for (ip, name) in store {
+        out.push_str(&format!("{ip} {name}\n"));
+    }
+    out
+}
+
+/// Strips the brackets and port of a `[host]:port` pattern.
+fn strip_port(pattern: &str) -> &str {
+    pattern
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map_or(pattern, |(host, _)| host)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_entries_and_skips_the_rest() {
+        let content: &str = "\
+# comment
+nas.home.arpa,192.168.1.20 ssh-ed25519 AAAAC3Nza
+[gitea.lan]:2222,[192.168.1.30]:2222 ssh-ed25519 AAAAC3Nza
+Router.lan ssh-rsa AAAAB3Nza
+router.lan ecdsa-sha2-nistp256 AAAAE2Vj
+192.168.1.40 ssh-ed25519 AAAAC3Nza
+|1|JfKTdBh7rNbXkVAQCRp4OQoPfmI=|USECr3SWf1JUPsms5AqfD5QfxkM= ssh-rsa AAAAB3Nza
+*.corp.example ssh-rsa AAAAB3Nza
+@cert-authority *.lan ssh-rsa AAAAB3Nza
+";
+        let known: KnownHosts = parse_known_hosts(content);
+        assert_eq!(
+            known.named,
+            vec![
+                ("192.168.1.20".parse().unwrap(), "nas.home.arpa".to_string()),
+                ("192.168.1.30".parse().unwrap(), "gitea.lan".to_string()),
+            ]
+        );
+        assert_eq!(known.unresolved, vec!["router.lan".to_string()]);
+        assert_eq!(known.hashed, 1);
+    }
+
+    #[test]
+    fn store_round_trips() {
+        let mut store: BTreeMap<IpAddr, String> = BTreeMap::new();
+        store.insert("10.0.0.1".parse().unwrap(), "gw.lan".to_string());
+        store.insert("fd00::2".parse().unwrap(), "nas.lan".to_string());
+        assert_eq!(parse_store(&render_store(&store)), store);
+    }
+}
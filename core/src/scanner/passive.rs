@@ -0,0 +1,249 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Passive host harvesting ahead of an active sweep.
+//!
+//! Busy segments constantly announce their members: ARP requests and replies,
+//! DHCP, mDNS, SSDP and NetBIOS broadcasts. [`harvest`] listens on every
+//! interface with on-link targets for a while and records each host that speaks
+//! up, so the following sweep only has to probe the addresses that stayed quiet.
+//!
+//! The capture is promiscuous unless asked otherwise, but on a switched network
+//! that rarely shows more: the switch only forwards broadcasts, multicasts and
+//! frames addressed to this machine. Each listener counts the unicast frames
+//! between other hosts it saw and says so when there were none, so a quiet
+//! capture is not mistaken for a broken one.
+//!
+//! Only on-link addresses are harvested. Frames from routed hosts carry the
+//! router's MAC address and would attribute every remote address to the router.
+//!
+//! Some of that chatter also says what the sender is. DHCP client messages
+//! carry a vendor class and parameter request list, and mDNS TXT records a
+//! model and friendly name. These [`IdentityHints`] are collected by MAC
+//! address, since DHCP clients often have no address yet when they speak.
+
+use std::{collections::HashMap, sync::atomic::Ordering, time::Duration};
+
+use pnet::{
+    datalink::NetworkInterface,
+    packet::{
+        Packet,
+        ethernet::{EtherTypes, EthernetPacket},
+        ip::IpNextHeaderProtocols,
+        ipv4::Ipv4Packet,
+        udp::UdpPacket,
+    },
+    util::MacAddr,
+};
+use tokio::{task::JoinHandle, time::Instant};
+use zond_common::{
+    debug, info,
+    models::{
+        host::{Host, IdentityHints},
+        ip::set::IpSet,
+    },
+    net::interface,
+    warn,
+};
+use zond_protocols::{
+    self as protocol,
+    dhcp::{self, ClientHints, DHCP_SERVER_PORT},
+    ethernet,
+    mdns::{self, MDNS_PORT, MdnsRecord},
+};
+
+use crate::network::channel::{self, EthernetHandle};
+
+/// How often a listener checks for a stop request while the segment is quiet.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Listens for `duration` and returns the targets that revealed themselves.
+///
+/// Interfaces are put into promiscuous mode if `promiscuous` is set. Those
+/// whose capture cannot be opened are skipped with a warning.
+pub async fn harvest(targets: &IpSet, duration: Duration, promiscuous: bool) -> Vec<Host> {
+    let deadline: Instant = Instant::now() + duration;
+    let (interface_map, _) = interface::map_ips_to_interfaces(targets.clone());
+
+    let listeners: Vec<JoinHandle<Vec<Host>>> = interface_map
+        .into_iter()
+        .filter(|(_, (on_link, _))| !on_link.is_empty())
+        .filter_map(
+            |(intf, (on_link, _))| match channel::start_capture_with(&intf, promiscuous) {
+                Ok(handle) => Some(tokio::spawn(listen(
+                    intf,
+                    handle,
+                    on_link,
+                    deadline,
+                    promiscuous,
+                ))),
+                Err(e) => {
+                    warn!("Passive warmup skips {}: {e}", intf.name);
+                    None
+                }
+            },
+        )
+        .collect();
+
+    let mut hosts: Vec<Host> = Vec::new();
+    for listener in listeners {
+        if let Ok(found) = listener.await {
+            hosts.extend(found);
+        }
+    }
+    hosts
+}
+
+/// Records the on-link hosts heard on one interface until `deadline`.
+async fn listen(
+    intf: NetworkInterface,
+    mut handle: EthernetHandle,
+    on_link: IpSet,
+    deadline: Instant,
+    promiscuous: bool,
+) -> Vec<Host> {
+    let mut hosts: HashMap<MacAddr, Host> = HashMap::new();
+    let mut hints: HashMap<MacAddr, IdentityHints> = HashMap::new();
+    let mut frames: usize = 0;
+    let mut foreign_unicast: usize = 0;
+
+    while Instant::now() < deadline && !super::STOP_SIGNAL.load(Ordering::Relaxed) {
+        let wake: Instant = deadline.min(Instant::now() + STOP_POLL_INTERVAL);
+        tokio::select! {
+            pkt = handle.rx.recv() => match pkt {
+                Some((bytes, _)) => {
+                    frames += 1;
+                    foreign_unicast += usize::from(is_foreign_unicast(&bytes, intf.mac));
+                    record(&mut hosts, &mut hints, &bytes, &on_link, intf.mac);
+                }
+                None => {
+                    if let Err(e) = handle.recover() {
+                        warn!("{e}, ending the passive warmup early");
+                        break;
+                    }
+                }
+            },
+            _ = tokio::time::sleep_until(wake) => {}
+        }
+    }
+
+    debug!(
+        verbosity = 1,
+        "Passive warmup on {} heard {} host(s) in {frames} frame(s)",
+        intf.name,
+        hosts.len()
+    );
+    if !promiscuous {
+        debug!(
+            verbosity = 1,
+            "{} was not promiscuous, so only broadcasts, multicasts and frames for this machine were captured",
+            intf.name
+        );
+    } else if foreign_unicast == 0 {
+        info!(
+            "{} saw no unicast traffic between other hosts. That is normal on a switched network, \
+             which only forwards broadcasts, multicasts and frames for this machine; listen on a \
+             mirror port or a tap to see more",
+            intf.name
+        );
+    } else {
+        debug!(
+            verbosity = 1,
+            "{} saw {foreign_unicast} unicast frame(s) between other hosts", intf.name
+        );
+    }
+    hosts
+        .into_iter()
+        .map(|(mac, mut host)| {
+            if let Some(found) = hints.remove(&mac) {
+                host.hints.merge(found);
+            }
+            host
+        })
+        .collect()
+}
+
+/// Adds the sender of `bytes` to `hosts` if it is an on-link target, and
+/// whatever it tells about itself to `hints`.
+fn record(
+    hosts: &mut HashMap<MacAddr, Host>,
+    hints: &mut HashMap<MacAddr, IdentityHints>,
+    bytes: &[u8],
+    on_link: &IpSet,
+    own_mac: Option<MacAddr>,
+) {
+    let Ok(frame) = ethernet::get_packet_from_u8(bytes) else {
+        return;
+    };
+    let source_mac: MacAddr = frame.get_source();
+    if Some(source_mac) == own_mac {
+        return;
+    }
+    if let Some((mac, found)) = identity_hints(&frame) {
+        hints.entry(mac).or_default().merge(found);
+    }
+
+    // ARP probes announce 0.0.0.0, which `on_link` never contains.
+    let Ok(source_ip) = protocol::get_ip_addr_from_eth(&frame) else {
+        return;
+    };
+    if !on_link.contains(&source_ip) {
+        return;
+    }
+
+    let host: &mut Host = hosts.entry(source_mac).or_insert_with(|| {
+        super::increment_host_count();
+        Host::new(source_ip).with_mac(source_mac)
+    });
+    host.ips.insert(source_ip);
+}
+
+/// Returns `true` for a unicast frame neither sent by nor addressed to
+/// `own_mac`, which only a promiscuous capture on a shared segment sees.
+fn is_foreign_unicast(bytes: &[u8], own_mac: Option<MacAddr>) -> bool {
+    let Some(frame) = EthernetPacket::new(bytes) else {
+        return false;
+    };
+    let destination: MacAddr = frame.get_destination();
+    !destination.is_multicast()
+        && Some(destination) != own_mac
+        && Some(frame.get_source()) != own_mac
+}
+
+/// Reads the identity hints of a DHCP client message or an mDNS response, and
+/// the MAC address of the device they describe.
+fn identity_hints(frame: &EthernetPacket) -> Option<(MacAddr, IdentityHints)> {
+    if frame.get_ethertype() != EtherTypes::Ipv4 {
+        return None;
+    }
+    let ipv4: Ipv4Packet = Ipv4Packet::new(frame.payload())?;
+    if ipv4.get_next_level_protocol() != IpNextHeaderProtocols::Udp {
+        return None;
+    }
+    let udp: UdpPacket = UdpPacket::new(ipv4.payload())?;
+
+    match (udp.get_source(), udp.get_destination()) {
+        (_, DHCP_SERVER_PORT) => {
+            let client: ClientHints = dhcp::parse_client_message(udp.payload()).ok()?;
+            let hints: IdentityHints = IdentityHints {
+                dhcp_vendor_class: client.vendor_class,
+                dhcp_parameters: client.parameter_list,
+                ..IdentityHints::default()
+            };
+            Some((client.mac, hints))
+        }
+        (MDNS_PORT, _) if mdns::is_response(udp.payload()) => {
+            let record: MdnsRecord = mdns::extract_resource(udp.payload()).ok()?;
+            let hints: IdentityHints = IdentityHints {
+                mdns_model: record.model,
+                mdns_name: record.friendly_name,
+                ..IdentityHints::default()
+            };
+            Some((frame.get_source(), hints))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! External enrichers.
+//!
+//! Any executable on `PATH` whose name starts with [`PREFIX`], such as
+//! `zond-enrich-cmdb`, is run once per enrichment pass. It receives every host
+//! as one line of JSON on stdin, in the report format of `zond schema`, and
+//! answers with one line per host on stdout, in the same order:
+//!
+//! ```json
+//! {"annotations": {"asset_tag": "A-1042", "owner": "facilities"}, "location": "Rack 3"}
+//! ```
+//!
+//! `annotations` are added to [`Host::annotations`]; `hostname`, `model` and
+//! `location` fill in what the scan did not find. Empty lines and `{}` leave a
+//! host alone. Whatever the program writes to stderr ends up in the debug log.
+//!
+//! This lets sites hook up their CMDB or inventory without patching zond.
+//!
+//! Enrichers only run with `--plugins` ([`ZondConfig::plugins`]), since zond
+//! often runs as root. Programs anyone may overwrite are skipped, and as root
+//! so are programs and directories that do not belong to root alone.
+//!
+//! [`ZondConfig::plugins`]: zond_common::config::ZondConfig::plugins
+
+use std::{
+    collections::BTreeSet,
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+};
+
+use serde_json::Value;
+use tokio::{
+    io::AsyncWriteExt,
+    process::{Child, Command},
+};
+use zond_common::{
+    debug, info,
+    models::host::{DataSource, Host},
+    warn,
+};
+
+/// File name prefix of external enrichers.
+pub const PREFIX: &str = "zond-enrich-";
+
+/// Runs every enricher found on `PATH` over `hosts`, in the order of their
+/// names.
+pub async fn enrich(hosts: &mut [Host]) {
+    for plugin in find() {
+        let name: String = plugin
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match run(&plugin, hosts).await {
+            Ok(annotated) => info!("{name} annotated {annotated} host(s)"),
+            Err(e) => warn!("Enricher {name} failed: {e:#}"),
+        }
+    }
+}
+
+/// Returns the enrichers on `PATH`. Like a shell, the first directory holding
+/// a name wins.
+fn find() -> Vec<PathBuf> {
+    let path: OsString = env::var_os("PATH").unwrap_or_default();
+    let mut names: BTreeSet<OsString> = BTreeSet::new();
+    let mut plugins: Vec<PathBuf> = Vec::new();
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = dir.read_dir() else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name: OsString = entry.file_name();
+            if !name.to_string_lossy().starts_with(PREFIX) || !is_trusted(&entry.path()) {
+                continue;
+            }
+            if names.insert(name) {
+                plugins.push(entry.path());
+            }
+        }
+    }
+    plugins.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    plugins
+}
+
+/// Returns `true` if `path` is an executable file no one but its owner may
+/// overwrite. As root, the file and the directory it is in must also belong to
+/// root and be closed to writes by group and others, since whoever can change
+/// them could run code as root.
+#[cfg(unix)]
+fn is_trusted(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let as_root: bool = unsafe { libc::geteuid() } == 0;
+    // Links are resolved first, so the directory checked is the real one.
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    let Ok(meta) = path.metadata() else {
+        return false;
+    };
+    if !meta.is_file() || meta.mode() & 0o111 == 0 || !is_guarded(&meta, as_root) {
+        return false;
+    }
+    !as_root
+        || path
+            .parent()
+            .and_then(|dir| dir.metadata().ok())
+            .is_some_and(|dir| is_guarded(&dir, as_root))
+}
+
+#[cfg(unix)]
+fn is_guarded(meta: &std::fs::Metadata, as_root: bool) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match as_root {
+        true => meta.uid() == 0 && meta.mode() & 0o022 == 0,
+        false => meta.mode() & 0o002 == 0,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_trusted(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Pipes `hosts` through `plugin` and applies its answers. Returns how many
+/// hosts it changed.
+async fn run(plugin: &Path, hosts: &mut [Host]) -> anyhow::Result<usize> {
+    let mut input: String = String::new();
+    for host in hosts.iter() {
+        input.push_str(&serde_json::to_string(host)?);
+        input.push('\n');
+    }
+
+    let mut child: Child = Command::new(plugin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    // Written from a task of its own, so a plugin answering before it read
+    // every host cannot deadlock on a full pipe.
+    if let Some(mut stdin) = child.stdin.take() {
+        tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+    let output: Output = child.wait_with_output().await?;
+
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        debug!(verbosity = 1, "{}: {line}", plugin.display());
+    }
+    anyhow::ensure!(output.status.success(), "exited with {}", output.status);
+
+    let mut annotated: usize = 0;
+    for (idx, line) in String::from_utf8_lossy(&output.stdout).lines().enumerate() {
+        let Some(host) = hosts.get_mut(idx) else {
+            anyhow::bail!("answered more lines than there are hosts");
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let answer: Value = serde_json::from_str(line)
+            .map_err(|e| anyhow::anyhow!("line {} is not JSON: {e}", idx + 1))?;
+        if apply(host, &answer) {
+            annotated += 1;
+        }
+    }
+    Ok(annotated)
+}
+
+/// Merges one answer into `host` and returns whether anything changed.
+fn apply(host: &mut Host, answer: &Value) -> bool {
+    let mut changed: bool = false;
+    if let Some(annotations) = answer.get("annotations").and_then(Value::as_object) {
+        for (key, value) in annotations {
+            let value: String = match value {
+                Value::String(text) => text.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => continue,
+            };
+            changed |= host.annotations.insert(key.clone(), value.clone()) != Some(value);
+        }
+    }
+
+    for (field, slot) in [
+        ("hostname", &mut host.hostname),
+        ("model", &mut host.model),
+        ("location", &mut host.location),
+    ] {
+        if slot.is_none()
+            && let Some(value) = answer.get(field).and_then(Value::as_str)
+        {
+            *slot = Some(value.to_string());
+            changed = true;
+        }
+    }
+
+    if changed {
+        host.sources.insert(DataSource::Plugin);
+    }
+    changed
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+
+    #[test]
+    fn answers_fill_gaps_and_add_annotations() {
+        let mut host: Host = Host::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7)));
+        host.hostname = Some("printer.lan".to_string());
+
+        let answer: Value = serde_json::json!({
+            "annotations": {"asset_tag": "A-1042", "rack": 3, "ignored": ["x"]},
+            "hostname": "cmdb-name",
+            "location": "Floor 2",
+        });
+        assert!(apply(&mut host, &answer));
+        assert_eq!(host.hostname.as_deref(), Some("printer.lan"));
+        assert_eq!(host.location.as_deref(), Some("Floor 2"));
+        assert_eq!(host.annotations["asset_tag"], "A-1042");
+        assert_eq!(host.annotations["rack"], "3");
+        assert!(!host.annotations.contains_key("ignored"));
+        assert!(host.sources.contains(&DataSource::Plugin));
+
+        assert!(!apply(&mut host, &answer));
+        assert!(!apply(&mut host, &serde_json::json!({})));
+    }
+}
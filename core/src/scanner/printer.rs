@@ -0,0 +1,131 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Printer identification after a port scan.
+//!
+//! Hosts with IPP (631/tcp) open are asked for `Get-Printer-Attributes`, which
+//! yields make, model and location. Hosts that only expose JetDirect (9100/tcp)
+//! get a PJL `INFO ID` instead, which only names the model. Either answer tags
+//! the host with [`NetworkRole::Printer`].
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    task::JoinSet,
+    time::{error::Elapsed, timeout},
+};
+use zond_common::{
+    debug,
+    models::{
+        host::{Host, NetworkRole},
+        port::{PortState, Protocol},
+    },
+};
+use zond_protocols::{
+    ipp::{self, PrinterAttributes},
+    pjl,
+};
+
+const IPP_PORT: u16 = 631;
+const JETDIRECT_PORT: u16 = 9100;
+
+/// Time allowed for one complete exchange, including the connect.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Replies are cut off here; the attributes asked for fit many times over.
+const MAX_RESPONSE_LEN: u64 = 64 * 1024;
+
+/// Identifies the printers among `hosts` and records their model and location.
+pub async fn identify_printers(hosts: &mut [Host]) {
+    let mut probes: JoinSet<(usize, Option<PrinterAttributes>)> = JoinSet::new();
+    for (idx, host) in hosts.iter().enumerate() {
+        let ipp: bool = has_open_tcp(host, IPP_PORT);
+        let jetdirect: bool = has_open_tcp(host, JETDIRECT_PORT);
+        if !ipp && !jetdirect {
+            continue;
+        }
+
+        let ip: IpAddr = host.primary_ip;
+        probes.spawn(async move {
+            let mut found: Option<PrinterAttributes> = None;
+            if ipp {
+                found = query_ipp(ip).await;
+            }
+            if jetdirect
+                && found
+                    .as_ref()
+                    .is_none_or(|attrs| attrs.make_and_model.is_none())
+            {
+                let model: Option<String> = query_pjl(ip).await;
+                if model.is_some() {
+                    found.get_or_insert_default().make_and_model = model;
+                }
+            }
+            (idx, found)
+        });
+    }
+
+    while let Some(result) = probes.join_next().await {
+        let Ok((idx, Some(attributes))) = result else {
+            continue;
+        };
+        let host: &mut Host = &mut hosts[idx];
+        host.network_roles.insert(NetworkRole::Printer);
+        host.model = attributes.make_and_model.or(host.model.take());
+        host.location = attributes.location.or(host.location.take());
+    }
+}
+
+fn has_open_tcp(host: &Host, number: u16) -> bool {
+    host.ports().iter().any(|port| {
+        port.number == number && port.protocol == Protocol::Tcp && port.state == PortState::Open
+    })
+}
+
+async fn query_ipp(ip: IpAddr) -> Option<PrinterAttributes> {
+    let addr: SocketAddr = SocketAddr::new(ip, IPP_PORT);
+    let response: Vec<u8> = exchange(addr, &ipp::create_get_printer_attributes(addr)).await?;
+    match ipp::get_printer_attributes(&response) {
+        Ok(attributes) => Some(attributes),
+        Err(e) => {
+            debug!(verbosity = 1, "IPP query of {ip} failed: {e}");
+            None
+        }
+    }
+}
+
+async fn query_pjl(ip: IpAddr) -> Option<String> {
+    let addr: SocketAddr = SocketAddr::new(ip, JETDIRECT_PORT);
+    let response: Vec<u8> = exchange(addr, pjl::INFO_ID_REQUEST).await?;
+    pjl::get_model(&String::from_utf8_lossy(&response))
+}
+
+/// Sends `request` and collects the reply until the peer closes the connection
+/// or the timeout expires.
+async fn exchange(addr: SocketAddr, request: &[u8]) -> Option<Vec<u8>> {
+    let mut response: Vec<u8> = Vec::new();
+    let session = async {
+        let mut stream: TcpStream = TcpStream::connect(addr).await?;
+        stream.write_all(request).await?;
+        (&mut stream)
+            .take(MAX_RESPONSE_LEN)
+            .read_to_end(&mut response)
+            .await
+    };
+
+    // PJL printers often keep the connection open, so running into the
+    // timeout with a reply in hand is a success.
+    let outcome: Result<io::Result<usize>, Elapsed> = timeout(PROBE_TIMEOUT, session).await;
+    match outcome {
+        Ok(Err(_)) => None,
+        _ => (!response.is_empty()).then_some(response),
+    }
+}
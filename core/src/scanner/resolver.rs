@@ -7,94 +7,368 @@
 use hickory_resolver::system_conf::read_system_conf;
 use std::net::SocketAddr;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     net::IpAddr,
-    sync::atomic::{AtomicU16, Ordering},
-    time::Duration,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, ensure};
 use pnet::packet::{Packet, udp::UdpPacket};
-use tokio::sync::mpsc::UnboundedReceiver;
-use zond_common::{models::host::Host, utils};
+use tokio::{
+    sync::mpsc::UnboundedReceiver,
+    time::{Interval, MissedTickBehavior},
+};
+use tracing::Span;
+use zond_common::{
+    debug,
+    models::host::{Host, IdentityHints},
+    phase, utils, warn,
+};
 use zond_protocols::{
-    dns,
+    dns::{self, ZoneHint},
     mdns::{self, MdnsRecord},
     udp,
 };
 
+use super::spill::AddressQueue;
 use crate::network::transport::{self, TransportHandle, TransportType};
 
 const DNS_PORT: u16 = 53;
 const MDNS_PORT: u16 = 5353;
+/// How long lookups may still run once scanning finished, unless configured.
+pub(crate) const DEFAULT_BUDGET: Duration = Duration::from_secs(2);
+/// How often the drain rechecks the stop signal while no answer arrives.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the addresses of a zone wait for its SOA answer before their PTR
+/// queries go to the configured server anyway.
+const SOA_TIMEOUT: Duration = Duration::from_secs(1);
+/// How often unanswered SOA queries are checked against [`SOA_TIMEOUT`].
+const SOA_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+/// Addresses of one zone kept in memory while its SOA query is in flight;
+/// the rest wait on disk.
+const PENDING_IN_MEMORY: usize = 4096;
+
+/// Lookups still awaiting an answer after scanning finished, 0 otherwise.
+pub(crate) static OUTSTANDING: AtomicUsize = AtomicUsize::new(0);
 
 type Hostname = String;
 type TransID = u16;
+type Zone = String;
+
+/// What we know about a reverse zone before spending PTR queries on it.
+enum ZoneState {
+    /// The SOA query is still in flight; addresses wait here until it answers
+    /// or [`SOA_TIMEOUT`] passes.
+    Pending(AddressQueue),
+    /// The zone does not exist upstream, so its PTR lookups are skipped.
+    Missing,
+    /// PTR queries for this zone are sent to the given name server.
+    Server(SocketAddr),
+}
+
+/// A query waiting for its response, and where that response has to come from.
+struct PendingQuery<T> {
+    subject: T,
+    server: SocketAddr,
+    src_port: u16,
+    sent: Instant,
+}
+
+impl<T> PendingQuery<T> {
+    /// Returns `true` if `packet` came from the queried server to the port the
+    /// query was sent from. Anyone else guessing the transaction ID is ignored.
+    fn is_answered_by(&self, source: IpAddr, packet: &UdpPacket) -> bool {
+        source == self.server.ip()
+            && packet.get_source() == self.server.port()
+            && packet.get_destination() == self.src_port
+    }
+}
 
 pub struct HostnameResolver {
     udp_handle: TransportHandle,
-    dns_map: HashMap<TransID, IpAddr>,
+    dns_map: HashMap<TransID, PendingQuery<IpAddr>>,
+    soa_map: HashMap<TransID, PendingQuery<Zone>>,
+    zone_map: HashMap<Zone, ZoneState>,
+    ptr_backlog: Vec<(IpAddr, SocketAddr)>,
     mdns_cache: HashMap<IpAddr, MdnsRecord>,
     hostname_map: HashMap<IpAddr, Hostname>,
+    /// Addresses a PTR lookup was already queued for during this run.
+    queried: HashSet<IpAddr>,
+    /// How many lookup requests were dropped as repeats of a queried address.
+    deduped: usize,
+    /// Whether PTR lookups are sent for public addresses as well.
+    resolve_public: bool,
+    /// How many public addresses were not looked up.
+    skipped_public: usize,
+    /// How many DNS responses were dropped for not matching their query.
+    mismatched: usize,
     dns_rx: UnboundedReceiver<IpAddr>,
     dns_socket: SocketAddr,
-    id_counter: AtomicU16,
 }
 
 impl HostnameResolver {
-    pub fn new(dns_rx: UnboundedReceiver<IpAddr>) -> anyhow::Result<Self> {
+    pub fn new(dns_rx: UnboundedReceiver<IpAddr>, resolve_public: bool) -> anyhow::Result<Self> {
         Ok(Self {
             udp_handle: transport::start_packet_capture(TransportType::UdpLayer4)?,
             dns_map: HashMap::new(),
+            soa_map: HashMap::new(),
+            zone_map: HashMap::new(),
+            ptr_backlog: Vec::new(),
             mdns_cache: HashMap::new(),
             hostname_map: HashMap::new(),
+            queried: HashSet::new(),
+            deduped: 0,
+            resolve_public,
+            skipped_public: 0,
+            mismatched: 0,
             dns_rx,
             dns_socket: get_dns_server_socket()?,
-            id_counter: AtomicU16::new(0),
         })
     }
 
-    pub async fn run(mut self) -> Self {
+    /// Answers lookups until every scanner is done, then waits up to `budget`
+    /// for the answers still outstanding.
+    pub async fn run(mut self, budget: Duration) -> Self {
+        let mut soa_check: Interval = tokio::time::interval(SOA_CHECK_INTERVAL);
+        soa_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
             tokio::select! {
                 res = self.dns_rx.recv() => {
                     match res {
                         Some(ip) => {
-                            let _ = self.send_dns_query(&ip).await;
+                            let _ = self.queue_ptr_query(ip).await;
                         }
                         None => break,
                     }
                 }
                 pkt = self.udp_handle.rx.recv() => {
-                    if let Some((bytes, _addr)) = pkt {
-                        let _ = self.process_udp_packets(&bytes);
+                    match pkt {
+                        Some((bytes, source, _)) => {
+                            let _ = self.process_udp_packets(&bytes, source);
+                            self.send_ptr_backlog().await;
+                        }
+                        None => {
+                            if !self.recover_capture() {
+                                break;
+                            }
+                        }
                     }
                 }
+                _ = soa_check.tick() => {
+                    self.expire_soa_queries();
+                    self.send_ptr_backlog().await;
+                }
+            }
+        }
+
+        if self.outstanding() > 0 {
+            let _phase: Span = phase!("resolver drain", pending = self.dns_map.len());
+            self.drain(budget).await;
+        }
+
+        if self.deduped > 0 {
+            debug!(
+                verbosity = 1,
+                "Skipped {} duplicate PTR lookup(s) for {} address(es)",
+                self.deduped,
+                self.queried.len()
+            );
+        }
+        if self.mismatched > 0 {
+            debug!(
+                verbosity = 1,
+                "Ignored {} DNS response(s) that did not match their query", self.mismatched
+            );
+        }
+        if self.skipped_public > 0 {
+            debug!(
+                verbosity = 1,
+                "Skipped PTR lookup(s) for {} public address(es), pass --resolve-public to resolve them",
+                self.skipped_public
+            );
+        }
+
+        self
+    }
+
+    /// Reopens the capture of DNS answers after it stopped. If that fails,
+    /// the lookups still outstanding are given up and `false` is returned.
+    fn recover_capture(&mut self) -> bool {
+        match self.udp_handle.recover() {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("{e}, hostnames stay empty");
+                self.dns_map.clear();
+                self.soa_map.clear();
+                false
             }
         }
+    }
 
-        if !self.dns_map.is_empty() {
-            let _ = tokio::time::timeout(Duration::from_millis(250), async {
-                while !self.dns_map.is_empty() {
-                    if let Some((bytes, _addr)) = self.udp_handle.rx.recv().await {
-                        let _ = self.process_udp_packets(&bytes);
+    /// Lookups sent but not answered yet.
+    fn outstanding(&self) -> usize {
+        self.dns_map.len() + self.soa_map.len()
+    }
+
+    /// Processes answers until none is outstanding, `budget` runs out or the
+    /// scan is stopped, publishing the count in [`OUTSTANDING`] meanwhile.
+    async fn drain(&mut self, budget: Duration) {
+        let deadline: Instant = Instant::now() + budget;
+        while self.outstanding() > 0 && !super::STOP_SIGNAL.load(Ordering::Relaxed) {
+            OUTSTANDING.store(self.outstanding(), Ordering::Relaxed);
+            let remaining: Duration = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            let wait: Duration = remaining.min(DRAIN_POLL_INTERVAL);
+            match tokio::time::timeout(wait, self.udp_handle.rx.recv()).await {
+                Ok(Some((bytes, source, _))) => {
+                    let _ = self.process_udp_packets(&bytes, source);
+                    self.send_ptr_backlog().await;
+                }
+                Ok(None) => {
+                    if !self.recover_capture() {
+                        break;
                     }
                 }
-            })
-            .await;
+                Err(_) => {}
+            }
+            self.expire_soa_queries();
+            self.send_ptr_backlog().await;
         }
 
-        self
+        if self.outstanding() > 0 {
+            debug!(
+                verbosity = 1,
+                "Gave up on {} hostname lookup(s) after {}ms",
+                self.outstanding(),
+                budget.as_millis()
+            );
+        }
+        OUTSTANDING.store(0, Ordering::Relaxed);
     }
 
-    async fn send_dns_query(&mut self, ip: &IpAddr) -> anyhow::Result<()> {
-        ensure!(is_queryable(ip), "{ip} cannot be queried");
-        let id: u16 = self.get_next_trans_id();
-        self.dns_map.insert(id, *ip);
-        let (dns_addr, dns_port) = (self.dns_socket.ip(), self.dns_socket.port());
+    /// Gives up on SOA queries unanswered for [`SOA_TIMEOUT`], releasing the
+    /// addresses of their zones to the configured server.
+    fn expire_soa_queries(&mut self) {
+        let mut expired: Vec<Zone> = Vec::new();
+        self.soa_map.retain(|_, query| {
+            let waiting: bool = query.sent.elapsed() < SOA_TIMEOUT;
+            if !waiting {
+                expired.push(query.subject.clone());
+            }
+            waiting
+        });
 
-        let bytes: Vec<u8> = dns::create_ptr_packet(ip, id)?;
+        for zone in expired {
+            debug!(
+                verbosity = 2,
+                "SOA query for {zone} timed out, sending its PTR queries to the configured server"
+            );
+            self.settle_zone(&zone, ZoneState::Server(self.dns_socket));
+        }
+    }
+
+    /// Routes a PTR lookup through the zone hints of its reverse zone.
+    ///
+    /// The first address of an unknown zone triggers an SOA query, and every
+    /// address of that zone is held back until the answer decides where (or
+    /// whether) its PTR query goes, or [`SOA_TIMEOUT`] passes. Each address is
+    /// looked up at most once per run, however often retransmitted replies
+    /// report it, and public addresses only with `resolve_public`.
+    async fn queue_ptr_query(&mut self, ip: IpAddr) -> anyhow::Result<()> {
+        if !self.queried.insert(ip) {
+            self.deduped += 1;
+            return Ok(());
+        }
+        if utils::ip::is_public(&ip) && !self.resolve_public {
+            self.skipped_public += 1;
+            return Ok(());
+        }
+        ensure!(is_queryable(&ip), "{ip} cannot be queried");
+        let zone: Zone = utils::ip::reverse_zone(&ip);
+
+        match self.zone_map.get_mut(&zone) {
+            Some(ZoneState::Pending(queue)) => {
+                queue.push(ip);
+                Ok(())
+            }
+            Some(ZoneState::Missing) => {
+                debug!(
+                    verbosity = 2,
+                    "Skipping PTR for {ip}, {zone} does not exist"
+                );
+                Ok(())
+            }
+            Some(ZoneState::Server(server)) => {
+                let server: SocketAddr = *server;
+                self.send_ptr_query(&ip, server).await
+            }
+            None => {
+                let mut queue: AddressQueue =
+                    AddressQueue::new(PENDING_IN_MEMORY, utils::paths::spill_dir());
+                queue.push(ip);
+                self.zone_map
+                    .insert(zone.clone(), ZoneState::Pending(queue));
+                if let Err(e) = self.send_soa_query(&zone).await {
+                    debug!(verbosity = 2, "SOA query for {zone} failed: {e}");
+                    self.settle_zone(&zone, ZoneState::Server(self.dns_socket));
+                    self.send_ptr_backlog().await;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_soa_query(&mut self, zone: &str) -> anyhow::Result<()> {
+        let id: TransID = self.next_trans_id();
         let src_port: u16 = rand::random_range(50_000..u16::MAX);
+        self.soa_map.insert(
+            id,
+            PendingQuery {
+                subject: zone.to_string(),
+                server: self.dns_socket,
+                src_port,
+                sent: Instant::now(),
+            },
+        );
+
+        let bytes: Vec<u8> = dns::create_soa_packet(zone, id)?;
+        self.send_udp(bytes, self.dns_socket, src_port).await
+    }
+
+    async fn send_ptr_query(&mut self, ip: &IpAddr, server: SocketAddr) -> anyhow::Result<()> {
+        let id: TransID = self.next_trans_id();
+        let src_port: u16 = rand::random_range(50_000..u16::MAX);
+        self.dns_map.insert(
+            id,
+            PendingQuery {
+                subject: *ip,
+                server,
+                src_port,
+                sent: Instant::now(),
+            },
+        );
+
+        let bytes: Vec<u8> = dns::create_ptr_packet(ip, id)?;
+        self.send_udp(bytes, server, src_port).await
+    }
+
+    async fn send_ptr_backlog(&mut self) {
+        for (ip, server) in std::mem::take(&mut self.ptr_backlog) {
+            let _ = self.send_ptr_query(&ip, server).await;
+        }
+    }
+
+    async fn send_udp(
+        &self,
+        bytes: Vec<u8>,
+        server: SocketAddr,
+        src_port: u16,
+    ) -> anyhow::Result<()> {
+        let (dns_addr, dns_port) = (server.ip(), server.port());
         let udp_bytes: Vec<u8> = udp::create_packet(src_port, dns_port, bytes)?;
         let tx = self.udp_handle.tx.clone();
         tokio::task::spawn_blocking(move || {
@@ -108,25 +382,104 @@ impl HostnameResolver {
         Ok(())
     }
 
-    fn process_udp_packets(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+    /// Records the verdict for a zone and releases any addresses waiting on it.
+    fn settle_zone(&mut self, zone: &str, state: ZoneState) {
+        let previous: Option<ZoneState> = self.zone_map.insert(zone.to_string(), state);
+
+        if let (Some(ZoneState::Pending(mut queue)), Some(ZoneState::Server(server))) =
+            (previous, self.zone_map.get(zone))
+        {
+            let server: SocketAddr = *server;
+            self.ptr_backlog
+                .extend(queue.take().into_iter().map(|ip| (ip, server)));
+        }
+    }
+
+    fn process_udp_packets(&mut self, bytes: &[u8], source: IpAddr) -> anyhow::Result<()> {
         let udp_packet = UdpPacket::new(bytes).context("truncated or invalid UDP packet")?;
         match udp_packet.get_source() {
-            DNS_PORT => self.process_dns_packet(udp_packet)?,
-            MDNS_PORT => self.process_mdns_packet(udp_packet)?,
+            DNS_PORT => self.process_dns_packet(udp_packet, source)?,
+            MDNS_PORT => self.process_mdns_packet(udp_packet, source)?,
             _ => {}
         }
         Ok(())
     }
 
-    fn process_dns_packet(&mut self, packet: UdpPacket) -> anyhow::Result<()> {
-        let (response_id, hostname) = dns::get_hostname(packet.payload())?;
-        if let Some(ip) = self.dns_map.remove(&response_id) {
-            self.hostname_map.insert(ip, hostname);
+    /// Handles a response to one of our queries.
+    ///
+    /// Besides the transaction ID, the response has to come from the server the
+    /// query went to, reach the port it was sent from, and repeat its question.
+    /// Anything else is dropped without touching the pending query, so a spoofed
+    /// response cannot plant a hostname or keep the real answer out.
+    fn process_dns_packet(&mut self, packet: UdpPacket, source: IpAddr) -> anyhow::Result<()> {
+        let payload: &[u8] = packet.payload();
+        let id: u16 = dns::get_transaction_id(payload)?;
+
+        if let Some(query) = self.soa_map.get(&id) {
+            if !query.is_answered_by(source, &packet) || !dns::answers_soa(payload, &query.subject)
+            {
+                self.mismatched += 1;
+                return Ok(());
+            }
+            let zone: Zone = query.subject.clone();
+            self.soa_map.remove(&id);
+            return self.process_soa_response(&zone, payload);
+        }
+
+        let Some(query) = self.dns_map.get(&id) else {
+            return Ok(());
+        };
+        if !query.is_answered_by(source, &packet) || !dns::answers_ptr(payload, &query.subject) {
+            self.mismatched += 1;
+            return Ok(());
         }
+        let ip: IpAddr = query.subject;
+        self.dns_map.remove(&id);
+
+        let (_, hostname) = dns::get_hostname(payload)?;
+        self.hostname_map.insert(ip, hostname);
+        Ok(())
+    }
+
+    fn process_soa_response(&mut self, zone: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let hint: ZoneHint = match dns::get_zone_hint(payload) {
+            Ok((_, hint)) => hint,
+            Err(e) => {
+                self.settle_zone(zone, ZoneState::Server(self.dns_socket));
+                return Err(e);
+            }
+        };
+
+        let state: ZoneState = match hint {
+            ZoneHint::Missing => {
+                debug!(
+                    verbosity = 1,
+                    "Reverse zone {zone} does not exist, skipping PTR lookups"
+                );
+                ZoneState::Missing
+            }
+            ZoneHint::Authority {
+                primary_ns,
+                addr: Some(ns_addr),
+            } if utils::ip::is_private(&ns_addr) => {
+                debug!(
+                    verbosity = 1,
+                    "Reverse zone {zone} is served by {primary_ns} ({ns_addr})"
+                );
+                ZoneState::Server(SocketAddr::new(ns_addr, DNS_PORT))
+            }
+            _ => ZoneState::Server(self.dns_socket),
+        };
+
+        self.settle_zone(zone, state);
         Ok(())
     }
 
-    fn process_mdns_packet(&mut self, packet: UdpPacket) -> anyhow::Result<()> {
+    /// Caches what an mDNS packet announced under the address it is about.
+    ///
+    /// TXT records often come without address records, so their hints are
+    /// filed under the sender. Announcements for the same address accumulate.
+    fn process_mdns_packet(&mut self, packet: UdpPacket, source: IpAddr) -> anyhow::Result<()> {
         let mdns_record: MdnsRecord = mdns::extract_resource(packet.payload())?;
 
         let preferred_ip = mdns_record
@@ -142,12 +495,22 @@ impl HostnameResolver {
                     }
                 })
             })
-            .or_else(|| mdns_record.ips.iter().next());
-
-        if let Some(ip) = preferred_ip {
-            self.mdns_cache.insert(*ip, mdns_record);
-        }
+            .or_else(|| mdns_record.ips.iter().next())
+            .copied();
 
+        let cached: &mut MdnsRecord = match preferred_ip {
+            Some(ip) => {
+                let cached: &mut MdnsRecord = self.mdns_cache.entry(ip).or_default();
+                cached.hostname = cached.hostname.take().or(mdns_record.hostname);
+                cached.ips.extend(mdns_record.ips);
+                cached
+            }
+            // Without an address record, the PTR names may be services of
+            // anyone. Only the TXT hints are taken for the sender.
+            None => self.mdns_cache.entry(source).or_default(),
+        };
+        cached.model = cached.model.take().or(mdns_record.model);
+        cached.friendly_name = cached.friendly_name.take().or(mdns_record.friendly_name);
         Ok(())
     }
 
@@ -170,23 +533,33 @@ impl HostnameResolver {
                     }
 
                     host.ips.extend(mdns_record.ips);
+                    host.hints.merge(IdentityHints {
+                        mdns_model: mdns_record.model,
+                        mdns_name: mdns_record.friendly_name,
+                        ..IdentityHints::default()
+                    });
                 }
             }
         }
     }
 
-    fn get_next_trans_id(&self) -> u16 {
-        self.id_counter.fetch_add(1, Ordering::Relaxed)
+    /// Picks a random transaction ID no pending query holds, so an answer
+    /// cannot be forged by guessing the next ID in line. Only if every ID is
+    /// taken does a new query share one.
+    fn next_trans_id(&self) -> TransID {
+        let start: TransID = rand::random();
+        (0..=TransID::MAX)
+            .map(|offset| start.wrapping_add(offset))
+            .find(|id| !self.dns_map.contains_key(id) && !self.soa_map.contains_key(id))
+            .unwrap_or(start)
     }
 }
 
+/// Link-local and loopback IPv6 addresses have no reverse zone anywhere.
 fn is_queryable(ip: &IpAddr) -> bool {
     match ip {
-        IpAddr::V6(ipv6_addr) => utils::ip::is_global_unicast(ipv6_addr),
-        IpAddr::V4(_ipv4_addr) => {
-            // Future refinement: check for private ranges/localhost
-            true
-        }
+        IpAddr::V6(ipv6_addr) => !ipv6_addr.is_unicast_link_local() && !ipv6_addr.is_loopback(),
+        IpAddr::V4(_ipv4_addr) => true,
     }
 }
 
@@ -0,0 +1,330 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! A fake peer for testing the scanner without extra hardware.
+//!
+//! A [`Responder`] watches one interface and answers ARP requests and ICMP
+//! echo requests for a set of addresses no machine holds, each from its own
+//! locally administered MAC address (see [`fake_mac`]). Run on one end of a
+//! veth pair, e.g. inside the namespace of the integration tests, it gives
+//! the local scanner a peer whose behaviour is under control:
+//!
+//! * [`Responder::delay`] holds every answer back, to check measured RTTs.
+//! * [`Responder::drop_rate`] ignores a share of requests, to exercise retries.
+//! * [`Responder::copies`] sends every answer more than once, to exercise
+//!   deduplication.
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use pnet::{
+    datalink::{MacAddr, NetworkInterface},
+    packet::{
+        Packet,
+        arp::{ArpOperations, ArpPacket},
+        ethernet::{EtherTypes, EthernetPacket},
+        ip::IpNextHeaderProtocols,
+        ipv4::Ipv4Packet,
+    },
+};
+use tokio::{sync::mpsc, task::JoinHandle, time::Instant};
+use zond_common::{debug, models::ip::set::IpSet, utils::privilege, warn};
+use zond_protocols as protocol;
+
+use crate::error::ZondError;
+use crate::network::channel::{self, EthernetHandle};
+
+/// How often the receive loop checks whether it was stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const REPLY_TTL: u8 = 64;
+
+/// Kind of request a [`Responder`] answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Arp,
+    IcmpEcho,
+}
+
+/// Counts of what a [`Responder`] did until it was stopped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponderStats {
+    pub arp: usize,
+    pub icmp: usize,
+    /// Requests for a fake address left unanswered on purpose.
+    pub dropped: usize,
+}
+
+/// A frame answering one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reply {
+    kind: RequestKind,
+    /// The fake address asked for.
+    target: Ipv4Addr,
+    requester: Ipv4Addr,
+    frame: Vec<u8>,
+}
+
+/// Answers ARP and ICMP echo requests for addresses no machine holds.
+#[derive(Debug, Clone)]
+pub struct Responder {
+    addresses: IpSet,
+    delay: Duration,
+    drop_rate: f64,
+    copies: usize,
+    icmp: bool,
+}
+
+impl Responder {
+    /// Answers for the IPv4 addresses of `addresses`, at once and once.
+    pub fn new(addresses: IpSet) -> Self {
+        Self {
+            addresses,
+            delay: Duration::ZERO,
+            drop_rate: 0.0,
+            copies: 1,
+            icmp: true,
+        }
+    }
+
+    /// Holds every answer back for `delay`.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Leaves this share of requests (0.0 to 1.0) unanswered.
+    pub fn drop_rate(mut self, drop_rate: f64) -> Self {
+        self.drop_rate = drop_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sends every answer `copies` times.
+    pub fn copies(mut self, copies: usize) -> Self {
+        self.copies = copies.max(1);
+        self
+    }
+
+    /// Answers ARP only, like a host that ignores pings.
+    pub fn without_icmp(mut self) -> Self {
+        self.icmp = false;
+        self
+    }
+
+    /// Answers on `intf` until the scan is stopped, e.g. by Ctrl-C.
+    ///
+    /// ### Errors
+    /// - [`ZondError::PermissionDenied`] without raw socket access.
+    /// - The error of opening the capture on `intf`.
+    pub async fn run(&self, intf: &NetworkInterface) -> Result<ResponderStats, ZondError> {
+        super::reset_stop_signal();
+        if !privilege::has_raw_socket_access() {
+            return Err(ZondError::PermissionDenied(
+                "answering ARP needs raw sockets, run as root".to_string(),
+            ));
+        }
+
+        // Requests for the fake addresses are not addressed to this machine.
+        let EthernetHandle { mut tx, mut rx, .. } = channel::start_capture_with(intf, true)?;
+
+        // Every answer is held back equally long, so they leave in order.
+        let (out_tx, mut out_rx) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+        let sender: JoinHandle<()> = tokio::spawn(async move {
+            while let Some((due, frame)) = out_rx.recv().await {
+                tokio::time::sleep_until(due).await;
+                if let Some(Err(e)) = tx.send_to(&frame, None) {
+                    warn!("Failed to send answer: {e}");
+                }
+            }
+        });
+
+        let mut stats: ResponderStats = ResponderStats::default();
+        while !super::STOP_SIGNAL.load(Ordering::Relaxed) {
+            let Ok(received) = tokio::time::timeout(POLL_INTERVAL, rx.recv()).await else {
+                continue;
+            };
+            let Some((frame, _)) = received else {
+                break;
+            };
+            let Some(reply) = self.answer(&frame) else {
+                continue;
+            };
+            if rand::random_bool(self.drop_rate) {
+                stats.dropped += 1;
+                continue;
+            }
+
+            debug!(
+                verbosity = 2,
+                "Answering {:?} for {} from {}", reply.kind, reply.target, reply.requester
+            );
+            match reply.kind {
+                RequestKind::Arp => stats.arp += 1,
+                RequestKind::IcmpEcho => stats.icmp += 1,
+            }
+            let due: Instant = Instant::now() + self.delay;
+            for _ in 0..self.copies {
+                let _ = out_tx.send((due, reply.frame.clone()));
+            }
+        }
+
+        drop(out_tx);
+        let _ = sender.await;
+        Ok(stats)
+    }
+
+    /// Builds the answer to `frame`, if it asks for one of the fake addresses.
+    fn answer(&self, frame: &[u8]) -> Option<Reply> {
+        let eth: EthernetPacket = EthernetPacket::new(frame)?;
+        match eth.get_ethertype() {
+            EtherTypes::Arp => self.answer_arp(&eth),
+            EtherTypes::Ipv4 if self.icmp => self.answer_echo(&eth),
+            _ => None,
+        }
+    }
+
+    fn answer_arp(&self, eth: &EthernetPacket) -> Option<Reply> {
+        let arp: ArpPacket = ArpPacket::new(eth.payload())?;
+        let target: Ipv4Addr = arp.get_target_proto_addr();
+        if arp.get_operation() != ArpOperations::Request || !self.owns(target) {
+            return None;
+        }
+        let requester: Ipv4Addr = arp.get_sender_proto_addr();
+        let frame: Vec<u8> = protocol::arp::create_reply(
+            fake_mac(target),
+            arp.get_sender_hw_addr(),
+            target,
+            requester,
+        )
+        .ok()?;
+        Some(Reply {
+            kind: RequestKind::Arp,
+            target,
+            requester,
+            frame,
+        })
+    }
+
+    fn answer_echo(&self, eth: &EthernetPacket) -> Option<Reply> {
+        let ipv4: Ipv4Packet = Ipv4Packet::new(eth.payload())?;
+        let target: Ipv4Addr = ipv4.get_destination();
+        if ipv4.get_next_level_protocol() != IpNextHeaderProtocols::Icmp || !self.owns(target) {
+            return None;
+        }
+        let requester: Ipv4Addr = ipv4.get_source();
+        let icmp: Vec<u8> = protocol::icmp::create_echo_reply_v4(ipv4.payload())?;
+
+        let mut frame: Vec<u8> =
+            protocol::ethernet::make_header(fake_mac(target), eth.get_source(), EtherTypes::Ipv4)
+                .ok()?;
+        let ip: Vec<u8> = protocol::ip::create_ipv4_packet(
+            target,
+            requester,
+            IpNextHeaderProtocols::Icmp,
+            REPLY_TTL,
+            &icmp,
+        )
+        .ok()?;
+        frame.extend_from_slice(&ip);
+        Some(Reply {
+            kind: RequestKind::IcmpEcho,
+            target,
+            requester,
+            frame,
+        })
+    }
+
+    fn owns(&self, ip: Ipv4Addr) -> bool {
+        self.addresses.contains(&IpAddr::V4(ip))
+    }
+}
+
+/// The MAC address a [`Responder`] answers for `ip` from: locally
+/// administered, `02:5a` followed by the four octets of `ip`.
+pub fn fake_mac(ip: Ipv4Addr) -> MacAddr {
+    let [a, b, c, d] = ip.octets();
+    MacAddr::new(0x02, 0x5A, a, b, c, d)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCANNER_MAC: MacAddr = MacAddr(0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF);
+    const SCANNER: Ipv4Addr = Ipv4Addr::new(10, 200, 0, 1);
+    const FAKE: Ipv4Addr = Ipv4Addr::new(10, 200, 0, 50);
+
+    fn responder() -> Responder {
+        let mut addresses: IpSet = IpSet::new();
+        addresses.insert(IpAddr::V4(FAKE));
+        Responder::new(addresses)
+    }
+
+    fn ping(dst: Ipv4Addr) -> Vec<u8> {
+        let mut frame: Vec<u8> =
+            protocol::ethernet::make_header(SCANNER_MAC, fake_mac(dst), EtherTypes::Ipv4).unwrap();
+        let icmp: Vec<u8> = protocol::icmp::create_echo_request_v4(0x1234, 1).unwrap();
+        frame.extend(
+            protocol::ip::create_ipv4_packet(SCANNER, dst, IpNextHeaderProtocols::Icmp, 64, &icmp)
+                .unwrap(),
+        );
+        frame
+    }
+
+    #[test]
+    fn answers_arp_for_fake_addresses_only() {
+        let request: Vec<u8> =
+            protocol::arp::create_packet(SCANNER_MAC, MacAddr::zero(), SCANNER, FAKE).unwrap();
+        let reply: Reply = responder().answer(&request).unwrap();
+        assert_eq!(reply.kind, RequestKind::Arp);
+        assert_eq!(
+            reply.frame,
+            protocol::arp::create_reply(fake_mac(FAKE), SCANNER_MAC, FAKE, SCANNER).unwrap()
+        );
+
+        let other: Vec<u8> = protocol::arp::create_packet(
+            SCANNER_MAC,
+            MacAddr::zero(),
+            SCANNER,
+            Ipv4Addr::new(10, 200, 0, 51),
+        )
+        .unwrap();
+        assert!(responder().answer(&other).is_none());
+        // Its own answers are not requests.
+        assert!(responder().answer(&reply.frame).is_none());
+    }
+
+    #[test]
+    fn answers_pings_unless_told_not_to() {
+        let reply: Reply = responder().answer(&ping(FAKE)).unwrap();
+        assert_eq!(reply.kind, RequestKind::IcmpEcho);
+        assert_eq!((reply.target, reply.requester), (FAKE, SCANNER));
+
+        let eth: EthernetPacket = EthernetPacket::new(&reply.frame).unwrap();
+        assert_eq!(eth.get_destination(), SCANNER_MAC);
+        assert_eq!(eth.get_source(), fake_mac(FAKE));
+        let ipv4: Ipv4Packet = Ipv4Packet::new(eth.payload()).unwrap();
+        assert_eq!(ipv4.get_destination(), SCANNER);
+        assert_eq!(
+            protocol::icmp::parse_echo_reply_v4(ipv4.payload()),
+            Some((0x1234, 1))
+        );
+
+        assert!(responder().without_icmp().answer(&ping(FAKE)).is_none());
+        assert!(responder().answer(&ping(SCANNER)).is_none());
+    }
+}
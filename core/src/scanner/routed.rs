@@ -7,89 +7,168 @@
 use std::{
     collections::{HashMap, VecDeque, hash_map::Entry},
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
-    sync::atomic::Ordering,
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, atomic::Ordering},
+    thread,
     time::{Duration, Instant},
 };
 
 use anyhow::ensure;
 use async_trait::async_trait;
-use pnet::{datalink::NetworkInterface, packet::tcp::TcpPacket};
+use pnet::{
+    datalink::NetworkInterface,
+    packet::{Packet, icmp::IcmpPacket, ipv4::Ipv4Packet, tcp::TcpPacket},
+};
 use tokio::sync::mpsc::UnboundedSender;
-use zond_common::{error, success};
+use tracing::Span;
+use zond_common::{debug, error, phase, utils::paths, warn};
 
-use zond_common::models::{host::Host, ip::set::IpSet};
+use zond_common::models::{
+    host::{Host, NetworkRole, Unreachable},
+    ip::set::IpSet,
+    port::{Port, PortState, Protocol},
+    reachability::Method,
+};
 use zond_protocols as protocol;
+use zond_protocols::sctp::{self, ReplyKind};
 
-use crate::network::transport::{self, TransportHandle, TransportType};
+use crate::error::ZondError;
+use crate::network::transport::{self, RawPacket, TransportHandle, TransportSender, TransportType};
 
 use super::NetworkExplorer;
+use super::capture::{FrameRing, LinkType};
+use super::deadline::AdaptiveWindow;
+use super::ipid::{self, IpIdReport, IpIdSample};
+use super::spill::{self, ProbeLog};
+use super::trace::{self, PacketTrace};
 
 // this shit needs improvement
 const MIN_SCAN_DURATION: Duration = Duration::from_millis(200);
 const MAX_SCAN_DURATION: Duration = Duration::from_millis(3000);
 const MS_PER_IP: f64 = 0.5;
+/// Longest wait after the last probe that measured round trips can ask for.
+const MAX_ADAPTIVE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Source ports probes are sent from when the user did not pick a range.
+pub const DEFAULT_SOURCE_PORTS: RangeInclusive<u16> = 50_000..=65_535;
+
+/// Port SYN probes are sent to when the user did not pick one.
+pub const DEFAULT_SYN_PORT: u16 = 443;
 
+/// Ports SCTP INIT probes are sent to when the user did not pick any:
+/// Diameter, M3UA, SIP, S1AP and NGAP.
+pub const DEFAULT_SCTP_PORTS: [u16; 5] = [3868, 2905, 5060, 36412, 38412];
+
+/// TCP source port, or the identifier of an ICMP echo request.
+type SrcPort = u16;
+/// TCP sequence number, ICMP echo sequence or SCTP initiate tag.
 type SeqNum = u32;
 
+/// The kind of probe sent to every target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    /// A TCP SYN to each of the given ports; any TCP answer counts as alive.
+    Syn(Vec<u16>),
+    /// An ICMPv4 echo request; only matching echo replies count as alive.
+    IcmpEcho,
+    /// An SCTP INIT to each of the given ports; any SCTP answer counts as
+    /// alive, and INIT-ACKs and ABORTs mark the port open or closed.
+    SctpInit(Vec<u16>),
+}
+
+impl Probe {
+    /// The kind of discovery probe this is, as recorded on the hosts.
+    pub fn method(&self) -> Method {
+        match self {
+            Probe::Syn(_) => Method::Tcp,
+            Probe::IcmpEcho => Method::Icmp,
+            Probe::SctpInit(_) => Method::Sctp,
+        }
+    }
+}
+
 pub struct RoutedScanner {
     src_v4: Option<Ipv4Addr>,
     src_v6: Option<Ipv6Addr>,
     responded_ips: HashMap<IpAddr, VecDeque<Duration>>,
     ips: IpSet,
-    tcp_handle: TransportHandle,
+    probe: Probe,
+    probe_tx: Arc<Mutex<TransportSender>>,
+    reply_handle: TransportHandle,
+    ttls: HashMap<IpAddr, u8>,
+    icmp_handle: Option<TransportHandle>,
+    unreachable: HashMap<IpAddr, Unreachable>,
     dns_tx: Option<UnboundedSender<IpAddr>>,
-    rtt_map: HashMap<(IpAddr, SeqNum), Instant>,
+    source_ports: RangeInclusive<u16>,
+    rtt_map: ProbeLog,
+    trace: PacketTrace,
+    capture: FrameRing,
+    ip_ids: HashMap<IpAddr, Vec<IpIdSample>>,
+    window: AdaptiveWindow,
+    /// Port states learned from SCTP answers.
+    sctp_ports: HashMap<IpAddr, Vec<Port>>,
+    /// Keep listening until the deadline even once every target answered, so
+    /// the answers of all ports are in.
+    exhaustive: bool,
 }
 
 #[async_trait]
 impl NetworkExplorer for RoutedScanner {
     async fn discover_hosts(&mut self) -> anyhow::Result<Vec<Host>> {
+        let send_phase: Span = phase!("send");
         if let Err(e) = self.send_discovery_packets() {
             error!("Failed to send packets: {e}");
         }
+        drop(send_phase);
+        let receive_phase: Span = phase!("receive");
 
-        let deadline: Instant = calculate_deadline(self.ips.len() as usize);
+        let probes_per_ip: usize = match &self.probe {
+            Probe::Syn(ports) | Probe::SctpInit(ports) => ports.len(),
+            Probe::IcmpEcho => 1,
+        };
+        let sent_at: Instant = Instant::now();
+        let fixed_window: Duration = receive_window(self.ips.len() as usize * probes_per_ip);
+        let mut capture_failure: Option<ZondError> = None;
 
         loop {
+            let settled: usize = self.responded_ips.len() + self.unreachable.len();
             if super::STOP_SIGNAL.load(Ordering::Relaxed)
-                || self.ips.len() == (self.responded_ips.len() as u64)
+                || (!self.exhaustive && self.ips.len() == settled as u64)
             {
                 break;
             }
 
+            // Replies timed so far move the deadline, see [`AdaptiveWindow`].
+            let deadline: Instant = sent_at + self.window.window().unwrap_or(fixed_window);
             let remaining: Duration = deadline.saturating_duration_since(Instant::now());
             if remaining.is_zero() {
                 break;
             }
 
             tokio::select! {
-                res = self.tcp_handle.rx.recv() => {
+                res = self.reply_handle.rx.recv() => {
                     match res {
-                        Some((bytes, ip)) => {
-                            if !self.ips.contains(&ip) {
-                                continue;
-                            }
-
-                            let entry = self.responded_ips.entry(ip);
-                            let is_new = matches!(entry, Entry::Vacant(_));
-                            let latencies = entry.or_default();
-
-                            if is_new {
-                                let _ = self.dns_tx.as_ref().map(|dns| dns.send(ip));
-                                super::increment_host_count();
+                        Some((bytes, ip, received)) => self.handle_reply(&bytes, ip, received),
+                        None => {
+                            if let Err(e) = self.reply_handle.recover() {
+                                capture_failure = Some(e);
+                                break;
                             }
-
-                            if let Some(tcp_packet) = TcpPacket::new(&bytes) {
-                                let ack_num: u32 = tcp_packet.get_acknowledgement();
-                                let original_seq: u32 = ack_num.wrapping_sub(1);
-
-                                if let Some(start_time) = self.rtt_map.remove(&(ip, original_seq)) {
-                                    let rtt: Duration = start_time.elapsed();
-                                    latencies.push_back(rtt);
-                                }
+                        }
+                    }
+                },
+                res = recv_optional(&mut self.icmp_handle) => {
+                    match res {
+                        Some((bytes, reporter, _)) => self.process_icmp_packet(&bytes, reporter),
+                        None => {
+                            if let Some(Err(e)) =
+                                self.icmp_handle.as_mut().map(TransportHandle::recover)
+                            {
+                                warn!("{e}, unreachable targets will look silent");
+                                self.icmp_handle = None;
                             }
-                        },
-                        None => break,
+                        }
                     }
                 },
                 _ = tokio::time::sleep(remaining) => {
@@ -98,16 +177,38 @@ impl NetworkExplorer for RoutedScanner {
             }
         }
 
+        drop(receive_phase);
+        self.trace.flush();
+        if self.rtt_map.spilled() > 0 {
+            debug!(
+                verbosity = 1,
+                "Kept {} unanswered probe(s) on disk to stay within the memory limit",
+                self.rtt_map.spilled()
+            );
+        }
         self.rtt_map.clear();
-        let hosts: Vec<Host> = self
+        if let Some(e) = capture_failure {
+            return Err(e.into());
+        }
+        let method: Method = self.probe.method();
+        let mut hosts: Vec<Host> = self
             .responded_ips
             .drain()
             .map(|(ip, latencies)| {
                 let mut host = Host::new(ip);
                 host.set_rtts(latencies);
+                host.ttl = self.ttls.get(&ip).copied();
+                host.reachability.answered(ip, method);
+                for port in self.sctp_ports.remove(&ip).unwrap_or_default() {
+                    host.add_port(port);
+                }
                 host
             })
             .collect();
+        apply_ip_id_report(&mut hosts, ipid::analyze(&self.ip_ids));
+
+        // A router answered for these targets, so they are not hosts found.
+        super::record_unreachable(self.unreachable.drain().map(|(_, unreachable)| unreachable));
 
         Ok(hosts)
     }
@@ -117,10 +218,35 @@ impl RoutedScanner {
     pub fn new(
         intf: NetworkInterface,
         ips: IpSet,
+        source_ports: RangeInclusive<u16>,
+        probe: Probe,
         dns_tx: Option<UnboundedSender<IpAddr>>,
     ) -> anyhow::Result<Self> {
-        let tcp_handle: TransportHandle =
-            transport::start_packet_capture(TransportType::TcpLayer4)?;
+        match &probe {
+            Probe::Syn(ports) => ensure!(!ports.is_empty(), "no SYN probe ports given"),
+            Probe::SctpInit(ports) => ensure!(!ports.is_empty(), "no SCTP probe ports given"),
+            Probe::IcmpEcho => {}
+        }
+
+        let (send_type, reply_type) = match probe {
+            Probe::Syn(_) => (TransportType::TcpLayer4, TransportType::TcpLayer3),
+            Probe::IcmpEcho => (TransportType::IcmpLayer4, TransportType::IcmpLayer3),
+            Probe::SctpInit(_) => (TransportType::SctpLayer4, TransportType::SctpLayer3),
+        };
+        let probe_tx: Arc<Mutex<TransportSender>> = transport::open_sender(send_type)?;
+        let reply_handle: TransportHandle = transport::start_packet_capture(reply_type)?;
+
+        // Unreachable messages only quote TCP probes here, see `process_icmp_packet`.
+        let icmp_handle: Option<TransportHandle> = match probe {
+            Probe::IcmpEcho | Probe::SctpInit(_) => None,
+            Probe::Syn(_) => match transport::start_packet_capture(TransportType::IcmpLayer4) {
+                Ok(handle) => Some(handle),
+                Err(e) => {
+                    warn!("ICMP capture unavailable, unreachable targets will look silent: {e}");
+                    None
+                }
+            },
+        };
 
         let src_v4: Option<Ipv4Addr> = intf.ips.iter().find_map(|ip_net| match ip_net.ip() {
             IpAddr::V4(ipv4) => Some(ipv4),
@@ -136,57 +262,406 @@ impl RoutedScanner {
             src_v4.is_some() || src_v6.is_some(),
             "interface has no ip addresses"
         );
+        ensure!(
+            !source_ports.is_empty() && *source_ports.start() > 0,
+            "invalid source port range {source_ports:?}"
+        );
+
+        let trace: PacketTrace = PacketTrace::new(match probe {
+            Probe::Syn(ref ports) => {
+                let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+                format!("syn:{} on {}", ports.join(","), intf.name)
+            }
+            Probe::IcmpEcho => format!("icmp on {}", intf.name),
+            Probe::SctpInit(ref ports) => {
+                let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+                format!("sctp:{} on {}", ports.join(","), intf.name)
+            }
+        });
+
+        let capture: FrameRing = FrameRing::new(format!("routed-{}", intf.name), LinkType::RawIp);
 
         Ok(Self {
             src_v4,
             src_v6,
             responded_ips: HashMap::new(),
             ips,
-            tcp_handle,
+            probe,
+            probe_tx,
+            reply_handle,
+            ttls: HashMap::new(),
+            icmp_handle,
+            unreachable: HashMap::new(),
             dns_tx,
-            rtt_map: HashMap::new(),
+            source_ports,
+            rtt_map: ProbeLog::with_memory(spill::DEFAULT_MEMORY_LIMIT, paths::spill_dir()),
+            trace,
+            capture,
+            ip_ids: HashMap::new(),
+            window: AdaptiveWindow::new(MIN_SCAN_DURATION, MAX_ADAPTIVE_WINDOW),
+            sctp_ports: HashMap::new(),
+            exhaustive: false,
         })
     }
 
+    /// Waits out the whole receive window instead of stopping once every
+    /// target answered one probe, for port sweeps rather than discovery.
+    pub fn exhaustive(mut self) -> Self {
+        self.exhaustive = true;
+        self
+    }
+
+    /// Keeps at most `bytes` worth of unanswered probes in memory, moving the
+    /// rest to disk.
+    pub fn memory_limit(mut self, bytes: u64) -> Self {
+        self.rtt_map = ProbeLog::with_memory(bytes, paths::spill_dir());
+        self
+    }
+
+    /// Keeps a reply of a target in the capture ring and processes it, saving
+    /// the ring before passing on a panic of the parser.
+    fn handle_reply(&mut self, bytes: &[u8], ip: IpAddr, received: Instant) {
+        if !self.ips.contains(&ip) {
+            return;
+        }
+        self.capture.record(bytes);
+        let outcome: thread::Result<()> =
+            panic::catch_unwind(AssertUnwindSafe(|| self.process_reply(bytes, ip, received)));
+        if let Err(payload) = outcome {
+            self.capture.dump("Parsing a reply panicked");
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Records a reply from a target.
+    ///
+    /// For SYN probes any TCP segment proves the host is alive, but only one that
+    /// acknowledges a probe we sent yields an RTT. For ICMP probes only echo
+    /// replies count, as the capture also sees every other ICMP message. SCTP
+    /// probes work like SYNs, matched by the initiate tag the answer echoes.
+    ///
+    /// Every reply also yields an IP ID sample for [`ipid::analyze`], so a
+    /// sweep over several ports samples each responder several times.
+    ///
+    /// The RTT runs from the moment the kernel accepted the probe to the
+    /// `received` stamp of the capture, see [`crate::network::timestamp`].
+    fn process_reply(&mut self, bytes: &[u8], ip: IpAddr, received: Instant) {
+        if !self.ips.contains(&ip) {
+            return;
+        }
+        let Some(ipv4_packet) = Ipv4Packet::new(bytes) else {
+            self.capture.parse_error();
+            return;
+        };
+        self.trace.reply(ip, || trace::describe_ipv4(bytes));
+
+        let tcp_packet: Option<TcpPacket> = match self.probe {
+            Probe::Syn(_) => TcpPacket::new(ipv4_packet.payload()),
+            _ => None,
+        };
+        let sctp_reply: Option<sctp::Reply> = match self.probe {
+            Probe::SctpInit(_) => sctp::parse_reply(ipv4_packet.payload()),
+            _ => None,
+        };
+        if tcp_packet.is_none() && sctp_reply.is_none() && self.probe != Probe::IcmpEcho {
+            self.capture.parse_error();
+        }
+        let probe_key: Option<(SrcPort, SeqNum)> = match self.probe {
+            Probe::Syn(_) => tcp_packet.as_ref().map(|tcp_packet| {
+                let ack_num: u32 = tcp_packet.get_acknowledgement();
+                (tcp_packet.get_destination(), ack_num.wrapping_sub(1))
+            }),
+            Probe::IcmpEcho => match protocol::icmp::parse_echo_reply_v4(ipv4_packet.payload()) {
+                Some((identifier, sequence)) => Some((identifier, sequence as SeqNum)),
+                None => return,
+            },
+            Probe::SctpInit(_) => sctp_reply.and_then(|reply| Some((reply.dst_port, reply.tag?))),
+        };
+
+        self.unreachable.remove(&ip);
+        self.ttls.insert(ip, ipv4_packet.get_ttl());
+        self.ip_ids.entry(ip).or_default().push(IpIdSample {
+            at: received,
+            id: ipv4_packet.get_identification(),
+            ttl: ipv4_packet.get_ttl(),
+        });
+
+        let entry = self.responded_ips.entry(ip);
+        let is_new = matches!(entry, Entry::Vacant(_));
+        let latencies = entry.or_default();
+
+        if let Some((src_port, seq)) = probe_key
+            && let Some(start_time) = self.rtt_map.remove(&(ip, src_port, seq))
+        {
+            let rtt: Duration = received.saturating_duration_since(start_time);
+            latencies.push_back(rtt);
+            self.window.record(rtt);
+        }
+        if let Some(reply) = sctp_reply {
+            let state: Option<PortState> = match reply.kind {
+                ReplyKind::InitAck => Some(PortState::Open),
+                ReplyKind::Abort => Some(PortState::Closed),
+                ReplyKind::Other => None,
+            };
+            if let Some(state) = state {
+                let port: Port = Port::new(reply.src_port, Protocol::Sctp, state);
+                self.sctp_ports.entry(ip).or_default().push(port);
+            }
+        }
+
+        if is_new {
+            let _ = self.dns_tx.as_ref().map(|dns| dns.send(ip));
+            super::increment_host_count();
+        }
+    }
+
+    /// Records an ICMP destination-unreachable message that answers one of our probes.
+    ///
+    /// Messages that do not quote a probe we sent (matched by target, source port
+    /// and sequence number) are ignored, as are targets that already answered.
+    fn process_icmp_packet(&mut self, bytes: &[u8], reporter: IpAddr) {
+        let Some(report) = protocol::icmp::parse_tcp_unreachable_v4(bytes) else {
+            return;
+        };
+
+        let target: IpAddr = IpAddr::V4(report.target);
+        if self.responded_ips.contains_key(&target)
+            || self
+                .rtt_map
+                .remove(&(target, report.src_port, report.seq))
+                .is_none()
+        {
+            return;
+        }
+
+        let local: IpAddr = IpAddr::V4(self.src_v4.unwrap_or(Ipv4Addr::UNSPECIFIED));
+        self.trace.reply(target, || {
+            format!(
+                "{} ({:?})",
+                trace::describe_icmp(reporter, local, bytes),
+                report.reason
+            )
+        });
+        self.unreachable.insert(
+            target,
+            Unreachable {
+                target,
+                reporter,
+                reason: report.reason,
+            },
+        );
+    }
+
+    /// Sends one probe per target, or one SYN per target and port.
+    ///
+    /// Ports are swept one after another, so a host sees its SYNs spread over the
+    /// whole sweep rather than in a burst. Each SYN uses its own source port,
+    /// drawn from `source_ports`, and sequence number, so the sweep is not
+    /// trivially filterable by a single port and the RSTs it triggers are spread
+    /// out instead of hammering one local port. Echo requests likewise get a
+    /// random identifier and sequence number.
     fn send_discovery_packets(&mut self) -> anyhow::Result<()> {
-        let src_port: u16 = rand::random_range(50_000..u16::MAX);
-        let dst_port: u16 = 443;
-        for dst_addr in self.ips.iter() {
-            let src_addr: IpAddr = match dst_addr {
-                IpAddr::V4(_) => {
-                    ensure!(self.src_v4.is_some(), "interface has no ipv4 address");
-                    IpAddr::V4(self.src_v4.unwrap())
+        let ips: IpSet = self.ips.clone();
+        match self.probe.clone() {
+            Probe::Syn(ports) => {
+                for dst_port in ports {
+                    for dst_addr in &ips {
+                        super::hold_while_paused();
+                        self.send_syn(dst_addr, dst_port)?;
+                    }
                 }
-                IpAddr::V6(_) => {
-                    ensure!(self.src_v6.is_some(), "interface has no ipv6 address");
-                    IpAddr::V6(self.src_v6.unwrap())
+            }
+            Probe::IcmpEcho => {
+                // Echo requests are ICMPv4, which IPv6 targets do not answer.
+                for dst_addr in &ips {
+                    let IpAddr::V4(dst_addr) = dst_addr else {
+                        continue;
+                    };
+                    super::hold_while_paused();
+                    self.send_echo_request(dst_addr)?;
                 }
-            };
+            }
+            Probe::SctpInit(ports) => {
+                for dst_port in ports {
+                    for dst_addr in &ips {
+                        super::hold_while_paused();
+                        self.send_init(dst_addr, dst_port)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 
-            let seq_num: u32 = rand::random_range(0..=u32::MAX);
-            let packet: Vec<u8> =
-                protocol::tcp::create_packet(&src_addr, &dst_addr, src_port, dst_port, seq_num)?;
+    fn send_syn(&mut self, dst_addr: IpAddr, dst_port: u16) -> anyhow::Result<()> {
+        let src_addr: IpAddr = match dst_addr {
+            IpAddr::V4(_) => {
+                ensure!(self.src_v4.is_some(), "interface has no ipv4 address");
+                IpAddr::V4(self.src_v4.unwrap())
+            }
+            IpAddr::V6(_) => {
+                ensure!(self.src_v6.is_some(), "interface has no ipv6 address");
+                IpAddr::V6(self.src_v6.unwrap())
+            }
+        };
 
-            if let Some(packet) = TcpPacket::new(&packet) {
-                let mut tx = self.tcp_handle.tx.lock().unwrap();
-                match tx.send_to(packet, dst_addr) {
-                    Ok(_) => {
-                        success!(verbosity = 2, "Sent discovery packet to {dst_addr}");
-                        self.rtt_map.insert((dst_addr, seq_num), Instant::now());
-                    }
-                    Err(e) => error!(verbosity = 2, "Failed to send packet to {dst_addr}: {e}"),
+        let src_port: SrcPort = rand::random_range(self.source_ports.clone());
+        let seq_num: SeqNum = rand::random_range(0..=u32::MAX);
+        let packet: Vec<u8> =
+            protocol::tcp::create_packet(&src_addr, &dst_addr, src_port, dst_port, seq_num)?;
+
+        if let Some(packet) = TcpPacket::new(&packet) {
+            let mut tx = self.probe_tx.lock().unwrap();
+            match tx.send_to(packet, dst_addr) {
+                Ok(_) => {
+                    let sent: Instant = Instant::now();
+                    super::record_probe();
+                    self.trace.probe(dst_addr, || {
+                        trace::describe_tcp(src_addr, dst_addr, packet.packet())
+                    });
+                    self.rtt_map.insert((dst_addr, src_port, seq_num), sent);
+                }
+                Err(e) => error!(verbosity = 2, "Failed to send packet to {dst_addr}: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends an SCTP INIT with a random source port and initiate tag.
+    ///
+    /// SCTP checksums cover no pseudo-header, so unlike a SYN the packet does
+    /// not depend on the source address.
+    fn send_init(&mut self, dst_addr: IpAddr, dst_port: u16) -> anyhow::Result<()> {
+        let src_addr: IpAddr = IpAddr::V4(self.src_v4.unwrap_or(Ipv4Addr::UNSPECIFIED));
+        let src_port: SrcPort = rand::random_range(self.source_ports.clone());
+        let tag: SeqNum = rand::random_range(1..=u32::MAX);
+        let packet: Vec<u8> = protocol::sctp::create_init_packet(src_port, dst_port, tag)?;
+
+        let mut tx = self.probe_tx.lock().unwrap();
+        match tx.send_to(RawPacket(&packet), dst_addr) {
+            Ok(_) => {
+                let sent: Instant = Instant::now();
+                super::record_probe();
+                self.trace.probe(dst_addr, || {
+                    trace::describe_sctp(src_addr, dst_addr, &packet)
+                });
+                self.rtt_map.insert((dst_addr, src_port, tag), sent);
+            }
+            Err(e) => error!(verbosity = 2, "Failed to send INIT to {dst_addr}: {e}"),
+        }
+        Ok(())
+    }
+
+    fn send_echo_request(&mut self, dst_addr: Ipv4Addr) -> anyhow::Result<()> {
+        ensure!(self.src_v4.is_some(), "interface has no ipv4 address");
+        let src_addr: IpAddr = IpAddr::V4(self.src_v4.unwrap());
+        let dst_addr: IpAddr = IpAddr::V4(dst_addr);
+        let identifier: SrcPort = rand::random();
+        let sequence: u16 = rand::random();
+        let packet: Vec<u8> = protocol::icmp::create_echo_request_v4(identifier, sequence)?;
+
+        if let Some(packet) = IcmpPacket::new(&packet) {
+            let mut tx = self.probe_tx.lock().unwrap();
+            match tx.send_to(packet, dst_addr) {
+                Ok(_) => {
+                    let sent: Instant = Instant::now();
+                    super::record_probe();
+                    self.trace.probe(dst_addr, || {
+                        trace::describe_icmp(src_addr, dst_addr, packet.packet())
+                    });
+                    self.rtt_map
+                        .insert((dst_addr, identifier, sequence as SeqNum), sent);
                 }
+                Err(e) => error!(
+                    verbosity = 2,
+                    "Failed to send echo request to {dst_addr}: {e}"
+                ),
             }
         }
         Ok(())
     }
 }
 
-fn calculate_deadline(ips_len: usize) -> Instant {
-    let variable_ms = (ips_len as f64 * MS_PER_IP) as u64;
+/// Flags likely NAT devices and merges addresses found to be one machine.
+///
+/// The merged host keeps the addresses, ports and roles of every host in the
+/// group, see [`Host::merge`].
+fn apply_ip_id_report(hosts: &mut Vec<Host>, report: IpIdReport) {
+    for host in hosts.iter_mut() {
+        if report.nat.contains(&host.primary_ip) {
+            debug!(
+                verbosity = 1,
+                "{} answers with mixed TTLs and IP IDs, likely NAT", host.primary_ip
+            );
+            host.network_roles.insert(NetworkRole::Nat);
+        }
+    }
 
-    let scan_duration = (MIN_SCAN_DURATION + Duration::from_millis(variable_ms))
-        .clamp(MIN_SCAN_DURATION, MAX_SCAN_DURATION);
+    for group in report.same_host {
+        let (linked, rest): (Vec<Host>, Vec<Host>) = std::mem::take(hosts)
+            .into_iter()
+            .partition(|host| group.contains(&host.primary_ip));
+        *hosts = rest;
 
-    Instant::now() + scan_duration
+        let mut linked = linked.into_iter();
+        let Some(mut merged) = linked.next() else {
+            continue;
+        };
+        for other in linked {
+            debug!(
+                verbosity = 1,
+                "{} and {} share an IP ID counter, merging", merged.primary_ip, other.primary_ip
+            );
+            merged.merge(other);
+        }
+        hosts.push(merged);
+    }
+}
+
+/// Receives from an optional capture, never resolving if there is none.
+async fn recv_optional(handle: &mut Option<TransportHandle>) -> Option<(Vec<u8>, IpAddr, Instant)> {
+    match handle {
+        Some(handle) => handle.rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// How long replies are awaited once `probes` probes went out, until enough
+/// of them were timed for an [`AdaptiveWindow`].
+pub(super) fn receive_window(probes: usize) -> Duration {
+    let variable_ms = (probes as f64 * MS_PER_IP) as u64;
+
+    (MIN_SCAN_DURATION + Duration::from_millis(variable_ms))
+        .clamp(MIN_SCAN_DURATION, MAX_SCAN_DURATION)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_hosts_keep_every_address_and_port() {
+        let ip = |last: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, last));
+        let mut hosts: Vec<Host> = (1..=3).map(|last| Host::new(ip(last))).collect();
+        hosts[1].add_port(Port::new(22, Protocol::Tcp, PortState::Open));
+        let report: IpIdReport = IpIdReport {
+            nat: Vec::new(),
+            same_host: vec![vec![ip(1), ip(2)]],
+        };
+
+        apply_ip_id_report(&mut hosts, report);
+        assert_eq!(hosts.len(), 2);
+        let merged: &Host = hosts.iter().find(|host| host.ips.len() == 2).unwrap();
+        assert!(merged.ips.contains(&ip(1)) && merged.ips.contains(&ip(2)));
+        assert_eq!(merged.ports().len(), 1);
+    }
 }
@@ -0,0 +1,522 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Probes awaiting an answer, moved to disk past a memory limit.
+//!
+//! Routed scanners remember every probe they send until it is answered, to
+//! time the round trip and to tell an answer from a stray packet. Most probes
+//! of a large sweep are never answered, so on a million-address scan these
+//! records alone outgrow the memory of a small device. A [`ProbeLog`] keeps up
+//! to a limit of them in a hash map and writes the map out to a file as a
+//! sorted run of fixed-size records whenever it fills up: 32 bytes a probe
+//! instead of the ~90 a map entry takes.
+//!
+//! Lookups run on the receive path, so they stay off the disk where they can.
+//! Every run keeps the key of each [`INDEX_STRIDE`]th record in memory, which
+//! narrows a lookup to one block of the file, and the block read last is
+//! kept, since answers arrive in about the order the probes went out. A
+//! lookup checks the map first, then every run whose key range covers the
+//! probe, newest first. Sweeps go through the targets in address order, so
+//! few runs cover any one probe. Answered probes on disk are remembered in
+//! memory rather than written back, so the receive path never writes, and the
+//! file is deleted with the log.
+//!
+//! Local scans keep their records in memory: they sweep one segment, which is
+//! rarely larger than a /16.
+//!
+//! The resolver holds back the addresses of a reverse zone until its SOA
+//! query is answered. An [`AddressQueue`] moves them to disk in batches past
+//! a limit as well, and reads them back once when the zone is settled.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{self, AtomicU32},
+    time::{Duration, Instant},
+};
+
+use zond_common::{debug, utils::paths, warn};
+
+/// Target, TCP or SCTP source port (or ICMP identifier), and sequence number
+/// (or initiate tag) of a probe.
+pub(super) type ProbeKey = (IpAddr, u16, u32);
+
+/// Memory a probe in the map takes, counting the spare capacity of the map.
+pub(super) const ENTRY_BYTES: u64 = 96;
+/// Memory the records of all scanners running at once may take by default.
+pub(crate) const DEFAULT_MEMORY_LIMIT: u64 = 64 * 1024 * 1024;
+
+const KEY_LEN: usize = 23;
+const RECORD_LEN: usize = 32;
+/// Records per block of a run, and per key in its index.
+const INDEX_STRIDE: u64 = 64;
+/// Length of an address in an [`AddressQueue`] file: family, then 16 bytes.
+const ADDRESS_LEN: usize = 17;
+
+/// Tells apart the files of the logs of one process.
+static NEXT_FILE: AtomicU32 = AtomicU32::new(0);
+
+type Key = [u8; KEY_LEN];
+type Record = [u8; RECORD_LEN];
+
+/// When each unanswered probe was sent.
+pub(super) struct ProbeLog {
+    limit: usize,
+    dir: PathBuf,
+    /// Sent times on disk are counted from here.
+    epoch: Instant,
+    recent: HashMap<ProbeKey, Instant>,
+    spill: Option<Spill>,
+}
+
+/// The file records are moved to once the map is full.
+struct Spill {
+    path: PathBuf,
+    file: File,
+    runs: Vec<Run>,
+    /// Keys of the answered probes on disk.
+    answered: HashSet<Key>,
+    /// The block read last, by its offset.
+    block: Option<(u64, Vec<Record>)>,
+}
+
+/// Records written in one go, sorted by key.
+struct Run {
+    offset: u64,
+    len: u64,
+    first: Key,
+    last: Key,
+    /// Key of the first record of every block.
+    index: Vec<Key>,
+}
+
+impl ProbeLog {
+    /// Creates a log that keeps up to `limit` probes in memory and moves the
+    /// rest to a file in `dir`.
+    pub(super) fn new(limit: usize, dir: PathBuf) -> Self {
+        Self {
+            limit: limit.max(1),
+            dir,
+            epoch: Instant::now(),
+            recent: HashMap::new(),
+            spill: None,
+        }
+    }
+
+    /// Creates a log whose probes take up to `bytes` of memory.
+    pub(super) fn with_memory(bytes: u64, dir: PathBuf) -> Self {
+        let limit: usize = usize::try_from(bytes / ENTRY_BYTES).unwrap_or(usize::MAX);
+        Self::new(limit, dir)
+    }
+
+    pub(super) fn insert(&mut self, key: ProbeKey, sent: Instant) {
+        self.recent.insert(key, sent);
+        if self.recent.len() >= self.limit
+            && let Err(e) = self.spill()
+        {
+            warn!("Cannot move probe records to {}: {e}", self.dir.display());
+            self.limit = usize::MAX;
+        }
+    }
+
+    /// Forgets the probe `key` and returns when it was sent, or `None` if it
+    /// is unknown or was answered already.
+    pub(super) fn remove(&mut self, key: &ProbeKey) -> Option<Instant> {
+        if let Some(sent) = self.recent.remove(key) {
+            return Some(sent);
+        }
+        let spill: &mut Spill = self.spill.as_mut()?;
+        match spill.take(&encode_key(key)) {
+            Ok(nanos) => nanos.map(|nanos| self.epoch + Duration::from_nanos(nanos)),
+            Err(e) => {
+                debug!(verbosity = 2, "Probe record lookup failed: {e}");
+                None
+            }
+        }
+    }
+
+    /// Forgets every probe and deletes the file.
+    pub(super) fn clear(&mut self) {
+        self.recent.clear();
+        self.spill = None;
+    }
+
+    /// How many probes were moved to disk.
+    pub(super) fn spilled(&self) -> u64 {
+        self.spill
+            .as_ref()
+            .map_or(0, |spill| spill.runs.iter().map(|run| run.len).sum())
+    }
+
+    /// Moves every probe in the map to a new run on disk.
+    fn spill(&mut self) -> io::Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(Spill::create(&self.dir)?);
+        }
+        let epoch: Instant = self.epoch;
+        let mut records: Vec<Record> = self
+            .recent
+            .drain()
+            .map(|(key, sent)| encode(&key, sent.saturating_duration_since(epoch)))
+            .collect();
+        records.sort_unstable();
+        self.spill
+            .as_mut()
+            .map_or(Ok(()), |spill| spill.append(&records))
+    }
+}
+
+impl Spill {
+    fn create(dir: &Path) -> io::Result<Self> {
+        let id: u32 = NEXT_FILE.fetch_add(1, atomic::Ordering::Relaxed);
+        let path: PathBuf = dir.join(format!("probes-{}-{id}", process::id()));
+        let file: File = create_file(&path)?;
+        Ok(Self {
+            path,
+            file,
+            runs: Vec::new(),
+            answered: HashSet::new(),
+            block: None,
+        })
+    }
+
+    fn append(&mut self, records: &[Record]) -> io::Result<()> {
+        let (Some(first), Some(last)) = (records.first(), records.last()) else {
+            return Ok(());
+        };
+        let offset: u64 = self.file.seek(SeekFrom::End(0))?;
+        let mut writer: BufWriter<&File> = BufWriter::new(&self.file);
+        for record in records {
+            writer.write_all(record)?;
+        }
+        writer.flush()?;
+        self.runs.push(Run {
+            offset,
+            len: records.len() as u64,
+            first: key_of(first),
+            last: key_of(last),
+            index: records
+                .iter()
+                .step_by(INDEX_STRIDE as usize)
+                .map(key_of)
+                .collect(),
+        });
+        Ok(())
+    }
+
+    /// Looks `key` up, newest run first, and marks it answered. Returns its
+    /// sent time, or `None` if it is not on disk or was answered already.
+    fn take(&mut self, key: &Key) -> io::Result<Option<u64>> {
+        if self.answered.contains(key) {
+            return Ok(None);
+        }
+        // Offset and length of the one block of each run that may hold it.
+        let blocks: Vec<(u64, u64)> = self
+            .runs
+            .iter()
+            .rev()
+            .filter(|run| run.first <= *key && *key <= run.last)
+            .map(|run| {
+                let block: u64 = run.index.partition_point(|first| first <= key) as u64 - 1;
+                let start: u64 = block * INDEX_STRIDE;
+                (
+                    run.offset + start * RECORD_LEN as u64,
+                    INDEX_STRIDE.min(run.len - start),
+                )
+            })
+            .collect();
+
+        for (offset, len) in blocks {
+            let records: &[Record] = self.read_block(offset, len)?;
+            if let Ok(idx) = records.binary_search_by(|record| key_of(record).cmp(key)) {
+                let nanos: u64 = sent_of(&records[idx]);
+                self.answered.insert(*key);
+                return Ok(Some(nanos));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The `len` records at `offset`, read from the file unless they are the
+    /// block read last.
+    fn read_block(&mut self, offset: u64, len: u64) -> io::Result<&[Record]> {
+        if self.block.as_ref().is_none_or(|(at, _)| *at != offset) {
+            let mut bytes: Vec<u8> = vec![0; len as usize * RECORD_LEN];
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file.read_exact(&mut bytes)?;
+            let records: Vec<Record> = bytes
+                .chunks_exact(RECORD_LEN)
+                .map(|chunk| Record::try_from(chunk).expect("chunks are one record long"))
+                .collect();
+            self.block = Some((offset, records));
+        }
+        Ok(self
+            .block
+            .as_ref()
+            .map_or(&[][..], |(_, records)| records.as_slice()))
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Addresses waiting for the same answer, in the order they came.
+pub(super) struct AddressQueue {
+    limit: usize,
+    dir: PathBuf,
+    recent: Vec<IpAddr>,
+    spill: Option<(PathBuf, File)>,
+}
+
+impl AddressQueue {
+    /// Creates a queue that keeps up to `limit` addresses in memory and moves
+    /// the rest to a file in `dir`.
+    pub(super) fn new(limit: usize, dir: PathBuf) -> Self {
+        Self {
+            limit: limit.max(1),
+            dir,
+            recent: Vec::new(),
+            spill: None,
+        }
+    }
+
+    pub(super) fn push(&mut self, ip: IpAddr) {
+        self.recent.push(ip);
+        if self.recent.len() >= self.limit
+            && let Err(e) = self.spill()
+        {
+            warn!(
+                "Cannot move queued addresses to {}: {e}",
+                self.dir.display()
+            );
+            self.limit = usize::MAX;
+        }
+    }
+
+    /// Empties the queue, deleting its file, and returns every address in it.
+    pub(super) fn take(&mut self) -> Vec<IpAddr> {
+        let mut ips: Vec<IpAddr> = Vec::new();
+        if let Some((path, mut file)) = self.spill.take() {
+            let mut bytes: Vec<u8> = Vec::new();
+            match file
+                .seek(SeekFrom::Start(0))
+                .and_then(|_| file.read_to_end(&mut bytes))
+            {
+                Ok(_) => ips.extend(bytes.chunks_exact(ADDRESS_LEN).map(decode_address)),
+                Err(e) => warn!("Lost queued addresses in {}: {e}", path.display()),
+            }
+            let _ = fs::remove_file(&path);
+        }
+        ips.append(&mut self.recent);
+        ips
+    }
+
+    /// Appends every address in memory to the file.
+    fn spill(&mut self) -> io::Result<()> {
+        if self.spill.is_none() {
+            let id: u32 = NEXT_FILE.fetch_add(1, atomic::Ordering::Relaxed);
+            let path: PathBuf = self.dir.join(format!("queue-{}-{id}", process::id()));
+            let file: File = create_file(&path)?;
+            self.spill = Some((path, file));
+        }
+        let Some((_, file)) = self.spill.as_mut() else {
+            return Ok(());
+        };
+        let mut writer: BufWriter<&File> = BufWriter::new(file);
+        for ip in self.recent.drain(..) {
+            writer.write_all(&encode_address(&ip))?;
+        }
+        writer.flush()
+    }
+}
+
+impl Drop for AddressQueue {
+    fn drop(&mut self) {
+        if let Some((path, _)) = &self.spill {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Creates `path` readable by this user only, in a directory of theirs: the
+/// spill directory may be in the shared temporary directory, and what goes in
+/// names targets and, for the resolver, addresses it will query.
+fn create_file(path: &Path) -> io::Result<File> {
+    paths::create_private(path).map_err(|e| io::Error::other(format!("{e:#}")))
+}
+
+/// Family and address, big-endian so that they sort by address.
+fn encode_address(ip: &IpAddr) -> [u8; ADDRESS_LEN] {
+    let mut bytes: [u8; ADDRESS_LEN] = [0; ADDRESS_LEN];
+    match ip {
+        IpAddr::V4(ip) => {
+            bytes[0] = 4;
+            bytes[1..5].copy_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            bytes[0] = 6;
+            bytes[1..17].copy_from_slice(&ip.octets());
+        }
+    }
+    bytes
+}
+
+fn decode_address(bytes: &[u8]) -> IpAddr {
+    match bytes[0] {
+        4 => IpAddr::from([bytes[1], bytes[2], bytes[3], bytes[4]]),
+        _ => IpAddr::from(<[u8; 16]>::try_from(&bytes[1..17]).unwrap_or_default()),
+    }
+}
+
+/// Address, port and sequence number, big-endian so that records sort by
+/// address.
+fn encode_key((ip, port, seq): &ProbeKey) -> Key {
+    let mut key: Key = [0; KEY_LEN];
+    key[..ADDRESS_LEN].copy_from_slice(&encode_address(ip));
+    key[17..19].copy_from_slice(&port.to_be_bytes());
+    key[19..].copy_from_slice(&seq.to_be_bytes());
+    key
+}
+
+/// A key followed by the nanoseconds from the epoch of the log to the probe.
+fn encode(key: &ProbeKey, sent: Duration) -> Record {
+    let nanos: u64 = u64::try_from(sent.as_nanos()).unwrap_or(u64::MAX);
+    let mut record: Record = [0; RECORD_LEN];
+    record[..KEY_LEN].copy_from_slice(&encode_key(key));
+    record[KEY_LEN..KEY_LEN + 8].copy_from_slice(&nanos.to_be_bytes());
+    record
+}
+
+fn key_of(record: &Record) -> Key {
+    let mut key: Key = [0; KEY_LEN];
+    key.copy_from_slice(&record[..KEY_LEN]);
+    key
+}
+
+fn sent_of(record: &Record) -> u64 {
+    let mut nanos: [u8; 8] = [0; 8];
+    nanos.copy_from_slice(&record[KEY_LEN..KEY_LEN + 8]);
+    u64::from_be_bytes(nanos)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn probe(host: u8, port: u16) -> ProbeKey {
+        (IpAddr::V4(Ipv4Addr::new(10, 0, 0, host)), port, 7)
+    }
+
+    #[test]
+    fn finds_probes_moved_to_disk() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("zond-spill-test-{}", process::id()));
+        let start: Instant = Instant::now();
+        let mut log: ProbeLog = ProbeLog::new(4, dir.clone());
+
+        for host in 1..=9 {
+            log.insert(
+                probe(host, 50_000),
+                start + Duration::from_millis(host.into()),
+            );
+        }
+        let v6: ProbeKey = (IpAddr::V6(Ipv6Addr::LOCALHOST), 1, 2);
+        log.insert(v6, start);
+        assert_eq!(log.spilled(), 8);
+
+        // From the map, from the first run and from the second one.
+        assert_eq!(
+            log.remove(&probe(9, 50_000)),
+            Some(start + Duration::from_millis(9))
+        );
+        assert_eq!(
+            log.remove(&probe(2, 50_000)),
+            Some(start + Duration::from_millis(2))
+        );
+        assert_eq!(
+            log.remove(&probe(6, 50_000)),
+            Some(start + Duration::from_millis(6))
+        );
+        assert_eq!(log.remove(&v6), Some(start));
+
+        // Answered once, or never sent.
+        assert_eq!(log.remove(&probe(2, 50_000)), None);
+        assert_eq!(log.remove(&probe(3, 50_001)), None);
+        assert_eq!(log.remove(&probe(200, 50_000)), None);
+
+        log.clear();
+        assert_eq!(log.spilled(), 0);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn lookups_read_the_block_the_index_points_to() {
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("zond-spill-index-test-{}", process::id()));
+        let start: Instant = Instant::now();
+        let mut log: ProbeLog = ProbeLog::new(200, dir.clone());
+        for port in 0..200 {
+            log.insert(probe(1, port), start + Duration::from_micros(port.into()));
+        }
+        assert_eq!(log.spilled(), 200);
+
+        for port in [0, 63, 64, 130, 199] {
+            assert_eq!(
+                log.remove(&probe(1, port)),
+                Some(start + Duration::from_micros(port.into()))
+            );
+        }
+        assert_eq!(log.remove(&probe(1, 64)), None);
+        log.clear();
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn queued_addresses_come_back_in_order() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("zond-queue-test-{}", process::id()));
+        let mut queue: AddressQueue = AddressQueue::new(2, dir.clone());
+        let ips: Vec<IpAddr> = vec![
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 3)),
+        ];
+        for ip in &ips {
+            queue.push(*ip);
+        }
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        assert_eq!(queue.take(), ips);
+        assert!(queue.take().is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn records_sort_by_address() {
+        let low: Record = encode(&probe(2, u16::MAX), Duration::from_secs(9));
+        let high: Record = encode(&probe(10, 1), Duration::ZERO);
+        assert!(low < high);
+        assert_eq!(key_of(&high), encode_key(&probe(10, 1)));
+        assert_eq!(sent_of(&low), 9_000_000_000);
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! TLS certificate retrieval from open TCP ports.
+//!
+//! Every port gets a handshake, whether or not its number suggests TLS, since
+//! devices run their web interfaces on all sorts of ports. Services that do not
+//! speak TLS answer with garbage or nothing and are dropped quietly.
+//!
+//! The handshake is left to rustls, offering TLS 1.3 and 1.2, which hides the
+//! certificate from anyone but the client in 1.3. Device certificates are
+//! mostly self-signed or issued for names the scan does not know, so the
+//! certificate is accepted whatever it says: it is only read, see
+//! [`tls::parse_certificate`], and nothing is sent over the connection.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use tokio::{net::TcpStream, task::JoinSet, time::timeout};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{
+        ClientConfig, DigitallySignedStruct, Error, SignatureScheme,
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        crypto::{self, CryptoProvider},
+        pki_types::{CertificateDer, ServerName, UnixTime},
+    },
+};
+use zond_common::debug;
+use zond_protocols::tls;
+
+pub use zond_protocols::tls::Certificate;
+
+/// Time allowed per port, from connect until the handshake is done.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Reads the certificate served on each of `ports`, asking for `server_name`
+/// where the server hosts several names.
+///
+/// Returns the ports that completed a handshake, in port order.
+pub async fn read_certificates(
+    ip: IpAddr,
+    ports: &[u16],
+    server_name: Option<&str>,
+) -> Vec<(u16, Certificate)> {
+    let connector: TlsConnector = connector();
+    // SNI carries DNS names only; an address literal sends none.
+    let name: ServerName<'static> = server_name
+        .map(|name| name.trim_end_matches('.'))
+        .filter(|name| name.parse::<IpAddr>().is_err())
+        .and_then(|name| ServerName::try_from(name.to_string()).ok())
+        .unwrap_or_else(|| ServerName::IpAddress(ip.into()));
+
+    let mut handshakes: JoinSet<(u16, Option<Certificate>)> = JoinSet::new();
+    for &port in ports {
+        let (connector, name) = (connector.clone(), name.clone());
+        handshakes.spawn(async move {
+            let addr: SocketAddr = SocketAddr::new(ip, port);
+            (port, fetch(addr, &connector, name).await)
+        });
+    }
+
+    let mut certificates: Vec<(u16, Certificate)> = Vec::new();
+    while let Some(result) = handshakes.join_next().await {
+        if let Ok((port, Some(certificate))) = result {
+            certificates.push((port, certificate));
+        }
+    }
+    certificates.sort_by_key(|(port, _)| *port);
+    certificates
+}
+
+async fn fetch(
+    addr: SocketAddr,
+    connector: &TlsConnector,
+    name: ServerName<'static>,
+) -> Option<Certificate> {
+    match timeout(HANDSHAKE_TIMEOUT, handshake(addr, connector, name)).await {
+        Ok(Ok(certificate)) => Some(certificate),
+        Ok(Err(e)) => {
+            debug!(verbosity = 1, "No TLS certificate from {addr}: {e:#}");
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+/// Completes a handshake and decodes the leaf certificate the server sent.
+async fn handshake(
+    addr: SocketAddr,
+    connector: &TlsConnector,
+    name: ServerName<'static>,
+) -> anyhow::Result<Certificate> {
+    let stream: TcpStream = TcpStream::connect(addr).await?;
+    let stream = connector.connect(name, stream).await?;
+    let leaf: &CertificateDer = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|chain| chain.first())
+        .context("server sent no certificate")?;
+    tls::parse_certificate(leaf)
+}
+
+fn connector() -> TlsConnector {
+    let provider: Arc<CryptoProvider> = Arc::new(crypto::ring::default_provider());
+    let verifier: Arc<AnyCertificate> = Arc::new(AnyCertificate(provider));
+    let config: ClientConfig = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+}
+
+/// Accepts every certificate, as the scan reads certificates rather than
+/// trusting them.
+#[derive(Debug)]
+struct AnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
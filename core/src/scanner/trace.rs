@@ -0,0 +1,332 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Per-host packet traces shown at `-vv`.
+//!
+//! Scanners run concurrently, so logging every probe and reply as it happens
+//! interleaves lines from different interfaces and hosts. Instead, each scanner
+//! owns a [`PacketTrace`] that buffers decoded probes and replies per host and
+//! prints them as one block per host once the scanner is done.
+//!
+//! Tracing is off unless [`discover`](super::discover) runs with
+//! [`ZondConfig::packet_trace`](zond_common::config::ZondConfig::packet_trace), in
+//! which case nothing is decoded or buffered.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    net::IpAddr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use pnet::packet::{
+    Packet,
+    arp::{ArpOperations, ArpPacket},
+    ethernet::{EtherTypes, EthernetPacket},
+    icmp::{IcmpPacket, IcmpTypes},
+    icmpv6::{Icmpv6Packet, Icmpv6Types},
+    ip::IpNextHeaderProtocols,
+    ipv4::Ipv4Packet,
+    ipv6::Ipv6Packet,
+    tcp::{TcpFlags, TcpPacket},
+};
+use zond_common::debug;
+use zond_protocols::sctp::{self, ReplyKind};
+
+/// Whether scanners record packet traces, set per discovery run.
+pub(crate) static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Probe,
+    Reply,
+}
+
+#[derive(Debug)]
+struct TraceEntry {
+    at: Instant,
+    direction: Direction,
+    summary: String,
+}
+
+/// Buffered probes and replies of one scanner, grouped by host.
+pub struct PacketTrace {
+    scanner: String,
+    enabled: bool,
+    hosts: BTreeMap<IpAddr, Vec<TraceEntry>>,
+}
+
+impl PacketTrace {
+    /// Starts an empty trace for the scanner described by `scanner`
+    /// (e.g. `"arp on eth0"`).
+    pub fn new(scanner: impl Into<String>) -> Self {
+        Self {
+            scanner: scanner.into(),
+            enabled: ENABLED.load(Ordering::Relaxed),
+            hosts: BTreeMap::new(),
+        }
+    }
+
+    /// Records a probe sent to `host`. `summary` is only evaluated when tracing.
+    pub fn probe(&mut self, host: IpAddr, summary: impl FnOnce() -> String) {
+        self.record(host, Direction::Probe, summary);
+    }
+
+    /// Records a reply from `host`. `summary` is only evaluated when tracing.
+    pub fn reply(&mut self, host: IpAddr, summary: impl FnOnce() -> String) {
+        self.record(host, Direction::Reply, summary);
+    }
+
+    /// Prints one block per host that replied and empties the trace.
+    pub fn flush(&mut self) {
+        for block in self.render() {
+            debug!(verbosity = 2, "{block}");
+        }
+        self.hosts.clear();
+    }
+
+    fn record(&mut self, host: IpAddr, direction: Direction, summary: impl FnOnce() -> String) {
+        if !self.enabled {
+            return;
+        }
+        self.hosts.entry(host).or_default().push(TraceEntry {
+            at: Instant::now(),
+            direction,
+            summary: summary(),
+        });
+    }
+
+    /// Formats the hosts that replied, followed by a count of silent ones.
+    fn render(&self) -> Vec<String> {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut silent: usize = 0;
+
+        for (host, entries) in &self.hosts {
+            if entries.iter().all(|e| e.direction == Direction::Probe) {
+                silent += 1;
+                continue;
+            }
+
+            let start: Instant = entries[0].at;
+            let mut block: String = format!("Packet trace of {host} ({})", self.scanner);
+            for entry in entries {
+                let offset: Duration = entry.at.saturating_duration_since(start);
+                let arrow: char = match entry.direction {
+                    Direction::Probe => '>',
+                    Direction::Reply => '<',
+                };
+                let _ = write!(
+                    block,
+                    "\n  +{:>8.3}ms {arrow} {}",
+                    offset.as_secs_f64() * 1000.0,
+                    entry.summary
+                );
+            }
+            blocks.push(block);
+        }
+
+        if silent > 0 {
+            blocks.push(format!(
+                "Packet trace ({}): {silent} probed host(s) did not reply",
+                self.scanner
+            ));
+        }
+        blocks
+    }
+}
+
+/// Decodes an Ethernet frame carrying ARP or ICMPv6.
+pub fn describe_ethernet(frame: &[u8]) -> String {
+    let Some(eth) = EthernetPacket::new(frame) else {
+        return "truncated Ethernet frame".to_string();
+    };
+
+    match eth.get_ethertype() {
+        EtherTypes::Arp => match ArpPacket::new(eth.payload()) {
+            Some(arp) if arp.get_operation() == ArpOperations::Request => format!(
+                "ARP who-has {} tell {}",
+                arp.get_target_proto_addr(),
+                arp.get_sender_proto_addr()
+            ),
+            Some(arp) if arp.get_operation() == ArpOperations::Reply => format!(
+                "ARP {} is-at {}",
+                arp.get_sender_proto_addr(),
+                arp.get_sender_hw_addr()
+            ),
+            Some(arp) => format!("ARP op {}", arp.get_operation().0),
+            None => "truncated ARP packet".to_string(),
+        },
+        EtherTypes::Ipv6 => match Ipv6Packet::new(eth.payload()) {
+            Some(ipv6) if ipv6.get_next_header() == IpNextHeaderProtocols::Icmpv6 => {
+                let kind: String = match Icmpv6Packet::new(ipv6.payload()) {
+                    Some(icmp) => match icmp.get_icmpv6_type() {
+                        Icmpv6Types::NeighborSolicit => "neighbor solicitation".to_string(),
+                        Icmpv6Types::NeighborAdvert => "neighbor advertisement".to_string(),
+                        Icmpv6Types::EchoRequest => "echo request".to_string(),
+                        Icmpv6Types::EchoReply => "echo reply".to_string(),
+                        other => format!("type {}", other.0),
+                    },
+                    None => "truncated".to_string(),
+                };
+                format!(
+                    "ICMPv6 {} > {} {kind}",
+                    ipv6.get_source(),
+                    ipv6.get_destination()
+                )
+            }
+            Some(ipv6) => format!(
+                "IPv6 {} > {} next header {}",
+                ipv6.get_source(),
+                ipv6.get_destination(),
+                ipv6.get_next_header().0
+            ),
+            None => "truncated IPv6 packet".to_string(),
+        },
+        other => format!("EtherType 0x{:04x}", other.0),
+    }
+}
+
+/// Decodes an IPv4 packet carrying TCP or ICMP.
+pub fn describe_ipv4(packet: &[u8]) -> String {
+    let Some(ipv4) = Ipv4Packet::new(packet) else {
+        return "truncated IPv4 packet".to_string();
+    };
+    let (src, dst) = (
+        IpAddr::V4(ipv4.get_source()),
+        IpAddr::V4(ipv4.get_destination()),
+    );
+
+    let summary: String = match ipv4.get_next_level_protocol() {
+        IpNextHeaderProtocols::Tcp => describe_tcp(src, dst, ipv4.payload()),
+        IpNextHeaderProtocols::Icmp => describe_icmp(src, dst, ipv4.payload()),
+        IpNextHeaderProtocols::Sctp => describe_sctp(src, dst, ipv4.payload()),
+        other => format!("IPv4 {src} > {dst} protocol {}", other.0),
+    };
+    format!("{summary} ttl {}", ipv4.get_ttl())
+}
+
+/// Decodes a TCP segment exchanged between `src` and `dst`.
+pub fn describe_tcp(src: IpAddr, dst: IpAddr, segment: &[u8]) -> String {
+    let Some(tcp) = TcpPacket::new(segment) else {
+        return format!("TCP {src} > {dst} truncated");
+    };
+
+    let flags = tcp.get_flags();
+    let names: Vec<&str> = [
+        (TcpFlags::SYN, "SYN"),
+        (TcpFlags::ACK, "ACK"),
+        (TcpFlags::RST, "RST"),
+        (TcpFlags::FIN, "FIN"),
+        (TcpFlags::PSH, "PSH"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect();
+
+    format!(
+        "TCP {src}:{} > {dst}:{} [{}] seq {} ack {}",
+        tcp.get_source(),
+        tcp.get_destination(),
+        names.join(","),
+        tcp.get_sequence(),
+        tcp.get_acknowledgement()
+    )
+}
+
+/// Decodes an ICMPv4 message exchanged between `src` and `dst`.
+pub fn describe_icmp(src: IpAddr, dst: IpAddr, message: &[u8]) -> String {
+    let Some(icmp) = IcmpPacket::new(message) else {
+        return format!("ICMP {src} > {dst} truncated");
+    };
+
+    let kind: String = match icmp.get_icmp_type() {
+        IcmpTypes::EchoRequest => "echo request".to_string(),
+        IcmpTypes::EchoReply => "echo reply".to_string(),
+        IcmpTypes::DestinationUnreachable => {
+            format!("unreachable code {}", icmp.get_icmp_code().0)
+        }
+        IcmpTypes::TimeExceeded => "time exceeded".to_string(),
+        other => format!("type {} code {}", other.0, icmp.get_icmp_code().0),
+    };
+    format!("ICMP {src} > {dst} {kind}")
+}
+
+/// Decodes an SCTP packet exchanged between `src` and `dst` by its first chunk.
+pub fn describe_sctp(src: IpAddr, dst: IpAddr, packet: &[u8]) -> String {
+    let Some(reply) = sctp::parse_reply(packet) else {
+        return format!("SCTP {src} > {dst} truncated");
+    };
+
+    let tag: u32 = reply.tag.unwrap_or(0);
+    let chunk: String = match (reply.kind, packet.get(12)) {
+        (ReplyKind::InitAck, _) => format!("INIT-ACK tag {tag:#010x}"),
+        (ReplyKind::Abort, _) => format!("ABORT tag {tag:#010x}"),
+        // Probes are sent with a zero tag and carry theirs in the chunk.
+        (ReplyKind::Other, Some(1)) => match packet.get(16..20) {
+            Some(initiate) => format!(
+                "INIT initiate {:#010x}",
+                u32::from_be_bytes(initiate.try_into().unwrap_or_default())
+            ),
+            None => "INIT".to_string(),
+        },
+        (ReplyKind::Other, chunk_type) => format!("chunk {}", chunk_type.copied().unwrap_or(0)),
+    };
+    format!(
+        "SCTP {src}:{} > {dst}:{} {chunk}",
+        reply.src_port, reply.dst_port
+    )
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn render_groups_replies_by_host() {
+        let alive: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let silent: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        let mut trace: PacketTrace = PacketTrace {
+            scanner: "syn on eth0".to_string(),
+            enabled: true,
+            hosts: BTreeMap::new(),
+        };
+        trace.probe(alive, || "SYN".to_string());
+        trace.probe(silent, || "SYN".to_string());
+        trace.reply(alive, || "SYN,ACK".to_string());
+
+        let blocks: Vec<String> = trace.render();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].starts_with("Packet trace of 10.0.0.1 (syn on eth0)"));
+        assert!(blocks[0].contains("> SYN") && blocks[0].contains("< SYN,ACK"));
+        assert!(blocks[1].contains("1 probed host(s) did not reply"));
+    }
+
+    #[test]
+    fn disabled_trace_skips_decoding() {
+        let mut trace: PacketTrace = PacketTrace {
+            scanner: "arp on eth0".to_string(),
+            enabled: false,
+            hosts: BTreeMap::new(),
+        };
+        trace.probe(IpAddr::V4(Ipv4Addr::LOCALHOST), || {
+            panic!("summary must not be built")
+        });
+        assert!(trace.render().is_empty());
+    }
+}
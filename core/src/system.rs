@@ -10,7 +10,13 @@ use std::process::Command;
 
 use anyhow;
 use pnet::datalink::NetworkInterface;
-use zond_common::models::localhost::{FirewallStatus, IpServiceGroup, Service};
+use zond_common::models::localhost::{
+    FirewallRules, FirewallStatus, IpServiceGroup, SelfTest, Service,
+};
+
+pub mod doctor;
+mod firewall;
+mod selftest;
 
 /// Intermediate representation of a socket entry.
 #[derive(Debug)]
@@ -26,18 +32,36 @@ pub fn get_local_services() -> anyhow::Result<Vec<IpServiceGroup>> {
     Ok(aggregate_services(entries))
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn retrieve_sockets() -> anyhow::Result<Vec<SocketInfo>> {
     let raw_data = retrieve_raw_socket_data()?;
     Ok(parse_socket_data(&raw_data))
 }
 
+/// macOS has no `ss`, so listening sockets come from `lsof`.
+#[cfg(target_os = "macos")]
+fn retrieve_sockets() -> anyhow::Result<Vec<SocketInfo>> {
+    let output = Command::new("lsof")
+        .args([
+            "-nP",
+            "+c",
+            "0",
+            "-iTCP",
+            "-sTCP:LISTEN",
+            "-iUDP",
+            "-F",
+            "cPn",
+        ])
+        .output()?;
+    Ok(parse_lsof_fields(&String::from_utf8_lossy(&output.stdout)))
+}
+
 #[cfg(target_os = "windows")]
 fn retrieve_sockets() -> anyhow::Result<Vec<SocketInfo>> {
     windows_impl::retrieve_native_sockets()
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn retrieve_raw_socket_data() -> anyhow::Result<String> {
     use std::process::Command;
     let output = Command::new("ss").arg("-lntuH").arg("-p").output()?;
@@ -49,12 +73,41 @@ fn retrieve_raw_socket_data() -> anyhow::Result<String> {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn parse_socket_data(stdout: &str) -> Vec<SocketInfo> {
     stdout.lines().filter_map(parse_socket_line).collect()
 }
 
-#[cfg(not(target_os = "windows"))]
+/// Parses `lsof -F cPn` output: a `c` line names the command, each socket
+/// follows as a `P` (protocol) and an `n` (address) line.
+#[cfg(target_os = "macos")]
+fn parse_lsof_fields(stdout: &str) -> Vec<SocketInfo> {
+    let mut entries: Vec<SocketInfo> = Vec::new();
+    let mut command: &str = "Unknown";
+    let mut protocol: String = String::new();
+    for line in stdout.lines() {
+        let (tag, value) = line.split_at_checked(1).unwrap_or_default();
+        match tag {
+            "c" => command = value,
+            "P" => protocol = value.to_ascii_lowercase(),
+            // Connected UDP sockets name their peer after `->`.
+            "n" if !value.contains("->") && (protocol == "tcp" || protocol == "udp") => {
+                if let Some((ip, port)) = parse_address_port(value) {
+                    entries.push(SocketInfo {
+                        ip,
+                        port,
+                        protocol: protocol.clone(),
+                        process_name: command.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn parse_socket_line(line: &str) -> Option<SocketInfo> {
     let parts: Vec<&str> = line.split_whitespace().collect();
     if parts.len() < 5 {
@@ -121,7 +174,7 @@ fn parse_address_port(addr_port: &str) -> Option<(IpAddr, u16)> {
     Some((ip, port))
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn parse_process_name(users_field: &str) -> Option<String> {
     if !users_field.starts_with("users:((") {
         return None;
@@ -209,12 +262,52 @@ pub fn get_firewall_status() -> anyhow::Result<FirewallStatus> {
         }
         Ok(FirewallStatus::NotDetected)
     }
-    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    #[cfg(target_os = "macos")]
+    {
+        let output = Command::new("/usr/libexec/ApplicationFirewall/socketfilterfw")
+            .arg("--getglobalstate")
+            .output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("enabled") || stdout.contains("blocking") {
+            Ok(FirewallStatus::Active)
+        } else if stdout.contains("disabled") {
+            Ok(FirewallStatus::Inactive)
+        } else {
+            Ok(FirewallStatus::NotDetected)
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
     {
         Ok(FirewallStatus::NotDetected)
     }
 }
 
+/// Returns the inbound allow rules of the native firewall on Windows and
+/// macOS, `None` elsewhere.
+pub fn get_firewall_rules() -> anyhow::Result<Option<FirewallRules>> {
+    firewall::rules()
+}
+
+/// Connects to the IPv4 TCP listeners in `groups` the way a LAN host would,
+/// through the address of the LAN interface.
+///
+/// # Errors
+///
+/// Returns an error if there is no LAN interface with an IPv4 address.
+pub fn self_test(groups: &[IpServiceGroup]) -> anyhow::Result<SelfTest> {
+    let interface: NetworkInterface = zond_common::net::interface::get_lan_interface()?;
+    let lan_ip: std::net::Ipv4Addr = interface
+        .ips
+        .iter()
+        .find_map(|net| match net.ip() {
+            IpAddr::V4(ip) => Some(ip),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("{} has no IPv4 address", interface.name))?;
+    selftest::run(groups, lan_ip)
+}
+
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use super::*;
@@ -375,6 +468,20 @@ mod windows_impl {
     }
 }
 
+/// Returns the DNS domain and search list of the system resolver, in the
+/// order it tries them.
+pub fn get_search_domains() -> anyhow::Result<Vec<String>> {
+    let (config, _) = hickory_resolver::system_conf::read_system_conf()?;
+    let mut domains: Vec<String> = Vec::new();
+    for name in config.domain().into_iter().chain(config.search()) {
+        let domain: String = name.to_string().trim_end_matches('.').to_string();
+        if !domain.is_empty() && !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+    Ok(domains)
+}
+
 pub fn get_network_interfaces() -> anyhow::Result<Vec<NetworkInterface>> {
     zond_common::net::interface::get_prioritized_interfaces(10)
 }
@@ -0,0 +1,344 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Checks of the environment zond runs in, for `zond doctor`.
+//!
+//! Where it can, a check does the real thing instead of predicting it: it
+//! opens a raw socket rather than looking at the privileges, and asks the
+//! name server rather than reading its address. Each failure comes with what
+//! to do about it.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hickory_resolver::{
+    Name, TokioResolver, name_server::TokioConnectionProvider, proto::rr::RecordType,
+    system_conf::read_system_conf,
+};
+use pnet::{
+    datalink::{self, Config, NetworkInterface},
+    util::MacAddr,
+};
+use zond_common::{
+    net::interface,
+    parse::cron::UtcMinute,
+    utils::{mac, paths, privilege},
+};
+
+use crate::error::ZondError;
+use crate::network::{
+    channel,
+    transport::{self, TransportType},
+};
+
+/// How long the name server has to answer.
+const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+
+const PRIVILEGE_HINT: &str = "run zond with sudo or --elevate, or grant it the capabilities \
+    once: sudo setcap cap_net_raw,cap_net_admin+ep \"$(command -v zond)\"";
+const DNS_HINT: &str = "hostnames stay empty without it; check the name server in \
+    /etc/resolv.conf and that UDP port 53 is not blocked, or pass --no-dns";
+const CLOCK_HINT: &str = "report times, schedules and certificate expiry depend on it; enable \
+    NTP, e.g. `timedatectl set-ntp true`. Devices without a battery-backed clock start at an \
+    old date until NTP synchronizes";
+
+/// The outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Zond works, but with less detail or accuracy.
+    Warn,
+    Fail,
+}
+
+/// One check and what it found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked, e.g. `Raw sockets`.
+    pub name: String,
+    pub status: Status,
+    /// What the check found.
+    pub detail: String,
+    /// What to do about a warning or failure.
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    pub fn warn(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: Status::Warn,
+            hint: Some(hint.into()),
+            ..Self::pass(name, detail)
+        }
+    }
+
+    pub fn fail(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: Status::Fail,
+            hint: Some(hint.into()),
+            ..Self::pass(name, detail)
+        }
+    }
+}
+
+/// Runs every check of the machine and its network setup.
+pub async fn run() -> Vec<Check> {
+    let mut checks: Vec<Check> = vec![raw_sockets()];
+    checks.extend(datalink_channels());
+    checks.push(dns().await);
+    checks.push(oui_database());
+    checks.push(clock());
+    checks
+}
+
+/// Opens the raw socket routed discovery sends its SYNs from.
+fn raw_sockets() -> Check {
+    const NAME: &str = "Raw sockets";
+    if !privilege::has_raw_socket_access() {
+        return Check::fail(
+            NAME,
+            "neither root nor CAP_NET_RAW, scans fall back to TCP connects",
+            PRIVILEGE_HINT,
+        );
+    }
+    match transport::open_sender(TransportType::TcpLayer4) {
+        Ok(_) => Check::pass(NAME, "TCP raw socket opened"),
+        Err(e) => Check::fail(
+            NAME,
+            e.to_string(),
+            "the privileges are there, so a sandbox or security module (SELinux, AppArmor, \
+             seccomp) likely blocks raw sockets; check its audit log",
+        ),
+    }
+}
+
+/// Opens a data link channel on every interface ARP and NDP sweeps use.
+fn datalink_channels() -> Vec<Check> {
+    let interfaces: Vec<NetworkInterface> = match interface::get_prioritized_interfaces(usize::MAX)
+    {
+        Ok(interfaces) => interfaces,
+        Err(e) => {
+            return vec![Check::fail(
+                "Interfaces",
+                e.to_string(),
+                "zond cannot list the network interfaces of this machine",
+            )];
+        }
+    };
+    let interfaces: Vec<NetworkInterface> = interfaces
+        .into_iter()
+        .filter(interface::is_layer_2_capable)
+        .collect();
+    if interfaces.is_empty() {
+        return vec![Check::warn(
+            "Interfaces",
+            "no interface with a MAC address is up",
+            "ARP and NDP discovery need an Ethernet or Wi-Fi interface; other targets are \
+             probed with routed scans",
+        )];
+    }
+
+    interfaces
+        .iter()
+        .map(|intf| {
+            let name: String = format!("Capture {}", intf.name);
+            let cfg: Config = Config {
+                read_timeout: Some(Duration::from_millis(50)),
+                promiscuous: false,
+                ..Default::default()
+            };
+            match channel::open_eth_channel(intf, datalink::channel, cfg) {
+                Ok(_) => Check::pass(name, "data link channel opened"),
+                Err(ZondError::PermissionDenied(e)) => Check::fail(name, e, PRIVILEGE_HINT),
+                Err(e) => Check::fail(
+                    name,
+                    e.to_string(),
+                    format!(
+                        "check that {} is up and its driver supports packet capture",
+                        intf.name
+                    ),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Asks the configured name server for the root zone, which every server
+/// answers.
+async fn dns() -> Check {
+    const NAME: &str = "DNS";
+    let (config, opts) = match read_system_conf() {
+        Ok(conf) => conf,
+        Err(e) => return Check::fail(NAME, format!("no resolver configuration: {e}"), DNS_HINT),
+    };
+    let Some(server) = config.name_servers().first().map(|ns| ns.socket_addr) else {
+        return Check::fail(NAME, "no name server configured", DNS_HINT);
+    };
+
+    let resolver: TokioResolver =
+        TokioResolver::builder_with_config(config, TokioConnectionProvider::default())
+            .with_options(opts)
+            .build();
+    match tokio::time::timeout(DNS_TIMEOUT, resolver.lookup(Name::root(), RecordType::SOA)).await {
+        Ok(Ok(_)) => Check::pass(NAME, format!("{server} answered")),
+        Ok(Err(e)) if e.is_no_records_found() || e.is_nx_domain() => {
+            Check::pass(NAME, format!("{server} answered"))
+        }
+        Ok(Err(e)) => Check::fail(NAME, format!("{server}: {e}"), DNS_HINT),
+        Err(_) => Check::fail(
+            NAME,
+            format!("{server} did not answer within {}s", DNS_TIMEOUT.as_secs()),
+            DNS_HINT,
+        ),
+    }
+}
+
+/// Loads the OUI database and looks up a prefix every version of it has.
+fn oui_database() -> Check {
+    const NAME: &str = "OUI database";
+    let cisco: MacAddr = MacAddr::new(0x00, 0x00, 0x0c, 0x00, 0x00, 0x01);
+    let known: bool = mac::get_vendor(cisco).is_some();
+    let source: String = match mac::installed_oui_db() {
+        Some(path) => format!("registry {}", path.display()),
+        None => "built-in database".to_string(),
+    };
+    if !known {
+        return Check::fail(
+            NAME,
+            format!("{source} does not know 00:00:0C (Cisco)"),
+            "vendors stay empty; run `zond update-oui` to install a current registry",
+        );
+    }
+
+    let installed: Option<PathBuf> = paths::data_dir()
+        .map(|dir| dir.join(mac::OUI_CSV_FILE))
+        .filter(|path| path.exists());
+    match (mac::installed_oui_db(), installed) {
+        (None, Some(path)) => Check::warn(
+            NAME,
+            format!(
+                "{} was not loaded, using the built-in database",
+                path.display()
+            ),
+            "the file does not match its checksum; run `zond update-oui` to install it again",
+        ),
+        _ => Check::pass(NAME, format!("{source} loaded")),
+    }
+}
+
+/// Compares the wall clock with the age of the zond binary and asks the
+/// kernel whether it is synchronized.
+fn clock() -> Check {
+    let built: Option<SystemTime> = env::current_exe()
+        .and_then(fs::metadata)
+        .and_then(|meta| meta.modified())
+        .ok();
+    judge_clock(SystemTime::now(), built, synchronized())
+}
+
+/// A clock at `now` cannot be right if it is earlier than `built`, when the
+/// zond binary was written. `synced` is the kernel's view, if it has one.
+fn judge_clock(now: SystemTime, built: Option<SystemTime>, synced: Option<bool>) -> Check {
+    const NAME: &str = "Clock";
+    let minute = |time: SystemTime| UtcMinute::from_unix(unix_secs(time));
+    if let Some(built) = built
+        && now < built
+    {
+        return Check::fail(
+            NAME,
+            format!(
+                "{}, before this zond binary was written ({})",
+                minute(now),
+                minute(built)
+            ),
+            CLOCK_HINT,
+        );
+    }
+    match synced {
+        Some(true) => Check::pass(NAME, format!("{}, synchronized", minute(now))),
+        Some(false) => Check::warn(
+            NAME,
+            format!("{}, not synchronized to a time source", minute(now)),
+            CLOCK_HINT,
+        ),
+        None => Check::pass(NAME, minute(now).to_string()),
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(target_os = "linux")]
+fn synchronized() -> Option<bool> {
+    // SAFETY: with `modes` zeroed, adjtimex only reads the clock state into `timex`.
+    let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+    let state: libc::c_int = unsafe { libc::adjtimex(&mut timex) };
+    match state {
+        -1 => None,
+        _ => Some(state != libc::TIME_ERROR && timex.status & libc::STA_UNSYNC == 0),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn synchronized() -> Option<bool> {
+    None
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn judges_the_clock_against_the_binary() {
+        let built: SystemTime = UNIX_EPOCH + Duration::from_secs(1_780_000_000);
+        let day: Duration = Duration::from_secs(86_400);
+
+        let behind: Check = judge_clock(UNIX_EPOCH + day, Some(built), Some(true));
+        assert_eq!(behind.status, Status::Fail);
+        assert!(behind.detail.starts_with("1970-01-02 00:00 UTC, before"));
+
+        let unsynced: Check = judge_clock(built + day, Some(built), Some(false));
+        assert_eq!(unsynced.status, Status::Warn);
+        assert!(unsynced.hint.is_some());
+
+        assert_eq!(
+            judge_clock(built + day, Some(built), Some(true)).status,
+            Status::Pass
+        );
+        assert_eq!(judge_clock(built, None, None).hint, None);
+    }
+}
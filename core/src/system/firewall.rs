@@ -0,0 +1,294 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Inbound rules of the native firewalls of Windows and macOS.
+//!
+//! * **Windows:** the policy of the current profile (`netsh advfirewall show
+//!   currentprofile`) decides what happens to unmatched traffic, and every
+//!   enabled inbound allow rule (`netsh advfirewall firewall show rule`) opens
+//!   its ports, optionally for one program only.
+//! * **macOS:** the application firewall (`socketfilterfw`) allows or blocks
+//!   programs rather than ports. Signed software may be let in without being
+//!   listed, so unlisted programs are [`Exposure::Unknown`] unless that is
+//!   switched off.
+//!
+//! Block rules are not read; they are rare next to the default policy.
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+
+#[cfg(any(target_os = "windows", test))]
+use std::ops::RangeInclusive;
+use zond_common::models::localhost::FirewallRules;
+#[cfg(any(target_os = "macos", target_os = "windows", test))]
+use zond_common::models::localhost::Transport;
+#[cfg(any(target_os = "macos", target_os = "windows", test))]
+use zond_common::models::localhost::{AllowRule, Exposure};
+
+/// Reads the inbound rules, or `None` where zond cannot read them.
+pub fn rules() -> anyhow::Result<Option<FirewallRules>> {
+    #[cfg(target_os = "windows")]
+    {
+        let profile: String = run("netsh", &["advfirewall", "show", "currentprofile"])?;
+        let rules: String = run(
+            "netsh",
+            &[
+                "advfirewall",
+                "firewall",
+                "show",
+                "rule",
+                "name=all",
+                "dir=in",
+                "verbose",
+            ],
+        )?;
+        Ok(Some(parse_netsh(&profile, &rules)))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        const SOCKETFILTERFW: &str = "/usr/libexec/ApplicationFirewall/socketfilterfw";
+        let state: String = run(SOCKETFILTERFW, &["--getglobalstate"])?;
+        let block_all: String = run(SOCKETFILTERFW, &["--getblockall"])?;
+        let signed: String = run(SOCKETFILTERFW, &["--getallowsigned"])?;
+        let apps: String = run(SOCKETFILTERFW, &["--listapps"])?;
+        Ok(Some(parse_socketfilterfw(
+            &state, &block_all, &signed, &apps,
+        )))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        Ok(None)
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn run(program: &str, args: &[&str]) -> anyhow::Result<String> {
+    let output: std::process::Output = Command::new(program).args(args).output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{program} exited with {}",
+        output.status
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses the current profile and the verbose inbound rule list of `netsh`.
+#[cfg(any(target_os = "windows", test))]
+fn parse_netsh(profile: &str, rules: &str) -> FirewallRules {
+    let field = |text: &str, key: &str| -> Option<String> {
+        text.lines()
+            .find_map(|line| line.trim().strip_prefix(key))
+            .map(|value| value.trim().trim_start_matches(':').trim().to_string())
+    };
+    let enabled: bool = field(profile, "State").is_some_and(|state| state == "ON");
+    let policy: String = field(profile, "Firewall Policy").unwrap_or_default();
+
+    if !enabled || policy.starts_with("AllowInbound") {
+        return FirewallRules {
+            default_inbound: Exposure::Reachable,
+            allow: Vec::new(),
+        };
+    }
+    // `BlockInboundAlways` ignores every allow rule.
+    if policy.starts_with("BlockInboundAlways") {
+        return FirewallRules {
+            default_inbound: Exposure::Blocked,
+            allow: Vec::new(),
+        };
+    }
+
+    let allow: Vec<AllowRule> = rules
+        .split("Rule Name:")
+        .skip(1)
+        .filter_map(|block| {
+            let name: &str = block.lines().next()?.trim();
+            let value = |key: &str| field(block, key).unwrap_or_default();
+            if value("Enabled:") != "Yes"
+                || value("Direction:") != "In"
+                || value("Action:") != "Allow"
+            {
+                return None;
+            }
+            let protocol: Transport = match value("Protocol:").as_str() {
+                "Any" => Transport::Any,
+                "TCP" => Transport::Tcp,
+                "UDP" => Transport::Udp,
+                _ => return None,
+            };
+            let ports: Vec<RangeInclusive<u16>> = match value("LocalPort:").as_str() {
+                "Any" => Vec::new(),
+                list => {
+                    // Keywords such as `RPC` or `IPHTTPS` stand for dynamic ports.
+                    let ports: Vec<RangeInclusive<u16>> =
+                        list.split(',').filter_map(parse_port_range).collect();
+                    if ports.is_empty() {
+                        return None;
+                    }
+                    ports
+                }
+            };
+            let program: Option<String> =
+                Some(value("Program:")).filter(|program| !program.is_empty() && program != "Any");
+            Some(AllowRule {
+                name: name.to_string(),
+                program,
+                protocol,
+                ports,
+            })
+        })
+        .collect();
+
+    FirewallRules {
+        default_inbound: Exposure::Blocked,
+        allow,
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn parse_port_range(token: &str) -> Option<RangeInclusive<u16>> {
+    let token: &str = token.trim();
+    match token.split_once('-') {
+        Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+        None => {
+            let port: u16 = token.parse().ok()?;
+            Some(port..=port)
+        }
+    }
+}
+
+/// Parses the answers of `socketfilterfw --getglobalstate`, `--getblockall`,
+/// `--getallowsigned` and `--listapps`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_socketfilterfw(state: &str, block_all: &str, signed: &str, apps: &str) -> FirewallRules {
+    if state.contains("disabled") || state.contains("State = 0") {
+        return FirewallRules {
+            default_inbound: Exposure::Reachable,
+            allow: Vec::new(),
+        };
+    }
+    // Older releases answer `Block all ENABLED!`.
+    if block_all.contains("block all non-essential")
+        || block_all.contains("Block all ENABLED")
+        || state.contains("State = 2")
+    {
+        return FirewallRules {
+            default_inbound: Exposure::Blocked,
+            allow: Vec::new(),
+        };
+    }
+
+    let lines: Vec<&str> = apps.lines().map(str::trim).collect();
+    let allow: Vec<AllowRule> = lines
+        .windows(2)
+        .filter_map(|pair| {
+            // `1 : /Applications/Docker.app` followed by `( Allow incoming connections )`
+            let (_, path) = pair[0].split_once(" : ")?;
+            if !pair[1].contains("Allow incoming connections") {
+                return None;
+            }
+            let path: &str = path.trim();
+            Some(AllowRule {
+                name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                program: Some(path.to_string()),
+                protocol: Transport::Any,
+                ports: Vec::new(),
+            })
+        })
+        .collect();
+
+    let allows_signed: bool = signed.contains("ENABLED");
+    FirewallRules {
+        default_inbound: if allows_signed {
+            Exposure::Unknown
+        } else {
+            Exposure::Blocked
+        },
+        allow,
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn netsh_allow_rules_are_read() {
+        let profile: &str = "Private Profile Settings:\r\n\
+            ----------------------------------------------------------------------\r\n\
+            State                                 ON\r\n\
+            Firewall Policy                       BlockInbound,AllowOutbound\r\n";
+        let rules: &str = "\r\n\
+            Rule Name:                            Remote Desktop - User Mode (TCP-In)\r\n\
+            ----------------------------------------------------------------------\r\n\
+            Enabled:                              Yes\r\n\
+            Direction:                            In\r\n\
+            Protocol:                             TCP\r\n\
+            LocalPort:                            3389\r\n\
+            Program:                              %SystemRoot%\\system32\\svchost.exe\r\n\
+            Action:                               Allow\r\n\
+            \r\n\
+            Rule Name:                            Dev servers\r\n\
+            ----------------------------------------------------------------------\r\n\
+            Enabled:                              Yes\r\n\
+            Direction:                            In\r\n\
+            Protocol:                             Any\r\n\
+            LocalPort:                            8000-8010,RPC\r\n\
+            Program:                              Any\r\n\
+            Action:                               Allow\r\n\
+            \r\n\
+            Rule Name:                            Disabled\r\n\
+            ----------------------------------------------------------------------\r\n\
+            Enabled:                              No\r\n\
+            Direction:                            In\r\n\
+            Protocol:                             TCP\r\n\
+            LocalPort:                            21\r\n\
+            Action:                               Allow\r\n";
+
+        let parsed: FirewallRules = parse_netsh(profile, rules);
+        assert_eq!(parsed.default_inbound, Exposure::Blocked);
+        assert_eq!(parsed.allow.len(), 2);
+        assert_eq!(parsed.allow[0].name, "Remote Desktop - User Mode (TCP-In)");
+        assert_eq!(
+            parsed.allow[0].program.as_deref(),
+            Some(r"%SystemRoot%\system32\svchost.exe")
+        );
+        assert_eq!(parsed.allow[1].protocol, Transport::Any);
+        assert_eq!(parsed.allow[1].ports, vec![8000..=8010]);
+        assert!(parsed.allow[1].program.is_none());
+
+        let off: &str = "State                                 OFF\r\n";
+        assert_eq!(parse_netsh(off, rules).default_inbound, Exposure::Reachable);
+    }
+
+    #[test]
+    fn socketfilterfw_apps_are_read() {
+        let state: &str = "Firewall is enabled. (State = 1)\n";
+        let block_all: &str = "Firewall has block all state set to disabled.\n";
+        let signed: &str = "Automatically allow built-in signed software DISABLED.\n\
+            Automatically allow downloaded signed software DISABLED.\n";
+        let apps: &str = "ALF: total number of apps = 2 \n\n\
+            1 :  /Applications/Docker.app \n \t ( Allow incoming connections ) \n\n\
+            2 :  /usr/local/bin/node \n \t ( Block incoming connections ) \n";
+
+        let parsed: FirewallRules = parse_socketfilterfw(state, block_all, signed, apps);
+        assert_eq!(parsed.default_inbound, Exposure::Blocked);
+        assert_eq!(parsed.allow.len(), 1);
+        assert_eq!(parsed.allow[0].name, "Docker.app");
+
+        let disabled: &str = "Firewall is disabled. (State = 0)\n";
+        let parsed: FirewallRules = parse_socketfilterfw(disabled, block_all, signed, apps);
+        assert_eq!(parsed.default_inbound, Exposure::Reachable);
+    }
+}
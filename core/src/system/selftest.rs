@@ -0,0 +1,237 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Probes the host's own listening services from the LAN side.
+//!
+//! Connecting to its own address proves little: the kernel delivers such
+//! packets over loopback, which ufw, firewalld and Windows Firewall all trust.
+//! On Linux the probes are therefore sent from a throwaway network namespace,
+//! joined to the host by a veth pair, so they arrive on an ordinary interface
+//! and meet the same input rules as packets from the LAN. Elsewhere, or without
+//! the rights to create a namespace, the host connects to itself and says so.
+//!
+//! Only TCP listeners on IPv4 are probed: a silent UDP port cannot be told from
+//! a filtered one, and the namespace has no IPv6 route.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
+    thread,
+    time::Duration,
+};
+
+use zond_common::models::localhost::{IpServiceGroup, SelfTest};
+#[cfg(target_os = "linux")]
+use zond_common::warn;
+
+/// Listening LAN hosts answer well within this; firewalls drop silently.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A listening port and the address it is probed on.
+struct Target {
+    listener: (IpAddr, u16),
+    addr: SocketAddr,
+}
+
+/// Connects to every IPv4 TCP listener in `groups`, wildcard listeners on
+/// `lan_ip`.
+///
+/// # Errors
+///
+/// Returns an error if the namespace was created but could not be entered.
+pub fn run(groups: &[IpServiceGroup], lan_ip: Ipv4Addr) -> anyhow::Result<SelfTest> {
+    let targets: Vec<Target> = targets(groups, lan_ip);
+
+    #[cfg(target_os = "linux")]
+    match namespace::Namespace::create() {
+        Ok(namespace) => {
+            let results: HashMap<(IpAddr, u16), bool> = namespace.run(|| probe(&targets))?;
+            return Ok(SelfTest {
+                source: "a network namespace".to_string(),
+                target: IpAddr::V4(lan_ip),
+                results,
+            });
+        }
+        Err(e) => warn!("Could not set up a network namespace, probing over loopback: {e:#}"),
+    }
+
+    Ok(SelfTest {
+        source: "the host itself (loopback)".to_string(),
+        target: IpAddr::V4(lan_ip),
+        results: probe(&targets),
+    })
+}
+
+fn targets(groups: &[IpServiceGroup], lan_ip: Ipv4Addr) -> Vec<Target> {
+    groups
+        .iter()
+        .flat_map(|group| &group.tcp_services)
+        .flat_map(|service| {
+            let probed: Option<Ipv4Addr> = match service.local_addr {
+                IpAddr::V4(ip) if ip.is_unspecified() => Some(lan_ip),
+                IpAddr::V4(ip) if !ip.is_loopback() => Some(ip),
+                _ => None,
+            };
+            service.local_ports.iter().filter_map(move |port| {
+                Some(Target {
+                    listener: (service.local_addr, *port),
+                    addr: SocketAddr::new(IpAddr::V4(probed?), *port),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Connects to all targets at once and records which accepted.
+fn probe(targets: &[Target]) -> HashMap<(IpAddr, u16), bool> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = targets
+            .iter()
+            .map(|target| {
+                scope.spawn(move || {
+                    let open: bool =
+                        TcpStream::connect_timeout(&target.addr, CONNECT_TIMEOUT).is_ok();
+                    (target.listener, open)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod namespace {
+    use std::{fs::File, os::fd::AsRawFd, process::Command, thread};
+
+    use anyhow::{Context, anyhow};
+
+    /// Address of the host end of the veth pair, the namespace's gateway.
+    const HOST_ADDR: &str = "169.254.77.1";
+    /// Address the probes leave the namespace from.
+    const PEER_ADDR: &str = "169.254.77.2/30";
+
+    /// A network namespace routed to the host, removed again on drop.
+    pub struct Namespace {
+        name: String,
+        host_link: String,
+    }
+
+    impl Namespace {
+        pub fn create() -> anyhow::Result<Self> {
+            let pid: u32 = std::process::id();
+            let namespace: Namespace = Namespace {
+                name: format!("zond-selftest-{pid}"),
+                host_link: format!("zst{pid}h"),
+            };
+            let peer_link: String = format!("zst{pid}n");
+            let name: &str = &namespace.name;
+            let host: &str = &namespace.host_link;
+
+            ip(&["netns", "add", name])?;
+            // From here on, dropping `namespace` cleans up after a failed step.
+            ip(&[
+                "link", "add", host, "type", "veth", "peer", "name", &peer_link, "netns", name,
+            ])?;
+            ip(&["addr", "add", &format!("{HOST_ADDR}/30"), "dev", host])?;
+            ip(&["link", "set", host, "up"])?;
+            ip(&["-n", name, "addr", "add", PEER_ADDR, "dev", &peer_link])?;
+            ip(&["-n", name, "link", "set", &peer_link, "up"])?;
+            ip(&["-n", name, "route", "add", "default", "via", HOST_ADDR])?;
+            Ok(namespace)
+        }
+
+        /// Runs `f` on a thread inside the namespace. Threads `f` spawns
+        /// inherit it.
+        pub fn run<T: Send>(&self, f: impl FnOnce() -> T + Send) -> anyhow::Result<T> {
+            let file: File = File::open(format!("/run/netns/{}", self.name))
+                .with_context(|| format!("failed to open namespace {}", self.name))?;
+            thread::scope(|scope| {
+                scope
+                    .spawn(|| {
+                        // SAFETY: switches the network namespace of this thread only.
+                        if unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+                            return Err(std::io::Error::last_os_error())
+                                .context("failed to enter the network namespace");
+                        }
+                        Ok(f())
+                    })
+                    .join()
+                    .map_err(|_| anyhow!("self-test thread panicked"))?
+            })
+        }
+    }
+
+    impl Drop for Namespace {
+        fn drop(&mut self) {
+            // Deleting either end of a veth pair deletes both.
+            let _ = ip(&["link", "del", &self.host_link]);
+            let _ = ip(&["netns", "del", &self.name]);
+        }
+    }
+
+    fn ip(args: &[&str]) -> anyhow::Result<()> {
+        let output: std::process::Output = Command::new("ip")
+            .args(args)
+            .output()
+            .context("failed to run ip")?;
+        anyhow::ensure!(
+            output.status.success(),
+            "ip {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use zond_common::models::localhost::Service;
+
+    use super::*;
+
+    #[test]
+    fn wildcard_listeners_are_probed_on_the_lan_address() {
+        let lan_ip: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 20);
+        let listen = |ip: IpAddr, port: u16| {
+            Service::new("svc".to_string(), ip, [port].into_iter().collect())
+        };
+        let groups: Vec<IpServiceGroup> = vec![IpServiceGroup::new(
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            vec![
+                listen(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 22),
+                listen(IpAddr::V4(Ipv4Addr::new(10, 8, 0, 1)), 8080),
+                listen(IpAddr::V4(Ipv4Addr::LOCALHOST), 5432),
+                listen(IpAddr::V6(std::net::Ipv6Addr::UNSPECIFIED), 22),
+            ],
+            vec![listen(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 53)],
+        )];
+
+        let addrs: Vec<SocketAddr> = targets(&groups, lan_ip)
+            .iter()
+            .map(|target| target.addr)
+            .collect();
+        assert_eq!(
+            addrs,
+            vec![
+                SocketAddr::from((lan_ip, 22)),
+                SocketAddr::from(([10, 8, 0, 1], 8080)),
+            ]
+        );
+    }
+}
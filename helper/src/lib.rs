@@ -0,0 +1,247 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! # Privileged Socket Helper
+//!
+//! `zond-helper` is started through polkit's `pkexec` (or installed setuid
+//! root) by `zond --elevate`. It opens the raw sockets zond asks for and hands
+//! their file descriptors back over a unix socket, its standard input, so zond
+//! itself keeps running as the user who started it. The helper never reads
+//! from the sockets it opens: everything that parses traffic, targets, files
+//! or plugins stays in the unprivileged process.
+//!
+//! ## Protocol
+//!
+//! zond writes requests of [`REQUEST_LEN`] bytes, see [`Request`]. Each one
+//! is answered with an `errno` as four big-endian bytes, `0` on success, and
+//! the file descriptor of the new socket attached as `SCM_RIGHTS` to a
+//! successful socket request. The helper exits once zond closes its end.
+
+#[cfg(target_os = "linux")]
+use std::{
+    io::{self, Read, Write},
+    mem,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::net::UnixStream,
+    },
+    ptr,
+};
+
+/// Length of a request.
+pub const REQUEST_LEN: usize = 4;
+
+/// Length of the `errno` every reply starts with.
+#[cfg(target_os = "linux")]
+const REPLY_LEN: usize = 4;
+
+/// What zond asks the helper for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    /// Answered without a socket once the helper runs, so zond can tell
+    /// whether the user was authorized.
+    Hello,
+    /// An `AF_PACKET` socket receiving every protocol, for a data link
+    /// channel.
+    Packet,
+    /// A raw IPv4 socket for `protocol`, with `IP_HDRINCL` set if the header
+    /// is included in what is sent.
+    RawIpv4 { protocol: u8, header_included: bool },
+}
+
+impl Request {
+    pub fn encode(self) -> [u8; REQUEST_LEN] {
+        match self {
+            Self::Hello => [0, 0, 0, 0],
+            Self::Packet => [1, 0, 0, 0],
+            Self::RawIpv4 {
+                protocol,
+                header_included,
+            } => [2, protocol, u8::from(header_included), 0],
+        }
+    }
+
+    /// Returns `None` for anything [`encode`](Self::encode) does not produce.
+    pub fn decode(bytes: [u8; REQUEST_LEN]) -> Option<Self> {
+        match bytes {
+            [0, 0, 0, 0] => Some(Self::Hello),
+            [1, 0, 0, 0] => Some(Self::Packet),
+            [2, protocol, flag @ (0 | 1), 0] => Some(Self::RawIpv4 {
+                protocol,
+                header_included: flag == 1,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Sends `reply` to a request: the `errno` of an error, or success with the
+/// socket, if there is one, attached.
+///
+/// # Errors
+///
+/// Returns an error if the reply cannot be sent, e.g. because zond exited.
+#[cfg(target_os = "linux")]
+pub fn send_reply(stream: &UnixStream, reply: io::Result<Option<OwnedFd>>) -> io::Result<()> {
+    let (errno, fd): (i32, Option<RawFd>) = match &reply {
+        Ok(fd) => (0, fd.as_ref().map(AsRawFd::as_raw_fd)),
+        Err(e) => (e.raw_os_error().unwrap_or(libc::EIO), None),
+    };
+    let payload: [u8; REPLY_LEN] = errno.to_be_bytes();
+    let mut iov: libc::iovec = libc::iovec {
+        iov_base: payload.as_ptr() as *mut libc::c_void,
+        iov_len: payload.len(),
+    };
+    // Aligned for `cmsghdr`, and large enough for one descriptor.
+    let mut control: [u64; 4] = [0; 4];
+
+    // SAFETY: the header is zeroed, then pointed at buffers that outlive the
+    // call; the control message is written within `control`, which holds
+    // `CMSG_SPACE` of one descriptor.
+    let sent: isize = unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        if let Some(fd) = fd {
+            let fd_len: u32 = mem::size_of::<libc::c_int>() as u32;
+            msg.msg_control = control.as_mut_ptr().cast();
+            msg.msg_controllen = libc::CMSG_SPACE(fd_len) as _;
+            let cmsg: *mut libc::cmsghdr = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fd_len) as _;
+            ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<libc::c_int>(), fd);
+        }
+        libc::sendmsg(stream.as_raw_fd(), &msg, libc::MSG_NOSIGNAL)
+    };
+    let mut writer: &UnixStream = stream;
+    match usize::try_from(sent) {
+        Ok(n) => writer.write_all(&payload[n..]),
+        Err(_) => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Receives the reply to a request, with the socket attached to it, if any.
+///
+/// # Errors
+///
+/// Returns the error the helper reported, [`io::ErrorKind::UnexpectedEof`]
+/// if it exited, or the error of receiving.
+#[cfg(target_os = "linux")]
+pub fn recv_reply(stream: &UnixStream) -> io::Result<Option<OwnedFd>> {
+    let mut payload: [u8; REPLY_LEN] = [0; REPLY_LEN];
+    let mut iov: libc::iovec = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let mut control: [u64; 4] = [0; 4];
+    let mut fd: Option<OwnedFd> = None;
+
+    // SAFETY: as in `send_reply`. Descriptors in the control messages are
+    // new to this process, so each is owned exactly once.
+    let (received, truncated): (isize, bool) = unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = mem::size_of_val(&control) as _;
+        let received: isize = libc::recvmsg(stream.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC);
+        if received > 0 {
+            let mut cmsg: *mut libc::cmsghdr = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                    let raw: RawFd = ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast());
+                    fd = Some(OwnedFd::from_raw_fd(raw));
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+        (received, msg.msg_flags & libc::MSG_CTRUNC != 0)
+    };
+
+    let received: usize = match usize::try_from(received) {
+        Ok(0) => {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the privileged helper exited",
+            ));
+        }
+        Ok(n) => n,
+        Err(_) => return Err(io::Error::last_os_error()),
+    };
+    if truncated {
+        return Err(io::Error::other("the reply of the helper was truncated"));
+    }
+    let mut reader: &UnixStream = stream;
+    reader.read_exact(&mut payload[received..])?;
+
+    match i32::from_be_bytes(payload) {
+        0 => Ok(fd),
+        errno => Err(io::Error::from_raw_os_error(errno)),
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_survive_the_wire() {
+        for request in [
+            Request::Hello,
+            Request::Packet,
+            Request::RawIpv4 {
+                protocol: 6,
+                header_included: false,
+            },
+            Request::RawIpv4 {
+                protocol: 255,
+                header_included: true,
+            },
+        ] {
+            assert_eq!(Request::decode(request.encode()), Some(request));
+        }
+        assert_eq!(Request::decode([1, 0, 0, 1]), None);
+        assert_eq!(Request::decode([2, 6, 2, 0]), None);
+        assert_eq!(Request::decode([3, 0, 0, 0]), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn replies_carry_errors_and_descriptors() {
+        use std::fs::File;
+
+        let (helper, zond) = UnixStream::pair().unwrap();
+
+        send_reply(&helper, Ok(None)).unwrap();
+        assert!(recv_reply(&zond).unwrap().is_none());
+
+        send_reply(&helper, Err(io::Error::from_raw_os_error(libc::EPERM))).unwrap();
+        let err: io::Error = recv_reply(&zond).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EPERM));
+
+        let file: File = File::open("/proc/self/status").unwrap();
+        send_reply(&helper, Ok(Some(OwnedFd::from(file)))).unwrap();
+        let mut status: String = String::new();
+        File::from(recv_reply(&zond).unwrap().unwrap())
+            .read_to_string(&mut status)
+            .unwrap();
+        assert!(status.starts_with("Name:"));
+
+        drop(helper);
+        let err: io::Error = recv_reply(&zond).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! The privileged socket helper of `zond --elevate`, see the library.
+//!
+//! Only the sockets in [`Request`] are ever opened. The helper takes no
+//! arguments, reads no files and holds on to nothing it hands out.
+
+use std::process::ExitCode;
+
+#[cfg(target_os = "linux")]
+use zond_helper::{REQUEST_LEN, Request};
+
+#[cfg(target_os = "linux")]
+fn main() -> ExitCode {
+    use std::io::{self, Read};
+    use std::os::{
+        fd::{FromRawFd, OwnedFd},
+        unix::net::UnixStream,
+    };
+
+    if !stdin_is_unix_socket() {
+        eprintln!("zond-helper is started by zond --elevate and cannot be run by itself");
+        return ExitCode::from(2);
+    }
+    // SAFETY: standard input is a unix socket, checked above, and nothing
+    // else in this process uses it.
+    let stream: UnixStream = unsafe { UnixStream::from_raw_fd(libc::STDIN_FILENO) };
+    let mut reader: &UnixStream = &stream;
+
+    loop {
+        let mut bytes: [u8; REQUEST_LEN] = [0; REQUEST_LEN];
+        // zond closes its end when it exits.
+        if reader.read_exact(&mut bytes).is_err() {
+            return ExitCode::SUCCESS;
+        }
+        let reply: io::Result<Option<OwnedFd>> = match Request::decode(bytes) {
+            Some(Request::Hello) => Ok(None),
+            Some(request) => open(request).map(Some),
+            None => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+        if zond_helper::send_reply(&stream, reply).is_err() {
+            return ExitCode::SUCCESS;
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() -> ExitCode {
+    eprintln!("zond-helper is only supported on Linux");
+    ExitCode::from(2)
+}
+
+/// Opens the socket `request` asks for, closed on exec.
+#[cfg(target_os = "linux")]
+fn open(request: Request) -> std::io::Result<std::os::fd::OwnedFd> {
+    use std::{
+        io, mem,
+        os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    };
+
+    let (domain, protocol, header_included): (libc::c_int, libc::c_int, bool) = match request {
+        Request::Packet => (
+            libc::AF_PACKET,
+            libc::c_int::from((libc::ETH_P_ALL as u16).to_be()),
+            false,
+        ),
+        Request::RawIpv4 {
+            protocol,
+            header_included,
+        } => (libc::AF_INET, libc::c_int::from(protocol), header_included),
+        Request::Hello => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+    };
+
+    // SAFETY: socket has no memory preconditions; a valid result is a new
+    // descriptor owned by nothing else.
+    let socket: OwnedFd =
+        match unsafe { libc::socket(domain, libc::SOCK_RAW | libc::SOCK_CLOEXEC, protocol) } {
+            -1 => return Err(io::Error::last_os_error()),
+            fd => unsafe { OwnedFd::from_raw_fd(fd) },
+        };
+
+    if header_included {
+        let on: libc::c_int = 1;
+        // SAFETY: `on` outlives the call and its size is passed along.
+        let set: libc::c_int = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_HDRINCL,
+                (&on as *const libc::c_int).cast(),
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if set == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(socket)
+}
+
+/// Returns `true` if standard input is a unix socket, as zond passes it.
+#[cfg(target_os = "linux")]
+fn stdin_is_unix_socket() -> bool {
+    use std::mem;
+
+    let mut domain: libc::c_int = 0;
+    let mut len: libc::socklen_t = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: `domain` and `len` outlive the call and describe each other.
+    let got: libc::c_int = unsafe {
+        libc::getsockopt(
+            libc::STDIN_FILENO,
+            libc::SOL_SOCKET,
+            libc::SO_DOMAIN,
+            (&mut domain as *mut libc::c_int).cast(),
+            &mut len,
+        )
+    };
+    got == 0 && domain == libc::AF_UNIX
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Recursive resolver detection on 53/udp.
+//!
+//! Consumer routers and NAS boxes resolve names for the clients of their LAN,
+//! and some do so for anyone who asks, which turns them into amplifiers once
+//! the port is reachable from outside. The probe asks for the address of an
+//! external name with recursion desired. A server that answers it resolves
+//! names for LAN clients like us, while authoritative or forwarding-only
+//! servers refuse or fail. Whether it also answers the Internet cannot be told
+//! from inside, so it is reported as a recursive resolver, not as open.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::{net::UdpSocket, time::timeout};
+use zond_common::models::{
+    fingerprint::DNS_RECURSIVE_RESOLVER,
+    port::{Port, PortState, Protocol},
+};
+use zond_protocols::dns;
+
+pub const DNS_PORT: u16 = 53;
+
+/// A name no LAN server is authoritative for, so answering it takes recursion.
+const EXTERNAL_NAME: &str = "example.com";
+/// Recursive resolution may have to walk the hierarchy from the root.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Probes a UDP port for a DNS server and checks whether it resolves
+/// [`EXTERNAL_NAME`] for us.
+///
+/// Returns `None` if `port` is not 53/udp or nothing DNS-like answered.
+pub async fn probe_udp(ip: IpAddr, port: u16) -> Option<String> {
+    if port != DNS_PORT {
+        return None;
+    }
+
+    let local: SocketAddr = match ip {
+        IpAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        IpAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    };
+    let socket: UdpSocket = UdpSocket::bind(local).await.ok()?;
+    socket.connect(SocketAddr::new(ip, port)).await.ok()?;
+    // Echoed by the reply; random, so other hosts cannot guess and forge it.
+    let id: u16 = rand::random();
+    let query: Vec<u8> = dns::create_a_packet(EXTERNAL_NAME, id).ok()?;
+    socket.send(&query).await.ok()?;
+
+    let mut buffer: [u8; 1500] = [0; 1500];
+    let n: usize = timeout(PROBE_TIMEOUT, socket.recv(&mut buffer))
+        .await
+        .ok()?
+        .ok()?;
+    describe_reply(&buffer[..n], id)
+}
+
+/// Returns `true` if `port` is a DNS server that answered the probe.
+pub fn is_dns(port: &Port) -> bool {
+    port.state == PortState::Open
+        && port.protocol == Protocol::Udp
+        && port.number == DNS_PORT
+        && port
+            .service_info
+            .as_deref()
+            .is_some_and(|info| info.starts_with("dns"))
+}
+
+/// Returns `true` if `port` is open and resolved the probe's query recursively.
+pub fn is_recursive_resolver(port: &Port) -> bool {
+    port.state == PortState::Open && port.service_info.as_deref() == Some(DNS_RECURSIVE_RESOLVER)
+}
+
+/// Tells a DNS server answering query `id` from other replies, and recursive
+/// resolvers from the rest.
+fn describe_reply(bytes: &[u8], id: u16) -> Option<String> {
+    let is_response: bool = bytes.get(2).is_some_and(|flags| flags & 0x80 != 0);
+    if !is_response || dns::get_transaction_id(bytes).ok()? != id {
+        return None;
+    }
+
+    if dns::resolves_recursively(bytes, EXTERNAL_NAME) {
+        Some(DNS_RECURSIVE_RESOLVER.to_string())
+    } else {
+        Some("dns".to_string())
+    }
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recursive_answers_mark_recursive_resolvers() {
+        const ID: u16 = 0x5A4E;
+        let mut reply: Vec<u8> = dns::create_a_packet(EXTERNAL_NAME, ID).unwrap();
+        assert_eq!(describe_reply(&reply, ID), None, "a query is not a reply");
+
+        // QR set, RCODE 5 (REFUSED)
+        reply[2] |= 0x80;
+        reply[3] = 0x05;
+        assert_eq!(describe_reply(&reply, ID).as_deref(), Some("dns"));
+
+        // RA set, NOERROR and one answer
+        reply[3] = 0x80;
+        reply[7] = 1;
+        reply.extend_from_slice(&[0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0x0E, 0x10, 0, 4]);
+        reply.extend_from_slice(&[93, 184, 215, 14]);
+        let mut port: Port = Port::new(DNS_PORT, Protocol::Udp, PortState::Open);
+        port.service_info = describe_reply(&reply, ID);
+        assert!(is_recursive_resolver(&port) && is_dns(&port));
+
+        assert_eq!(describe_reply(&reply, !ID), None, "foreign transaction ID");
+    }
+}
@@ -20,6 +20,8 @@ use tokio::time::timeout;
 use zond_common::models::fingerprint::ServiceDefinition;
 use zond_common::models::port::{Port, Protocol};
 
+use crate::{iot, rtsp};
+
 /// A compiled regex match rule for a service.
 pub struct CompiledMatch {
     pub name: Option<String>,
@@ -191,6 +193,17 @@ pub async fn fingerprint_tcp(mut stream: TcpStream, mut port: Port) -> Port {
     let mut buffer = [0u8; 4096];
     let mut responses = String::new();
 
+    // Stage 0: Dedicated protocol probes
+    let dedicated: Option<String> = if rtsp::RTSP_PORTS.contains(&port.number) {
+        rtsp::probe(&mut stream).await
+    } else {
+        iot::identify_tcp(&mut stream, port.number).await
+    };
+    if let Some(info) = dedicated {
+        port.service_info = Some(info);
+        return port;
+    }
+
     // Stage 1: Banner Grab
     if let Ok(Ok(n)) = timeout(Duration::from_millis(500), stream.read(&mut buffer)).await
         && n > 0
@@ -0,0 +1,322 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Read-only probes for IoT and OT protocols.
+//!
+//! Generic banner matching cannot recognize these protocols: none of them talks
+//! first, and their replies are binary. Each probe sends one request that
+//! changes nothing on the device and checks the reply's framing:
+//!
+//! * **MQTT** (1883/tcp): a `CONNECT` without credentials. The broker's return
+//!   code tells whether it accepts anonymous clients; the session is closed
+//!   again right away, without subscribing or publishing.
+//! * **Modbus** (502/tcp): *Read Device Identification*, which reports vendor and
+//!   product without touching any register or coil.
+//! * **CoAP** (5683/udp): a `GET` of `/.well-known/core`, the resource directory.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    time::timeout,
+};
+use zond_common::models::{
+    fingerprint::MQTT_ANONYMOUS,
+    port::{Port, PortState},
+};
+
+pub const MQTT_PORT: u16 = 1883;
+pub const MODBUS_PORT: u16 = 502;
+pub const COAP_PORT: u16 = 5683;
+
+/// Service names that mark their host as an IoT or OT device.
+const IOT_SERVICES: [&str; 3] = ["mqtt", "modbus", "coap"];
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// MQTT 3.1.1 `CONNECT` with a clean session, client ID `zond` and no credentials.
+const MQTT_CONNECT: &[u8] = b"\x10\x10\x00\x04MQTT\x04\x02\x00\x3C\x00\x04zond";
+const MQTT_DISCONNECT: &[u8] = b"\xE0\x00";
+const MQTT_CONNACK: u8 = 0x20;
+
+/// Echoed by Modbus and CoAP replies to match them to the request.
+const REQUEST_ID: u16 = 0x5A4E;
+
+/// Function code of *Encapsulated Interface Transport*, which carries *Read
+/// Device Identification*.
+const MODBUS_MEI: u8 = 0x2B;
+const MODBUS_EXCEPTION: u8 = 0x80;
+/// *Read Device Identification* for the basic objects, addressed to unit `0xFF`
+/// as the Modbus TCP specification recommends.
+const MODBUS_READ_DEVICE_ID: &[u8] = b"\x5A\x4E\x00\x00\x00\x05\xFF\x2B\x0E\x01\x00";
+
+/// Confirmable CoAP `GET /.well-known/core` without a token.
+const COAP_GET_CORE: &[u8] = b"\x40\x01\x5A\x4E\xBB.well-known\x04core";
+/// Response code `2.05 Content`.
+const COAP_CONTENT: u8 = 0x45;
+
+/// How an MQTT broker answered an anonymous `CONNECT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MqttAccess {
+    Anonymous,
+    AuthRequired,
+    /// Refused for another reason, e.g. an unsupported protocol level.
+    Refused,
+}
+
+/// Runs the dedicated probe for `port` over an established connection.
+///
+/// Returns the service description, or `None` if `port` has no dedicated probe
+/// or the reply did not match.
+pub async fn identify_tcp(stream: &mut TcpStream, port: u16) -> Option<String> {
+    match port {
+        MQTT_PORT => probe_mqtt(stream).await,
+        MODBUS_PORT => probe_modbus(stream).await,
+        _ => None,
+    }
+}
+
+/// Probes a UDP port, as far as a dedicated probe exists for it.
+///
+/// A silent UDP port cannot be told apart from a filtered one, so only ports
+/// that answered are returned.
+pub async fn probe_udp(ip: IpAddr, port: u16) -> Option<String> {
+    match port {
+        COAP_PORT => probe_coap(ip, port).await,
+        _ => None,
+    }
+}
+
+/// Returns `true` if `port` is open and runs an IoT or OT protocol.
+pub fn is_iot(port: &Port) -> bool {
+    port.state == PortState::Open
+        && port
+            .service_info
+            .as_deref()
+            .and_then(|info| info.split_whitespace().next())
+            .is_some_and(|service| IOT_SERVICES.contains(&service))
+}
+
+async fn probe_mqtt(stream: &mut TcpStream) -> Option<String> {
+    stream.write_all(MQTT_CONNECT).await.ok()?;
+    let mut connack: [u8; 4] = [0; 4];
+    timeout(PROBE_TIMEOUT, stream.read_exact(&mut connack))
+        .await
+        .ok()?
+        .ok()?;
+
+    let access: MqttAccess = parse_connack(&connack)?;
+    if access == MqttAccess::Anonymous {
+        let _ = stream.write_all(MQTT_DISCONNECT).await;
+    }
+    Some(match access {
+        MqttAccess::Anonymous => MQTT_ANONYMOUS.to_string(),
+        MqttAccess::AuthRequired => "mqtt (auth required)".to_string(),
+        MqttAccess::Refused => "mqtt".to_string(),
+    })
+}
+
+fn parse_connack(bytes: &[u8]) -> Option<MqttAccess> {
+    let [MQTT_CONNACK, 0x02, _, code] = *bytes else {
+        return None;
+    };
+    Some(match code {
+        0x00 => MqttAccess::Anonymous,
+        // 0x04: bad user name or password, 0x05: not authorized.
+        0x04 | 0x05 => MqttAccess::AuthRequired,
+        _ => MqttAccess::Refused,
+    })
+}
+
+async fn probe_modbus(stream: &mut TcpStream) -> Option<String> {
+    stream.write_all(MODBUS_READ_DEVICE_ID).await.ok()?;
+    let mut buffer: [u8; 260] = [0; 260];
+    let n: usize = timeout(PROBE_TIMEOUT, stream.read(&mut buffer))
+        .await
+        .ok()?
+        .ok()?;
+    parse_device_id(&buffer[..n])
+}
+
+/// Validates a *Read Device Identification* reply and describes the device.
+///
+/// Exception replies still prove that Modbus is spoken, they only lack details.
+fn parse_device_id(bytes: &[u8]) -> Option<String> {
+    if bytes.len() < 9
+        || u16::from_be_bytes([bytes[0], bytes[1]]) != REQUEST_ID
+        || bytes[2..4] != [0, 0]
+    {
+        return None;
+    }
+
+    match bytes[7] {
+        MODBUS_MEI => {}
+        code if code == MODBUS_MEI | MODBUS_EXCEPTION => return Some("modbus".to_string()),
+        _ => return None,
+    }
+
+    // MEI type, device ID code, conformity, more follows, next object ID and
+    // the object count precede the objects.
+    let count: u8 = *bytes.get(13)?;
+    let mut objects: &[u8] = bytes.get(14..)?;
+    let mut details: Vec<String> = Vec::new();
+    for _ in 0..count {
+        let [id, len, rest @ ..] = objects else {
+            break;
+        };
+        let Some(value) = rest.get(..*len as usize) else {
+            break;
+        };
+        // Vendor name, product code and revision.
+        if *id <= 0x02 {
+            details.push(String::from_utf8_lossy(value).trim().to_string());
+        }
+        objects = &rest[*len as usize..];
+    }
+
+    details.retain(|detail| !detail.is_empty());
+    if details.is_empty() {
+        return Some("modbus".to_string());
+    }
+    Some(format!("modbus ({})", details.join(" ")))
+}
+
+async fn probe_coap(ip: IpAddr, port: u16) -> Option<String> {
+    let local: SocketAddr = match ip {
+        IpAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        IpAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    };
+    let socket: UdpSocket = UdpSocket::bind(local).await.ok()?;
+    socket.connect(SocketAddr::new(ip, port)).await.ok()?;
+    socket.send(COAP_GET_CORE).await.ok()?;
+
+    let mut buffer: [u8; 1500] = [0; 1500];
+    let n: usize = timeout(PROBE_TIMEOUT, socket.recv(&mut buffer))
+        .await
+        .ok()?
+        .ok()?;
+    parse_coap_reply(&buffer[..n])
+}
+
+/// Validates a reply to [`COAP_GET_CORE`] and counts the listed resources.
+fn parse_coap_reply(bytes: &[u8]) -> Option<String> {
+    if bytes.len() < 4
+        || bytes[0] >> 6 != 1
+        || u16::from_be_bytes([bytes[2], bytes[3]]) != REQUEST_ID
+    {
+        return None;
+    }
+
+    let token_len: usize = (bytes[0] & 0x0F) as usize;
+    match coap_payload(bytes.get(4 + token_len..)?) {
+        Some(links) if bytes[1] == COAP_CONTENT && !links.is_empty() => {
+            let resources: usize = links.split(|&b| b == b',').count();
+            Some(format!("coap ({resources} resources)"))
+        }
+        _ => Some("coap".to_string()),
+    }
+}
+
+/// Skips the options of a CoAP message and returns its payload, if any.
+fn coap_payload(mut options: &[u8]) -> Option<&[u8]> {
+    while let [first, rest @ ..] = options {
+        if *first == 0xFF {
+            return Some(rest);
+        }
+        // Delta and length nibbles of 13 and 14 are followed by one and two
+        // extension bytes; 15 is reserved for the payload marker.
+        let extension = |nibble: u8| match nibble {
+            0..=12 => Some((0, nibble as usize)),
+            13 => Some((1, 13)),
+            14 => Some((2, 269)),
+            _ => None,
+        };
+        let (delta_ext, _) = extension(first >> 4)?;
+        let (len_ext, base_len) = extension(first & 0x0F)?;
+        let len_bytes: &[u8] = rest.get(delta_ext..delta_ext + len_ext)?;
+        let len: usize = match len_ext {
+            0 => base_len,
+            1 => base_len + len_bytes[0] as usize,
+            _ => base_len + u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize,
+        };
+        options = rest.get(delta_ext + len_ext + len..)?;
+    }
+    None
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zond_common::models::port::Protocol;
+
+    #[test]
+    fn reads_mqtt_connack() {
+        assert_eq!(
+            parse_connack(&[0x20, 0x02, 0x00, 0x00]),
+            Some(MqttAccess::Anonymous)
+        );
+        assert_eq!(
+            parse_connack(&[0x20, 0x02, 0x00, 0x05]),
+            Some(MqttAccess::AuthRequired)
+        );
+        assert_eq!(
+            parse_connack(&[0x20, 0x02, 0x00, 0x01]),
+            Some(MqttAccess::Refused)
+        );
+        assert_eq!(parse_connack(b"HTTP"), None);
+    }
+
+    #[test]
+    fn reads_modbus_device_identification() {
+        let mut reply: Vec<u8> = vec![0x5A, 0x4E, 0x00, 0x00, 0x00, 0x00, 0xFF];
+        reply.extend_from_slice(&[MODBUS_MEI, 0x0E, 0x01, 0x01, 0x00, 0x00, 0x02]);
+        reply.extend_from_slice(&[0x00, 0x07]);
+        reply.extend_from_slice(b"Siemens");
+        reply.extend_from_slice(&[0x01, 0x07]);
+        reply.extend_from_slice(b"S7-1200");
+        assert_eq!(
+            parse_device_id(&reply).as_deref(),
+            Some("modbus (Siemens S7-1200)")
+        );
+
+        let exception: [u8; 9] = [0x5A, 0x4E, 0x00, 0x00, 0x00, 0x03, 0xFF, 0xAB, 0x01];
+        assert_eq!(parse_device_id(&exception).as_deref(), Some("modbus"));
+
+        let foreign: [u8; 9] = [0x12, 0x34, 0x00, 0x00, 0x00, 0x03, 0xFF, 0xAB, 0x01];
+        assert_eq!(parse_device_id(&foreign), None);
+    }
+
+    #[test]
+    fn reads_coap_reply() {
+        let mut reply: Vec<u8> = vec![0x60, COAP_CONTENT, 0x5A, 0x4E, 0xC1, 0x28, 0xFF];
+        reply.extend_from_slice(b"</sensors/temp>;rt=\"temperature\",</actuators/led>");
+        assert_eq!(
+            parse_coap_reply(&reply).as_deref(),
+            Some("coap (2 resources)")
+        );
+
+        let not_found: [u8; 4] = [0x60, 0x84, 0x5A, 0x4E];
+        assert_eq!(parse_coap_reply(&not_found).as_deref(), Some("coap"));
+        assert_eq!(parse_coap_reply(&[0x00, 0x45, 0x5A, 0x4E]), None);
+
+        let mut port: Port = Port::new(COAP_PORT, Protocol::Udp, PortState::Open);
+        port.service_info = parse_coap_reply(&reply);
+        assert!(is_iot(&port));
+    }
+}
@@ -6,5 +6,8 @@
 
 //! Zond service fingerprinting plugins.
 
+pub mod dns;
 pub mod fingerprint;
+pub mod iot;
+pub mod rtsp;
 pub use crate::fingerprint::*;
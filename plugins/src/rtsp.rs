@@ -0,0 +1,150 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! RTSP probe for IP cameras and video recorders.
+//!
+//! `OPTIONS` confirms RTSP and usually carries a `Server` header naming the
+//! firmware. A `DESCRIBE` of the root stream then shows whether the device
+//! hands out stream details without credentials: `401` means authentication
+//! is enforced, `200` means anyone on the network can watch.
+
+use std::{net::SocketAddr, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+use zond_common::models::port::{Port, PortState};
+
+/// The standard port and the alternates cameras commonly move RTSP to.
+pub const RTSP_PORTS: [u16; 3] = [554, 8554, 10554];
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// The parts of an RTSP response the probe cares about.
+#[derive(Debug, PartialEq, Eq)]
+struct RtspReply {
+    status: u16,
+    server: Option<String>,
+}
+
+/// Probes an established connection to an RTSP port.
+///
+/// Returns the service description, e.g. `rtsp (Hikvision-Webs, auth required)`,
+/// or `None` if the peer does not speak RTSP.
+pub async fn probe(stream: &mut TcpStream) -> Option<String> {
+    let peer: SocketAddr = stream.peer_addr().ok()?;
+    let url: String = format!("rtsp://{peer}/");
+
+    let options: RtspReply = request(
+        stream,
+        &format!("OPTIONS {url} RTSP/1.0\r\nCSeq: 1\r\n\r\n"),
+    )
+    .await?;
+    let describe: Option<RtspReply> = request(
+        stream,
+        &format!("DESCRIBE {url} RTSP/1.0\r\nCSeq: 2\r\nAccept: application/sdp\r\n\r\n"),
+    )
+    .await;
+
+    let mut details: Vec<String> = Vec::new();
+    if let Some(server) = options
+        .server
+        .or(describe.as_ref().and_then(|r| r.server.clone()))
+    {
+        details.push(server);
+    }
+    match describe.map(|reply| reply.status) {
+        Some(401) => details.push("auth required".to_string()),
+        Some(200) => details.push("no auth".to_string()),
+        _ => {}
+    }
+
+    if details.is_empty() {
+        return Some("rtsp".to_string());
+    }
+    Some(format!("rtsp ({})", details.join(", ")))
+}
+
+/// Returns `true` if `port` is open and serves RTSP, which marks a camera or
+/// video recorder.
+pub fn is_camera(port: &Port) -> bool {
+    port.state == PortState::Open
+        && port
+            .service_info
+            .as_deref()
+            .and_then(|info| info.split_whitespace().next())
+            == Some("rtsp")
+}
+
+async fn request(stream: &mut TcpStream, message: &str) -> Option<RtspReply> {
+    stream.write_all(message.as_bytes()).await.ok()?;
+    let mut buffer: [u8; 2048] = [0; 2048];
+    let n: usize = timeout(PROBE_TIMEOUT, stream.read(&mut buffer))
+        .await
+        .ok()?
+        .ok()?;
+    parse_reply(&String::from_utf8_lossy(&buffer[..n]))
+}
+
+fn parse_reply(response: &str) -> Option<RtspReply> {
+    let mut lines = response.lines();
+    let status: u16 = lines
+        .next()?
+        .strip_prefix("RTSP/1.")?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()?;
+
+    let server: Option<String> = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("server"))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    Some(RtspReply { status, server })
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rtsp_replies() {
+        let options: &str = "RTSP/1.0 200 OK\r\nCSeq: 1\r\nserver: Hikvision-Webs\r\n\
+                             Public: OPTIONS, DESCRIBE, SETUP\r\n\r\n";
+        assert_eq!(
+            parse_reply(options),
+            Some(RtspReply {
+                status: 200,
+                server: Some("Hikvision-Webs".to_string())
+            })
+        );
+
+        let describe: &str = "RTSP/1.0 401 Unauthorized\r\nCSeq: 2\r\n\r\n";
+        assert_eq!(
+            parse_reply(describe),
+            Some(RtspReply {
+                status: 401,
+                server: None
+            })
+        );
+
+        assert_eq!(parse_reply("HTTP/1.1 200 OK\r\n\r\n"), None);
+    }
+}
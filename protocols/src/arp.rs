@@ -9,7 +9,9 @@ use crate::utils::{ARP_LEN, MIN_ETH_FRAME_NO_FCS};
 use anyhow::Context;
 use pnet::datalink::MacAddr;
 use pnet::packet::Packet;
-use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket};
+use pnet::packet::arp::{
+    ArpHardwareTypes, ArpOperation, ArpOperations, ArpPacket, MutableArpPacket,
+};
 use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
 use std::net::Ipv4Addr;
 
@@ -19,8 +21,61 @@ pub fn create_packet(
     src_addr: Ipv4Addr,
     dst_addr: Ipv4Addr,
 ) -> anyhow::Result<Vec<u8>> {
-    let eth_header: Vec<u8> =
-        ethernet::make_header(src_mac, MacAddr::broadcast(), EtherTypes::Arp)?;
+    build(
+        ArpOperations::Request,
+        MacAddr::broadcast(),
+        src_mac,
+        dst_mac,
+        src_addr,
+        dst_addr,
+    )
+}
+
+/// Creates an ARP request addressed straight to `dst_mac` instead of broadcast,
+/// for targets whose MAC address is already known.
+pub fn create_unicast_packet(
+    src_mac: MacAddr,
+    dst_mac: MacAddr,
+    src_addr: Ipv4Addr,
+    dst_addr: Ipv4Addr,
+) -> anyhow::Result<Vec<u8>> {
+    build(
+        ArpOperations::Request,
+        dst_mac,
+        src_mac,
+        dst_mac,
+        src_addr,
+        dst_addr,
+    )
+}
+
+/// Creates an ARP reply telling `dst_mac` (at `dst_addr`) that `src_addr` is
+/// at `src_mac`.
+pub fn create_reply(
+    src_mac: MacAddr,
+    dst_mac: MacAddr,
+    src_addr: Ipv4Addr,
+    dst_addr: Ipv4Addr,
+) -> anyhow::Result<Vec<u8>> {
+    build(
+        ArpOperations::Reply,
+        dst_mac,
+        src_mac,
+        dst_mac,
+        src_addr,
+        dst_addr,
+    )
+}
+
+fn build(
+    operation: ArpOperation,
+    eth_dst: MacAddr,
+    src_mac: MacAddr,
+    dst_mac: MacAddr,
+    src_addr: Ipv4Addr,
+    dst_addr: Ipv4Addr,
+) -> anyhow::Result<Vec<u8>> {
+    let eth_header: Vec<u8> = ethernet::make_header(src_mac, eth_dst, EtherTypes::Arp)?;
 
     let mut arp_buffer: [u8; ARP_LEN] = [0u8; ARP_LEN];
     {
@@ -30,7 +85,7 @@ pub fn create_packet(
         arp_packet.set_protocol_type(EtherTypes::Ipv4);
         arp_packet.set_hw_addr_len(6);
         arp_packet.set_proto_addr_len(4);
-        arp_packet.set_operation(ArpOperations::Request);
+        arp_packet.set_operation(operation);
         arp_packet.set_sender_hw_addr(src_mac);
         arp_packet.set_target_hw_addr(dst_mac);
         arp_packet.set_sender_proto_addr(src_addr);
@@ -154,6 +209,45 @@ mod tests {
         assert_eq!(arp_packet.get_target_proto_addr(), dst_addr);
     }
 
+    #[test]
+    fn create_unicast_arp_request_packet() {
+        let src_mac = MacAddr::new(0x01, 0x02, 0x03, 0x04, 0x05, 0x06);
+        let dst_mac = MacAddr::new(0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF);
+        let dst_addr = Ipv4Addr::new(192, 168, 1, 1);
+
+        let buffer =
+            create_unicast_packet(src_mac, dst_mac, Ipv4Addr::new(192, 168, 1, 10), dst_addr)
+                .expect("Packet creation failed");
+        let eth_packet = EthernetPacket::new(&buffer).expect("Failed to parse Ethernet packet");
+        assert_eq!(eth_packet.get_destination(), dst_mac);
+
+        let arp_packet = ArpPacket::new(eth_packet.payload()).expect("Failed to parse ARP packet");
+        assert_eq!(arp_packet.get_operation(), ArpOperations::Request);
+        assert_eq!(arp_packet.get_target_hw_addr(), dst_mac);
+        assert_eq!(arp_packet.get_target_proto_addr(), dst_addr);
+    }
+
+    #[test]
+    fn create_arp_reply_packet() {
+        let src_mac = MacAddr::new(0x02, 0x5A, 0x0A, 0xC8, 0x00, 0x32);
+        let dst_mac = MacAddr::new(0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF);
+        let src_addr = Ipv4Addr::new(10, 200, 0, 50);
+        let dst_addr = Ipv4Addr::new(10, 200, 0, 1);
+
+        let buffer =
+            create_reply(src_mac, dst_mac, src_addr, dst_addr).expect("Packet creation failed");
+        let eth_packet = EthernetPacket::new(&buffer).expect("Failed to parse Ethernet packet");
+        assert_eq!(eth_packet.get_destination(), dst_mac);
+        assert_eq!(eth_packet.get_source(), src_mac);
+
+        let arp_packet = ArpPacket::new(eth_packet.payload()).expect("Failed to parse ARP packet");
+        assert_eq!(arp_packet.get_operation(), ArpOperations::Reply);
+        assert_eq!(arp_packet.get_sender_hw_addr(), src_mac);
+        assert_eq!(arp_packet.get_sender_proto_addr(), src_addr);
+        assert_eq!(arp_packet.get_target_hw_addr(), dst_mac);
+        assert_eq!(arp_packet.get_target_proto_addr(), dst_addr);
+    }
+
     #[test]
     fn get_ip_addr_success() {
         let expected_ip = Ipv4Addr::new(192, 168, 1, 123);
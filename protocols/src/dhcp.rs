@@ -0,0 +1,160 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Parsing of DHCPv4 client messages (RFC 2131, RFC 2132).
+//!
+//! Clients broadcast `DISCOVER` and `REQUEST` to everyone on the link, and both
+//! name what the client is: option 60 carries a vendor class such as
+//! `MSFT 5.0` or `android-dhcp-14`, and the order of the options it asks for in
+//! option 55 differs between operating systems. Only these identity hints are
+//! read; addresses are left to the server's answer.
+
+use anyhow::{Result, ensure};
+use pnet::util::MacAddr;
+
+pub const DHCP_SERVER_PORT: u16 = 67;
+pub const DHCP_CLIENT_PORT: u16 = 68;
+
+const BOOTREQUEST: u8 = 1;
+const HTYPE_ETHERNET: u8 = 1;
+/// Fixed BOOTP fields before the magic cookie.
+const FIXED_LEN: usize = 236;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+const OPTION_PAD: u8 = 0;
+const OPTION_HOSTNAME: u8 = 12;
+const OPTION_PARAMETER_LIST: u8 = 55;
+const OPTION_VENDOR_CLASS: u8 = 60;
+const OPTION_END: u8 = 255;
+
+/// What a DHCP client tells about itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClientHints {
+    /// Hardware address from `chaddr`.
+    pub mac: MacAddr,
+    /// Option 12.
+    pub hostname: Option<String>,
+    /// Option 60, e.g. `MSFT 5.0`.
+    pub vendor_class: Option<String>,
+    /// Option 55, in the order the client asked.
+    pub parameter_list: Vec<u8>,
+}
+
+/// Reads the identity hints of the client message in the UDP `payload`.
+///
+/// # Errors
+///
+/// Returns an error if the payload is not an Ethernet `BOOTREQUEST` with the
+/// DHCP magic cookie, or its options run past the end.
+pub fn parse_client_message(payload: &[u8]) -> Result<ClientHints> {
+    ensure!(
+        payload.len() >= FIXED_LEN + MAGIC_COOKIE.len(),
+        "DHCP message truncated"
+    );
+    ensure!(payload[0] == BOOTREQUEST, "not a DHCP client message");
+    ensure!(
+        payload[1] == HTYPE_ETHERNET && payload[2] == 6,
+        "DHCP client without an Ethernet address"
+    );
+    ensure!(
+        payload[FIXED_LEN..FIXED_LEN + 4] == MAGIC_COOKIE,
+        "BOOTP message without DHCP options"
+    );
+
+    let chaddr: [u8; 6] = payload[28..34].try_into()?;
+    let mut hints: ClientHints = ClientHints {
+        mac: MacAddr::from(chaddr),
+        ..ClientHints::default()
+    };
+
+    let mut offset: usize = FIXED_LEN + MAGIC_COOKIE.len();
+    while let Some(&code) = payload.get(offset) {
+        match code {
+            OPTION_END => break,
+            OPTION_PAD => {
+                offset += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let len: usize = *payload
+            .get(offset + 1)
+            .ok_or_else(|| anyhow::anyhow!("DHCP option {code} truncated"))?
+            as usize;
+        let value: &[u8] = payload
+            .get(offset + 2..offset + 2 + len)
+            .ok_or_else(|| anyhow::anyhow!("DHCP option {code} truncated"))?;
+        match code {
+            OPTION_HOSTNAME => hints.hostname = text(value),
+            OPTION_VENDOR_CLASS => hints.vendor_class = text(value),
+            OPTION_PARAMETER_LIST => hints.parameter_list = value.to_vec(),
+            _ => {}
+        }
+        offset += 2 + len;
+    }
+
+    Ok(hints)
+}
+
+/// Decodes an option holding text, dropping trailing NULs some clients send.
+fn text(value: &[u8]) -> Option<String> {
+    let text: String = String::from_utf8_lossy(value)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `DISCOVER` from a Windows client, as sent by 10 and 11.
+    fn discover() -> Vec<u8> {
+        let mut packet: Vec<u8> = vec![0; FIXED_LEN];
+        packet[..4].copy_from_slice(&[BOOTREQUEST, HTYPE_ETHERNET, 6, 0]);
+        packet[28..34].copy_from_slice(&[0x00, 0x15, 0x5d, 0x01, 0x02, 0x03]);
+        packet.extend_from_slice(&MAGIC_COOKIE);
+        packet.extend_from_slice(&[53, 1, 1]);
+        packet.extend_from_slice(&[OPTION_PAD]);
+        packet.extend_from_slice(&[OPTION_HOSTNAME, 7]);
+        packet.extend_from_slice(b"DESKTOP");
+        packet.extend_from_slice(&[OPTION_VENDOR_CLASS, 8]);
+        packet.extend_from_slice(b"MSFT 5.0");
+        packet.extend_from_slice(&[OPTION_PARAMETER_LIST, 4, 1, 3, 6, 15]);
+        packet.push(OPTION_END);
+        packet
+    }
+
+    #[test]
+    fn reads_client_hints() {
+        let hints: ClientHints = parse_client_message(&discover()).unwrap();
+        assert_eq!(hints.mac, MacAddr::new(0x00, 0x15, 0x5d, 0x01, 0x02, 0x03));
+        assert_eq!(hints.hostname.as_deref(), Some("DESKTOP"));
+        assert_eq!(hints.vendor_class.as_deref(), Some("MSFT 5.0"));
+        assert_eq!(hints.parameter_list, [1, 3, 6, 15]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_server_messages() {
+        let packet: Vec<u8> = discover();
+        assert!(parse_client_message(&packet[..FIXED_LEN]).is_err());
+        assert!(parse_client_message(&packet[..packet.len() - 4]).is_err());
+
+        let mut reply: Vec<u8> = packet;
+        reply[0] = 2;
+        assert!(parse_client_message(&reply).is_err());
+    }
+}
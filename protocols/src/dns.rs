@@ -4,12 +4,39 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use anyhow::{Context, Result, anyhow};
-use dns_parser::{Builder, Packet, QueryClass, QueryType, RData};
+use anyhow::{Context, Result, anyhow, ensure};
+use dns_parser::{Builder, Packet, QueryClass, QueryType, RData, ResponseCode};
 use std::net::IpAddr;
 
 use zond_common::utils::ip;
 
+use crate::utils::DNS_HDR_LEN;
+
+/// What an SOA lookup revealed about a reverse zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZoneHint {
+    /// The zone does not exist (NXDOMAIN), so PTR lookups inside it are pointless.
+    Missing,
+    /// The zone exists and names its primary server. `addr` is only set when the
+    /// response carried a glue record for that server.
+    Authority {
+        primary_ns: String,
+        addr: Option<IpAddr>,
+    },
+    /// The server answered without a conclusive SOA record.
+    Unknown,
+}
+
+/// Reads the transaction ID from a DNS payload without parsing the whole message.
+pub fn get_transaction_id(payload: &[u8]) -> Result<u16> {
+    ensure!(
+        payload.len() >= DNS_HDR_LEN,
+        "truncated DNS header (payload len {})",
+        payload.len()
+    );
+    Ok(u16::from_be_bytes([payload[0], payload[1]]))
+}
+
 pub fn get_hostname(payload: &[u8]) -> Result<(u16, String)> {
     let packet = Packet::parse(payload).context("Failed to parse DNS packet")?;
 
@@ -22,13 +49,106 @@ pub fn get_hostname(payload: &[u8]) -> Result<(u16, String)> {
     Err(anyhow!("No valid PTR record found"))
 }
 
+/// Interprets the response to an SOA query created by [`create_soa_packet`].
+pub fn get_zone_hint(payload: &[u8]) -> Result<(u16, ZoneHint)> {
+    let packet = Packet::parse(payload).context("Failed to parse DNS packet")?;
+    let id: u16 = packet.header.id;
+
+    if packet.header.response_code == ResponseCode::NameError {
+        return Ok((id, ZoneHint::Missing));
+    }
+
+    let primary_ns: Option<String> = packet.answers.iter().find_map(|record| match &record.data {
+        RData::SOA(soa) => Some(soa.primary_ns.to_string()),
+        _ => None,
+    });
+
+    let Some(primary_ns) = primary_ns else {
+        return Ok((id, ZoneHint::Unknown));
+    };
+
+    let addr: Option<IpAddr> = packet
+        .additional
+        .iter()
+        .filter(|record| record.name.to_string().eq_ignore_ascii_case(&primary_ns))
+        .find_map(|record| match &record.data {
+            RData::A(a) => Some(IpAddr::V4(a.0)),
+            RData::AAAA(aaaa) => Some(IpAddr::V6(aaaa.0)),
+            _ => None,
+        });
+
+    Ok((id, ZoneHint::Authority { primary_ns, addr }))
+}
+
 /// Constructs a raw DNS query packet for a PTR lookup.
 pub fn create_ptr_packet(ip_addr: &IpAddr, id: u16) -> Result<Vec<u8>> {
     let ptr_name: String = ip::reverse_address_to_ptr(ip_addr);
+    create_query_packet(&ptr_name, QueryType::PTR, id)
+}
+
+/// Constructs a raw DNS query packet asking for the SOA of a zone.
+pub fn create_soa_packet(zone: &str, id: u16) -> Result<Vec<u8>> {
+    create_query_packet(zone, QueryType::SOA, id)
+}
+
+/// Constructs a recursive DNS query packet for the A records of `qname`.
+pub fn create_a_packet(qname: &str, id: u16) -> Result<Vec<u8>> {
+    create_query_packet(qname, QueryType::A, id)
+}
+
+/// Returns `true` if `payload` resolved the A query for `qname` recursively.
+///
+/// The server has to offer recursion, report success and carry at least one
+/// answer. Refusals, `SERVFAIL` and referrals all leave the name unresolved.
+pub fn resolves_recursively(payload: &[u8], qname: &str) -> bool {
+    if !answers_question(payload, qname, QueryType::A) {
+        return false;
+    }
+    let Ok(packet) = Packet::parse(payload) else {
+        return false;
+    };
+    packet.header.recursion_available
+        && packet.header.response_code == ResponseCode::NoError
+        && !packet.answers.is_empty()
+}
+
+/// Returns `true` if `payload` is a response to the PTR query for `ip_addr`.
+pub fn answers_ptr(payload: &[u8], ip_addr: &IpAddr) -> bool {
+    answers_question(
+        payload,
+        &ip::reverse_address_to_ptr(ip_addr),
+        QueryType::PTR,
+    )
+}
 
+/// Returns `true` if `payload` is a response to the SOA query for `zone`.
+pub fn answers_soa(payload: &[u8], zone: &str) -> bool {
+    answers_question(payload, zone, QueryType::SOA)
+}
+
+/// Servers echo the question of a query in their response, so a response that
+/// asks something else was not sent for our query, whatever its ID says.
+fn answers_question(payload: &[u8], qname: &str, qtype: QueryType) -> bool {
+    let Ok(packet) = Packet::parse(payload) else {
+        return false;
+    };
+    let [question] = packet.questions.as_slice() else {
+        return false;
+    };
+
+    !packet.header.query
+        && question.qtype == qtype
+        && question
+            .qname
+            .to_string()
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case(qname.trim_end_matches('.'))
+}
+
+fn create_query_packet(qname: &str, qtype: QueryType, id: u16) -> Result<Vec<u8>> {
     let mut builder: Builder = Builder::new_query(id, true);
 
-    builder.add_question(&ptr_name, false, QueryType::PTR, QueryClass::IN);
+    builder.add_question(qname, false, qtype, QueryClass::IN);
 
     let packet_bytes: Vec<u8> = builder
         .build()
@@ -36,3 +156,83 @@ pub fn create_ptr_packet(ip_addr: &IpAddr, id: u16) -> Result<Vec<u8>> {
 
     Ok(packet_bytes)
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soa_query_targets_zone() {
+        let bytes = create_soa_packet("1.168.192.in-addr.arpa", 0x1234).unwrap();
+        let packet = Packet::parse(&bytes).unwrap();
+
+        assert_eq!(packet.header.id, 0x1234);
+        assert_eq!(packet.questions.len(), 1);
+        assert_eq!(packet.questions[0].qtype, QueryType::SOA);
+        assert_eq!(
+            packet.questions[0].qname.to_string(),
+            "1.168.192.in-addr.arpa"
+        );
+    }
+
+    #[test]
+    fn nxdomain_marks_zone_missing() {
+        let mut bytes = create_soa_packet("1.168.192.in-addr.arpa", 7).unwrap();
+        // QR bit set, RCODE 3 (NXDOMAIN)
+        bytes[2] |= 0x80;
+        bytes[3] = (bytes[3] & 0xF0) | 0x03;
+
+        let (id, hint) = get_zone_hint(&bytes).unwrap();
+        assert_eq!(id, 7);
+        assert_eq!(hint, ZoneHint::Missing);
+    }
+
+    #[test]
+    fn responses_must_repeat_the_question() {
+        let ip: IpAddr = "192.168.1.20".parse().unwrap();
+        let mut bytes = create_ptr_packet(&ip, 9).unwrap();
+        assert!(!answers_ptr(&bytes, &ip), "a query is not a response");
+
+        bytes[2] |= 0x80;
+        assert!(answers_ptr(&bytes, &ip));
+        assert!(!answers_ptr(&bytes, &"192.168.1.21".parse().unwrap()));
+        assert!(!answers_soa(&bytes, "1.168.192.in-addr.arpa"));
+    }
+
+    #[test]
+    fn recursion_needs_an_answer() {
+        let mut bytes = create_a_packet("example.com", 3).unwrap();
+        // QR and RA bits set, no answers yet
+        bytes[2] |= 0x80;
+        bytes[3] |= 0x80;
+        assert!(!resolves_recursively(&bytes, "example.com"));
+
+        // example.com A 93.184.215.14, the name pointing back at the question
+        bytes[7] = 1;
+        bytes.extend_from_slice(&[0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0x0E, 0x10, 0, 4]);
+        bytes.extend_from_slice(&[93, 184, 215, 14]);
+        assert!(resolves_recursively(&bytes, "example.com"));
+        assert!(!resolves_recursively(&bytes, "example.org"));
+
+        bytes[3] &= !0x80;
+        assert!(!resolves_recursively(&bytes, "example.com"), "no RA bit");
+    }
+
+    #[test]
+    fn transaction_id_rejects_truncated_header() {
+        assert!(get_transaction_id(&[0x12, 0x34]).is_err());
+        assert_eq!(
+            get_transaction_id(&[0x12, 0x34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
+            0x1234
+        );
+    }
+}
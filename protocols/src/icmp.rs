@@ -6,20 +6,27 @@
 
 use crate::ethernet;
 use crate::ip;
-use crate::utils::{ETH_HDR_LEN, ICMP_V6_ECHO_REQ_LEN, IP_V6_HDR_LEN};
+use crate::utils::{ETH_HDR_LEN, ICMP_V4_ECHO_REQ_LEN, ICMP_V6_ECHO_REQ_LEN, IP_V6_HDR_LEN};
 use anyhow::Context;
 use pnet::datalink::MacAddr;
 use pnet::packet::Packet;
 use pnet::packet::ethernet::EtherTypes;
+use pnet::packet::icmp::echo_reply::EchoReplyPacket;
+use pnet::packet::icmp::echo_request::MutableEchoRequestPacket as MutableEchoRequestPacketV4;
+use pnet::packet::icmp::{IcmpCode, IcmpPacket, IcmpTypes};
 use pnet::packet::icmpv6::echo_reply::Icmpv6Codes;
 use pnet::packet::icmpv6::echo_request::{EchoRequestPacket, MutableEchoRequestPacket};
 use pnet::packet::icmpv6::{Icmpv6Packet, Icmpv6Types, checksum};
 use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
-use std::net::Ipv6Addr;
+use pnet::packet::ipv4::Ipv4Packet;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use zond_common::models::host::UnreachableReason;
 
 const TOTAL_LEN: usize = ETH_HDR_LEN + IP_V6_HDR_LEN + ICMP_V6_ECHO_REQ_LEN;
 const PAYLOAD_LENGTH: u16 = ICMP_V6_ECHO_REQ_LEN as u16;
 const NEXT_PROTOCOL: IpNextHeaderProtocol = IpNextHeaderProtocols::Icmpv6;
+/// Offset of the quoted datagram inside an ICMPv4 destination-unreachable message.
+const UNREACHABLE_QUOTE_OFFSET: usize = 8;
 
 pub fn create_all_nodes_echo_request_v6(
     src_mac: MacAddr,
@@ -54,3 +61,204 @@ pub fn create_all_nodes_echo_request_v6(
 
     Ok(final_packet)
 }
+
+/// Builds an ICMPv4 echo request (without IP header) for a Layer 4 ICMP socket.
+pub fn create_echo_request_v4(identifier: u16, sequence: u16) -> anyhow::Result<Vec<u8>> {
+    let mut buffer: Vec<u8> = vec![0u8; ICMP_V4_ECHO_REQ_LEN];
+    {
+        let mut echo: MutableEchoRequestPacketV4 = MutableEchoRequestPacketV4::new(&mut buffer)
+            .context("failed to create echo request packet")?;
+        echo.set_icmp_type(IcmpTypes::EchoRequest);
+        echo.set_icmp_code(IcmpCode::new(0));
+        echo.set_identifier(identifier);
+        echo.set_sequence_number(sequence);
+    }
+
+    let checksum: u16 = pnet::packet::icmp::checksum(
+        &IcmpPacket::new(&buffer).context("failed to create ICMP packet")?,
+    );
+    buffer[2..4].copy_from_slice(&checksum.to_be_bytes());
+    Ok(buffer)
+}
+
+/// Extracts identifier and sequence number from an ICMPv4 echo reply.
+///
+/// Returns `None` for any other ICMP message.
+pub fn parse_echo_reply_v4(payload: &[u8]) -> Option<(u16, u16)> {
+    let reply: EchoReplyPacket = EchoReplyPacket::new(payload)?;
+    if reply.get_icmp_type() != IcmpTypes::EchoReply {
+        return None;
+    }
+    Some((reply.get_identifier(), reply.get_sequence_number()))
+}
+
+/// Turns an ICMPv4 echo request (without IP header) into the reply a host
+/// would send, echoing identifier, sequence number and data.
+///
+/// Returns `None` for any other ICMP message.
+pub fn create_echo_reply_v4(request: &[u8]) -> Option<Vec<u8>> {
+    let icmp: IcmpPacket = IcmpPacket::new(request)?;
+    if icmp.get_icmp_type() != IcmpTypes::EchoRequest || request.len() < ICMP_V4_ECHO_REQ_LEN {
+        return None;
+    }
+
+    let mut reply: Vec<u8> = request.to_vec();
+    reply[0] = IcmpTypes::EchoReply.0;
+    reply[2..4].fill(0);
+    let checksum: u16 = pnet::packet::icmp::checksum(&IcmpPacket::new(&reply)?);
+    reply[2..4].copy_from_slice(&checksum.to_be_bytes());
+    Some(reply)
+}
+
+/// The probe an ICMPv4 destination-unreachable message refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnreachableReport {
+    /// Destination of the original datagram, i.e. the scan target.
+    pub target: Ipv4Addr,
+    /// TCP source port of the original datagram.
+    pub src_port: u16,
+    /// TCP sequence number of the original datagram.
+    pub seq: u32,
+    pub reason: UnreachableReason,
+}
+
+/// Parses an ICMPv4 destination-unreachable message quoting a TCP segment.
+///
+/// Returns `None` for any other ICMP message, for codes that do not indicate
+/// filtering or unreachability, and for quotes too short to identify the probe.
+pub fn parse_tcp_unreachable_v4(payload: &[u8]) -> Option<UnreachableReport> {
+    let icmp: IcmpPacket = IcmpPacket::new(payload)?;
+    if icmp.get_icmp_type() != IcmpTypes::DestinationUnreachable {
+        return None;
+    }
+
+    let reason: UnreachableReason = match icmp.get_icmp_code().0 {
+        0 => UnreachableReason::Network,
+        1 => UnreachableReason::Host,
+        3 => UnreachableReason::Port,
+        9 | 10 | 13 => UnreachableReason::AdminProhibited,
+        _ => return None,
+    };
+
+    let quoted: Ipv4Packet = Ipv4Packet::new(payload.get(UNREACHABLE_QUOTE_OFFSET..)?)?;
+    if quoted.get_next_level_protocol() != IpNextHeaderProtocols::Tcp {
+        return None;
+    }
+
+    let header_len: usize = quoted.get_header_length() as usize * 4;
+    let segment: &[u8] = payload.get(UNREACHABLE_QUOTE_OFFSET + header_len..)?;
+    let src_port: u16 = u16::from_be_bytes(segment.get(0..2)?.try_into().ok()?);
+    let seq: u32 = u32::from_be_bytes(segment.get(4..8)?.try_into().ok()?);
+
+    Some(UnreachableReport {
+        target: quoted.get_destination(),
+        src_port,
+        seq,
+        reason,
+    })
+}
+
+/// A datagram quoted by an ICMPv4 destination-unreachable message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnreachableQuote {
+    /// Destination of the original datagram, i.e. the scan target.
+    pub target: Ipv4Addr,
+    /// IP protocol number of the original datagram.
+    pub protocol: u8,
+    /// Code of the message, e.g. 2 for protocol unreachable.
+    pub code: u8,
+}
+
+/// Parses an ICMPv4 destination-unreachable message down to the destination
+/// and protocol of the datagram it quotes, whatever that protocol is.
+///
+/// Returns `None` for any other ICMP message and for truncated quotes.
+pub fn parse_unreachable_quote_v4(payload: &[u8]) -> Option<UnreachableQuote> {
+    let icmp: IcmpPacket = IcmpPacket::new(payload)?;
+    if icmp.get_icmp_type() != IcmpTypes::DestinationUnreachable {
+        return None;
+    }
+    let quoted: Ipv4Packet = Ipv4Packet::new(payload.get(UNREACHABLE_QUOTE_OFFSET..)?)?;
+    Some(UnreachableQuote {
+        target: quoted.get_destination(),
+        protocol: quoted.get_next_level_protocol().0,
+        code: icmp.get_icmp_code().0,
+    })
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_msg(code: u8) -> Vec<u8> {
+        let mut msg: Vec<u8> = vec![3, code, 0, 0, 0, 0, 0, 0];
+        // Quoted IPv4 header: IHL 5, protocol TCP, 10.0.0.2 -> 203.0.113.7
+        msg.extend_from_slice(&[
+            0x45, 0, 0, 40, 0, 0, 0, 0, 64, 6, 0, 0, 10, 0, 0, 2, 203, 0, 113, 7,
+        ]);
+        // First 8 bytes of the TCP segment: ports 51000 -> 443, seq 0xDEADBEEF
+        msg.extend_from_slice(&[0xC7, 0x38, 0x01, 0xBB, 0xDE, 0xAD, 0xBE, 0xEF]);
+        msg
+    }
+
+    #[test]
+    fn parses_admin_prohibited() {
+        let report = parse_tcp_unreachable_v4(&unreachable_msg(13)).unwrap();
+        assert_eq!(report.target, Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(report.src_port, 51_000);
+        assert_eq!(report.seq, 0xDEAD_BEEF);
+        assert_eq!(report.reason, UnreachableReason::AdminProhibited);
+    }
+
+    #[test]
+    fn echo_request_round_trips_as_reply() {
+        let mut packet: Vec<u8> = create_echo_request_v4(0x1234, 7).unwrap();
+        let checksum: u16 = pnet::packet::icmp::checksum(&IcmpPacket::new(&packet).unwrap());
+        assert_eq!(u16::from_be_bytes([packet[2], packet[3]]), checksum);
+
+        assert_eq!(parse_echo_reply_v4(&packet), None);
+        let reply: Vec<u8> = create_echo_reply_v4(&packet).unwrap();
+        assert_eq!(parse_echo_reply_v4(&reply), Some((0x1234, 7)));
+        let checksum: u16 = pnet::packet::icmp::checksum(&IcmpPacket::new(&reply).unwrap());
+        assert_eq!(u16::from_be_bytes([reply[2], reply[3]]), checksum);
+        assert!(create_echo_reply_v4(&reply).is_none());
+
+        // A reply echoes the request with type 0
+        packet[0] = 0;
+        assert_eq!(parse_echo_reply_v4(&packet), Some((0x1234, 7)));
+    }
+
+    #[test]
+    fn quotes_of_any_protocol_are_read() {
+        let mut msg: Vec<u8> = unreachable_msg(2);
+        // Rewrite the quoted protocol to GRE.
+        msg[8 + 9] = 47;
+        assert_eq!(
+            parse_unreachable_quote_v4(&msg),
+            Some(UnreachableQuote {
+                target: Ipv4Addr::new(203, 0, 113, 7),
+                protocol: 47,
+                code: 2,
+            })
+        );
+        assert!(parse_unreachable_quote_v4(&msg[..20]).is_none());
+    }
+
+    #[test]
+    fn ignores_other_codes_and_truncated_quotes() {
+        assert!(parse_tcp_unreachable_v4(&unreachable_msg(4)).is_none());
+
+        let mut truncated = unreachable_msg(1);
+        truncated.truncate(30);
+        assert!(parse_tcp_unreachable_v4(&truncated).is_none());
+    }
+}
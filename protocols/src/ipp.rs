@@ -0,0 +1,203 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! IPP `Get-Printer-Attributes` over HTTP (RFC 8010, RFC 8011).
+//!
+//! Only the attributes that describe the device are requested, and only text
+//! values are decoded from the reply.
+
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result, bail, ensure};
+
+const GET_PRINTER_ATTRIBUTES: u16 = 0x000B;
+const REQUEST_ID: u32 = 1;
+
+const TAG_OPERATION_ATTRIBUTES: u8 = 0x01;
+const TAG_END_OF_ATTRIBUTES: u8 = 0x03;
+const TAG_TEXT: u8 = 0x41;
+const TAG_NAME: u8 = 0x42;
+const TAG_KEYWORD: u8 = 0x44;
+const TAG_URI: u8 = 0x45;
+const TAG_CHARSET: u8 = 0x47;
+const TAG_NATURAL_LANGUAGE: u8 = 0x48;
+
+/// Path of the default print queue defined by IPP Everywhere.
+const PRINTER_PATH: &str = "/ipp/print";
+
+/// What a printer reports about itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PrinterAttributes {
+    /// `printer-make-and-model`, e.g. `HP LaserJet M404dn`.
+    pub make_and_model: Option<String>,
+    /// `printer-location`, as configured by its administrator.
+    pub location: Option<String>,
+}
+
+/// Builds the complete HTTP request asking the printer at `addr` for its
+/// make, model and location.
+pub fn create_get_printer_attributes(addr: SocketAddr) -> Vec<u8> {
+    let mut body: Vec<u8> = Vec::new();
+    body.extend_from_slice(&[0x02, 0x00]);
+    body.extend_from_slice(&GET_PRINTER_ATTRIBUTES.to_be_bytes());
+    body.extend_from_slice(&REQUEST_ID.to_be_bytes());
+
+    body.push(TAG_OPERATION_ATTRIBUTES);
+    push_attribute(&mut body, TAG_CHARSET, "attributes-charset", "utf-8");
+    push_attribute(
+        &mut body,
+        TAG_NATURAL_LANGUAGE,
+        "attributes-natural-language",
+        "en",
+    );
+    let uri: String = format!("ipp://{addr}{PRINTER_PATH}");
+    push_attribute(&mut body, TAG_URI, "printer-uri", &uri);
+    push_attribute(
+        &mut body,
+        TAG_KEYWORD,
+        "requested-attributes",
+        "printer-make-and-model",
+    );
+    push_attribute(&mut body, TAG_KEYWORD, "", "printer-location");
+    body.push(TAG_END_OF_ATTRIBUTES);
+
+    let mut request: Vec<u8> = format!(
+        "POST {PRINTER_PATH} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/ipp\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    request.extend(body);
+    request
+}
+
+/// An empty `name` adds another value to the previous attribute.
+fn push_attribute(body: &mut Vec<u8>, tag: u8, name: &str, value: &str) {
+    body.push(tag);
+    body.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    body.extend_from_slice(name.as_bytes());
+    body.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    body.extend_from_slice(value.as_bytes());
+}
+
+/// Parses the HTTP response to [`create_get_printer_attributes`].
+pub fn get_printer_attributes(response: &[u8]) -> Result<PrinterAttributes> {
+    ensure!(response.starts_with(b"HTTP/1."), "not an HTTP response");
+    let body_start: usize = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("truncated HTTP header")?
+        + 4;
+    let body: &[u8] = &response[body_start..];
+
+    ensure!(
+        body.len() >= 8,
+        "truncated IPP response (len {})",
+        body.len()
+    );
+    let status: u16 = u16::from_be_bytes([body[2], body[3]]);
+    ensure!(status < 0x0100, "IPP request failed (status {status:#06x})");
+
+    let mut attributes: PrinterAttributes = PrinterAttributes::default();
+    let mut rest: &[u8] = &body[8..];
+    let mut name: String = String::new();
+    while let [tag, tail @ ..] = rest {
+        if *tag == TAG_END_OF_ATTRIBUTES {
+            return Ok(attributes);
+        }
+        if *tag < 0x10 {
+            rest = tail;
+            continue;
+        }
+
+        let (attr_name, tail) = read_field(tail)?;
+        let (value, tail) = read_field(tail)?;
+        rest = tail;
+        if !attr_name.is_empty() {
+            name = String::from_utf8_lossy(attr_name).into_owned();
+        }
+        if !matches!(*tag, TAG_TEXT | TAG_NAME) {
+            continue;
+        }
+
+        let text: String = String::from_utf8_lossy(value).trim().to_string();
+        let slot: &mut Option<String> = match name.as_str() {
+            "printer-make-and-model" => &mut attributes.make_and_model,
+            "printer-location" => &mut attributes.location,
+            _ => continue,
+        };
+        if slot.is_none() && !text.is_empty() {
+            *slot = Some(text);
+        }
+    }
+    bail!("IPP response ends without end-of-attributes tag")
+}
+
+/// Splits a length-prefixed field off the front of `bytes`.
+fn read_field(bytes: &[u8]) -> Result<(&[u8], &[u8])> {
+    let [hi, lo, rest @ ..] = bytes else {
+        bail!("truncated IPP attribute");
+    };
+    let len: usize = u16::from_be_bytes([*hi, *lo]) as usize;
+    ensure!(rest.len() >= len, "truncated IPP attribute value");
+    Ok(rest.split_at(len))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_is_framed_for_http() {
+        let addr: SocketAddr = "[fe80::1]:631".parse().unwrap();
+        let request: Vec<u8> = create_get_printer_attributes(addr);
+        let text: String = String::from_utf8_lossy(&request).into_owned();
+
+        assert!(text.starts_with("POST /ipp/print HTTP/1.1\r\nHost: [fe80::1]:631\r\n"));
+        assert!(text.contains("ipp://[fe80::1]:631/ipp/print"));
+        assert_eq!(request.last(), Some(&TAG_END_OF_ATTRIBUTES));
+    }
+
+    #[test]
+    fn reads_printer_attributes() {
+        let mut body: Vec<u8> = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+        body.push(TAG_OPERATION_ATTRIBUTES);
+        push_attribute(&mut body, TAG_CHARSET, "attributes-charset", "utf-8");
+        body.push(0x04);
+        push_attribute(
+            &mut body,
+            TAG_TEXT,
+            "printer-make-and-model",
+            "HP LaserJet M404dn",
+        );
+        push_attribute(&mut body, TAG_TEXT, "printer-location", " 2nd floor ");
+        body.push(TAG_END_OF_ATTRIBUTES);
+
+        let mut response: Vec<u8> =
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/ipp\r\n\r\n".to_vec();
+        response.extend(body);
+
+        let attributes: PrinterAttributes = get_printer_attributes(&response).unwrap();
+        assert_eq!(
+            attributes.make_and_model.as_deref(),
+            Some("HP LaserJet M404dn")
+        );
+        assert_eq!(attributes.location.as_deref(), Some("2nd floor"));
+
+        let mut refused: Vec<u8> = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+        refused.extend_from_slice(&[0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03]);
+        assert!(get_printer_attributes(&refused).is_err());
+    }
+}
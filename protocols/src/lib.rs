@@ -5,54 +5,137 @@
 // https://mozilla.org/MPL/2.0/.
 
 pub mod arp;
+pub mod dhcp;
 pub mod dns;
 pub mod ethernet;
 pub mod icmp;
 pub mod ip;
+pub mod ipp;
 pub mod mdns;
 pub mod ndp;
+pub mod netbios;
+pub mod pjl;
+pub mod sctp;
+pub mod smb;
 pub mod tcp;
+pub mod tls;
 pub mod udp;
 pub mod utils;
 
+use zond_common::models::ip::set::IpSet;
 use zond_common::sender::{PacketType, SenderConfig};
+use zond_common::warn;
 
 use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
 use pnet::util::MacAddr;
+use std::collections::VecDeque;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 type Bytes = Vec<u8>;
 type PacketIter = Box<dyn Iterator<Item = (Bytes, IpAddr)> + Send>;
 
+/// Builds the probes for every enabled packet type.
+///
+/// Packets of different protocols are interleaved round-robin, so IPv6 discovery
+/// does not wait for the whole ARP sweep and both families share the send rate.
 pub fn eth_packet_iter(sender_config: &SenderConfig) -> anyhow::Result<PacketIter> {
-    let mut combined_iter: PacketIter = Box::new(std::iter::empty());
+    let mut iters: Vec<PacketIter> = Vec::new();
 
     if sender_config.has_packet_type(PacketType::ARP) {
-        let arp_iter = create_arp_packets(sender_config)?;
-        combined_iter = Box::new(combined_iter.chain(arp_iter));
+        iters.push(create_arp_packets(sender_config)?);
     }
 
     if sender_config.has_packet_type(PacketType::ICMPv6) {
-        let icmp_iter = create_icmpv6_packets(sender_config)?;
-        combined_iter = Box::new(combined_iter.chain(icmp_iter));
+        iters.push(create_icmpv6_packets(sender_config)?);
     }
 
-    Ok(combined_iter)
+    Ok(Box::new(RoundRobin::new(iters)))
 }
 
+/// Takes one item from each iterator in turn, dropping iterators once they run dry.
+struct RoundRobin<I> {
+    iters: VecDeque<I>,
+}
+
+impl<I: Iterator> RoundRobin<I> {
+    fn new(iters: impl IntoIterator<Item = I>) -> Self {
+        Self {
+            iters: iters.into_iter().collect(),
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for RoundRobin<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(mut iter) = self.iters.pop_front() {
+            if let Some(item) = iter.next() {
+                self.iters.push_back(iter);
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iters
+            .iter()
+            .map(Iterator::size_hint)
+            .fold((0, Some(0)), |(lower, upper), (l, u)| {
+                (
+                    lower.saturating_add(l),
+                    upper.zip(u).and_then(|(a, b)| a.checked_add(b)),
+                )
+            })
+    }
+}
+
+/// Builds an ARP request for every IPv4 target.
+///
+/// On interfaces with several subnets, each request is sent from the address
+/// in the target's own subnet, since hosts ignore requests from a sender
+/// outside their network. Targets outside every subnet fall back to the first
+/// address, with a single warning.
+///
+/// With a probe MAC set, the requests are RFC 5227 ARP probes with an empty
+/// sender address, so neighbors answer without caching our addresses at a MAC
+/// that is gone after the scan.
 pub fn create_arp_packets(sender_config: &SenderConfig) -> anyhow::Result<PacketIter> {
-    let src_mac = sender_config.get_local_mac()?;
+    let src_mac = sender_config.get_probe_mac()?;
     let dst_mac = MacAddr::broadcast();
-    let src_net = sender_config.get_ipv4_net()?;
-    let src_addr = src_net.ip();
+    let fallback_addr: Ipv4Addr = sender_config.get_ipv4_net()?.ip();
 
-    let targets: Vec<Ipv4Addr> = sender_config.iter_targets_v4().copied().collect();
+    let config: SenderConfig = sender_config.clone();
+    let targets: IpSet = config.targets_v4().clone();
+    let mut warned: bool = false;
+    let probing: bool = config.probe_mac().is_some();
 
-    let iter = targets.into_iter().map(move |dst_addr| {
-        let packet = arp::create_packet(src_mac, dst_mac, src_addr, dst_addr)
-            .expect("Failed to create ARP packet");
+    let iter = targets.into_iter().filter_map(move |dst_addr| {
+        let IpAddr::V4(dst_addr) = dst_addr else {
+            return None;
+        };
+        let src_addr: Ipv4Addr = config.ipv4_source_for(dst_addr).unwrap_or_else(|| {
+            if !warned && !probing {
+                warned = true;
+                warn!(
+                    "{dst_addr} is outside every IPv4 subnet of the interface, \
+                     sending ARP requests for such targets from {fallback_addr}"
+                );
+            }
+            fallback_addr
+        });
+        let src_addr: Ipv4Addr = match probing {
+            true => Ipv4Addr::UNSPECIFIED,
+            false => src_addr,
+        };
+        let packet = match config.neighbors().get(&dst_addr) {
+            Some(&known_mac) => arp::create_unicast_packet(src_mac, known_mac, src_addr, dst_addr),
+            None => arp::create_packet(src_mac, dst_mac, src_addr, dst_addr),
+        }
+        .expect("Failed to create ARP packet");
 
-        (packet, IpAddr::V4(dst_addr))
+        Some((packet, IpAddr::V4(dst_addr)))
     });
 
     Ok(Box::new(iter))
@@ -79,3 +162,100 @@ pub fn get_ip_addr_from_eth(frame: &EthernetPacket) -> anyhow::Result<IpAddr> {
         )),
     }
 }
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pnet::datalink::NetworkInterface;
+    use pnet::ipnetwork::{IpNetwork, Ipv4Network};
+    use std::collections::HashMap;
+
+    #[test]
+    fn round_robin_interleaves_until_all_are_drained() {
+        let iters: Vec<std::vec::IntoIter<u8>> = vec![vec![1, 3, 5, 6], vec![2, 4]]
+            .into_iter()
+            .map(Vec::into_iter)
+            .collect();
+        let rr: RoundRobin<std::vec::IntoIter<u8>> = RoundRobin::new(iters);
+
+        assert_eq!(rr.size_hint(), (6, Some(6)));
+        assert_eq!(rr.collect::<Vec<u8>>(), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn arp_source_matches_the_target_subnet() {
+        let interface: NetworkInterface = NetworkInterface {
+            name: "eth0".to_string(),
+            description: String::new(),
+            index: 0,
+            mac: Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)),
+            ips: vec![
+                IpNetwork::V4(Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 5), 24).unwrap()),
+                IpNetwork::V4(Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 5), 24).unwrap()),
+            ],
+            flags: 0,
+        };
+        let mut config: SenderConfig = SenderConfig::from(&interface);
+        config.add_targets([
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 20)),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+            IpAddr::V4(Ipv4Addr::new(172, 16, 0, 20)),
+        ]);
+
+        let sources: HashMap<IpAddr, Ipv4Addr> = create_arp_packets(&config)
+            .unwrap()
+            .map(|(packet, dst)| {
+                let frame: EthernetPacket = EthernetPacket::new(&packet).unwrap();
+                (dst, arp::get_ipv4_addr_from_eth(&frame).unwrap())
+            })
+            .collect();
+
+        assert_eq!(
+            sources[&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 20))],
+            Ipv4Addr::new(10, 0, 0, 5)
+        );
+        assert_eq!(
+            sources[&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20))],
+            Ipv4Addr::new(192, 168, 1, 5)
+        );
+        assert_eq!(
+            sources[&IpAddr::V4(Ipv4Addr::new(172, 16, 0, 20))],
+            Ipv4Addr::new(10, 0, 0, 5)
+        );
+    }
+
+    #[test]
+    fn probes_from_a_random_mac_leave_the_sender_address_empty() {
+        let interface: NetworkInterface = NetworkInterface {
+            name: "eth0".to_string(),
+            description: String::new(),
+            index: 0,
+            mac: Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)),
+            ips: vec![IpNetwork::V4(
+                Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 5), 24).unwrap(),
+            )],
+            flags: 0,
+        };
+        let mut config: SenderConfig = SenderConfig::from(&interface);
+        config.set_probe_mac(MacAddr::new(0x06, 1, 2, 3, 4, 5));
+        config.add_targets([IpAddr::V4(Ipv4Addr::new(10, 0, 0, 20))]);
+
+        for (packet, _) in create_arp_packets(&config).unwrap() {
+            let frame: EthernetPacket = EthernetPacket::new(&packet).unwrap();
+            assert_eq!(frame.get_source(), MacAddr::new(0x06, 1, 2, 3, 4, 5));
+            assert_eq!(
+                arp::get_ipv4_addr_from_eth(&frame).unwrap(),
+                Ipv4Addr::UNSPECIFIED
+            );
+        }
+    }
+}
@@ -4,40 +4,427 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use anyhow::{Context, Result};
-use dns_parser::{Packet, RData};
-use std::{collections::HashSet, net::IpAddr};
+//! Parsing of multicast DNS announcements, and the queries that ask a link or a
+//! single host for them.
+//!
+//! Besides names and addresses, the TXT records of DNS-SD services often say
+//! what the device is: `model=` (Apple devices), `md=` (Chromecast), `am=`
+//! (AirPlay), `ty=` (printers) and a friendly name in `fn=`.
+//!
+//! Anyone on the link can send mDNS, so every length, count and compression
+//! pointer is checked against the payload before it is followed. Malformed
+//! packets yield an [`MdnsError`] and never panic.
+
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use pnet::packet::{ethernet::EtherTypes, ip::IpNextHeaderProtocols};
+use pnet::util::MacAddr;
+use thiserror::Error;
+
+use crate::utils::{DNS_HDR_LEN, IP_V4_HDR_LEN};
+use crate::{ethernet, ip, udp};
+
+pub const MDNS_PORT: u16 = 5353;
+pub const MDNS_GROUP_V4: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+/// Ethernet address `224.0.0.251` maps to.
+const MDNS_GROUP_MAC: MacAddr = MacAddr(0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb);
+/// DNS-SD meta-query every advertising device answers (RFC 6763, section 9).
+const SERVICES_NAME: &str = "_services._dns-sd._udp.local";
+/// Link-local multicast must arrive with this TTL (RFC 6762, section 11).
+const MDNS_TTL: u8 = 255;
+const UDP_HDR_LEN: usize = 8;
+const CLASS_IN: u16 = 1;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+
+/// Encoded length of the smallest question: root name, type and class.
+const MIN_QUESTION_LEN: usize = 5;
+/// Encoded length of the smallest resource record: root name, type, class,
+/// TTL and RDATA length.
+const MIN_RECORD_LEN: usize = 11;
+/// Longest name RFC 1035 allows, in its encoded form.
+const MAX_NAME_LEN: usize = 255;
+/// TXT keys naming the model, most specific first.
+const MODEL_KEYS: [&str; 4] = ["model", "md", "am", "ty"];
+const FRIENDLY_NAME_KEY: &str = "fn";
 
 #[derive(Debug, Default)]
 pub struct MdnsRecord {
     pub hostname: Option<String>,
     pub ips: HashSet<IpAddr>,
+    /// Model named by a TXT record, e.g. `MacBookPro18,3`.
+    pub model: Option<String>,
+    /// Friendly name from a TXT record, e.g. `Living Room TV`.
+    pub friendly_name: Option<String>,
+}
+
+/// Reasons an mDNS payload is rejected.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MdnsError {
+    /// The payload ends inside the named part of the message.
+    #[error("truncated mDNS {0}")]
+    Truncated(&'static str),
+
+    /// The header announces more entries than the payload could hold.
+    #[error("{0} mDNS entries announced, more than fit into {1} bytes")]
+    TooManyRecords(usize, usize),
+
+    /// A compression pointer does not point strictly backwards, which could loop.
+    #[error("mDNS name compression loop at offset {0}")]
+    CompressionLoop(usize),
+
+    /// A name exceeds the 255 bytes DNS allows.
+    #[error("mDNS name longer than {MAX_NAME_LEN} bytes")]
+    NameTooLong,
+
+    /// A label uses one of the reserved or extended label types.
+    #[error("invalid mDNS label type {0:#04x}")]
+    InvalidLabel(u8),
+
+    /// An address record carries RDATA of the wrong size.
+    #[error("mDNS record of type {0} has {1} bytes of data")]
+    InvalidRecordLength(u16, usize),
+}
+
+/// Returns `true` if `data` is an mDNS response rather than a query.
+pub fn is_response(data: &[u8]) -> bool {
+    data.len() >= DNS_HDR_LEN && data[2] & 0x80 != 0
+}
+
+/// Builds the DNS-SD service enumeration query, a `PTR` question for
+/// `_services._dns-sd._udp.local` with the ID of 0 multicast queries use.
+pub fn create_services_query() -> Vec<u8> {
+    let mut query: Vec<u8> = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    push_question(&mut query, SERVICES_NAME, TYPE_PTR);
+    query
+}
+
+/// Builds a legacy unicast query (RFC 6762, section 6.7) for the `A` and
+/// `AAAA` records of `hostname`, to be sent straight to the host from an
+/// ephemeral port. The host answers the sender alone, listing every address
+/// it holds the name on.
+pub fn create_address_query(hostname: &str, id: u16) -> Vec<u8> {
+    let mut query: Vec<u8> = id.to_be_bytes().to_vec();
+    query.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 0, 0, 0]);
+    push_question(&mut query, hostname, TYPE_A);
+    push_question(&mut query, hostname, TYPE_AAAA);
+    query
+}
+
+fn push_question(query: &mut Vec<u8>, name: &str, qtype: u16) {
+    for label in name.trim_end_matches('.').split('.') {
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&qtype.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+}
+
+/// Wraps [`create_services_query`] into an Ethernet frame from port 5353 to
+/// the IPv4 mDNS group, so answers are multicast to every listener on the
+/// link instead of sent back to the querier alone.
+pub fn create_services_query_frame(
+    src_mac: MacAddr,
+    src_addr: Ipv4Addr,
+) -> anyhow::Result<Vec<u8>> {
+    let query: Vec<u8> = create_services_query();
+    let total_len: usize = IP_V4_HDR_LEN + UDP_HDR_LEN + query.len();
+
+    let mut frame: Vec<u8> = ethernet::make_header(src_mac, MDNS_GROUP_MAC, EtherTypes::Ipv4)?;
+    frame.extend(ip::create_ipv4_header(
+        src_addr,
+        MDNS_GROUP_V4,
+        total_len as u16,
+        IpNextHeaderProtocols::Udp,
+        MDNS_TTL,
+    )?);
+    frame.extend(udp::create_packet(MDNS_PORT, MDNS_PORT, query)?);
+    Ok(frame)
 }
 
-pub fn extract_resource(data: &[u8]) -> Result<MdnsRecord> {
-    let packet = Packet::parse(data).context("failed to parse mDNS packet")?;
+/// Collects the hostname, addresses and TXT identity hints announced in an
+/// mDNS packet.
+///
+/// Answers and additional records are read; questions and authority records
+/// are only checked and skipped.
+pub fn extract_resource(data: &[u8]) -> Result<MdnsRecord, MdnsError> {
+    if data.len() < DNS_HDR_LEN {
+        return Err(MdnsError::Truncated("header"));
+    }
+    let count = |idx: usize| u16::from_be_bytes([data[idx], data[idx + 1]]) as usize;
+    let (questions, answers, authority, additional) = (count(4), count(6), count(8), count(10));
+
+    let records: usize = answers + authority + additional;
+    let available: usize = data.len() - DNS_HDR_LEN;
+    if questions * MIN_QUESTION_LEN + records * MIN_RECORD_LEN > available {
+        return Err(MdnsError::TooManyRecords(questions + records, available));
+    }
+
+    let mut offset: usize = DNS_HDR_LEN;
+    for _ in 0..questions {
+        offset = read_name(data, offset)?.1;
+        offset = checked_end(data, offset, 4, "question")?;
+    }
+
     let mut metadata: MdnsRecord = MdnsRecord::default();
+    for idx in 0..records {
+        let (rtype, rdata_start, rdata_end) = read_record(data, offset)?;
+        offset = rdata_end;
 
-    for record in packet.answers.iter().chain(packet.additional.iter()) {
-        match &record.data {
-            RData::PTR(ptr) => {
-                let name: String = ptr.0.to_string();
+        let in_authority: bool = (answers..answers + authority).contains(&idx);
+        if in_authority {
+            continue;
+        }
+        let rdata: &[u8] = &data[rdata_start..rdata_end];
+        match rtype {
+            TYPE_PTR => {
+                let name: String = read_name(data, rdata_start)?.0;
                 if !name.ends_with(".arpa") {
                     metadata.hostname = Some(name);
                 }
             }
-
-            RData::A(a) => {
-                metadata.ips.insert(IpAddr::V4(a.0));
+            TYPE_A => {
+                let octets: [u8; 4] = rdata
+                    .try_into()
+                    .map_err(|_| MdnsError::InvalidRecordLength(rtype, rdata.len()))?;
+                metadata.ips.insert(IpAddr::V4(Ipv4Addr::from(octets)));
             }
-
-            RData::AAAA(aaaa) => {
-                metadata.ips.insert(IpAddr::V6(aaaa.0));
+            TYPE_AAAA => {
+                let octets: [u8; 16] = rdata
+                    .try_into()
+                    .map_err(|_| MdnsError::InvalidRecordLength(rtype, rdata.len()))?;
+                metadata.ips.insert(IpAddr::V6(Ipv6Addr::from(octets)));
             }
-
+            TYPE_TXT => read_txt(rdata, &mut metadata),
             _ => {}
         }
     }
 
     Ok(metadata)
 }
+
+/// Takes the model and friendly name from the `key=value` strings of a TXT
+/// record. Malformed strings are skipped, since TXT data is free-form.
+fn read_txt(rdata: &[u8], record: &mut MdnsRecord) {
+    let mut pos: usize = 0;
+    while let Some(&len) = rdata.get(pos) {
+        let Some(entry) = rdata.get(pos + 1..pos + 1 + len as usize) else {
+            return;
+        };
+        pos += 1 + len as usize;
+
+        let entry: String = String::from_utf8_lossy(entry).into_owned();
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        let value: &str = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let key: String = key.to_ascii_lowercase();
+        if key == FRIENDLY_NAME_KEY {
+            record
+                .friendly_name
+                .get_or_insert_with(|| value.to_string());
+        } else if MODEL_KEYS.contains(&key.as_str()) && record.model.is_none() {
+            record.model = Some(value.to_string());
+        }
+    }
+}
+
+/// Reads the resource record at `offset` and returns its type and the bounds
+/// of its RDATA.
+fn read_record(data: &[u8], offset: usize) -> Result<(u16, usize, usize), MdnsError> {
+    let offset: usize = read_name(data, offset)?.1;
+    let fixed_end: usize = checked_end(data, offset, 10, "record")?;
+    let fixed: &[u8] = &data[offset..fixed_end];
+
+    let rtype: u16 = u16::from_be_bytes([fixed[0], fixed[1]]);
+    let rdata_len: usize = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+    let rdata_end: usize = checked_end(data, fixed_end, rdata_len, "record data")?;
+    Ok((rtype, fixed_end, rdata_end))
+}
+
+/// Decodes the possibly compressed name at `offset`.
+///
+/// Returns the dotted name without a trailing dot, and the offset following
+/// the name in place rather than at the end of a pointer target. Every pointer
+/// has to point before the previous one's target, so decoding always ends.
+fn read_name(data: &[u8], offset: usize) -> Result<(String, usize), MdnsError> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut encoded_len: usize = 0;
+    let mut pos: usize = offset;
+    let mut limit: usize = offset;
+    let mut end: Option<usize> = None;
+
+    loop {
+        let len: u8 = *data.get(pos).ok_or(MdnsError::Truncated("name"))?;
+        match len & 0xC0 {
+            0x00 if len == 0 => break,
+            0x00 => {
+                let label_end: usize = checked_end(data, pos + 1, len as usize, "label")?;
+                encoded_len += len as usize + 1;
+                if encoded_len + 1 > MAX_NAME_LEN {
+                    return Err(MdnsError::NameTooLong);
+                }
+                labels.push(String::from_utf8_lossy(&data[pos + 1..label_end]).into_owned());
+                pos = label_end;
+            }
+            0xC0 => {
+                let low: u8 = *data.get(pos + 1).ok_or(MdnsError::Truncated("name"))?;
+                let target: usize = (((len & 0x3F) as usize) << 8) | low as usize;
+                if target >= limit {
+                    return Err(MdnsError::CompressionLoop(pos));
+                }
+                end.get_or_insert(pos + 2);
+                limit = target;
+                pos = target;
+            }
+            _ => return Err(MdnsError::InvalidLabel(len)),
+        }
+    }
+
+    Ok((labels.join("."), end.unwrap_or(pos + 1)))
+}
+
+/// Returns `start + len` if the payload holds that many bytes from `start`.
+fn checked_end(
+    data: &[u8],
+    start: usize,
+    len: usize,
+    part: &'static str,
+) -> Result<usize, MdnsError> {
+    start
+        .checked_add(len)
+        .filter(|end| *end <= data.len())
+        .ok_or(MdnsError::Truncated(part))
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A response announcing `nas.local` at 192.168.1.20 through a reverse PTR,
+    /// a service PTR and an A record that reuse earlier names by compression.
+    fn announcement() -> Vec<u8> {
+        let mut packet: Vec<u8> = vec![0, 0, 0x84, 0, 0, 0, 0, 2, 0, 0, 0, 1];
+        // 12: 20.1.168.192.in-addr.arpa PTR nas.local
+        packet.extend_from_slice(b"\x0220\x011\x03168\x03192\x07in-addr\x04arpa\x00");
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120, 0, 11]);
+        // 49: nas.local
+        packet.extend_from_slice(b"\x03nas\x05local\x00");
+        // _http._tcp.local PTR nas.local, via pointers to 53 and 49
+        packet.extend_from_slice(b"\x05_http\x04_tcp\xc0\x35");
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120, 0, 2, 0xC0, 0x31]);
+        // nas.local A 192.168.1.20
+        packet.extend_from_slice(&[0xC0, 0x31, 0, 1, 0x80, 1, 0, 0, 0, 120, 0, 4]);
+        packet.extend_from_slice(&[192, 168, 1, 20]);
+        packet
+    }
+
+    #[test]
+    fn reads_compressed_announcement() {
+        let record: MdnsRecord = extract_resource(&announcement()).unwrap();
+        assert_eq!(record.hostname.as_deref(), Some("nas.local"));
+        assert!(
+            record
+                .ips
+                .contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)))
+        );
+    }
+
+    #[test]
+    fn reads_txt_identity_hints() {
+        let mut packet: Vec<u8> = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        packet.extend_from_slice(b"\x02tv\x05local\x00");
+        let txt: &[u8] = b"\x05id=42\x0dmd=Chromecast\x0efn=Living Room\x04junk";
+        packet.extend_from_slice(&[0, 16, 0, 1, 0, 0, 0, 120, 0, txt.len() as u8]);
+        packet.extend_from_slice(txt);
+
+        let record: MdnsRecord = extract_resource(&packet).unwrap();
+        assert_eq!(record.model.as_deref(), Some("Chromecast"));
+        assert_eq!(record.friendly_name.as_deref(), Some("Living Room"));
+    }
+
+    #[test]
+    fn services_query_is_a_single_ptr_question() {
+        let query: Vec<u8> = create_services_query();
+        assert!(!is_response(&query));
+        assert_eq!(read_name(&query, DNS_HDR_LEN).unwrap().0, SERVICES_NAME);
+        assert_eq!(query[query.len() - 4..], [0, 12, 0, 1]);
+
+        let record: MdnsRecord = extract_resource(&query).unwrap();
+        assert!(record.hostname.is_none() && record.ips.is_empty());
+        assert!(is_response(&announcement()));
+    }
+
+    #[test]
+    fn address_query_asks_for_both_families() {
+        let query: Vec<u8> = create_address_query("nas.local.", 0x1234);
+        assert_eq!(query[..6], [0x12, 0x34, 0, 0, 0, 2]);
+        let (name, offset) = read_name(&query, DNS_HDR_LEN).unwrap();
+        assert_eq!(name, "nas.local");
+        assert_eq!(query[offset..offset + 4], [0, 1, 0, 1]);
+        assert_eq!(query[query.len() - 4..], [0, 28, 0, 1]);
+        assert!(extract_resource(&query).is_ok());
+    }
+
+    #[test]
+    fn rejects_compression_loops() {
+        // A name that points at itself.
+        let mut packet: Vec<u8> = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        packet.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 120, 0, 0]);
+        assert_eq!(
+            extract_resource(&packet).unwrap_err(),
+            MdnsError::CompressionLoop(12)
+        );
+
+        // A label followed by a pointer back to it.
+        let mut packet: Vec<u8> = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        packet.extend_from_slice(&[0x01, b'a', 0xC0, 12]);
+        packet.extend_from_slice(&[0, 1, 0, 1, 0, 0, 0, 120, 0, 0]);
+        assert_eq!(
+            extract_resource(&packet).unwrap_err(),
+            MdnsError::CompressionLoop(14)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_records_and_absurd_counts() {
+        let packet: Vec<u8> = announcement();
+        for len in 0..packet.len() {
+            assert!(extract_resource(&packet[..len]).is_err(), "len {len}");
+        }
+
+        let mut inflated: Vec<u8> = packet.clone();
+        inflated[6..8].copy_from_slice(&u16::MAX.to_be_bytes());
+        assert!(matches!(
+            extract_resource(&inflated),
+            Err(MdnsError::TooManyRecords(..))
+        ));
+
+        let mut bad_rdata: Vec<u8> = packet;
+        let len_idx: usize = bad_rdata.len() - 5;
+        bad_rdata[len_idx] = 200;
+        assert_eq!(
+            extract_resource(&bad_rdata).unwrap_err(),
+            MdnsError::Truncated("record data")
+        );
+    }
+}
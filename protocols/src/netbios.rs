@@ -0,0 +1,242 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! NetBIOS name service queries (RFC 1002) sent straight to one host.
+//!
+//! A node status request asks a host which names it registered. A name query
+//! for its workstation name then lists every IPv4 address the host registered
+//! that name on, which reveals the other interfaces of multi-homed Windows
+//! machines.
+
+use std::net::Ipv4Addr;
+
+use anyhow::{Result, ensure};
+
+use crate::utils::DNS_HDR_LEN;
+
+pub const NBNS_PORT: u16 = 137;
+
+/// Bytes of a NetBIOS name: 15 characters and a suffix naming the service.
+const NAME_LEN: usize = 16;
+/// A name in its first-level encoding: a length byte, two letters per byte
+/// and the terminating root label.
+const ENCODED_NAME_LEN: usize = 1 + NAME_LEN * 2 + 1;
+const TYPE_NB: u16 = 0x0020;
+const TYPE_NBSTAT: u16 = 0x0021;
+const CLASS_IN: u16 = 1;
+/// Suffix of the name the workstation service registers.
+const WORKSTATION_SUFFIX: u8 = 0x00;
+/// Name flag set on group names, which many hosts share.
+const GROUP_FLAG: u16 = 0x8000;
+/// Length of a node status entry: name, suffix and flags.
+const STATUS_ENTRY_LEN: usize = NAME_LEN + 2;
+/// Length of an address entry of a name query answer: flags and address.
+const ADDRESS_ENTRY_LEN: usize = 6;
+
+/// A name from a node status answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameEntry {
+    /// The name without its padding, e.g. `DESKTOP-4F2K`.
+    pub name: String,
+    pub suffix: u8,
+    pub group: bool,
+}
+
+/// Builds a node status request for the wildcard name `*`, which every
+/// NetBIOS host answers.
+pub fn create_node_status_query(id: u16) -> Vec<u8> {
+    create_query(id, b"*", 0x00, 0x00, TYPE_NBSTAT)
+}
+
+/// Builds a unicast name query for `name` registered with `suffix`.
+pub fn create_name_query(id: u16, name: &str, suffix: u8) -> Vec<u8> {
+    create_query(id, name.as_bytes(), b' ', suffix, TYPE_NB)
+}
+
+fn create_query(id: u16, name: &[u8], padding: u8, suffix: u8, qtype: u16) -> Vec<u8> {
+    let mut raw: [u8; NAME_LEN] = [padding; NAME_LEN];
+    for (slot, byte) in raw[..NAME_LEN - 1].iter_mut().zip(name) {
+        *slot = byte.to_ascii_uppercase();
+    }
+    raw[NAME_LEN - 1] = suffix;
+
+    let mut query: Vec<u8> = Vec::with_capacity(DNS_HDR_LEN + ENCODED_NAME_LEN + 4);
+    query.extend_from_slice(&id.to_be_bytes());
+    query.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    query.push((NAME_LEN * 2) as u8);
+    for byte in raw {
+        query.push(b'A' + (byte >> 4));
+        query.push(b'A' + (byte & 0x0F));
+    }
+    query.push(0);
+    query.extend_from_slice(&qtype.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    query
+}
+
+/// Reads the names of a node status answer.
+pub fn get_node_status(response: &[u8]) -> Result<Vec<NameEntry>> {
+    let rdata: &[u8] = answer_rdata(response, TYPE_NBSTAT)?;
+    ensure!(!rdata.is_empty(), "empty NetBIOS node status");
+    let count: usize = rdata[0] as usize;
+    ensure!(
+        rdata.len() > count * STATUS_ENTRY_LEN,
+        "truncated NetBIOS node status ({count} names in {} bytes)",
+        rdata.len()
+    );
+
+    let entries: Vec<NameEntry> = rdata[1..]
+        .chunks_exact(STATUS_ENTRY_LEN)
+        .take(count)
+        .map(|entry| NameEntry {
+            name: String::from_utf8_lossy(&entry[..NAME_LEN - 1])
+                .trim_end()
+                .to_string(),
+            suffix: entry[NAME_LEN - 1],
+            group: u16::from_be_bytes([entry[NAME_LEN], entry[NAME_LEN + 1]]) & GROUP_FLAG != 0,
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Picks the unique workstation name among `entries`, the one a name query
+/// for the host itself has to ask for.
+pub fn workstation_name(entries: &[NameEntry]) -> Option<&str> {
+    entries
+        .iter()
+        .find(|entry| entry.suffix == WORKSTATION_SUFFIX && !entry.group)
+        .map(|entry| entry.name.as_str())
+}
+
+/// Reads the addresses of a positive name query answer.
+pub fn get_addresses(response: &[u8]) -> Result<Vec<Ipv4Addr>> {
+    let rdata: &[u8] = answer_rdata(response, TYPE_NB)?;
+    ensure!(
+        rdata.len() % ADDRESS_ENTRY_LEN == 0,
+        "NetBIOS address list of {} bytes",
+        rdata.len()
+    );
+    Ok(rdata
+        .chunks_exact(ADDRESS_ENTRY_LEN)
+        .map(|entry| Ipv4Addr::new(entry[2], entry[3], entry[4], entry[5]))
+        .collect())
+}
+
+/// Checks that `response` is a successful answer of type `rtype` and returns
+/// the data of its single resource record.
+fn answer_rdata(response: &[u8], rtype: u16) -> Result<&[u8]> {
+    ensure!(
+        response.len() >= DNS_HDR_LEN + ENCODED_NAME_LEN + 10,
+        "truncated NetBIOS answer (len {})",
+        response.len()
+    );
+    ensure!(response[2] & 0x80 != 0, "not a NetBIOS answer");
+    let rcode: u8 = response[3] & 0x0F;
+    ensure!(rcode == 0, "NetBIOS error {rcode}");
+    let answers: u16 = u16::from_be_bytes([response[6], response[7]]);
+    ensure!(answers > 0, "NetBIOS answer without records");
+
+    let fixed: usize = DNS_HDR_LEN + ENCODED_NAME_LEN;
+    ensure!(
+        response[DNS_HDR_LEN] as usize == NAME_LEN * 2,
+        "unexpected NetBIOS name length {}",
+        response[DNS_HDR_LEN]
+    );
+    let found: u16 = u16::from_be_bytes([response[fixed], response[fixed + 1]]);
+    ensure!(
+        found == rtype,
+        "unexpected NetBIOS record type {found:#06x}"
+    );
+
+    let rdata_len: usize = u16::from_be_bytes([response[fixed + 8], response[fixed + 9]]) as usize;
+    let rdata: &[u8] = response
+        .get(fixed + 10..fixed + 10 + rdata_len)
+        .ok_or_else(|| anyhow::anyhow!("truncated NetBIOS record data"))?;
+    Ok(rdata)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Turns `query` into an answer carrying `rdata`, the way hosts echo the
+    /// question name into the answer record.
+    fn answer(query: &[u8], rdata: &[u8]) -> Vec<u8> {
+        let mut packet: Vec<u8> = query[..DNS_HDR_LEN + ENCODED_NAME_LEN + 4].to_vec();
+        packet[2] = 0x84;
+        packet[4..8].copy_from_slice(&[0, 0, 0, 1]);
+        packet.extend_from_slice(&[0, 0, 0, 0]);
+        packet.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        packet.extend_from_slice(rdata);
+        packet
+    }
+
+    #[test]
+    fn encodes_padded_names() {
+        let query: Vec<u8> = create_node_status_query(7);
+        assert_eq!(
+            &query[DNS_HDR_LEN + 1..DNS_HDR_LEN + 33],
+            b"CKAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+        );
+
+        let query: Vec<u8> = create_name_query(7, "pc", 0x00);
+        assert_eq!(
+            &query[DNS_HDR_LEN + 1..DNS_HDR_LEN + 33],
+            b"FAEDCACACACACACACACACACACACACAAA"
+        );
+        assert_eq!(query[query.len() - 4..], [0, 0x20, 0, 1]);
+    }
+
+    #[test]
+    fn finds_addresses_of_the_workstation_name() {
+        let mut status: Vec<u8> = vec![3];
+        for (name, suffix, flags) in [
+            (&b"WORKGROUP      "[..], 0x00, 0x8400u16),
+            (b"DESKTOP-4F2K   ", 0x20, 0x0400),
+            (b"DESKTOP-4F2K   ", 0x00, 0x0400),
+        ] {
+            status.extend_from_slice(name);
+            status.push(suffix);
+            status.extend_from_slice(&flags.to_be_bytes());
+        }
+        status.extend_from_slice(&[0; 6]);
+        let entries: Vec<NameEntry> =
+            get_node_status(&answer(&create_node_status_query(1), &status)).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].group);
+        assert_eq!(workstation_name(&entries), Some("DESKTOP-4F2K"));
+
+        let addresses: [u8; 12] = [0, 0, 10, 0, 0, 5, 0, 0, 192, 168, 56, 1];
+        let query: Vec<u8> = create_name_query(2, "DESKTOP-4F2K", 0x00);
+        assert_eq!(
+            get_addresses(&answer(&query, &addresses)).unwrap(),
+            [Ipv4Addr::new(10, 0, 0, 5), Ipv4Addr::new(192, 168, 56, 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_negative_and_truncated_answers() {
+        let query: Vec<u8> = create_name_query(2, "GONE", 0x00);
+        let mut negative: Vec<u8> = answer(&query, &[]);
+        negative[3] = 0x03;
+        assert!(get_addresses(&negative).is_err());
+
+        let full: Vec<u8> = answer(&query, &[0, 0, 10, 0, 0, 5]);
+        for len in 0..full.len() {
+            assert!(get_addresses(&full[..len]).is_err(), "len {len}");
+        }
+        assert!(get_node_status(&full).is_err());
+    }
+}
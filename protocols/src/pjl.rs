@@ -0,0 +1,50 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! PJL identification over raw JetDirect / AppSocket (port 9100).
+//!
+//! Whatever reaches port 9100 is printed, so the request is wrapped in the
+//! *Universal Exit Language* sequence that makes the printer treat it as a PJL
+//! job header rather than page content.
+
+/// `@PJL INFO ID`, wrapped in UEL sequences.
+pub const INFO_ID_REQUEST: &[u8] = b"\x1b%-12345X@PJL INFO ID\r\n\x1b%-12345X\r\n";
+
+/// Extracts the model from the reply to [`INFO_ID_REQUEST`].
+///
+/// Printers echo the command and answer on the next line, usually quoted and
+/// terminated by a form feed: `@PJL INFO ID\r\n"HP LaserJet 4250"\r\n\x0c`.
+pub fn get_model(response: &str) -> Option<String> {
+    let mut lines = response.lines();
+    lines.find(|line| line.trim().eq_ignore_ascii_case("@PJL INFO ID"))?;
+    let model: &str = lines
+        .next()?
+        .trim_matches(|c: char| c == '"' || c == '\x0c' || c.is_whitespace());
+    (!model.is_empty()).then(|| model.to_string())
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_info_id() {
+        let reply: &str = "@PJL INFO ID\r\n\"HP LaserJet 4250\"\r\n\x0c";
+        assert_eq!(get_model(reply).as_deref(), Some("HP LaserJet 4250"));
+
+        assert_eq!(get_model("@PJL INFO ID\r\n\x0c"), None);
+        assert_eq!(get_model("220 FTP ready\r\n"), None);
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! SCTP INIT probes and the answers they draw (RFC 9260).
+//!
+//! An INIT is the SCTP counterpart of a TCP SYN: a listening port answers with
+//! an INIT-ACK, a closed one with an ABORT, and no association is ever set up,
+//! since the probe never returns the cookie.
+
+/// Common header: ports, verification tag and checksum.
+const COMMON_HDR_LEN: usize = 12;
+/// INIT chunk without optional parameters.
+const INIT_CHUNK_LEN: usize = 20;
+const CHUNK_INIT: u8 = 1;
+const CHUNK_INIT_ACK: u8 = 2;
+const CHUNK_ABORT: u8 = 6;
+/// Set in an ABORT whose verification tag was copied from the packet it answers.
+const FLAG_TAG_REFLECTED: u8 = 0x01;
+const ADVERTISED_WINDOW: u32 = 65_535;
+const OUTBOUND_STREAMS: u16 = 10;
+const INBOUND_STREAMS: u16 = 2048;
+
+/// How a port answered an INIT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyKind {
+    /// A listener offered to set up an association.
+    InitAck,
+    /// Nothing listens on the port.
+    Abort,
+    /// Any other chunk, still proof that the host runs SCTP.
+    Other,
+}
+
+/// An SCTP packet received in answer to a probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply {
+    /// Port of the target that answered.
+    pub src_port: u16,
+    /// Source port of the probe.
+    pub dst_port: u16,
+    /// Initiate tag of the probe, unless the target only reflected the zero
+    /// tag the INIT was sent with.
+    pub tag: Option<u32>,
+    pub kind: ReplyKind,
+}
+
+/// Builds an INIT (without IP header) for a Layer 4 SCTP socket.
+///
+/// `initiate_tag` must not be zero; answers carry it as their verification tag.
+pub fn create_init_packet(
+    src_port: u16,
+    dst_port: u16,
+    initiate_tag: u32,
+) -> anyhow::Result<Vec<u8>> {
+    anyhow::ensure!(initiate_tag != 0, "SCTP initiate tag must not be zero");
+
+    let mut buffer: Vec<u8> = Vec::with_capacity(COMMON_HDR_LEN + INIT_CHUNK_LEN);
+    buffer.extend_from_slice(&src_port.to_be_bytes());
+    buffer.extend_from_slice(&dst_port.to_be_bytes());
+    // An INIT is the one chunk sent with a zero verification tag.
+    buffer.extend_from_slice(&0u32.to_be_bytes());
+    buffer.extend_from_slice(&0u32.to_be_bytes());
+
+    buffer.extend_from_slice(&[CHUNK_INIT, 0]);
+    buffer.extend_from_slice(&(INIT_CHUNK_LEN as u16).to_be_bytes());
+    buffer.extend_from_slice(&initiate_tag.to_be_bytes());
+    buffer.extend_from_slice(&ADVERTISED_WINDOW.to_be_bytes());
+    buffer.extend_from_slice(&OUTBOUND_STREAMS.to_be_bytes());
+    buffer.extend_from_slice(&INBOUND_STREAMS.to_be_bytes());
+    // Initial TSN; the probe never sends data.
+    buffer.extend_from_slice(&initiate_tag.to_be_bytes());
+
+    // CRC32c goes on the wire least significant byte first.
+    let checksum: u32 = crc32c(&buffer);
+    buffer[8..12].copy_from_slice(&checksum.to_le_bytes());
+    Ok(buffer)
+}
+
+/// Parses an SCTP packet (without IP header) by its first chunk.
+///
+/// Returns `None` for packets too short to hold a chunk.
+pub fn parse_reply(packet: &[u8]) -> Option<Reply> {
+    let src_port: u16 = u16::from_be_bytes(packet.get(0..2)?.try_into().ok()?);
+    let dst_port: u16 = u16::from_be_bytes(packet.get(2..4)?.try_into().ok()?);
+    let tag: u32 = u32::from_be_bytes(packet.get(4..8)?.try_into().ok()?);
+    let chunk_type: u8 = *packet.get(COMMON_HDR_LEN)?;
+    let chunk_flags: u8 = *packet.get(COMMON_HDR_LEN + 1)?;
+
+    let kind: ReplyKind = match chunk_type {
+        CHUNK_INIT_ACK => ReplyKind::InitAck,
+        CHUNK_ABORT => ReplyKind::Abort,
+        _ => ReplyKind::Other,
+    };
+    let reflected: bool = kind == ReplyKind::Abort && chunk_flags & FLAG_TAG_REFLECTED != 0;
+
+    Some(Reply {
+        src_port,
+        dst_port,
+        tag: (!reflected && tag != 0).then_some(tag),
+        kind,
+    })
+}
+
+/// CRC32c (Castagnoli), as used by the SCTP checksum.
+fn crc32c(bytes: &[u8]) -> u32 {
+    const POLY: u32 = 0x82F6_3B78;
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (POLY & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_carries_the_tag_and_a_valid_checksum() {
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+
+        let packet: Vec<u8> = create_init_packet(50_000, 3868, 0xDEAD_BEEF).unwrap();
+        assert_eq!(packet.len(), COMMON_HDR_LEN + INIT_CHUNK_LEN);
+        assert_eq!(&packet[0..4], &[0xC3, 0x50, 0x0F, 0x1C]);
+        assert_eq!(&packet[4..8], &[0; 4]);
+        assert_eq!(&packet[12..16], &[CHUNK_INIT, 0, 0, 20]);
+        assert_eq!(&packet[16..20], &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut zeroed: Vec<u8> = packet.clone();
+        zeroed[8..12].fill(0);
+        assert_eq!(packet[8..12], crc32c(&zeroed).to_le_bytes());
+
+        assert!(create_init_packet(50_000, 3868, 0).is_err());
+    }
+
+    #[test]
+    fn replies_are_told_apart_by_their_first_chunk() {
+        let reply = |tag: u32, chunk: u8, flags: u8| -> Vec<u8> {
+            let mut packet: Vec<u8> = vec![0x0F, 0x1C, 0xC3, 0x50];
+            packet.extend_from_slice(&tag.to_be_bytes());
+            packet.extend_from_slice(&[0; 4]);
+            packet.extend_from_slice(&[chunk, flags, 0, 4]);
+            packet
+        };
+
+        let ack: Reply = parse_reply(&reply(7, CHUNK_INIT_ACK, 0)).unwrap();
+        assert_eq!(
+            ack,
+            Reply {
+                src_port: 3868,
+                dst_port: 50_000,
+                tag: Some(7),
+                kind: ReplyKind::InitAck,
+            }
+        );
+
+        let abort: Reply = parse_reply(&reply(7, CHUNK_ABORT, 0)).unwrap();
+        assert_eq!((abort.kind, abort.tag), (ReplyKind::Abort, Some(7)));
+        let reflected: Reply = parse_reply(&reply(0, CHUNK_ABORT, FLAG_TAG_REFLECTED)).unwrap();
+        assert_eq!(reflected.tag, None);
+
+        assert!(parse_reply(&reply(7, CHUNK_INIT_ACK, 0)[..12]).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Minimal SMB2 negotiation over direct TCP (port 445).
+//!
+//! Only the `NEGOTIATE` exchange is implemented: it is unauthenticated and its
+//! response already states whether the server requires signed messages.
+
+use anyhow::{Result, ensure};
+
+/// Length of the SMB2 header preceding every command.
+const SMB2_HDR_LEN: usize = 64;
+/// Length of the NetBIOS session header used by direct TCP transport.
+const NBSS_HDR_LEN: usize = 4;
+const SMB2_MAGIC: [u8; 4] = [0xFE, b'S', b'M', b'B'];
+const SMB2_NEGOTIATE: u16 = 0x0000;
+const NEGOTIATE_RESPONSE_SIZE: u16 = 65;
+const SIGNING_ENABLED: u16 = 0x0001;
+const SIGNING_REQUIRED: u16 = 0x0002;
+
+/// SMB 2.0.2 through 3.0.2. SMB 3.1.1 would require negotiate contexts.
+const DIALECTS: [u16; 4] = [0x0202, 0x0210, 0x0300, 0x0302];
+
+/// Bytes of a `NEGOTIATE` response needed to read the security mode.
+pub const NEGOTIATE_RESPONSE_PREFIX_LEN: usize = NBSS_HDR_LEN + SMB2_HDR_LEN + 4;
+
+/// Builds an SMB2 `NEGOTIATE` request, framed for direct TCP transport.
+pub fn create_negotiate_request() -> Vec<u8> {
+    let mut smb: Vec<u8> = Vec::with_capacity(SMB2_HDR_LEN + 36 + DIALECTS.len() * 2);
+
+    // Header: magic, structure size, credit charge, status, command, credits
+    // requested, then flags, next command, message, process, tree and session
+    // IDs and the signature, all zero.
+    smb.extend_from_slice(&SMB2_MAGIC);
+    smb.extend_from_slice(&(SMB2_HDR_LEN as u16).to_le_bytes());
+    smb.extend_from_slice(&0u16.to_le_bytes());
+    smb.extend_from_slice(&0u32.to_le_bytes());
+    smb.extend_from_slice(&SMB2_NEGOTIATE.to_le_bytes());
+    smb.extend_from_slice(&1u16.to_le_bytes());
+    smb.resize(SMB2_HDR_LEN, 0);
+
+    // Body: structure size, dialect count, security mode, reserved,
+    // capabilities, client GUID and start time, then the dialects.
+    smb.extend_from_slice(&36u16.to_le_bytes());
+    smb.extend_from_slice(&(DIALECTS.len() as u16).to_le_bytes());
+    smb.extend_from_slice(&SIGNING_ENABLED.to_le_bytes());
+    smb.resize(SMB2_HDR_LEN + 36, 0);
+    DIALECTS
+        .iter()
+        .for_each(|dialect| smb.extend_from_slice(&dialect.to_le_bytes()));
+
+    let mut packet: Vec<u8> = Vec::with_capacity(NBSS_HDR_LEN + smb.len());
+    packet.push(0);
+    packet.extend_from_slice(&(smb.len() as u32).to_be_bytes()[1..]);
+    packet.extend(smb);
+    packet
+}
+
+/// Reads from a `NEGOTIATE` response whether the server requires signing.
+///
+/// `response` starts with the NetBIOS session header; only the first
+/// [`NEGOTIATE_RESPONSE_PREFIX_LEN`] bytes are inspected.
+pub fn is_signing_required(response: &[u8]) -> Result<bool> {
+    ensure!(
+        response.len() >= NEGOTIATE_RESPONSE_PREFIX_LEN,
+        "truncated SMB2 response (len {})",
+        response.len()
+    );
+    let smb: &[u8] = &response[NBSS_HDR_LEN..];
+    ensure!(smb[..4] == SMB2_MAGIC, "not an SMB2 response");
+
+    let status: u32 = u32::from_le_bytes([smb[8], smb[9], smb[10], smb[11]]);
+    let command: u16 = u16::from_le_bytes([smb[12], smb[13]]);
+    ensure!(
+        command == SMB2_NEGOTIATE,
+        "unexpected SMB2 command {command:#06x}"
+    );
+    ensure!(
+        status == 0,
+        "SMB2 negotiation failed (status {status:#010x})"
+    );
+
+    let body: &[u8] = &smb[SMB2_HDR_LEN..];
+    let size: u16 = u16::from_le_bytes([body[0], body[1]]);
+    ensure!(
+        size == NEGOTIATE_RESPONSE_SIZE,
+        "malformed SMB2 negotiate response (structure size {size})"
+    );
+    let security_mode: u16 = u16::from_le_bytes([body[2], body[3]]);
+    Ok(security_mode & SIGNING_REQUIRED != 0)
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiate_response(status: u32, security_mode: u16) -> Vec<u8> {
+        let mut smb: Vec<u8> = Vec::new();
+        smb.extend_from_slice(&SMB2_MAGIC);
+        smb.extend_from_slice(&64u16.to_le_bytes());
+        smb.extend_from_slice(&0u16.to_le_bytes());
+        smb.extend_from_slice(&status.to_le_bytes());
+        smb.extend_from_slice(&SMB2_NEGOTIATE.to_le_bytes());
+        smb.resize(SMB2_HDR_LEN, 0);
+        smb.extend_from_slice(&NEGOTIATE_RESPONSE_SIZE.to_le_bytes());
+        smb.extend_from_slice(&security_mode.to_le_bytes());
+        smb.extend_from_slice(&0x0302u16.to_le_bytes());
+
+        let mut packet: Vec<u8> = vec![0, 0, 0, smb.len() as u8];
+        packet.extend(smb);
+        packet
+    }
+
+    #[test]
+    fn negotiate_request_layout() {
+        let packet: Vec<u8> = create_negotiate_request();
+        let len: usize = u32::from_be_bytes([0, packet[1], packet[2], packet[3]]) as usize;
+
+        assert_eq!(packet[0], 0);
+        assert_eq!(len, packet.len() - NBSS_HDR_LEN);
+        assert_eq!(len, SMB2_HDR_LEN + 36 + DIALECTS.len() * 2);
+        assert_eq!(packet[NBSS_HDR_LEN..NBSS_HDR_LEN + 4], SMB2_MAGIC);
+    }
+
+    #[test]
+    fn reads_signing_requirement() {
+        let required: Vec<u8> = negotiate_response(0, SIGNING_ENABLED | SIGNING_REQUIRED);
+        assert!(is_signing_required(&required).unwrap());
+
+        let optional: Vec<u8> = negotiate_response(0, SIGNING_ENABLED);
+        assert!(!is_signing_required(&optional).unwrap());
+
+        let denied: Vec<u8> = negotiate_response(0xC000_0022, SIGNING_REQUIRED);
+        assert!(is_signing_required(&denied).is_err());
+        assert!(is_signing_required(&required[..20]).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) 2026 OverTheFlow and Contributors
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// https://mozilla.org/MPL/2.0/.
+
+//! Identifying fields of X.509 server certificates (RFC 5280).
+//!
+//! The handshake that fetches a certificate is left to rustls, see
+//! `zond_core::scanner::tls`; this module only decodes what a certificate
+//! says about the device presenting it, with `x509-parser`.
+
+use std::net::IpAddr;
+
+use anyhow::{Result, anyhow, ensure};
+use x509_parser::{
+    certificate::X509Certificate, extensions::GeneralName, time::ASN1Time, x509::X509Name,
+};
+
+/// What a certificate says about the server presenting it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// Common name of the subject, or its organization if it has none.
+    pub subject: Option<String>,
+    /// Common name of the issuer, or its organization if it has none.
+    pub issuer: Option<String>,
+    /// DNS names and addresses of the subject alternative name extension.
+    pub alt_names: Vec<String>,
+    /// End of the validity period as `YYYY-MM-DD`.
+    pub not_after: Option<String>,
+    /// Subject and issuer are the same name, as with factory certificates.
+    pub self_signed: bool,
+}
+
+/// Decodes the identifying fields of a DER encoded X.509 certificate.
+///
+/// # Errors
+/// Fails if `der` is not a complete certificate.
+pub fn parse_certificate(der: &[u8]) -> Result<Certificate> {
+    let (rest, certificate): (&[u8], X509Certificate) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| anyhow!("malformed certificate: {e}"))?;
+    ensure!(rest.is_empty(), "trailing data after the certificate");
+
+    let alt_names: Vec<String> = certificate
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|extension| {
+            extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(alt_name)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Certificate {
+        subject: display_name(certificate.subject()),
+        issuer: display_name(certificate.issuer()),
+        alt_names,
+        not_after: Some(format_date(&certificate.validity().not_after)),
+        self_signed: certificate.subject().as_raw() == certificate.issuer().as_raw(),
+    })
+}
+
+/// Picks the common name out of an X.501 name, falling back to the organization.
+fn display_name(name: &X509Name) -> Option<String> {
+    name.iter_common_name()
+        .chain(name.iter_organization())
+        .find_map(|attribute| text(attribute.as_str().ok()?))
+}
+
+/// DNS names and addresses; other kinds of names do not identify a device.
+fn alt_name(name: &GeneralName) -> Option<String> {
+    match name {
+        GeneralName::DNSName(name) => text(name),
+        GeneralName::IPAddress(bytes) => match bytes.len() {
+            4 => <[u8; 4]>::try_from(*bytes).ok().map(IpAddr::from),
+            16 => <[u8; 16]>::try_from(*bytes).ok().map(IpAddr::from),
+            _ => None,
+        }
+        .map(|ip| ip.to_string()),
+        _ => None,
+    }
+}
+
+fn text(value: &str) -> Option<String> {
+    let value: &str = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Formats a certificate time as `YYYY-MM-DD`.
+fn format_date(time: &ASN1Time) -> String {
+    let date = time.to_datetime().date();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+// ╔════════════════════════════════════════════╗
+// ║ ████████╗███████╗███████╗████████╗███████╗ ║
+// ║ ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝ ║
+// ║    ██║   █████╗  ███████╗   ██║   ███████╗ ║
+// ║    ██║   ██╔══╝  ╚════██║   ██║   ╚════██║ ║
+// ║    ██║   ███████╗███████║   ██║   ███████║ ║
+// ║    ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝ ║
+// ╚════════════════════════════════════════════╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEQUENCE: u8 = 0x30;
+    const SET: u8 = 0x31;
+    const OID: u8 = 0x06;
+    const UTF8_STRING: u8 = 0x0C;
+    const OCTET_STRING: u8 = 0x04;
+    const DNS_NAME: u8 = 0x82;
+    const IP_ADDRESS: u8 = 0x87;
+    const COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+    const ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0A];
+    const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1D, 0x11];
+    /// ecdsa-with-SHA256
+    const ECDSA_SHA256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+    /// id-ecPublicKey and prime256v1
+    const EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+    const PRIME256V1: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+
+    fn der(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+        let body: Vec<u8> = parts.concat();
+        let mut out: Vec<u8> = vec![tag];
+        match body.len() {
+            len @ 0..=0x7F => out.push(len as u8),
+            len => {
+                out.push(0x82);
+                out.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        out.extend_from_slice(&body);
+        out
+    }
+
+    fn name(oid: &[u8], value: &str) -> Vec<u8> {
+        let attribute: Vec<u8> = der(
+            SEQUENCE,
+            &[&der(OID, &[oid]), &der(UTF8_STRING, &[value.as_bytes()])],
+        );
+        der(SEQUENCE, &[&der(SET, &[&attribute])])
+    }
+
+    /// A certificate for `nas.local` with a SAN extension, issued by an
+    /// organization without a common name. The signature is not checked.
+    fn certificate() -> Vec<u8> {
+        let alt_names: Vec<u8> = der(
+            SEQUENCE,
+            &[
+                &der(DNS_NAME, &[b"nas.local"]),
+                &der(IP_ADDRESS, &[&[192, 168, 1, 20]]),
+            ],
+        );
+        let extension: Vec<u8> = der(
+            SEQUENCE,
+            &[
+                &der(OID, &[SUBJECT_ALT_NAME]),
+                &der(OCTET_STRING, &[&alt_names]),
+            ],
+        );
+        let algorithm: Vec<u8> = der(SEQUENCE, &[&der(OID, &[ECDSA_SHA256])]);
+        let mut point: Vec<u8> = vec![0, 0x04];
+        point.extend_from_slice(&[0x11; 64]);
+        let public_key: Vec<u8> = der(
+            SEQUENCE,
+            &[
+                &der(
+                    SEQUENCE,
+                    &[&der(OID, &[EC_PUBLIC_KEY]), &der(OID, &[PRIME256V1])],
+                ),
+                &der(0x03, &[&point]),
+            ],
+        );
+        let tbs: Vec<u8> = der(
+            SEQUENCE,
+            &[
+                &der(0xA0, &[&der(0x02, &[&[2]])]),
+                &der(0x02, &[&[0x01, 0x23]]),
+                &algorithm,
+                &name(ORGANIZATION, "Synology Inc."),
+                &der(
+                    SEQUENCE,
+                    &[
+                        &der(0x17, &[b"250101000000Z"]),
+                        &der(0x18, &[b"20350101000000Z"]),
+                    ],
+                ),
+                &name(COMMON_NAME, "nas.local"),
+                &public_key,
+                &der(0xA3, &[&der(SEQUENCE, &[&extension])]),
+            ],
+        );
+        der(SEQUENCE, &[&tbs, &algorithm, &der(0x03, &[&[0]])])
+    }
+
+    #[test]
+    fn parses_certificate_fields() {
+        let parsed: Certificate = parse_certificate(&certificate()).unwrap();
+        assert_eq!(
+            parsed,
+            Certificate {
+                subject: Some("nas.local".to_string()),
+                issuer: Some("Synology Inc.".to_string()),
+                alt_names: vec!["nas.local".to_string(), "192.168.1.20".to_string()],
+                not_after: Some("2035-01-01".to_string()),
+                self_signed: false,
+            }
+        );
+
+        let der: Vec<u8> = certificate();
+        for len in 0..der.len() {
+            assert!(parse_certificate(&der[..len]).is_err(), "len {len}");
+        }
+    }
+}
@@ -7,8 +7,9 @@
 // Application Layer
 pub const DNS_HDR_LEN: usize = 12;
 // Network Layer
+pub const ICMP_V4_ECHO_REQ_LEN: usize = 8;
 pub const ICMP_V6_ECHO_REQ_LEN: usize = 8;
-// pub const IP_V4_HDR_LEN: usize = 20;
+pub const IP_V4_HDR_LEN: usize = 20;
 pub const IP_V6_HDR_LEN: usize = 40;
 // Data Link Layer
 pub const ARP_LEN: usize = 28;
@@ -31,6 +31,7 @@ async fn privileged_discovery_netns() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut collection = IpSet::new();
@@ -86,6 +87,7 @@ async fn privileged_discovery_hostname_resolution() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut collection = IpSet::new();
@@ -136,6 +138,7 @@ async fn privileged_discovery_stress_multi_alias() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut collection = IpSet::new();
@@ -20,6 +20,7 @@ async fn discovery_single_loopback() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut targets = IpSet::new();
@@ -49,6 +50,7 @@ async fn discovery_range_loopback() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut targets = IpSet::new();
@@ -85,6 +87,7 @@ async fn stop_signal_aborts() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     STOP_SIGNAL.store(false, Ordering::Relaxed);
@@ -110,6 +113,7 @@ async fn discovery_empty_set() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let targets = IpSet::new();
@@ -131,6 +135,7 @@ async fn discovery_redundant_ranges() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut targets = IpSet::new();
@@ -153,6 +158,7 @@ async fn discovery_loopback_stress() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut targets = IpSet::new();
@@ -65,6 +65,7 @@ async fn port_state_fidelity_unprivileged() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut target_map = TargetMap::new();
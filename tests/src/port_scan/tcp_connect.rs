@@ -26,6 +26,7 @@ async fn tcp_connect_scan_open_port() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut target_map = TargetMap::new();
@@ -71,6 +72,7 @@ async fn tcp_connect_scan_closed_port() {
         redact: false,
         quiet: 0,
         disable_input: true,
+        ..Default::default()
     };
 
     let mut target_map = TargetMap::new();
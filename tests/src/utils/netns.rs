@@ -4,7 +4,8 @@
 // If a copy of the MPL was not distributed with this file, You can obtain one at
 // https://mozilla.org/MPL/2.0/.
 
-use std::process::Command;
+use std::path::Path;
+use std::process::{Child, Command};
 use std::thread;
 use std::time::Duration;
 
@@ -15,7 +16,7 @@ use std::time::Duration;
 pub struct NetnsContext {
     pub ns_name: String,
     pub host_if: String,
-    pub _target_if: String,
+    pub target_if: String,
 }
 
 impl NetnsContext {
@@ -63,10 +64,29 @@ impl NetnsContext {
         Some(Self {
             ns_name,
             host_if,
-            _target_if: target_if,
+            target_if,
         })
     }
 
+    /// Starts `zond respond` of the binary `zond` on the target interface
+    /// inside the namespace, so that the fake `addresses` answer ARP and ping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the responder fails to start.
+    pub fn spawn_responder(&self, zond: &Path, addresses: &str, extra_args: &[&str]) -> Responder {
+        let child: Child = Command::new("ip")
+            .args(["netns", "exec", &self.ns_name])
+            .arg(zond)
+            .args(["--no-banner", "respond", "--interface", &self.target_if])
+            .args(extra_args)
+            .arg(addresses)
+            .spawn()
+            .expect("Failed to start zond respond");
+        thread::sleep(Duration::from_millis(500));
+        Responder(child)
+    }
+
     fn cleanup(ns_name: &str, host_if: &str) {
         let _ = Command::new("ip").args(["netns", "del", ns_name]).output();
         let _ = Command::new("ip").args(["link", "del", host_if]).output();
@@ -79,6 +99,16 @@ impl Drop for NetnsContext {
     }
 }
 
+/// A running `zond respond`, killed when dropped.
+pub struct Responder(Child);
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
 pub fn run_cmd(cmd: &str, args: &[&str]) -> bool {
     let status = Command::new(cmd).args(args).status();
     match status {